@@ -185,7 +185,7 @@ impl completion::CompletionModel for CompletionModel {
         &self,
         completion_request: completion::CompletionRequest,
     ) -> Result<completion::CompletionResponse<AwsConverseOutput>, CompletionError> {
-        let request = AwsCompletionRequest(completion_request);
+        let mut request = AwsCompletionRequest(completion_request);
 
         let mut converse_builder = self
             .client
@@ -194,6 +194,8 @@ impl completion::CompletionModel for CompletionModel {
             .converse()
             .model_id(self.model.as_str());
 
+        request.check_supported()?;
+        request.apply_reasoning(&self.model)?;
         let tool_config = request.tools_config()?;
         let messages = request.messages()?;
         converse_builder = converse_builder
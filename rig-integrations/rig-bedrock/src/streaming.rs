@@ -1,4 +1,5 @@
 use crate::types::completion_request::AwsCompletionRequest;
+use crate::types::converse_output::StopReason;
 use crate::{completion::CompletionModel, types::errors::AwsSdkConverseStreamError};
 use async_stream::stream;
 use aws_sdk_bedrockruntime::types as aws_bedrock;
@@ -6,7 +7,7 @@ use rig::completion::GetTokenUsage;
 use rig::streaming::StreamingCompletionResponse;
 use rig::{
     completion::CompletionError,
-    streaming::{RawStreamingChoice, RawStreamingToolCall},
+    streaming::{RawStreamingChoice, RawStreamingToolCall, ResponseMetadata},
 };
 use serde::{Deserialize, Serialize};
 
@@ -50,7 +51,7 @@ impl CompletionModel {
         &self,
         completion_request: rig::completion::CompletionRequest,
     ) -> Result<StreamingCompletionResponse<BedrockStreamingResponse>, CompletionError> {
-        let request = AwsCompletionRequest(completion_request);
+        let mut request = AwsCompletionRequest(completion_request);
 
         let mut converse_builder = self
             .client
@@ -59,6 +60,8 @@ impl CompletionModel {
             .converse_stream()
             .model_id(self.model.as_str());
 
+        request.check_supported()?;
+        request.apply_reasoning(&self.model)?;
         let tool_config = request.tools_config()?;
         let prompt_with_history = request.messages()?;
         converse_builder = converse_builder
@@ -154,6 +157,9 @@ impl CompletionModel {
                             }
                     },
                     aws_bedrock::ConverseStreamOutput::MessageStop(message_stop_event) => {
+                        if let Ok(reason) = StopReason::try_from(message_stop_event.stop_reason.clone()) {
+                            yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::finish_reason((&reason).into())));
+                        }
                         match message_stop_event.stop_reason {
                             aws_bedrock::StopReason::ToolUse => {
                                 if let Some(tool_call) = current_tool_call.take() {
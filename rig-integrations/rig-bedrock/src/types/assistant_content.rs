@@ -52,6 +52,8 @@ impl TryFrom<AwsConverseOutput> for completion::CompletionResponse<AwsConverseOu
             })
             .unwrap_or_default();
 
+        let finish_reason = Some((&value.0.stop_reason).into());
+
         if let Some(tool_use) = choice.iter().find_map(|content| match content {
             AssistantContent::ToolCall(tool_call) => Some(tool_call.to_owned()),
             _ => None,
@@ -62,6 +64,10 @@ impl TryFrom<AwsConverseOutput> for completion::CompletionResponse<AwsConverseOu
                     ToolFunction::new(tool_use.function.name, tool_use.function.arguments),
                 ))),
                 usage,
+                finish_reason,
+                id: None,
+                model: None,
+                logprobs: None,
                 raw_response: value,
             });
         }
@@ -69,6 +75,10 @@ impl TryFrom<AwsConverseOutput> for completion::CompletionResponse<AwsConverseOu
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason,
+            id: None,
+            model: None,
+            logprobs: None,
             raw_response: value,
         })
     }
@@ -81,9 +91,9 @@ impl TryFrom<aws_bedrock::ContentBlock> for RigAssistantContent {
 
     fn try_from(value: aws_bedrock::ContentBlock) -> Result<Self, Self::Error> {
         match value {
-            aws_bedrock::ContentBlock::Text(text) => {
-                Ok(RigAssistantContent(AssistantContent::Text(Text { text })))
-            }
+            aws_bedrock::ContentBlock::Text(text) => Ok(RigAssistantContent(
+                AssistantContent::Text(Text::from(text)),
+            )),
             aws_bedrock::ContentBlock::ToolUse(call) => Ok(RigAssistantContent(
                 completion::AssistantContent::tool_call(
                     &call.tool_use_id,
@@ -148,6 +158,9 @@ impl TryFrom<RigAssistantContent> for aws_bedrock::ContentBlock {
             AssistantContent::Image(_) => Err(CompletionError::ProviderError(
                 "AWS Bedrock does not support image content in assistant messages".to_owned(),
             )),
+            AssistantContent::HostedToolCall(_) => Err(CompletionError::ProviderError(
+                "AWS Bedrock does not support hosted tool calls".to_owned(),
+            )),
         }
     }
 }
@@ -6,8 +6,9 @@ use aws_sdk_bedrockruntime::types::{
     ToolSpecification,
 };
 use rig::OneOrMany;
-use rig::completion::{CompletionError, Message};
+use rig::completion::{CompletionError, Message, SamplingParam};
 use rig::message::{DocumentMediaType, UserContent};
+use rig::providers::anthropic::completion::ThinkingConfig;
 
 pub struct AwsCompletionRequest(pub rig::completion::CompletionRequest);
 
@@ -33,9 +34,67 @@ impl AwsCompletionRequest {
                 inference_configuration.set_max_tokens(Some(*max_tokens as i32));
         }
 
+        if let Some(top_p) = &self.0.sampling.top_p {
+            inference_configuration = inference_configuration.set_top_p(Some(*top_p as f32));
+        }
+
+        if let Some(stop) = &self.0.sampling.stop {
+            inference_configuration =
+                inference_configuration.set_stop_sequences(Some(stop.clone()));
+        }
+
         Some(inference_configuration.build())
     }
 
+    /// Checks the requested sampling parameters against what the Converse API's inference
+    /// configuration supports. Model-specific parameters such as `top_k` can still be passed
+    /// through `additional_params`.
+    pub fn check_sampling(&self) -> Result<(), CompletionError> {
+        self.0
+            .sampling
+            .check_supported("AWS Bedrock", &[SamplingParam::TopP, SamplingParam::Stop])
+    }
+
+    /// Checks the request against what the Converse API supports: sampling parameters (see
+    /// [Self::check_sampling]), log-probabilities and hosted tools.
+    pub fn check_supported(&self) -> Result<(), CompletionError> {
+        self.check_sampling()?;
+        self.0.check_logprobs_unsupported("AWS Bedrock")?;
+        self.0.check_hosted_tools_unsupported("AWS Bedrock")
+    }
+
+    /// Maps the reasoning configuration onto the additional model request fields. Claude models
+    /// accept the `thinking` configuration of the Anthropic messages API, the reasoning of other
+    /// models cannot be configured and is ignored with a warning (or rejected in strict mode).
+    pub fn apply_reasoning(&mut self, model: &str) -> Result<(), CompletionError> {
+        if !model.contains("anthropic.claude") {
+            return self.0.check_reasoning_unsupported("AWS Bedrock");
+        }
+
+        let Some(max_tokens) = self.0.max_tokens else {
+            if self.0.reasoning.is_some() {
+                return Err(CompletionError::RequestError(
+                    "`max_tokens` must be set to configure the thinking of Claude models".into(),
+                ));
+            }
+            return Ok(());
+        };
+
+        let Some(thinking) = ThinkingConfig::from_request(&mut self.0, max_tokens)? else {
+            return Ok(());
+        };
+
+        let thinking = serde_json::to_value(thinking)?;
+        match &mut self.0.additional_params {
+            Some(serde_json::Value::Object(params)) => {
+                params.insert("thinking".into(), thinking);
+            }
+            params => *params = Some(serde_json::json!({ "thinking": thinking })),
+        }
+
+        Ok(())
+    }
+
     pub fn tools_config(&self) -> Result<Option<ToolConfiguration>, CompletionError> {
         let mut tools = vec![];
         for tool_definition in self.0.tools.iter() {
@@ -143,15 +202,17 @@ mod tests {
         CompletionRequest {
             preamble: None,
             chat_history: OneOrMany::one(Message::User {
-                content: OneOrMany::one(UserContent::Text(Text {
-                    text: "test".to_string(),
-                })),
+                content: OneOrMany::one(UserContent::Text(Text::from("test"))),
             }),
             documents: vec![],
             tools: vec![],
+            hosted_tools: Vec::new(),
             temperature: None,
             max_tokens: None,
             tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
         }
     }
@@ -384,4 +445,64 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_apply_reasoning() {
+        use rig::completion::{ReasoningConfig, ReasoningEffort, SamplingParams};
+
+        let request = |strict| CompletionRequest {
+            max_tokens: Some(4_096),
+            reasoning: Some(ReasoningConfig::effort(ReasoningEffort::Low)),
+            sampling: SamplingParams {
+                strict,
+                ..Default::default()
+            },
+            additional_params: Some(serde_json::json!({ "top_k": 10 })),
+            ..minimal_request()
+        };
+
+        // Claude models get the thinking configuration of the Anthropic messages API
+        let mut claude = AwsCompletionRequest(request(true));
+        claude
+            .apply_reasoning("us.anthropic.claude-sonnet-4-20250514-v1:0")
+            .unwrap();
+        assert_eq!(
+            claude.0.additional_params,
+            Some(serde_json::json!({
+                "top_k": 10,
+                "thinking": { "type": "enabled", "budget_tokens": 2_048 }
+            }))
+        );
+
+        // Other models cannot be configured
+        let mut nova = AwsCompletionRequest(request(false));
+        nova.apply_reasoning("amazon.nova-pro-v1:0").unwrap();
+        assert_eq!(
+            nova.0.additional_params,
+            Some(serde_json::json!({ "top_k": 10 }))
+        );
+        assert!(
+            AwsCompletionRequest(request(true))
+                .apply_reasoning("amazon.nova-pro-v1:0")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let mut request = CompletionRequest {
+            hosted_tools: vec![rig::completion::HostedTool::web_search()],
+            logprobs: Some(0),
+            ..minimal_request()
+        };
+        // Unsupported options are only warned about outside of strict mode
+        assert!(
+            AwsCompletionRequest(request.clone())
+                .check_supported()
+                .is_ok()
+        );
+
+        request.sampling.strict = true;
+        assert!(AwsCompletionRequest(request).check_supported().is_err());
+    }
 }
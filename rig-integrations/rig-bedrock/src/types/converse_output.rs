@@ -78,6 +78,19 @@ pub enum StopReason {
     Unknown(UnknownVariantValue),
 }
 
+impl From<&StopReason> for rig::completion::FinishReason {
+    fn from(reason: &StopReason) -> Self {
+        match reason {
+            StopReason::EndTurn => Self::Stop,
+            StopReason::MaxTokens => Self::Length,
+            StopReason::ToolUse => Self::ToolCalls,
+            StopReason::StopSequence => Self::StopSequence,
+            StopReason::ContentFiltered | StopReason::GuardrailIntervened => Self::ContentFilter,
+            StopReason::Unknown(reason) => Self::Other(reason.to_string()),
+        }
+    }
+}
+
 /// Opaque struct used as inner data for the `Unknown` variant defined in enums in
 /// the crate.
 ///
@@ -34,6 +34,8 @@ impl TryFrom<RigMessage> for aws_bedrock::Message {
                 .set_content(Some(
                     content
                         .into_iter()
+                        // Hosted tool calls can only be sent back to the provider that made them
+                        .filter(|content| !matches!(content, AssistantContent::HostedToolCall(_)))
                         .map(|content| RigAssistantContent(content).try_into())
                         .collect::<Result<Vec<aws_bedrock::ContentBlock>, _>>()?,
                 ))
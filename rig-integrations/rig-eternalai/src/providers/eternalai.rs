@@ -16,7 +16,7 @@ use rig::OneOrMany;
 use rig::agent::AgentBuilder;
 use rig::client::ClientBuilderError;
 use rig::completion::GetTokenUsage;
-use rig::completion::{CompletionError, CompletionRequest};
+use rig::completion::{CompletionError, CompletionRequest, SamplingParam};
 use rig::embeddings::{EmbeddingError, EmbeddingsBuilder};
 use rig::extractor::ExtractorBuilder;
 use rig::http_client;
@@ -511,9 +511,25 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             })
             .unwrap_or_default();
 
+        let finish_reason =
+            response
+                .choices
+                .first()
+                .map(|choice| match choice.finish_reason.as_str() {
+                    "stop" => completion::FinishReason::Stop,
+                    "length" => completion::FinishReason::Length,
+                    "tool_calls" => completion::FinishReason::ToolCalls,
+                    "content_filter" => completion::FinishReason::ContentFilter,
+                    other => completion::FinishReason::Other(other.to_string()),
+                });
+
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason,
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: None,
             raw_response: response,
         })
     }
@@ -656,7 +672,19 @@ impl completion::CompletionModel for CompletionModel {
                 .collect::<Vec<_>>(),
         );
 
-        let request = if completion_request.tools.is_empty() {
+        let sampling = &completion_request.sampling;
+        sampling.check_supported(
+            "EternalAI",
+            &[
+                SamplingParam::TopP,
+                SamplingParam::Stop,
+                SamplingParam::Seed,
+                SamplingParam::PresencePenalty,
+                SamplingParam::FrequencyPenalty,
+            ],
+        )?;
+
+        let mut request = if completion_request.tools.is_empty() {
             json!({
                 "model": self.model,
                 "messages": full_history,
@@ -672,6 +700,18 @@ impl completion::CompletionModel for CompletionModel {
             })
         };
 
+        for (key, value) in [
+            ("top_p", json!(sampling.top_p)),
+            ("stop", json!(sampling.stop)),
+            ("seed", json!(sampling.seed)),
+            ("presence_penalty", json!(sampling.presence_penalty)),
+            ("frequency_penalty", json!(sampling.frequency_penalty)),
+        ] {
+            if !value.is_null() {
+                request[key] = value;
+            }
+        }
+
         tracing::debug!(target: "rig", "Sending completion request: {}", request);
 
         let response = self
@@ -742,9 +782,17 @@ impl completion::CompletionModel for CompletionModel {
                     AssistantContent::Reasoning(_) => {
                         panic!("Reasoning is currently unimplemented on Eternal AI. If you need this, please open a ticket!")
                     }
+                    // Eternal AI has no hosted tools
+                    AssistantContent::HostedToolCall(_) => {}
                 }
             }
 
+            yield Ok(RawStreamingChoice::Metadata(rig::streaming::ResponseMetadata::new(
+                resp.id.clone(),
+                resp.model.clone(),
+                resp.finish_reason.clone(),
+            )));
+
             yield Ok(RawStreamingChoice::FinalResponse(resp.raw_response.clone()));
         });
 
@@ -0,0 +1,112 @@
+use rig::embeddings::cache::{EmbeddingCache, EmbeddingCacheError};
+use rig::wasm_compat::WasmBoxedFuture;
+use rusqlite::OptionalExtension;
+use tokio_rusqlite::Connection;
+
+/// [EmbeddingCache] persisted in a SQLite table.
+///
+/// ```rust,ignore
+/// use rig::embeddings::cache::CachedEmbeddingModel;
+/// use rig_sqlite::SqliteEmbeddingCache;
+///
+/// let conn = tokio_rusqlite::Connection::open("embeddings.db").await?;
+/// let cache = SqliteEmbeddingCache::new(conn).await?;
+/// let model = CachedEmbeddingModel::new(model, "openai", TEXT_EMBEDDING_3_SMALL, cache);
+/// ```
+#[derive(Clone)]
+pub struct SqliteEmbeddingCache {
+    conn: Connection,
+    table_name: String,
+}
+
+impl SqliteEmbeddingCache {
+    /// Create the cache in the `embedding_cache` table, creating the table if it doesn't exist.
+    pub async fn new(conn: Connection) -> Result<Self, EmbeddingCacheError> {
+        Self::with_table_name(conn, "embedding_cache").await
+    }
+
+    /// Create the cache in the given table, creating the table if it doesn't exist.
+    pub async fn with_table_name(
+        conn: Connection,
+        table_name: impl Into<String>,
+    ) -> Result<Self, EmbeddingCacheError> {
+        let table_name = table_name.into();
+        let create_table = format!(
+            "CREATE TABLE IF NOT EXISTS {table_name} (key TEXT PRIMARY KEY, embedding BLOB NOT NULL)"
+        );
+
+        conn.call(move |conn| {
+            conn.execute_batch(&create_table)?;
+            Ok(())
+        })
+        .await
+        .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))?;
+
+        Ok(Self { conn, table_name })
+    }
+}
+
+fn serialize_embedding(vec: &[f64]) -> Vec<u8> {
+    vec.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn deserialize_embedding(bytes: &[u8]) -> Vec<f64> {
+    bytes
+        .chunks_exact(8)
+        .map(|chunk| f64::from_le_bytes(chunk.try_into().expect("chunk of 8 bytes")))
+        .collect()
+}
+
+impl EmbeddingCache for SqliteEmbeddingCache {
+    fn get_many<'a>(
+        &'a self,
+        keys: &'a [String],
+    ) -> WasmBoxedFuture<'a, Result<Vec<Option<Vec<f64>>>, EmbeddingCacheError>> {
+        Box::pin(async move {
+            let keys = keys.to_vec();
+            let query = format!("SELECT embedding FROM {} WHERE key = ?1", self.table_name);
+
+            self.conn
+                .call(move |conn| {
+                    let mut stmt = conn.prepare_cached(&query)?;
+                    let mut embeddings = Vec::with_capacity(keys.len());
+                    for key in &keys {
+                        let bytes = stmt
+                            .query_row([key], |row| row.get::<_, Vec<u8>>(0))
+                            .optional()?;
+                        embeddings.push(bytes.as_deref().map(deserialize_embedding));
+                    }
+                    Ok(embeddings)
+                })
+                .await
+                .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))
+        })
+    }
+
+    fn insert_many(
+        &self,
+        entries: Vec<(String, Vec<f64>)>,
+    ) -> WasmBoxedFuture<'_, Result<(), EmbeddingCacheError>> {
+        Box::pin(async move {
+            let query = format!(
+                "INSERT OR REPLACE INTO {} (key, embedding) VALUES (?1, ?2)",
+                self.table_name
+            );
+
+            self.conn
+                .call(move |conn| {
+                    let tx = conn.transaction()?;
+                    {
+                        let mut stmt = tx.prepare_cached(&query)?;
+                        for (key, vec) in &entries {
+                            stmt.execute(rusqlite::params![key, serialize_embedding(vec)])?;
+                        }
+                    }
+                    tx.commit()?;
+                    Ok(())
+                })
+                .await
+                .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))
+        })
+    }
+}
@@ -10,6 +10,10 @@ use tokio_rusqlite::Connection;
 use tracing::{debug, info};
 use zerocopy::IntoBytes;
 
+mod embedding_cache;
+
+pub use embedding_cache::SqliteEmbeddingCache;
+
 #[derive(Debug)]
 pub enum SqliteError {
     DatabaseError(Box<dyn std::error::Error + Send + Sync>),
@@ -0,0 +1,27 @@
+use rig::embeddings::cache::EmbeddingCache;
+use rig_sqlite::SqliteEmbeddingCache;
+use tokio_rusqlite::Connection;
+
+#[tokio::test]
+async fn embedding_cache_round_trip() {
+    let conn = Connection::open_in_memory().await.unwrap();
+    let cache = SqliteEmbeddingCache::new(conn).await.unwrap();
+
+    cache
+        .insert_many(vec![
+            ("a".to_string(), vec![0.5, -1.0]),
+            ("b".to_string(), vec![2.0]),
+        ])
+        .await
+        .unwrap();
+    cache
+        .insert_many(vec![("b".to_string(), vec![3.0])])
+        .await
+        .unwrap();
+
+    let keys = ["a".to_string(), "b".to_string(), "c".to_string()];
+    assert_eq!(
+        cache.get_many(&keys).await.unwrap(),
+        vec![Some(vec![0.5, -1.0]), Some(vec![3.0]), None]
+    );
+}
@@ -6,7 +6,7 @@ use crate::types::{
 };
 use rig::completion::{
     CompletionError, CompletionModel as CompletionModelTrait, CompletionRequest,
-    CompletionResponse, GetTokenUsage,
+    CompletionResponse, GetTokenUsage, SamplingParam,
 };
 use rig::streaming::StreamingCompletionResponse;
 use serde::{Deserialize, Serialize};
@@ -89,6 +89,20 @@ impl CompletionModelTrait for CompletionModel {
 
         let vertex_request = VertexCompletionRequest(request);
 
+        vertex_request.0.sampling.check_supported(
+            "Vertex AI",
+            &[
+                SamplingParam::TopP,
+                SamplingParam::TopK,
+                SamplingParam::Stop,
+                SamplingParam::Seed,
+                SamplingParam::PresencePenalty,
+                SamplingParam::FrequencyPenalty,
+            ],
+        )?;
+        vertex_request
+            .0
+            .check_hosted_tools_unsupported("Vertex AI")?;
         let contents = vertex_request.contents()?;
         let generation_config = vertex_request.generation_config();
         let system_instruction = vertex_request.system_instruction();
@@ -1,6 +1,6 @@
 use crate::types::message::RigMessage;
 use google_cloud_aiplatform_v1 as vertexai;
-use rig::completion::CompletionError;
+use rig::completion::{CompletionError, ReasoningEffort};
 
 pub struct VertexCompletionRequest(pub rig::completion::CompletionRequest);
 
@@ -83,6 +83,45 @@ impl VertexCompletionRequest {
             config = config.set_max_output_tokens(max_tokens as i32);
         }
 
+        let sampling = &self.0.sampling;
+        if let Some(top_p) = sampling.top_p {
+            config = config.set_top_p(top_p as f32);
+        }
+        if let Some(top_k) = sampling.top_k {
+            config = config.set_top_k(top_k as f32);
+        }
+        if let Some(stop) = &sampling.stop {
+            config = config.set_stop_sequences(stop.clone());
+        }
+        if let Some(seed) = sampling.seed {
+            config = config.set_seed(seed as i32);
+        }
+        if let Some(presence_penalty) = sampling.presence_penalty {
+            config = config.set_presence_penalty(presence_penalty as f32);
+        }
+        if let Some(frequency_penalty) = sampling.frequency_penalty {
+            config = config.set_frequency_penalty(frequency_penalty as f32);
+        }
+
+        if let Some(top_logprobs) = self.0.logprobs {
+            config = config.set_response_logprobs(true);
+            if top_logprobs > 0 {
+                config = config.set_logprobs(top_logprobs as i32);
+            }
+        }
+
+        if let Some(reasoning) = &self.0.reasoning {
+            // A budget of 0 disables thinking on the models supporting it
+            let thinking_budget = reasoning
+                .resolved_budget_tokens()
+                .unwrap_or_else(|| ReasoningEffort::Medium.budget_tokens());
+            config = config.set_thinking_config(
+                vertexai::model::generation_config::ThinkingConfig::new()
+                    .set_thinking_budget(i32::try_from(thinking_budget).unwrap_or(i32::MAX))
+                    .set_or_clear_include_thoughts(reasoning.summary),
+            );
+        }
+
         config = config.set_candidate_count(1);
 
         Some(config)
@@ -101,15 +140,17 @@ mod tests {
         CompletionRequest {
             preamble: None,
             chat_history: OneOrMany::one(Message::User {
-                content: OneOrMany::one(UserContent::Text(Text {
-                    text: "test".to_string(),
-                })),
+                content: OneOrMany::one(UserContent::Text(Text::from("test"))),
             }),
             documents: vec![],
             tools: vec![],
+            hosted_tools: Vec::new(),
             temperature: None,
             max_tokens: None,
             tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
         }
     }
@@ -413,4 +454,21 @@ mod tests {
         assert_eq!(config.max_output_tokens, Some(100));
         assert_eq!(config.candidate_count, Some(1));
     }
+
+    #[test]
+    fn test_generation_config_with_reasoning() {
+        let request = CompletionRequest {
+            reasoning: Some(rig::completion::ReasoningConfig::effort(
+                ReasoningEffort::High,
+            )),
+            ..minimal_request()
+        };
+
+        let config = VertexCompletionRequest(request)
+            .generation_config()
+            .unwrap();
+        let thinking = config.thinking_config.unwrap();
+        assert_eq!(thinking.thinking_budget, Some(16_384));
+        assert_eq!(thinking.include_thoughts, None);
+    }
 }
@@ -1,6 +1,8 @@
 use google_cloud_aiplatform_v1 as vertexai;
 use rig::OneOrMany;
-use rig::completion::{CompletionError, CompletionResponse, Usage};
+use rig::completion::{
+    CompletionError, CompletionResponse, FinishReason, TokenLogprob, TopLogprob, Usage,
+};
 use rig::message::{AssistantContent, Text, ToolCall, ToolFunction};
 use serde::{Deserialize, Serialize};
 
@@ -39,7 +41,7 @@ impl TryFrom<VertexGenerateContentOutput> for CompletionResponse<VertexGenerateC
                     ToolFunction::new(function_call.name.clone(), args_json),
                 )));
             } else if let Some(text) = part.text() {
-                assistant_contents.push(AssistantContent::Text(Text { text: text.clone() }));
+                assistant_contents.push(AssistantContent::Text(Text::from(text.clone())));
             }
         }
 
@@ -63,14 +65,77 @@ impl TryFrom<VertexGenerateContentOutput> for CompletionResponse<VertexGenerateC
             })
             .unwrap_or_default();
 
+        let has_tool_calls = choice
+            .iter()
+            .any(|content| matches!(content, AssistantContent::ToolCall(_)));
+        let finish_reason = map_finish_reason(&candidate.finish_reason, has_tool_calls);
+        let id = Some(response.response_id.clone()).filter(|id| !id.is_empty());
+        let model = Some(response.model_version.clone()).filter(|model| !model.is_empty());
+        let logprobs = candidate.logprobs_result.as_ref().map(map_logprobs);
+
         Ok(CompletionResponse {
             choice,
             usage,
+            finish_reason,
+            id,
+            model,
+            logprobs,
             raw_response: value,
         })
     }
 }
 
+fn map_logprobs(result: &vertexai::model::LogprobsResult) -> Vec<TokenLogprob> {
+    result
+        .chosen_candidates
+        .iter()
+        .enumerate()
+        .map(|(i, chosen)| TokenLogprob {
+            token: chosen.token.clone().unwrap_or_default(),
+            logprob: chosen.log_probability.unwrap_or_default() as f64,
+            bytes: None,
+            top_logprobs: result
+                .top_candidates
+                .get(i)
+                .map(|top| {
+                    top.candidates
+                        .iter()
+                        .map(|candidate| TopLogprob {
+                            token: candidate.token.clone().unwrap_or_default(),
+                            logprob: candidate.log_probability.unwrap_or_default() as f64,
+                            bytes: None,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Vertex AI reports `STOP` when the model calls functions, so tool calls take precedence.
+fn map_finish_reason(
+    reason: &vertexai::model::candidate::FinishReason,
+    has_tool_calls: bool,
+) -> Option<FinishReason> {
+    use vertexai::model::candidate::FinishReason as VertexFinishReason;
+
+    match reason {
+        VertexFinishReason::Unspecified => None,
+        VertexFinishReason::Stop if has_tool_calls => Some(FinishReason::ToolCalls),
+        VertexFinishReason::Stop => Some(FinishReason::Stop),
+        VertexFinishReason::MaxTokens => Some(FinishReason::Length),
+        VertexFinishReason::Safety
+        | VertexFinishReason::Recitation
+        | VertexFinishReason::Blocklist
+        | VertexFinishReason::ProhibitedContent
+        | VertexFinishReason::Spii
+        | VertexFinishReason::ModelArmor => Some(FinishReason::ContentFilter),
+        other => Some(FinishReason::Other(
+            other.name().unwrap_or("UNKNOWN").to_string(),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,9 +187,7 @@ mod tests {
         let response = completion_response.unwrap();
         assert_eq!(
             response.choice,
-            OneOrMany::one(AssistantContent::Text(Text {
-                text: "Hello, world!".to_string()
-            }))
+            OneOrMany::one(AssistantContent::Text(Text::from("Hello, world!")))
         );
     }
 
@@ -171,6 +234,45 @@ mod tests {
         assert_eq!(response.usage.total_tokens, 30);
     }
 
+    #[test]
+    fn test_finish_reason_conversion() {
+        let response: CompletionResponse<VertexGenerateContentOutput> =
+            create_text_response("test").try_into().unwrap();
+        assert_eq!(response.finish_reason, Some(FinishReason::Stop));
+
+        let response: CompletionResponse<VertexGenerateContentOutput> =
+            create_tool_call_response("add", serde_json::json!({}))
+                .try_into()
+                .unwrap();
+        assert_eq!(response.finish_reason, Some(FinishReason::ToolCalls));
+    }
+
+    #[test]
+    fn test_logprobs_conversion() {
+        use vertexai::model::logprobs_result::{Candidate, TopCandidates};
+
+        let mut response = create_text_response("Yes").0;
+        response.candidates[0].logprobs_result = Some(
+            vertexai::model::LogprobsResult::new()
+                .set_chosen_candidates([Candidate::new()
+                    .set_token("Yes")
+                    .set_log_probability(-0.5)])
+                .set_top_candidates([TopCandidates::new().set_candidates([
+                    Candidate::new().set_token("Yes").set_log_probability(-0.5),
+                    Candidate::new().set_token("No").set_log_probability(-1.0),
+                ])]),
+        );
+
+        let response: CompletionResponse<VertexGenerateContentOutput> =
+            VertexGenerateContentOutput(response).try_into().unwrap();
+        let logprobs = response.logprobs.unwrap();
+
+        assert_eq!(logprobs.len(), 1);
+        assert_eq!(logprobs[0].token, "Yes");
+        assert_eq!(logprobs[0].logprob, -0.5);
+        assert_eq!(logprobs[0].top_logprobs[1].token, "No");
+    }
+
     #[test]
     fn test_empty_response_error() {
         // Create a response with no candidates
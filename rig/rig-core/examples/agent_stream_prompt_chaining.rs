@@ -0,0 +1,48 @@
+use std::io::Write;
+
+use futures::StreamExt;
+use rig::agent::MultiTurnStreamItem;
+use rig::pipeline::{self, Op, StreamOp, stream_op::stream_prompt};
+use rig::prelude::*;
+use rig::providers::openai;
+use rig::providers::openai::client::Client;
+use rig::streaming::StreamedAssistantContent;
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    // Create OpenAI client
+    let openai_client = Client::from_env();
+
+    let topic_agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You pick an unusual topic for a short story. Only return the topic.")
+        .build();
+
+    let writer_agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a writer. Write a very short story about the given topic.")
+        .build();
+
+    let pipeline = pipeline::new()
+        // Pick a topic (regular op, resolved before streaming starts)
+        .prompt(topic_agent)
+        .map(|topic| topic.unwrap_or_else(|_| "A lighthouse keeper".to_string()))
+        // Stream the story
+        .chain_stream(stream_prompt(writer_agent))
+        // Only keep the text deltas
+        .filter_map(|item| match item {
+            Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(text))) => {
+                Some(text.text)
+            }
+            _ => None,
+        });
+
+    let mut stream = pipeline.call_stream("Pick a topic".to_string());
+    while let Some(text) = stream.next().await {
+        print!("{text}");
+        std::io::stdout().flush()?;
+    }
+    println!();
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! The evaluator-optimizer workflow of the `agent_evaluator_optimizer` example,
+//! expressed as a `pipeline::graph` with a loop between a generator and an evaluator agent.
+use rig::pipeline::graph::{END, Graph, InMemoryCheckpointer};
+use rig::prelude::*;
+use rig::providers::openai;
+
+const TASK: &str = "Implement a Stack with:
+1. push(x)
+2. pop()
+3. getMin()
+All operations should be O(1).
+";
+
+#[derive(Clone, Debug, Default)]
+struct State {
+    solution: String,
+    feedback: Option<String>,
+    passed: bool,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    tracing_subscriber::fmt().init();
+
+    let openai_client = openai::Client::from_env();
+
+    let generator = openai_client
+        .agent(openai::GPT_4O)
+        .preamble(
+            "Your goal is to complete the task based on <user input>. If there is feedback \
+            from your previous generations, you should reflect on it to improve your solution. \
+            Only output your code implementation.",
+        )
+        .build();
+
+    let evaluator = openai_client
+        .agent(openai::GPT_4O)
+        .preamble(
+            "Evaluate the following code implementation for code correctness, time complexity \
+            and style. You should be evaluating only and not attempting to solve the task. \
+            Only output \"PASS\" if all criteria are met and you have no further suggestions for improvements. \
+            Otherwise provide detailed feedback on what needs improvement and why.",
+        )
+        .build();
+
+    let checkpointer = InMemoryCheckpointer::new();
+
+    let graph = Graph::builder()
+        .agent_node(
+            "generate",
+            generator,
+            |state: &State| match &state.feedback {
+                Some(feedback) => format!("{TASK}\n\nFeedback:\n{feedback}"),
+                None => TASK.to_string(),
+            },
+            |state, solution| State { solution, ..state },
+        )
+        .agent_node(
+            "evaluate",
+            evaluator,
+            |state: &State| format!("{TASK}\n\n{}", state.solution),
+            |state, evaluation| State {
+                passed: evaluation.trim() == "PASS",
+                feedback: Some(evaluation),
+                ..state
+            },
+        )
+        .edge("generate", "evaluate")
+        .conditional_edge(
+            "evaluate",
+            |state: &State| {
+                if state.passed { END } else { "generate" }
+            },
+        )
+        .start("generate")
+        .max_iterations(10)
+        .checkpointer(checkpointer.clone())
+        .build()?;
+
+    let state = graph.run(State::default()).await?;
+
+    println!(
+        "Solution after {} steps:\n{}",
+        checkpointer.checkpoints().len(),
+        state.solution
+    );
+
+    Ok(())
+}
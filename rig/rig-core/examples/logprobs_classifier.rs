@@ -0,0 +1,45 @@
+//! Uses the log-probabilities of the generated tokens to get the confidence of a classification.
+use rig::completion::{CompletionModel, TokenLogprob};
+use rig::prelude::*;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let model = openai::Client::from_env().completion_model(openai::GPT_4O_MINI);
+
+    let response = model
+        .completion_request("I am happy")
+        .preamble(
+            "Classify the sentiment of the user's message. \
+            Answer with a single word: positive, negative or neutral."
+                .to_string(),
+        )
+        .temperature(0.0)
+        .top_logprobs(3)
+        .send()
+        .await?;
+
+    let logprobs = response.logprobs.unwrap_or_default();
+    let Some(label) = logprobs.first() else {
+        anyhow::bail!("The provider did not return log-probabilities");
+    };
+
+    println!(
+        "Sentiment: {} (confidence: {:.2}%)",
+        label.token,
+        label.probability() * 100.0
+    );
+    for alternative in &label.top_logprobs {
+        println!(
+            "  {:<10} {:.2}%",
+            alternative.token,
+            alternative.logprob.exp() * 100.0
+        );
+    }
+
+    if let Some(mean) = TokenLogprob::mean(&logprobs) {
+        println!("Mean log-probability of the answer: {mean:.3}");
+    }
+
+    Ok(())
+}
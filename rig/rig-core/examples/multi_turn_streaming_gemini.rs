@@ -95,7 +95,7 @@ where
             while let Some(content) = stream.next().await {
                 match content {
                     Ok(StreamedAssistantContent::Text(text)) => {
-                        yield Ok(Text { text: text.text });
+                        yield Ok(text);
                         did_call_tool = false;
                     },
                     Ok(StreamedAssistantContent::ToolCall(tool_call)) => {
@@ -113,7 +113,7 @@ where
                     },
                     Ok(StreamedAssistantContent::Reasoning(rig::message::Reasoning { reasoning, .. })) => {
                         if !reasoning.is_empty() {
-                            yield Ok(Text { text: reasoning.first().unwrap().to_owned() });
+                            yield Ok(Text::from(reasoning.first().unwrap()));
                         }
                         did_call_tool = false;
                     },
@@ -176,7 +176,7 @@ async fn custom_stream_to_stdout(stream: &mut StreamingResult) -> Result<(), std
     print!("Response: ");
     while let Some(content) = stream.next().await {
         match content {
-            Ok(Text { text }) => {
+            Ok(Text { text, .. }) => {
                 print!("{text}");
                 std::io::Write::flush(&mut std::io::stdout())?;
             }
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use rig::client::Modality;
+use rig::prelude::*;
+use rig::providers::openai;
+use rig::realtime::{AudioFormat, RealtimeModel, ServerEvent, SessionConfig, TurnDetection};
+use rig::{completion::ToolDefinition, tool::Tool};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Write;
+
+#[derive(Deserialize)]
+struct OperationArgs {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Math error")]
+struct MathError;
+
+#[derive(Deserialize, Serialize)]
+struct Adder;
+impl Tool for Adder {
+    const NAME: &'static str = "add";
+    type Error = MathError;
+    type Args = OperationArgs;
+    type Output = i32;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "add".to_string(),
+            description: "Add x and y together".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "x": { "type": "number", "description": "The first number to add" },
+                    "y": { "type": "number", "description": "The second number to add" }
+                },
+                "required": ["x", "y"],
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        println!("\n[tool-call] Adding {} and {}", args.x, args.y);
+        Ok(args.x + args.y)
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let client = openai::Client::from_env();
+
+    // The agent provides the instructions and the tools of the session
+    let agent = client
+        .agent(openai::GPT_4O)
+        .preamble("You are a calculator. Answer briefly.")
+        .tool(Adder)
+        .build();
+
+    let model = client.realtime_model(openai::realtime::GPT_REALTIME);
+    let config = SessionConfig::new()
+        .modalities([Modality::Text, Modality::Audio])
+        .voice("alloy")
+        .output_audio_format(AudioFormat::Pcm16)
+        // Microphone audio would be sent with `append_audio`, the turns being detected by the server
+        .turn_detection(TurnDetection::server_vad());
+
+    let mut session = model.connect_agent(&agent, config).await?;
+    session.sender().send_text("What is 2 + 5?").await?;
+
+    let mut audio = Vec::new();
+    let mut responses = 0;
+    while let Some(event) = session.next_event().await {
+        match event? {
+            ServerEvent::AudioTranscriptDelta { delta, .. } => {
+                print!("{delta}");
+                std::io::stdout().flush()?;
+            }
+            ServerEvent::AudioDelta { data, .. } => audio.extend(data),
+            ServerEvent::ToolResult { output, .. } => println!("[tool-result] {output}"),
+            ServerEvent::Error { message, .. } => eprintln!("Error: {message}"),
+            ServerEvent::ResponseDone { usage, .. } => {
+                responses += 1;
+                println!("\n[response-done] {usage:?}");
+                // The first response is the tool call, the second one the answer
+                if responses == 2 {
+                    break;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    println!("Received {} bytes of 24kHz PCM16 audio", audio.len());
+    session.close().await?;
+
+    Ok(())
+}
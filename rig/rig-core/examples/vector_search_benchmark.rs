@@ -0,0 +1,132 @@
+//! Compares the recall and latency of the in-memory vector store index strategies
+//! (brute force, LSH and HNSW) on synthetic clustered data.
+//!
+//! Run with `cargo run --release --example vector_search_benchmark`.
+//! The corpus size can be changed with the `NUM_DOCUMENTS` environment variable.
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
+
+use rig::{
+    OneOrMany,
+    embeddings::Embedding,
+    vector_store::{IndexStrategy, in_memory_store::InMemoryVectorStore},
+};
+
+const DIMENSIONS: usize = 128;
+const NUM_CLUSTERS: usize = 64;
+const NUM_QUERIES: usize = 200;
+const TOP_K: usize = 10;
+
+fn main() {
+    let num_documents = std::env::var("NUM_DOCUMENTS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(20_000);
+
+    let mut rng = fastrand::Rng::with_seed(7);
+    let centroids = (0..NUM_CLUSTERS)
+        .map(|_| random_vector(&mut rng, 1.0))
+        .collect::<Vec<_>>();
+
+    let documents = (0..num_documents)
+        .map(|i| {
+            let vec = jitter(&mut rng, &centroids[i % NUM_CLUSTERS], 0.3);
+            (
+                format!("doc{i}"),
+                i,
+                OneOrMany::one(Embedding {
+                    document: String::new(),
+                    vec,
+                }),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let queries = (0..NUM_QUERIES)
+        .map(|i| Embedding {
+            document: String::new(),
+            vec: jitter(&mut rng, &centroids[i % NUM_CLUSTERS], 0.3),
+        })
+        .collect::<Vec<_>>();
+
+    println!(
+        "{num_documents} documents, {DIMENSIONS} dimensions, {NUM_QUERIES} queries, top {TOP_K}\n"
+    );
+    println!(
+        "{:<14} {:>12} {:>14} {:>14} {:>10}",
+        "strategy", "build (ms)", "mean query (µs)", "p95 query (µs)", "recall"
+    );
+
+    let mut ground_truth = Vec::new();
+
+    for (name, strategy) in [
+        ("brute force", IndexStrategy::BruteForce),
+        (
+            "lsh",
+            IndexStrategy::LSH {
+                num_tables: 8,
+                num_hyperplanes: 12,
+            },
+        ),
+        ("hnsw", IndexStrategy::hnsw()),
+    ] {
+        let start = Instant::now();
+        let store = InMemoryVectorStore::builder()
+            .index_strategy(strategy)
+            .documents_with_ids(documents.clone())
+            .build();
+        let build_time = start.elapsed();
+
+        let mut latencies = Vec::with_capacity(NUM_QUERIES);
+        let mut results = Vec::with_capacity(NUM_QUERIES);
+
+        for query in &queries {
+            let start = Instant::now();
+            let top_n = store.top_n_ids_by_embedding(query, TOP_K);
+            latencies.push(start.elapsed());
+
+            results.push(top_n.into_iter().map(|(_, id)| id).collect::<HashSet<_>>());
+        }
+
+        // Brute force is exact, so it provides the ground truth for recall
+        if ground_truth.is_empty() {
+            ground_truth = results.clone();
+        }
+
+        let hits = results
+            .iter()
+            .zip(ground_truth.iter())
+            .map(|(result, expected)| result.intersection(expected).count())
+            .sum::<usize>();
+        let recall = hits as f64 / (NUM_QUERIES * TOP_K) as f64;
+
+        latencies.sort();
+        let mean = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+        let p95 = latencies[latencies.len() * 95 / 100];
+
+        println!(
+            "{:<14} {:>12} {:>14} {:>14} {:>10.3}",
+            name,
+            build_time.as_millis(),
+            mean.as_micros(),
+            p95.as_micros(),
+            recall
+        );
+    }
+}
+
+fn random_vector(rng: &mut fastrand::Rng, scale: f64) -> Vec<f64> {
+    (0..DIMENSIONS)
+        .map(|_| (rng.f64() * 2.0 - 1.0) * scale)
+        .collect()
+}
+
+fn jitter(rng: &mut fastrand::Rng, centroid: &[f64], scale: f64) -> Vec<f64> {
+    centroid
+        .iter()
+        .zip(random_vector(rng, scale))
+        .map(|(c, noise)| c + noise)
+        .collect()
+}
@@ -3,7 +3,8 @@ use std::{collections::HashMap, sync::Arc};
 use tokio::sync::RwLock;
 
 use crate::{
-    completion::{CompletionModel, Document},
+    client::ModelInfo,
+    completion::{CompletionModel, Document, HostedTool, ReasoningConfig, SamplingParams},
     message::ToolChoice,
     tool::{
         Tool, ToolSet,
@@ -61,6 +62,12 @@ where
     dynamic_context: Vec<(usize, Box<dyn VectorStoreIndexDyn + Send + Sync>)>,
     /// Temperature of the model
     temperature: Option<f64>,
+    /// Common sampling parameters (top p, top k, stop sequences, seed, penalties...)
+    sampling: SamplingParams,
+    /// Reasoning ("thinking") configuration of the model
+    reasoning: Option<ReasoningConfig>,
+    /// Tools hosted and executed by the model provider
+    hosted_tools: Vec<HostedTool>,
     /// Tool server handle
     tool_server_handle: Option<ToolServerHandle>,
     /// Whether or not the underlying LLM should be forced to use a tool before providing a response.
@@ -85,6 +92,9 @@ where
             preamble: None,
             static_context: vec![],
             temperature: None,
+            sampling: SamplingParams::default(),
+            reasoning: None,
+            hosted_tools: Vec::new(),
             max_tokens: None,
             additional_params: None,
             dynamic_context: vec![],
@@ -158,6 +168,9 @@ where
             dynamic_context: vec![],
             dynamic_tools: vec![],
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             tools,
             tool_choice: self.tool_choice,
             context_compressor: self.context_compressor,
@@ -195,6 +208,9 @@ where
             dynamic_context: vec![],
             dynamic_tools: vec![],
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             tools,
             tool_choice: self.tool_choice,
             context_compressor: self.context_compressor,
@@ -236,6 +252,9 @@ where
             dynamic_context: vec![],
             dynamic_tools: vec![],
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             tools,
             tool_choice: self.tool_choice,
             context_compressor: self.context_compressor,
@@ -315,6 +334,9 @@ where
             dynamic_context: vec![],
             dynamic_tools,
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             tools: toolset,
             tool_choice: self.tool_choice,
             context_compressor: self.context_compressor,
@@ -329,6 +351,72 @@ where
         self
     }
 
+    /// Set the nucleus sampling probability mass of the model
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.sampling.top_p = Some(top_p);
+        self
+    }
+
+    /// Set the number of most likely tokens the model samples from
+    pub fn top_k(mut self, top_k: u64) -> Self {
+        self.sampling.top_k = Some(top_k);
+        self
+    }
+
+    /// Set the sequences that stop the generation
+    pub fn stop(mut self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.sampling.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the sampling seed of the model
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampling.seed = Some(seed);
+        self
+    }
+
+    /// Set the presence penalty of the model
+    pub fn presence_penalty(mut self, presence_penalty: f64) -> Self {
+        self.sampling.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    /// Set the frequency penalty of the model
+    pub fn frequency_penalty(mut self, frequency_penalty: f64) -> Self {
+        self.sampling.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Set the logit bias (token id to bias) of the model
+    pub fn logit_bias(mut self, logit_bias: HashMap<u32, f64>) -> Self {
+        self.sampling.logit_bias = Some(logit_bias);
+        self
+    }
+
+    /// Fail completion requests instead of warning when the provider does not support one of the sampling parameters
+    pub fn strict_sampling(mut self, strict: bool) -> Self {
+        self.sampling.strict = strict;
+        self
+    }
+
+    /// Set all the sampling parameters at once
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Set the reasoning ("thinking") configuration of the model
+    pub fn reasoning(mut self, reasoning: ReasoningConfig) -> Self {
+        self.reasoning = Some(reasoning);
+        self
+    }
+
+    /// Add a tool hosted and executed by the model provider, such as web search
+    pub fn hosted_tool(mut self, tool: HostedTool) -> Self {
+        self.hosted_tools.push(tool);
+        self
+    }
+
     /// Set the maximum number of tokens for the completion
     pub fn max_tokens(mut self, max_tokens: u64) -> Self {
         self.max_tokens = Some(max_tokens);
@@ -347,6 +435,17 @@ where
         self
     }
 
+    /// Configure the agent from the model's metadata, see [ModelListingClient].
+    /// Only fills in the context window if it was not already set.
+    ///
+    /// [ModelListingClient]: crate::client::ModelListingClient
+    pub fn model_info(mut self, info: &ModelInfo) -> Self {
+        if self.context_window.is_none() {
+            self.context_window = info.context_window;
+        }
+        self
+    }
+
     /// Build the agent
     pub fn build(self) -> Agent<M> {
         let tool_server_handle = if let Some(handle) = self.tool_server_handle {
@@ -362,6 +461,9 @@ where
             preamble: self.preamble,
             static_context: self.static_context,
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             max_tokens: self.max_tokens,
             additional_params: self.additional_params,
             tool_choice: self.tool_choice,
@@ -421,6 +523,12 @@ where
     dynamic_tools: Vec<(usize, Box<dyn VectorStoreIndexDyn + Send + Sync>)>,
     /// Temperature of the model
     temperature: Option<f64>,
+    /// Common sampling parameters (top p, top k, stop sequences, seed, penalties...)
+    sampling: SamplingParams,
+    /// Reasoning ("thinking") configuration of the model
+    reasoning: Option<ReasoningConfig>,
+    /// Tools hosted and executed by the model provider
+    hosted_tools: Vec<HostedTool>,
     /// Actual tool implementations
     tools: ToolSet,
     /// Whether or not the underlying LLM should be forced to use a tool before providing a response.
@@ -446,6 +554,9 @@ where
             static_context: vec![],
             static_tools: vec![],
             temperature: None,
+            sampling: SamplingParams::default(),
+            reasoning: None,
+            hosted_tools: Vec::new(),
             max_tokens: None,
             additional_params: None,
             dynamic_context: vec![],
@@ -595,6 +706,72 @@ where
         self
     }
 
+    /// Set the nucleus sampling probability mass of the model
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.sampling.top_p = Some(top_p);
+        self
+    }
+
+    /// Set the number of most likely tokens the model samples from
+    pub fn top_k(mut self, top_k: u64) -> Self {
+        self.sampling.top_k = Some(top_k);
+        self
+    }
+
+    /// Set the sequences that stop the generation
+    pub fn stop(mut self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.sampling.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set the sampling seed of the model
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampling.seed = Some(seed);
+        self
+    }
+
+    /// Set the presence penalty of the model
+    pub fn presence_penalty(mut self, presence_penalty: f64) -> Self {
+        self.sampling.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    /// Set the frequency penalty of the model
+    pub fn frequency_penalty(mut self, frequency_penalty: f64) -> Self {
+        self.sampling.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Set the logit bias (token id to bias) of the model
+    pub fn logit_bias(mut self, logit_bias: HashMap<u32, f64>) -> Self {
+        self.sampling.logit_bias = Some(logit_bias);
+        self
+    }
+
+    /// Fail completion requests instead of warning when the provider does not support one of the sampling parameters
+    pub fn strict_sampling(mut self, strict: bool) -> Self {
+        self.sampling.strict = strict;
+        self
+    }
+
+    /// Set all the sampling parameters at once
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Set the reasoning ("thinking") configuration of the model
+    pub fn reasoning(mut self, reasoning: ReasoningConfig) -> Self {
+        self.reasoning = Some(reasoning);
+        self
+    }
+
+    /// Add a tool hosted and executed by the model provider, such as web search
+    pub fn hosted_tool(mut self, tool: HostedTool) -> Self {
+        self.hosted_tools.push(tool);
+        self
+    }
+
     /// Set the maximum number of tokens for the completion
     pub fn max_tokens(mut self, max_tokens: u64) -> Self {
         self.max_tokens = Some(max_tokens);
@@ -613,6 +790,17 @@ where
         self
     }
 
+    /// Configure the agent from the model's metadata, see [ModelListingClient].
+    /// Only fills in the context window if it was not already set.
+    ///
+    /// [ModelListingClient]: crate::client::ModelListingClient
+    pub fn model_info(mut self, info: &ModelInfo) -> Self {
+        if self.context_window.is_none() {
+            self.context_window = info.context_window;
+        }
+        self
+    }
+
     /// Build the agent
     pub fn build(self) -> Agent<M> {
         let tool_server_handle = ToolServer::new()
@@ -628,6 +816,9 @@ where
             preamble: self.preamble,
             static_context: self.static_context,
             temperature: self.temperature,
+            sampling: self.sampling,
+            reasoning: self.reasoning,
+            hosted_tools: self.hosted_tools,
             max_tokens: self.max_tokens,
             additional_params: self.additional_params,
             tool_choice: self.tool_choice,
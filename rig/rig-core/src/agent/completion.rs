@@ -3,7 +3,7 @@ use crate::{
     agent::prompt_request::streaming::StreamingPromptRequest,
     completion::{
         Chat, Completion, CompletionError, CompletionModel, CompletionRequestBuilder, Document,
-        GetTokenUsage, Message, Prompt, PromptError,
+        GetTokenUsage, HostedTool, Message, Prompt, PromptError, ReasoningConfig, SamplingParams,
     },
     message::ToolChoice,
     streaming::{StreamingChat, StreamingCompletion, StreamingPrompt},
@@ -64,6 +64,12 @@ where
     pub static_context: Vec<Document>,
     /// Temperature of the model
     pub temperature: Option<f64>,
+    /// Common sampling parameters (top p, top k, stop sequences, seed, penalties...)
+    pub sampling: SamplingParams,
+    /// Reasoning ("thinking") configuration of the model
+    pub reasoning: Option<ReasoningConfig>,
+    /// Tools hosted and executed by the model provider
+    pub hosted_tools: Vec<HostedTool>,
     /// Maximum number of tokens for the completion
     pub max_tokens: Option<u64>,
     /// Additional parameters to be passed to the model
@@ -128,6 +134,9 @@ where
             .completion_request(prompt)
             .messages(chat_history)
             .temperature_opt(self.temperature)
+            .sampling(self.sampling.clone())
+            .reasoning_opt(self.reasoning.clone())
+            .hosted_tools(self.hosted_tools.clone())
             .max_tokens_opt(self.max_tokens)
             .additional_params_opt(self.additional_params.clone())
             .documents(self.static_context.clone());
@@ -109,13 +109,18 @@
 mod builder;
 mod completion;
 pub(crate) mod prompt_request;
+#[cfg(feature = "agent-spec")]
+#[cfg_attr(docsrs, doc(cfg(feature = "agent-spec")))]
+pub mod spec;
 mod tool;
 
 pub use crate::message::Text;
 pub use builder::{AgentBuilder, AgentBuilderSimple};
 pub use completion::Agent;
 pub use prompt_request::streaming::{
-    FinalResponse, MultiTurnStreamItem, StreamingPromptRequest, stream_to_stdout,
+    FinalResponse, MultiTurnStreamItem, StreamingError, StreamingPromptRequest, stream_to_stdout,
 };
 pub use prompt_request::{CancelSignal, PromptRequest, PromptResponse};
 pub use prompt_request::{PromptHook, StreamingPromptHook};
+#[cfg(feature = "agent-spec")]
+pub use spec::{AgentRegistry, AgentSpec, AgentSpecError};
@@ -17,7 +17,7 @@ use tracing::info_span;
 
 use crate::{
     OneOrMany,
-    completion::{Completion, CompletionModel, Message, PromptError, Usage},
+    completion::{Completion, CompletionModel, FinishReason, Message, PromptError, Usage},
     json_utils,
     message::{AssistantContent, UserContent},
     tool::ToolSetError,
@@ -26,6 +26,9 @@ use crate::{
 
 use super::Agent;
 
+/// The message sent to the model when asking it to continue a truncated response.
+pub(crate) const CONTINUE_PROMPT: &str = "Your previous response was cut off. Continue exactly where you left off, without repeating anything.";
+
 pub trait PromptType {}
 pub struct Standard;
 pub struct Extended;
@@ -62,6 +65,8 @@ where
     hook: Option<P>,
     /// How many tools should be executed at the same time (1 by default).
     concurrency: usize,
+    /// How many times the model may be asked to continue a response truncated by `max_tokens` (0 by default).
+    max_continuations: usize,
 }
 
 impl<'a, M> PromptRequest<'a, Standard, M, ()>
@@ -78,6 +83,7 @@ where
             state: PhantomData,
             hook: None,
             concurrency: 1,
+            max_continuations: 0,
         }
     }
 }
@@ -102,6 +108,7 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            max_continuations: self.max_continuations,
         }
     }
     /// Set the maximum depth for multi-turn conversations (ie, the maximum number of turns an LLM can have calling tools before writing a text response).
@@ -115,6 +122,7 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            max_continuations: self.max_continuations,
         }
     }
 
@@ -125,6 +133,16 @@ where
         self
     }
 
+    /// Automatically ask the model to continue when a text response was cut off by the
+    /// maximum number of tokens (see [`crate::completion::FinishReason::Length`]).
+    /// The partial responses are concatenated into the final output and stored as a single
+    /// assistant message in the chat history. Continuations do not count towards the multi-turn
+    /// depth.
+    pub fn auto_continue(mut self, max_continuations: usize) -> Self {
+        self.max_continuations = max_continuations;
+        self
+    }
+
     /// Add chat history to the prompt request
     pub fn with_history(self, history: &'a mut Vec<Message>) -> PromptRequest<'a, S, M, P> {
         PromptRequest {
@@ -135,6 +153,7 @@ where
             state: PhantomData,
             hook: self.hook,
             concurrency: self.concurrency,
+            max_continuations: self.max_continuations,
         }
     }
 
@@ -151,6 +170,7 @@ where
             state: PhantomData,
             hook: Some(hook),
             concurrency: self.concurrency,
+            max_continuations: self.max_continuations,
         }
     }
 }
@@ -276,6 +296,15 @@ where
 pub struct PromptResponse {
     pub output: String,
     pub total_usage: Usage,
+    /// Why the model stopped generating the final response, if reported by the provider
+    pub finish_reason: Option<FinishReason>,
+    /// The id of the final response, if reported by the provider
+    pub id: Option<String>,
+    /// The model that served the final response, if reported by the provider
+    pub model: Option<String>,
+    /// The messages exchanged while answering the prompt: the prompt itself, the assistant
+    /// responses (including tool calls) and the tool results
+    pub messages: Vec<Message>,
 }
 
 impl PromptResponse {
@@ -283,6 +312,10 @@ impl PromptResponse {
         Self {
             output: output.into(),
             total_usage,
+            finish_reason: None,
+            id: None,
+            model: None,
+            messages: Vec::new(),
         }
     }
 }
@@ -315,6 +348,7 @@ where
         } else {
             &mut vec![self.prompt.to_owned()]
         };
+        let prompt_index = chat_history.len() - 1;
 
         if let Some(text) = self.prompt.rag_text() {
             agent_span.record("gen_ai.prompt", text);
@@ -323,6 +357,10 @@ where
         let cancel_sig = CancelSignal::new();
 
         let mut current_max_depth = 0;
+        let mut continuations = 0;
+        let mut partial_output = String::new();
+        // Index of the first truncated response in the chat history, while continuing it
+        let mut continuation_start = None;
         let mut usage = Usage::new();
         let current_span_id: AtomicU64 = AtomicU64::new(0);
 
@@ -411,23 +449,59 @@ where
                 .iter()
                 .partition(|choice| matches!(choice, AssistantContent::ToolCall(_)));
 
-            chat_history.push(Message::Assistant {
-                id: None,
-                content: resp.choice.clone(),
-            });
+            let merged_texts = texts
+                .into_iter()
+                .filter_map(|content| {
+                    if let AssistantContent::Text(text) = content {
+                        Some(text.text.clone())
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+
+            if tool_calls.is_empty()
+                && resp
+                    .finish_reason
+                    .as_ref()
+                    .is_some_and(FinishReason::is_truncated)
+                && continuations < self.max_continuations
+            {
+                continuations += 1;
+                tracing::info!(
+                    "Response truncated, continuing: {}/{}",
+                    continuations,
+                    self.max_continuations
+                );
+                partial_output.push_str(&merged_texts);
+                continuation_start.get_or_insert(chat_history.len());
+                chat_history.push(Message::Assistant {
+                    id: None,
+                    content: resp.choice.clone(),
+                });
+                chat_history.push(Message::user(CONTINUE_PROMPT));
+                // Continuations do not count towards the multi-turn depth
+                current_max_depth -= 1;
+                continue;
+            }
+
+            // Fold the continued responses into a single message, so the synthetic continuation
+            // prompts do not end up in the caller's chat history
+            let content = match continuation_start.take() {
+                Some(start) => {
+                    chat_history.truncate(start);
+                    let partial = AssistantContent::text(&partial_output);
+                    OneOrMany::many(std::iter::once(partial).chain(resp.choice.clone()))
+                        .expect("There is at least the partial response")
+                }
+                None => resp.choice.clone(),
+            };
+
+            chat_history.push(Message::Assistant { id: None, content });
 
             if tool_calls.is_empty() {
-                let merged_texts = texts
-                    .into_iter()
-                    .filter_map(|content| {
-                        if let AssistantContent::Text(text) = content {
-                            Some(text.text.clone())
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join("\n");
+                let merged_texts = partial_output + &merged_texts;
 
                 if self.max_depth > 1 {
                     tracing::info!("Depth reached: {}/{}", current_max_depth, self.max_depth);
@@ -438,9 +512,18 @@ where
                 agent_span.record("gen_ai.usage.output_tokens", usage.output_tokens);
 
                 // If there are no tool calls, depth is not relevant, we can just return the merged text response.
-                return Ok(PromptResponse::new(merged_texts, usage));
+                return Ok(PromptResponse {
+                    finish_reason: resp.finish_reason,
+                    id: resp.id,
+                    model: resp.model,
+                    messages: chat_history[prompt_index..].to_vec(),
+                    ..PromptResponse::new(merged_texts, usage)
+                });
             }
 
+            // The partial text is part of the turn that led to the tool calls, not of the output
+            partial_output.clear();
+
             let hook = self.hook.clone();
 
             let tool_calls: Vec<AssistantContent> = tool_calls.into_iter().cloned().collect();
@@ -566,3 +649,66 @@ where
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        agent::AgentBuilder,
+        completion::{FinishReason, Message, Prompt},
+        message::UserContent,
+        testing::{MockCompletionModel, MockResponse},
+    };
+
+    use super::CONTINUE_PROMPT;
+
+    #[tokio::test]
+    async fn test_auto_continue_concatenates_truncated_output() {
+        let model = MockCompletionModel::new()
+            .response(MockResponse::text("The quick brown ").finish_reason(FinishReason::Length))
+            .response(MockResponse::text("fox jumps").finish_reason(FinishReason::Stop));
+        let agent = AgentBuilder::new(model.clone()).build();
+
+        let response = agent
+            .prompt("Tell me about the fox")
+            .auto_continue(1)
+            .extended_details()
+            .await
+            .unwrap();
+
+        assert_eq!(response.output, "The quick brown fox jumps");
+        assert_eq!(response.finish_reason, Some(FinishReason::Stop));
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        let Message::User { content } = requests[1].chat_history.last() else {
+            panic!("The continuation prompt should be a user message");
+        };
+        let UserContent::Text(text) = content.first() else {
+            panic!("The continuation prompt should be text");
+        };
+        assert_eq!(text.text, CONTINUE_PROMPT);
+
+        // The continuation prompt is kept out of the returned history
+        assert_eq!(response.messages.len(), 2);
+        let Message::Assistant { content, .. } = &response.messages[1] else {
+            panic!("The continued response should be a single assistant message");
+        };
+        assert_eq!(content.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_truncated_output_is_returned_without_auto_continue() {
+        let model = MockCompletionModel::new()
+            .response(MockResponse::text("The quick brown ").finish_reason(FinishReason::Length));
+        let agent = AgentBuilder::new(model).build();
+
+        let response = agent
+            .prompt("Tell me about the fox")
+            .extended_details()
+            .await
+            .unwrap();
+
+        assert_eq!(response.output, "The quick brown ");
+        assert!(response.finish_reason.unwrap().is_truncated());
+    }
+}
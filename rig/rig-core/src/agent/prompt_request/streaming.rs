@@ -16,8 +16,8 @@ use tracing::info_span;
 use tracing_futures::Instrument;
 
 use crate::{
-    agent::Agent,
-    completion::{CompletionError, CompletionModel, PromptError},
+    agent::{Agent, prompt_request::CONTINUE_PROMPT},
+    completion::{CompletionError, CompletionModel, FinishReason, PromptError},
     message::{Message, Text},
     tool::ToolSetError,
 };
@@ -51,6 +51,12 @@ pub enum MultiTurnStreamItem<R> {
 pub struct FinalResponse {
     response: String,
     aggregated_usage: crate::completion::Usage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<FinishReason>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
 }
 
 impl FinalResponse {
@@ -58,9 +64,27 @@ impl FinalResponse {
         Self {
             response: String::new(),
             aggregated_usage: crate::completion::Usage::new(),
+            finish_reason: None,
+            id: None,
+            model: None,
         }
     }
 
+    /// Why the model stopped generating the final response, if reported by the provider
+    pub fn finish_reason(&self) -> Option<&FinishReason> {
+        self.finish_reason.as_ref()
+    }
+
+    /// The id of the final response, if reported by the provider
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// The model that served the final response, if reported by the provider
+    pub fn model(&self) -> Option<&str> {
+        self.model.as_deref()
+    }
+
     pub fn response(&self) -> &str {
         &self.response
     }
@@ -79,6 +103,7 @@ impl<R> MultiTurnStreamItem<R> {
         Self::FinalResponse(FinalResponse {
             response: response.to_string(),
             aggregated_usage,
+            ..FinalResponse::empty()
         })
     }
 
@@ -121,6 +146,8 @@ where
     agent: Arc<Agent<M>>,
     /// Optional per-request hook for events
     hook: Option<P>,
+    /// Maximum number of automatic continuations of truncated responses
+    max_continuations: usize,
 }
 
 impl<M, P> StreamingPromptRequest<M, P>
@@ -137,6 +164,7 @@ where
             max_depth: 0,
             agent,
             hook: None,
+            max_continuations: 0,
         }
     }
 
@@ -147,6 +175,15 @@ where
         self
     }
 
+    /// Automatically ask the model to continue when a text response was cut off by the
+    /// maximum number of tokens (see [`crate::completion::FinishReason::Length`]).
+    /// The continuations are streamed as they arrive and the [`FinalResponse`] holds the
+    /// concatenated text. Continuations do not count towards the multi-turn depth.
+    pub fn auto_continue(mut self, max_continuations: usize) -> Self {
+        self.max_continuations = max_continuations;
+        self
+    }
+
     /// Add chat history to the prompt request
     pub fn with_history(mut self, history: Vec<Message>) -> Self {
         self.chat_history = Some(history);
@@ -164,6 +201,7 @@ where
             max_depth: self.max_depth,
             agent: self.agent,
             hook: Some(hook),
+            max_continuations: self.max_continuations,
         }
     }
 
@@ -201,6 +239,9 @@ where
 
         let mut last_text_response = String::new();
         let mut is_text_response = false;
+        let mut continuations = 0;
+        let mut continuing = false;
+        let mut partial_output = String::new();
         let mut max_depth_reached = false;
 
         let mut aggregated_usage = crate::completion::Usage::new();
@@ -234,9 +275,22 @@ where
                     );
                 }
 
+                // A continuation sends the truncated output so far as a single assistant message,
+                // followed by the continuation prompt. Neither is added to the chat history.
+                let mut request_history = chat_history.read().await.clone();
+                let request_prompt = if continuing {
+                    request_history.push(current_prompt.clone());
+                    request_history.push(Message::Assistant {
+                        id: None,
+                        content: OneOrMany::one(AssistantContent::text(&partial_output)),
+                    });
+                    Message::user(CONTINUE_PROMPT)
+                } else {
+                    current_prompt.clone()
+                };
+
                 if let Some(ref hook) = self.hook {
-                    let reader = chat_history.read().await;
-                    hook.on_completion_call(&current_prompt, &reader.to_vec(), cancel_signal.clone())
+                    hook.on_completion_call(&request_prompt, &request_history, cancel_signal.clone())
                         .await;
 
                     if cancel_signal.is_cancelled() {
@@ -247,9 +301,8 @@ where
                 // Calculate and emit context estimate BEFORE sending the LLM request.
                 // This allows the UI to show the estimated context usage before the request is sent.
                 {
-                    let history_snapshot = chat_history.read().await;
-                    let mut all_messages: Vec<Message> = history_snapshot.clone();
-                    all_messages.push(current_prompt.clone());
+                    let mut all_messages = request_history.clone();
+                    all_messages.push(request_prompt.clone());
 
                     let preamble = agent.preamble.as_deref().unwrap_or("");
                     // Get tool definitions from agent (serialized as JSON for estimation)
@@ -285,7 +338,7 @@ where
 
                 let mut stream = tracing::Instrument::instrument(
                     agent
-                    .stream_completion(current_prompt.clone(), (*chat_history.read().await).clone())
+                    .stream_completion(request_prompt, request_history)
                     .await?
                     .stream(), chat_stream_span
                 )
@@ -372,7 +425,7 @@ where
 
                             match tc_result {
                                 Ok(text) => {
-                                    let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: OneOrMany::one(ToolResultContent::Text(Text { text })) };
+                                    let tr = ToolResult { id: tool_call.id, call_id: tool_call.call_id, content: OneOrMany::one(ToolResultContent::Text(Text::from(text))) };
                                     yield Ok(MultiTurnStreamItem::StreamUserItem(StreamedUserContent::ToolResult(tr)));
                                 }
                                 Err(e) => {
@@ -398,6 +451,15 @@ where
                             yield Ok(MultiTurnStreamItem::stream_item(StreamedAssistantContent::ReasoningDelta { reasoning, id }));
                             did_call_tool = false;
                         },
+                        Ok(StreamedAssistantContent::Logprobs(logprobs)) => {
+                            yield Ok(MultiTurnStreamItem::stream_item(StreamedAssistantContent::Logprobs(logprobs)));
+                        },
+                        Ok(StreamedAssistantContent::HostedToolCall(call)) => {
+                            yield Ok(MultiTurnStreamItem::stream_item(StreamedAssistantContent::HostedToolCall(call)));
+                        },
+                        Ok(StreamedAssistantContent::Citation(citation)) => {
+                            yield Ok(MultiTurnStreamItem::stream_item(StreamedAssistantContent::Citation(citation)));
+                        },
                         Ok(StreamedAssistantContent::Final(final_resp)) => {
                             if let Some(usage) = final_resp.token_usage() { aggregated_usage += usage; };
                             if is_text_response {
@@ -421,11 +483,29 @@ where
                     }
                 }
 
-                // Add (parallel) tool calls to chat history
+                // Add (parallel) tool calls to chat history, preceded by the reasoning and hosted
+                // tool calls that led to them since some providers require them to be sent back
                 if !tool_calls.is_empty() {
+                    // The truncated output of a continued turn is part of the turn that led to the
+                    // tool calls, not of the final response
+                    let partial = continuing
+                        .then(|| AssistantContent::text(std::mem::take(&mut partial_output)));
+                    continuing = false;
+
+                    let content: Vec<_> = partial
+                        .into_iter()
+                        .chain(stream.choice.iter().filter(|content| {
+                            matches!(
+                                content,
+                                AssistantContent::Reasoning(_) | AssistantContent::HostedToolCall(_)
+                            )
+                        }).cloned())
+                        .chain(tool_calls.iter().cloned())
+                        .collect();
+
                     chat_history.write().await.push(Message::Assistant {
                         id: None,
-                        content: OneOrMany::many(tool_calls.clone()).expect("Impossible EmptyListError"),
+                        content: OneOrMany::many(content).expect("Impossible EmptyListError"),
                     });
                 }
 
@@ -455,12 +535,35 @@ where
                     None => unreachable!("Chat history should never be empty at this point"),
                 };
 
+                if !did_call_tool
+                    && stream.finish_reason.as_ref().is_some_and(FinishReason::is_truncated)
+                    && continuations < self.max_continuations
+                {
+                    continuations += 1;
+                    tracing::info!(
+                        "Response truncated, continuing: {}/{}",
+                        continuations,
+                        self.max_continuations
+                    );
+                    partial_output.push_str(&last_text_response);
+                    continuing = true;
+                    // Continuations do not count towards the multi-turn depth
+                    current_max_depth -= 1;
+                    continue;
+                }
+
                 if !did_call_tool {
                     let current_span = tracing::Span::current();
                     current_span.record("gen_ai.usage.input_tokens", aggregated_usage.input_tokens);
                     current_span.record("gen_ai.usage.output_tokens", aggregated_usage.output_tokens);
                     tracing::info!("Agent multi-turn stream finished");
-                    yield Ok(MultiTurnStreamItem::final_response(&last_text_response, aggregated_usage));
+                    yield Ok(MultiTurnStreamItem::FinalResponse(FinalResponse {
+                        response: format!("{partial_output}{last_text_response}"),
+                        aggregated_usage,
+                        finish_reason: stream.finish_reason.clone(),
+                        id: stream.id.clone(),
+                        model: stream.model.clone(),
+                    }));
                     break;
                 }
             }
@@ -502,7 +605,7 @@ pub async fn stream_to_stdout<R>(
     while let Some(content) = stream.next().await {
         match content {
             Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(
-                Text { text },
+                Text { text, .. },
             ))) => {
                 print!("{text}");
                 std::io::Write::flush(&mut std::io::stdout()).unwrap();
@@ -713,4 +816,109 @@ mod tests {
              This indicates that span.enter() is being used inside async_stream instead of .instrument()"
         );
     }
+
+    #[tokio::test]
+    async fn test_auto_continue_streams_truncated_output() {
+        use crate::agent::AgentBuilder;
+        use crate::testing::{MockCompletionModel, MockResponse};
+
+        let model = MockCompletionModel::new()
+            .response(MockResponse::text("The quick brown ").finish_reason(FinishReason::Length))
+            .text("fox jumps");
+        let agent = AgentBuilder::new(model.clone()).build();
+
+        let mut stream = agent
+            .stream_prompt("Tell me about the fox")
+            .auto_continue(1)
+            .await;
+
+        let mut final_response = None;
+        while let Some(item) = stream.next().await {
+            if let MultiTurnStreamItem::FinalResponse(response) = item.unwrap() {
+                final_response = Some(response);
+            }
+        }
+
+        let final_response = final_response.expect("The stream should finish");
+        assert_eq!(final_response.response(), "The quick brown fox jumps");
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[1].chat_history.last().rag_text().as_deref(),
+            Some(CONTINUE_PROMPT)
+        );
+    }
+
+    #[derive(serde::Deserialize)]
+    struct AddArgs {
+        x: i32,
+        y: i32,
+    }
+
+    struct Adder;
+
+    impl crate::tool::Tool for Adder {
+        const NAME: &'static str = "add";
+        type Error = std::convert::Infallible;
+        type Args = AddArgs;
+        type Output = i32;
+
+        async fn definition(&self, _prompt: String) -> crate::completion::ToolDefinition {
+            crate::completion::ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Add x and y".to_string(),
+                parameters: serde_json::json!({ "type": "object" }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(args.x + args.y)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_continue_then_tool_call() {
+        use crate::agent::AgentBuilder;
+        use crate::testing::{MockCompletionModel, MockResponse};
+
+        let model = MockCompletionModel::new()
+            .response(MockResponse::text("Let me add ").finish_reason(FinishReason::Length))
+            .tool_call("add", serde_json::json!({ "x": 1, "y": 2 }))
+            .text("The sum is 3");
+        let agent = AgentBuilder::new(model.clone()).tool(Adder).build();
+
+        let mut stream = agent
+            .stream_prompt("What is 1 + 2?")
+            .auto_continue(1)
+            .multi_turn(2)
+            .await;
+
+        let mut final_response = None;
+        while let Some(item) = stream.next().await {
+            if let MultiTurnStreamItem::FinalResponse(response) = item.unwrap() {
+                final_response = Some(response);
+            }
+        }
+
+        // The truncated text belongs to the tool call turn, not to the final response
+        let final_response = final_response.expect("The stream should finish");
+        assert_eq!(final_response.response(), "The sum is 3");
+
+        // The continuation is folded into the assistant message of the tool call turn
+        let requests = model.requests();
+        assert_eq!(requests.len(), 3);
+        let history = requests[2].chat_history.iter().collect::<Vec<_>>();
+        assert_eq!(history.len(), 3);
+        assert!(
+            history
+                .iter()
+                .all(|message| message.rag_text().as_deref() != Some(CONTINUE_PROMPT))
+        );
+        let Message::Assistant { content, .. } = history[1] else {
+            panic!("The tool call turn should be an assistant message");
+        };
+        assert_eq!(content.first(), AssistantContent::text("Let me add "));
+        assert!(matches!(content.last(), AssistantContent::ToolCall(_)));
+    }
 }
@@ -0,0 +1,798 @@
+//! Declarative agent definitions.
+//!
+//! An [AgentSpec] describes an agent (provider, model, preamble, sampling parameters, tools,
+//! MCP servers, dynamic context and context compression) in a configuration file, so that
+//! models and prompts can be changed without recompiling. The tools, vector indices, MCP servers
+//! and provider clients referenced by a spec are registered by name in an [AgentRegistry], which
+//! validates the spec and resolves it into an [Agent].
+//!
+//! Specs can be written in TOML, YAML or JSON. Since [AgentSpec] implements [serde::Deserialize],
+//! any other serde format can be used as well by deserializing it directly.
+//!
+//! This module requires the `agent-spec` feature.
+//!
+//! # Example
+//! ```toml
+//! name = "support"
+//! provider = "openai"
+//! model = "gpt-4o"
+//! preamble = "You are a helpful support agent."
+//! temperature = 0.3
+//! max_tokens = 1024
+//! tools = ["search_tickets"]
+//! max_context_tokens = 32000
+//!
+//! [[dynamic_context]]
+//! index = "faq"
+//! samples = 3
+//!
+//! [compressor]
+//! strategy = "sliding_window"
+//! preserve_first = 1
+//! ```
+//!
+//! ```no_run
+//! use rig::agent::spec::{AgentRegistry, AgentSpec};
+//! use rig::prelude::*;
+//! use rig::providers::openai;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let registry = AgentRegistry::new()
+//!     .provider("openai", openai::Client::from_env())
+//!     .tool(search_tickets)
+//!     .index("faq", faq_index);
+//!
+//! let spec = AgentSpec::from_path("agents/support.toml")?;
+//! let agent = registry.build(&spec)?;
+//!
+//! let answer = agent.prompt("How do I reset my password?").await?;
+//! # Ok(())
+//! # }
+//! ```
+#![allow(deprecated)]
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    agent::{Agent, AgentBuilder},
+    client::{CompletionClient, completion::CompletionModelHandle},
+    completion::{CompletionModel, GetTokenUsage, HostedTool, ReasoningConfig, SamplingParams},
+    compression::{SlidingWindowCompressor, SummarizingCompressor, TruncationCompressor},
+    message::ToolChoice,
+    tool::{Tool, ToolDyn, ToolSet, server::ToolServer},
+    vector_store::request::Filter,
+    vector_store::{TopNResults, VectorSearchRequest, VectorStoreError, VectorStoreIndexDyn},
+    wasm_compat::WasmBoxedFuture,
+};
+
+#[derive(Debug, Error)]
+pub enum AgentSpecError {
+    #[error("failed to read agent spec: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse TOML agent spec: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("failed to parse YAML agent spec: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    #[error("failed to parse JSON agent spec: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("unsupported agent spec format `{0}`, expected `toml`, `yaml` or `json`")]
+    UnsupportedFormat(String),
+    /// The spec is well-formed but invalid, e.g. it references an unknown tool. Contains one
+    /// message per problem found.
+    #[error("invalid agent spec:\n  - {}", .0.join("\n  - "))]
+    Invalid(Vec<String>),
+}
+
+/// A declarative definition of an agent. See the [module documentation](self) for an example.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AgentSpec {
+    /// Name of the agent used for logging and debugging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Description of the agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The name of the provider, as registered with [AgentRegistry::provider]
+    pub provider: String,
+    /// The model id, as passed to the provider's `completion_model`
+    pub model: String,
+    /// System prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preamble: Option<String>,
+    /// Context documents always available to the agent
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub context: Vec<String>,
+    /// Temperature of the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Maximum number of tokens for the completion
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// Common sampling parameters (top p, top k, stop sequences, seed, penalties...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingParams>,
+    /// Reasoning ("thinking") configuration of the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<ReasoningConfig>,
+    /// Tools hosted and executed by the model provider
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hosted_tools: Vec<HostedTool>,
+    /// Whether the model should be forced to use a tool
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    /// Additional provider-specific parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_params: Option<serde_json::Value>,
+    /// The model's context window size in tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    /// Tools available to the agent, by their registered name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// MCP servers whose tools are available to the agent, by their registered name
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<String>,
+    /// Vector indices queried on each prompt for additional context
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dynamic_context: Vec<DynamicContextSpec>,
+    /// Compressor applied to the chat history once it exceeds `max_context_tokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressor: Option<CompressorSpec>,
+    /// Maximum context tokens before compression
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_context_tokens: Option<usize>,
+}
+
+/// A vector index used as dynamic context.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DynamicContextSpec {
+    /// The name of the index, as registered with [AgentRegistry::index]
+    pub index: String,
+    /// The number of documents retrieved on each prompt
+    pub samples: usize,
+}
+
+/// The context compression strategy of an agent, see [crate::compression].
+///
+/// Settings left unset use the defaults of the corresponding compressor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CompressorSpec {
+    /// See [TruncationCompressor]
+    Truncation {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_preserve: Option<usize>,
+    },
+    /// See [SlidingWindowCompressor]
+    SlidingWindow {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        preserve_first: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min_recent: Option<usize>,
+    },
+    /// See [SummarizingCompressor]. The summaries are generated by the given provider and model,
+    /// which default to the ones of the agent.
+    Summarizing {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        provider: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        preserve_first: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        preserve_recent: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_summary_tokens: Option<usize>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prompt: Option<String>,
+    },
+}
+
+impl AgentSpec {
+    /// Create a spec for the given provider and model.
+    pub fn new(provider: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            model: model.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parse a spec written in TOML.
+    pub fn from_toml_str(spec: &str) -> Result<Self, AgentSpecError> {
+        Ok(toml::from_str(spec)?)
+    }
+
+    /// Parse a spec written in YAML.
+    pub fn from_yaml_str(spec: &str) -> Result<Self, AgentSpecError> {
+        Ok(serde_yaml::from_str(spec)?)
+    }
+
+    /// Parse a spec written in JSON.
+    pub fn from_json_str(spec: &str) -> Result<Self, AgentSpecError> {
+        Ok(serde_json::from_str(spec)?)
+    }
+
+    /// Load a spec from a `.toml`, `.yaml`, `.yml` or `.json` file.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, AgentSpecError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match extension.as_str() {
+            "toml" => Self::from_toml_str(&std::fs::read_to_string(path)?),
+            "yaml" | "yml" => Self::from_yaml_str(&std::fs::read_to_string(path)?),
+            "json" => Self::from_json_str(&std::fs::read_to_string(path)?),
+            _ => Err(AgentSpecError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    /// Check the values of the spec, independently of any registry.
+    pub fn validate(&self) -> Result<(), AgentSpecError> {
+        into_result(self.problems())
+    }
+
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if self.provider.trim().is_empty() {
+            problems.push("`provider` must not be empty".to_string());
+        }
+        if self.model.trim().is_empty() {
+            problems.push("`model` must not be empty".to_string());
+        }
+        if let Some(temperature) = self.temperature
+            && !(0.0..=2.0).contains(&temperature)
+        {
+            problems.push(format!(
+                "`temperature` must be between 0 and 2, got {temperature}"
+            ));
+        }
+        if self.max_tokens == Some(0) {
+            problems.push("`max_tokens` must be greater than 0".to_string());
+        }
+        if let Some(top_p) = self.sampling.as_ref().and_then(|sampling| sampling.top_p)
+            && !(0.0..=1.0).contains(&top_p)
+        {
+            problems.push(format!(
+                "`sampling.top_p` must be between 0 and 1, got {top_p}"
+            ));
+        }
+
+        for (i, tool) in self.tools.iter().enumerate() {
+            if self.tools[..i].contains(tool) {
+                problems.push(format!("tool `{tool}` is listed more than once in `tools`"));
+            }
+        }
+        for (i, context) in self.dynamic_context.iter().enumerate() {
+            if context.samples == 0 {
+                problems.push(format!(
+                    "`dynamic_context[{i}].samples` must be greater than 0"
+                ));
+            }
+        }
+
+        if self.compressor.is_some() && self.max_context_tokens.is_none() {
+            problems
+                .push("`compressor` has no effect unless `max_context_tokens` is set".to_string());
+        }
+        if self.max_context_tokens == Some(0) {
+            problems.push("`max_context_tokens` must be greater than 0".to_string());
+        }
+        if let Some(CompressorSpec::Summarizing {
+            model: Some(model), ..
+        }) = &self.compressor
+            && model.trim().is_empty()
+        {
+            problems.push("`compressor.model` must not be empty".to_string());
+        }
+
+        problems
+    }
+}
+
+type ModelFactory = Arc<dyn Fn(&str) -> CompletionModelHandle<'static> + Send + Sync>;
+type ToolFactory = Arc<dyn Fn() -> Box<dyn ToolDyn> + Send + Sync>;
+type SharedIndex = Arc<dyn VectorStoreIndexDyn + Send + Sync>;
+
+/// The providers, tools, vector indices and MCP servers that [AgentSpec]s can reference by name.
+///
+/// # Example
+/// ```no_run
+/// use rig::agent::spec::{AgentRegistry, AgentSpec};
+/// use rig::providers::{anthropic, openai};
+///
+/// let registry = AgentRegistry::new()
+///     .provider("openai", openai::Client::from_env())
+///     .provider("anthropic", anthropic::Client::from_env());
+///
+/// let spec = AgentSpec::from_toml_str(r#"
+///     provider = "anthropic"
+///     model = "claude-sonnet-4-0"
+///     preamble = "You are a comedian."
+/// "#)?;
+///
+/// let agent = registry.build(&spec)?;
+/// # Ok::<(), rig::agent::spec::AgentSpecError>(())
+/// ```
+#[derive(Clone, Default)]
+pub struct AgentRegistry {
+    providers: HashMap<String, ModelFactory>,
+    tools: HashMap<String, ToolFactory>,
+    indices: HashMap<String, SharedIndex>,
+    #[cfg(feature = "rmcp")]
+    mcp_servers: HashMap<String, (Vec<rmcp::model::Tool>, rmcp::service::ServerSink)>,
+}
+
+impl AgentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a provider client under the given name.
+    pub fn provider<C, R>(self, name: impl Into<String>, client: C) -> Self
+    where
+        C: CompletionClient + Send + Sync + 'static,
+        C::CompletionModel: CompletionModel<StreamingResponse = R> + 'static,
+        R: Clone + Unpin + GetTokenUsage + 'static,
+    {
+        self.completion_model_fn(name, move |model| client.completion_model(model))
+    }
+
+    /// Register a function creating completion models from a model id under the given provider
+    /// name. Useful for models that are not created from a [CompletionClient].
+    pub fn completion_model_fn<M, R, F>(mut self, name: impl Into<String>, make_model: F) -> Self
+    where
+        F: Fn(&str) -> M + Send + Sync + 'static,
+        M: CompletionModel<StreamingResponse = R> + 'static,
+        R: Clone + Unpin + GetTokenUsage + 'static,
+    {
+        let factory: ModelFactory =
+            Arc::new(move |model| CompletionModelHandle::new(Arc::new(make_model(model))));
+        self.providers.insert(name.into(), factory);
+        self
+    }
+
+    /// Register a tool under its name. Each agent built from the registry gets its own clone of
+    /// the tool.
+    pub fn tool<T>(mut self, tool: T) -> Self
+    where
+        T: Tool + Clone + Send + Sync + 'static,
+    {
+        let name = tool.name();
+        let factory: ToolFactory = Arc::new(move || Box::new(tool.clone()));
+        self.tools.insert(name, factory);
+        self
+    }
+
+    /// Register a vector index under the given name, to be used as dynamic context.
+    pub fn index(
+        mut self,
+        name: impl Into<String>,
+        index: impl VectorStoreIndexDyn + Send + Sync + 'static,
+    ) -> Self {
+        self.indices.insert(name.into(), Arc::new(index));
+        self
+    }
+
+    /// Register the tools of an MCP server (from `rmcp`) under the given name.
+    #[cfg(feature = "rmcp")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "rmcp")))]
+    pub fn mcp_server(
+        mut self,
+        name: impl Into<String>,
+        tools: Vec<rmcp::model::Tool>,
+        client: rmcp::service::ServerSink,
+    ) -> Self {
+        self.mcp_servers.insert(name.into(), (tools, client));
+        self
+    }
+
+    /// Check the spec and that everything it references is registered. All the problems found
+    /// are reported at once.
+    pub fn validate(&self, spec: &AgentSpec) -> Result<(), AgentSpecError> {
+        let mut problems = spec.problems();
+
+        if !spec.provider.trim().is_empty() && !self.providers.contains_key(&spec.provider) {
+            problems.push(unknown(
+                "provider",
+                &spec.provider,
+                "`provider`",
+                self.providers.keys(),
+            ));
+        }
+        for tool in &spec.tools {
+            if !self.tools.contains_key(tool) {
+                problems.push(unknown("tool", tool, "`tools`", self.tools.keys()));
+            }
+        }
+        for server in &spec.mcp_servers {
+            #[cfg(feature = "rmcp")]
+            let registered = self.mcp_servers.keys();
+            #[cfg(not(feature = "rmcp"))]
+            let registered = std::iter::empty::<&String>();
+
+            if !registered.clone().any(|name| name == server) {
+                problems.push(unknown("MCP server", server, "`mcp_servers`", registered));
+            }
+        }
+        for (i, context) in spec.dynamic_context.iter().enumerate() {
+            if !self.indices.contains_key(&context.index) {
+                problems.push(unknown(
+                    "index",
+                    &context.index,
+                    &format!("`dynamic_context[{i}].index`"),
+                    self.indices.keys(),
+                ));
+            }
+        }
+        if let Some(CompressorSpec::Summarizing {
+            provider: Some(provider),
+            ..
+        }) = &spec.compressor
+            && !self.providers.contains_key(provider)
+        {
+            problems.push(unknown(
+                "provider",
+                provider,
+                "`compressor.provider`",
+                self.providers.keys(),
+            ));
+        }
+
+        into_result(problems)
+    }
+
+    /// Validate the spec and build the agent it describes.
+    ///
+    /// Must be called within a tokio runtime, as the agent's tool server is started.
+    pub fn build(
+        &self,
+        spec: &AgentSpec,
+    ) -> Result<Agent<CompletionModelHandle<'static>>, AgentSpecError> {
+        self.validate(spec)?;
+
+        let mut builder = AgentBuilder::new(self.providers[&spec.provider](&spec.model));
+
+        if let Some(name) = &spec.name {
+            builder = builder.name(name);
+        }
+        if let Some(description) = &spec.description {
+            builder = builder.description(description);
+        }
+        if let Some(preamble) = &spec.preamble {
+            builder = builder.preamble(preamble);
+        }
+        for doc in &spec.context {
+            builder = builder.context(doc);
+        }
+        if let Some(temperature) = spec.temperature {
+            builder = builder.temperature(temperature);
+        }
+        if let Some(max_tokens) = spec.max_tokens {
+            builder = builder.max_tokens(max_tokens);
+        }
+        if let Some(sampling) = &spec.sampling {
+            builder = builder.sampling(sampling.clone());
+        }
+        if let Some(reasoning) = &spec.reasoning {
+            builder = builder.reasoning(reasoning.clone());
+        }
+        for tool in &spec.hosted_tools {
+            builder = builder.hosted_tool(tool.clone());
+        }
+        if let Some(tool_choice) = &spec.tool_choice {
+            builder = builder.tool_choice(tool_choice.clone());
+        }
+        if let Some(params) = &spec.additional_params {
+            builder = builder.additional_params(params.clone());
+        }
+        if let Some(context_window) = spec.context_window {
+            builder = builder.context_window(context_window);
+        }
+        for context in &spec.dynamic_context {
+            builder = builder.dynamic_context(
+                context.samples,
+                RegisteredIndex(self.indices[&context.index].clone()),
+            );
+        }
+        if let Some(compressor) = &spec.compressor {
+            builder = self.with_compressor(builder, spec, compressor);
+        }
+        if let Some(tokens) = spec.max_context_tokens {
+            builder = builder.max_context_tokens(tokens);
+        }
+
+        let mut toolset = ToolSet::default();
+        let mut tool_names = Vec::new();
+        for tool in &spec.tools {
+            let tool = self.tools[tool]();
+            tool_names.push(tool.name());
+            toolset.add_tool_boxed(tool);
+        }
+        #[cfg(feature = "rmcp")]
+        for server in &spec.mcp_servers {
+            let (tools, client) = &self.mcp_servers[server];
+            for tool in tools {
+                tool_names.push(tool.name.to_string());
+                toolset.add_tool(crate::tool::rmcp::McpTool::from_mcp_server(
+                    tool.clone(),
+                    client.clone(),
+                ));
+            }
+        }
+
+        let tool_server_handle = ToolServer::new()
+            .static_tool_names(tool_names)
+            .add_tools(toolset)
+            .run();
+
+        Ok(builder.tool_server_handle(tool_server_handle).build())
+    }
+
+    fn with_compressor(
+        &self,
+        builder: AgentBuilder<CompletionModelHandle<'static>>,
+        spec: &AgentSpec,
+        compressor: &CompressorSpec,
+    ) -> AgentBuilder<CompletionModelHandle<'static>> {
+        match compressor {
+            CompressorSpec::Truncation { min_preserve } => {
+                let mut compressor = TruncationCompressor::new();
+                if let Some(count) = *min_preserve {
+                    compressor = compressor.with_min_preserve(count);
+                }
+                builder.context_compressor(compressor)
+            }
+            CompressorSpec::SlidingWindow {
+                preserve_first,
+                min_recent,
+            } => {
+                let mut compressor = SlidingWindowCompressor::new();
+                if let Some(count) = *preserve_first {
+                    compressor = compressor.with_preserve_first(count);
+                }
+                if let Some(count) = *min_recent {
+                    compressor = compressor.with_min_recent(count);
+                }
+                builder.context_compressor(compressor)
+            }
+            CompressorSpec::Summarizing {
+                provider,
+                model,
+                preserve_first,
+                preserve_recent,
+                max_summary_tokens,
+                prompt,
+            } => {
+                let provider = provider.as_ref().unwrap_or(&spec.provider);
+                let model = model.as_ref().unwrap_or(&spec.model);
+                let summarizer = AgentBuilder::new(self.providers[provider](model)).build();
+
+                let mut compressor = SummarizingCompressor::new(summarizer);
+                if let Some(count) = *preserve_first {
+                    compressor = compressor.with_preserve_first(count);
+                }
+                if let Some(count) = *preserve_recent {
+                    compressor = compressor.with_preserve_recent(count);
+                }
+                if let Some(tokens) = *max_summary_tokens {
+                    compressor = compressor.with_max_summary_tokens(tokens);
+                }
+                if let Some(prompt) = prompt {
+                    compressor = compressor.with_custom_prompt(prompt.clone());
+                }
+                builder.context_compressor(compressor)
+            }
+        }
+    }
+}
+
+fn unknown<'a>(
+    kind: &str,
+    name: &str,
+    field: &str,
+    registered: impl Iterator<Item = &'a String>,
+) -> String {
+    let mut registered = registered
+        .map(|name| format!("`{name}`"))
+        .collect::<Vec<_>>();
+    registered.sort();
+
+    if registered.is_empty() {
+        format!("unknown {kind} `{name}` in {field}: no {kind} is registered")
+    } else {
+        format!(
+            "unknown {kind} `{name}` in {field}, registered: {}",
+            registered.join(", ")
+        )
+    }
+}
+
+fn into_result(problems: Vec<String>) -> Result<(), AgentSpecError> {
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AgentSpecError::Invalid(problems))
+    }
+}
+
+/// A vector index shared between the agents built from a registry.
+struct RegisteredIndex(SharedIndex);
+
+impl VectorStoreIndexDyn for RegisteredIndex {
+    fn top_n<'a>(
+        &'a self,
+        req: VectorSearchRequest<Filter<serde_json::Value>>,
+    ) -> WasmBoxedFuture<'a, TopNResults> {
+        self.0.top_n(req)
+    }
+
+    fn top_n_ids<'a>(
+        &'a self,
+        req: VectorSearchRequest<Filter<serde_json::Value>>,
+    ) -> WasmBoxedFuture<'a, Result<Vec<(f64, String)>, VectorStoreError>> {
+        self.0.top_n_ids(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        completion::{Prompt, ToolDefinition},
+        testing::MockCompletionModel,
+    };
+    use assert_fs::prelude::*;
+    use serde_json::json;
+
+    #[derive(Clone)]
+    struct Echo;
+
+    impl Tool for Echo {
+        const NAME: &'static str = "echo";
+        type Error = std::convert::Infallible;
+        type Args = serde_json::Value;
+        type Output = serde_json::Value;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Echo the arguments".to_string(),
+                parameters: json!({ "type": "object" }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(args)
+        }
+    }
+
+    const SPEC: &str = r#"
+        name = "support"
+        provider = "mock"
+        model = "mock-large"
+        preamble = "You are a helpful assistant."
+        temperature = 0.3
+        max_tokens = 512
+        tools = ["echo"]
+        max_context_tokens = 4000
+
+        [sampling]
+        top_p = 0.9
+
+        [additional_params]
+        user = "ops"
+
+        [compressor]
+        strategy = "sliding_window"
+        preserve_first = 1
+    "#;
+
+    #[test]
+    fn test_parse_formats() {
+        let spec = AgentSpec::from_toml_str(SPEC).unwrap();
+
+        assert_eq!(spec.name.as_deref(), Some("support"));
+        assert_eq!(spec.model, "mock-large");
+        assert_eq!(spec.tools, vec!["echo".to_string()]);
+        assert_eq!(spec.additional_params, Some(json!({ "user": "ops" })));
+        assert_eq!(
+            spec.compressor,
+            Some(CompressorSpec::SlidingWindow {
+                preserve_first: Some(1),
+                min_recent: None,
+            })
+        );
+
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(AgentSpec::from_json_str(&json).unwrap(), spec);
+
+        let yaml = serde_yaml::to_string(&spec).unwrap();
+        let file = assert_fs::NamedTempFile::new("support.yml").unwrap();
+        file.write_str(&yaml).unwrap();
+        assert_eq!(AgentSpec::from_path(file.path()).unwrap(), spec);
+
+        // Typos are rejected rather than silently ignored
+        let err = AgentSpec::from_toml_str("provider = \"mock\"\nmodel = \"m\"\ntemprature = 1.0")
+            .unwrap_err();
+        assert!(err.to_string().contains("temprature"), "{err}");
+    }
+
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let registry = AgentRegistry::new()
+            .completion_model_fn("mock", |_| MockCompletionModel::new())
+            .tool(Echo);
+
+        let spec = AgentSpec {
+            temperature: Some(3.5),
+            tools: vec!["echo".into(), "serch".into()],
+            dynamic_context: vec![DynamicContextSpec {
+                index: "faq".into(),
+                samples: 2,
+            }],
+            compressor: Some(CompressorSpec::Truncation { min_preserve: None }),
+            ..AgentSpec::new("mok", "mock-large")
+        };
+
+        let Err(AgentSpecError::Invalid(problems)) = registry.validate(&spec) else {
+            panic!("the spec should be invalid");
+        };
+
+        assert_eq!(
+            problems,
+            vec![
+                "`temperature` must be between 0 and 2, got 3.5".to_string(),
+                "`compressor` has no effect unless `max_context_tokens` is set".to_string(),
+                "unknown provider `mok` in `provider`, registered: `mock`".to_string(),
+                "unknown tool `serch` in `tools`, registered: `echo`".to_string(),
+                "unknown index `faq` in `dynamic_context[0].index`: no index is registered"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_agent_from_spec() {
+        let model = MockCompletionModel::new().text("Hello!");
+        let registry = AgentRegistry::new()
+            .completion_model_fn("mock", {
+                let model = model.clone();
+                move |_| model.clone()
+            })
+            .tool(Echo);
+
+        let agent = registry
+            .build(&AgentSpec::from_toml_str(SPEC).unwrap())
+            .unwrap();
+
+        assert_eq!(agent.name.as_deref(), Some("support"));
+        assert_eq!(agent.max_context_tokens, Some(4000));
+        assert!(agent.context_compressor.is_some());
+
+        let response = agent.prompt("Hi").await.unwrap();
+        assert_eq!(response, "Hello!");
+
+        let request = &model.requests()[0];
+        assert_eq!(
+            request.preamble.as_deref(),
+            Some("You are a helpful assistant.")
+        );
+        assert_eq!(request.temperature, Some(0.3));
+        assert_eq!(request.max_tokens, Some(512));
+        assert_eq!(request.sampling.top_p, Some(0.9));
+        assert_eq!(request.tools.len(), 1);
+        assert_eq!(request.tools[0].name, "echo");
+    }
+}
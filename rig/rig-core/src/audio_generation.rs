@@ -6,9 +6,10 @@ use crate::{
     http_client,
     wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
-use futures::future::BoxFuture;
+use bytes::Bytes;
+use futures::{Stream, future::BoxFuture};
 use serde_json::Value;
-use std::sync::Arc;
+use std::{pin::Pin, sync::Arc};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -59,6 +60,15 @@ pub struct AudioGenerationResponse<T> {
     pub response: T,
 }
 
+/// The chunks of a streamed audio generation, in the order they are synthesised.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub type StreamingAudioGenerationResponse =
+    Pin<Box<dyn Stream<Item = Result<Bytes, AudioGenerationError>> + Send>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub type StreamingAudioGenerationResponse =
+    Pin<Box<dyn Stream<Item = Result<Bytes, AudioGenerationError>>>>;
+
 pub trait AudioGenerationModel: Sized + Clone + WasmCompatSend + WasmCompatSync {
     type Response: Send + Sync;
 
@@ -73,6 +83,24 @@ pub trait AudioGenerationModel: Sized + Clone + WasmCompatSend + WasmCompatSync
         Output = Result<AudioGenerationResponse<Self::Response>, AudioGenerationError>,
     > + Send;
 
+    /// Generates audio, yielding the chunks of audio as they are synthesised, which lets playback
+    /// start before the whole audio is generated.
+    /// Models without streaming support yield the whole audio as a single chunk.
+    fn stream_audio_generation(
+        &self,
+        request: AudioGenerationRequest,
+    ) -> impl std::future::Future<
+        Output = Result<StreamingAudioGenerationResponse, AudioGenerationError>,
+    > + Send {
+        async move {
+            let response = self.audio_generation(request).await?;
+            let chunk = Bytes::from(response.audio);
+
+            Ok(Box::pin(futures::stream::once(async move { Ok(chunk) }))
+                as StreamingAudioGenerationResponse)
+        }
+    }
+
     fn audio_generation_request(&self) -> AudioGenerationRequestBuilder<Self> {
         AudioGenerationRequestBuilder::new(self.clone())
     }
@@ -194,4 +222,12 @@ where
 
         model.audio_generation(self.build()).await
     }
+
+    /// Sends the request, streaming the chunks of audio as they are synthesised (see
+    /// [AudioGenerationModel::stream_audio_generation]).
+    pub async fn stream(self) -> Result<StreamingAudioGenerationResponse, AudioGenerationError> {
+        let model = self.model.clone();
+
+        model.stream_audio_generation(self.build()).await
+    }
 }
@@ -0,0 +1,378 @@
+//! This module provides functionality for working with provider batch APIs.
+//!
+//! Batch APIs process many requests asynchronously (usually within 24 hours) at a discount,
+//! which makes them a good fit for offline workloads such as scoring eval datasets or embedding
+//! large corpora. Each request of a batch is identified by a custom id, used to match the
+//! results (which are not returned in order) with the requests.
+//!
+//! # Example
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use futures::StreamExt;
+//! use rig::batch::{BatchClient, BatchItem, CompletionBatchModel};
+//! use rig::completion::CompletionModel;
+//! use rig::prelude::*;
+//! use rig::providers::anthropic;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = anthropic::Client::from_env();
+//! let model = client.completion_model(anthropic::completion::CLAUDE_3_5_HAIKU);
+//!
+//! let requests = ["What is 1 + 1?", "What is 2 + 2?"]
+//!     .into_iter()
+//!     .enumerate()
+//!     .map(|(i, prompt)| {
+//!         let request = model.completion_request(prompt).max_tokens(100).build();
+//!         BatchItem::new(format!("question-{i}"), request)
+//!     })
+//!     .collect();
+//!
+//! let batch = model.submit_batch(requests).await?;
+//! let batch = client.wait_for_batch(&batch.id, Duration::from_secs(60)).await?;
+//!
+//! let mut results = model.batch_results(&batch).await?;
+//! while let Some(result) = results.next().await {
+//!     let result = result?;
+//!     println!("{}: {:?}", result.custom_id, result.result.map(|response| response.choice));
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{pin::Pin, time::Duration};
+
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{
+    client::{Client, Provider},
+    completion::{CompletionError, CompletionModel, CompletionRequest, CompletionResponse},
+    embeddings::EmbeddingModel,
+    files::FilesError,
+    http_client::{self, HttpClientExt},
+    providers::anthropic::decoders::jsonl::{JSONLDecoder, JSONLDecoderError},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+// Errors
+#[derive(Debug, Error)]
+pub enum BatchError {
+    /// Http error (e.g.: connection error, timeout, etc.)
+    #[error("HttpError: {0}")]
+    HttpError(#[from] http_client::Error),
+
+    /// Json error (e.g.: serialization, deserialization)
+    #[error("JsonError: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Error converting a request of the batch to the provider's format
+    #[error("RequestError: {0}")]
+    RequestError(#[from] CompletionError),
+
+    /// Error uploading the input file of the batch
+    #[error("FilesError: {0}")]
+    FilesError(#[from] FilesError),
+
+    /// The results of the batch are not available (yet)
+    #[error("ResultsUnavailable: {0}")]
+    ResultsUnavailable(String),
+
+    /// Error returned by the provider
+    #[error("ProviderError: {0}")]
+    ProviderError(String),
+}
+
+impl From<JSONLDecoderError> for BatchError {
+    fn from(err: JSONLDecoderError) -> Self {
+        match err {
+            JSONLDecoderError::ParseError(err) => BatchError::JsonError(err),
+            JSONLDecoderError::NoBodyError => {
+                BatchError::ProviderError("Response has no body".into())
+            }
+        }
+    }
+}
+
+/// A request of a batch, identified by a custom id.
+#[derive(Debug, Clone)]
+pub struct BatchItem<T> {
+    /// The id used to match the result with the request, unique within the batch
+    pub custom_id: String,
+    pub request: T,
+}
+
+impl<T> BatchItem<T> {
+    pub fn new(custom_id: impl Into<String>, request: T) -> Self {
+        Self {
+            custom_id: custom_id.into(),
+            request,
+        }
+    }
+}
+
+/// The processing status of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchStatus {
+    /// The input of the batch is being validated
+    Validating,
+    InProgress,
+    /// The batch was processed and its results are being prepared
+    Finalizing,
+    /// The batch was processed, its results are available
+    Completed,
+    /// The batch could not be processed, e.g. because its input is invalid
+    Failed,
+    /// The batch was not processed in time, the results of the processed requests are available
+    Expired,
+    Cancelling,
+    /// The batch was cancelled, the results of the processed requests are available
+    Cancelled,
+}
+
+impl BatchStatus {
+    /// Whether the batch is done processing (successfully or not).
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            Self::Completed | Self::Failed | Self::Expired | Self::Cancelled
+        )
+    }
+}
+
+/// The number of requests of a batch, by outcome.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRequestCounts {
+    pub total: u64,
+    pub succeeded: u64,
+    /// The requests that errored, expired or were cancelled
+    pub failed: u64,
+}
+
+/// A batch, as returned by the provider when it is submitted or retrieved.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    pub status: BatchStatus,
+    pub request_counts: BatchRequestCounts,
+    /// The batch object returned by the provider
+    pub raw: serde_json::Value,
+}
+
+/// Why a request of a batch did not produce a result.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum BatchItemError {
+    #[error("request failed: {0}")]
+    Failed(String),
+    #[error("request was cancelled")]
+    Cancelled,
+    #[error("request expired")]
+    Expired,
+}
+
+/// The result of a request of a batch.
+#[derive(Debug, Clone)]
+pub struct BatchItemResult<T> {
+    pub custom_id: String,
+    pub result: Result<T, BatchItemError>,
+}
+
+/// The results of a batch, in no particular order.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub type BatchResults<T> =
+    Pin<Box<dyn Stream<Item = Result<BatchItemResult<T>, BatchError>> + Send>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub type BatchResults<T> = Pin<Box<dyn Stream<Item = Result<BatchItemResult<T>, BatchError>>>>;
+
+/// A provider client that can retrieve and cancel batches.
+pub trait BatchClient {
+    /// Retrieve the current state of the batch with the given id.
+    fn retrieve_batch(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Batch, BatchError>> + WasmCompatSend;
+
+    /// Cancel the batch with the given id. Requests that were already processed are not
+    /// cancelled and their results stay available.
+    fn cancel_batch(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<Batch, BatchError>> + WasmCompatSend;
+
+    /// Poll the batch with the given id every `poll_interval` until it is done processing.
+    fn wait_for_batch(
+        &self,
+        id: &str,
+        poll_interval: Duration,
+    ) -> impl Future<Output = Result<Batch, BatchError>> + WasmCompatSend
+    where
+        Self: WasmCompatSync,
+    {
+        async move {
+            loop {
+                let batch = self.retrieve_batch(id).await?;
+                if batch.status.is_terminal() {
+                    return Ok(batch);
+                }
+
+                tracing::debug!(target: "rig::batch", "Batch {id} is {:?}", batch.status);
+                futures_timer::Delay::new(poll_interval).await;
+            }
+        }
+    }
+}
+
+/// A completion model whose requests can be submitted as a batch.
+pub trait CompletionBatchModel: CompletionModel {
+    /// Submit the requests as a batch, to be processed by this model.
+    fn submit_batch(
+        &self,
+        requests: Vec<BatchItem<CompletionRequest>>,
+    ) -> impl Future<Output = Result<Batch, BatchError>> + WasmCompatSend;
+
+    /// Stream the results of a batch, decoded as they are downloaded.
+    fn batch_results(
+        &self,
+        batch: &Batch,
+    ) -> impl Future<Output = Result<BatchResults<CompletionResponse<Self::Response>>, BatchError>>
+    + WasmCompatSend;
+}
+
+/// An embedding model whose requests can be submitted as a batch.
+///
+/// Each request embeds a list of documents. Its result contains the embedding vectors, in the
+/// order of the documents.
+pub trait EmbeddingBatchModel: EmbeddingModel {
+    /// Submit the requests as a batch, to be processed by this model.
+    fn submit_embedding_batch(
+        &self,
+        requests: Vec<BatchItem<Vec<String>>>,
+    ) -> impl Future<Output = Result<Batch, BatchError>> + WasmCompatSend;
+
+    /// Stream the results of an embeddings batch, decoded as they are downloaded.
+    fn embedding_batch_results(
+        &self,
+        batch: &Batch,
+    ) -> impl Future<Output = Result<BatchResults<Vec<Vec<f64>>>, BatchError>> + WasmCompatSend;
+}
+
+impl<Ext, H> Client<Ext, H>
+where
+    H: HttpClientExt + 'static,
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+{
+    /// Send a request to a batch endpoint and deserialize its response.
+    pub(crate) async fn batch_response<B, T>(
+        &self,
+        req: http_client::Request<B>,
+    ) -> Result<T, BatchError>
+    where
+        B: Into<bytes::Bytes> + WasmCompatSend,
+        T: DeserializeOwned,
+    {
+        let response = self.send(req).await?;
+        let status = response.status();
+        let text = http_client::text(response).await?;
+
+        if !status.is_success() {
+            return Err(BatchError::ProviderError(format!(
+                "Failed with '{status}': {text}"
+            )));
+        }
+
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Download a JSONL file of batch results, decoding it line by line.
+    pub(crate) async fn batch_results_jsonl<T>(
+        &self,
+        path: &str,
+    ) -> Result<impl Stream<Item = Result<T, BatchError>> + use<Ext, H, T>, BatchError>
+    where
+        T: DeserializeOwned + Unpin,
+    {
+        let req = self
+            .get(path)?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        let response = self.send_streaming(req).await?;
+
+        Ok(decode_jsonl(response))
+    }
+}
+
+/// Decodes a JSONL response body line by line.
+pub(crate) fn decode_jsonl<T>(
+    response: http_client::StreamingResponse,
+) -> impl Stream<Item = Result<T, BatchError>>
+where
+    T: DeserializeOwned + Unpin,
+{
+    let bytes = response.into_body().map(|chunk| {
+        chunk
+            .map(|bytes| bytes.to_vec())
+            .map_err(std::io::Error::other)
+    });
+
+    JSONLDecoder::new(bytes).map(|line| line.map_err(BatchError::from))
+}
+
+/// Serializes the lines of a JSONL file.
+pub(crate) fn encode_jsonl<T: Serialize>(lines: &[T]) -> Result<Vec<u8>, BatchError> {
+    let mut bytes = Vec::new();
+    for line in lines {
+        serde_json::to_writer(&mut bytes, line)?;
+        bytes.push(b'\n');
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+
+    #[tokio::test]
+    async fn test_decode_jsonl_across_chunks() {
+        let chunks: Vec<Result<Bytes, http_client::Error>> = vec![
+            Ok(Bytes::from_static(b"{\"custom_id\":\"a\"}\n{\"cust")),
+            Ok(Bytes::from_static(
+                b"om_id\":\"b\"}\n\n{\"custom_id\":\"c\"}",
+            )),
+        ];
+        let body: http_client::sse::BoxedStream = Box::pin(futures::stream::iter(chunks));
+        let response = http::Response::new(body);
+
+        let lines: Vec<serde_json::Value> = decode_jsonl(response)
+            .map(|line| line.unwrap())
+            .collect()
+            .await;
+
+        assert_eq!(
+            lines,
+            vec![
+                serde_json::json!({ "custom_id": "a" }),
+                serde_json::json!({ "custom_id": "b" }),
+                serde_json::json!({ "custom_id": "c" }),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_encode_jsonl() {
+        let bytes = encode_jsonl(&[
+            serde_json::json!({ "custom_id": "a" }),
+            serde_json::json!({ "custom_id": "b" }),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "{\"custom_id\":\"a\"}\n{\"custom_id\":\"b\"}\n"
+        );
+    }
+}
@@ -529,9 +529,13 @@ impl DynClientBuilder {
         let request = CompletionRequest {
             preamble: None,
             tools: vec![],
+            hosted_tools: Vec::new(),
             documents: vec![],
             temperature: None,
             max_tokens: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
             tool_choice: None,
             chat_history: crate::OneOrMany::one(prompt.into()),
@@ -558,9 +562,13 @@ impl DynClientBuilder {
         let request = CompletionRequest {
             preamble: None,
             tools: vec![],
+            hosted_tools: Vec::new(),
             documents: vec![],
             temperature: None,
             max_tokens: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
             tool_choice: None,
             chat_history: OneOrMany::many(history)
@@ -7,6 +7,7 @@ pub mod builder;
 pub mod completion;
 pub mod embeddings;
 pub mod image_generation;
+pub mod model_listing;
 pub mod transcription;
 pub mod verify;
 
@@ -14,6 +15,7 @@ use bytes::Bytes;
 pub use completion::CompletionClient;
 pub use embeddings::EmbeddingsClient;
 use http::{HeaderMap, HeaderName, HeaderValue};
+pub use model_listing::{Modality, ModelInfo, ModelListingClient, ModelListingError};
 use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, marker::PhantomData, sync::Arc};
 use thiserror::Error;
@@ -169,10 +171,12 @@ where
     }
 }
 
+#[non_exhaustive]
 pub enum Transport {
     Http,
     Sse,
     NdJson,
+    WebSocket,
 }
 
 /// An API provider extension, this abstracts over extensions which may be use in conjunction with
@@ -267,6 +271,12 @@ impl<Ext, H> Client<Ext, H> {
         &self.ext
     }
 
+    /// The underlying HTTP client, for requests that must not go through the JSON defaults of
+    /// [HttpClientExt::send] on [Client] (e.g. raw file uploads).
+    pub(crate) fn http_client(&self) -> &H {
+        &self.http_client
+    }
+
     pub fn with_ext<NewExt>(self, new_ext: NewExt) -> Client<NewExt, H> {
         Client {
             base_url: self.base_url,
@@ -397,6 +407,47 @@ where
 
         self.ext.with_custom(req)
     }
+
+    /// A request to open a WebSocket connection (see `http_client::websocket::connect`, behind the
+    /// `realtime` feature), the `http(s)` scheme of the base URL being replaced with `ws(s)`.
+    pub fn websocket<S>(&self, path: S) -> http_client::Result<Builder>
+    where
+        S: AsRef<str>,
+    {
+        let uri = self
+            .ext
+            .build_uri(&self.base_url, path.as_ref(), Transport::WebSocket);
+        let uri = match uri.split_once("://") {
+            Some(("https", rest)) => format!("wss://{rest}"),
+            Some(("http", rest)) => format!("ws://{rest}"),
+            _ => uri,
+        };
+
+        let mut req = Request::get(uri);
+
+        if let Some(hs) = req.headers_mut() {
+            hs.extend(self.headers.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        self.ext.with_custom(req)
+    }
+
+    pub fn delete<S>(&self, path: S) -> http_client::Result<Builder>
+    where
+        S: AsRef<str>,
+    {
+        let uri = self
+            .ext
+            .build_uri(&self.base_url, path.as_ref(), Transport::Http);
+
+        let mut req = Request::delete(uri);
+
+        if let Some(hs) = req.headers_mut() {
+            hs.extend(self.headers.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        self.ext.with_custom(req)
+    }
 }
 
 impl<Ext, H> VerifyClient for Client<Ext, H>
@@ -0,0 +1,314 @@
+//! Model discovery: listing the models served by a provider with their (normalized) metadata.
+//!
+//! The metadata returned by providers vary a lot: some only return the model ids, others their
+//! context window, modalities or supported features. Fields not returned by a provider are left
+//! empty (`None`) rather than guessed.
+
+use crate::{
+    agent::AgentBuilder,
+    client::{Client, CompletionClient, Provider},
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ModelListingError {
+    #[error("provider error: {0}")]
+    ProviderError(String),
+    #[error("http error: {0}")]
+    HttpError(
+        #[from]
+        #[source]
+        http_client::Error,
+    ),
+    #[error("json error: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// An input or output modality of a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Modality {
+    Text,
+    Image,
+    Audio,
+    Video,
+    File,
+}
+
+impl Modality {
+    /// Parses the modality names used by providers, e.g. `"text"` or `"image"`.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "image" | "images" | "vision" => Some(Self::Image),
+            "audio" => Some(Self::Audio),
+            "video" => Some(Self::Video),
+            "file" | "pdf" => Some(Self::File),
+            _ => None,
+        }
+    }
+}
+
+/// A model served by a provider, along with its metadata.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelInfo {
+    /// The id of the model, as passed to `completion_model`, `embedding_model`, etc.
+    pub id: String,
+    /// A human readable name of the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The organization owning the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owned_by: Option<String>,
+    /// The maximum number of tokens in the context window (input and output).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window: Option<u64>,
+    /// The maximum number of tokens the model can generate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_output_tokens: Option<u64>,
+    /// The modalities accepted by the model, empty if unknown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_modalities: Vec<Modality>,
+    /// The modalities generated by the model, empty if unknown.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub output_modalities: Vec<Modality>,
+    /// Whether the model supports tool calling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_tools: Option<bool>,
+    /// Whether the model supports reasoning (thinking).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supports_reasoning: Option<bool>,
+    /// Whether the model is an embedding model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_embedding: Option<bool>,
+    /// The number of dimensions of the embeddings generated by the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_dimensions: Option<usize>,
+}
+
+impl ModelInfo {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A provider client that can list the models it serves.
+pub trait ModelListingClient {
+    /// List the models available to this client.
+    fn list_models(
+        &self,
+    ) -> impl Future<Output = Result<Vec<ModelInfo>, ModelListingError>> + WasmCompatSend;
+
+    /// Get the metadata of the given model, `None` if the provider does not serve it.
+    fn model_info(
+        &self,
+        model: &str,
+    ) -> impl Future<Output = Result<Option<ModelInfo>, ModelListingError>> + WasmCompatSend
+    where
+        Self: WasmCompatSync,
+    {
+        async move {
+            Ok(self
+                .list_models()
+                .await?
+                .into_iter()
+                .find(|info| info.id == model))
+        }
+    }
+
+    /// Create an agent builder with the given completion model, configured from the model's
+    /// metadata (e.g. its context window).
+    ///
+    /// # Example with OpenRouter
+    /// ```no_run
+    /// use rig::prelude::*;
+    /// use rig::providers::openrouter;
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = openrouter::Client::new("your-api-key")?;
+    ///
+    /// let agent = client
+    ///     .agent_with_model_info("anthropic/claude-sonnet-4")
+    ///     .await?
+    ///     .preamble("You are a helpful assistant.")
+    ///     .build();
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn agent_with_model_info(
+        &self,
+        model: impl Into<String> + WasmCompatSend,
+    ) -> impl Future<Output = Result<AgentBuilder<Self::CompletionModel>, ModelListingError>>
+    + WasmCompatSend
+    where
+        Self: CompletionClient + WasmCompatSync,
+    {
+        async move {
+            let model = model.into();
+            let info = self.model_info(&model).await?;
+            let builder = self.agent(model);
+
+            Ok(match info {
+                Some(info) => builder.model_info(&info),
+                None => builder,
+            })
+        }
+    }
+}
+
+impl<Ext, H> Client<Ext, H>
+where
+    H: HttpClientExt,
+    Ext: Provider,
+{
+    /// Send a GET request to a model listing endpoint and deserialize its response.
+    pub(crate) async fn get_models<T>(&self, path: &str) -> Result<T, ModelListingError>
+    where
+        T: DeserializeOwned,
+    {
+        let req = self
+            .get(path)?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        self.models_response(req).await
+    }
+
+    /// Send a request to a model listing endpoint and deserialize its response.
+    pub(crate) async fn models_response<B, T>(
+        &self,
+        req: http_client::Request<B>,
+    ) -> Result<T, ModelListingError>
+    where
+        B: Into<bytes::Bytes> + WasmCompatSend,
+        T: DeserializeOwned,
+    {
+        let response = self.http_client.send(req).await?;
+        let status = response.status();
+        let text = http_client::text(response).await?;
+
+        if !status.is_success() {
+            return Err(ModelListingError::ProviderError(format!(
+                "Failed with '{status}': {text}"
+            )));
+        }
+
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+/// The response of the OpenAI-compatible `/models` endpoints.
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenAIModelList {
+    pub data: Vec<OpenAIModel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenAIModel {
+    pub id: String,
+    #[serde(default)]
+    pub owned_by: Option<String>,
+}
+
+impl From<OpenAIModel> for ModelInfo {
+    fn from(model: OpenAIModel) -> Self {
+        Self {
+            owned_by: model.owned_by,
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl OpenAIModelList {
+    pub(crate) fn into_models(self) -> Vec<ModelInfo> {
+        self.data.into_iter().map(ModelInfo::from).collect()
+    }
+}
+
+/// Implements [ModelListingClient] for providers exposing an OpenAI-compatible `/models` endpoint
+/// that does not return any metadata besides the model ids.
+macro_rules! impl_openai_compatible_model_listing {
+    ($ext:ty, $path:literal) => {
+        impl<H> $crate::client::ModelListingClient for $crate::client::Client<$ext, H>
+        where
+            H: $crate::http_client::HttpClientExt
+                + $crate::wasm_compat::WasmCompatSend
+                + $crate::wasm_compat::WasmCompatSync
+                + 'static,
+        {
+            async fn list_models(
+                &self,
+            ) -> Result<Vec<$crate::client::ModelInfo>, $crate::client::ModelListingError> {
+                let models: $crate::client::model_listing::OpenAIModelList =
+                    self.get_models($path).await?;
+
+                Ok(models.into_models())
+            }
+        }
+    };
+}
+
+pub(crate) use impl_openai_compatible_model_listing;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openai_model_list() {
+        let models: OpenAIModelList = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [
+                { "id": "gpt-4o", "object": "model", "created": 1715367049, "owned_by": "system" },
+                { "id": "text-embedding-3-small", "object": "model" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            models.into_models(),
+            vec![
+                ModelInfo {
+                    owned_by: Some("system".to_string()),
+                    ..ModelInfo::new("gpt-4o")
+                },
+                ModelInfo::new("text-embedding-3-small"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_agent_builder_model_info() {
+        use crate::testing::MockCompletionModel;
+
+        let info = ModelInfo {
+            context_window: Some(128_000),
+            ..ModelInfo::new("mock")
+        };
+
+        let agent = AgentBuilder::new(MockCompletionModel::new())
+            .model_info(&info)
+            .build();
+        assert_eq!(agent.context_window, Some(128_000));
+
+        // An explicit context window is kept
+        let agent = AgentBuilder::new(MockCompletionModel::new())
+            .context_window(8_000)
+            .model_info(&info)
+            .build();
+        assert_eq!(agent.context_window, Some(8_000));
+    }
+
+    #[test]
+    fn test_modality_from_name() {
+        assert_eq!(Modality::from_name("Text"), Some(Modality::Text));
+        assert_eq!(Modality::from_name("image"), Some(Modality::Image));
+        assert_eq!(Modality::from_name("embeddings"), None);
+    }
+}
@@ -66,6 +66,7 @@ pub enum AssistantContent {
     ToolCall(ToolCall),
     Reasoning(Reasoning),
     Image(Image),
+    HostedToolCall(HostedToolCall),
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -196,6 +197,120 @@ impl ToolFunction {
     }
 }
 
+/// The kind of a tool hosted by the provider, see [crate::completion::HostedTool].
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HostedToolKind {
+    WebSearch,
+    CodeInterpreter,
+    FileSearch,
+    UrlContext,
+}
+
+impl std::fmt::Display for HostedToolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HostedToolKind::WebSearch => "web_search",
+            HostedToolKind::CodeInterpreter => "code_interpreter",
+            HostedToolKind::FileSearch => "file_search",
+            HostedToolKind::UrlContext => "url_context",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A call of a tool hosted and executed by the provider (see [crate::completion::HostedTool]),
+/// along with its result.
+///
+/// Unlike a [ToolCall], there is nothing to execute: the model has already used the result of the
+/// call when the response is returned.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct HostedToolCall {
+    pub id: String,
+    pub tool: HostedToolKind,
+    /// The input of the call: the search query, the executed code or the fetched URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input: Option<String>,
+    /// The sources found by a search, or the fetched pages
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<HostedToolSource>,
+    /// The output of the executed code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// The error reported by the provider if the call failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The call and result items returned by the provider, sent back as-is to the same provider
+    /// in the following turns of the conversation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw: Option<serde_json::Value>,
+}
+
+impl HostedToolCall {
+    pub fn new(id: impl Into<String>, tool: HostedToolKind) -> Self {
+        Self {
+            id: id.into(),
+            tool,
+            input: None,
+            sources: Vec::new(),
+            output: None,
+            error: None,
+            raw: None,
+        }
+    }
+
+    pub fn with_input(mut self, input: Option<String>) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn with_sources(mut self, sources: Vec<HostedToolSource>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    pub fn with_output(mut self, output: Option<String>) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn with_error(mut self, error: Option<String>) -> Self {
+        self.error = error;
+        self
+    }
+
+    pub fn with_raw(mut self, raw: serde_json::Value) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+}
+
+/// A source found by a hosted tool: a web page or a chunk of an uploaded file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct HostedToolSource {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The id of the file the source comes from, see [crate::files]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The relevant text of the source, when returned by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+impl HostedToolSource {
+    /// A web page source.
+    pub fn url(url: impl Into<String>, title: Option<String>) -> Self {
+        Self {
+            url: Some(url.into()),
+            title,
+            ..Default::default()
+        }
+    }
+}
+
 // ================================================================
 // Base content models
 // ================================================================
@@ -204,21 +319,107 @@ impl ToolFunction {
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Text {
     pub text: String,
+    /// The sources cited by the text, only returned by providers in assistant content
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub citations: Vec<Citation>,
 }
 
 impl Text {
     pub fn text(&self) -> &str {
         &self.text
     }
+
+    pub fn with_citations(mut self, citations: Vec<Citation>) -> Self {
+        self.citations = citations;
+        self
+    }
+
+    /// The span of the text cited by a citation, `None` when the citation applies to the whole text.
+    pub fn cited_span(&self, citation: &Citation) -> Option<&str> {
+        self.text.get(citation.span.clone()?)
+    }
 }
 
 impl std::fmt::Display for Text {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Self { text } = self;
+        let Self { text, .. } = self;
         write!(f, "{text}")
     }
 }
 
+/// A span of assistant text, grounded in one or more sources.
+///
+/// The sources are either the [crate::completion::Document]s of the request (see
+/// [CitationSource::document_id]), or the results of a hosted tool such as web search.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct Citation {
+    /// The byte range of the cited span in the text, `None` when the citation applies to the
+    /// whole text
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span: Option<std::ops::Range<usize>>,
+    pub sources: Vec<CitationSource>,
+}
+
+impl Citation {
+    pub fn new(span: Option<std::ops::Range<usize>>, sources: Vec<CitationSource>) -> Self {
+        Self { span, sources }
+    }
+
+    /// The ids of the documents of the request cited.
+    pub fn document_ids(&self) -> impl Iterator<Item = &str> {
+        self.sources
+            .iter()
+            .filter_map(|source| source.document_id.as_deref())
+    }
+
+    /// Moves the span of the citation by `offset` bytes, e.g. when its text is appended to
+    /// another text.
+    pub(crate) fn offset(mut self, offset: usize) -> Self {
+        self.span = self.span.map(|span| span.start + offset..span.end + offset);
+        self
+    }
+}
+
+/// The source of a [Citation].
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct CitationSource {
+    /// The id of the cited [crate::completion::Document], for the documents sent with the request
+    /// (including the dynamic context of agents)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// The text quoted from the source
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<String>,
+}
+
+impl CitationSource {
+    /// A document of the request.
+    pub fn document(id: impl Into<String>) -> Self {
+        Self {
+            document_id: Some(id.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A web page.
+    pub fn url(url: impl Into<String>, title: Option<String>) -> Self {
+        Self {
+            url: Some(url.into()),
+            title,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_quote(mut self, quote: Option<String>) -> Self {
+        self.quote = quote;
+        self
+    }
+}
+
 /// Image content containing image data and metadata about it.
 #[derive(Default, Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub struct Image {
@@ -268,6 +469,8 @@ pub enum DocumentSourceKind {
     Raw(Vec<u8>),
     /// A string (or a string literal).
     String(String),
+    /// The id of a file uploaded to the provider (see [crate::files]).
+    FileId(String),
     #[default]
     /// An unknown file source (there's nothing there).
     Unknown,
@@ -290,6 +493,10 @@ impl DocumentSourceKind {
         Self::String(input.into())
     }
 
+    pub fn file_id(file_id: &str) -> Self {
+        Self::FileId(file_id.into())
+    }
+
     pub fn unknown() -> Self {
         Self::Unknown
     }
@@ -308,6 +515,7 @@ impl std::fmt::Display for DocumentSourceKind {
             Self::Url(string) => write!(f, "{string}"),
             Self::Base64(string) => write!(f, "{string}"),
             Self::String(string) => write!(f, "{string}"),
+            Self::FileId(file_id) => write!(f, "<file {file_id}>"),
             Self::Raw(_) => write!(f, "<binary data>"),
             Self::Unknown => write!(f, "<unknown>"),
         }
@@ -445,7 +653,7 @@ impl Message {
         match self {
             Message::User { content } => {
                 for item in content.iter() {
-                    if let UserContent::Text(Text { text }) = item {
+                    if let UserContent::Text(Text { text, .. }) = item {
                         return Some(text.clone());
                     }
                 }
@@ -552,6 +760,15 @@ impl UserContent {
         })
     }
 
+    /// Helper to create an image from the id of a file uploaded to the provider
+    pub fn image_file_id(file_id: impl Into<String>, media_type: Option<ImageMediaType>) -> Self {
+        UserContent::Image(Image {
+            data: DocumentSourceKind::FileId(file_id.into()),
+            media_type,
+            ..Default::default()
+        })
+    }
+
     /// Helper constructor to make creating user audio content easier.
     pub fn audio(data: impl Into<String>, media_type: Option<AudioMediaType>) -> Self {
         UserContent::Audio(Audio {
@@ -608,6 +825,18 @@ impl UserContent {
         })
     }
 
+    /// Helper to create a document from the id of a file uploaded to the provider
+    pub fn document_file_id(
+        file_id: impl Into<String>,
+        media_type: Option<DocumentMediaType>,
+    ) -> Self {
+        UserContent::Document(Document {
+            data: DocumentSourceKind::FileId(file_id.into()),
+            media_type,
+            ..Default::default()
+        })
+    }
+
     /// Helper constructor to make creating user tool result content easier.
     pub fn tool_result(id: impl Into<String>, content: OneOrMany<ToolResultContent>) -> Self {
         UserContent::ToolResult(ToolResult {
@@ -899,7 +1128,10 @@ impl std::str::FromStr for ImageDetail {
 
 impl From<String> for Text {
     fn from(text: String) -> Self {
-        Text { text }
+        Text {
+            text,
+            citations: Vec::new(),
+        }
     }
 }
 
@@ -63,7 +63,7 @@
 //! For more information on how to use the completion functionality, refer to the documentation of
 //! the individual traits, structs, and enums defined in this module.
 
-use super::message::{AssistantContent, DocumentMediaType};
+use super::message::{AssistantContent, DocumentMediaType, HostedToolKind};
 use crate::client::FinalCompletionResponse;
 #[allow(deprecated)]
 use crate::client::completion::CompletionModelHandle;
@@ -259,10 +259,102 @@ pub struct CompletionResponse<T> {
     pub choice: OneOrMany<AssistantContent>,
     /// Tokens used during prompting and responding
     pub usage: Usage,
+    /// The reason why the model stopped generating, if reported by the provider
+    pub finish_reason: Option<FinishReason>,
+    /// The id of the response, if returned by the provider
+    pub id: Option<String>,
+    /// The name of the model that served the request, if returned by the provider
+    pub model: Option<String>,
+    /// The log-probabilities of the generated tokens, if requested (see
+    /// [CompletionRequestBuilder::logprobs]) and returned by the provider
+    pub logprobs: Option<Vec<TokenLogprob>>,
     /// The raw response returned by the completion model provider
     pub raw_response: T,
 }
 
+/// Provider-neutral reason why the model stopped generating.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FinishReason {
+    /// The model reached a natural stopping point
+    Stop,
+    /// The output was cut off by the maximum number of tokens
+    Length,
+    /// The model stopped to call one or more tools
+    ToolCalls,
+    /// The output was omitted or cut off by a content filter
+    ContentFilter,
+    /// The model generated one of the provided stop sequences
+    StopSequence,
+    /// Any other provider specific reason
+    Other(String),
+}
+
+impl FinishReason {
+    /// Whether the output was cut off before the model finished (i.e.: [FinishReason::Length]).
+    pub fn is_truncated(&self) -> bool {
+        matches!(self, FinishReason::Length)
+    }
+}
+
+impl std::fmt::Display for FinishReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FinishReason::Stop => write!(f, "stop"),
+            FinishReason::Length => write!(f, "length"),
+            FinishReason::ToolCalls => write!(f, "tool_calls"),
+            FinishReason::ContentFilter => write!(f, "content_filter"),
+            FinishReason::StopSequence => write!(f, "stop_sequence"),
+            FinishReason::Other(reason) => write!(f, "{reason}"),
+        }
+    }
+}
+
+/// The log-probability of a generated token.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TokenLogprob {
+    /// The generated token
+    pub token: String,
+    /// The log-probability of the token
+    pub logprob: f64,
+    /// The UTF-8 bytes of the token, if returned by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+    /// The most likely tokens at this position, if requested (see
+    /// [CompletionRequestBuilder::top_logprobs])
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_logprobs: Vec<TopLogprob>,
+}
+
+/// One of the most likely tokens at a given position of the output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopLogprob {
+    /// The token
+    pub token: String,
+    /// The log-probability of the token
+    pub logprob: f64,
+    /// The UTF-8 bytes of the token, if returned by the provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<Vec<u8>>,
+}
+
+impl TokenLogprob {
+    /// The linear probability of the token (between 0 and 1).
+    pub fn probability(&self) -> f64 {
+        self.logprob.exp()
+    }
+
+    /// The mean log-probability of a sequence of tokens, `None` if it is empty.
+    /// A low value is a hint that the model was unsure of its output.
+    pub fn mean(logprobs: &[TokenLogprob]) -> Option<f64> {
+        if logprobs.is_empty() {
+            return None;
+        }
+
+        Some(logprobs.iter().map(|logprob| logprob.logprob).sum::<f64>() / logprobs.len() as f64)
+    }
+}
+
 /// A trait for grabbing the token usage of a completion response.
 ///
 /// Primarily designed for streamed completion responses in streamed multi-turn, as otherwise it would be impossible to do.
@@ -419,6 +511,10 @@ where
                 .map(|resp| CompletionResponse {
                     choice: resp.choice,
                     usage: resp.usage,
+                    finish_reason: resp.finish_reason,
+                    id: resp.id,
+                    model: resp.model,
+                    logprobs: resp.logprobs,
                     raw_response: (),
                 })
         })
@@ -453,7 +549,7 @@ where
 }
 
 /// Struct representing a general completion request that can be sent to a completion model provider.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct CompletionRequest {
     /// The preamble to be sent to the completion model provider
     pub preamble: Option<String>,
@@ -464,12 +560,26 @@ pub struct CompletionRequest {
     pub documents: Vec<Document>,
     /// The tools to be sent to the completion model provider
     pub tools: Vec<ToolDefinition>,
+    /// The tools hosted and executed by the completion model provider (web search, code
+    /// interpreter...), see [HostedTool]. Providers that cannot run hosted tools ignore them with
+    /// a warning, or reject them in strict mode (see [SamplingParams::strict]).
+    pub hosted_tools: Vec<HostedTool>,
     /// The temperature to be sent to the completion model provider
     pub temperature: Option<f64>,
     /// The max tokens to be sent to the completion model provider
     pub max_tokens: Option<u64>,
     /// Whether tools are required to be used by the model provider or not before providing a response.
     pub tool_choice: Option<ToolChoice>,
+    /// Common sampling parameters (top p, top k, stop sequences, seed, penalties...)
+    pub sampling: SamplingParams,
+    /// Whether to return the log-probabilities of the generated tokens, along with the given
+    /// number of most likely alternatives at each position (`Some(0)` only returns the generated tokens).
+    /// Providers that do not support log-probabilities ignore this option with a warning, or
+    /// reject it in strict mode (see [SamplingParams::strict]).
+    pub logprobs: Option<u32>,
+    /// The reasoning ("thinking") configuration of the model.
+    /// Providers that do not support configuring the reasoning ignore this option.
+    pub reasoning: Option<ReasoningConfig>,
     /// Additional provider-specific parameters to be sent to the completion model provider
     pub additional_params: Option<serde_json::Value>,
 }
@@ -502,6 +612,491 @@ impl CompletionRequest {
             content: OneOrMany::many(messages).expect("There will be atleast one document"),
         })
     }
+
+    /// Returns the reasoning configuration, unless the provider-specific `param` configuring the
+    /// reasoning is set in the additional parameters (which take precedence).
+    pub fn reasoning_unless_overridden(&self, param: &str) -> Option<&ReasoningConfig> {
+        let overridden = self
+            .additional_params
+            .as_ref()
+            .is_some_and(|params| params.get(param).is_some());
+
+        self.reasoning.as_ref().filter(|_| !overridden)
+    }
+
+    /// Returns the sampling parameters without the ones whose provider-specific key (given by
+    /// `key`) is set in the additional parameters, which take precedence. This keeps providers
+    /// flattening both into the request body from sending a key twice.
+    pub fn sampling_unless_overridden(
+        &self,
+        key: impl Fn(SamplingParam) -> &'static str,
+    ) -> SamplingParams {
+        let mut sampling = self.sampling.clone();
+
+        if let Some(params) = &self.additional_params {
+            for param in SamplingParam::ALL {
+                if params.get(key(param)).is_some() {
+                    sampling.unset(param);
+                }
+            }
+        }
+
+        sampling
+    }
+
+    /// Checks that no log-probabilities are requested from `provider`, which cannot return them.
+    /// They are ignored with a warning, or rejected in strict mode (see [SamplingParams::strict]).
+    pub fn check_logprobs_unsupported(&self, provider: &str) -> Result<(), CompletionError> {
+        if self.logprobs.is_none() {
+            return Ok(());
+        }
+
+        if self.sampling.strict {
+            return Err(CompletionError::RequestError(
+                format!("{provider} does not support log-probabilities").into(),
+            ));
+        }
+
+        tracing::warn!(
+            target: "rig::completions",
+            "{provider} does not support log-probabilities, they will not be returned"
+        );
+
+        Ok(())
+    }
+
+    /// Checks that no reasoning configuration is given to `provider`, which cannot configure the
+    /// reasoning of the model. It is ignored with a warning, or rejected in strict mode (see
+    /// [SamplingParams::strict]). A disabled reasoning configuration is always accepted.
+    pub fn check_reasoning_unsupported(&self, provider: &str) -> Result<(), CompletionError> {
+        if self
+            .reasoning
+            .as_ref()
+            .is_none_or(ReasoningConfig::is_disabled)
+        {
+            return Ok(());
+        }
+
+        if self.sampling.strict {
+            return Err(CompletionError::RequestError(
+                format!("{provider} does not support configuring the reasoning").into(),
+            ));
+        }
+
+        tracing::warn!(
+            target: "rig::completions",
+            "{provider} does not support configuring the reasoning, it will be ignored"
+        );
+
+        Ok(())
+    }
+
+    /// Checks that no hosted tools are given to `provider`, which cannot run them.
+    /// They are ignored with a warning, or rejected in strict mode (see [SamplingParams::strict]).
+    pub fn check_hosted_tools_unsupported(&self, provider: &str) -> Result<(), CompletionError> {
+        for tool in &self.hosted_tools {
+            if self.sampling.strict {
+                return Err(tool.unsupported(provider));
+            }
+
+            tracing::warn!(
+                target: "rig::completions",
+                "The {} hosted tool is not supported by {provider}, it will be ignored",
+                tool.kind()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// Sampling parameters shared by most completion model providers.
+///
+/// Each provider maps the parameters it supports onto its own request format. Parameters a
+/// provider does not support are dropped with a warning, or rejected with a
+/// [CompletionError::RequestError] when [SamplingParams::strict] is set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SamplingParams {
+    /// Nucleus sampling: only the tokens comprising the top `top_p` probability mass are considered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    /// Only the `top_k` most likely tokens are considered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_k: Option<u64>,
+    /// Sequences that stop the generation when produced by the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop: Option<Vec<String>>,
+    /// Seed used for (best effort) deterministic sampling
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// Penalizes tokens that already appeared in the output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f64>,
+    /// Penalizes tokens proportionally to how often they already appeared in the output
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f64>,
+    /// Bias added to the logits of the given token ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logit_bias: Option<HashMap<u32, f64>>,
+    /// Whether unsupported parameters (including log-probabilities) should produce an error
+    /// instead of a warning
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// A parameter of [SamplingParams].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SamplingParam {
+    TopP,
+    TopK,
+    Stop,
+    Seed,
+    PresencePenalty,
+    FrequencyPenalty,
+    LogitBias,
+}
+
+impl SamplingParam {
+    /// All sampling parameters.
+    pub const ALL: [SamplingParam; 7] = [
+        SamplingParam::TopP,
+        SamplingParam::TopK,
+        SamplingParam::Stop,
+        SamplingParam::Seed,
+        SamplingParam::PresencePenalty,
+        SamplingParam::FrequencyPenalty,
+        SamplingParam::LogitBias,
+    ];
+
+    /// The name of the parameter, which is also its key in OpenAI compatible APIs.
+    pub fn name(self) -> &'static str {
+        match self {
+            SamplingParam::TopP => "top_p",
+            SamplingParam::TopK => "top_k",
+            SamplingParam::Stop => "stop",
+            SamplingParam::Seed => "seed",
+            SamplingParam::PresencePenalty => "presence_penalty",
+            SamplingParam::FrequencyPenalty => "frequency_penalty",
+            SamplingParam::LogitBias => "logit_bias",
+        }
+    }
+}
+
+impl std::fmt::Display for SamplingParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Error returned in strict mode when a provider does not support a sampling parameter.
+#[derive(Debug, Error)]
+#[error("{provider} does not support the `{param}` sampling parameter")]
+pub struct UnsupportedSamplingParam {
+    pub provider: String,
+    pub param: SamplingParam,
+}
+
+impl SamplingParams {
+    /// Whether the given parameter is set.
+    pub fn is_set(&self, param: SamplingParam) -> bool {
+        match param {
+            SamplingParam::TopP => self.top_p.is_some(),
+            SamplingParam::TopK => self.top_k.is_some(),
+            SamplingParam::Stop => self.stop.is_some(),
+            SamplingParam::Seed => self.seed.is_some(),
+            SamplingParam::PresencePenalty => self.presence_penalty.is_some(),
+            SamplingParam::FrequencyPenalty => self.frequency_penalty.is_some(),
+            SamplingParam::LogitBias => self.logit_bias.is_some(),
+        }
+    }
+
+    fn unset(&mut self, param: SamplingParam) {
+        match param {
+            SamplingParam::TopP => self.top_p = None,
+            SamplingParam::TopK => self.top_k = None,
+            SamplingParam::Stop => self.stop = None,
+            SamplingParam::Seed => self.seed = None,
+            SamplingParam::PresencePenalty => self.presence_penalty = None,
+            SamplingParam::FrequencyPenalty => self.frequency_penalty = None,
+            SamplingParam::LogitBias => self.logit_bias = None,
+        }
+    }
+
+    /// Checks the parameters that are set against the ones supported by `provider`.
+    /// Unsupported parameters are logged as a warning, or returned as an error in strict mode.
+    pub fn check_supported(
+        &self,
+        provider: &str,
+        supported: &[SamplingParam],
+    ) -> Result<(), CompletionError> {
+        for param in SamplingParam::ALL {
+            if !self.is_set(param) || supported.contains(&param) {
+                continue;
+            }
+
+            if self.strict {
+                return Err(CompletionError::RequestError(Box::new(
+                    UnsupportedSamplingParam {
+                        provider: provider.to_string(),
+                        param,
+                    },
+                )));
+            }
+
+            tracing::warn!(
+                target: "rig::completions",
+                "{provider} does not support the `{param}` sampling parameter, it will be ignored"
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// How much effort a model should put into reasoning before answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningEffort {
+    /// Disables reasoning, for models where it is optional
+    None,
+    Minimal,
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    /// The reasoning token budget used for providers configured with a budget rather than
+    /// an effort level.
+    pub fn budget_tokens(&self) -> u64 {
+        match self {
+            ReasoningEffort::None => 0,
+            ReasoningEffort::Minimal => 1_024,
+            ReasoningEffort::Low => 4_096,
+            ReasoningEffort::Medium => 8_192,
+            ReasoningEffort::High => 16_384,
+        }
+    }
+
+    /// The effort level matching a reasoning token budget, for providers configured with an
+    /// effort level rather than a budget.
+    pub fn from_budget_tokens(budget_tokens: u64) -> Self {
+        [
+            ReasoningEffort::None,
+            ReasoningEffort::Minimal,
+            ReasoningEffort::Low,
+            ReasoningEffort::Medium,
+        ]
+        .into_iter()
+        .find(|effort| budget_tokens <= effort.budget_tokens())
+        .unwrap_or(ReasoningEffort::High)
+    }
+}
+
+impl std::fmt::Display for ReasoningEffort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ReasoningEffort::None => "none",
+            ReasoningEffort::Minimal => "minimal",
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Provider-neutral reasoning ("thinking") configuration.
+///
+/// Providers are either configured with an effort level (e.g. OpenAI, xAI, Groq) or a token
+/// budget (e.g. Anthropic, Gemini). When only one of them is set, the other one is derived from it
+/// (see [ReasoningEffort::budget_tokens]).
+///
+/// ```rust
+/// use rig::completion::{ReasoningConfig, ReasoningEffort};
+///
+/// let reasoning = ReasoningConfig::effort(ReasoningEffort::High).with_summary(true);
+/// let reasoning = ReasoningConfig::budget_tokens(2_048);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReasoningConfig {
+    /// How much effort the model should put into reasoning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
+    /// The maximum number of tokens the model can use to reason
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u64>,
+    /// Whether the reasoning (or a summary of it, depending on the provider) should be returned
+    /// with the response. Uses the provider's default when `None`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<bool>,
+}
+
+impl ReasoningConfig {
+    /// Reasoning with the given effort level.
+    pub fn effort(effort: ReasoningEffort) -> Self {
+        Self {
+            effort: Some(effort),
+            ..Default::default()
+        }
+    }
+
+    /// Reasoning with the given token budget.
+    pub fn budget_tokens(budget_tokens: u64) -> Self {
+        Self {
+            budget_tokens: Some(budget_tokens),
+            ..Default::default()
+        }
+    }
+
+    /// Disables reasoning, for models where it is optional.
+    pub fn disabled() -> Self {
+        Self::effort(ReasoningEffort::None)
+    }
+
+    /// Sets the effort level.
+    pub fn with_effort(mut self, effort: ReasoningEffort) -> Self {
+        self.effort = Some(effort);
+        self
+    }
+
+    /// Sets the token budget.
+    pub fn with_budget_tokens(mut self, budget_tokens: u64) -> Self {
+        self.budget_tokens = Some(budget_tokens);
+        self
+    }
+
+    /// Sets whether the reasoning (or a summary of it) should be returned with the response.
+    pub fn with_summary(mut self, summary: bool) -> Self {
+        self.summary = Some(summary);
+        self
+    }
+
+    /// The effort level, derived from the token budget if not set.
+    /// `None` if neither is set, in which case the provider's default should be used.
+    pub fn resolved_effort(&self) -> Option<ReasoningEffort> {
+        self.effort
+            .or_else(|| self.budget_tokens.map(ReasoningEffort::from_budget_tokens))
+    }
+
+    /// The token budget, derived from the effort level if not set.
+    /// `None` if neither is set, in which case the provider's default should be used.
+    pub fn resolved_budget_tokens(&self) -> Option<u64> {
+        self.budget_tokens
+            .or_else(|| self.effort.map(|effort| effort.budget_tokens()))
+    }
+
+    /// Whether this configuration disables reasoning.
+    pub fn is_disabled(&self) -> bool {
+        self.resolved_effort() == Some(ReasoningEffort::None)
+    }
+}
+
+/// A tool hosted and executed by the completion model provider, as opposed to the tools of
+/// [ToolDefinition]s which are executed by Rig.
+///
+/// Providers translate the hosted tools to their native tools:
+///
+/// | Hosted tool       | OpenAI (Responses API) | Anthropic                        | Gemini           |
+/// |-------------------|------------------------|----------------------------------|------------------|
+/// | `WebSearch`       | `web_search`           | `web_search_20250305`            | `google_search`  |
+/// | `CodeInterpreter` | `code_interpreter`     | `code_execution_20250522` (beta) | `code_execution` |
+/// | `FileSearch`      | `file_search`          | -                                | `file_search`    |
+/// | `UrlContext`      | -                      | `web_fetch_20250910` (beta)      | `url_context`    |
+///
+/// These providers reject the hosted tools they do not support with a
+/// [CompletionError::RequestError], other providers ignore hosted tools. The calls of hosted tools
+/// are returned as [AssistantContent::HostedToolCall] items, along with their results.
+///
+/// ```rust
+/// use rig::completion::HostedTool;
+///
+/// let web_search = HostedTool::web_search().with_allowed_domains(["docs.rs"]);
+/// let file_search = HostedTool::file_search(["vs_abc123"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum HostedTool {
+    /// Searches the web
+    WebSearch {
+        /// Only search these domains, all domains when empty
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_domains: Vec<String>,
+        /// The maximum number of searches per request. Only supported by Anthropic
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_uses: Option<u32>,
+    },
+    /// Writes and runs code in a sandbox
+    CodeInterpreter,
+    /// Searches files uploaded to the provider
+    FileSearch {
+        /// The vector stores (OpenAI) or file search stores (Gemini) to search
+        store_ids: Vec<String>,
+        /// The maximum number of results to return. Only supported by OpenAI
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_num_results: Option<u32>,
+    },
+    /// Fetches the content of the URLs given in the prompt
+    UrlContext,
+}
+
+impl HostedTool {
+    pub fn web_search() -> Self {
+        Self::WebSearch {
+            allowed_domains: Vec::new(),
+            max_uses: None,
+        }
+    }
+
+    pub fn code_interpreter() -> Self {
+        Self::CodeInterpreter
+    }
+
+    pub fn file_search<S: Into<String>>(store_ids: impl IntoIterator<Item = S>) -> Self {
+        Self::FileSearch {
+            store_ids: store_ids.into_iter().map(Into::into).collect(),
+            max_num_results: None,
+        }
+    }
+
+    pub fn url_context() -> Self {
+        Self::UrlContext
+    }
+
+    /// Restricts a web search to the given domains. Has no effect on other tools.
+    pub fn with_allowed_domains<S: Into<String>>(
+        mut self,
+        domains: impl IntoIterator<Item = S>,
+    ) -> Self {
+        if let Self::WebSearch {
+            allowed_domains, ..
+        } = &mut self
+        {
+            *allowed_domains = domains.into_iter().map(Into::into).collect();
+        }
+        self
+    }
+
+    pub fn kind(&self) -> HostedToolKind {
+        match self {
+            Self::WebSearch { .. } => HostedToolKind::WebSearch,
+            Self::CodeInterpreter => HostedToolKind::CodeInterpreter,
+            Self::FileSearch { .. } => HostedToolKind::FileSearch,
+            Self::UrlContext => HostedToolKind::UrlContext,
+        }
+    }
+
+    /// The error returned by providers that do not support this hosted tool.
+    pub(crate) fn unsupported(&self, provider: &str) -> CompletionError {
+        CompletionError::RequestError(
+            format!(
+                "The {} hosted tool is not supported by {provider}",
+                self.kind()
+            )
+            .into(),
+        )
+    }
 }
 
 /// Builder struct for constructing a completion request.
@@ -555,9 +1150,13 @@ pub struct CompletionRequestBuilder<M: CompletionModel> {
     chat_history: Vec<Message>,
     documents: Vec<Document>,
     tools: Vec<ToolDefinition>,
+    hosted_tools: Vec<HostedTool>,
     temperature: Option<f64>,
     max_tokens: Option<u64>,
     tool_choice: Option<ToolChoice>,
+    sampling: SamplingParams,
+    logprobs: Option<u32>,
+    reasoning: Option<ReasoningConfig>,
     additional_params: Option<serde_json::Value>,
 }
 
@@ -570,9 +1169,13 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             chat_history: Vec::new(),
             documents: Vec::new(),
             tools: Vec::new(),
+            hosted_tools: Vec::new(),
             temperature: None,
             max_tokens: None,
             tool_choice: None,
+            sampling: SamplingParams::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
         }
     }
@@ -627,6 +1230,18 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             .fold(self, |builder, tool| builder.tool(tool))
     }
 
+    /// Adds a tool hosted by the provider to the completion request.
+    pub fn hosted_tool(mut self, tool: HostedTool) -> Self {
+        self.hosted_tools.push(tool);
+        self
+    }
+
+    /// Adds a list of tools hosted by the provider to the completion request.
+    pub fn hosted_tools(mut self, tools: Vec<HostedTool>) -> Self {
+        self.hosted_tools.extend(tools);
+        self
+    }
+
     /// Adds additional parameters to the completion request.
     /// This can be used to set additional provider-specific parameters. For example,
     /// Cohere's completion models accept a `connectors` parameter that can be used to
@@ -686,6 +1301,87 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
         self
     }
 
+    /// Sets all the sampling parameters for the completion request at once.
+    pub fn sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Sets the nucleus sampling probability mass for the completion request.
+    pub fn top_p(mut self, top_p: f64) -> Self {
+        self.sampling.top_p = Some(top_p);
+        self
+    }
+
+    /// Sets the number of most likely tokens to sample from for the completion request.
+    pub fn top_k(mut self, top_k: u64) -> Self {
+        self.sampling.top_k = Some(top_k);
+        self
+    }
+
+    /// Sets the stop sequences for the completion request.
+    pub fn stop(mut self, stop: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.sampling.stop = Some(stop.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets the sampling seed for the completion request.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.sampling.seed = Some(seed);
+        self
+    }
+
+    /// Sets the presence penalty for the completion request.
+    pub fn presence_penalty(mut self, presence_penalty: f64) -> Self {
+        self.sampling.presence_penalty = Some(presence_penalty);
+        self
+    }
+
+    /// Sets the frequency penalty for the completion request.
+    pub fn frequency_penalty(mut self, frequency_penalty: f64) -> Self {
+        self.sampling.frequency_penalty = Some(frequency_penalty);
+        self
+    }
+
+    /// Sets the logit bias (token id to bias) for the completion request.
+    pub fn logit_bias(mut self, logit_bias: HashMap<u32, f64>) -> Self {
+        self.sampling.logit_bias = Some(logit_bias);
+        self
+    }
+
+    /// Makes the request fail instead of warning when the provider does not support one of the
+    /// sampling parameters, or log-probabilities.
+    pub fn strict_sampling(mut self, strict: bool) -> Self {
+        self.sampling.strict = strict;
+        self
+    }
+
+    /// Sets whether to return the log-probabilities of the generated tokens
+    /// (in [CompletionResponse::logprobs] or as [crate::streaming::StreamedAssistantContent::Logprobs] items).
+    pub fn logprobs(mut self, logprobs: bool) -> Self {
+        self.logprobs = logprobs.then(|| self.logprobs.unwrap_or(0));
+        self
+    }
+
+    /// Returns the log-probabilities of the generated tokens along with the `top_logprobs`
+    /// most likely alternatives at each position.
+    pub fn top_logprobs(mut self, top_logprobs: u32) -> Self {
+        self.logprobs = Some(top_logprobs);
+        self
+    }
+
+    /// Sets the reasoning ("thinking") configuration of the model.
+    pub fn reasoning(mut self, reasoning: ReasoningConfig) -> Self {
+        self.reasoning = Some(reasoning);
+        self
+    }
+
+    /// Sets the reasoning ("thinking") configuration of the model, `None` to use the provider's default.
+    pub fn reasoning_opt(mut self, reasoning: Option<ReasoningConfig>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
     /// Builds the completion request.
     pub fn build(self) -> CompletionRequest {
         let chat_history = OneOrMany::many([self.chat_history, vec![self.prompt]].concat())
@@ -696,9 +1392,13 @@ impl<M: CompletionModel> CompletionRequestBuilder<M> {
             chat_history,
             documents: self.documents,
             tools: self.tools,
+            hosted_tools: self.hosted_tools,
             temperature: self.temperature,
             max_tokens: self.max_tokens,
             tool_choice: self.tool_choice,
+            sampling: self.sampling,
+            logprobs: self.logprobs,
+            reasoning: self.reasoning,
             additional_params: self.additional_params,
         }
     }
@@ -779,9 +1479,13 @@ mod tests {
             chat_history: OneOrMany::one("What is the capital of France?".into()),
             documents: vec![doc1, doc2],
             tools: Vec::new(),
+            hosted_tools: Vec::new(),
             temperature: None,
             max_tokens: None,
             tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
         };
 
@@ -809,12 +1513,175 @@ mod tests {
             chat_history: OneOrMany::one("What is the capital of France?".into()),
             documents: Vec::new(),
             tools: Vec::new(),
+            hosted_tools: Vec::new(),
             temperature: None,
             max_tokens: None,
             tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
             additional_params: None,
         };
 
         assert_eq!(request.normalized_documents(), None);
     }
+
+    #[test]
+    fn test_check_logprobs_unsupported() {
+        let mut request = CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one("What is the capital of France?".into()),
+            documents: Vec::new(),
+            tools: Vec::new(),
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
+            additional_params: None,
+        };
+        assert!(request.check_logprobs_unsupported("Test").is_ok());
+
+        // Requested log-probabilities are only warned about outside of strict mode
+        request.logprobs = Some(0);
+        assert!(request.check_logprobs_unsupported("Test").is_ok());
+
+        request.sampling.strict = true;
+        assert_eq!(
+            request
+                .check_logprobs_unsupported("Test")
+                .unwrap_err()
+                .to_string(),
+            "RequestError: Test does not support log-probabilities"
+        );
+    }
+
+    #[test]
+    fn test_check_reasoning_unsupported() {
+        let mut request = CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one("What is the capital of France?".into()),
+            documents: Vec::new(),
+            tools: Vec::new(),
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: SamplingParams {
+                strict: true,
+                ..Default::default()
+            },
+            logprobs: None,
+            reasoning: Some(ReasoningConfig::disabled()),
+            additional_params: None,
+        };
+        assert!(request.check_reasoning_unsupported("Test").is_ok());
+
+        request.reasoning = Some(ReasoningConfig::effort(ReasoningEffort::High));
+        assert_eq!(
+            request
+                .check_reasoning_unsupported("Test")
+                .unwrap_err()
+                .to_string(),
+            "RequestError: Test does not support configuring the reasoning"
+        );
+
+        // The reasoning configuration is only warned about outside of strict mode
+        request.sampling.strict = false;
+        assert!(request.check_reasoning_unsupported("Test").is_ok());
+    }
+
+    #[test]
+    fn test_check_hosted_tools_unsupported() {
+        let mut request = CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one("What is the capital of France?".into()),
+            documents: Vec::new(),
+            tools: Vec::new(),
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
+            additional_params: None,
+        };
+        assert!(request.check_hosted_tools_unsupported("Test").is_ok());
+
+        // Hosted tools are only warned about outside of strict mode
+        request.hosted_tools.push(HostedTool::web_search());
+        assert!(request.check_hosted_tools_unsupported("Test").is_ok());
+
+        request.sampling.strict = true;
+        assert_eq!(
+            request
+                .check_hosted_tools_unsupported("Test")
+                .unwrap_err()
+                .to_string(),
+            "RequestError: The web_search hosted tool is not supported by Test"
+        );
+    }
+
+    #[test]
+    fn test_check_supported_sampling_params() {
+        let sampling = SamplingParams {
+            top_p: Some(0.9),
+            seed: Some(42),
+            ..Default::default()
+        };
+
+        assert!(sampling.is_set(SamplingParam::TopP));
+        assert!(!sampling.is_set(SamplingParam::TopK));
+        // Unsupported parameters are only warned about outside of strict mode
+        assert!(
+            sampling
+                .check_supported("Test", &[SamplingParam::TopP])
+                .is_ok()
+        );
+
+        let strict = SamplingParams {
+            strict: true,
+            ..sampling
+        };
+        assert!(
+            strict
+                .check_supported("Test", &[SamplingParam::TopP, SamplingParam::Seed])
+                .is_ok()
+        );
+
+        let err = strict
+            .check_supported("Test", &[SamplingParam::TopP])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "RequestError: Test does not support the `seed` sampling parameter"
+        );
+    }
+
+    #[test]
+    fn test_reasoning_config_resolution() {
+        let effort = ReasoningConfig::effort(ReasoningEffort::High);
+        assert_eq!(effort.resolved_effort(), Some(ReasoningEffort::High));
+        assert_eq!(effort.resolved_budget_tokens(), Some(16_384));
+
+        let budget = ReasoningConfig::budget_tokens(3_000);
+        assert_eq!(budget.resolved_effort(), Some(ReasoningEffort::Low));
+        assert_eq!(budget.resolved_budget_tokens(), Some(3_000));
+
+        // Explicit values take precedence over derived ones
+        let both = budget.with_effort(ReasoningEffort::Minimal);
+        assert_eq!(both.resolved_effort(), Some(ReasoningEffort::Minimal));
+        assert_eq!(both.resolved_budget_tokens(), Some(3_000));
+
+        assert!(ReasoningConfig::disabled().is_disabled());
+        assert!(ReasoningConfig::budget_tokens(0).is_disabled());
+        assert_eq!(ReasoningConfig::default().resolved_effort(), None);
+        assert_eq!(
+            ReasoningEffort::from_budget_tokens(100_000),
+            ReasoningEffort::High
+        );
+    }
 }
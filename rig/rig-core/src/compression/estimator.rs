@@ -74,6 +74,16 @@ fn estimate_assistant_content_tokens(content: &AssistantContent) -> usize {
             r.reasoning.iter().map(|s| estimate_tokens(s)).sum()
         }
         AssistantContent::Image(_) => 85,
+        AssistantContent::HostedToolCall(call) => {
+            // The call and its result are sent back as-is when available
+            match &call.raw {
+                Some(raw) => estimate_tokens(&raw.to_string()),
+                None => {
+                    estimate_tokens(call.input.as_deref().unwrap_or_default())
+                        + estimate_tokens(call.output.as_deref().unwrap_or_default())
+                }
+            }
+        }
     }
 }
 
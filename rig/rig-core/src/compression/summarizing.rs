@@ -335,6 +335,13 @@ impl<P: Prompt> SummarizingCompressor<P> {
                             AssistantContent::Image(_) => {
                                 output.push_str("[Image generated]\n");
                             }
+                            AssistantContent::HostedToolCall(call) => {
+                                output.push_str(&format!(
+                                    "[Hosted Tool Call: {}({})]\n",
+                                    call.tool,
+                                    call.input.as_deref().unwrap_or_default()
+                                ));
+                            }
                         }
                     }
                 }
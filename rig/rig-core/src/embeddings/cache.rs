@@ -0,0 +1,603 @@
+//! The module defines the [CachedEmbeddingModel] struct, which wraps an [EmbeddingModel] and
+//! serves previously computed embeddings from an [EmbeddingCache] instead of calling the provider.
+//!
+//! Cache entries are keyed by provider, model name, number of dimensions and a hash of the
+//! embedded text (see [cache_key]), so different models can safely share the same cache.
+//!
+//! Since [CachedEmbeddingModel] implements [EmbeddingModel] itself, it can be used anywhere a
+//! model is expected (e.g.: [EmbeddingsBuilder](super::EmbeddingsBuilder), vector store indexes),
+//! which also caches the query embeddings computed by `top_n` searches.
+//!
+//! Two caches are provided:
+//! - [LruEmbeddingCache]: a bounded in-memory cache evicting the least recently used entries,
+//! - [FileEmbeddingCache]: an append-only file that persists entries across runs.
+//!
+//! # Example
+//! ```rust,ignore
+//! use rig::embeddings::cache::{CachedEmbeddingModel, LruEmbeddingCache};
+//!
+//! let model = openai_client.embedding_model(TEXT_EMBEDDING_3_SMALL);
+//! let model = CachedEmbeddingModel::new(
+//!     model,
+//!     "openai",
+//!     TEXT_EMBEDDING_3_SMALL,
+//!     LruEmbeddingCache::new(10_000),
+//! );
+//!
+//! // The second call is served from the cache
+//! let embedding = model.embed_text("Hello world").await?;
+//! let embedding = model.embed_text("Hello world").await?;
+//! ```
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use crate::{
+    embeddings::{Embedding, EmbeddingError, EmbeddingModel, ingest::content_hash},
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum EmbeddingCacheError {
+    /// Json error (e.g.: serialization, deserialization)
+    #[error("JsonError: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    #[cfg(not(target_family = "wasm"))]
+    /// Error returned by the cache backend (e.g.: io or database error)
+    #[error("BackendError: {0}")]
+    BackendError(Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    #[cfg(target_family = "wasm")]
+    /// Error returned by the cache backend (e.g.: io or database error)
+    #[error("BackendError: {0}")]
+    BackendError(Box<dyn std::error::Error + 'static>),
+}
+
+/// Storage for embedding vectors, keyed by [cache_key].
+///
+/// The trait is object safe so a cache can be shared between models as an `Arc<dyn EmbeddingCache>`.
+pub trait EmbeddingCache: WasmCompatSend + WasmCompatSync {
+    /// Look up the vectors of the given keys. The result has one entry per key, in the same order.
+    fn get_many<'a>(
+        &'a self,
+        keys: &'a [String],
+    ) -> WasmBoxedFuture<'a, Result<Vec<Option<Vec<f64>>>, EmbeddingCacheError>>;
+
+    /// Store the vectors of the given keys, replacing existing entries.
+    fn insert_many(
+        &self,
+        entries: Vec<(String, Vec<f64>)>,
+    ) -> WasmBoxedFuture<'_, Result<(), EmbeddingCacheError>>;
+}
+
+impl<C: EmbeddingCache + ?Sized> EmbeddingCache for Arc<C> {
+    fn get_many<'a>(
+        &'a self,
+        keys: &'a [String],
+    ) -> WasmBoxedFuture<'a, Result<Vec<Option<Vec<f64>>>, EmbeddingCacheError>> {
+        (**self).get_many(keys)
+    }
+
+    fn insert_many(
+        &self,
+        entries: Vec<(String, Vec<f64>)>,
+    ) -> WasmBoxedFuture<'_, Result<(), EmbeddingCacheError>> {
+        (**self).insert_many(entries)
+    }
+}
+
+/// Build the cache key of a text embedded by the given provider and model.
+pub fn cache_key(provider: &str, model: &str, ndims: usize, text: &str) -> String {
+    format!("{provider}:{model}:{ndims}:{}", content_hash(&[text]))
+}
+
+/// In-memory cache holding at most `capacity` vectors, evicting the least recently used ones.
+#[derive(Debug)]
+pub struct LruEmbeddingCache {
+    capacity: usize,
+    state: Mutex<LruState>,
+}
+
+#[derive(Debug, Default)]
+struct LruState {
+    /// Vectors along with the tick of their last use
+    entries: HashMap<String, (Vec<f64>, u64)>,
+    /// Keys ordered by the tick of their last use
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl LruState {
+    fn touch(&mut self, key: &str) -> Option<Vec<f64>> {
+        self.tick += 1;
+        let tick = self.tick;
+
+        let (vec, last_used) = self.entries.get_mut(key)?;
+        let key = self
+            .recency
+            .remove(last_used)
+            .expect("Every entry has a recency tick");
+        *last_used = tick;
+        let vec = vec.clone();
+        self.recency.insert(tick, key);
+
+        Some(vec)
+    }
+}
+
+impl LruEmbeddingCache {
+    /// Create a cache holding at most `capacity` vectors.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(LruState::default()),
+        }
+    }
+
+    /// Number of cached vectors.
+    pub fn len(&self) -> usize {
+        self.state
+            .lock()
+            .expect("cache lock poisoned")
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Default for LruEmbeddingCache {
+    /// Cache holding at most 10 000 vectors.
+    fn default() -> Self {
+        Self::new(10_000)
+    }
+}
+
+impl EmbeddingCache for LruEmbeddingCache {
+    fn get_many<'a>(
+        &'a self,
+        keys: &'a [String],
+    ) -> WasmBoxedFuture<'a, Result<Vec<Option<Vec<f64>>>, EmbeddingCacheError>> {
+        Box::pin(async move {
+            let mut state = self.state.lock().expect("cache lock poisoned");
+            Ok(keys.iter().map(|key| state.touch(key)).collect())
+        })
+    }
+
+    fn insert_many(
+        &self,
+        entries: Vec<(String, Vec<f64>)>,
+    ) -> WasmBoxedFuture<'_, Result<(), EmbeddingCacheError>> {
+        Box::pin(async move {
+            let mut state = self.state.lock().expect("cache lock poisoned");
+
+            for (key, vec) in entries {
+                state.tick += 1;
+                let tick = state.tick;
+
+                if let Some((_, last_used)) = state.entries.insert(key.clone(), (vec, tick)) {
+                    state.recency.remove(&last_used);
+                }
+                state.recency.insert(tick, key);
+
+                while state.entries.len() > self.capacity {
+                    let Some((_, oldest)) = state.recency.pop_first() else {
+                        break;
+                    };
+                    state.entries.remove(&oldest);
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+/// Cache persisted to an append-only file of JSON lines, loaded in memory when opened.
+/// Entries are never evicted.
+///
+/// Writes run on tokio's blocking thread pool, so inserting requires a tokio runtime.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug)]
+pub struct FileEmbeddingCache {
+    entries: Mutex<HashMap<String, Vec<f64>>>,
+    file: Arc<Mutex<std::fs::File>>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct FileEntry {
+    key: String,
+    vec: Vec<f64>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl FileEmbeddingCache {
+    /// Open the cache file at `path`, creating it if it doesn't exist.
+    /// Lines that can't be parsed (e.g.: a partial write after a crash) are ignored.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, EmbeddingCacheError> {
+        let path = path.as_ref();
+
+        let entries = match std::fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .filter_map(|line| serde_json::from_str::<FileEntry>(line).ok())
+                .map(|entry| (entry.key, entry.vec))
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(EmbeddingCacheError::BackendError(Box::new(e))),
+        };
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))?;
+
+        Ok(Self {
+            entries: Mutex::new(entries),
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// Number of cached vectors.
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl EmbeddingCache for FileEmbeddingCache {
+    fn get_many<'a>(
+        &'a self,
+        keys: &'a [String],
+    ) -> WasmBoxedFuture<'a, Result<Vec<Option<Vec<f64>>>, EmbeddingCacheError>> {
+        Box::pin(async move {
+            let entries = self.entries.lock().expect("cache lock poisoned");
+            Ok(keys.iter().map(|key| entries.get(key).cloned()).collect())
+        })
+    }
+
+    fn insert_many(
+        &self,
+        entries: Vec<(String, Vec<f64>)>,
+    ) -> WasmBoxedFuture<'_, Result<(), EmbeddingCacheError>> {
+        Box::pin(async move {
+            use std::io::Write;
+
+            let mut lines = String::new();
+            for (key, vec) in &entries {
+                lines.push_str(&serde_json::to_string(&FileEntry {
+                    key: key.clone(),
+                    vec: vec.clone(),
+                })?);
+                lines.push('\n');
+            }
+
+            // The file is only locked on the blocking thread, never across an await point.
+            let file = self.file.clone();
+            tokio::task::spawn_blocking(move || {
+                file.lock()
+                    .expect("cache lock poisoned")
+                    .write_all(lines.as_bytes())
+            })
+            .await
+            .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))?
+            .map_err(|e| EmbeddingCacheError::BackendError(Box::new(e)))?;
+
+            self.entries
+                .lock()
+                .expect("cache lock poisoned")
+                .extend(entries);
+
+            Ok(())
+        })
+    }
+}
+
+/// Embedding model wrapper serving embeddings from an [EmbeddingCache] when possible.
+///
+/// Only the texts missing from the cache are sent to the wrapped model. Cache errors are logged
+/// and treated as misses, so a failing cache never fails an embedding request.
+#[derive(Clone)]
+pub struct CachedEmbeddingModel<M> {
+    model: M,
+    provider: String,
+    model_name: String,
+    cache: Arc<dyn EmbeddingCache>,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+impl<M: EmbeddingModel> CachedEmbeddingModel<M> {
+    /// Wrap `model` with `cache`. The provider and model names are part of the cache keys.
+    pub fn new(
+        model: M,
+        provider: impl Into<String>,
+        model_name: impl Into<String>,
+        cache: impl EmbeddingCache + 'static,
+    ) -> Self {
+        Self::with_shared_cache(model, provider, model_name, Arc::new(cache))
+    }
+
+    /// Wrap `model` with a cache shared with other models.
+    pub fn with_shared_cache(
+        model: M,
+        provider: impl Into<String>,
+        model_name: impl Into<String>,
+        cache: Arc<dyn EmbeddingCache>,
+    ) -> Self {
+        Self {
+            model,
+            provider: provider.into(),
+            model_name: model_name.into(),
+            cache,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Get a reference to the wrapped model.
+    pub fn inner(&self) -> &M {
+        &self.model
+    }
+
+    /// Number of texts served from the cache.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Number of texts sent to the wrapped model.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+}
+
+impl<M> EmbeddingModel for CachedEmbeddingModel<M>
+where
+    M: EmbeddingModel,
+{
+    const MAX_DOCUMENTS: usize = M::MAX_DOCUMENTS;
+
+    type Client = M::Client;
+
+    /// Create the wrapped model with an in-memory [LruEmbeddingCache].
+    /// The provider part of the cache keys is the type name of the wrapped model.
+    fn make(client: &Self::Client, model: impl Into<String>, dims: Option<usize>) -> Self {
+        let model = model.into();
+        Self::new(
+            M::make(client, model.clone(), dims),
+            std::any::type_name::<M>(),
+            model,
+            LruEmbeddingCache::default(),
+        )
+    }
+
+    fn ndims(&self) -> usize {
+        self.model.ndims()
+    }
+
+    async fn embed_texts(
+        &self,
+        texts: impl IntoIterator<Item = String> + WasmCompatSend,
+    ) -> Result<Vec<Embedding>, EmbeddingError> {
+        let texts = texts.into_iter().collect::<Vec<_>>();
+        let ndims = self.model.ndims();
+        let keys = texts
+            .iter()
+            .map(|text| cache_key(&self.provider, &self.model_name, ndims, text))
+            .collect::<Vec<_>>();
+
+        let cached = self.cache.get_many(&keys).await.unwrap_or_else(|e| {
+            tracing::warn!(target: "rig", "Embedding cache lookup failed: {e}");
+            vec![None; keys.len()]
+        });
+
+        // Embed each missing text once, even if it appears several times in the request
+        let mut missing: HashMap<String, usize> = HashMap::new();
+        let mut missing_texts = Vec::new();
+        for (text, vec) in texts.iter().zip(cached.iter()) {
+            if vec.is_none() && !missing.contains_key(text.as_str()) {
+                missing.insert(text.clone(), missing_texts.len());
+                missing_texts.push(text.clone());
+            }
+        }
+
+        self.hits.fetch_add(
+            cached.iter().filter(|vec| vec.is_some()).count() as u64,
+            Ordering::Relaxed,
+        );
+        self.misses
+            .fetch_add(missing_texts.len() as u64, Ordering::Relaxed);
+
+        let embedded = if missing_texts.is_empty() {
+            Vec::new()
+        } else {
+            self.model.embed_texts(missing_texts.clone()).await?
+        };
+
+        let entries = missing_texts
+            .iter()
+            .zip(embedded.iter())
+            .map(|(text, embedding)| {
+                (
+                    cache_key(&self.provider, &self.model_name, ndims, text),
+                    embedding.vec.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        if !entries.is_empty()
+            && let Err(e) = self.cache.insert_many(entries).await
+        {
+            tracing::warn!(target: "rig", "Embedding cache insert failed: {e}");
+        }
+
+        texts
+            .into_iter()
+            .zip(cached)
+            .map(|(text, vec)| {
+                let vec = match vec {
+                    Some(vec) => vec,
+                    None => embedded
+                        .get(missing[text.as_str()])
+                        .ok_or_else(|| {
+                            EmbeddingError::ResponseError(
+                                "Response contains fewer embeddings than requested".to_string(),
+                            )
+                        })?
+                        .vec
+                        .clone(),
+                };
+                Ok(Embedding {
+                    document: text,
+                    vec,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{
+        CachedEmbeddingModel, EmbeddingCache, FileEmbeddingCache, LruEmbeddingCache, cache_key,
+    };
+    use crate::{
+        client::Nothing,
+        embeddings::{Embedding, EmbeddingError, EmbeddingModel},
+    };
+
+    #[derive(Default)]
+    struct Model {
+        texts_embedded: AtomicUsize,
+    }
+
+    impl EmbeddingModel for Model {
+        const MAX_DOCUMENTS: usize = 5;
+
+        type Client = Nothing;
+
+        fn make(_: &Self::Client, _: impl Into<String>, _: Option<usize>) -> Self {
+            Self::default()
+        }
+
+        fn ndims(&self) -> usize {
+            1
+        }
+
+        async fn embed_texts(
+            &self,
+            texts: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<Embedding>, EmbeddingError> {
+            Ok(texts
+                .into_iter()
+                .map(|text| {
+                    self.texts_embedded.fetch_add(1, Ordering::SeqCst);
+                    Embedding {
+                        vec: vec![text.len() as f64],
+                        document: text,
+                    }
+                })
+                .collect())
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        assert_eq!(cache_key("p", "m", 1, "a"), cache_key("p", "m", 1, "a"));
+        assert_ne!(cache_key("p", "m", 1, "a"), cache_key("p", "m", 2, "a"));
+        assert_ne!(cache_key("p", "m", 1, "a"), cache_key("p", "n", 1, "a"));
+    }
+
+    #[tokio::test]
+    async fn test_cached_embedding_model() {
+        let model = CachedEmbeddingModel::new(
+            Model::default(),
+            "test",
+            "model",
+            LruEmbeddingCache::new(10),
+        );
+
+        let embeddings = model
+            .embed_texts(vec!["a".to_string(), "bb".to_string(), "a".to_string()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            embeddings
+                .iter()
+                .map(|embedding| embedding.vec[0])
+                .collect::<Vec<_>>(),
+            vec![1.0, 2.0, 1.0]
+        );
+        assert_eq!(model.inner().texts_embedded.load(Ordering::SeqCst), 2);
+
+        let embedding = model.embed_text("bb").await.unwrap();
+
+        assert_eq!(embedding.document, "bb");
+        assert_eq!(embedding.vec, vec![2.0]);
+        assert_eq!(model.inner().texts_embedded.load(Ordering::SeqCst), 2);
+        assert_eq!(model.hits(), 1);
+        assert_eq!(model.misses(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction() {
+        let cache = LruEmbeddingCache::new(2);
+
+        cache
+            .insert_many(vec![("a".into(), vec![1.0]), ("b".into(), vec![2.0])])
+            .await
+            .unwrap();
+
+        // Using "a" makes "b" the least recently used entry
+        cache.get_many(&["a".to_string()]).await.unwrap();
+        cache
+            .insert_many(vec![("c".into(), vec![3.0])])
+            .await
+            .unwrap();
+
+        let keys = ["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(
+            cache.get_many(&keys).await.unwrap(),
+            vec![Some(vec![1.0]), None, Some(vec![3.0])]
+        );
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_cache() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("embeddings.jsonl");
+
+        let cache = FileEmbeddingCache::open(&path).unwrap();
+        cache
+            .insert_many(vec![("a".into(), vec![1.0, 2.0])])
+            .await
+            .unwrap();
+        drop(cache);
+
+        let cache = FileEmbeddingCache::open(&path).unwrap();
+        assert_eq!(
+            cache
+                .get_many(&["a".to_string(), "b".to_string()])
+                .await
+                .unwrap(),
+            vec![Some(vec![1.0, 2.0]), None]
+        );
+    }
+}
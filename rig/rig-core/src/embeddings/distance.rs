@@ -73,9 +73,220 @@ impl VectorDistance for crate::embeddings::Embedding {
     }
 }
 
+#[cfg(not(feature = "rayon"))]
+impl VectorDistance for crate::embeddings::F32Embedding {
+    fn dot_product(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (x * y) as f64)
+            .sum()
+    }
+
+    fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
+        let dot_product = self.dot_product(other);
+
+        if normalized {
+            dot_product
+        } else {
+            let magnitude1: f64 = self.vec.iter().map(|x| (x * x) as f64).sum::<f64>().sqrt();
+            let magnitude2: f64 = other.vec.iter().map(|x| (x * x) as f64).sum::<f64>().sqrt();
+
+            dot_product / (magnitude1 * magnitude2)
+        }
+    }
+
+    fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+        let cosine_sim = self.cosine_similarity(other, normalized);
+        cosine_sim.acos() / std::f64::consts::PI
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| ((x - y) as f64).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn manhattan_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (x - y).abs() as f64)
+            .sum()
+    }
+
+    fn chebyshev_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (x - y).abs() as f64)
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+impl VectorDistance for crate::embeddings::Int8Embedding {
+    fn dot_product(&self, other: &Self) -> f64 {
+        let dot: i64 = self
+            .vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| *x as i64 * *y as i64)
+            .sum();
+
+        dot as f64 * self.scale as f64 * other.scale as f64
+    }
+
+    fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
+        let dot_product = self.dot_product(other);
+
+        if normalized {
+            dot_product
+        } else {
+            let magnitude1: f64 = self.vec.iter().map(|x| (*x as i64).pow(2)).sum::<i64>() as f64;
+            let magnitude2: f64 = other.vec.iter().map(|x| (*x as i64).pow(2)).sum::<i64>() as f64;
+
+            dot_product
+                / ((magnitude1.sqrt() * self.scale as f64)
+                    * (magnitude2.sqrt() * other.scale as f64))
+        }
+    }
+
+    fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+        let cosine_sim = self.cosine_similarity(other, normalized);
+        cosine_sim.acos() / std::f64::consts::PI
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    fn manhattan_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).abs())
+            .sum()
+    }
+
+    fn chebyshev_distance(&self, other: &Self) -> f64 {
+        self.vec
+            .iter()
+            .zip(other.vec.iter())
+            .map(|(x, y)| (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).abs())
+            .fold(0.0, f64::max)
+    }
+}
+
+#[cfg(not(feature = "rayon"))]
+impl VectorDistance for crate::embeddings::BinaryEmbedding {
+    fn dot_product(&self, other: &Self) -> f64 {
+        self.ndims as f64 - 2.0 * self.hamming_distance(other) as f64
+    }
+
+    fn cosine_similarity(&self, other: &Self, _normalized: bool) -> f64 {
+        // Every component is +1 or -1, so the magnitude of both vectors is sqrt(ndims).
+        self.dot_product(other) / self.ndims as f64
+    }
+
+    fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+        let cosine_sim = self.cosine_similarity(other, normalized);
+        cosine_sim.acos() / std::f64::consts::PI
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f64 {
+        2.0 * (self.hamming_distance(other) as f64).sqrt()
+    }
+
+    fn manhattan_distance(&self, other: &Self) -> f64 {
+        2.0 * self.hamming_distance(other) as f64
+    }
+
+    fn chebyshev_distance(&self, other: &Self) -> f64 {
+        if self.hamming_distance(other) > 0 {
+            2.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Vectors using the same representation are compared directly. Vectors using different
+/// representations are dequantized to `f32` before being compared.
+impl VectorDistance for crate::embeddings::QuantizedVector {
+    fn dot_product(&self, other: &Self) -> f64 {
+        use crate::embeddings::QuantizedVector::*;
+        match (self, other) {
+            (F32(a), F32(b)) => a.dot_product(b),
+            (Int8(a), Int8(b)) => a.dot_product(b),
+            (Binary(a), Binary(b)) => a.dot_product(b),
+            (a, b) => dequantized(a).dot_product(&dequantized(b)),
+        }
+    }
+
+    fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
+        use crate::embeddings::QuantizedVector::*;
+        match (self, other) {
+            (F32(a), F32(b)) => a.cosine_similarity(b, normalized),
+            (Int8(a), Int8(b)) => a.cosine_similarity(b, normalized),
+            (Binary(a), Binary(b)) => a.cosine_similarity(b, normalized),
+            (a, b) => dequantized(a).cosine_similarity(&dequantized(b), normalized),
+        }
+    }
+
+    fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+        let cosine_sim = self.cosine_similarity(other, normalized);
+        cosine_sim.acos() / std::f64::consts::PI
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f64 {
+        use crate::embeddings::QuantizedVector::*;
+        match (self, other) {
+            (F32(a), F32(b)) => a.euclidean_distance(b),
+            (Int8(a), Int8(b)) => a.euclidean_distance(b),
+            (Binary(a), Binary(b)) => a.euclidean_distance(b),
+            (a, b) => dequantized(a).euclidean_distance(&dequantized(b)),
+        }
+    }
+
+    fn manhattan_distance(&self, other: &Self) -> f64 {
+        use crate::embeddings::QuantizedVector::*;
+        match (self, other) {
+            (F32(a), F32(b)) => a.manhattan_distance(b),
+            (Int8(a), Int8(b)) => a.manhattan_distance(b),
+            (Binary(a), Binary(b)) => a.manhattan_distance(b),
+            (a, b) => dequantized(a).manhattan_distance(&dequantized(b)),
+        }
+    }
+
+    fn chebyshev_distance(&self, other: &Self) -> f64 {
+        use crate::embeddings::QuantizedVector::*;
+        match (self, other) {
+            (F32(a), F32(b)) => a.chebyshev_distance(b),
+            (Int8(a), Int8(b)) => a.chebyshev_distance(b),
+            (Binary(a), Binary(b)) => a.chebyshev_distance(b),
+            (a, b) => dequantized(a).chebyshev_distance(&dequantized(b)),
+        }
+    }
+}
+
+fn dequantized(vector: &crate::embeddings::QuantizedVector) -> crate::embeddings::F32Embedding {
+    crate::embeddings::F32Embedding::from(vector.dequantize())
+}
+
 #[cfg(feature = "rayon")]
 mod rayon {
-    use crate::embeddings::{Embedding, distance::VectorDistance};
+    use crate::embeddings::{
+        BinaryEmbedding, Embedding, F32Embedding, Int8Embedding, distance::VectorDistance,
+    };
     use rayon::prelude::*;
 
     impl VectorDistance for Embedding {
@@ -130,12 +341,185 @@ mod rayon {
                 .fold(0.0, f64::max)
         }
     }
+
+    impl VectorDistance for F32Embedding {
+        fn dot_product(&self, other: &Self) -> f64 {
+            self.vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| (x * y) as f64)
+                .sum()
+        }
+
+        fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
+            let dot_product = self.dot_product(other);
+
+            if normalized {
+                dot_product
+            } else {
+                let magnitude1: f64 = self
+                    .vec
+                    .par_iter()
+                    .map(|x| (x * x) as f64)
+                    .sum::<f64>()
+                    .sqrt();
+                let magnitude2: f64 = other
+                    .vec
+                    .par_iter()
+                    .map(|x| (x * x) as f64)
+                    .sum::<f64>()
+                    .sqrt();
+
+                dot_product / (magnitude1 * magnitude2)
+            }
+        }
+
+        fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+            let cosine_sim = self.cosine_similarity(other, normalized);
+            cosine_sim.acos() / std::f64::consts::PI
+        }
+
+        fn euclidean_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| ((x - y) as f64).powi(2))
+                .sum::<f64>()
+                .sqrt()
+        }
+
+        fn manhattan_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| (x - y).abs() as f64)
+                .sum()
+        }
+
+        fn chebyshev_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .iter()
+                .zip(other.vec.iter())
+                .map(|(x, y)| (x - y).abs() as f64)
+                .fold(0.0, f64::max)
+        }
+    }
+
+    impl VectorDistance for Int8Embedding {
+        fn dot_product(&self, other: &Self) -> f64 {
+            let dot: i64 = self
+                .vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| *x as i64 * *y as i64)
+                .sum();
+
+            dot as f64 * self.scale as f64 * other.scale as f64
+        }
+
+        fn cosine_similarity(&self, other: &Self, normalized: bool) -> f64 {
+            let dot_product = self.dot_product(other);
+
+            if normalized {
+                dot_product
+            } else {
+                let magnitude1 =
+                    self.vec.par_iter().map(|x| (*x as i64).pow(2)).sum::<i64>() as f64;
+                let magnitude2 = other
+                    .vec
+                    .par_iter()
+                    .map(|x| (*x as i64).pow(2))
+                    .sum::<i64>() as f64;
+
+                dot_product
+                    / ((magnitude1.sqrt() * self.scale as f64)
+                        * (magnitude2.sqrt() * other.scale as f64))
+            }
+        }
+
+        fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+            let cosine_sim = self.cosine_similarity(other, normalized);
+            cosine_sim.acos() / std::f64::consts::PI
+        }
+
+        fn euclidean_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| {
+                    (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).powi(2)
+                })
+                .sum::<f64>()
+                .sqrt()
+        }
+
+        fn manhattan_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .par_iter()
+                .zip(other.vec.par_iter())
+                .map(|(x, y)| {
+                    (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).abs()
+                })
+                .sum()
+        }
+
+        fn chebyshev_distance(&self, other: &Self) -> f64 {
+            self.vec
+                .iter()
+                .zip(other.vec.iter())
+                .map(|(x, y)| {
+                    (*x as f64 * self.scale as f64 - *y as f64 * other.scale as f64).abs()
+                })
+                .fold(0.0, f64::max)
+        }
+    }
+
+    impl VectorDistance for BinaryEmbedding {
+        fn dot_product(&self, other: &Self) -> f64 {
+            let hamming: u32 = self
+                .bits
+                .par_iter()
+                .zip(other.bits.par_iter())
+                .map(|(a, b)| (a ^ b).count_ones())
+                .sum();
+
+            self.ndims as f64 - 2.0 * hamming as f64
+        }
+
+        fn cosine_similarity(&self, other: &Self, _normalized: bool) -> f64 {
+            // Every component is +1 or -1, so the magnitude of both vectors is sqrt(ndims).
+            self.dot_product(other) / self.ndims as f64
+        }
+
+        fn angular_distance(&self, other: &Self, normalized: bool) -> f64 {
+            let cosine_sim = self.cosine_similarity(other, normalized);
+            cosine_sim.acos() / std::f64::consts::PI
+        }
+
+        fn euclidean_distance(&self, other: &Self) -> f64 {
+            2.0 * (self.hamming_distance(other) as f64).sqrt()
+        }
+
+        fn manhattan_distance(&self, other: &Self) -> f64 {
+            2.0 * self.hamming_distance(other) as f64
+        }
+
+        fn chebyshev_distance(&self, other: &Self) -> f64 {
+            if self.hamming_distance(other) > 0 {
+                2.0
+            } else {
+                0.0
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::VectorDistance;
-    use crate::embeddings::Embedding;
+    use crate::embeddings::{
+        BinaryEmbedding, Embedding, F32Embedding, Int8Embedding, QuantizedVector,
+    };
 
     fn embeddings() -> (Embedding, Embedding) {
         let embedding_1 = Embedding {
@@ -198,4 +582,62 @@ mod tests {
 
         assert_eq!(embedding_1.chebyshev_distance(&embedding_2), 4.0)
     }
+
+    #[test]
+    fn test_f32_distances() {
+        let (embedding_1, embedding_2) = embeddings();
+        let (embedding_1, embedding_2) = (
+            F32Embedding::from(&embedding_1),
+            F32Embedding::from(&embedding_2),
+        );
+
+        assert_eq!(embedding_1.dot_product(&embedding_2), 32.0);
+        assert!(
+            (embedding_1.cosine_similarity(&embedding_2, false) - 0.9875414397573881).abs() < 1e-6
+        );
+        assert_eq!(embedding_1.euclidean_distance(&embedding_2), 5.0);
+        assert_eq!(embedding_1.manhattan_distance(&embedding_2), 7.0);
+        assert_eq!(embedding_1.chebyshev_distance(&embedding_2), 4.0);
+    }
+
+    #[test]
+    fn test_int8_distances() {
+        let (embedding_1, embedding_2) = embeddings();
+        let (embedding_1, embedding_2) = (
+            Int8Embedding::quantize(&embedding_1.vec),
+            Int8Embedding::quantize(&embedding_2.vec),
+        );
+
+        assert!((embedding_1.dot_product(&embedding_2) - 32.0).abs() < 0.1);
+        assert!(
+            (embedding_1.cosine_similarity(&embedding_2, false) - 0.9875414397573881).abs() < 1e-3
+        );
+        assert!((embedding_1.euclidean_distance(&embedding_2) - 5.0).abs() < 0.05);
+        assert!((embedding_1.manhattan_distance(&embedding_2) - 7.0).abs() < 0.05);
+        assert!((embedding_1.chebyshev_distance(&embedding_2) - 4.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_binary_distances() {
+        let embedding_1 = BinaryEmbedding::quantize(&[1.0, -2.0, 3.0, -4.0]);
+        let embedding_2 = BinaryEmbedding::quantize(&[1.0, 5.0, 7.0, -0.5]);
+
+        assert_eq!(embedding_1.hamming_distance(&embedding_2), 1);
+        assert_eq!(embedding_1.dot_product(&embedding_2), 2.0);
+        assert_eq!(embedding_1.cosine_similarity(&embedding_2, false), 0.5);
+        assert_eq!(embedding_1.euclidean_distance(&embedding_2), 2.0);
+        assert_eq!(embedding_1.manhattan_distance(&embedding_2), 2.0);
+        assert_eq!(embedding_1.chebyshev_distance(&embedding_2), 2.0);
+    }
+
+    #[test]
+    fn test_mixed_quantized_vectors() {
+        let (embedding_1, embedding_2) = embeddings();
+        let embedding_1 = QuantizedVector::F32(F32Embedding::from(&embedding_1));
+        let embedding_2 = QuantizedVector::Int8(Int8Embedding::quantize(&embedding_2.vec));
+
+        assert!(
+            (embedding_1.cosine_similarity(&embedding_2, false) - 0.9875414397573881).abs() < 1e-3
+        );
+    }
 }
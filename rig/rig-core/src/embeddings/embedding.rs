@@ -6,6 +6,7 @@
 //! Finally, the module defines the [EmbeddingError] enum, which represents various errors that
 //! can occur during embedding generation or processing.
 
+use super::F32Embedding;
 use crate::wasm_compat::WasmBoxedFuture;
 use crate::{http_client, wasm_compat::*};
 use serde::{Deserialize, Serialize};
@@ -73,6 +74,26 @@ pub trait EmbeddingModel: WasmCompatSend + WasmCompatSync {
                 .expect("There should be at least one embedding"))
         }
     }
+
+    /// Embed multiple text documents in a single request, returning single precision vectors
+    /// in the same order as the input.
+    ///
+    /// The default implementation narrows the output of [EmbeddingModel::embed_texts].
+    /// Providers that can return `f32` vectors natively override it to skip the `f64` round-trip.
+    fn embed_texts_f32(
+        &self,
+        texts: impl IntoIterator<Item = String> + WasmCompatSend,
+    ) -> impl std::future::Future<Output = Result<Vec<F32Embedding>, EmbeddingError>> + WasmCompatSend
+    {
+        async {
+            Ok(self
+                .embed_texts(texts)
+                .await?
+                .iter()
+                .map(F32Embedding::from)
+                .collect())
+        }
+    }
 }
 
 #[deprecated(
@@ -0,0 +1,713 @@
+//! The module defines the [IngestPipeline] struct, which embeds a stream of documents batch by
+//! batch and writes each batch straight into a vector store implementing [InsertDocuments].
+//!
+//! Unlike the [EmbeddingsBuilder](super::EmbeddingsBuilder), which embeds everything in memory
+//! in one go, the pipeline:
+//! - content-hashes every document and skips the ones recorded in its [IngestCheckpoint],
+//! - embeds batches with a configurable concurrency and request rate,
+//! - retries failed batches according to a [RetryPolicy],
+//! - records each batch in the checkpoint once it is stored, so that an interrupted ingestion
+//!   can be resumed by running it again with the same checkpoint.
+//!
+//! # Example
+//! ```rust,ignore
+//! use rig::embeddings::ingest::{FileCheckpoint, IngestPipeline};
+//!
+//! let checkpoint = FileCheckpoint::open("ingest.checkpoint")?;
+//!
+//! let report = IngestPipeline::new(model, vector_store)
+//!     .concurrency(4)
+//!     .requests_per_minute(300)
+//!     .checkpoint(checkpoint)
+//!     .ingest(futures::stream::iter(documents))
+//!     .await?;
+//!
+//! println!("Embedded {} documents", report.documents_embedded);
+//! ```
+
+use std::{
+    collections::HashSet,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
+
+use futures::{Stream, StreamExt, TryStreamExt};
+use futures_timer::Delay;
+use serde::Serialize;
+
+use crate::{
+    OneOrMany,
+    embeddings::{Embed, EmbedError, Embedding, EmbeddingError, EmbeddingModel, TextEmbedder},
+    http_client::retry::{ExponentialBackoff, RetryPolicy, retry_with},
+    vector_store::{InsertDocuments, VectorStoreError},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum IngestError {
+    /// Error extracting the texts to embed from a document
+    #[error("EmbedError: {0}")]
+    EmbedError(#[from] EmbedError),
+
+    /// Error returned by the embedding model once retries are exhausted
+    #[error("EmbeddingError: {0}")]
+    EmbeddingError(#[from] EmbeddingError),
+
+    /// Error writing a batch into the vector store
+    #[error("VectorStoreError: {0}")]
+    VectorStoreError(#[from] VectorStoreError),
+
+    /// Error reading or writing the checkpoint
+    #[error("CheckpointError: {0}")]
+    CheckpointError(String),
+}
+
+/// Records which documents have already been embedded and stored, keyed by [content_hash].
+pub trait IngestCheckpoint: WasmCompatSend + WasmCompatSync {
+    /// Whether a document with the given content hash was already ingested.
+    fn contains(
+        &self,
+        hash: &str,
+    ) -> impl std::future::Future<Output = Result<bool, IngestError>> + WasmCompatSend;
+
+    /// Record the content hashes of documents that were successfully ingested.
+    fn record(
+        &self,
+        hashes: &[String],
+    ) -> impl std::future::Future<Output = Result<(), IngestError>> + WasmCompatSend;
+}
+
+/// Checkpoint kept in memory. Skips duplicates within a process, but does not survive a crash.
+#[derive(Debug, Default)]
+pub struct InMemoryCheckpoint {
+    hashes: Mutex<HashSet<String>>,
+}
+
+impl InMemoryCheckpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of recorded documents.
+    pub fn len(&self) -> usize {
+        self.hashes.lock().expect("checkpoint lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl IngestCheckpoint for InMemoryCheckpoint {
+    async fn contains(&self, hash: &str) -> Result<bool, IngestError> {
+        Ok(self
+            .hashes
+            .lock()
+            .map_err(|e| IngestError::CheckpointError(e.to_string()))?
+            .contains(hash))
+    }
+
+    async fn record(&self, hashes: &[String]) -> Result<(), IngestError> {
+        self.hashes
+            .lock()
+            .map_err(|e| IngestError::CheckpointError(e.to_string()))?
+            .extend(hashes.iter().cloned());
+
+        Ok(())
+    }
+}
+
+/// Checkpoint persisted to an append-only file with one content hash per line.
+/// Reopening the same file resumes an interrupted ingestion.
+///
+/// Writes run on tokio's blocking thread pool, so recording requires a tokio runtime.
+#[cfg(not(target_family = "wasm"))]
+#[derive(Debug)]
+pub struct FileCheckpoint {
+    hashes: Mutex<HashSet<String>>,
+    file: Arc<Mutex<std::fs::File>>,
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl FileCheckpoint {
+    /// Open the checkpoint file at `path`, creating it if it doesn't exist.
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self, IngestError> {
+        let path = path.as_ref();
+
+        let hashes = match std::fs::read_to_string(path) {
+            Ok(contents) => contents
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(e) => return Err(IngestError::CheckpointError(e.to_string())),
+        };
+
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| IngestError::CheckpointError(e.to_string()))?;
+
+        Ok(Self {
+            hashes: Mutex::new(hashes),
+            file: Arc::new(Mutex::new(file)),
+        })
+    }
+
+    /// Number of recorded documents.
+    pub fn len(&self) -> usize {
+        self.hashes.lock().expect("checkpoint lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+impl IngestCheckpoint for FileCheckpoint {
+    async fn contains(&self, hash: &str) -> Result<bool, IngestError> {
+        Ok(self
+            .hashes
+            .lock()
+            .map_err(|e| IngestError::CheckpointError(e.to_string()))?
+            .contains(hash))
+    }
+
+    async fn record(&self, hashes: &[String]) -> Result<(), IngestError> {
+        use std::io::Write;
+
+        let lines = hashes
+            .iter()
+            .map(|hash| format!("{hash}\n"))
+            .collect::<String>();
+
+        // The file is only locked on the blocking thread, never across an await point.
+        let file = self.file.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut file = file.lock().map_err(|e| e.to_string())?;
+            file.write_all(lines.as_bytes())
+                .and_then(|_| file.sync_data())
+                .map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| IngestError::CheckpointError(e.to_string()))?
+        .map_err(IngestError::CheckpointError)?;
+
+        self.hashes
+            .lock()
+            .map_err(|e| IngestError::CheckpointError(e.to_string()))?
+            .extend(hashes.iter().cloned());
+
+        Ok(())
+    }
+}
+
+/// Stable 128-bit FNV-1a hash of the texts of a document, as a hex string.
+/// The texts are length-prefixed so that `["ab", "c"]` and `["a", "bc"]` hash differently.
+pub fn content_hash<S: AsRef<str>>(texts: &[S]) -> String {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013B;
+
+    let mut hash = OFFSET_BASIS;
+    let mut write = |bytes: &[u8]| {
+        for byte in bytes {
+            hash ^= *byte as u128;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+
+    for text in texts {
+        let text = text.as_ref();
+        write(&(text.len() as u64).to_le_bytes());
+        write(text.as_bytes());
+    }
+
+    format!("{hash:032x}")
+}
+
+/// Summary of an ingestion run.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IngestReport {
+    /// Documents embedded and written to the store during this run.
+    pub documents_embedded: usize,
+    /// Documents skipped because they were already in the checkpoint, appeared twice in the
+    /// stream, or had no text to embed.
+    pub documents_skipped: usize,
+    /// Texts sent to the embedding model.
+    pub texts_embedded: usize,
+    /// Batches written to the store.
+    pub batches: usize,
+    /// Embedding requests that were retried.
+    pub retries: usize,
+}
+
+/// Document waiting to be embedded, along with its texts and content hash.
+struct PendingDocument<T> {
+    document: T,
+    texts: Vec<String>,
+    hash: String,
+}
+
+/// Streaming, resumable embedding ingestion into a vector store.
+/// See the [module documentation](self) for details.
+pub struct IngestPipeline<M, S, C = InMemoryCheckpoint, R = ExponentialBackoff> {
+    model: M,
+    store: S,
+    checkpoint: C,
+    retry_policy: R,
+    batch_size: Option<usize>,
+    concurrency: usize,
+    requests_per_minute: Option<u32>,
+}
+
+impl<M, S> IngestPipeline<M, S>
+where
+    M: EmbeddingModel,
+    S: InsertDocuments,
+{
+    /// Create a new ingestion pipeline writing into `store`.
+    ///
+    /// Defaults to an in-memory checkpoint, a concurrency of 1, no rate limit, and retrying
+    /// failed batches up to 3 times with exponential backoff.
+    pub fn new(model: M, store: S) -> Self {
+        Self {
+            model,
+            store,
+            checkpoint: InMemoryCheckpoint::default(),
+            retry_policy: ExponentialBackoff::new(
+                Duration::from_millis(500),
+                2.0,
+                Some(Duration::from_secs(30)),
+                Some(3),
+            ),
+            batch_size: None,
+            concurrency: 1,
+            requests_per_minute: None,
+        }
+    }
+}
+
+impl<M, S, C, R> IngestPipeline<M, S, C, R>
+where
+    M: EmbeddingModel,
+    S: InsertDocuments,
+    C: IngestCheckpoint,
+    R: RetryPolicy + WasmCompatSend + WasmCompatSync,
+{
+    /// Set the checkpoint used to skip already ingested documents and to record progress.
+    pub fn checkpoint<C2: IngestCheckpoint>(self, checkpoint: C2) -> IngestPipeline<M, S, C2, R> {
+        IngestPipeline {
+            model: self.model,
+            store: self.store,
+            checkpoint,
+            retry_policy: self.retry_policy,
+            batch_size: self.batch_size,
+            concurrency: self.concurrency,
+            requests_per_minute: self.requests_per_minute,
+        }
+    }
+
+    /// Set the policy used to retry failed embedding requests.
+    pub fn retry_policy<R2>(self, retry_policy: R2) -> IngestPipeline<M, S, C, R2>
+    where
+        R2: RetryPolicy + WasmCompatSend + WasmCompatSync,
+    {
+        IngestPipeline {
+            model: self.model,
+            store: self.store,
+            checkpoint: self.checkpoint,
+            retry_policy,
+            batch_size: self.batch_size,
+            concurrency: self.concurrency,
+            requests_per_minute: self.requests_per_minute,
+        }
+    }
+
+    /// Set the maximum number of texts per batch. Defaults to, and is capped at, [EmbeddingModel::MAX_DOCUMENTS].
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = Some(batch_size.max(1));
+        self
+    }
+
+    /// Set the number of batches embedded concurrently.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Limit the number of batches started per minute.
+    pub fn requests_per_minute(mut self, requests_per_minute: u32) -> Self {
+        self.requests_per_minute = Some(requests_per_minute.max(1));
+        self
+    }
+
+    /// Get a reference to the checkpoint.
+    pub fn get_checkpoint(&self) -> &C {
+        &self.checkpoint
+    }
+
+    /// Embed the documents of the stream and write them into the vector store.
+    ///
+    /// Stops at the first batch that still fails after retries. Batches written before the
+    /// failure are recorded in the checkpoint, so running the pipeline again with the same
+    /// checkpoint and input resumes where it stopped.
+    pub async fn ingest<T>(
+        &self,
+        documents: impl Stream<Item = T> + WasmCompatSend,
+    ) -> Result<IngestReport, IngestError>
+    where
+        T: Embed + Serialize + WasmCompatSend,
+    {
+        let batch_size = self
+            .batch_size
+            .unwrap_or(M::MAX_DOCUMENTS)
+            .min(M::MAX_DOCUMENTS)
+            .max(1);
+        let interval = self
+            .requests_per_minute
+            .map(|rpm| Duration::from_secs(60) / rpm);
+
+        let skipped = AtomicUsize::new(0);
+        let batches = self.batches(documents, batch_size, &skipped);
+
+        let mut report = batches
+            .enumerate()
+            .then(|(i, batch)| async move {
+                // Space out the start of each batch to respect the rate limit
+                if let Some(interval) = interval
+                    && i > 0
+                {
+                    Delay::new(interval).await;
+                }
+                batch
+            })
+            .map_ok(|batch| self.ingest_batch(batch))
+            .try_buffer_unordered(self.concurrency)
+            .try_fold(IngestReport::default(), |mut report, batch| async move {
+                report.documents_embedded += batch.documents_embedded;
+                report.texts_embedded += batch.texts_embedded;
+                report.batches += batch.batches;
+                report.retries += batch.retries;
+                Ok(report)
+            })
+            .await?;
+
+        report.documents_skipped = skipped.into_inner();
+
+        tracing::info!(target: "rig",
+            "Ingestion finished: {} documents embedded, {} skipped, {} retries",
+            report.documents_embedded,
+            report.documents_skipped,
+            report.retries
+        );
+
+        Ok(report)
+    }
+
+    /// Group the documents that still need embedding into batches of at most `batch_size` texts.
+    /// A document with more texts than `batch_size` gets a batch of its own.
+    fn batches<'a, T>(
+        &'a self,
+        documents: impl Stream<Item = T> + WasmCompatSend + 'a,
+        batch_size: usize,
+        skipped: &'a AtomicUsize,
+    ) -> impl Stream<Item = Result<Vec<PendingDocument<T>>, IngestError>> + WasmCompatSend + 'a
+    where
+        T: Embed + WasmCompatSend + 'a,
+    {
+        async_stream::try_stream! {
+            let mut seen = HashSet::new();
+            let mut batch = Vec::new();
+            let mut batch_texts = 0;
+
+            futures::pin_mut!(documents);
+            while let Some(document) = documents.next().await {
+                let mut embedder = TextEmbedder::default();
+                document.embed(&mut embedder)?;
+                let texts = embedder.texts;
+                let hash = content_hash(&texts);
+
+                if texts.is_empty() || seen.contains(&hash) || self.checkpoint.contains(&hash).await? {
+                    skipped.fetch_add(1, Ordering::Relaxed);
+                    continue;
+                }
+                seen.insert(hash.clone());
+
+                if batch_texts > 0 && batch_texts + texts.len() > batch_size {
+                    batch_texts = 0;
+                    yield std::mem::take(&mut batch);
+                }
+
+                batch_texts += texts.len();
+                batch.push(PendingDocument { document, texts, hash });
+            }
+
+            if !batch.is_empty() {
+                yield batch;
+            }
+        }
+    }
+
+    async fn ingest_batch<T>(
+        &self,
+        batch: Vec<PendingDocument<T>>,
+    ) -> Result<IngestReport, IngestError>
+    where
+        T: Embed + Serialize + WasmCompatSend,
+    {
+        let texts = batch
+            .iter()
+            .flat_map(|pending| pending.texts.iter().cloned())
+            .collect::<Vec<_>>();
+        let texts_embedded = texts.len();
+
+        let mut retries = 0;
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(M::MAX_DOCUMENTS) {
+            let (chunk_embeddings, chunk_retries) = self.embed_with_retry(chunk).await?;
+            embeddings.extend(chunk_embeddings);
+            retries += chunk_retries;
+        }
+
+        let mut hashes = Vec::with_capacity(batch.len());
+        let mut embeddings = embeddings.into_iter();
+        let documents = batch
+            .into_iter()
+            .map(|pending| {
+                hashes.push(pending.hash);
+                let document_embeddings = embeddings.by_ref().take(pending.texts.len());
+                let document_embeddings = OneOrMany::many(document_embeddings)
+                    .expect("Documents without texts are skipped before batching");
+                (pending.document, document_embeddings)
+            })
+            .collect::<Vec<_>>();
+
+        let documents_embedded = documents.len();
+        self.store.insert_documents(documents).await?;
+        self.checkpoint.record(&hashes).await?;
+
+        Ok(IngestReport {
+            documents_embedded,
+            documents_skipped: 0,
+            texts_embedded,
+            batches: 1,
+            retries,
+        })
+    }
+
+    /// Embed a chunk of texts, retrying according to the retry policy.
+    /// Returns the embeddings and the number of retries.
+    async fn embed_with_retry(
+        &self,
+        texts: &[String],
+    ) -> Result<(Vec<Embedding>, usize), EmbeddingError> {
+        retry_with(&self.retry_policy, || async {
+            let embeddings = self.model.embed_texts(texts.to_vec()).await?;
+            if embeddings.len() == texts.len() {
+                Ok(embeddings)
+            } else {
+                Err(EmbeddingError::ResponseError(format!(
+                    "Expected {} embeddings, got {}",
+                    texts.len(),
+                    embeddings.len()
+                )))
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Mutex,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+
+    use serde::Serialize;
+
+    use super::{InMemoryCheckpoint, IngestCheckpoint, IngestError, IngestPipeline, content_hash};
+    use crate::{
+        Embed, OneOrMany,
+        client::Nothing,
+        embeddings::{Embedding, EmbeddingError, EmbeddingModel},
+        http_client::retry::{Constant, Never},
+        vector_store::{InsertDocuments, VectorStoreError},
+    };
+
+    /// Fails every `fail_every`-th request.
+    #[derive(Default)]
+    struct FlakyModel {
+        requests: AtomicUsize,
+        fail_every: Option<usize>,
+    }
+
+    impl EmbeddingModel for FlakyModel {
+        const MAX_DOCUMENTS: usize = 2;
+
+        type Client = Nothing;
+
+        fn make(_: &Self::Client, _: impl Into<String>, _: Option<usize>) -> Self {
+            Self::default()
+        }
+
+        fn ndims(&self) -> usize {
+            1
+        }
+
+        async fn embed_texts(
+            &self,
+            texts: impl IntoIterator<Item = String> + Send,
+        ) -> Result<Vec<Embedding>, EmbeddingError> {
+            let request = self.requests.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.fail_every.is_some_and(|n| request.is_multiple_of(n)) {
+                return Err(EmbeddingError::ProviderError("rate limited".to_string()));
+            }
+
+            Ok(texts
+                .into_iter()
+                .map(|text| Embedding {
+                    vec: vec![text.len() as f64],
+                    document: text,
+                })
+                .collect())
+        }
+    }
+
+    #[derive(Default)]
+    struct Store {
+        documents: Mutex<Vec<(String, OneOrMany<Embedding>)>>,
+    }
+
+    impl InsertDocuments for Store {
+        async fn insert_documents<Doc: Serialize + Embed + Send>(
+            &self,
+            documents: Vec<(Doc, OneOrMany<Embedding>)>,
+        ) -> Result<(), VectorStoreError> {
+            let mut store = self.documents.lock().unwrap();
+            for (doc, embeddings) in documents {
+                store.push((serde_json::to_string(&doc)?, embeddings));
+            }
+            Ok(())
+        }
+    }
+
+    fn documents() -> Vec<String> {
+        ["flurbo", "glarb-glarb", "linlingdong", "flurbo", "marble"]
+            .into_iter()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn test_content_hash() {
+        assert_eq!(content_hash(&["ab", "c"]), content_hash(&["ab", "c"]));
+        assert_ne!(content_hash(&["ab", "c"]), content_hash(&["a", "bc"]));
+        assert_eq!(content_hash::<&str>(&[]).len(), 32);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_skips_duplicates() {
+        let pipeline = IngestPipeline::new(FlakyModel::default(), Store::default());
+
+        let report = pipeline
+            .ingest(futures::stream::iter(documents()))
+            .await
+            .unwrap();
+
+        assert_eq!(report.documents_embedded, 4);
+        assert_eq!(report.documents_skipped, 1);
+        assert_eq!(report.batches, 2);
+        assert_eq!(pipeline.store.documents.lock().unwrap().len(), 4);
+
+        // Running again with the same checkpoint skips everything
+        let report = pipeline
+            .ingest(futures::stream::iter(documents()))
+            .await
+            .unwrap();
+
+        assert_eq!(report.documents_embedded, 0);
+        assert_eq!(report.documents_skipped, 5);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_retries_failed_batches() {
+        let model = FlakyModel {
+            fail_every: Some(2),
+            ..Default::default()
+        };
+        let pipeline = IngestPipeline::new(model, Store::default())
+            .retry_policy(Constant::new(Duration::from_millis(1), Some(1)));
+
+        let report = pipeline
+            .ingest(futures::stream::iter(documents()))
+            .await
+            .unwrap();
+
+        assert_eq!(report.documents_embedded, 4);
+        assert_eq!(report.retries, 1);
+    }
+
+    #[tokio::test]
+    async fn test_ingest_resumes_from_checkpoint() {
+        let model = FlakyModel {
+            fail_every: Some(2),
+            ..Default::default()
+        };
+        let pipeline = IngestPipeline::new(model, Store::default())
+            .retry_policy(Never)
+            .checkpoint(InMemoryCheckpoint::new());
+
+        let result = pipeline.ingest(futures::stream::iter(documents())).await;
+
+        assert!(matches!(result, Err(IngestError::EmbeddingError(_))));
+        assert_eq!(pipeline.get_checkpoint().len(), 2);
+        assert!(
+            pipeline
+                .get_checkpoint()
+                .contains(&content_hash(&["flurbo"]))
+                .await
+                .unwrap()
+        );
+
+        // The third request succeeds, so the remaining documents are embedded
+        let report = pipeline
+            .ingest(futures::stream::iter(documents()))
+            .await
+            .unwrap();
+
+        assert_eq!(report.documents_embedded, 2);
+        assert_eq!(report.documents_skipped, 3);
+        assert_eq!(pipeline.store.documents.lock().unwrap().len(), 4);
+    }
+
+    #[cfg(not(target_family = "wasm"))]
+    #[tokio::test]
+    async fn test_file_checkpoint() {
+        use super::FileCheckpoint;
+
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join("ingest.checkpoint");
+
+        let checkpoint = FileCheckpoint::open(&path).unwrap();
+        checkpoint
+            .record(&["a".to_string(), "b".to_string()])
+            .await
+            .unwrap();
+        drop(checkpoint);
+
+        let checkpoint = FileCheckpoint::open(&path).unwrap();
+        assert_eq!(checkpoint.len(), 2);
+        assert!(checkpoint.contains("b").await.unwrap());
+        assert!(!checkpoint.contains("c").await.unwrap());
+    }
+}
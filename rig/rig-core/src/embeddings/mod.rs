@@ -4,12 +4,18 @@
 //! and document similarity.
 
 pub mod builder;
+pub mod cache;
 pub mod embed;
 pub mod embedding;
+pub mod ingest;
+pub mod quantization;
 pub mod tool;
 
 pub mod distance;
 pub use builder::EmbeddingsBuilder;
 pub use embed::{Embed, EmbedError, TextEmbedder, to_texts};
 pub use embedding::*;
+pub use quantization::{
+    BinaryEmbedding, F32Embedding, Int8Embedding, Quantization, QuantizedVector,
+};
 pub use tool::ToolSchema;
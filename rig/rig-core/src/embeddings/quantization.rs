@@ -0,0 +1,249 @@
+//! Compact embedding representations used to reduce the memory footprint of vector stores.
+//!
+//! [Embedding](super::Embedding) stores its vector as `Vec<f64>`, which costs 8 bytes per dimension.
+//! This module provides three smaller representations:
+//! - [F32Embedding]: single precision floats (4 bytes per dimension).
+//! - [Int8Embedding]: symmetric scalar quantization to `i8` with a per-vector scale (1 byte per dimension).
+//! - [BinaryEmbedding]: one sign bit per dimension (1/8 byte per dimension).
+//!
+//! Each representation implements [VectorDistance](super::distance::VectorDistance), so it can be
+//! compared against another vector of the same representation. [QuantizedVector] wraps all three
+//! and is what the [InMemoryVectorStore](crate::vector_store::in_memory_store::InMemoryVectorStore)
+//! keeps when a [Quantization] is configured.
+
+use serde::{Deserialize, Serialize};
+
+use super::Embedding;
+
+/// Quantization scheme applied to embedding vectors.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Quantization {
+    /// Keep the original `f64` vectors.
+    #[default]
+    None,
+    /// Store vectors as `f32`.
+    F32,
+    /// Store vectors as `i8` with a per-vector scale.
+    Int8,
+    /// Store only the sign of each dimension, packed into bits.
+    Binary,
+}
+
+impl Quantization {
+    /// Quantize a vector with this scheme.
+    /// Returns `None` if the scheme is [Quantization::None].
+    pub fn quantize(&self, vec: &[f64]) -> Option<QuantizedVector> {
+        match self {
+            Quantization::None => None,
+            Quantization::F32 => Some(QuantizedVector::F32(F32Embedding::from(vec))),
+            Quantization::Int8 => Some(QuantizedVector::Int8(Int8Embedding::quantize(vec))),
+            Quantization::Binary => Some(QuantizedVector::Binary(BinaryEmbedding::quantize(vec))),
+        }
+    }
+}
+
+/// Embedding vector stored in single precision.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct F32Embedding {
+    pub vec: Vec<f32>,
+}
+
+impl F32Embedding {
+    /// The number of dimensions of the vector.
+    pub fn ndims(&self) -> usize {
+        self.vec.len()
+    }
+}
+
+impl From<Vec<f32>> for F32Embedding {
+    fn from(vec: Vec<f32>) -> Self {
+        Self { vec }
+    }
+}
+
+impl From<&[f64]> for F32Embedding {
+    fn from(vec: &[f64]) -> Self {
+        Self {
+            vec: vec.iter().map(|x| *x as f32).collect(),
+        }
+    }
+}
+
+impl From<&Embedding> for F32Embedding {
+    fn from(embedding: &Embedding) -> Self {
+        Self::from(embedding.vec.as_slice())
+    }
+}
+
+/// Embedding vector quantized to `i8`.
+///
+/// Each component is stored as `round(x / scale)` where `scale = max(|x|) / 127`,
+/// so the original value is approximately `q * scale`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Int8Embedding {
+    pub vec: Vec<i8>,
+    pub scale: f32,
+}
+
+impl Int8Embedding {
+    /// Quantize a vector using symmetric scalar quantization.
+    pub fn quantize(vec: &[f64]) -> Self {
+        let max = vec.iter().fold(0.0f64, |acc, x| acc.max(x.abs()));
+
+        if max == 0.0 {
+            return Self {
+                vec: vec![0; vec.len()],
+                scale: 0.0,
+            };
+        }
+
+        let scale = max / i8::MAX as f64;
+
+        Self {
+            vec: vec
+                .iter()
+                .map(|x| (x / scale).round().clamp(-127.0, 127.0) as i8)
+                .collect(),
+            scale: scale as f32,
+        }
+    }
+
+    /// Reconstruct an approximation of the original vector.
+    pub fn dequantize(&self) -> Vec<f32> {
+        self.vec.iter().map(|q| *q as f32 * self.scale).collect()
+    }
+
+    /// The number of dimensions of the vector.
+    pub fn ndims(&self) -> usize {
+        self.vec.len()
+    }
+}
+
+/// Embedding vector reduced to the sign of each component.
+///
+/// Bit `i` is set when component `i` is strictly positive. Distances are computed
+/// as if each component were `+1` (bit set) or `-1` (bit unset).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BinaryEmbedding {
+    pub bits: Vec<u64>,
+    pub ndims: usize,
+}
+
+impl BinaryEmbedding {
+    /// Quantize a vector by keeping the sign of each component.
+    pub fn quantize(vec: &[f64]) -> Self {
+        let mut bits = vec![0u64; vec.len().div_ceil(64)];
+
+        for (i, x) in vec.iter().enumerate() {
+            if *x > 0.0 {
+                bits[i / 64] |= 1 << (i % 64);
+            }
+        }
+
+        Self {
+            bits,
+            ndims: vec.len(),
+        }
+    }
+
+    /// Number of components whose sign differs between the two vectors.
+    pub fn hamming_distance(&self, other: &Self) -> u32 {
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .map(|(a, b)| (a ^ b).count_ones())
+            .sum()
+    }
+
+    /// Reconstruct the `+1`/`-1` vector represented by the bits.
+    pub fn dequantize(&self) -> Vec<f32> {
+        (0..self.ndims)
+            .map(|i| {
+                if self.bits[i / 64] & (1 << (i % 64)) != 0 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            })
+            .collect()
+    }
+}
+
+/// A vector stored with one of the [Quantization] schemes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuantizedVector {
+    F32(F32Embedding),
+    Int8(Int8Embedding),
+    Binary(BinaryEmbedding),
+}
+
+impl QuantizedVector {
+    /// Reconstruct an approximation of the original vector.
+    pub fn dequantize(&self) -> Vec<f32> {
+        match self {
+            QuantizedVector::F32(embedding) => embedding.vec.clone(),
+            QuantizedVector::Int8(embedding) => embedding.dequantize(),
+            QuantizedVector::Binary(embedding) => embedding.dequantize(),
+        }
+    }
+
+    /// Approximate size of the vector data in bytes.
+    pub fn size_in_bytes(&self) -> usize {
+        match self {
+            QuantizedVector::F32(embedding) => embedding.vec.len() * size_of::<f32>(),
+            QuantizedVector::Int8(embedding) => embedding.vec.len() + size_of::<f32>(),
+            QuantizedVector::Binary(embedding) => embedding.bits.len() * size_of::<u64>(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BinaryEmbedding, Int8Embedding, Quantization, QuantizedVector};
+
+    #[test]
+    fn test_int8_round_trip() {
+        let vec = vec![0.5, -1.0, 0.25, 0.0];
+        let quantized = Int8Embedding::quantize(&vec);
+
+        assert_eq!(quantized.vec, vec![64, -127, 32, 0]);
+
+        for (original, restored) in vec.iter().zip(quantized.dequantize()) {
+            assert!((*original as f32 - restored).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn test_int8_zero_vector() {
+        let quantized = Int8Embedding::quantize(&[0.0, 0.0]);
+
+        assert_eq!(quantized.vec, vec![0, 0]);
+        assert_eq!(quantized.dequantize(), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_binary_quantize() {
+        let vec = (0..70)
+            .map(|i| if i % 3 == 0 { 1.0 } else { -1.0 })
+            .collect::<Vec<f64>>();
+        let quantized = BinaryEmbedding::quantize(&vec);
+
+        assert_eq!(quantized.bits.len(), 2);
+        assert_eq!(quantized.ndims, 70);
+        assert_eq!(
+            quantized.dequantize(),
+            vec.iter().map(|x| *x as f32).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_quantization_none() {
+        assert_eq!(Quantization::None.quantize(&[1.0, 2.0]), None);
+        assert!(matches!(
+            Quantization::Binary.quantize(&[1.0, 2.0]),
+            Some(QuantizedVector::Binary(_))
+        ));
+    }
+}
@@ -0,0 +1,215 @@
+//! Datasets of examples to run experiments on. See [Experiment](super::experiment::Experiment).
+//!
+//! Datasets can be loaded from JSONL files, where each line is an object with an `input` field
+//! and optional `id` and `expected` fields:
+//! ```text
+//! {"id": "capital-fr", "input": "What is the capital of France?", "expected": "Paris"}
+//! {"id": "capital-jp", "input": "What is the capital of Japan?", "expected": "Tokyo"}
+//! ```
+//!
+//! Or from CSV files with a header row containing an `input` column and optional `id` and
+//! `expected` columns:
+//! ```text
+//! id,input,expected
+//! capital-fr,What is the capital of France?,Paris
+//! ```
+//!
+//! Any other field or column is kept in the [Example::metadata] of the example.
+
+use std::{collections::BTreeMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use super::EvalError;
+
+/// A single example of a dataset: an input and, optionally, the expected output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Example {
+    /// Identifier of the example, used to match rows when comparing reports.
+    /// Defaults to the index of the example in the dataset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The input given to the model, agent or pipeline under evaluation
+    pub input: String,
+    /// The expected output, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// Any other field of the example
+    #[serde(default, flatten)]
+    pub metadata: BTreeMap<String, serde_json::Value>,
+}
+
+impl Example {
+    pub fn new(input: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            input: input.into(),
+            expected: None,
+            metadata: BTreeMap::new(),
+        }
+    }
+
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    pub fn expected(mut self, expected: impl Into<String>) -> Self {
+        self.expected = Some(expected.into());
+        self
+    }
+
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+}
+
+/// A named list of [Example]s.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Dataset {
+    pub name: String,
+    pub examples: Vec<Example>,
+}
+
+impl Dataset {
+    pub fn new(name: impl Into<String>, examples: Vec<Example>) -> Self {
+        Self {
+            name: name.into(),
+            examples,
+        }
+    }
+
+    /// Parse a dataset from JSONL content. Empty lines are ignored.
+    pub fn from_jsonl(name: impl Into<String>, content: &str) -> Result<Self, EvalError> {
+        let examples = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                serde_json::from_str(line).map_err(|e| {
+                    EvalError::Dataset(format!("Invalid example on line {}: {e}", i + 1))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(name, examples))
+    }
+
+    /// Parse a dataset from CSV content. The first row must be a header row containing an
+    /// `input` column.
+    pub fn from_csv(name: impl Into<String>, content: &str) -> Result<Self, EvalError> {
+        let mut reader = csv::Reader::from_reader(content.as_bytes());
+        let headers = reader.headers()?.clone();
+
+        if !headers.iter().any(|header| header == "input") {
+            return Err(EvalError::Dataset(
+                "CSV dataset must have an `input` column".into(),
+            ));
+        }
+
+        let examples = reader
+            .records()
+            .map(|record| {
+                let record = record?;
+                let mut example = Example::new("");
+
+                for (header, value) in headers.iter().zip(record.iter()) {
+                    match header {
+                        "input" => example.input = value.to_string(),
+                        "id" => example.id = Some(value.to_string()),
+                        "expected" => example.expected = Some(value.to_string()),
+                        _ => {
+                            example.metadata.insert(header.to_string(), value.into());
+                        }
+                    }
+                }
+
+                Ok(example)
+            })
+            .collect::<Result<Vec<_>, EvalError>>()?;
+
+        Ok(Self::new(name, examples))
+    }
+
+    /// Load a dataset from a `.jsonl` or `.csv` file. The dataset is named after the file stem.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EvalError> {
+        let path = path.as_ref();
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl") => Self::from_jsonl(name, &content),
+            Some("csv") => Self::from_csv(name, &content),
+            _ => Err(EvalError::Dataset(format!(
+                "Unsupported dataset file: {}. Expected a .jsonl or .csv file",
+                path.display()
+            ))),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.examples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.examples.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Example> {
+        self.examples.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dataset_from_jsonl() {
+        let content = r#"
+{"id": "fr", "input": "What is the capital of France?", "expected": "Paris", "difficulty": 1}
+
+{"input": "What is the capital of Japan?"}
+"#;
+        let dataset = Dataset::from_jsonl("capitals", content).unwrap();
+
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(
+            dataset.examples[0],
+            Example::new("What is the capital of France?")
+                .id("fr")
+                .expected("Paris")
+                .metadata("difficulty", 1)
+        );
+        assert_eq!(
+            dataset.examples[1],
+            Example::new("What is the capital of Japan?")
+        );
+
+        let err = Dataset::from_jsonl("capitals", "{\"expected\": \"Paris\"}").unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+
+    #[test]
+    fn test_dataset_from_csv() {
+        let content = "id,input,expected,topic\n\
+            fr,\"Capital of France, in one word?\",Paris,geography\n\
+            jp,Capital of Japan?,Tokyo,geography\n";
+        let dataset = Dataset::from_csv("capitals", content).unwrap();
+
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(
+            dataset.examples[0],
+            Example::new("Capital of France, in one word?")
+                .id("fr")
+                .expected("Paris")
+                .metadata("topic", "geography")
+        );
+
+        assert!(Dataset::from_csv("capitals", "question,answer\nfoo,bar\n").is_err());
+    }
+}
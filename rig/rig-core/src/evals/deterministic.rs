@@ -0,0 +1,1148 @@
+//! Deterministic metrics which do not need a model call, for cheap regression testing:
+//! - [ExactMatchMetric]: exact (or normalized) match against a reference answer,
+//! - [RegexMetric]: the output matches a regular expression,
+//! - [JsonSchemaMetric]: the output is JSON which is valid against the JSON schema of a type,
+//! - [DeserializeMetric]: the output is JSON which deserializes as a type (cheaper),
+//! - [RougeLMetric] and [BleuMetric]: text overlap with a reference answer,
+//! - [ToolTrajectoryMetric]: the agent called the expected tools, in order,
+//! - [RetrievalMetric]: a vector store index retrieves the relevant documents (hit@k and MRR).
+//!
+//! The metrics comparing the output to a reference answer can be used with datasets, where each
+//! example has its own reference, with [with_reference](super::experiment::with_reference).
+
+use std::{collections::HashMap, marker::PhantomData};
+
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    agent::PromptResponse,
+    completion::{AssistantContent, Message},
+    message::ToolFunction,
+    vector_store::{VectorStoreIndex, request::VectorSearchRequest},
+};
+
+use super::{Eval, EvalError, EvalOutcome};
+
+/// The score of metrics that either match (`1.0`) or do not (`0.0`).
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct MatchScore {
+    pub score: f64,
+}
+
+impl MatchScore {
+    fn outcome(matches: bool) -> EvalOutcome<Self> {
+        if matches {
+            EvalOutcome::Pass(Self { score: 1.0 })
+        } else {
+            EvalOutcome::Fail(Self { score: 0.0 })
+        }
+    }
+}
+
+/// Lowercase the text, remove punctuation and collapse whitespace.
+pub fn normalize(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Passes if the output is equal to the reference answer.
+/// With [normalized](ExactMatchMetric::normalized), both are [normalize]d before being compared.
+#[derive(Clone, Debug)]
+pub struct ExactMatchMetric {
+    reference: String,
+    normalized: bool,
+}
+
+impl ExactMatchMetric {
+    pub fn new(reference: impl Into<String>) -> Self {
+        Self {
+            reference: reference.into(),
+            normalized: false,
+        }
+    }
+
+    /// Ignore case, punctuation and whitespace differences.
+    pub fn normalized(mut self) -> Self {
+        self.normalized = true;
+        self
+    }
+}
+
+impl Eval<MatchScore> for ExactMatchMetric {
+    async fn eval(&self, input: String) -> EvalOutcome<MatchScore> {
+        let matches = if self.normalized {
+            normalize(&input) == normalize(&self.reference)
+        } else {
+            input.trim() == self.reference.trim()
+        };
+
+        MatchScore::outcome(matches)
+    }
+}
+
+/// Passes if the output matches a regular expression (anywhere in the output, use `^` and `$`
+/// to match the whole output).
+#[derive(Clone, Debug)]
+pub struct RegexMetric {
+    regex: Regex,
+}
+
+impl RegexMetric {
+    pub fn new(pattern: &str) -> Result<Self, EvalError> {
+        Ok(Self {
+            regex: Regex::new(pattern)?,
+        })
+    }
+}
+
+impl Eval<MatchScore> for RegexMetric {
+    async fn eval(&self, input: String) -> EvalOutcome<MatchScore> {
+        MatchScore::outcome(self.regex.is_match(&input))
+    }
+}
+
+/// The score of [DeserializeMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct DeserializeScore {
+    pub score: f64,
+    /// Why the output is not valid, if it is not
+    pub error: Option<String>,
+}
+
+/// Passes if the output is JSON which can be deserialized as `T`.
+/// Markdown code fences around the JSON are ignored.
+///
+/// Only the constraints enforced by the [Deserialize] implementation of `T` are checked: use
+/// [JsonSchemaMetric] to also check schema-only constraints such as lengths or patterns.
+pub struct DeserializeMetric<T> {
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for DeserializeMetric<T> {
+    fn default() -> Self {
+        Self { _t: PhantomData }
+    }
+}
+
+impl<T> DeserializeMetric<T>
+where
+    T: for<'a> Deserialize<'a>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T> Eval<DeserializeScore> for DeserializeMetric<T>
+where
+    T: for<'a> Deserialize<'a> + 'static,
+{
+    async fn eval(&self, input: String) -> EvalOutcome<DeserializeScore> {
+        let json = strip_code_fences(&input);
+
+        match serde_json::from_str::<T>(json) {
+            Ok(_) => EvalOutcome::Pass(DeserializeScore {
+                score: 1.0,
+                error: None,
+            }),
+            Err(e) => EvalOutcome::Fail(DeserializeScore {
+                score: 0.0,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+}
+
+/// The score of [JsonSchemaMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct JsonSchemaScore {
+    pub score: f64,
+    /// Why the output is not valid, one message per violation
+    pub errors: Vec<String>,
+}
+
+/// Passes if the output is JSON which is valid against the JSON schema of `T`, as generated by
+/// [schemars]. Markdown code fences around the JSON are ignored.
+///
+/// The keywords generated by [schemars] are checked: `type`, `enum`, `const`, `properties`,
+/// `required`, `additionalProperties`, `items`, `prefixItems`, `minItems`/`maxItems`,
+/// `minLength`/`maxLength`, `pattern`, `minimum`/`maximum` (and their exclusive variants),
+/// `anyOf`/`oneOf`/`allOf` and local `$ref`s. Other keywords (e.g. `format`) are ignored.
+pub struct JsonSchemaMetric<T> {
+    schema: Value,
+    _t: PhantomData<fn() -> T>,
+}
+
+impl<T> Default for JsonSchemaMetric<T>
+where
+    T: JsonSchema,
+{
+    fn default() -> Self {
+        Self {
+            schema: schemars::schema_for!(T).to_value(),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<T> JsonSchemaMetric<T>
+where
+    T: JsonSchema,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The JSON schema outputs are validated against.
+    pub fn schema(&self) -> &Value {
+        &self.schema
+    }
+
+    /// The violations of the schema by `value`, empty if it is valid.
+    pub fn validate(&self, value: &Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        validate_schema(&self.schema, &self.schema, value, "$", &mut errors);
+        errors
+    }
+}
+
+impl<T> Eval<JsonSchemaScore> for JsonSchemaMetric<T>
+where
+    T: JsonSchema + 'static,
+{
+    async fn eval(&self, input: String) -> EvalOutcome<JsonSchemaScore> {
+        let errors = match serde_json::from_str::<Value>(strip_code_fences(&input)) {
+            Ok(value) => self.validate(&value),
+            Err(e) => vec![e.to_string()],
+        };
+
+        if errors.is_empty() {
+            EvalOutcome::Pass(JsonSchemaScore { score: 1.0, errors })
+        } else {
+            EvalOutcome::Fail(JsonSchemaScore { score: 0.0, errors })
+        }
+    }
+}
+
+/// Collects the violations of `schema` by `value`. `root` is the schema `$ref`s are resolved in.
+fn validate_schema(
+    root: &Value,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<String>,
+) {
+    let schema = match schema {
+        Value::Bool(true) => return,
+        Value::Bool(false) => {
+            errors.push(format!("{path}: no value is allowed"));
+            return;
+        }
+        Value::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        match reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer))
+        {
+            Some(target) => validate_schema(root, target, value, path, errors),
+            None => errors.push(format!("{path}: cannot resolve `{reference}`")),
+        }
+    }
+
+    if let Some(types) = schema.get("type") {
+        let allowed = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            types => types.as_str().into_iter().collect::<Vec<_>>(),
+        };
+        if !allowed.iter().any(|ty| has_type(value, ty)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+
+    if let Some(variants) = schema.get("enum").and_then(Value::as_array)
+        && !variants.contains(value)
+    {
+        errors.push(format!(
+            "{path}: {value} is not one of {}",
+            Value::from(variants.clone())
+        ));
+    }
+    if let Some(expected) = schema.get("const")
+        && expected != value
+    {
+        errors.push(format!("{path}: expected {expected}, got {value}"));
+    }
+
+    let number = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    let count = |keyword: &str| schema.get(keyword).and_then(Value::as_u64);
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+
+            for required in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(required) {
+                    errors.push(format!("{path}: missing required property `{required}`"));
+                }
+            }
+
+            for (key, property) in object {
+                let property_path = format!("{path}.{key}");
+                match (
+                    properties.and_then(|p| p.get(key)),
+                    schema.get("additionalProperties"),
+                ) {
+                    (Some(property_schema), _) | (None, Some(property_schema)) => {
+                        validate_schema(root, property_schema, property, &property_path, errors)
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(min) = count("minItems")
+                && (items.len() as u64) < min
+            {
+                errors.push(format!(
+                    "{path}: expected at least {min} items, got {}",
+                    items.len()
+                ));
+            }
+            if let Some(max) = count("maxItems")
+                && items.len() as u64 > max
+            {
+                errors.push(format!(
+                    "{path}: expected at most {max} items, got {}",
+                    items.len()
+                ));
+            }
+
+            let prefix = schema
+                .get("prefixItems")
+                .and_then(Value::as_array)
+                .map_or(&[][..], Vec::as_slice);
+            for (i, item) in items.iter().enumerate() {
+                let item_path = format!("{path}[{i}]");
+                match (prefix.get(i), schema.get("items")) {
+                    (Some(item_schema), _) | (None, Some(item_schema)) => {
+                        validate_schema(root, item_schema, item, &item_path, errors)
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        Value::String(text) => {
+            let length = text.chars().count() as u64;
+            if let Some(min) = count("minLength")
+                && length < min
+            {
+                errors.push(format!(
+                    "{path}: expected at least {min} characters, got {length}"
+                ));
+            }
+            if let Some(max) = count("maxLength")
+                && length > max
+            {
+                errors.push(format!(
+                    "{path}: expected at most {max} characters, got {length}"
+                ));
+            }
+            if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+                match Regex::new(pattern) {
+                    Ok(regex) if regex.is_match(text) => {}
+                    Ok(_) => errors.push(format!("{path}: {value} does not match `{pattern}`")),
+                    Err(e) => errors.push(format!("{path}: invalid pattern `{pattern}`: {e}")),
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = number("minimum")
+                && n < min
+            {
+                errors.push(format!("{path}: {n} is lower than the minimum of {min}"));
+            }
+            if let Some(max) = number("maximum")
+                && n > max
+            {
+                errors.push(format!("{path}: {n} is greater than the maximum of {max}"));
+            }
+            if let Some(min) = number("exclusiveMinimum")
+                && n <= min
+            {
+                errors.push(format!("{path}: {n} is not greater than {min}"));
+            }
+            if let Some(max) = number("exclusiveMaximum")
+                && n >= max
+            {
+                errors.push(format!("{path}: {n} is not lower than {max}"));
+            }
+        }
+        _ => {}
+    }
+
+    let branches = |keyword: &str| {
+        schema
+            .get(keyword)
+            .and_then(Value::as_array)
+            .map(|branches| {
+                branches
+                    .iter()
+                    .map(|branch| {
+                        let mut branch_errors = Vec::new();
+                        validate_schema(root, branch, value, path, &mut branch_errors);
+                        branch_errors
+                    })
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    if let Some(results) = branches("allOf") {
+        errors.extend(results.into_iter().flatten());
+    }
+    if let Some(results) = branches("anyOf")
+        && results.iter().all(|errors| !errors.is_empty())
+    {
+        errors.push(format!("{path}: no variant of `anyOf` matches"));
+    }
+    if let Some(results) = branches("oneOf") {
+        let matching = results.iter().filter(|errors| errors.is_empty()).count();
+        if matching != 1 {
+            errors.push(format!(
+                "{path}: expected exactly one variant of `oneOf` to match, {matching} did"
+            ));
+        }
+    }
+}
+
+fn has_type(value: &Value, ty: &str) -> bool {
+    match ty {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn strip_code_fences(text: &str) -> &str {
+    let text = text.trim();
+    let Some(rest) = text.strip_prefix("```") else {
+        return text;
+    };
+    // Skip the language tag of the fence, if any
+    let rest = rest.split_once('\n').map_or("", |(_, rest)| rest);
+    rest.strip_suffix("```").unwrap_or(rest).trim()
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    normalize(text)
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// The score of [RougeLMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RougeLScore {
+    /// The F1 score of the longest common subsequence
+    pub score: f64,
+    pub precision: f64,
+    pub recall: f64,
+}
+
+/// ROUGE-L: the F1 score of the longest common subsequence of words between the output and the
+/// reference answer. Texts are [normalize]d before being split in words.
+#[derive(Clone, Debug)]
+pub struct RougeLMetric {
+    reference: Vec<String>,
+    threshold: f64,
+}
+
+impl RougeLMetric {
+    pub fn new(reference: &str, threshold: f64) -> Self {
+        Self {
+            reference: tokenize(reference),
+            threshold,
+        }
+    }
+
+    pub fn score(&self, output: &str) -> RougeLScore {
+        let candidate = tokenize(output);
+        let lcs = longest_common_subsequence(&candidate, &self.reference) as f64;
+
+        let precision = ratio(lcs, candidate.len());
+        let recall = ratio(lcs, self.reference.len());
+        let score = if precision + recall == 0.0 {
+            0.0
+        } else {
+            2.0 * precision * recall / (precision + recall)
+        };
+
+        RougeLScore {
+            score,
+            precision,
+            recall,
+        }
+    }
+}
+
+impl Eval<RougeLScore> for RougeLMetric {
+    async fn eval(&self, input: String) -> EvalOutcome<RougeLScore> {
+        let score = self.score(&input);
+        if score.score >= self.threshold {
+            EvalOutcome::Pass(score)
+        } else {
+            EvalOutcome::Fail(score)
+        }
+    }
+}
+
+fn longest_common_subsequence(a: &[String], b: &[String]) -> usize {
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+
+    for x in a {
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = if x == y {
+                previous[j] + 1
+            } else {
+                current[j].max(previous[j + 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// The score of [BleuMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct BleuScore {
+    pub score: f64,
+    /// The modified precision of each n-gram order, starting with unigrams
+    pub precisions: Vec<f64>,
+    pub brevity_penalty: f64,
+}
+
+/// Sentence-level BLEU with up to 4-grams between the output and the reference answer.
+/// Texts are [normalize]d before being split in words, and n-gram precisions of order 2 and
+/// above are smoothed with add-one smoothing so that short outputs do not score 0.
+#[derive(Clone, Debug)]
+pub struct BleuMetric {
+    reference: Vec<String>,
+    threshold: f64,
+    max_order: usize,
+}
+
+impl BleuMetric {
+    pub fn new(reference: &str, threshold: f64) -> Self {
+        Self {
+            reference: tokenize(reference),
+            threshold,
+            max_order: 4,
+        }
+    }
+
+    /// Set the maximum n-gram order. Defaults to 4.
+    pub fn max_order(mut self, max_order: usize) -> Self {
+        self.max_order = max_order.max(1);
+        self
+    }
+
+    pub fn score(&self, output: &str) -> BleuScore {
+        let candidate = tokenize(output);
+        if candidate.is_empty() {
+            return BleuScore {
+                score: 0.0,
+                precisions: vec![0.0; self.max_order],
+                brevity_penalty: 0.0,
+            };
+        }
+
+        let precisions = (1..=self.max_order)
+            .map(|n| {
+                let reference_counts = ngram_counts(&self.reference, n);
+                let candidate_counts = ngram_counts(&candidate, n);
+                let total = candidate.len().saturating_sub(n - 1);
+                let clipped = candidate_counts
+                    .iter()
+                    .map(|(ngram, count)| (*count).min(*reference_counts.get(ngram).unwrap_or(&0)))
+                    .sum::<usize>();
+
+                if n == 1 {
+                    ratio(clipped as f64, total)
+                } else {
+                    (clipped as f64 + 1.0) / (total as f64 + 1.0)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let brevity_penalty = if candidate.len() >= self.reference.len() {
+            1.0
+        } else {
+            (1.0 - self.reference.len() as f64 / candidate.len() as f64).exp()
+        };
+
+        let score = if precisions.contains(&0.0) {
+            0.0
+        } else {
+            let log_mean = precisions.iter().map(|p| p.ln()).sum::<f64>() / precisions.len() as f64;
+            brevity_penalty * log_mean.exp()
+        };
+
+        BleuScore {
+            score,
+            precisions,
+            brevity_penalty,
+        }
+    }
+}
+
+impl Eval<BleuScore> for BleuMetric {
+    async fn eval(&self, input: String) -> EvalOutcome<BleuScore> {
+        let score = self.score(&input);
+        if score.score >= self.threshold {
+            EvalOutcome::Pass(score)
+        } else {
+            EvalOutcome::Fail(score)
+        }
+    }
+}
+
+fn ngram_counts(tokens: &[String], n: usize) -> HashMap<&[String], usize> {
+    let mut counts = HashMap::new();
+    for ngram in tokens.windows(n) {
+        *counts.entry(ngram).or_insert(0) += 1;
+    }
+    counts
+}
+
+fn ratio(count: f64, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count / total as f64
+    }
+}
+
+/// A tool call expected by [ToolTrajectoryMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct ExpectedToolCall {
+    pub name: String,
+    /// The expected arguments. Only the given fields are compared, so a subset of the arguments
+    /// can be given. If `None`, the arguments are not checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub args: Option<serde_json::Value>,
+}
+
+impl ExpectedToolCall {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            args: None,
+        }
+    }
+
+    pub fn args(mut self, args: serde_json::Value) -> Self {
+        self.args = Some(args);
+        self
+    }
+
+    fn matches(&self, call: &ToolFunction) -> bool {
+        if call.name != self.name {
+            return false;
+        }
+
+        let Some(expected) = &self.args else {
+            return true;
+        };
+
+        // Some providers return the arguments as a JSON string
+        match &call.arguments {
+            serde_json::Value::String(args) => serde_json::from_str(args)
+                .is_ok_and(|args: serde_json::Value| is_json_subset(expected, &args)),
+            args => is_json_subset(expected, args),
+        }
+    }
+}
+
+/// Whether all the fields of `expected` are present in `actual` with the same values.
+fn is_json_subset(expected: &serde_json::Value, actual: &serde_json::Value) -> bool {
+    match (expected, actual) {
+        (serde_json::Value::Object(expected), serde_json::Value::Object(actual)) => {
+            expected.iter().all(|(key, value)| {
+                actual
+                    .get(key)
+                    .is_some_and(|actual| is_json_subset(value, actual))
+            })
+        }
+        _ => expected == actual,
+    }
+}
+
+/// The score of [ToolTrajectoryMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct TrajectoryScore {
+    /// Ratio of expected tool calls which were matched, in order
+    pub score: f64,
+    pub matched: usize,
+    pub expected: usize,
+    /// The names of the tools called, in order
+    pub calls: Vec<String>,
+}
+
+/// Passes if the agent called the expected tools (with the expected arguments), in order.
+///
+/// Other tool calls are allowed between the expected ones unless the metric is
+/// [exact](ToolTrajectoryMetric::exact). The metric scores the messages of a
+/// [PromptResponse] (see [ToolTrajectoryMetric::score_response]). As an [Eval], the input must
+/// be the JSON serialized messages.
+#[derive(Clone, Debug)]
+pub struct ToolTrajectoryMetric {
+    expected: Vec<ExpectedToolCall>,
+    exact: bool,
+}
+
+impl ToolTrajectoryMetric {
+    pub fn new(expected: Vec<ExpectedToolCall>) -> Self {
+        Self {
+            expected,
+            exact: false,
+        }
+    }
+
+    /// Fail if the agent made any tool call besides the expected ones.
+    pub fn exact(mut self) -> Self {
+        self.exact = true;
+        self
+    }
+
+    pub fn score_response(&self, response: &PromptResponse) -> EvalOutcome<TrajectoryScore> {
+        self.score_messages(&response.messages)
+    }
+
+    pub fn score_messages(&self, messages: &[Message]) -> EvalOutcome<TrajectoryScore> {
+        let calls = messages
+            .iter()
+            .filter_map(|message| match message {
+                Message::Assistant { content, .. } => Some(content.iter()),
+                Message::User { .. } => None,
+            })
+            .flatten()
+            .filter_map(|content| match content {
+                AssistantContent::ToolCall(call) => Some(&call.function),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut expected = self.expected.iter().peekable();
+        let mut matched = 0;
+        for call in &calls {
+            if expected
+                .peek()
+                .is_some_and(|expected| expected.matches(call))
+            {
+                expected.next();
+                matched += 1;
+            }
+        }
+
+        let passed = matched == self.expected.len() && (!self.exact || calls.len() == matched);
+        let score = TrajectoryScore {
+            score: if self.expected.is_empty() {
+                if calls.is_empty() { 1.0 } else { 0.0 }
+            } else {
+                matched as f64 / self.expected.len() as f64
+            },
+            matched,
+            expected: self.expected.len(),
+            calls: calls.iter().map(|call| call.name.clone()).collect(),
+        };
+
+        if passed {
+            EvalOutcome::Pass(score)
+        } else {
+            EvalOutcome::Fail(score)
+        }
+    }
+}
+
+impl Eval<TrajectoryScore> for ToolTrajectoryMetric {
+    async fn eval(&self, input: String) -> EvalOutcome<TrajectoryScore> {
+        match serde_json::from_str::<Vec<Message>>(&input) {
+            Ok(messages) => self.score_messages(&messages),
+            Err(e) => EvalOutcome::Invalid(format!("Input is not a list of messages: {e}")),
+        }
+    }
+}
+
+/// The score of [RetrievalMetric].
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct RetrievalScore {
+    /// The reciprocal rank of the first relevant document (`0.0` if none was retrieved).
+    /// Averaging it over a dataset gives the MRR.
+    pub score: f64,
+    /// Whether a relevant document was retrieved in the top `k` results.
+    /// The pass rate over a dataset gives the hit@k.
+    pub hit: bool,
+    pub retrieved_ids: Vec<String>,
+}
+
+/// Scores the retrieval of a vector store index for a query (the eval input): passes if one of
+/// the relevant documents is in the top `k` results, and scores the reciprocal rank of the first
+/// relevant document.
+pub struct RetrievalMetric<I> {
+    index: I,
+    k: usize,
+    relevant_ids: Vec<String>,
+}
+
+impl<I> RetrievalMetric<I>
+where
+    I: VectorStoreIndex,
+{
+    pub fn new<T>(index: I, k: usize, relevant_ids: impl IntoIterator<Item = T>) -> Self
+    where
+        T: Into<String>,
+    {
+        Self {
+            index,
+            k,
+            relevant_ids: relevant_ids.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl<I> Eval<RetrievalScore> for RetrievalMetric<I>
+where
+    I: VectorStoreIndex + 'static,
+{
+    async fn eval(&self, input: String) -> EvalOutcome<RetrievalScore> {
+        let request = match VectorSearchRequest::builder()
+            .query(input)
+            .samples(self.k as u64)
+            .build()
+        {
+            Ok(request) => request,
+            Err(e) => return EvalOutcome::Invalid(e.to_string()),
+        };
+
+        let retrieved_ids = match self.index.top_n_ids(request).await {
+            Ok(results) => results
+                .into_iter()
+                .take(self.k)
+                .map(|(_, id)| id)
+                .collect::<Vec<_>>(),
+            Err(e) => return EvalOutcome::Invalid(e.to_string()),
+        };
+
+        let rank = retrieved_ids
+            .iter()
+            .position(|id| self.relevant_ids.contains(id));
+        let score = RetrievalScore {
+            score: rank.map_or(0.0, |rank| 1.0 / (rank + 1) as f64),
+            hit: rank.is_some(),
+            retrieved_ids,
+        };
+
+        if score.hit {
+            EvalOutcome::Pass(score)
+        } else {
+            EvalOutcome::Fail(score)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        OneOrMany,
+        message::ToolCall,
+        vector_store::{VectorStoreError, request::Filter},
+        wasm_compat::WasmCompatSend,
+    };
+
+    #[tokio::test]
+    async fn test_exact_and_regex_match() {
+        let metric = ExactMatchMetric::new("Paris");
+        assert!(metric.eval(" Paris\n".into()).await.is_pass());
+        assert!(!metric.eval("paris.".into()).await.is_pass());
+        assert!(metric.normalized().eval("paris.".into()).await.is_pass());
+
+        let metric = RegexMetric::new(r"^\d{4}-\d{2}-\d{2}$").unwrap();
+        assert!(metric.eval("2024-01-31".into()).await.is_pass());
+        assert!(!metric.eval("31/01/2024".into()).await.is_pass());
+        assert!(RegexMetric::new("(").is_err());
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Person {
+        name: String,
+        age: u8,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    enum Role {
+        Admin,
+        User,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Account {
+        #[schemars(length(min = 3, max = 8), regex(pattern = r"^[a-z]+$"))]
+        username: String,
+        role: Role,
+        #[schemars(length(max = 2))]
+        people: Vec<Person>,
+        nickname: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn test_deserialize() {
+        let metric = DeserializeMetric::<Person>::new();
+        assert!(
+            metric
+                .eval("```json\n{\"name\": \"Alice\", \"age\": 30}\n```".into())
+                .await
+                .is_pass()
+        );
+
+        let outcome = metric.eval(r#"{"name": "Alice"}"#.into()).await;
+        assert!(!outcome.is_pass());
+        assert!(
+            outcome
+                .score()
+                .unwrap()
+                .error
+                .as_ref()
+                .unwrap()
+                .contains("age")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_json_schema() {
+        let metric = JsonSchemaMetric::<Account>::new();
+        let valid = json!({
+            "username": "alice",
+            "role": "Admin",
+            "people": [{ "name": "Bob", "age": 30 }],
+            "nickname": null
+        });
+        assert!(
+            metric.validate(&valid).is_empty(),
+            "{:?}",
+            metric.validate(&valid)
+        );
+        assert!(
+            metric
+                .eval(format!("```json\n{valid}\n```"))
+                .await
+                .is_pass()
+        );
+
+        let invalid = json!({
+            "username": "Al",
+            "role": "Guest",
+            "people": [{ "name": "Bob", "age": 300 }, { "name": 1, "age": 1 }, {}],
+        });
+        let errors = metric.validate(&invalid);
+        let has_error = |needle: &str| errors.iter().any(|error| error.contains(needle));
+        // Lengths, patterns, enums, numeric ranges, nested types and required properties
+        assert!(
+            has_error("$.username: expected at least 3 characters"),
+            "{errors:?}"
+        );
+        assert!(has_error("$.username: \"Al\" does not match"), "{errors:?}");
+        assert!(has_error(r#"$.role: "Guest" is not one of"#), "{errors:?}");
+        assert!(
+            has_error("$.people: expected at most 2 items"),
+            "{errors:?}"
+        );
+        assert!(
+            has_error("$.people[0].age: 300 is greater than the maximum"),
+            "{errors:?}"
+        );
+        assert!(
+            has_error("$.people[1].name: expected string, got number"),
+            "{errors:?}"
+        );
+        assert!(
+            has_error("$.people[2]: missing required property `name`"),
+            "{errors:?}"
+        );
+        // Optional fields can be omitted
+        assert!(!has_error("nickname"), "{errors:?}");
+
+        let outcome = metric.eval("not json".into()).await;
+        assert!(!outcome.is_pass());
+        assert_eq!(outcome.score().unwrap().errors.len(), 1);
+    }
+
+    #[test]
+    fn test_rouge_l() {
+        let metric = RougeLMetric::new("the cat sat on the mat", 0.5);
+        assert_eq!(metric.score("The cat sat on the mat.").score, 1.0);
+
+        // LCS = "the cat on the mat" (5 words)
+        let score = metric.score("the cat was on the mat today");
+        assert_eq!(score.precision, 5.0 / 7.0);
+        assert_eq!(score.recall, 5.0 / 6.0);
+
+        assert_eq!(metric.score("").score, 0.0);
+    }
+
+    #[test]
+    fn test_bleu() {
+        let metric = BleuMetric::new("the quick brown fox jumps over the lazy dog", 0.5);
+        let score = metric.score("the quick brown fox jumps over the lazy dog");
+        assert!((score.score - 1.0).abs() < 1e-9);
+
+        let score = metric.score("the quick brown fox");
+        assert!(score.brevity_penalty < 1.0);
+        assert_eq!(score.precisions[0], 1.0);
+        assert!(score.score < 0.5);
+
+        assert_eq!(metric.score("completely unrelated").score, 0.0);
+    }
+
+    fn tool_call(name: &str, args: serde_json::Value) -> Message {
+        Message::Assistant {
+            id: None,
+            content: OneOrMany::one(AssistantContent::ToolCall(ToolCall::new(
+                format!("call_{name}"),
+                ToolFunction::new(name.to_string(), args),
+            ))),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_trajectory() {
+        let messages = vec![
+            Message::user("What is 2 + 3 - 1?"),
+            tool_call("add", json!({"x": 2, "y": 3})),
+            tool_call("log", json!({"message": "added"})),
+            tool_call("subtract", json!({"x": 5, "y": 1})),
+            Message::assistant("4"),
+        ];
+
+        let metric = ToolTrajectoryMetric::new(vec![
+            ExpectedToolCall::new("add").args(json!({"x": 2})),
+            ExpectedToolCall::new("subtract"),
+        ]);
+        let outcome = metric.score_messages(&messages);
+        assert!(outcome.is_pass());
+        assert_eq!(
+            outcome.score().unwrap().calls,
+            vec!["add", "log", "subtract"]
+        );
+        assert!(!metric.clone().exact().score_messages(&messages).is_pass());
+
+        // Order matters
+        let metric = ToolTrajectoryMetric::new(vec![
+            ExpectedToolCall::new("subtract"),
+            ExpectedToolCall::new("add"),
+        ]);
+        let outcome = metric.score_messages(&messages);
+        assert!(!outcome.is_pass());
+        assert_eq!(outcome.score().unwrap().score, 0.5);
+
+        let input = serde_json::to_string(&messages).unwrap();
+        assert!(!metric.eval(input).await.is_pass());
+        assert!(matches!(
+            metric.eval("not json".into()).await,
+            EvalOutcome::Invalid(_)
+        ));
+    }
+
+    struct FixedIndex;
+
+    impl VectorStoreIndex for FixedIndex {
+        type Filter = Filter<serde_json::Value>;
+
+        async fn top_n<T: for<'a> Deserialize<'a> + WasmCompatSend>(
+            &self,
+            _req: VectorSearchRequest,
+        ) -> Result<Vec<(f64, String, T)>, VectorStoreError> {
+            Ok(vec![])
+        }
+
+        async fn top_n_ids(
+            &self,
+            _req: VectorSearchRequest,
+        ) -> Result<Vec<(f64, String)>, VectorStoreError> {
+            Ok(vec![
+                (0.9, "doc1".to_string()),
+                (0.8, "doc2".to_string()),
+                (0.7, "doc3".to_string()),
+            ])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retrieval() {
+        let outcome = RetrievalMetric::new(FixedIndex, 3, ["doc2"])
+            .eval("query".into())
+            .await;
+        assert!(outcome.is_pass());
+        assert_eq!(outcome.score().unwrap().score, 0.5);
+
+        let outcome = RetrievalMetric::new(FixedIndex, 2, ["doc3"])
+            .eval("query".into())
+            .await;
+        assert!(!outcome.is_pass());
+        assert_eq!(outcome.score().unwrap().retrieved_ids, vec!["doc1", "doc2"]);
+    }
+}
@@ -0,0 +1,564 @@
+//! Experiments run a model, agent or pipeline over a [Dataset] and score its outputs with one or
+//! more [Metric]s, producing an [ExperimentReport].
+//!
+//! # Example
+//! ```rust
+//! use rig::evals::{
+//!     EvalOutcome,
+//!     dataset::Dataset,
+//!     experiment::{self, Experiment, Pricing},
+//!     report::ExperimentReport,
+//! };
+//!
+//! let dataset = Dataset::load("evals/capitals.jsonl")?;
+//!
+//! let experiment = Experiment::builder("capitals", dataset)
+//!     .metric(experiment::from_fn("contains_expected", |example, output| {
+//!         let expected = example.expected.as_deref().unwrap_or_default();
+//!         if output.contains(expected) {
+//!             EvalOutcome::Pass(1.0)
+//!         } else {
+//!             EvalOutcome::Fail(0.0)
+//!         }
+//!     }))
+//!     .concurrency(4)
+//!     .pricing(Pricing::new(2.5, 10.0))
+//!     .build();
+//!
+//! let report = experiment.run_agent(&agent).await;
+//! report.save("evals/reports")?;
+//!
+//! // Gate changes against a previous run
+//! let baseline = ExperimentReport::load("evals/baseline/capitals.json")?;
+//! let comparison = report.compare(&baseline);
+//! println!("{}", comparison.to_markdown());
+//! assert!(!comparison.is_regression(0.05));
+//! ```
+
+use std::{fmt::Display, marker::PhantomData};
+
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    agent::{Agent, PromptResponse},
+    completion::{CompletionModel, Prompt, PromptError, Usage},
+    pipeline::Op,
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+use super::{
+    Eval, EvalOutcome,
+    dataset::{Dataset, Example},
+    report::{ExperimentReport, RowResult},
+};
+
+/// The score given by a [Metric] to an output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    /// The numerical value of the score (e.g.: `1.0` for a passing boolean check).
+    pub value: f64,
+    /// Any details given by the metric (e.g.: the feedback of an LLM judge)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl Score {
+    pub fn new(value: f64) -> Self {
+        Self {
+            value,
+            details: None,
+        }
+    }
+
+    pub fn details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+}
+
+/// A metric scoring the output produced for an [Example] of a dataset.
+///
+/// Unlike [Eval], metrics have access to the example (and thus to its expected output), which
+/// makes them suitable for datasets where each example has a different reference answer.
+/// Any [Eval] can be used as a metric with [EvalMetric].
+pub trait Metric: WasmCompatSend + WasmCompatSync {
+    /// The name of the metric in reports.
+    fn name(&self) -> &str;
+
+    /// Score the `output` produced for the given `example`.
+    fn score<'a>(
+        &'a self,
+        example: &'a Example,
+        output: &'a str,
+    ) -> WasmBoxedFuture<'a, EvalOutcome<Score>>;
+}
+
+/// A [Metric] wrapping an [Eval]. The eval is applied to the output and ignores the example.
+///
+/// The score is taken from the `score` field of the eval output if it has one (e.g.:
+/// [LlmScoreMetricScore](super::LlmScoreMetricScore)), otherwise it is `1.0` when the eval passes
+/// and `0.0` when it fails. The eval output is kept in the score details.
+pub struct EvalMetric<E, Output> {
+    name: String,
+    eval: E,
+    _t: PhantomData<fn() -> Output>,
+}
+
+impl<E, Output> EvalMetric<E, Output> {
+    pub fn new(name: impl Into<String>, eval: E) -> Self {
+        Self {
+            name: name.into(),
+            eval,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<E, Output> Metric for EvalMetric<E, Output>
+where
+    E: Eval<Output>,
+    Output: for<'a> Deserialize<'a> + Serialize + Clone + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score<'a>(
+        &'a self,
+        _example: &'a Example,
+        output: &'a str,
+    ) -> WasmBoxedFuture<'a, EvalOutcome<Score>> {
+        Box::pin(async move { into_score(self.eval.eval(output.to_string()).await) })
+    }
+}
+
+/// A [Metric] building an [Eval] from the expected output of each example. See [with_reference].
+pub struct ReferenceMetric<F, Output> {
+    name: String,
+    f: F,
+    _t: PhantomData<fn() -> Output>,
+}
+
+impl<F, E, Output> Metric for ReferenceMetric<F, Output>
+where
+    F: Fn(&str) -> E + WasmCompatSend + WasmCompatSync,
+    E: Eval<Output>,
+    Output: for<'a> Deserialize<'a> + Serialize + Clone + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score<'a>(
+        &'a self,
+        example: &'a Example,
+        output: &'a str,
+    ) -> WasmBoxedFuture<'a, EvalOutcome<Score>> {
+        Box::pin(async move {
+            let Some(expected) = &example.expected else {
+                return EvalOutcome::Invalid("Example has no expected output".into());
+            };
+
+            into_score((self.f)(expected).eval(output.to_string()).await)
+        })
+    }
+}
+
+/// Create a metric which builds an [Eval] from the expected output of each example,
+/// e.g.: `with_reference("exact_match", |expected| ExactMatchMetric::new(expected))`.
+/// Examples without an expected output are scored as invalid.
+pub fn with_reference<F, E, Output>(name: impl Into<String>, f: F) -> ReferenceMetric<F, Output>
+where
+    F: Fn(&str) -> E + WasmCompatSend + WasmCompatSync,
+    E: Eval<Output>,
+    Output: for<'a> Deserialize<'a> + Serialize + Clone + Send + Sync,
+{
+    ReferenceMetric {
+        name: name.into(),
+        f,
+        _t: PhantomData,
+    }
+}
+
+/// Convert the outcome of an [Eval] into a [Score] outcome. See [EvalMetric].
+fn into_score<Output: Serialize>(outcome: EvalOutcome<Output>) -> EvalOutcome<Score> {
+    let passed = outcome.is_pass();
+    let eval_output = match outcome {
+        EvalOutcome::Pass(output) | EvalOutcome::Fail(output) => output,
+        EvalOutcome::Invalid(reason) => return EvalOutcome::Invalid(reason),
+    };
+
+    let details = serde_json::to_value(eval_output).ok();
+    let value = details
+        .as_ref()
+        .and_then(|details| details.get("score"))
+        .and_then(|score| score.as_f64())
+        .unwrap_or(if passed { 1.0 } else { 0.0 });
+    let score = Score { value, details };
+
+    if passed {
+        EvalOutcome::Pass(score)
+    } else {
+        EvalOutcome::Fail(score)
+    }
+}
+
+/// A [Metric] defined by a function. See [from_fn].
+pub struct FnMetric<F> {
+    name: String,
+    f: F,
+}
+
+impl<F> Metric for FnMetric<F>
+where
+    F: Fn(&Example, &str) -> EvalOutcome<f64> + WasmCompatSend + WasmCompatSync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn score<'a>(
+        &'a self,
+        example: &'a Example,
+        output: &'a str,
+    ) -> WasmBoxedFuture<'a, EvalOutcome<Score>> {
+        let outcome = match (self.f)(example, output) {
+            EvalOutcome::Pass(value) => EvalOutcome::Pass(Score::new(value)),
+            EvalOutcome::Fail(value) => EvalOutcome::Fail(Score::new(value)),
+            EvalOutcome::Invalid(reason) => EvalOutcome::Invalid(reason),
+        };
+        Box::pin(std::future::ready(outcome))
+    }
+}
+
+/// Create a metric from a function scoring the output produced for an example.
+pub fn from_fn<F>(name: impl Into<String>, f: F) -> FnMetric<F>
+where
+    F: Fn(&Example, &str) -> EvalOutcome<f64> + WasmCompatSend + WasmCompatSync,
+{
+    FnMetric {
+        name: name.into(),
+        f,
+    }
+}
+
+/// The output produced for an example by the model, agent or pipeline under evaluation.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TargetOutput {
+    pub output: String,
+    /// The token usage, if known
+    pub usage: Option<Usage>,
+}
+
+/// Trait for the outputs of the ops that can be evaluated with [Experiment::run].
+/// `Err` values are recorded as errored rows in the report.
+pub trait IntoTargetOutput {
+    fn into_target_output(self) -> Result<TargetOutput, String>;
+}
+
+impl IntoTargetOutput for TargetOutput {
+    fn into_target_output(self) -> Result<TargetOutput, String> {
+        Ok(self)
+    }
+}
+
+impl IntoTargetOutput for String {
+    fn into_target_output(self) -> Result<TargetOutput, String> {
+        Ok(TargetOutput {
+            output: self,
+            usage: None,
+        })
+    }
+}
+
+impl IntoTargetOutput for PromptResponse {
+    fn into_target_output(self) -> Result<TargetOutput, String> {
+        Ok(TargetOutput {
+            output: self.output,
+            usage: Some(self.total_usage),
+        })
+    }
+}
+
+impl<T, E> IntoTargetOutput for Result<T, E>
+where
+    T: IntoTargetOutput,
+    E: Display,
+{
+    fn into_target_output(self) -> Result<TargetOutput, String> {
+        self.map_err(|err| err.to_string())?.into_target_output()
+    }
+}
+
+/// The price of a model, used to compute the cost of an experiment from its token usage.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Pricing {
+    /// Price of one million input tokens
+    pub input_per_million: f64,
+    /// Price of one million output tokens
+    pub output_per_million: f64,
+}
+
+impl Pricing {
+    pub fn new(input_per_million: f64, output_per_million: f64) -> Self {
+        Self {
+            input_per_million,
+            output_per_million,
+        }
+    }
+
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_million
+            + usage.output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Runs a model, agent or pipeline over a [Dataset] and scores the outputs with [Metric]s.
+pub struct Experiment {
+    name: String,
+    dataset: Dataset,
+    metrics: Vec<Box<dyn Metric>>,
+    concurrency: usize,
+    pricing: Option<Pricing>,
+}
+
+/// A builder for [Experiment].
+pub struct ExperimentBuilder {
+    name: String,
+    dataset: Dataset,
+    metrics: Vec<Box<dyn Metric>>,
+    concurrency: usize,
+    pricing: Option<Pricing>,
+}
+
+impl ExperimentBuilder {
+    pub fn new(name: impl Into<String>, dataset: Dataset) -> Self {
+        Self {
+            name: name.into(),
+            dataset,
+            metrics: Vec::new(),
+            concurrency: 1,
+            pricing: None,
+        }
+    }
+
+    /// Add a metric to apply to each output.
+    pub fn metric(mut self, metric: impl Metric + 'static) -> Self {
+        self.metrics.push(Box::new(metric));
+        self
+    }
+
+    /// Set the number of examples processed concurrently. Defaults to 1.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the pricing used to compute the cost of each example from its token usage.
+    pub fn pricing(mut self, pricing: Pricing) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    pub fn build(self) -> Experiment {
+        Experiment {
+            name: self.name,
+            dataset: self.dataset,
+            metrics: self.metrics,
+            concurrency: self.concurrency,
+            pricing: self.pricing,
+        }
+    }
+}
+
+impl Experiment {
+    pub fn builder(name: impl Into<String>, dataset: Dataset) -> ExperimentBuilder {
+        ExperimentBuilder::new(name, dataset)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn dataset(&self) -> &Dataset {
+        &self.dataset
+    }
+
+    /// Run the op `target` on the input of each example of the dataset and score its outputs.
+    /// Errors returned by the op are recorded in the report.
+    pub async fn run<T>(&self, target: &T) -> ExperimentReport
+    where
+        T: Op<Input = String>,
+        T::Output: IntoTargetOutput,
+    {
+        let rows = stream::iter(self.dataset.iter().enumerate())
+            .map(|(index, example)| self.run_example(target, index, example))
+            .buffered(self.concurrency)
+            .collect::<Vec<_>>()
+            .await;
+
+        ExperimentReport::new(&self.name, &self.dataset.name, rows)
+    }
+
+    /// Prompt the `agent` with the input of each example of the dataset and score its responses.
+    pub async fn run_agent<M>(&self, agent: &Agent<M>) -> ExperimentReport
+    where
+        M: CompletionModel + 'static,
+    {
+        self.run(&AgentTarget { agent }).await
+    }
+
+    async fn run_example<T>(&self, target: &T, index: usize, example: &Example) -> RowResult
+    where
+        T: Op<Input = String>,
+        T::Output: IntoTargetOutput,
+    {
+        let start = web_time::Instant::now();
+        let result = target
+            .call(example.input.clone())
+            .await
+            .into_target_output();
+        let latency_ms = start.elapsed().as_secs_f64() * 1000.0;
+
+        let mut row = RowResult {
+            id: example.id.clone().unwrap_or_else(|| index.to_string()),
+            input: example.input.clone(),
+            expected: example.expected.clone(),
+            output: None,
+            error: None,
+            latency_ms,
+            usage: None,
+            cost: None,
+            metrics: Default::default(),
+        };
+
+        match result {
+            Ok(TargetOutput { output, usage }) => {
+                for metric in &self.metrics {
+                    let outcome = metric.score(example, &output).await;
+                    row.metrics.insert(metric.name().to_string(), outcome);
+                }
+                row.cost = usage
+                    .as_ref()
+                    .zip(self.pricing.as_ref())
+                    .map(|(usage, pricing)| pricing.cost(usage));
+                row.usage = usage;
+                row.output = Some(output);
+            }
+            Err(error) => {
+                tracing::warn!(target: "rig", "Experiment `{}` failed on example `{}`: {error}", self.name, row.id);
+                row.error = Some(error);
+            }
+        }
+
+        row
+    }
+}
+
+struct AgentTarget<'a, M: CompletionModel> {
+    agent: &'a Agent<M>,
+}
+
+impl<M> Op for AgentTarget<'_, M>
+where
+    M: CompletionModel + 'static,
+{
+    type Input = String;
+    type Output = Result<PromptResponse, PromptError>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        self.agent.prompt(input).extended_details().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::map;
+
+    fn exact_match() -> impl Metric {
+        from_fn("exact_match", |example, output| {
+            if example.expected.as_deref() == Some(output) {
+                EvalOutcome::Pass(1.0)
+            } else {
+                EvalOutcome::Fail(0.0)
+            }
+        })
+    }
+
+    #[tokio::test]
+    async fn test_reference_metric() {
+        use crate::evals::deterministic::ExactMatchMetric;
+
+        let metric = with_reference("exact_match", |expected| {
+            ExactMatchMetric::new(expected).normalized()
+        });
+
+        let example = Example::new("Capital of France?").expected("Paris");
+        let outcome = metric.score(&example, "paris").await;
+        assert_eq!(outcome.score().unwrap().value, 1.0);
+        assert!(outcome.is_pass());
+        assert!(!metric.score(&example, "Lyon").await.is_pass());
+
+        let example = Example::new("Capital of France?");
+        assert!(matches!(
+            metric.score(&example, "Paris").await,
+            EvalOutcome::Invalid(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_experiment_run() {
+        let dataset = Dataset::new(
+            "shout",
+            vec![
+                Example::new("hello").expected("HELLO"),
+                Example::new("world").id("w").expected("WORLD!"),
+                Example::new("").expected(""),
+            ],
+        );
+
+        let experiment = Experiment::builder("uppercase", dataset)
+            .metric(exact_match())
+            .concurrency(2)
+            .pricing(Pricing::new(1.0, 2.0))
+            .build();
+
+        let target = map(|input: String| {
+            if input.is_empty() {
+                Err("Empty input")
+            } else {
+                Ok(TargetOutput {
+                    output: input.to_uppercase(),
+                    usage: Some(Usage {
+                        input_tokens: 1_000,
+                        output_tokens: 500,
+                        total_tokens: 1_500,
+                    }),
+                })
+            }
+        });
+
+        let report = experiment.run(&target).await;
+
+        assert_eq!(report.rows.len(), 3);
+        assert_eq!(report.rows[0].id, "0");
+        assert_eq!(report.rows[1].id, "w");
+        assert!(report.rows[0].passed());
+        assert!(!report.rows[1].passed());
+        assert_eq!(report.rows[2].error.as_deref(), Some("Empty input"));
+        assert_eq!(report.rows[0].cost, Some(0.002));
+
+        assert_eq!(report.summary.rows, 3);
+        assert_eq!(report.summary.errors, 1);
+        assert_eq!(report.summary.passed, 1);
+        assert_eq!(report.summary.usage.total_tokens, 3_000);
+        assert_eq!(report.summary.cost, Some(0.004));
+
+        let metric = &report.summary.metrics["exact_match"];
+        assert_eq!((metric.passed, metric.failed, metric.invalid), (1, 1, 0));
+        assert_eq!(metric.pass_rate, 0.5);
+    }
+}
@@ -1,6 +1,17 @@
 //! Evals.
 //! From OpenAI's evals repo:
 //! > Evals provide a framework for evaluating large language models (LLMs) or systems built using LLMs. We offer an existing registry of evals to test different dimensions of OpenAI models and the ability to write your own custom evals for use cases you care about. You can also use your data to build private evals which represent the common LLMs patterns in your workflow without exposing any of that data publicly.
+//!
+//! Individual evals implement the [Eval] trait. Besides the metrics using a model in this module,
+//! the [deterministic] module has cheap metrics for regression testing.
+//!
+//! To evaluate a model, agent or pipeline over a whole [dataset] of examples and produce a [report]
+//! that can be compared against a baseline run, see the [experiment] module.
+
+pub mod dataset;
+pub mod deterministic;
+pub mod experiment;
+pub mod report;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -20,11 +31,22 @@ pub enum EvalError {
     /// Generic eval module error
     #[error("Eval error: {0}")]
     Custom(String),
+    /// A dataset could not be loaded
+    #[error("Dataset error: {0}")]
+    Dataset(String),
+    #[error("IoError: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("JsonError: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("CsvError: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("RegexError: {0}")]
+    Regex(#[from] regex::Error),
 }
 
 /// The outcome of an evaluation (ie, sending an input to an LLM which then gets tested against a set of criteria).
 /// Invalid results due to things like functions returning errors should be encoded as invalid evaluation outcomes.
-#[derive(Deserialize, Serialize, Clone, Debug)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 #[serde(tag = "outcome", content = "data")]
 pub enum EvalOutcome<Output> {
     /// Evaluation passed
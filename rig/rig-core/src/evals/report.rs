@@ -0,0 +1,597 @@
+//! Reports of [Experiment](super::experiment::Experiment) runs.
+//!
+//! Reports can be saved as JSON (to be compared against later runs with [ExperimentReport::compare])
+//! and as Markdown (to be read by humans, e.g.: in the summary of a CI job).
+
+use std::{collections::BTreeMap, fmt::Write, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::completion::Usage;
+
+use super::{EvalError, EvalOutcome, experiment::Score};
+
+/// The result of an experiment for one example of the dataset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RowResult {
+    pub id: String,
+    pub input: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<String>,
+    /// The output produced for the example, if it did not error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// The error returned for the example, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub latency_ms: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+    /// The outcome of each metric, by metric name
+    pub metrics: BTreeMap<String, EvalOutcome<Score>>,
+}
+
+impl RowResult {
+    /// Whether the example did not error and passed all the metrics.
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.metrics.values().all(EvalOutcome::is_pass)
+    }
+}
+
+/// Summary statistics of a list of values.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+}
+
+impl Distribution {
+    /// Compute the distribution of `values`. Returns `None` if there are no values.
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        // Nearest-rank percentile
+        let percentile = |p: f64| {
+            let rank = (p * sorted.len() as f64).ceil() as usize;
+            sorted[rank.clamp(1, sorted.len()) - 1]
+        };
+
+        Some(Self {
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+        })
+    }
+}
+
+/// Aggregated results of a metric over all the examples that did not error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub invalid: usize,
+    /// Ratio of passed outcomes over all the outcomes (including invalid ones)
+    pub pass_rate: f64,
+    /// Distribution of the scores of the passed and failed outcomes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scores: Option<Distribution>,
+}
+
+/// Aggregated results of an experiment.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub rows: usize,
+    /// Number of examples for which the model, agent or pipeline returned an error
+    pub errors: usize,
+    /// Number of examples which did not error and passed all the metrics
+    pub passed: usize,
+    /// Ratio of passed examples over all the examples
+    pub pass_rate: f64,
+    pub metrics: BTreeMap<String, MetricSummary>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<Distribution>,
+    pub usage: Usage,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost: Option<f64>,
+}
+
+impl Summary {
+    fn new(rows: &[RowResult]) -> Self {
+        let passed = rows.iter().filter(|row| row.passed()).count();
+
+        let mut outcomes: BTreeMap<&str, Vec<&EvalOutcome<Score>>> = BTreeMap::new();
+        for row in rows {
+            for (name, outcome) in &row.metrics {
+                outcomes.entry(name).or_default().push(outcome);
+            }
+        }
+
+        let metrics = outcomes
+            .into_iter()
+            .map(|(name, outcomes)| {
+                let passed = outcomes.iter().filter(|outcome| outcome.is_pass()).count();
+                let invalid = outcomes
+                    .iter()
+                    .filter(|outcome| matches!(outcome, EvalOutcome::Invalid(_)))
+                    .count();
+                let scores = outcomes
+                    .iter()
+                    .filter_map(|outcome| outcome.score())
+                    .map(|score| score.value)
+                    .collect::<Vec<_>>();
+
+                let summary = MetricSummary {
+                    passed,
+                    failed: outcomes.len() - passed - invalid,
+                    invalid,
+                    pass_rate: ratio(passed, outcomes.len()),
+                    scores: Distribution::from_values(&scores),
+                };
+                (name.to_string(), summary)
+            })
+            .collect();
+
+        let latencies = rows.iter().map(|row| row.latency_ms).collect::<Vec<_>>();
+
+        let mut usage = Usage::new();
+        for row_usage in rows.iter().filter_map(|row| row.usage) {
+            usage += row_usage;
+        }
+
+        let costs = rows.iter().filter_map(|row| row.cost).collect::<Vec<_>>();
+
+        Self {
+            rows: rows.len(),
+            errors: rows.iter().filter(|row| row.error.is_some()).count(),
+            passed,
+            pass_rate: ratio(passed, rows.len()),
+            metrics,
+            latency_ms: Distribution::from_values(&latencies),
+            usage,
+            cost: (!costs.is_empty()).then(|| costs.iter().sum()),
+        }
+    }
+}
+
+/// The report of an experiment run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExperimentReport {
+    pub experiment: String,
+    pub dataset: String,
+    pub summary: Summary,
+    pub rows: Vec<RowResult>,
+}
+
+impl ExperimentReport {
+    /// Create a report from the results of each example, computing the summary.
+    pub fn new(
+        experiment: impl Into<String>,
+        dataset: impl Into<String>,
+        rows: Vec<RowResult>,
+    ) -> Self {
+        Self {
+            experiment: experiment.into(),
+            dataset: dataset.into(),
+            summary: Summary::new(&rows),
+            rows,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, EvalError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, EvalError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Load a report previously saved as JSON (e.g.: a baseline run).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EvalError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Save the report in the directory `dir` as `<experiment>.json` and `<experiment>.md`.
+    pub fn save(&self, dir: impl AsRef<Path>) -> Result<(), EvalError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(
+            dir.join(format!("{}.json", self.experiment)),
+            self.to_json()?,
+        )?;
+        std::fs::write(
+            dir.join(format!("{}.md", self.experiment)),
+            self.to_markdown(),
+        )?;
+        Ok(())
+    }
+
+    /// Render the report as Markdown: the summary, the metrics and the failed examples.
+    pub fn to_markdown(&self) -> String {
+        let summary = &self.summary;
+        let mut md = String::new();
+
+        let _ = writeln!(md, "# Experiment `{}`\n", self.experiment);
+        let _ = writeln!(
+            md,
+            "Dataset `{}`: {} examples, {} errors, {} passed ({}).\n",
+            self.dataset,
+            summary.rows,
+            summary.errors,
+            summary.passed,
+            percent(summary.pass_rate)
+        );
+
+        let _ = writeln!(
+            md,
+            "| Latency p50 (ms) | Latency p90 (ms) | Input tokens | Output tokens | Cost |"
+        );
+        let _ = writeln!(md, "|---|---|---|---|---|");
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} | {} | {} |\n",
+            summary
+                .latency_ms
+                .map_or("-".into(), |latency| format!("{:.0}", latency.p50)),
+            summary
+                .latency_ms
+                .map_or("-".into(), |latency| format!("{:.0}", latency.p90)),
+            summary.usage.input_tokens,
+            summary.usage.output_tokens,
+            summary.cost.map_or("-".into(), |cost| format!("{cost:.4}"))
+        );
+
+        if !summary.metrics.is_empty() {
+            let _ = writeln!(md, "## Metrics\n");
+            let _ = writeln!(
+                md,
+                "| Metric | Pass rate | Passed | Failed | Invalid | Mean score | Min | Max |"
+            );
+            let _ = writeln!(md, "|---|---|---|---|---|---|---|---|");
+            for (name, metric) in &summary.metrics {
+                let score = |f: fn(&Distribution) -> f64| {
+                    metric
+                        .scores
+                        .as_ref()
+                        .map_or("-".into(), |scores| format!("{:.3}", f(scores)))
+                };
+                let _ = writeln!(
+                    md,
+                    "| {name} | {} | {} | {} | {} | {} | {} | {} |",
+                    percent(metric.pass_rate),
+                    metric.passed,
+                    metric.failed,
+                    metric.invalid,
+                    score(|scores| scores.mean),
+                    score(|scores| scores.min),
+                    score(|scores| scores.max),
+                );
+            }
+            md.push('\n');
+        }
+
+        let failures = self
+            .rows
+            .iter()
+            .filter(|row| !row.passed())
+            .collect::<Vec<_>>();
+        if !failures.is_empty() {
+            let _ = writeln!(md, "## Failures\n");
+            let _ = writeln!(md, "| Id | Input | Expected | Output | Failed |");
+            let _ = writeln!(md, "|---|---|---|---|---|");
+            for row in failures {
+                let failed = match &row.error {
+                    Some(error) => format!("error: {error}"),
+                    None => row
+                        .metrics
+                        .iter()
+                        .filter(|(_, outcome)| !outcome.is_pass())
+                        .map(|(name, _)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                };
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} | {} |",
+                    cell(&row.id),
+                    cell(&row.input),
+                    cell(row.expected.as_deref().unwrap_or("-")),
+                    cell(row.output.as_deref().unwrap_or("-")),
+                    cell(&failed)
+                );
+            }
+        }
+
+        md
+    }
+
+    /// Compare the report against the report of a `baseline` run of the same dataset.
+    pub fn compare(&self, baseline: &ExperimentReport) -> Comparison {
+        let metric_names = self
+            .summary
+            .metrics
+            .keys()
+            .chain(baseline.summary.metrics.keys())
+            .collect::<std::collections::BTreeSet<_>>();
+
+        let metrics = metric_names
+            .into_iter()
+            .map(|name| {
+                let current = self.summary.metrics.get(name);
+                let previous = baseline.summary.metrics.get(name);
+                let delta = MetricDelta {
+                    baseline_pass_rate: previous.map(|metric| metric.pass_rate),
+                    pass_rate: current.map(|metric| metric.pass_rate),
+                    baseline_mean_score: previous
+                        .and_then(|metric| metric.scores)
+                        .map(|scores| scores.mean),
+                    mean_score: current
+                        .and_then(|metric| metric.scores)
+                        .map(|scores| scores.mean),
+                };
+                (name.clone(), delta)
+            })
+            .collect();
+
+        let baseline_passed = baseline
+            .rows
+            .iter()
+            .map(|row| (row.id.as_str(), row.passed()))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut regressions = Vec::new();
+        let mut fixes = Vec::new();
+        for row in &self.rows {
+            match (baseline_passed.get(row.id.as_str()), row.passed()) {
+                (Some(true), false) => regressions.push(row.id.clone()),
+                (Some(false), true) => fixes.push(row.id.clone()),
+                _ => {}
+            }
+        }
+
+        Comparison {
+            experiment: self.experiment.clone(),
+            baseline_experiment: baseline.experiment.clone(),
+            baseline_pass_rate: baseline.summary.pass_rate,
+            pass_rate: self.summary.pass_rate,
+            metrics,
+            regressions,
+            fixes,
+            baseline_mean_latency_ms: baseline.summary.latency_ms.map(|latency| latency.mean),
+            mean_latency_ms: self.summary.latency_ms.map(|latency| latency.mean),
+            baseline_cost: baseline.summary.cost,
+            cost: self.summary.cost,
+        }
+    }
+}
+
+/// The change of a metric between a baseline run and the current run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricDelta {
+    /// The pass rate in the baseline run, if the metric was applied
+    pub baseline_pass_rate: Option<f64>,
+    /// The pass rate in the current run, if the metric was applied
+    pub pass_rate: Option<f64>,
+    pub baseline_mean_score: Option<f64>,
+    pub mean_score: Option<f64>,
+}
+
+/// The comparison of an experiment report against a baseline report.
+/// See [ExperimentReport::compare].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub experiment: String,
+    pub baseline_experiment: String,
+    pub baseline_pass_rate: f64,
+    pub pass_rate: f64,
+    pub metrics: BTreeMap<String, MetricDelta>,
+    /// Ids of the examples which passed in the baseline run but not in the current run
+    pub regressions: Vec<String>,
+    /// Ids of the examples which failed in the baseline run but passed in the current run
+    pub fixes: Vec<String>,
+    pub baseline_mean_latency_ms: Option<f64>,
+    pub mean_latency_ms: Option<f64>,
+    pub baseline_cost: Option<f64>,
+    pub cost: Option<f64>,
+}
+
+impl Comparison {
+    /// Whether the overall pass rate or the pass rate of any metric dropped by more than
+    /// `tolerance` (e.g.: `0.05` for 5 percentage points) compared to the baseline.
+    pub fn is_regression(&self, tolerance: f64) -> bool {
+        self.baseline_pass_rate - self.pass_rate > tolerance
+            || self.metrics.values().any(|metric| {
+                match (metric.baseline_pass_rate, metric.pass_rate) {
+                    (Some(baseline), Some(current)) => baseline - current > tolerance,
+                    _ => false,
+                }
+            })
+    }
+
+    /// Render the comparison as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let optional = |value: Option<f64>, f: fn(f64) -> String| value.map_or("-".into(), f);
+
+        let _ = writeln!(
+            md,
+            "# `{}` vs baseline `{}`\n",
+            self.experiment, self.baseline_experiment
+        );
+        let _ = writeln!(md, "| | Baseline | Current |");
+        let _ = writeln!(md, "|---|---|---|");
+        let _ = writeln!(
+            md,
+            "| Pass rate | {} | {} |",
+            percent(self.baseline_pass_rate),
+            percent(self.pass_rate)
+        );
+        for (name, metric) in &self.metrics {
+            let _ = writeln!(
+                md,
+                "| {name} pass rate | {} | {} |",
+                optional(metric.baseline_pass_rate, percent),
+                optional(metric.pass_rate, percent)
+            );
+            let _ = writeln!(
+                md,
+                "| {name} mean score | {} | {} |",
+                optional(metric.baseline_mean_score, |score| format!("{score:.3}")),
+                optional(metric.mean_score, |score| format!("{score:.3}"))
+            );
+        }
+        let _ = writeln!(
+            md,
+            "| Mean latency (ms) | {} | {} |",
+            optional(self.baseline_mean_latency_ms, |latency| format!(
+                "{latency:.0}"
+            )),
+            optional(self.mean_latency_ms, |latency| format!("{latency:.0}"))
+        );
+        let _ = writeln!(
+            md,
+            "| Cost | {} | {} |\n",
+            optional(self.baseline_cost, |cost| format!("{cost:.4}")),
+            optional(self.cost, |cost| format!("{cost:.4}"))
+        );
+
+        if !self.regressions.is_empty() {
+            let _ = writeln!(md, "Regressions: {}\n", self.regressions.join(", "));
+        }
+        if !self.fixes.is_empty() {
+            let _ = writeln!(md, "Fixes: {}\n", self.fixes.join(", "));
+        }
+
+        md
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+fn percent(ratio: f64) -> String {
+    format!("{:.1}%", ratio * 100.0)
+}
+
+/// Make a value safe to use in a Markdown table cell.
+fn cell(value: &str) -> String {
+    const MAX_CHARS: usize = 80;
+
+    let value = value.replace('|', "\\|").replace(['\r', '\n'], " ");
+    if value.chars().count() > MAX_CHARS {
+        format!("{}…", value.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(id: &str, outcome: EvalOutcome<Score>, latency_ms: f64) -> RowResult {
+        RowResult {
+            id: id.to_string(),
+            input: format!("input | {id}"),
+            expected: None,
+            output: Some(format!("output\n{id}")),
+            error: None,
+            latency_ms,
+            usage: None,
+            cost: None,
+            metrics: BTreeMap::from([("score".to_string(), outcome)]),
+        }
+    }
+
+    #[test]
+    fn test_distribution() {
+        let distribution = Distribution::from_values(&[4.0, 1.0, 3.0, 2.0, 5.0]).unwrap();
+        assert_eq!(distribution.min, 1.0);
+        assert_eq!(distribution.max, 5.0);
+        assert_eq!(distribution.mean, 3.0);
+        assert_eq!(distribution.p50, 3.0);
+        assert_eq!(distribution.p90, 5.0);
+
+        assert!(Distribution::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_report_summary_and_markdown() {
+        let report = ExperimentReport::new(
+            "exp",
+            "data",
+            vec![
+                row("a", EvalOutcome::Pass(Score::new(0.9)), 10.0),
+                row("b", EvalOutcome::Fail(Score::new(0.2)), 20.0),
+                row("c", EvalOutcome::Invalid("judge failed".into()), 30.0),
+            ],
+        );
+
+        let metric = &report.summary.metrics["score"];
+        assert_eq!((metric.passed, metric.failed, metric.invalid), (1, 1, 1));
+        assert_eq!(metric.scores.unwrap().max, 0.9);
+        assert_eq!(report.summary.latency_ms.unwrap().p50, 20.0);
+        assert_eq!(report.summary.cost, None);
+
+        let md = report.to_markdown();
+        assert!(md.contains("| score | 33.3% | 1 | 1 | 1 | 0.550 | 0.200 | 0.900 |"));
+        assert!(md.contains("| b | input \\| b | - | output b | score |"));
+        assert!(!md.contains("| a |"));
+
+        let json = report.to_json().unwrap();
+        assert_eq!(ExperimentReport::from_json(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn test_compare() {
+        let baseline = ExperimentReport::new(
+            "baseline",
+            "data",
+            vec![
+                row("a", EvalOutcome::Pass(Score::new(1.0)), 10.0),
+                row("b", EvalOutcome::Fail(Score::new(0.0)), 10.0),
+            ],
+        );
+        let current = ExperimentReport::new(
+            "current",
+            "data",
+            vec![
+                row("a", EvalOutcome::Fail(Score::new(0.0)), 10.0),
+                row("b", EvalOutcome::Fail(Score::new(0.0)), 10.0),
+            ],
+        );
+
+        let comparison = current.compare(&baseline);
+        assert_eq!(comparison.regressions, vec!["a"]);
+        assert!(comparison.fixes.is_empty());
+        assert_eq!(comparison.metrics["score"].baseline_pass_rate, Some(0.5));
+        assert_eq!(comparison.metrics["score"].pass_rate, Some(0.0));
+        assert!(comparison.is_regression(0.1));
+        assert!(!comparison.is_regression(0.6));
+
+        let comparison = baseline.compare(&current);
+        assert_eq!(comparison.fixes, vec!["a"]);
+        assert!(!comparison.is_regression(0.0));
+        assert!(
+            comparison
+                .to_markdown()
+                .contains("| Pass rate | 0.0% | 50.0% |")
+        );
+    }
+}
@@ -20,6 +20,11 @@
 //!
 //! // Create the extractor
 //! let extractor = openai.extractor::<Person>(openai::GPT_4O)
+//!     .retries(2)
+//!     .validator(|person: &Person| match person.age {
+//!         Some(age) if age > 150 => Err(format!("{age} is not a plausible age")),
+//!         _ => Ok(()),
+//!     })
 //!     .build();
 //!
 //! // Extract structured data from text
@@ -27,6 +32,10 @@
 //!     .await
 //!     .expect("Failed to extract data from text");
 //! ```
+//!
+//! When an attempt fails (the model doesn't call `submit`, the arguments don't deserialize, or the
+//! validator rejects them), the next attempt sends the failed tool call back to the model along
+//! with a tool result describing the error, so the model can correct itself.
 
 use std::marker::PhantomData;
 
@@ -35,9 +44,13 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::{
+    OneOrMany,
     agent::{Agent, AgentBuilder, AgentBuilderSimple},
     completion::{Completion, CompletionError, CompletionModel, ToolDefinition},
-    message::{AssistantContent, Message, ToolCall, ToolChoice, ToolFunction},
+    message::{
+        AssistantContent, Message, ToolCall, ToolChoice, ToolFunction, ToolResultContent,
+        UserContent,
+    },
     tool::Tool,
     wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
@@ -54,6 +67,32 @@ pub enum ExtractionError {
 
     #[error("CompletionError: {0}")]
     CompletionError(#[from] CompletionError),
+
+    /// The extracted data was rejected by the validator of the extractor
+    #[error("Validation failed: {0}")]
+    ValidationError(String),
+
+    /// Every attempt failed. Contains the error of each attempt, in order.
+    #[error("Extraction failed after {} attempts, last error: {}", .0.len(), .0.last().map(ToString::to_string).unwrap_or_default())]
+    AttemptsExhausted(Vec<ExtractionError>),
+}
+
+/// Validation run on the extracted data after deserialization
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), String> + Send + Sync>;
+
+/// Error of a failed extraction attempt, along with the model output if there was one
+struct FailedAttempt {
+    error: ExtractionError,
+    choice: Option<OneOrMany<AssistantContent>>,
+}
+
+impl From<CompletionError> for FailedAttempt {
+    fn from(error: CompletionError) -> Self {
+        Self {
+            error: error.into(),
+            choice: None,
+        }
+    }
 }
 
 /// Extractor for structured data from text
@@ -65,6 +104,7 @@ where
     agent: Agent<M>,
     _t: PhantomData<T>,
     retries: u64,
+    validator: Option<Validator<T>>,
 }
 
 impl<M, T> Extractor<M, T>
@@ -74,74 +114,74 @@ where
 {
     /// Attempts to extract data from the given text with a number of retries.
     ///
-    /// The function will retry the extraction if the initial attempt fails or
-    /// if the model does not call the `submit` tool.
+    /// The function will retry the extraction if the initial attempt fails,
+    /// if the model does not call the `submit` tool, or if the validator rejects the data.
+    /// Retries include the failed attempt and its error so the model can correct itself.
     ///
     /// The number of retries is determined by the `retries` field on the Extractor struct.
+    /// If every attempt fails, [ExtractionError::AttemptsExhausted] is returned, even without retries.
     pub async fn extract(
         &self,
         text: impl Into<Message> + WasmCompatSend,
     ) -> Result<T, ExtractionError> {
-        let mut last_error = None;
-        let text_message = text.into();
-
-        for i in 0..=self.retries {
-            tracing::debug!(
-                "Attempting to extract JSON. Retries left: {retries}",
-                retries = self.retries - i
-            );
-            let attempt_text = text_message.clone();
-            match self.extract_json(attempt_text, vec![]).await {
-                Ok(data) => return Ok(data),
-                Err(e) => {
-                    tracing::warn!("Attempt {i} to extract JSON failed: {e:?}. Retrying...");
-                    last_error = Some(e);
-                }
-            }
-        }
-
-        // If the loop finishes without a successful extraction, return the last error encountered.
-        Err(last_error.unwrap_or(ExtractionError::NoData))
+        self.extract_with_chat_history(text, vec![]).await
     }
 
     /// Attempts to extract data from the given text with a number of retries.
     ///
-    /// The function will retry the extraction if the initial attempt fails or
-    /// if the model does not call the `submit` tool.
+    /// The function will retry the extraction if the initial attempt fails,
+    /// if the model does not call the `submit` tool, or if the validator rejects the data.
+    /// Retries include the failed attempt and its error so the model can correct itself.
     ///
     /// The number of retries is determined by the `retries` field on the Extractor struct.
+    /// If every attempt fails, [ExtractionError::AttemptsExhausted] is returned, even without retries.
     pub async fn extract_with_chat_history(
         &self,
         text: impl Into<Message> + WasmCompatSend,
         chat_history: Vec<Message>,
     ) -> Result<T, ExtractionError> {
-        let mut last_error = None;
-        let text_message = text.into();
+        let mut errors = Vec::new();
+        let mut prompt = text.into();
+        let mut chat_history = chat_history;
 
         for i in 0..=self.retries {
             tracing::debug!(
                 "Attempting to extract JSON. Retries left: {retries}",
                 retries = self.retries - i
             );
-            let attempt_text = text_message.clone();
-            match self.extract_json(attempt_text, chat_history.clone()).await {
+            match self
+                .extract_json(prompt.clone(), chat_history.clone())
+                .await
+            {
                 Ok(data) => return Ok(data),
-                Err(e) => {
-                    tracing::warn!("Attempt {i} to extract JSON failed: {e:?}. Retrying...");
-                    last_error = Some(e);
+                Err(FailedAttempt { error, choice }) => {
+                    tracing::warn!("Attempt {i} to extract JSON failed: {error:?}. Retrying...");
+
+                    // Feed the failed output and the error back to the model
+                    if let Some(choice) = choice {
+                        let correction = correction_message(&choice, &error);
+                        chat_history.push(prompt);
+                        chat_history.push(Message::Assistant {
+                            id: None,
+                            content: choice,
+                        });
+                        prompt = correction;
+                    }
+
+                    errors.push(error);
                 }
             }
         }
 
-        // If the loop finishes without a successful extraction, return the last error encountered.
-        Err(last_error.unwrap_or(ExtractionError::NoData))
+        // If the loop finishes without a successful extraction, return the errors encountered.
+        Err(ExtractionError::AttemptsExhausted(errors))
     }
 
     async fn extract_json(
         &self,
         text: impl Into<Message> + WasmCompatSend,
         messages: Vec<Message>,
-    ) -> Result<T, ExtractionError> {
+    ) -> Result<T, FailedAttempt> {
         let response = self.agent.completion(text, messages).await?.send().await?;
 
         if !response.choice.iter().any(|x| {
@@ -162,7 +202,7 @@ where
 
         let arguments = response
             .choice
-            .into_iter()
+            .iter()
             // We filter tool calls to look for submit tool calls
             .filter_map(|content| {
                 if let AssistantContent::ToolCall(ToolCall {
@@ -171,7 +211,7 @@ where
                 }) = content
                 {
                     if name == SUBMIT_TOOL_NAME {
-                        Some(arguments)
+                        Some(arguments.clone())
                     } else {
                         None
                     }
@@ -187,13 +227,22 @@ where
             );
         }
 
-        let raw_data = if let Some(arg) = arguments.into_iter().next() {
-            arg
-        } else {
-            return Err(ExtractionError::NoData);
+        let result = match arguments.into_iter().next() {
+            Some(raw_data) => serde_json::from_value::<T>(raw_data)
+                .map_err(ExtractionError::from)
+                .and_then(|data| match &self.validator {
+                    Some(validator) => validator(&data)
+                        .map(|_| data)
+                        .map_err(ExtractionError::ValidationError),
+                    None => Ok(data),
+                }),
+            None => Err(ExtractionError::NoData),
         };
 
-        Ok(serde_json::from_value(raw_data)?)
+        result.map_err(|error| FailedAttempt {
+            error,
+            choice: Some(response.choice),
+        })
     }
 
     pub async fn get_inner(&self) -> &Agent<M> {
@@ -205,6 +254,49 @@ where
     }
 }
 
+/// Build the message correcting a failed attempt.
+///
+/// Every tool call of the failed output gets a tool result, as most providers reject
+/// conversations with unanswered tool calls. If there are none, a plain user message is used.
+fn correction_message(choice: &OneOrMany<AssistantContent>, error: &ExtractionError) -> Message {
+    let tool_results = choice
+        .iter()
+        .filter_map(|content| match content {
+            AssistantContent::ToolCall(ToolCall {
+                id,
+                call_id,
+                function: ToolFunction { name, .. },
+                ..
+            }) => {
+                let text = if name == SUBMIT_TOOL_NAME {
+                    format!(
+                        "Error: {error}. Fix the data and call the `{SUBMIT_TOOL_NAME}` tool again."
+                    )
+                } else {
+                    format!(
+                        "Error: the `{name}` tool is not available. Call the `{SUBMIT_TOOL_NAME}` tool with the extracted data."
+                    )
+                };
+                let content = OneOrMany::one(ToolResultContent::text(text));
+
+                Some(match call_id {
+                    Some(call_id) => {
+                        UserContent::tool_result_with_call_id(id, call_id.clone(), content)
+                    }
+                    None => UserContent::tool_result(id, content),
+                })
+            }
+            _ => None,
+        });
+
+    match OneOrMany::many(tool_results) {
+        Ok(content) => Message::User { content },
+        Err(_) => Message::user(format!(
+            "You did not call the `{SUBMIT_TOOL_NAME}` tool. Call it with the data extracted from the provided text."
+        )),
+    }
+}
+
 /// Builder for the Extractor
 pub struct ExtractorBuilder<M, T>
 where
@@ -214,6 +306,7 @@ where
     agent_builder: AgentBuilderSimple<M>,
     _t: PhantomData<T>,
     retries: Option<u64>,
+    validator: Option<Validator<T>>,
 }
 
 impl<M, T> ExtractorBuilder<M, T>
@@ -233,6 +326,7 @@ where
                 .tool(SubmitTool::<T> {_t: PhantomData})
                 .tool_choice(ToolChoice::Required),
             retries: None,
+            validator: None,
             _t: PhantomData,
         }
     }
@@ -268,6 +362,16 @@ where
         self
     }
 
+    /// Set a validation run on the extracted data after deserialization.
+    /// Returning an error triggers a retry, with the error message sent back to the model.
+    pub fn validator(
+        mut self,
+        validator: impl Fn(&T) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
     /// Set the `tool_choice` option for the inner Agent.
     pub fn tool_choice(mut self, choice: ToolChoice) -> Self {
         self.agent_builder = self.agent_builder.tool_choice(choice);
@@ -280,6 +384,7 @@ where
             agent: self.agent_builder.build(),
             _t: PhantomData,
             retries: self.retries.unwrap_or(0),
+            validator: self.validator,
         }
     }
 }
@@ -318,3 +423,126 @@ where
         Ok(data)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{ExtractionError, ExtractorBuilder, SUBMIT_TOOL_NAME};
+    use crate::{
+        message::{Message, ToolResultContent, UserContent},
+        testing::MockCompletionModel,
+    };
+
+    #[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq)]
+    struct Person {
+        name: String,
+        age: u8,
+    }
+
+    /// Text of the tool result sent in the last request
+    fn last_tool_result(model: &MockCompletionModel) -> String {
+        let requests = model.requests();
+        let Message::User { content } = requests.last().unwrap().chat_history.last() else {
+            panic!("The prompt should be a user message");
+        };
+        let UserContent::ToolResult(result) = content.first() else {
+            panic!("The prompt should be a tool result");
+        };
+        let ToolResultContent::Text(text) = result.content.first() else {
+            panic!("The tool result should be text");
+        };
+        text.text
+    }
+
+    #[tokio::test]
+    async fn test_retry_feeds_back_deserialization_error() {
+        let model = MockCompletionModel::new()
+            .tool_call(SUBMIT_TOOL_NAME, json!({"name": "John"}))
+            .tool_call(SUBMIT_TOOL_NAME, json!({"name": "John", "age": 30}));
+        let extractor = ExtractorBuilder::<_, Person>::new(model.clone())
+            .retries(1)
+            .build();
+
+        let person = extractor.extract("John is 30").await.unwrap();
+
+        assert_eq!(
+            person,
+            Person {
+                name: "John".into(),
+                age: 30
+            }
+        );
+
+        // The retry contains the prompt, the failed call and the error
+        assert_eq!(model.requests()[1].chat_history.len(), 3);
+        assert!(last_tool_result(&model).contains("missing field `age`"));
+    }
+
+    #[tokio::test]
+    async fn test_validator_triggers_retry() {
+        let model = MockCompletionModel::new()
+            .tool_call(SUBMIT_TOOL_NAME, json!({"name": "John", "age": 200}))
+            .tool_call(SUBMIT_TOOL_NAME, json!({"name": "John", "age": 20}));
+        let extractor = ExtractorBuilder::<_, Person>::new(model.clone())
+            .retries(1)
+            .validator(|person: &Person| {
+                if person.age > 150 {
+                    Err(format!("{} is not a plausible age", person.age))
+                } else {
+                    Ok(())
+                }
+            })
+            .build();
+
+        let person = extractor.extract("John is 20").await.unwrap();
+
+        assert_eq!(person.age, 20);
+        assert!(last_tool_result(&model).contains("200 is not a plausible age"));
+    }
+
+    #[tokio::test]
+    async fn test_all_attempt_errors_are_returned() {
+        let model = MockCompletionModel::new()
+            .text("John is 30")
+            .tool_call(SUBMIT_TOOL_NAME, json!({"age": 30}));
+        let extractor = ExtractorBuilder::<_, Person>::new(model.clone())
+            .retries(1)
+            .build();
+
+        let error = extractor.extract("John is 30").await.unwrap_err();
+
+        let ExtractionError::AttemptsExhausted(errors) = error else {
+            panic!("Expected all attempt errors, got {error:?}");
+        };
+        assert!(matches!(errors[0], ExtractionError::NoData));
+        assert!(matches!(
+            errors[1],
+            ExtractionError::DeserializationError(_)
+        ));
+
+        // Without a tool call, the correction is a plain user message
+        let requests = model.requests();
+        let Message::User { content } = requests[1].chat_history.last() else {
+            panic!("The prompt should be a user message");
+        };
+        assert!(matches!(content.first(), UserContent::Text(_)));
+    }
+
+    #[tokio::test]
+    async fn test_attempts_exhausted_without_retries() {
+        let model = MockCompletionModel::new().text("John is 30");
+        let extractor = ExtractorBuilder::<_, Person>::new(model.clone()).build();
+
+        let error = extractor.extract("John is 30").await.unwrap_err();
+
+        let ExtractionError::AttemptsExhausted(errors) = error else {
+            panic!("Expected all attempt errors, got {error:?}");
+        };
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(errors[0], ExtractionError::NoData));
+        assert_eq!(model.requests().len(), 1);
+    }
+}
@@ -0,0 +1,282 @@
+//! This module provides functionality for working with provider file APIs.
+//!
+//! Documents and images are usually inlined in every request that uses them (as base64 or URLs),
+//! which makes each turn of a long conversation about the same PDF resend the whole PDF. Providers
+//! with a Files API let you upload the file once and reference it by id instead, using
+//! [DocumentSourceKind::FileId] (see [UserContent::document_file_id] and
+//! [UserContent::image_file_id]). Each provider translates the id into its native file reference.
+//!
+//! # Example
+//! ```no_run
+//! use rig::completion::Prompt;
+//! use rig::files::{FileUpload, FilesClient};
+//! use rig::message::{Message, UserContent};
+//! use rig::prelude::*;
+//! use rig::providers::anthropic;
+//! use rig::OneOrMany;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = anthropic::Client::from_env();
+//!
+//! let file = client
+//!     .upload_file(FileUpload::from_path("report.pdf")?)
+//!     .await?;
+//!
+//! let agent = client.agent(anthropic::completion::CLAUDE_3_7_SONNET).build();
+//! let message = Message::User {
+//!     content: OneOrMany::many(vec![
+//!         file.document(),
+//!         UserContent::text("Summarize this report"),
+//!     ])?,
+//! };
+//!
+//! println!("{}", agent.prompt(message).await?);
+//!
+//! client.delete_file(&file.id).await?;
+//! # Ok(())
+//! # }
+//! ```
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{
+    client::{Client, Provider},
+    completion::message::{
+        DocumentMediaType, DocumentSourceKind, ImageMediaType, MimeType, UserContent,
+    },
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+// Errors
+#[derive(Debug, Error)]
+pub enum FilesError {
+    /// Http error (e.g.: connection error, timeout, etc.)
+    #[error("HttpError: {0}")]
+    HttpError(#[from] http_client::Error),
+
+    /// Json error (e.g.: serialization, deserialization)
+    #[error("JsonError: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Error reading the file to upload
+    #[error("IoError: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// Error returned by the provider
+    #[error("ProviderError: {0}")]
+    ProviderError(String),
+}
+
+/// A file to upload.
+#[derive(Debug, Clone)]
+pub struct FileUpload {
+    pub filename: String,
+    pub data: Vec<u8>,
+    /// The MIME type of the file, guessed from the filename by [FileUpload::new]
+    pub mime_type: Option<String>,
+    /// What the file will be used for. Only used by OpenAI, where it defaults to `user_data`
+    pub purpose: Option<String>,
+}
+
+impl FileUpload {
+    pub fn new(filename: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        let filename = filename.into();
+        let mime_type = mime_guess::from_path(&filename)
+            .first()
+            .map(|mime| mime.to_string());
+
+        Self {
+            filename,
+            data: data.into(),
+            mime_type,
+            purpose: None,
+        }
+    }
+
+    /// Read the file to upload from disk.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, FilesError> {
+        let path = path.as_ref();
+        let filename = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        Ok(Self::new(filename, std::fs::read(path)?))
+    }
+
+    pub fn mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    pub fn purpose(mut self, purpose: impl Into<String>) -> Self {
+        self.purpose = Some(purpose.into());
+        self
+    }
+}
+
+/// A file uploaded to a provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileObject {
+    /// The id used to reference the file in messages, see [DocumentSourceKind::FileId]
+    pub id: String,
+    pub filename: Option<String>,
+    pub mime_type: Option<String>,
+    /// The size of the file in bytes
+    pub bytes: Option<u64>,
+    /// The file object returned by the provider
+    pub raw: serde_json::Value,
+}
+
+impl FileObject {
+    /// A document referencing this file, to be used in a user message.
+    pub fn document(&self) -> UserContent {
+        UserContent::document_file_id(
+            &self.id,
+            self.mime_type
+                .as_deref()
+                .and_then(DocumentMediaType::from_mime_type),
+        )
+    }
+
+    /// An image referencing this file, to be used in a user message.
+    pub fn image(&self) -> UserContent {
+        UserContent::image_file_id(
+            &self.id,
+            self.mime_type
+                .as_deref()
+                .and_then(ImageMediaType::from_mime_type),
+        )
+    }
+}
+
+impl From<&FileObject> for DocumentSourceKind {
+    fn from(file: &FileObject) -> Self {
+        DocumentSourceKind::FileId(file.id.clone())
+    }
+}
+
+/// A provider client that can upload files, to be referenced by id in messages.
+pub trait FilesClient {
+    /// Upload a file.
+    fn upload_file(
+        &self,
+        file: FileUpload,
+    ) -> impl Future<Output = Result<FileObject, FilesError>> + WasmCompatSend;
+
+    /// List the uploaded files.
+    fn list_files(
+        &self,
+    ) -> impl Future<Output = Result<Vec<FileObject>, FilesError>> + WasmCompatSend;
+
+    /// Retrieve the metadata of the file with the given id.
+    fn retrieve_file(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<FileObject, FilesError>> + WasmCompatSend;
+
+    /// Delete the file with the given id.
+    fn delete_file(
+        &self,
+        id: &str,
+    ) -> impl Future<Output = Result<(), FilesError>> + WasmCompatSend;
+}
+
+impl<Ext, H> Client<Ext, H>
+where
+    H: HttpClientExt + 'static,
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+{
+    /// Send a request to a files endpoint and deserialize its response.
+    pub(crate) async fn files_response<B, T>(
+        &self,
+        req: http_client::Request<B>,
+    ) -> Result<T, FilesError>
+    where
+        B: Into<bytes::Bytes> + WasmCompatSend,
+        T: DeserializeOwned,
+    {
+        let response = self.send(req).await?;
+        let status = response.status();
+        let text = http_client::text(response).await?;
+
+        files_result(status, &text)
+    }
+
+    /// Upload a file as a multipart form, with the given text fields.
+    pub(crate) async fn upload_multipart<T>(
+        &self,
+        req: http_client::Builder,
+        fields: &[(&str, &str)],
+        file: FileUpload,
+    ) -> Result<T, FilesError>
+    where
+        T: DeserializeOwned,
+    {
+        let mut part =
+            http_client::multipart::Part::bytes("file", file.data).filename(file.filename);
+        if let Some(mime) = file.mime_type.and_then(|mime| mime.parse().ok()) {
+            part = part.content_type(mime);
+        }
+
+        let form = fields
+            .iter()
+            .fold(http_client::MultipartForm::new(), |form, (name, value)| {
+                form.text(*name, *value)
+            })
+            .part(part);
+
+        let req = req.body(form).map_err(http_client::Error::from)?;
+
+        let response = self.send_multipart::<bytes::Bytes>(req).await?;
+        let status = response.status();
+        let body = response.into_body().await?;
+
+        files_result(status, &String::from_utf8_lossy(&body))
+    }
+}
+
+/// Deserialize the response of a files endpoint, or turn it into an error.
+pub(crate) fn files_result<T>(status: http::StatusCode, text: &str) -> Result<T, FilesError>
+where
+    T: DeserializeOwned,
+{
+    if !status.is_success() {
+        return Err(FilesError::ProviderError(format!(
+            "Failed with '{status}': {text}"
+        )));
+    }
+
+    Ok(serde_json::from_str(text)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_upload_guesses_mime_type() {
+        let upload = FileUpload::new("report.pdf", b"%PDF-1.4".to_vec());
+        assert_eq!(upload.mime_type.as_deref(), Some("application/pdf"));
+
+        let upload = FileUpload::new("data", b"".to_vec()).mime_type("text/plain");
+        assert_eq!(upload.mime_type.as_deref(), Some("text/plain"));
+    }
+
+    #[test]
+    fn test_file_object_content() {
+        let file = FileObject {
+            id: "file-abc".into(),
+            filename: Some("report.pdf".into()),
+            mime_type: Some("application/pdf".into()),
+            bytes: Some(8),
+            raw: serde_json::Value::Null,
+        };
+
+        assert_eq!(
+            file.document(),
+            UserContent::document_file_id("file-abc", Some(DocumentMediaType::PDF))
+        );
+    }
+}
@@ -8,6 +8,9 @@ pub mod multipart;
 pub mod retry;
 pub mod sse;
 pub mod stream_stats;
+#[cfg(feature = "realtime")]
+#[cfg_attr(docsrs, doc(cfg(feature = "realtime")))]
+pub mod websocket;
 
 pub use stream_stats::{
     ByteCountingStream, StreamBytesCounter,
@@ -1,7 +1,8 @@
 //! Helpers to handle connection delays when receiving errors
 
 use super::Error;
-use std::time::Duration;
+use futures_timer::Delay;
+use std::{fmt::Display, time::Duration};
 
 pub trait RetryPolicy {
     /// Submit a new retry delay based on the [`enum@Error`], last retry number and duration, if
@@ -12,6 +13,38 @@ pub trait RetryPolicy {
     fn set_reconnection_time(&mut self, duration: Duration);
 }
 
+/// Run `attempt` until it succeeds or `policy` gives up, waiting the delay chosen by the policy
+/// between attempts. Errors are passed to the policy as [`Error::Instance`].
+/// Returns the output along with the number of retries, or the last error.
+pub async fn retry_with<T, E, F, Fut>(
+    policy: &impl RetryPolicy,
+    mut attempt: F,
+) -> Result<(T, usize), E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    let mut last_retry: Option<(usize, Duration)> = None;
+
+    loop {
+        let error = match attempt().await {
+            Ok(output) => return Ok((output, last_retry.map(|(n, _)| n).unwrap_or(0))),
+            Err(error) => error,
+        };
+
+        let policy_error = Error::Instance(error.to_string().into());
+        match policy.retry(&policy_error, last_retry) {
+            Some(delay) => {
+                tracing::warn!(target: "rig", "Attempt failed, retrying in {delay:?}: {error}");
+                Delay::new(delay).await;
+                last_retry = Some((last_retry.map(|(n, _)| n).unwrap_or(0) + 1, delay));
+            }
+            None => return Err(error),
+        }
+    }
+}
+
 /// A [`RetryPolicy`] which backs off exponentially
 #[derive(Debug, Clone)]
 pub struct ExponentialBackoff {
@@ -0,0 +1,160 @@
+//! A WebSocket transport, for the bidirectional APIs that do not fit the request/response and SSE
+//! models of [`crate::http_client::HttpClientExt`] (e.g. the realtime APIs, see [`crate::realtime`]).
+//!
+//! The handshake request is built like any other request of a provider client (see
+//! [`crate::client::Client::websocket`]), so that the default headers and auth of the client are
+//! sent along with it. Secure (`wss://`) connections use the TLS backend selected by the
+//! `reqwest-tls` (native TLS) or `reqwest-rustls` (rustls) feature.
+
+use bytes::Bytes;
+use futures::{
+    SinkExt, StreamExt,
+    stream::{SplitSink, SplitStream},
+};
+use http::Request;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream,
+    tungstenite::{self, client::IntoClientRequest},
+};
+
+use super::{Error, Result, instance_error};
+
+type Stream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// A message sent or received over a [WebSocket]. Pings and pongs are handled by the transport.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebSocketMessage {
+    Text(String),
+    Binary(Bytes),
+    Close,
+}
+
+impl From<String> for WebSocketMessage {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<WebSocketMessage> for tungstenite::Message {
+    fn from(message: WebSocketMessage) -> Self {
+        match message {
+            WebSocketMessage::Text(text) => tungstenite::Message::Text(text),
+            WebSocketMessage::Binary(data) => tungstenite::Message::Binary(data.to_vec()),
+            WebSocketMessage::Close => tungstenite::Message::Close(None),
+        }
+    }
+}
+
+fn ws_error(error: tungstenite::Error) -> Error {
+    match error {
+        tungstenite::Error::Http(response) => {
+            let status = response.status();
+            match response.into_body() {
+                Some(body) => Error::InvalidStatusCodeWithMessage(
+                    status,
+                    String::from_utf8_lossy(&body).into_owned(),
+                ),
+                None => Error::InvalidStatusCode(status),
+            }
+        }
+        tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+            Error::StreamEnded
+        }
+        error => instance_error(error),
+    }
+}
+
+/// Open a WebSocket connection. The method of `req` is ignored, and its headers are sent along with
+/// the handshake headers.
+pub async fn connect(req: Request<()>) -> Result<WebSocket> {
+    let mut request = req
+        .uri()
+        .to_string()
+        .into_client_request()
+        .map_err(ws_error)?;
+    request
+        .headers_mut()
+        .extend(req.headers().iter().map(|(k, v)| (k.clone(), v.clone())));
+
+    let (stream, _) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(ws_error)?;
+
+    Ok(WebSocket { stream })
+}
+
+/// An open WebSocket connection.
+pub struct WebSocket {
+    stream: Stream,
+}
+
+impl WebSocket {
+    pub async fn send(&mut self, message: WebSocketMessage) -> Result<()> {
+        self.stream.send(message.into()).await.map_err(ws_error)
+    }
+
+    /// Receive the next message, `None` once the connection is closed.
+    pub async fn next(&mut self) -> Option<Result<WebSocketMessage>> {
+        next_message(&mut self.stream).await
+    }
+
+    /// Split the connection in halves that can be used concurrently, e.g. to send audio while
+    /// receiving the events of the server.
+    pub fn split(self) -> (WebSocketSender, WebSocketReceiver) {
+        let (sink, stream) = self.stream.split();
+        (WebSocketSender { sink }, WebSocketReceiver { stream })
+    }
+}
+
+/// The sending half of a [WebSocket].
+pub struct WebSocketSender {
+    sink: SplitSink<Stream, tungstenite::Message>,
+}
+
+impl WebSocketSender {
+    pub async fn send(&mut self, message: WebSocketMessage) -> Result<()> {
+        self.sink.send(message.into()).await.map_err(ws_error)
+    }
+
+    /// Send a close frame and close the connection.
+    pub async fn close(&mut self) -> Result<()> {
+        self.sink.close().await.map_err(ws_error)
+    }
+}
+
+/// The receiving half of a [WebSocket].
+pub struct WebSocketReceiver {
+    stream: SplitStream<Stream>,
+}
+
+impl WebSocketReceiver {
+    /// Receive the next message, `None` once the connection is closed.
+    pub async fn next(&mut self) -> Option<Result<WebSocketMessage>> {
+        next_message(&mut self.stream).await
+    }
+}
+
+async fn next_message<S>(stream: &mut S) -> Option<Result<WebSocketMessage>>
+where
+    S: futures::Stream<Item = std::result::Result<tungstenite::Message, tungstenite::Error>>
+        + Unpin,
+{
+    loop {
+        let message = match stream.next().await? {
+            Ok(message) => message,
+            Err(tungstenite::Error::ConnectionClosed) => return None,
+            Err(error) => return Some(Err(ws_error(error))),
+        };
+
+        return Some(Ok(match message {
+            tungstenite::Message::Text(text) => WebSocketMessage::Text(text),
+            tungstenite::Message::Binary(data) => WebSocketMessage::Binary(data.into()),
+            tungstenite::Message::Close(_) => WebSocketMessage::Close,
+            // Pongs are sent back by tungstenite
+            tungstenite::Message::Ping(_)
+            | tungstenite::Message::Pong(_)
+            | tungstenite::Message::Frame(_) => continue,
+        }));
+    }
+}
@@ -110,6 +110,7 @@ pub mod agent;
 #[cfg(feature = "audio")]
 #[cfg_attr(docsrs, doc(cfg(feature = "audio")))]
 pub mod audio_generation;
+pub mod batch;
 pub mod client;
 pub mod completion;
 pub mod compression;
@@ -119,6 +120,7 @@ pub mod embeddings;
 #[cfg_attr(docsrs, doc(cfg(feature = "experimental")))]
 pub mod evals;
 pub mod extractor;
+pub mod files;
 pub mod http_client;
 #[cfg(feature = "image")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
@@ -130,8 +132,12 @@ pub mod one_or_many;
 pub mod pipeline;
 pub mod prelude;
 pub mod providers;
+#[cfg(feature = "realtime")]
+#[cfg_attr(docsrs, doc(cfg(feature = "realtime")))]
+pub mod realtime;
 
 pub mod streaming;
+pub mod testing;
 pub mod tool;
 pub mod tools;
 pub mod transcription;
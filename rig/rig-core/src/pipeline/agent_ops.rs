@@ -159,25 +159,30 @@ where
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use crate::message;
-    use completion::{Prompt, PromptError};
+    use crate::{
+        agent::{Agent, AgentBuilder},
+        message,
+        testing::MockCompletionModel,
+    };
     use vector_store::{VectorStoreError, VectorStoreIndex, request::Filter};
 
-    pub struct MockModel;
-
-    impl Prompt for MockModel {
-        #[allow(refining_impl_trait)]
-        async fn prompt(&self, prompt: impl Into<message::Message>) -> Result<String, PromptError> {
-            let msg: message::Message = prompt.into();
-            let prompt = match msg {
-                message::Message::User { content } => match content.first() {
-                    message::UserContent::Text(message::Text { text }) => text,
-                    _ => unreachable!(),
-                },
-                _ => unreachable!(),
-            };
-            Ok(format!("Mock response: {prompt}"))
-        }
+    /// An agent over a [MockCompletionModel] answering a single prompt with `response`.
+    pub fn mock_agent(response: &str) -> (Agent<MockCompletionModel>, MockCompletionModel) {
+        let model = MockCompletionModel::new().text(response);
+        (AgentBuilder::new(model.clone()).build(), model)
+    }
+
+    /// Text of the prompt sent in the last request
+    pub fn last_prompt(model: &MockCompletionModel) -> String {
+        let requests = model.requests();
+        let message::Message::User { content } = requests.last().unwrap().chat_history.last()
+        else {
+            panic!("The prompt should be a user message");
+        };
+        let message::UserContent::Text(message::Text { text, .. }) = content.first() else {
+            panic!("The prompt should be text");
+        };
+        text
     }
 
     pub struct MockIndex;
@@ -230,10 +235,11 @@ pub mod tests {
 
     #[tokio::test]
     async fn test_prompt() {
-        let model = MockModel;
-        let prompt = prompt::<MockModel, String>(model);
+        let (agent, model) = mock_agent("Mock response");
+        let prompt = prompt::<_, String>(agent);
 
         let result = prompt.call("hello".to_string()).await.unwrap();
-        assert_eq!(result, "Mock response: hello");
+        assert_eq!(result, "Mock response");
+        assert_eq!(last_prompt(&model), "hello");
     }
 }
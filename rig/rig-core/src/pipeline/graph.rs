@@ -0,0 +1,726 @@
+//! Graph (state machine) workflows.
+//!
+//! The [Op] and [TryOp] combinators compose strictly acyclic chains of operations. A [Graph] instead
+//! is a set of named nodes connected by edges, which can be conditional and can form cycles. This makes
+//! it possible to express workflows such as evaluator-optimizer or plan-execute-replan loops.
+//!
+//! Every node of a graph receives the shared, typed state of the workflow and returns the updated state.
+//! Once a node has run, its outgoing edge decides which node runs next: either a fixed node
+//! ([GraphBuilder::edge]) or a node chosen by a router function from the current state
+//! ([GraphBuilder::conditional_edge]). The graph finishes when the [END] node is reached (or when a node
+//! without outgoing edge has run). Cycles are guarded by a maximum number of node executions
+//! ([GraphBuilder::max_iterations]).
+//!
+//! After each node execution, the state and the next node can be saved by a [Checkpointer], so that a
+//! long-running workflow can be inspected or resumed with [Graph::resume].
+//!
+//! Each node execution is recorded in a `graph_node` tracing span.
+//!
+//! # Example
+//! ```rust
+//! use rig::pipeline::{map, graph::{Graph, END}};
+//!
+//! #[derive(Clone, Debug)]
+//! struct State {
+//!     draft: String,
+//!     revisions: usize,
+//! }
+//!
+//! let graph = Graph::builder()
+//!     .node("write", map(|mut state: State| {
+//!         state.draft.push('!');
+//!         state.revisions += 1;
+//!         state
+//!     }))
+//!     .conditional_edge("write", |state: &State| {
+//!         if state.revisions < 3 { "write" } else { END }
+//!     })
+//!     .start("write")
+//!     .build()?;
+//!
+//! let state = graph.run(State { draft: "Hello".to_string(), revisions: 0 }).await?;
+//! assert_eq!(state.draft, "Hello!!!");
+//! ```
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    future::IntoFuture,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+use tracing::info_span;
+use tracing_futures::Instrument;
+
+use crate::{
+    completion,
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
+
+use super::{Op, TryOp};
+
+/// Name of the node that terminates the graph when it is reached.
+pub const END: &str = "__end__";
+
+/// The default maximum number of node executions of a graph run.
+pub const DEFAULT_MAX_ITERATIONS: usize = 25;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GraphError {
+    #[error("The graph has no start node")]
+    MissingStart,
+
+    #[error("Unknown node: {0}")]
+    UnknownNode(String),
+
+    #[error("Node `{0}` is defined more than once")]
+    DuplicateNode(String),
+
+    #[error("Node `{0}` has more than one outgoing edge")]
+    DuplicateEdge(String),
+
+    #[error("The graph did not reach its end after {0} node executions")]
+    MaxIterationsExceeded(usize),
+
+    #[error("Node `{node}` failed: {source}")]
+    Node {
+        node: String,
+        #[source]
+        source: BoxError,
+    },
+
+    #[error("CheckpointError: {0}")]
+    Checkpoint(BoxError),
+}
+
+/// A node of a [Graph]: takes the current state of the workflow and returns the updated state.
+///
+/// Nodes are usually created from an [Op], a [TryOp] or an agent with [GraphBuilder::node],
+/// [GraphBuilder::try_node] and [GraphBuilder::agent_node], but this trait can also be implemented directly.
+pub trait Node<S>: WasmCompatSend + WasmCompatSync {
+    fn run(&self, state: S) -> WasmBoxedFuture<'_, Result<S, BoxError>>;
+}
+
+struct OpNode<O>(O);
+
+impl<O, S> Node<S> for OpNode<O>
+where
+    O: Op<Input = S, Output = S>,
+    S: WasmCompatSend + WasmCompatSync + 'static,
+{
+    fn run(&self, state: S) -> WasmBoxedFuture<'_, Result<S, BoxError>> {
+        Box::pin(async move { Ok(self.0.call(state).await) })
+    }
+}
+
+struct TryOpNode<O>(O);
+
+impl<O, S> Node<S> for TryOpNode<O>
+where
+    O: TryOp<Input = S, Output = S>,
+    O::Error: Into<BoxError>,
+    S: WasmCompatSend + WasmCompatSync + 'static,
+{
+    fn run(&self, state: S) -> WasmBoxedFuture<'_, Result<S, BoxError>> {
+        Box::pin(async move { self.0.try_call(state).await.map_err(Into::into) })
+    }
+}
+
+struct AgentNode<P, F, U> {
+    agent: P,
+    prompt: F,
+    update: U,
+}
+
+impl<P, F, U, S> Node<S> for AgentNode<P, F, U>
+where
+    P: completion::Prompt,
+    F: Fn(&S) -> String + WasmCompatSend + WasmCompatSync,
+    U: Fn(S, String) -> S + WasmCompatSend + WasmCompatSync,
+    S: WasmCompatSend + WasmCompatSync + 'static,
+{
+    fn run(&self, state: S) -> WasmBoxedFuture<'_, Result<S, BoxError>> {
+        Box::pin(async move {
+            let prompt = (self.prompt)(&state);
+            let response = self.agent.prompt(prompt).into_future().await?;
+            Ok((self.update)(state, response))
+        })
+    }
+}
+
+type Router<S> = Box<dyn Fn(&S) -> String + Send + Sync>;
+
+enum Edge<S> {
+    Direct(String),
+    Conditional(Router<S>),
+}
+
+/// A snapshot of a graph run, taken after the execution of a node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint<S> {
+    /// The number of node executions so far
+    pub step: usize,
+    /// The node that was executed
+    pub node: String,
+    /// The node that will be executed next ([END] if the run is over)
+    pub next: String,
+    /// The state of the workflow after the execution of `node`
+    pub state: S,
+}
+
+/// Saves the [Checkpoint]s of a graph run.
+pub trait Checkpointer<S>: WasmCompatSend + WasmCompatSync {
+    fn save(&self, checkpoint: Checkpoint<S>) -> WasmBoxedFuture<'_, Result<(), GraphError>>;
+}
+
+/// A [Checkpointer] keeping the checkpoints in memory.
+/// Clones share the same checkpoints, so a clone can be kept to inspect the run after handing
+/// the checkpointer to a graph.
+#[derive(Debug)]
+pub struct InMemoryCheckpointer<S> {
+    checkpoints: Arc<Mutex<Vec<Checkpoint<S>>>>,
+}
+
+impl<S> Default for InMemoryCheckpointer<S> {
+    fn default() -> Self {
+        Self {
+            checkpoints: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<S> Clone for InMemoryCheckpointer<S> {
+    fn clone(&self) -> Self {
+        Self {
+            checkpoints: self.checkpoints.clone(),
+        }
+    }
+}
+
+impl<S: Clone> InMemoryCheckpointer<S> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All the checkpoints saved so far, in order.
+    pub fn checkpoints(&self) -> Vec<Checkpoint<S>> {
+        self.checkpoints
+            .lock()
+            .expect("checkpoints lock poisoned")
+            .clone()
+    }
+
+    /// The last checkpoint saved, if any.
+    pub fn latest(&self) -> Option<Checkpoint<S>> {
+        self.checkpoints
+            .lock()
+            .expect("checkpoints lock poisoned")
+            .last()
+            .cloned()
+    }
+}
+
+impl<S> Checkpointer<S> for InMemoryCheckpointer<S>
+where
+    S: WasmCompatSend + WasmCompatSync,
+{
+    fn save(&self, checkpoint: Checkpoint<S>) -> WasmBoxedFuture<'_, Result<(), GraphError>> {
+        let result = self
+            .checkpoints
+            .lock()
+            .map(|mut checkpoints| checkpoints.push(checkpoint))
+            .map_err(|err| GraphError::Checkpoint(err.to_string().into()));
+
+        Box::pin(async move { result })
+    }
+}
+
+/// A workflow made of nodes connected by (possibly conditional and cyclic) edges.
+/// See the [module documentation](self) for more details.
+pub struct Graph<S> {
+    nodes: HashMap<String, Box<dyn Node<S>>>,
+    edges: HashMap<String, Edge<S>>,
+    start: String,
+    max_iterations: usize,
+    checkpointer: Option<Box<dyn Checkpointer<S>>>,
+}
+
+impl<S> Graph<S>
+where
+    S: Clone + WasmCompatSend + WasmCompatSync,
+{
+    pub fn builder() -> GraphBuilder<S> {
+        GraphBuilder::default()
+    }
+
+    /// Runs the graph from its start node with the given initial state and returns the final state.
+    pub async fn run(&self, state: S) -> Result<S, GraphError> {
+        self.run_from(self.start.clone(), state, 0).await
+    }
+
+    /// Resumes a run from a [Checkpoint] (e.g.: the last checkpoint saved before a failure).
+    /// The node executions before the checkpoint count towards the maximum number of iterations.
+    pub async fn resume(&self, checkpoint: Checkpoint<S>) -> Result<S, GraphError> {
+        self.run_from(checkpoint.next, checkpoint.state, checkpoint.step)
+            .await
+    }
+
+    async fn run_from(
+        &self,
+        mut next: String,
+        mut state: S,
+        mut step: usize,
+    ) -> Result<S, GraphError> {
+        while next != END {
+            if step >= self.max_iterations {
+                return Err(GraphError::MaxIterationsExceeded(self.max_iterations));
+            }
+
+            let node = self
+                .nodes
+                .get(&next)
+                .ok_or_else(|| GraphError::UnknownNode(next.clone()))?;
+
+            let span = info_span!(
+                target: "rig::pipeline::graph",
+                "graph_node",
+                node = next.as_str(),
+                step,
+            );
+            state = node
+                .run(state)
+                .instrument(span)
+                .await
+                .map_err(|source| GraphError::Node {
+                    node: next.clone(),
+                    source,
+                })?;
+            step += 1;
+
+            let following = match self.edges.get(&next) {
+                Some(Edge::Direct(to)) => to.clone(),
+                Some(Edge::Conditional(router)) => router(&state),
+                None => END.to_string(),
+            };
+
+            tracing::debug!(
+                target: "rig::pipeline::graph",
+                from = next.as_str(),
+                to = following.as_str(),
+                "Graph transition"
+            );
+
+            if let Some(checkpointer) = &self.checkpointer {
+                checkpointer
+                    .save(Checkpoint {
+                        step,
+                        node: next,
+                        next: following.clone(),
+                        state: state.clone(),
+                    })
+                    .await?;
+            }
+
+            next = following;
+        }
+
+        Ok(state)
+    }
+}
+
+impl<S> Op for Graph<S>
+where
+    S: Clone + WasmCompatSend + WasmCompatSync,
+{
+    type Input = S;
+    type Output = Result<S, GraphError>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        self.run(input).await
+    }
+}
+
+/// Builder for [Graph].
+pub struct GraphBuilder<S> {
+    nodes: HashMap<String, Box<dyn Node<S>>>,
+    edges: HashMap<String, Edge<S>>,
+    start: Option<String>,
+    max_iterations: usize,
+    checkpointer: Option<Box<dyn Checkpointer<S>>>,
+    errors: Vec<GraphError>,
+}
+
+impl<S> Default for GraphBuilder<S> {
+    fn default() -> Self {
+        Self {
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+            start: None,
+            max_iterations: DEFAULT_MAX_ITERATIONS,
+            checkpointer: None,
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl<S> GraphBuilder<S>
+where
+    S: Clone + WasmCompatSend + WasmCompatSync + 'static,
+{
+    /// Adds an infallible node running `op` on the state.
+    pub fn node<O>(self, name: impl Into<String>, op: O) -> Self
+    where
+        O: Op<Input = S, Output = S> + 'static,
+    {
+        self.custom_node(name, OpNode(op))
+    }
+
+    /// Adds a fallible node running `op` on the state. An error stops the graph run.
+    pub fn try_node<O>(self, name: impl Into<String>, op: O) -> Self
+    where
+        O: TryOp<Input = S, Output = S> + 'static,
+        O::Error: Into<BoxError>,
+    {
+        self.custom_node(name, TryOpNode(op))
+    }
+
+    /// Adds a node prompting `agent` (or any other [completion::Prompt] implementation).
+    /// The prompt is built from the state by `prompt`, and `update` merges the response into the state.
+    pub fn agent_node<P, F, U>(
+        self,
+        name: impl Into<String>,
+        agent: P,
+        prompt: F,
+        update: U,
+    ) -> Self
+    where
+        P: completion::Prompt + 'static,
+        F: Fn(&S) -> String + WasmCompatSend + WasmCompatSync + 'static,
+        U: Fn(S, String) -> S + WasmCompatSend + WasmCompatSync + 'static,
+    {
+        self.custom_node(
+            name,
+            AgentNode {
+                agent,
+                prompt,
+                update,
+            },
+        )
+    }
+
+    /// Adds a node implementing the [Node] trait directly.
+    pub fn custom_node(mut self, name: impl Into<String>, node: impl Node<S> + 'static) -> Self {
+        let name = name.into();
+        match self.nodes.entry(name) {
+            Entry::Occupied(entry) => self
+                .errors
+                .push(GraphError::DuplicateNode(entry.key().clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(node));
+            }
+        }
+        self
+    }
+
+    /// Runs `to` after `from` (`to` can be [END]).
+    pub fn edge(self, from: impl Into<String>, to: impl Into<String>) -> Self {
+        self.add_edge(from.into(), Edge::Direct(to.into()))
+    }
+
+    /// Runs the node returned by `router` (which can be [END]) after `from`.
+    pub fn conditional_edge<F, R>(self, from: impl Into<String>, router: F) -> Self
+    where
+        F: Fn(&S) -> R + Send + Sync + 'static,
+        R: Into<String>,
+    {
+        self.add_edge(
+            from.into(),
+            Edge::Conditional(Box::new(move |state| router(state).into())),
+        )
+    }
+
+    fn add_edge(mut self, from: String, edge: Edge<S>) -> Self {
+        match self.edges.entry(from) {
+            Entry::Occupied(entry) => self
+                .errors
+                .push(GraphError::DuplicateEdge(entry.key().clone())),
+            Entry::Vacant(entry) => {
+                entry.insert(edge);
+            }
+        }
+        self
+    }
+
+    /// Sets the first node to run.
+    pub fn start(mut self, name: impl Into<String>) -> Self {
+        self.start = Some(name.into());
+        self
+    }
+
+    /// Sets the maximum number of node executions of a run (defaults to [DEFAULT_MAX_ITERATIONS]),
+    /// after which the run fails with [GraphError::MaxIterationsExceeded].
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Saves a [Checkpoint] after each node execution.
+    pub fn checkpointer(mut self, checkpointer: impl Checkpointer<S> + 'static) -> Self {
+        self.checkpointer = Some(Box::new(checkpointer));
+        self
+    }
+
+    /// Builds the graph, checking that the start node and the direct edges refer to existing nodes.
+    /// The nodes returned by conditional edges are checked when the graph runs.
+    pub fn build(mut self) -> Result<Graph<S>, GraphError> {
+        if !self.errors.is_empty() {
+            return Err(self.errors.remove(0));
+        }
+
+        let start = self.start.ok_or(GraphError::MissingStart)?;
+        if !self.nodes.contains_key(&start) {
+            return Err(GraphError::UnknownNode(start));
+        }
+
+        for (from, edge) in &self.edges {
+            if !self.nodes.contains_key(from) {
+                return Err(GraphError::UnknownNode(from.clone()));
+            }
+            if let Edge::Direct(to) = edge
+                && to != END
+                && !self.nodes.contains_key(to)
+            {
+                return Err(GraphError::UnknownNode(to.clone()));
+            }
+        }
+
+        Ok(Graph {
+            nodes: self.nodes,
+            edges: self.edges,
+            start,
+            max_iterations: self.max_iterations,
+            checkpointer: self.checkpointer,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{
+        self,
+        agent_ops::tests::{last_prompt, mock_agent},
+        map, then,
+    };
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct State {
+        value: i32,
+        log: Vec<String>,
+    }
+
+    impl State {
+        fn new(value: i32) -> Self {
+            Self { value, log: vec![] }
+        }
+    }
+
+    fn increment() -> impl Op<Input = State, Output = State> {
+        map(|mut state: State| {
+            state.value += 1;
+            state.log.push("increment".to_string());
+            state
+        })
+    }
+
+    #[tokio::test]
+    async fn test_linear_graph() {
+        let graph = Graph::builder()
+            .node("increment", increment())
+            .node(
+                "double",
+                then(|mut state: State| async move {
+                    state.value *= 2;
+                    state.log.push("double".to_string());
+                    state
+                }),
+            )
+            .edge("increment", "double")
+            .edge("double", END)
+            .start("increment")
+            .build()
+            .unwrap();
+
+        let state = graph.run(State::new(1)).await.unwrap();
+        assert_eq!(state.value, 4);
+        assert_eq!(state.log, vec!["increment", "double"]);
+    }
+
+    #[tokio::test]
+    async fn test_conditional_loop() {
+        let graph = Graph::builder()
+            .node("increment", increment())
+            .node(
+                "done",
+                map(|mut state: State| {
+                    state.log.push("done".to_string());
+                    state
+                }),
+            )
+            .conditional_edge("increment", |state: &State| {
+                if state.value < 3 { "increment" } else { "done" }
+            })
+            .start("increment")
+            .build()
+            .unwrap();
+
+        let state = graph.run(State::new(0)).await.unwrap();
+        assert_eq!(state.value, 3);
+        assert_eq!(
+            state.log,
+            vec!["increment", "increment", "increment", "done"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_iterations() {
+        let graph = Graph::builder()
+            .node("increment", increment())
+            .edge("increment", "increment")
+            .start("increment")
+            .max_iterations(5)
+            .build()
+            .unwrap();
+
+        let err = graph.run(State::new(0)).await.unwrap_err();
+        assert!(matches!(err, GraphError::MaxIterationsExceeded(5)));
+    }
+
+    #[tokio::test]
+    async fn test_node_error() {
+        let graph = Graph::builder()
+            .try_node(
+                "fail",
+                map(|state: State| {
+                    if state.value > 0 {
+                        Ok(state)
+                    } else {
+                        Err(std::io::Error::other("value must be positive"))
+                    }
+                }),
+            )
+            .start("fail")
+            .build()
+            .unwrap();
+
+        assert!(graph.run(State::new(1)).await.is_ok());
+
+        let err = graph.run(State::new(0)).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Node `fail` failed: value must be positive"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_router_target() {
+        let graph = Graph::builder()
+            .node("increment", increment())
+            .conditional_edge("increment", |_: &State| "missing")
+            .start("increment")
+            .build()
+            .unwrap();
+
+        let err = graph.run(State::new(0)).await.unwrap_err();
+        assert!(matches!(err, GraphError::UnknownNode(node) if node == "missing"));
+    }
+
+    #[test]
+    fn test_build_validation() {
+        let err = Graph::<State>::builder()
+            .node("increment", increment())
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, GraphError::MissingStart));
+
+        let err = Graph::builder()
+            .node("increment", increment())
+            .edge("increment", "missing")
+            .start("increment")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, GraphError::UnknownNode(node) if node == "missing"));
+
+        let err = Graph::builder()
+            .node("increment", increment())
+            .node("increment", increment())
+            .start("increment")
+            .build()
+            .err()
+            .unwrap();
+        assert!(matches!(err, GraphError::DuplicateNode(node) if node == "increment"));
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_and_resume() {
+        let checkpointer = InMemoryCheckpointer::new();
+        let graph = Graph::builder()
+            .node("increment", increment())
+            .conditional_edge(
+                "increment",
+                |state: &State| {
+                    if state.value < 3 { "increment" } else { END }
+                },
+            )
+            .start("increment")
+            .checkpointer(checkpointer.clone())
+            .build()
+            .unwrap();
+
+        let state = graph.run(State::new(0)).await.unwrap();
+        assert_eq!(state.value, 3);
+
+        let checkpoints = checkpointer.checkpoints();
+        assert_eq!(checkpoints.len(), 3);
+        assert_eq!(checkpoints[0].step, 1);
+        assert_eq!(checkpoints[0].next, "increment");
+        assert_eq!(checkpoints[2].next, END);
+        assert_eq!(checkpointer.latest().unwrap().state, state);
+
+        // Resuming from the first checkpoint only runs the remaining iterations
+        let resumed = graph.resume(checkpoints[0].clone()).await.unwrap();
+        assert_eq!(resumed.value, 3);
+        assert_eq!(resumed.log.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_agent_node_and_pipeline() {
+        let (agent, model) = mock_agent("value is odd");
+        let graph = Graph::builder()
+            .agent_node(
+                "agent",
+                agent,
+                |state: &State| format!("value is {}", state.value),
+                |mut state: State, response| {
+                    state.log.push(response);
+                    state
+                },
+            )
+            .start("agent")
+            .build()
+            .unwrap();
+
+        // Graphs are ops, so they can be used in pipelines
+        let pipeline = pipeline::new()
+            .map(State::new)
+            .chain(graph)
+            .map(|state| state.map(|state| state.log));
+
+        let log = pipeline.call(7).await.unwrap();
+        assert_eq!(log, vec!["value is odd"]);
+        assert_eq!(last_prompt(&model), "value is 7");
+    }
+}
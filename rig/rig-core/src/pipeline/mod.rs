@@ -86,9 +86,20 @@
 //!             ▼              
 //!          Output           
 //! ```
+//!
+//! ## Graphs
+//! Pipelines built with ops are acyclic. For workflows that loop or branch on a shared state
+//! (e.g.: evaluator-optimizer loops), see the [graph] module.
+//!
+//! ## Streaming
+//! Ops resolve to a single output. To stream intermediate results (e.g.: the tokens of an agent's
+//! response), use the [StreamOp] trait from the [stream_op] module, whose ops return a stream of items.
+//! A regular pipeline can be followed by a stream op using [chain_stream](Op::chain_stream).
 
 pub mod agent_ops;
+pub mod graph;
 pub mod op;
+pub mod stream_op;
 pub mod try_op;
 #[macro_use]
 pub mod parallel;
@@ -98,6 +109,7 @@ pub mod conditional;
 use std::future::Future;
 
 pub use op::{Op, map, passthrough, then};
+pub use stream_op::StreamOp;
 pub use try_op::TryOp;
 
 use crate::{completion, extractor::Extractor, vector_store};
@@ -303,39 +315,41 @@ pub fn with_error<E>() -> PipelineBuilder<E> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use agent_ops::tests::{Foo, MockIndex, MockModel};
+    use agent_ops::tests::{Foo, MockIndex, last_prompt, mock_agent};
     use parallel::parallel;
 
     #[tokio::test]
     async fn test_prompt_pipeline() {
-        let model = MockModel;
+        let (agent, model) = mock_agent("A flurbo is a unit of currency");
 
         let chain = super::new()
             .map(|input| format!("User query: {input}"))
-            .prompt(model);
+            .prompt(agent);
 
         let result = chain
             .call("What is a flurbo?")
             .await
             .expect("Failed to run chain");
 
-        assert_eq!(result, "Mock response: User query: What is a flurbo?");
+        assert_eq!(result, "A flurbo is a unit of currency");
+        assert_eq!(last_prompt(&model), "User query: What is a flurbo?");
     }
 
     #[tokio::test]
     async fn test_prompt_pipeline_error() {
-        let model = MockModel;
+        let (agent, model) = mock_agent("A flurbo is a unit of currency");
 
         let chain = super::with_error::<()>()
             .map(|input| format!("User query: {input}"))
-            .prompt(model);
+            .prompt(agent);
 
         let result = chain
             .try_call("What is a flurbo?")
             .await
             .expect("Failed to run chain");
 
-        assert_eq!(result, "Mock response: User query: What is a flurbo?");
+        assert_eq!(result, "A flurbo is a unit of currency");
+        assert_eq!(last_prompt(&model), "User query: What is a flurbo?");
     }
 
     #[tokio::test]
@@ -357,6 +371,7 @@ mod tests {
     #[tokio::test]
     async fn test_rag_pipeline() {
         let index = MockIndex;
+        let (agent, model) = mock_agent("A flurbo is a unit of currency");
 
         let chain = super::new()
             .chain(parallel!(
@@ -367,16 +382,17 @@ mod tests {
                 Ok(docs) => format!("User query: {}\n\nTop documents:\n{}", query, docs[0].2.foo),
                 Err(err) => format!("Error: {err}"),
             })
-            .prompt(MockModel);
+            .prompt(agent);
 
         let result = chain
             .call("What is a flurbo?")
             .await
             .expect("Failed to run chain");
 
+        assert_eq!(result, "A flurbo is a unit of currency");
         assert_eq!(
-            result,
-            "Mock response: User query: What is a flurbo?\n\nTop documents:\nbar"
+            last_prompt(&model),
+            "User query: What is a flurbo?\n\nTop documents:\nbar"
         );
     }
 }
@@ -171,6 +171,32 @@ pub trait Op: WasmCompatSend + WasmCompatSync {
     {
         Sequential::new(self, Prompt::new(prompt))
     }
+
+    /// Chain a stream op to the current op. The resulting op is a [StreamOp] which runs the
+    /// current op and then streams the items of `stream_op` from its output.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rig::pipeline::{self, Op, StreamOp, stream_op::stream_prompt};
+    ///
+    /// let agent = openai_client.agent("gpt-4").build();
+    ///
+    /// let pipeline = pipeline::new()
+    ///    .map(|name| format!("Find funny nicknames for the following name: {name}!"))
+    ///    .chain_stream(stream_prompt(agent));
+    ///
+    /// let mut stream = pipeline.call_stream("Alice".to_string());
+    /// while let Some(item) = stream.next().await {
+    ///     println!("{item:?}");
+    /// }
+    /// ```
+    fn chain_stream<S>(self, stream_op: S) -> SequentialStream<Self, S>
+    where
+        S: StreamOp<Input = Self::Output>,
+        Self: Sized,
+    {
+        SequentialStream::new(self, stream_op)
+    }
 }
 
 impl<T: Op> Op for &T {
@@ -213,6 +239,7 @@ where
 }
 
 use super::agent_ops::{Lookup, Prompt};
+use super::stream_op::{SequentialStream, StreamOp};
 use crate::{completion, vector_store};
 
 // ================================================================
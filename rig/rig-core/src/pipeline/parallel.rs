@@ -246,7 +246,7 @@ macro_rules! try_parallel_internal {
         munching: []
     ) => ({
         use $crate::pipeline::try_op::TryOp;
-        $crate::parallel_op!($($val),*)
+        $crate::try_parallel_op!($($val),*)
             .map_ok(|output| {
                 ($(
                     {
@@ -258,6 +258,22 @@ macro_rules! try_parallel_internal {
     })
 }
 
+#[macro_export]
+macro_rules! try_parallel_op {
+    ($op1:tt, $op2:tt) => {
+        $crate::pipeline::try_op::TryParallel::new($op1, $op2)
+    };
+    ($op1:tt $(, $ops:tt)*) => {
+        $crate::pipeline::try_op::TryParallel::new(
+            $op1,
+            $crate::try_parallel_op!($($ops),*)
+        )
+    };
+}
+
+/// Run ops returning `Result` concurrently with the same input and collect their success
+/// values in a tuple. As soon as one of the ops returns `Err`, the others are cancelled and
+/// the error is returned.
 #[macro_export]
 macro_rules! try_parallel {
     ($($es:expr),+ $(,)?) => {
@@ -273,9 +289,19 @@ macro_rules! try_parallel {
 
 pub use parallel;
 pub use parallel_internal;
+pub use try_parallel;
+pub use try_parallel_internal;
 
 #[cfg(test)]
 mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+        time::Duration,
+    };
+
     use super::*;
     use crate::pipeline::{
         self,
@@ -428,4 +454,28 @@ mod tests {
         let result = pipeline.try_call(1).await;
         assert_eq!(result, Err("1 is the number!".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_try_parallel_cancels_on_error() {
+        let completed = Arc::new(AtomicBool::new(false));
+        let flag = completed.clone();
+
+        let pipeline = try_parallel!(
+            then(move |x: i32| {
+                let flag = flag.clone();
+                async move {
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                    flag.store(true, Ordering::SeqCst);
+                    Ok::<_, String>(x)
+                }
+            }),
+            map(|x: i32| Err::<i32, _>(format!("{x} is the number!")))
+        );
+
+        let result = pipeline.call(1).await;
+        assert_eq!(result, Err("1 is the number!".to_string()));
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!completed.load(Ordering::SeqCst));
+    }
 }
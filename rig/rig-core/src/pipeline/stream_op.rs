@@ -0,0 +1,487 @@
+//! Streaming pipeline ops.
+//!
+//! An [Op] resolves to a single output, which makes it impossible to show intermediate progress or to
+//! stream the tokens of a prompt to the user. A [StreamOp] instead returns a [Stream] of items.
+//!
+//! Ops and stream ops can be combined:
+//! - [Op::chain_stream] runs an op and then streams the items of a stream op from its output
+//!   (e.g.: RAG preprocessing followed by the streamed answer of an agent),
+//! - [from_op] turns an op into a stream op yielding its single output,
+//! - [StreamOp::map], [StreamOp::then], [StreamOp::filter_map] and [StreamOp::chain] run per item,
+//! - [StreamOp::collect] turns a stream op back into an op returning all the items.
+//!
+//! # Example
+//! ```rust
+//! use rig::{
+//!     agent::MultiTurnStreamItem,
+//!     pipeline::{self, Op, StreamOp, stream_op::stream_prompt},
+//!     streaming::StreamedAssistantContent,
+//! };
+//!
+//! let agent = openai_client.agent("gpt-4o").build();
+//!
+//! let pipeline = pipeline::new()
+//!     .lookup::<_, _, Document>(index, 3)
+//!     .map(|docs| format!("Answer using the following documents:\n{docs:?}"))
+//!     .chain_stream(stream_prompt(agent))
+//!     // Only keep the text deltas
+//!     .filter_map(|item| match item {
+//!         Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(text))) => {
+//!             Some(text.text)
+//!         }
+//!         _ => None,
+//!     });
+//!
+//! let mut stream = pipeline.call_stream("What is a flurbo?".to_string());
+//! while let Some(text) = stream.next().await {
+//!     print!("{text}");
+//! }
+//! ```
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+
+use futures::{Stream, StreamExt, stream};
+
+use crate::{
+    agent::{MultiTurnStreamItem, StreamingError},
+    completion::{CompletionModel, GetTokenUsage},
+    streaming::StreamingPrompt,
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+use super::Op;
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub type ItemStream<'a, T> = Pin<Box<dyn Stream<Item = T> + Send + 'a>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub type ItemStream<'a, T> = Pin<Box<dyn Stream<Item = T> + 'a>>;
+
+// ================================================================
+// Core StreamOp trait
+// ================================================================
+pub trait StreamOp: WasmCompatSend + WasmCompatSync {
+    type Input: WasmCompatSend + WasmCompatSync;
+    type Item: WasmCompatSend + WasmCompatSync;
+
+    /// Execute the current op with the given input and return the stream of its items.
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item>;
+
+    /// Apply the function `f` to each item of the stream.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rig::pipeline::{StreamOp, stream_op::from_fn};
+    ///
+    /// let op = from_fn(|n: usize| futures::stream::iter(0..n)).map(|x| x * 2);
+    ///
+    /// let items = op.collect().call(3).await;
+    /// assert_eq!(items, vec![0, 2, 4]);
+    /// ```
+    fn map<F, Output>(self, f: F) -> Map<Self, F>
+    where
+        F: Fn(Self::Item) -> Output + WasmCompatSend + WasmCompatSync,
+        Output: WasmCompatSend + WasmCompatSync,
+        Self: Sized,
+    {
+        Map::new(self, f)
+    }
+
+    /// Same as `map` but for asynchronous functions. Items are processed one at a time, in order.
+    fn then<F, Fut>(self, f: F) -> Then<Self, F>
+    where
+        F: Fn(Self::Item) -> Fut + WasmCompatSend + WasmCompatSync,
+        Fut: Future + WasmCompatSend + 'static,
+        Fut::Output: WasmCompatSend + WasmCompatSync,
+        Self: Sized,
+    {
+        Then::new(self, f)
+    }
+
+    /// Apply the function `f` to each item of the stream, dropping the items for which it returns `None`.
+    fn filter_map<F, Output>(self, f: F) -> FilterMap<Self, F>
+    where
+        F: Fn(Self::Item) -> Option<Output> + WasmCompatSend + WasmCompatSync,
+        Output: WasmCompatSend + WasmCompatSync + 'static,
+        Self: Sized,
+    {
+        FilterMap::new(self, f)
+    }
+
+    /// Run the op `op` on each item of the stream. Items are processed one at a time, in order.
+    fn chain<T>(self, op: T) -> Chain<Self, T>
+    where
+        T: Op<Input = Self::Item>,
+        Self: Sized,
+    {
+        Chain::new(self, op)
+    }
+
+    /// Turn the current stream op into an [Op] returning all the items of the stream.
+    fn collect(self) -> Collect<Self>
+    where
+        Self: Sized,
+    {
+        Collect::new(self)
+    }
+}
+
+impl<T: StreamOp> StreamOp for &T {
+    type Input = T::Input;
+    type Item = T::Item;
+
+    #[inline]
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        (*self).call_stream(input)
+    }
+}
+
+// ================================================================
+// StreamOp combinators
+// ================================================================
+pub struct Map<S, F> {
+    prev: S,
+    f: F,
+}
+
+impl<S, F> Map<S, F> {
+    pub(crate) fn new(prev: S, f: F) -> Self {
+        Self { prev, f }
+    }
+}
+
+impl<S, F, Output> StreamOp for Map<S, F>
+where
+    S: StreamOp,
+    F: Fn(S::Item) -> Output + WasmCompatSend + WasmCompatSync,
+    Output: WasmCompatSend + WasmCompatSync,
+{
+    type Input = S::Input;
+    type Item = Output;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(self.prev.call_stream(input).map(&self.f))
+    }
+}
+
+pub struct Then<S, F> {
+    prev: S,
+    f: F,
+}
+
+impl<S, F> Then<S, F> {
+    pub(crate) fn new(prev: S, f: F) -> Self {
+        Self { prev, f }
+    }
+}
+
+impl<S, F, Fut> StreamOp for Then<S, F>
+where
+    S: StreamOp,
+    F: Fn(S::Item) -> Fut + WasmCompatSend + WasmCompatSync,
+    Fut: Future + WasmCompatSend + 'static,
+    Fut::Output: WasmCompatSend + WasmCompatSync,
+{
+    type Input = S::Input;
+    type Item = Fut::Output;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(self.prev.call_stream(input).then(&self.f))
+    }
+}
+
+pub struct FilterMap<S, F> {
+    prev: S,
+    f: F,
+}
+
+impl<S, F> FilterMap<S, F> {
+    pub(crate) fn new(prev: S, f: F) -> Self {
+        Self { prev, f }
+    }
+}
+
+impl<S, F, Output> StreamOp for FilterMap<S, F>
+where
+    S: StreamOp,
+    F: Fn(S::Item) -> Option<Output> + WasmCompatSend + WasmCompatSync,
+    Output: WasmCompatSend + WasmCompatSync + 'static,
+{
+    type Input = S::Input;
+    type Item = Output;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(
+            self.prev
+                .call_stream(input)
+                .filter_map(move |item| std::future::ready((self.f)(item))),
+        )
+    }
+}
+
+pub struct Chain<S, O> {
+    prev: S,
+    op: O,
+}
+
+impl<S, O> Chain<S, O> {
+    pub(crate) fn new(prev: S, op: O) -> Self {
+        Self { prev, op }
+    }
+}
+
+impl<S, O> StreamOp for Chain<S, O>
+where
+    S: StreamOp,
+    O: Op<Input = S::Item>,
+{
+    type Input = S::Input;
+    type Item = O::Output;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(
+            self.prev
+                .call_stream(input)
+                .then(move |item| self.op.call(item)),
+        )
+    }
+}
+
+/// An [Op] followed by a [StreamOp] streaming from the output of the op.
+/// See [Op::chain_stream].
+pub struct SequentialStream<O, S> {
+    op: O,
+    stream_op: S,
+}
+
+impl<O, S> SequentialStream<O, S> {
+    pub(crate) fn new(op: O, stream_op: S) -> Self {
+        Self { op, stream_op }
+    }
+}
+
+impl<O, S> StreamOp for SequentialStream<O, S>
+where
+    O: Op,
+    S: StreamOp<Input = O::Output>,
+{
+    type Input = O::Input;
+    type Item = S::Item;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(
+            stream::once(self.op.call(input))
+                .flat_map(move |output| self.stream_op.call_stream(output)),
+        )
+    }
+}
+
+/// A stream op turned into an [Op] returning all its items. See [StreamOp::collect].
+pub struct Collect<S> {
+    inner: S,
+}
+
+impl<S> Collect<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<S> Op for Collect<S>
+where
+    S: StreamOp,
+{
+    type Input = S::Input;
+    type Output = Vec<S::Item>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        self.inner.call_stream(input).collect().await
+    }
+}
+
+// ================================================================
+// Core StreamOp implementations
+// ================================================================
+/// An [Op] turned into a stream op yielding its single output. See [from_op].
+pub struct FromOp<O> {
+    op: O,
+}
+
+impl<O> StreamOp for FromOp<O>
+where
+    O: Op,
+{
+    type Input = O::Input;
+    type Item = O::Output;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin(stream::once(self.op.call(input)))
+    }
+}
+
+/// Create a stream op yielding the single output of `op`.
+pub fn from_op<O: Op>(op: O) -> FromOp<O> {
+    FromOp { op }
+}
+
+pub struct FromFn<F, Input> {
+    f: F,
+    _t: std::marker::PhantomData<Input>,
+}
+
+impl<F, Input, St> StreamOp for FromFn<F, Input>
+where
+    F: Fn(Input) -> St + WasmCompatSend + WasmCompatSync,
+    Input: WasmCompatSend + WasmCompatSync,
+    St: Stream + WasmCompatSend + 'static,
+    St::Item: WasmCompatSend + WasmCompatSync,
+{
+    type Input = Input;
+    type Item = St::Item;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        Box::pin((self.f)(input))
+    }
+}
+
+/// Create a stream op from a function returning a stream.
+pub fn from_fn<F, Input, St>(f: F) -> FromFn<F, Input>
+where
+    F: Fn(Input) -> St + WasmCompatSend + WasmCompatSync,
+    Input: WasmCompatSend + WasmCompatSync,
+    St: Stream + WasmCompatSend + 'static,
+    St::Item: WasmCompatSend + WasmCompatSync,
+{
+    FromFn {
+        f,
+        _t: std::marker::PhantomData,
+    }
+}
+
+/// The streaming counterpart of [prompt](super::agent_ops::prompt). See [stream_prompt].
+pub struct StreamPrompt<P, M, In> {
+    prompt: P,
+    _t: std::marker::PhantomData<(M, In)>,
+}
+
+impl<P, M, In> StreamOp for StreamPrompt<P, M, In>
+where
+    P: StreamingPrompt<M, M::StreamingResponse> + WasmCompatSend + WasmCompatSync,
+    M: CompletionModel + 'static,
+    M::StreamingResponse: WasmCompatSend + WasmCompatSync + GetTokenUsage,
+    In: Into<String> + WasmCompatSend + WasmCompatSync,
+{
+    type Input = In;
+    type Item = Result<MultiTurnStreamItem<M::StreamingResponse>, StreamingError>;
+
+    fn call_stream(&self, input: Self::Input) -> ItemStream<'_, Self::Item> {
+        let request = self.prompt.stream_prompt(input.into());
+        Box::pin(stream::once(request.into_future()).flatten())
+    }
+}
+
+/// Create a new streaming prompt operation.
+///
+/// The op will prompt the `agent` with the input and stream the items of the response
+/// (text deltas, tool calls, tool results and final response).
+pub fn stream_prompt<P, M, In>(agent: P) -> StreamPrompt<P, M, In>
+where
+    P: StreamingPrompt<M, M::StreamingResponse> + WasmCompatSend + WasmCompatSync,
+    M: CompletionModel + 'static,
+    M::StreamingResponse: WasmCompatSend + WasmCompatSync + GetTokenUsage,
+    In: Into<String> + WasmCompatSend + WasmCompatSync,
+{
+    StreamPrompt {
+        prompt: agent,
+        _t: std::marker::PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{self, map};
+
+    fn count_to() -> impl StreamOp<Input = usize, Item = usize> {
+        from_fn(|n: usize| stream::iter(1..=n))
+    }
+
+    #[tokio::test]
+    async fn test_stream_op_per_item_combinators() {
+        let op = count_to()
+            .map(|x| x * 10)
+            .then(|x| async move { x + 1 })
+            .filter_map(|x| (x != 21).then_some(x))
+            .chain(map(|x: usize| format!("#{x}")));
+
+        let items = op.call_stream(3).collect::<Vec<_>>().await;
+        assert_eq!(items, vec!["#11", "#31"]);
+    }
+
+    #[tokio::test]
+    async fn test_op_stream_adapters() {
+        let pipeline = pipeline::new()
+            .map(|(x, y): (usize, usize)| x + y)
+            .chain_stream(count_to())
+            .map(|x| x * 2);
+
+        let items = pipeline.call_stream((1, 2)).collect::<Vec<_>>().await;
+        assert_eq!(items, vec![2, 4, 6]);
+
+        let op = from_op(map(|x: usize| x + 1)).collect();
+        assert_eq!(op.call(1).await, vec![2]);
+
+        // Stream ops collected into ops can be chained with regular ops
+        let op = count_to()
+            .collect()
+            .map(|items| items.iter().sum::<usize>());
+        assert_eq!(op.call(4).await, 10);
+    }
+
+    #[tokio::test]
+    async fn test_stream_prompt() {
+        use crate::{
+            agent::AgentBuilder,
+            streaming::StreamedAssistantContent,
+            testing::{MockCompletionModel, MockResponse},
+        };
+
+        let model = MockCompletionModel::new().response(
+            MockResponse::text("Hello")
+                .with_content(crate::message::AssistantContent::text(" world")),
+        );
+        let agent = AgentBuilder::new(model.clone()).build();
+
+        let pipeline = pipeline::new()
+            .map(|name: String| format!("Greet {name}"))
+            .chain_stream(stream_prompt(agent));
+
+        let items = pipeline
+            .call_stream("Alice".to_string())
+            .collect::<Vec<_>>()
+            .await;
+
+        let texts = items
+            .iter()
+            .filter_map(|item| match item {
+                Ok(MultiTurnStreamItem::StreamAssistantItem(StreamedAssistantContent::Text(
+                    text,
+                ))) => Some(text.text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec!["Hello", " world"]);
+
+        let Some(Ok(MultiTurnStreamItem::FinalResponse(response))) = items.last() else {
+            panic!("The stream should end with the final response");
+        };
+        assert_eq!(response.response(), "Hello world");
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(
+            requests[0].chat_history.last().rag_text().as_deref(),
+            Some("Greet Alice")
+        );
+    }
+}
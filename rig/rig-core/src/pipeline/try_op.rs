@@ -1,10 +1,24 @@
-use std::future::Future;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    future::Future,
+    hash::Hash,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use futures::stream;
 #[allow(unused_imports)] // Needed since this is used in a macro rule
 use futures::try_join;
+use futures::{
+    future::{self, Either},
+    stream,
+};
+use futures_timer::Delay;
 
-use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
+use crate::{
+    http_client::retry::{RetryPolicy, retry_with},
+    wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync},
+};
 
 use super::op::{self};
 
@@ -188,6 +202,101 @@ pub trait TryOp: WasmCompatSend + WasmCompatSync {
     {
         TrySequential::new(self, op)
     }
+
+    /// Retry the current op according to the retry `policy` when it returns `Err`.
+    /// The input is cloned for each attempt. Once the policy gives up, the last error is returned.
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rig::{http_client::retry::ExponentialBackoff, pipeline::{self, TryOp}};
+    ///
+    /// let op = pipeline::new()
+    ///     .prompt(agent)
+    ///     .retry(ExponentialBackoff::new(Duration::from_millis(500), 2., None, Some(3)));
+    ///
+    /// let result = op.try_call("What is a flurbo?").await;
+    /// ```
+    fn retry<R>(self, policy: R) -> Retry<Self, R>
+    where
+        R: RetryPolicy + WasmCompatSend + WasmCompatSync,
+        Self::Input: Clone,
+        Self::Error: Display,
+        Self: Sized,
+    {
+        Retry::new(self, policy)
+    }
+
+    /// Fail the current op with [TimeoutError::Elapsed] if it does not complete within `duration`.
+    /// Errors of the current op are wrapped in [TimeoutError::Op].
+    ///
+    /// # Example
+    /// ```rust
+    /// use std::time::Duration;
+    /// use rig::pipeline::{self, TryOp};
+    ///
+    /// let op = pipeline::new()
+    ///     .prompt(agent)
+    ///     .timeout(Duration::from_secs(30));
+    ///
+    /// let result = op.try_call("What is a flurbo?").await;
+    /// ```
+    fn timeout(self, duration: Duration) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        Timeout::new(self, duration)
+    }
+
+    /// Call the op `op` with the same input if the current op returns `Err`
+    /// (e.g.: prompting a cheaper model or another provider).
+    ///
+    /// # Example
+    /// ```rust
+    /// use rig::pipeline::{self, TryOp};
+    ///
+    /// let op = pipeline::new()
+    ///     .map(|x: i32| if x % 2 == 0 { Ok(x) } else { Err("x is odd") })
+    ///     .fallback(pipeline::new().map(|x: i32| Ok::<_, String>(x + 1)));
+    ///
+    /// let result = op.try_call(1).await;
+    /// assert_eq!(result, Ok(2));
+    /// ```
+    fn fallback<T>(self, op: T) -> Fallback<Self, T>
+    where
+        T: TryOp<Input = Self::Input, Output = Self::Output>,
+        Self::Input: Clone,
+        Self: Sized,
+    {
+        Fallback::new(self, op)
+    }
+
+    /// Cache the success return values of the current op in `cache`, keyed on the input.
+    /// When the cache already contains a value for an input, the current op is not called.
+    ///
+    /// # Example
+    /// ```rust
+    /// use rig::pipeline::{self, TryOp, try_op::InMemoryCache};
+    ///
+    /// let cache = InMemoryCache::new();
+    /// let op = pipeline::new()
+    ///     .prompt(agent)
+    ///     .memoize(cache.clone());
+    ///
+    /// let first = op.try_call("What is a flurbo?".to_string()).await?;
+    /// // Served from the cache
+    /// let second = op.try_call("What is a flurbo?".to_string()).await?;
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    fn memoize<C>(self, cache: C) -> Memoize<Self, C>
+    where
+        C: Cache<Self::Input, Self::Output>,
+        Self::Input: Clone,
+        Self::Output: Clone,
+        Self: Sized,
+    {
+        Memoize::new(self, cache)
+    }
 }
 
 impl<Op, T, E> TryOp for Op
@@ -347,37 +456,244 @@ where
     }
 }
 
-// TODO: Implement TryParallel
-// pub struct TryParallel<Op1, Op2> {
-//     op1: Op1,
-//     op2: Op2,
-// }
+/// Run two ops concurrently with the same input. As soon as one of them returns `Err`,
+/// the other one is cancelled (i.e.: dropped) and the error is returned.
+/// See the [try_parallel!](crate::try_parallel!) macro to run more than two ops.
+pub struct TryParallel<Op1, Op2> {
+    op1: Op1,
+    op2: Op2,
+}
+
+impl<Op1, Op2> TryParallel<Op1, Op2> {
+    pub fn new(op1: Op1, op2: Op2) -> Self {
+        Self { op1, op2 }
+    }
+}
+
+impl<Op1, Op2> op::Op for TryParallel<Op1, Op2>
+where
+    Op1: TryOp,
+    Op1::Input: Clone,
+    Op2: TryOp<Input = Op1::Input, Error = Op1::Error>,
+{
+    type Input = Op1::Input;
+    type Output = Result<(Op1::Output, Op2::Output), Op1::Error>;
+
+    #[inline]
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        try_join!(self.op1.try_call(input.clone()), self.op2.try_call(input))
+    }
+}
+
+pub struct Retry<Op, R> {
+    op: Op,
+    policy: R,
+}
+
+impl<Op, R> Retry<Op, R> {
+    pub(crate) fn new(op: Op, policy: R) -> Self {
+        Self { op, policy }
+    }
+}
+
+impl<Op, R> op::Op for Retry<Op, R>
+where
+    Op: TryOp,
+    Op::Input: Clone,
+    Op::Error: Display,
+    R: RetryPolicy + WasmCompatSend + WasmCompatSync,
+{
+    type Input = Op::Input;
+    type Output = Result<Op::Output, Op::Error>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        retry_with(&self.policy, || self.op.try_call(input.clone()))
+            .await
+            .map(|(output, _)| output)
+    }
+}
+
+/// Error returned by ops wrapped with [TryOp::timeout].
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TimeoutError<E> {
+    /// The op did not complete in time
+    #[error("Op timed out after {0:?}")]
+    Elapsed(Duration),
+    /// The op completed in time but returned an error
+    #[error("{0}")]
+    Op(E),
+}
 
-// impl<Op1, Op2> TryParallel<Op1, Op2> {
-//     pub fn new(op1: Op1, op2: Op2) -> Self {
-//         Self { op1, op2 }
-//     }
-// }
+pub struct Timeout<Op> {
+    op: Op,
+    duration: Duration,
+}
 
-// impl<Op1, Op2> TryOp for TryParallel<Op1, Op2>
-// where
-//     Op1: TryOp,
-//     Op2: TryOp<Input = Op1::Input, Output = Op1::Output, Error = Op1::Error>,
-// {
-//     type Input = Op1::Input;
-//     type Output = (Op1::Output, Op2::Output);
-//     type Error = Op1::Error;
+impl<Op> Timeout<Op> {
+    pub(crate) fn new(op: Op, duration: Duration) -> Self {
+        Self { op, duration }
+    }
+}
 
-//     #[inline]
-//     async fn try_call(&self, input: Self::Input) -> Result<Self::Output, Self::Error> {
-//         let (output1, output2) = tokio::join!(self.op1.try_call(input.clone()), self.op2.try_call(input));
-//         Ok((output1?, output2?))
-//     }
-// }
+impl<Op> op::Op for Timeout<Op>
+where
+    Op: TryOp,
+{
+    type Input = Op::Input;
+    type Output = Result<Op::Output, TimeoutError<Op::Error>>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        let call = std::pin::pin!(self.op.try_call(input));
+
+        match future::select(call, Delay::new(self.duration)).await {
+            Either::Left((result, _)) => result.map_err(TimeoutError::Op),
+            Either::Right(_) => Err(TimeoutError::Elapsed(self.duration)),
+        }
+    }
+}
+
+pub struct Fallback<Op1, Op2> {
+    op: Op1,
+    fallback: Op2,
+}
+
+impl<Op1, Op2> Fallback<Op1, Op2> {
+    pub(crate) fn new(op: Op1, fallback: Op2) -> Self {
+        Self { op, fallback }
+    }
+}
+
+impl<Op1, Op2> op::Op for Fallback<Op1, Op2>
+where
+    Op1: TryOp,
+    Op1::Input: Clone,
+    Op2: TryOp<Input = Op1::Input, Output = Op1::Output>,
+{
+    type Input = Op1::Input;
+    type Output = Result<Op1::Output, Op2::Error>;
+
+    #[inline]
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        match self.op.try_call(input.clone()).await {
+            Ok(output) => Ok(output),
+            Err(_) => self.fallback.try_call(input).await,
+        }
+    }
+}
+
+/// A cache of op outputs keyed on the op inputs. See [TryOp::memoize].
+pub trait Cache<K, V>: WasmCompatSend + WasmCompatSync {
+    fn get<'a>(&'a self, key: &'a K) -> WasmBoxedFuture<'a, Option<V>>;
+
+    fn insert(&self, key: K, value: V) -> WasmBoxedFuture<'_, ()>;
+}
+
+/// A [Cache] keeping the values in memory.
+/// Clones share the same values, so a clone can be kept to inspect or clear the cache after
+/// handing it to an op.
+#[derive(Debug)]
+pub struct InMemoryCache<K, V> {
+    values: Arc<Mutex<HashMap<K, V>>>,
+}
+
+impl<K, V> Default for InMemoryCache<K, V> {
+    fn default() -> Self {
+        Self {
+            values: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<K, V> Clone for InMemoryCache<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+        }
+    }
+}
+
+impl<K, V> InMemoryCache<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of values in the cache.
+    pub fn len(&self) -> usize {
+        self.values.lock().expect("cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Remove all the values from the cache.
+    pub fn clear(&self) {
+        self.values.lock().expect("cache lock poisoned").clear()
+    }
+}
+
+impl<K, V> Cache<K, V> for InMemoryCache<K, V>
+where
+    K: Eq + Hash + WasmCompatSend + WasmCompatSync,
+    V: Clone + WasmCompatSend + WasmCompatSync,
+{
+    fn get<'a>(&'a self, key: &'a K) -> WasmBoxedFuture<'a, Option<V>> {
+        let value = self
+            .values
+            .lock()
+            .expect("cache lock poisoned")
+            .get(key)
+            .cloned();
+        Box::pin(future::ready(value))
+    }
+
+    fn insert(&self, key: K, value: V) -> WasmBoxedFuture<'_, ()> {
+        self.values
+            .lock()
+            .expect("cache lock poisoned")
+            .insert(key, value);
+        Box::pin(future::ready(()))
+    }
+}
+
+pub struct Memoize<Op, C> {
+    op: Op,
+    cache: C,
+}
+
+impl<Op, C> Memoize<Op, C> {
+    pub(crate) fn new(op: Op, cache: C) -> Self {
+        Self { op, cache }
+    }
+}
+
+impl<Op, C> op::Op for Memoize<Op, C>
+where
+    Op: TryOp,
+    Op::Input: Clone,
+    Op::Output: Clone,
+    C: Cache<Op::Input, Op::Output>,
+{
+    type Input = Op::Input;
+    type Output = Result<Op::Output, Op::Error>;
+
+    async fn call(&self, input: Self::Input) -> Self::Output {
+        if let Some(output) = self.cache.get(&input).await {
+            return Ok(output);
+        }
+
+        let output = self.op.try_call(input.clone()).await?;
+        self.cache.insert(input, output.clone()).await;
+        Ok(output)
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
     use super::*;
+    use crate::http_client::retry::Constant;
     use crate::pipeline::op::{map, then};
 
     #[tokio::test]
@@ -474,4 +790,98 @@ mod tests {
         let result = pipeline.try_call(1).await.unwrap();
         assert_eq!(result, 15);
     }
+
+    fn flaky(
+        failures: usize,
+    ) -> (
+        impl TryOp<Input = i32, Output = i32, Error = String>,
+        Arc<AtomicUsize>,
+    ) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let op = map(move |x: i32| {
+            if counter.fetch_add(1, Ordering::SeqCst) < failures {
+                Err(format!("Failed on {x}"))
+            } else {
+                Ok(x * 2)
+            }
+        });
+        (op, calls)
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let (op, calls) = flaky(2);
+        let pipeline = op.retry(Constant::new(Duration::from_millis(1), Some(3)));
+
+        assert_eq!(pipeline.try_call(2).await, Ok(4));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_gives_up() {
+        let (op, calls) = flaky(usize::MAX);
+        let pipeline = op.retry(Constant::new(Duration::from_millis(1), Some(2)));
+
+        assert_eq!(pipeline.try_call(2).await, Err("Failed on 2".to_string()));
+        // First attempt + 2 retries
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let pipeline = then(|x: u64| async move {
+            tokio::time::sleep(Duration::from_millis(x)).await;
+            if x == 0 { Err("x is zero") } else { Ok(x) }
+        })
+        .timeout(Duration::from_millis(50));
+
+        assert_eq!(pipeline.try_call(1).await, Ok(1));
+        assert_eq!(
+            pipeline.try_call(0).await,
+            Err(TimeoutError::Op("x is zero"))
+        );
+        assert_eq!(
+            pipeline.try_call(1_000).await,
+            Err(TimeoutError::Elapsed(Duration::from_millis(50)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback() {
+        let pipeline = map(|x: i32| if x % 2 == 0 { Ok(x) } else { Err("x is odd") }).fallback(
+            map(|x: i32| {
+                if x > 0 {
+                    Ok(x + 1)
+                } else {
+                    Err("x is negative")
+                }
+            }),
+        );
+
+        assert_eq!(pipeline.try_call(2).await, Ok(2));
+        assert_eq!(pipeline.try_call(1).await, Ok(2));
+        assert_eq!(pipeline.try_call(-1).await, Err("x is negative"));
+    }
+
+    #[tokio::test]
+    async fn test_memoize() {
+        let (op, calls) = flaky(1);
+        let cache = InMemoryCache::new();
+        let pipeline = op.memoize(cache.clone());
+
+        // Errors are not cached
+        assert_eq!(pipeline.try_call(1).await, Err("Failed on 1".to_string()));
+        assert!(cache.is_empty());
+
+        assert_eq!(pipeline.try_call(1).await, Ok(2));
+        assert_eq!(pipeline.try_call(1).await, Ok(2));
+        assert_eq!(pipeline.try_call(2).await, Ok(4));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(cache.len(), 2);
+
+        cache.clear();
+        assert_eq!(pipeline.try_call(1).await, Ok(2));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,299 @@
+//! Anthropic Message Batches API implementation
+//!
+//! See <https://docs.anthropic.com/en/docs/build-with-claude/batch-processing>.
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Client,
+    client::with_betas,
+    completion::{AnthropicCompletionRequest, CompletionModel, CompletionResponse},
+};
+use crate::{
+    batch::{
+        Batch, BatchClient, BatchError, BatchItem, BatchItemError, BatchItemResult,
+        BatchRequestCounts, BatchResults, BatchStatus, CompletionBatchModel,
+    },
+    completion::{self, CompletionError},
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+#[derive(Debug, Serialize)]
+struct CreateMessageBatchRequest {
+    requests: Vec<MessageBatchRequest>,
+}
+
+#[derive(Debug, Serialize)]
+struct MessageBatchRequest {
+    custom_id: String,
+    params: AnthropicCompletionRequest,
+}
+
+/// The message batch object of the Anthropic API, only the fields used to build a [Batch].
+#[derive(Debug, Deserialize)]
+struct MessageBatch {
+    id: String,
+    processing_status: ProcessingStatus,
+    request_counts: MessageBatchRequestCounts,
+    #[serde(default)]
+    cancel_initiated_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ProcessingStatus {
+    InProgress,
+    Canceling,
+    Ended,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageBatchRequestCounts {
+    processing: u64,
+    succeeded: u64,
+    errored: u64,
+    canceled: u64,
+    expired: u64,
+}
+
+impl MessageBatch {
+    fn from_raw(raw: serde_json::Value) -> Result<Batch, BatchError> {
+        let batch: Self = serde_json::from_value(raw.clone())?;
+        let counts = batch.request_counts;
+
+        let status = match batch.processing_status {
+            ProcessingStatus::InProgress => BatchStatus::InProgress,
+            ProcessingStatus::Canceling => BatchStatus::Cancelling,
+            ProcessingStatus::Ended if batch.cancel_initiated_at.is_some() => {
+                BatchStatus::Cancelled
+            }
+            ProcessingStatus::Ended if counts.expired > 0 => BatchStatus::Expired,
+            ProcessingStatus::Ended => BatchStatus::Completed,
+        };
+
+        Ok(Batch {
+            id: batch.id,
+            status,
+            request_counts: BatchRequestCounts {
+                total: counts.processing
+                    + counts.succeeded
+                    + counts.errored
+                    + counts.canceled
+                    + counts.expired,
+                succeeded: counts.succeeded,
+                failed: counts.errored + counts.canceled + counts.expired,
+            },
+            raw,
+        })
+    }
+}
+
+/// A line of the results of a message batch.
+#[derive(Debug, Deserialize)]
+struct MessageBatchResultLine {
+    custom_id: String,
+    result: MessageBatchResult,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum MessageBatchResult {
+    Succeeded { message: CompletionResponse },
+    Errored { error: serde_json::Value },
+    Canceled,
+    Expired,
+}
+
+impl MessageBatchResultLine {
+    fn into_result(self) -> BatchItemResult<completion::CompletionResponse<CompletionResponse>> {
+        let result = match self.result {
+            MessageBatchResult::Succeeded { message } => message
+                .try_into()
+                .map_err(|err: CompletionError| BatchItemError::Failed(err.to_string())),
+            MessageBatchResult::Errored { error } => Err(BatchItemError::Failed(
+                error
+                    .pointer("/error/message")
+                    .and_then(|message| message.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| error.to_string()),
+            )),
+            MessageBatchResult::Canceled => Err(BatchItemError::Cancelled),
+            MessageBatchResult::Expired => Err(BatchItemError::Expired),
+        };
+
+        BatchItemResult {
+            custom_id: self.custom_id,
+            result,
+        }
+    }
+}
+
+impl<H> BatchClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn retrieve_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        let req = self
+            .get(format!("/v1/messages/batches/{id}"))?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        MessageBatch::from_raw(self.batch_response(req).await?)
+    }
+
+    async fn cancel_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        let req = self
+            .post(format!("/v1/messages/batches/{id}/cancel"))?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        MessageBatch::from_raw(self.batch_response(req).await?)
+    }
+}
+
+impl<T> CompletionBatchModel for CompletionModel<T>
+where
+    T: HttpClientExt + Clone + Default + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn submit_batch(
+        &self,
+        requests: Vec<BatchItem<completion::CompletionRequest>>,
+    ) -> Result<Batch, BatchError> {
+        let requests = requests
+            .into_iter()
+            .map(|item| {
+                Ok(MessageBatchRequest {
+                    custom_id: item.custom_id,
+                    params: self.create_completion_request(item.request)?,
+                })
+            })
+            .collect::<Result<Vec<_>, CompletionError>>()?;
+
+        let betas: Vec<&str> = requests
+            .iter()
+            .flat_map(|request| request.params.betas())
+            .collect();
+        let body = serde_json::to_vec(&CreateMessageBatchRequest { requests })?;
+
+        let req = with_betas(self.client.post("/v1/messages/batches")?, &betas)
+            .body(body)
+            .map_err(http_client::Error::from)?;
+
+        MessageBatch::from_raw(self.client.batch_response(req).await?)
+    }
+
+    async fn batch_results(
+        &self,
+        batch: &Batch,
+    ) -> Result<BatchResults<completion::CompletionResponse<Self::Response>>, BatchError> {
+        if !batch.status.is_terminal() {
+            return Err(BatchError::ResultsUnavailable(format!(
+                "Batch {} is {:?}, its results are available once it has ended",
+                batch.id, batch.status
+            )));
+        }
+
+        let lines = self
+            .client
+            .batch_results_jsonl::<MessageBatchResultLine>(&format!(
+                "/v1/messages/batches/{}/results",
+                batch.id
+            ))
+            .await?;
+
+        Ok(Box::pin(lines.map(|line| Ok(line?.into_result()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_batch_status() {
+        let raw = json!({
+            "id": "msgbatch_013Zva2CMHLNnXjNJJKqJ2EF",
+            "type": "message_batch",
+            "processing_status": "ended",
+            "request_counts": {
+                "processing": 0,
+                "succeeded": 98,
+                "errored": 1,
+                "canceled": 0,
+                "expired": 1
+            },
+            "ended_at": "2024-08-20T18:37:24.100435Z",
+            "created_at": "2024-08-20T18:37:24.100435Z",
+            "expires_at": "2024-08-21T18:37:24.100435Z",
+            "cancel_initiated_at": null,
+            "results_url": "https://api.anthropic.com/v1/messages/batches/msgbatch_013Zva2CMHLNnXjNJJKqJ2EF/results"
+        });
+
+        let batch = MessageBatch::from_raw(raw).unwrap();
+
+        assert_eq!(batch.id, "msgbatch_013Zva2CMHLNnXjNJJKqJ2EF");
+        assert_eq!(batch.status, BatchStatus::Expired);
+        assert_eq!(
+            batch.request_counts,
+            BatchRequestCounts {
+                total: 100,
+                succeeded: 98,
+                failed: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_message_batch_results() {
+        let line: MessageBatchResultLine = serde_json::from_value(json!({
+            "custom_id": "my-second-request",
+            "result": {
+                "type": "succeeded",
+                "message": {
+                    "id": "msg_014VwiXbi91y3JMjcpyGBHX5",
+                    "type": "message",
+                    "role": "assistant",
+                    "model": "claude-3-5-haiku-20241022",
+                    "content": [{ "type": "text", "text": "Hello again!" }],
+                    "stop_reason": "end_turn",
+                    "stop_sequence": null,
+                    "usage": { "input_tokens": 11, "output_tokens": 36 }
+                }
+            }
+        }))
+        .unwrap();
+
+        let result = line.into_result();
+        assert_eq!(result.custom_id, "my-second-request");
+        let response = result.result.unwrap();
+        assert_eq!(response.usage.output_tokens, 36);
+
+        let line: MessageBatchResultLine = serde_json::from_value(json!({
+            "custom_id": "my-first-request",
+            "result": {
+                "type": "errored",
+                "error": {
+                    "type": "error",
+                    "error": { "type": "invalid_request_error", "message": "Invalid model" }
+                }
+            }
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_result().result.unwrap_err(),
+            BatchItemError::Failed("Invalid model".into())
+        );
+
+        let line: MessageBatchResultLine = serde_json::from_value(json!({
+            "custom_id": "my-third-request",
+            "result": { "type": "canceled" }
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_result().result.unwrap_err(),
+            BatchItemError::Cancelled
+        );
+    }
+}
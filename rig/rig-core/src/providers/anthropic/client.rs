@@ -4,11 +4,13 @@ use http::{HeaderName, HeaderValue};
 use super::completion::{ANTHROPIC_VERSION_LATEST, CompletionModel, OAuthCompletionModel};
 use crate::{
     client::{
-        self, ApiKey, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider,
-        ProviderBuilder, ProviderClient,
+        self, ApiKey, BearerAuth, Capabilities, Capable, DebugExt, ModelInfo, ModelListingClient,
+        ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
     },
-    http_client,
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
+use serde::Deserialize;
 
 // ================================================================
 // Main Anthropic Client
@@ -328,3 +330,122 @@ impl<H> ClientBuilder<H> {
         })
     }
 }
+
+/// Add the given betas to the `anthropic-beta` header of a request, keeping the betas configured
+/// on the client. Used for the betas that only some requests need (Files API, server tools).
+pub(crate) fn with_betas(mut req: http_client::Builder, betas: &[&str]) -> http_client::Builder {
+    let Some(headers) = req.headers_mut() else {
+        return req;
+    };
+
+    let mut merged: Vec<String> = headers
+        .get("anthropic-beta")
+        .and_then(|betas| betas.to_str().ok())
+        .map(|betas| {
+            betas
+                .split(',')
+                .map(|beta| beta.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let len = merged.len();
+
+    for beta in betas {
+        if !merged.iter().any(|merged| merged == beta) {
+            merged.push(beta.to_string());
+        }
+    }
+
+    if merged.len() > len
+        && let Ok(value) = HeaderValue::from_str(&merged.join(","))
+    {
+        headers.insert("anthropic-beta", value);
+    }
+
+    req
+}
+
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<Model>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    last_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+    #[serde(default)]
+    display_name: Option<String>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        Self {
+            name: model.display_name,
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let mut models = vec![];
+        let mut path = "/v1/models?limit=1000".to_string();
+
+        loop {
+            let page: ModelList = self.get_models(&path).await?;
+            models.extend(page.data.into_iter().map(ModelInfo::from));
+
+            match page.last_id {
+                Some(last_id) if page.has_more => {
+                    path = format!("/v1/models?limit=1000&after_id={last_id}");
+                }
+                _ => break,
+            }
+        }
+
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let page: ModelList = serde_json::from_value(serde_json::json!({
+            "data": [{
+                "type": "model",
+                "id": "claude-sonnet-4-20250514",
+                "display_name": "Claude Sonnet 4",
+                "created_at": "2025-05-22T00:00:00Z"
+            }],
+            "has_more": true,
+            "first_id": "claude-sonnet-4-20250514",
+            "last_id": "claude-sonnet-4-20250514"
+        }))
+        .unwrap();
+
+        assert!(page.has_more);
+        assert_eq!(
+            page.data
+                .into_iter()
+                .map(ModelInfo::from)
+                .collect::<Vec<_>>(),
+            vec![ModelInfo {
+                name: Some("Claude Sonnet 4".to_string()),
+                ..ModelInfo::new("claude-sonnet-4-20250514")
+            }]
+        );
+    }
+}
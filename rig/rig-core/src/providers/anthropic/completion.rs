@@ -11,7 +11,8 @@ use crate::{
 };
 use std::{convert::Infallible, str::FromStr};
 
-use super::client::{Client, OAuthClient};
+use super::client::{Client, OAuthClient, with_betas};
+use super::files;
 use crate::completion::CompletionRequest;
 use crate::providers::anthropic::streaming::StreamingCompletionResponse;
 use bytes::Bytes;
@@ -134,6 +135,109 @@ pub struct ToolDefinition {
     pub input_schema: serde_json::Value,
 }
 
+/// The beta of the code execution server tool.
+pub const CODE_EXECUTION_BETA: &str = "code-execution-2025-05-22";
+/// The beta of the web fetch server tool.
+pub const WEB_FETCH_BETA: &str = "web-fetch-2025-09-10";
+
+/// A tool executed by Anthropic, see [completion::HostedTool].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum ServerTool {
+    #[serde(rename = "web_search_20250305")]
+    WebSearch {
+        name: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        max_uses: Option<u32>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allowed_domains: Vec<String>,
+    },
+    #[serde(rename = "code_execution_20250522")]
+    CodeExecution { name: String },
+    #[serde(rename = "web_fetch_20250910")]
+    WebFetch { name: String },
+}
+
+impl ServerTool {
+    /// The beta required to use the server tool, if any.
+    pub fn beta(&self) -> Option<&'static str> {
+        match self {
+            ServerTool::WebSearch { .. } => None,
+            ServerTool::CodeExecution { .. } => Some(CODE_EXECUTION_BETA),
+            ServerTool::WebFetch { .. } => Some(WEB_FETCH_BETA),
+        }
+    }
+}
+
+impl TryFrom<&completion::HostedTool> for ServerTool {
+    type Error = CompletionError;
+
+    fn try_from(tool: &completion::HostedTool) -> Result<Self, Self::Error> {
+        match tool {
+            completion::HostedTool::WebSearch {
+                allowed_domains,
+                max_uses,
+            } => Ok(ServerTool::WebSearch {
+                name: "web_search".to_string(),
+                max_uses: *max_uses,
+                allowed_domains: allowed_domains.clone(),
+            }),
+            completion::HostedTool::CodeInterpreter => Ok(ServerTool::CodeExecution {
+                name: "code_execution".to_string(),
+            }),
+            completion::HostedTool::UrlContext => Ok(ServerTool::WebFetch {
+                name: "web_fetch".to_string(),
+            }),
+            tool => Err(tool.unsupported("Anthropic")),
+        }
+    }
+}
+
+/// A tool of a request, either a server tool or a function tool.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(untagged)]
+pub(crate) enum RequestTool {
+    Server(ServerTool),
+    Function(ToolDefinition),
+}
+
+/// Converts the function tools and the hosted tools of a request.
+pub(crate) fn request_tools(
+    tools: Vec<completion::ToolDefinition>,
+    hosted_tools: &[completion::HostedTool],
+) -> Result<Vec<RequestTool>, CompletionError> {
+    let mut request_tools = hosted_tools
+        .iter()
+        .map(|tool| ServerTool::try_from(tool).map(RequestTool::Server))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    request_tools.extend(tools.into_iter().map(|tool| {
+        RequestTool::Function(ToolDefinition {
+            name: tool.name,
+            description: Some(tool.description),
+            input_schema: tool.parameters,
+        })
+    }));
+
+    Ok(request_tools)
+}
+
+/// The betas required by a request: the Files API when files are referenced, and those of the
+/// server tools.
+pub(crate) fn request_betas(messages: &[Message], tools: &[RequestTool]) -> Vec<&'static str> {
+    let mut betas = Vec::new();
+    if files::references_files(messages) {
+        betas.push(files::FILES_API_BETA);
+    }
+
+    betas.extend(tools.iter().filter_map(|tool| match tool {
+        RequestTool::Server(tool) => tool.beta(),
+        RequestTool::Function(_) => None,
+    }));
+
+    betas
+}
+
 /// Cache control directive for Anthropic prompt caching
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -156,11 +260,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
     type Error = CompletionError;
 
     fn try_from(response: CompletionResponse) -> Result<Self, Self::Error> {
-        let content = response
-            .content
-            .iter()
-            .map(|content| content.clone().try_into())
-            .collect::<Result<Vec<_>, _>>()?;
+        let content = assistant_content(response.content.clone())?;
 
         let choice = OneOrMany::many(content).map_err(|_| {
             CompletionError::ResponseError(
@@ -177,11 +277,27 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response.stop_reason.as_deref().map(map_stop_reason),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: None,
             raw_response: response,
         })
     }
 }
 
+/// Map an Anthropic `stop_reason` to a [completion::FinishReason]
+pub(crate) fn map_stop_reason(reason: &str) -> completion::FinishReason {
+    match reason {
+        "end_turn" => completion::FinishReason::Stop,
+        "max_tokens" | "model_context_window_exceeded" => completion::FinishReason::Length,
+        "tool_use" => completion::FinishReason::ToolCalls,
+        "stop_sequence" => completion::FinishReason::StopSequence,
+        "refusal" => completion::FinishReason::ContentFilter,
+        other => completion::FinishReason::Other(other.to_string()),
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct Message {
     pub role: Role,
@@ -203,6 +319,9 @@ pub enum Content {
         text: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         cache_control: Option<CacheControl>,
+        /// The sources cited by the text, only returned in responses
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<Vec<TextCitation>>,
     },
     Image {
         source: ImageSource,
@@ -227,12 +346,209 @@ pub enum Content {
         source: DocumentSource,
         #[serde(skip_serializing_if = "Option::is_none")]
         cache_control: Option<CacheControl>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        title: Option<String>,
+        /// Metadata of the document, which is not cited
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        context: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        citations: Option<CitationsConfig>,
+    },
+    /// An image uploaded with the Files API (see [crate::files]), only sent in requests
+    #[serde(rename = "image", skip_deserializing)]
+    ImageFile {
+        source: FileSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    /// A document uploaded with the Files API (see [crate::files]), only sent in requests
+    #[serde(rename = "document", skip_deserializing)]
+    DocumentFile {
+        source: FileSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     Thinking {
         thinking: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         signature: Option<String>,
     },
+    /// Thinking flagged by the safety systems, returned encrypted. It is converted into a
+    /// [Reasoning] with no text and the encrypted data as signature, so that it can be sent back.
+    RedactedThinking { data: String },
+    /// A call of a server tool (see [ServerTool]), executed by Anthropic. It is converted, along
+    /// with its result, into a [message::HostedToolCall].
+    ServerToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    WebSearchToolResult {
+        tool_use_id: String,
+        content: serde_json::Value,
+    },
+    CodeExecutionToolResult {
+        tool_use_id: String,
+        content: serde_json::Value,
+    },
+    WebFetchToolResult {
+        tool_use_id: String,
+        content: serde_json::Value,
+    },
+}
+
+impl Content {
+    /// The id of the server tool call of a server tool result.
+    pub(crate) fn server_tool_use_id(&self) -> Option<&str> {
+        match self {
+            Content::WebSearchToolResult { tool_use_id, .. }
+            | Content::CodeExecutionToolResult { tool_use_id, .. }
+            | Content::WebFetchToolResult { tool_use_id, .. } => Some(tool_use_id),
+            _ => None,
+        }
+    }
+}
+
+/// Converts the content blocks of an assistant message, merging the server tool calls with their
+/// results into [message::HostedToolCall]s.
+pub(crate) fn assistant_content(
+    content: impl IntoIterator<Item = Content>,
+) -> Result<Vec<message::AssistantContent>, MessageError> {
+    let mut assistant_content = Vec::new();
+    let mut server_tool_uses = Vec::new();
+
+    for content in content {
+        if matches!(content, Content::ServerToolUse { .. }) {
+            server_tool_uses.push(content);
+        } else if let Some(id) = content.server_tool_use_id() {
+            let index = server_tool_uses
+                .iter()
+                .position(|tool_use| matches!(tool_use, Content::ServerToolUse { id: use_id, .. } if use_id == id))
+                .ok_or_else(|| {
+                    MessageError::ConversionError(format!(
+                        "Server tool result without a server tool call: {id}"
+                    ))
+                })?;
+            let tool_use = server_tool_uses.remove(index);
+            assistant_content.push(message::AssistantContent::HostedToolCall(hosted_tool_call(
+                tool_use,
+                Some(content),
+            )?));
+        } else {
+            assistant_content.push(content.try_into()?);
+        }
+    }
+
+    // Server tool calls without results, e.g. when a long turn was paused
+    for tool_use in server_tool_uses {
+        assistant_content.push(message::AssistantContent::HostedToolCall(hosted_tool_call(
+            tool_use, None,
+        )?));
+    }
+
+    Ok(assistant_content)
+}
+
+/// Converts a server tool call and its result into a [message::HostedToolCall]. The content blocks
+/// are kept as the raw call, to be sent back as-is.
+pub(crate) fn hosted_tool_call(
+    tool_use: Content,
+    result: Option<Content>,
+) -> Result<message::HostedToolCall, MessageError> {
+    let Content::ServerToolUse { id, name, input } = &tool_use else {
+        return Err(MessageError::ConversionError(
+            "Expected a server tool call".to_string(),
+        ));
+    };
+
+    let input_field = |field: &str| input.get(field).and_then(|value| value.as_str());
+    let (tool, call_input) = match name.as_str() {
+        "web_search" => (message::HostedToolKind::WebSearch, input_field("query")),
+        "web_fetch" => (message::HostedToolKind::UrlContext, input_field("url")),
+        name if name.contains("code_execution") => (
+            message::HostedToolKind::CodeInterpreter,
+            input_field("code").or_else(|| input_field("command")),
+        ),
+        name => {
+            return Err(MessageError::ConversionError(format!(
+                "Unsupported server tool: {name}"
+            )));
+        }
+    };
+
+    let mut call =
+        message::HostedToolCall::new(id.clone(), tool).with_input(call_input.map(str::to_string));
+
+    let to_value = |content: &Content| {
+        serde_json::to_value(content)
+            .map_err(|error| MessageError::ConversionError(error.to_string()))
+    };
+
+    let mut blocks = vec![to_value(&tool_use)?];
+    if let Some(result) = result {
+        blocks.push(to_value(&result)?);
+
+        let text = |value: &serde_json::Value, field: &str| {
+            value
+                .get(field)
+                .and_then(|value| value.as_str())
+                .map(str::to_string)
+        };
+
+        match &result {
+            Content::WebSearchToolResult { content, .. } => match content.as_array() {
+                Some(results) => {
+                    call.sources = results
+                        .iter()
+                        .filter_map(|result| {
+                            Some(message::HostedToolSource::url(
+                                text(result, "url")?,
+                                text(result, "title"),
+                            ))
+                        })
+                        .collect();
+                }
+                None => call.error = text(content, "error_code"),
+            },
+            Content::CodeExecutionToolResult { content, .. } => {
+                let return_code = content.get("return_code").and_then(|code| code.as_i64());
+                match return_code {
+                    Some(0) => call.output = text(content, "stdout"),
+                    Some(code) => {
+                        call.output = text(content, "stderr");
+                        call.error = Some(format!("The code exited with code {code}"));
+                    }
+                    None => call.error = text(content, "error_code"),
+                }
+            }
+            Content::WebFetchToolResult { content, .. } => match text(content, "url") {
+                Some(url) => {
+                    let document = content.get("content");
+                    call.sources = vec![message::HostedToolSource {
+                        url: Some(url),
+                        title: document.and_then(|document| text(document, "title")),
+                        text: document
+                            .and_then(|document| document.get("source"))
+                            .filter(|source| text(source, "type").as_deref() == Some("text"))
+                            .and_then(|source| text(source, "data")),
+                        ..Default::default()
+                    }];
+                }
+                None => call.error = text(content, "error_code"),
+            },
+            _ => {}
+        }
+    }
+
+    Ok(call.with_raw(serde_json::Value::Array(blocks)))
+}
+
+/// The content blocks of a hosted tool call made by Anthropic, `None` for the hosted tool calls
+/// of other providers.
+fn server_tool_blocks(call: &message::HostedToolCall) -> Option<Vec<Content>> {
+    let blocks: Vec<Content> = serde_json::from_value(call.raw.clone()?).ok()?;
+
+    matches!(blocks.first(), Some(Content::ServerToolUse { .. })).then_some(blocks)
 }
 
 impl FromStr for Content {
@@ -242,6 +558,7 @@ impl FromStr for Content {
         Ok(Content::Text {
             text: s.to_owned(),
             cache_control: None,
+            citations: None,
         })
     }
 }
@@ -311,6 +628,133 @@ pub struct DocumentSource {
     pub r#type: SourceType,
 }
 
+/// Enables the citations of a document.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct CitationsConfig {
+    pub enabled: bool,
+}
+
+/// A citation of a text block, pointing either to a document of the request or to a web search
+/// result. See <https://docs.anthropic.com/en/docs/build-with-claude/citations>.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct TextCitation {
+    /// `char_location`, `page_location`, `content_block_location` or
+    /// `web_search_result_location`
+    #[serde(rename = "type")]
+    pub r#type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cited_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub document_title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// The location of the cited text in the source (character, page or block indices)
+    #[serde(flatten)]
+    pub location: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<TextCitation> for message::CitationSource {
+    fn from(citation: TextCitation) -> Self {
+        match citation.url {
+            Some(url) => message::CitationSource::url(url, citation.title),
+            // The documents of the request are titled with their ids, see [request_messages]
+            None => message::CitationSource {
+                document_id: citation.document_title,
+                ..Default::default()
+            },
+        }
+        .with_quote(citation.cited_text)
+    }
+}
+
+/// Converts a text block into a [message::Text], the citations of the block applying to the whole
+/// text.
+fn cited_text(text: String, citations: Option<Vec<TextCitation>>) -> message::Text {
+    let citations = citations
+        .filter(|citations| !citations.is_empty())
+        .map(|citations| {
+            let sources = citations.into_iter().map(Into::into).collect();
+            vec![message::Citation::new(Some(0..text.len()), sources)]
+        })
+        .unwrap_or_default();
+
+    message::Text::from(text).with_citations(citations)
+}
+
+/// Converts the chat history of a request, preceded by its documents.
+///
+/// With `citations`, the documents are sent as plain text document blocks with citations enabled,
+/// titled with the ids of the documents so that the citations can be linked back to them.
+/// Otherwise, they are sent as text blocks rendered like for other providers.
+pub(crate) fn request_messages(
+    documents: Vec<completion::Document>,
+    chat_history: impl IntoIterator<Item = message::Message>,
+    citations: bool,
+) -> Result<Vec<Message>, MessageError> {
+    let mut messages = Vec::new();
+
+    if citations {
+        let documents = documents.into_iter().map(|document| {
+            let context = (!document.additional_props.is_empty()).then(|| {
+                let mut props = document.additional_props.into_iter().collect::<Vec<_>>();
+                props.sort();
+                props
+                    .into_iter()
+                    .map(|(key, value)| format!("{key}: {value}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+
+            Content::Document {
+                source: DocumentSource {
+                    data: document.text,
+                    media_type: DocumentFormat::TXT,
+                    r#type: SourceType::TEXT,
+                },
+                cache_control: None,
+                title: Some(document.id),
+                context,
+                citations: Some(CitationsConfig { enabled: true }),
+            }
+        });
+        if let Ok(content) = OneOrMany::many(documents) {
+            messages.push(Message {
+                role: Role::User,
+                content,
+            });
+        }
+    } else {
+        let documents = documents.iter().map(|document| Content::Text {
+            text: document.to_string(),
+            cache_control: None,
+            citations: None,
+        });
+        if let Ok(content) = OneOrMany::many(documents) {
+            messages.push(Message {
+                role: Role::User,
+                content,
+            });
+        }
+    }
+
+    for message in chat_history {
+        messages.push(message.try_into()?);
+    }
+
+    Ok(messages)
+}
+
+/// The source of an image or document uploaded with the Files API.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename = "file")]
+pub struct FileSource {
+    pub file_id: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum ImageFormat {
@@ -332,6 +776,9 @@ pub enum ImageFormat {
 pub enum DocumentFormat {
     #[serde(rename = "application/pdf")]
     PDF,
+    /// Only used for the documents of requests, see [request_messages]
+    #[serde(rename = "text/plain")]
+    TXT,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -339,6 +786,7 @@ pub enum DocumentFormat {
 pub enum SourceType {
     BASE64,
     URL,
+    TEXT,
 }
 
 impl From<String> for Content {
@@ -346,6 +794,7 @@ impl From<String> for Content {
         Content::Text {
             text,
             cache_control: None,
+            citations: None,
         }
     }
 }
@@ -375,6 +824,7 @@ impl From<SourceType> for message::ContentFormat {
         match source_type {
             SourceType::BASE64 => message::ContentFormat::Base64,
             SourceType::URL => message::ContentFormat::Url,
+            SourceType::TEXT => message::ContentFormat::String,
         }
     }
 }
@@ -425,9 +875,10 @@ impl TryFrom<message::AssistantContent> for Content {
     type Error = MessageError;
     fn try_from(text: message::AssistantContent) -> Result<Self, Self::Error> {
         match text {
-            message::AssistantContent::Text(message::Text { text }) => Ok(Content::Text {
+            message::AssistantContent::Text(message::Text { text, .. }) => Ok(Content::Text {
                 text,
                 cache_control: None,
+                citations: None,
             }),
             message::AssistantContent::Image(_) => Err(MessageError::ConversionError(
                 "Anthropic currently doesn't support images.".to_string(),
@@ -439,6 +890,11 @@ impl TryFrom<message::AssistantContent> for Content {
                     input: function.arguments,
                 })
             }
+            message::AssistantContent::Reasoning(Reasoning {
+                reasoning,
+                signature: Some(data),
+                ..
+            }) if reasoning.concat().is_empty() => Ok(Content::RedactedThinking { data }),
             message::AssistantContent::Reasoning(Reasoning {
                 reasoning,
                 signature,
@@ -447,6 +903,9 @@ impl TryFrom<message::AssistantContent> for Content {
                 thinking: reasoning.first().cloned().unwrap_or(String::new()),
                 signature,
             }),
+            message::AssistantContent::HostedToolCall(_) => Err(MessageError::ConversionError(
+                "Hosted tool calls are converted into several content blocks".to_string(),
+            )),
         }
     }
 }
@@ -459,16 +918,17 @@ impl TryFrom<message::Message> for Message {
             message::Message::User { content } => Message {
                 role: Role::User,
                 content: content.try_map(|content| match content {
-                    message::UserContent::Text(message::Text { text }) => Ok(Content::Text {
+                    message::UserContent::Text(message::Text { text, .. }) => Ok(Content::Text {
                         text,
                         cache_control: None,
+                        citations: None,
                     }),
                     message::UserContent::ToolResult(message::ToolResult {
                         id, content, ..
                     }) => Ok(Content::ToolResult {
                         tool_use_id: id,
                         content: content.try_map(|content| match content {
-                            message::ToolResultContent::Text(message::Text { text }) => {
+                            message::ToolResultContent::Text(message::Text { text, .. }) => {
                                 Ok(ToolResultContent::Text { text })
                             }
                             message::ToolResultContent::Image(image) => {
@@ -492,6 +952,13 @@ impl TryFrom<message::Message> for Message {
                         is_error: None,
                         cache_control: None,
                     }),
+                    message::UserContent::Image(message::Image {
+                        data: DocumentSourceKind::FileId(file_id),
+                        ..
+                    }) => Ok(Content::ImageFile {
+                        source: FileSource { file_id },
+                        cache_control: None,
+                    }),
                     message::UserContent::Image(message::Image {
                         data, media_type, ..
                     }) => {
@@ -527,6 +994,13 @@ impl TryFrom<message::Message> for Message {
                             cache_control: None,
                         })
                     }
+                    message::UserContent::Document(message::Document {
+                        data: DocumentSourceKind::FileId(file_id),
+                        ..
+                    }) => Ok(Content::DocumentFile {
+                        source: FileSource { file_id },
+                        cache_control: None,
+                    }),
                     message::UserContent::Document(message::Document {
                         data, media_type, ..
                     }) => {
@@ -553,6 +1027,9 @@ impl TryFrom<message::Message> for Message {
                         Ok(Content::Document {
                             source,
                             cache_control: None,
+                            title: None,
+                            context: None,
+                            citations: None,
                         })
                     }
                     message::UserContent::Audio { .. } => Err(MessageError::ConversionError(
@@ -564,10 +1041,27 @@ impl TryFrom<message::Message> for Message {
                 })?,
             },
 
-            message::Message::Assistant { content, .. } => Message {
-                content: content.try_map(|content| content.try_into())?,
-                role: Role::Assistant,
-            },
+            message::Message::Assistant { content, .. } => {
+                let mut blocks = Vec::new();
+                for content in content {
+                    match content {
+                        // The hosted tool calls of other providers can't be sent back
+                        message::AssistantContent::HostedToolCall(call) => {
+                            blocks.extend(server_tool_blocks(&call).unwrap_or_default())
+                        }
+                        content => blocks.push(content.try_into()?),
+                    }
+                }
+
+                Message {
+                    content: OneOrMany::many(blocks).map_err(|_| {
+                        MessageError::ConversionError(
+                            "Assistant message has no content supported by Anthropic".to_string(),
+                        )
+                    })?,
+                    role: Role::Assistant,
+                }
+            }
         })
     }
 }
@@ -577,7 +1071,9 @@ impl TryFrom<Content> for message::AssistantContent {
 
     fn try_from(content: Content) -> Result<Self, Self::Error> {
         Ok(match content {
-            Content::Text { text, .. } => message::AssistantContent::text(text),
+            Content::Text {
+                text, citations, ..
+            } => message::AssistantContent::Text(cited_text(text, citations)),
             Content::ToolUse { id, name, input } => {
                 message::AssistantContent::tool_call(id, name, input)
             }
@@ -587,6 +1083,9 @@ impl TryFrom<Content> for message::AssistantContent {
             } => message::AssistantContent::Reasoning(
                 Reasoning::new(&thinking).with_signature(signature),
             ),
+            Content::RedactedThinking { data } => message::AssistantContent::Reasoning(
+                Reasoning::multi(vec![]).with_signature(Some(data)),
+            ),
             _ => {
                 return Err(MessageError::ConversionError(
                     "Content did not contain a message, tool call, or reasoning".to_owned(),
@@ -636,8 +1135,17 @@ impl TryFrom<Message> for message::Message {
                         }
                         Content::Document { source, .. } => message::UserContent::document(
                             source.data,
-                            Some(message::DocumentMediaType::PDF),
+                            Some(match source.media_type {
+                                DocumentFormat::PDF => message::DocumentMediaType::PDF,
+                                DocumentFormat::TXT => message::DocumentMediaType::TXT,
+                            }),
                         ),
+                        Content::ImageFile { source, .. } => {
+                            message::UserContent::image_file_id(source.file_id, None)
+                        }
+                        Content::DocumentFile { source, .. } => {
+                            message::UserContent::document_file_id(source.file_id, None)
+                        }
                         _ => {
                             return Err(MessageError::ConversionError(
                                 "Unsupported content type for User role".to_owned(),
@@ -647,12 +1155,15 @@ impl TryFrom<Message> for message::Message {
                 })?,
             },
             Role::Assistant => match message.content.first() {
-                Content::Text { .. } | Content::ToolUse { .. } | Content::Thinking { .. } => {
-                    message::Message::Assistant {
-                        id: None,
-                        content: message.content.try_map(|content| content.try_into())?,
-                    }
-                }
+                Content::Text { .. }
+                | Content::ToolUse { .. }
+                | Content::Thinking { .. }
+                | Content::ServerToolUse { .. } => message::Message::Assistant {
+                    id: None,
+                    content: OneOrMany::many(assistant_content(message.content)?).map_err(
+                        |_| MessageError::ConversionError("Empty assistant message".to_string()),
+                    )?,
+                },
 
                 _ => {
                     return Err(MessageError::ConversionError(
@@ -671,6 +1182,8 @@ pub struct CompletionModel<T = reqwest::Client> {
     pub default_max_tokens: Option<u64>,
     /// Enable automatic prompt caching (adds cache_control breakpoints to system prompt and messages)
     pub prompt_caching: bool,
+    /// Enable citations of the documents of requests (see [CompletionModel::with_citations])
+    pub citations: bool,
 }
 
 impl<T> CompletionModel<T>
@@ -686,6 +1199,7 @@ where
             model,
             default_max_tokens,
             prompt_caching: false, // Default to off
+            citations: false,
         }
     }
 
@@ -695,6 +1209,7 @@ where
             model: model.to_string(),
             default_max_tokens: Some(calculate_max_tokens_custom(model)),
             prompt_caching: false, // Default to off
+            citations: false,
         }
     }
 
@@ -709,6 +1224,41 @@ where
         self.prompt_caching = true;
         self
     }
+
+    /// Enable citations of the documents of requests (e.g. from an agent's dynamic context).
+    ///
+    /// When enabled, documents are sent as document blocks with citations enabled, and the
+    /// response text is split in blocks citing the documents (see [message::Citation]). This
+    /// changes the shape and token cost of responses, so it is off by default.
+    pub fn with_citations(mut self) -> Self {
+        self.citations = true;
+        self
+    }
+
+    /// Create the request sent to the messages API, using the model's default `max_tokens` if
+    /// the request doesn't set it.
+    pub(crate) fn create_completion_request(
+        &self,
+        mut completion_request: completion::CompletionRequest,
+    ) -> Result<AnthropicCompletionRequest, CompletionError> {
+        // Check if max_tokens is set, required for Anthropic
+        if completion_request.max_tokens.is_none() {
+            if let Some(tokens) = self.default_max_tokens {
+                completion_request.max_tokens = Some(tokens);
+            } else {
+                return Err(CompletionError::RequestError(
+                    "`max_tokens` must be set for Anthropic".into(),
+                ));
+            }
+        }
+
+        AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: &self.model,
+            request: completion_request,
+            prompt_caching: self.prompt_caching,
+            citations: self.citations,
+        })
+    }
 }
 
 /// Anthropic requires a `max_tokens` parameter to be set, which is dependent on the model. If not
@@ -774,7 +1324,7 @@ impl TryFrom<message::ToolChoice> for ToolChoice {
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-struct AnthropicCompletionRequest {
+pub(crate) struct AnthropicCompletionRequest {
     model: String,
     messages: Vec<Message>,
     max_tokens: u64,
@@ -783,14 +1333,169 @@ struct AnthropicCompletionRequest {
     system: Vec<SystemContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<ToolChoice>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    tools: Vec<ToolDefinition>,
+    tools: Vec<RequestTool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     additional_params: Option<serde_json::Value>,
 }
 
+impl AnthropicCompletionRequest {
+    /// The betas required by the request.
+    pub(crate) fn betas(&self) -> Vec<&'static str> {
+        request_betas(&self.messages, &self.tools)
+    }
+}
+
+/// The extended thinking configuration of the messages API.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ThinkingConfig {
+    Enabled { budget_tokens: u64 },
+    Disabled,
+}
+
+/// The minimum thinking budget accepted by Anthropic.
+const MIN_THINKING_BUDGET_TOKENS: u64 = 1_024;
+
+impl ThinkingConfig {
+    /// Maps the provider-neutral reasoning configuration. Budgets are raised to the minimum
+    /// accepted by Anthropic, and must be lower than `max_tokens`: budgets derived from an effort
+    /// level are capped to half of `max_tokens` to leave room for the answer, while explicit
+    /// budgets that do not fit are rejected.
+    pub fn new(
+        reasoning: &completion::ReasoningConfig,
+        max_tokens: u64,
+    ) -> Result<Self, CompletionError> {
+        if reasoning.is_disabled() {
+            return Ok(Self::Disabled);
+        }
+
+        if max_tokens <= MIN_THINKING_BUDGET_TOKENS {
+            return Err(CompletionError::RequestError(
+                format!(
+                    "`max_tokens` ({max_tokens}) must be greater than the minimum thinking \
+                     budget of {MIN_THINKING_BUDGET_TOKENS} tokens"
+                )
+                .into(),
+            ));
+        }
+
+        let budget_tokens = match reasoning.budget_tokens {
+            Some(budget_tokens) if budget_tokens >= max_tokens => {
+                return Err(CompletionError::RequestError(
+                    format!(
+                        "The thinking budget ({budget_tokens}) must be lower than `max_tokens` \
+                         ({max_tokens})"
+                    )
+                    .into(),
+                ));
+            }
+            Some(budget_tokens) => budget_tokens,
+            None => reasoning
+                .resolved_effort()
+                .unwrap_or(completion::ReasoningEffort::Medium)
+                .budget_tokens()
+                .min(max_tokens / 2),
+        };
+
+        Ok(Self::Enabled {
+            budget_tokens: budget_tokens.max(MIN_THINKING_BUDGET_TOKENS),
+        })
+    }
+
+    /// The thinking configuration of a request, unless already given in the additional parameters.
+    ///
+    /// Extended thinking is not compatible with a modified temperature or `top_k`: when thinking
+    /// is enabled, these are dropped from the request with a warning, or rejected in strict mode.
+    pub fn from_request(
+        req: &mut CompletionRequest,
+        max_tokens: u64,
+    ) -> Result<Option<Self>, CompletionError> {
+        let Some(thinking) = req
+            .reasoning_unless_overridden("thinking")
+            .map(|reasoning| Self::new(reasoning, max_tokens))
+            .transpose()?
+        else {
+            return Ok(None);
+        };
+
+        if let Self::Enabled { .. } = thinking {
+            let temperature = req.temperature.filter(|temperature| *temperature != 1.0);
+            let incompatible = [
+                ("temperature", temperature.is_some()),
+                ("top_k", req.sampling.top_k.is_some()),
+            ];
+
+            for (param, _) in incompatible.into_iter().filter(|(_, set)| *set) {
+                if req.sampling.strict {
+                    return Err(CompletionError::RequestError(
+                        format!("Anthropic does not support `{param}` with extended thinking")
+                            .into(),
+                    ));
+                }
+
+                tracing::warn!(
+                    target: "rig::completions",
+                    "Anthropic does not support `{param}` with extended thinking, it will be ignored"
+                );
+            }
+
+            if temperature.is_some() {
+                req.temperature = None;
+            }
+            req.sampling.top_k = None;
+        }
+
+        Ok(Some(thinking))
+    }
+}
+
+/// The key of a sampling parameter in the Anthropic messages API.
+fn sampling_key(param: completion::SamplingParam) -> &'static str {
+    match param {
+        completion::SamplingParam::Stop => "stop_sequences",
+        param => param.name(),
+    }
+}
+
+/// The sampling parameters supported by the Anthropic messages API.
+#[derive(Debug, Deserialize, Serialize, Default)]
+pub(crate) struct SamplingParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+}
+
+impl TryFrom<completion::SamplingParams> for SamplingParams {
+    type Error = CompletionError;
+
+    fn try_from(sampling: completion::SamplingParams) -> Result<Self, Self::Error> {
+        sampling.check_supported(
+            "Anthropic",
+            &[
+                completion::SamplingParam::TopP,
+                completion::SamplingParam::TopK,
+                completion::SamplingParam::Stop,
+            ],
+        )?;
+
+        Ok(Self {
+            top_p: sampling.top_p,
+            top_k: sampling.top_k,
+            stop_sequences: sampling.stop,
+        })
+    }
+}
+
 /// Check if a Content block is empty (Anthropic doesn't allow cache_control on empty blocks)
 fn is_content_empty(content: &Content) -> bool {
     match content {
@@ -812,6 +1517,8 @@ fn set_content_cache_control(content: &mut Content, value: Option<CacheControl>)
         Content::Image { cache_control, .. } => *cache_control = value,
         Content::ToolResult { cache_control, .. } => *cache_control = value,
         Content::Document { cache_control, .. } => *cache_control = value,
+        Content::ImageFile { cache_control, .. } => *cache_control = value,
+        Content::DocumentFile { cache_control, .. } => *cache_control = value,
         _ => {}
     }
 }
@@ -822,7 +1529,11 @@ fn set_content_cache_control(content: &mut Content, value: Option<CacheControl>)
 /// are added.
 pub fn apply_cache_control(system: &mut [SystemContent], messages: &mut [Message]) {
     // Add cache_control to the system prompt (if non-empty)
-    if let Some(SystemContent::Text { text, cache_control }) = system.last_mut() {
+    if let Some(SystemContent::Text {
+        text,
+        cache_control,
+    }) = system.last_mut()
+    {
         if !text.is_empty() {
             *cache_control = Some(CacheControl::Ephemeral);
         }
@@ -850,6 +1561,7 @@ pub struct AnthropicRequestParams<'a> {
     pub model: &'a str,
     pub request: CompletionRequest,
     pub prompt_caching: bool,
+    pub citations: bool,
 }
 
 impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
@@ -858,8 +1570,9 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
     fn try_from(params: AnthropicRequestParams<'_>) -> Result<Self, Self::Error> {
         let AnthropicRequestParams {
             model,
-            request: req,
+            request: mut req,
             prompt_caching,
+            citations,
         } = params;
 
         // Check if max_tokens is set, required for Anthropic
@@ -869,26 +1582,13 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
             ));
         };
 
-        let mut full_history = vec![];
-        if let Some(docs) = req.normalized_documents() {
-            full_history.push(docs);
-        }
-        full_history.extend(req.chat_history);
-
-        let mut messages = full_history
-            .into_iter()
-            .map(Message::try_from)
-            .collect::<Result<Vec<Message>, _>>()?;
-
-        let tools = req
-            .tools
-            .into_iter()
-            .map(|tool| ToolDefinition {
-                name: tool.name,
-                description: Some(tool.description),
-                input_schema: tool.parameters,
-            })
-            .collect::<Vec<_>>();
+        let thinking = ThinkingConfig::from_request(&mut req, max_tokens)?;
+        req.check_logprobs_unsupported("Anthropic")?;
+        let sampling = req.sampling_unless_overridden(sampling_key);
+
+        let mut messages = request_messages(req.documents, req.chat_history, citations)?;
+
+        let tools = request_tools(req.tools, &req.hosted_tools)?;
 
         // Convert system prompt to array format for cache_control support
         let mut system = if let Some(preamble) = req.preamble {
@@ -915,8 +1615,10 @@ impl TryFrom<AnthropicRequestParams<'_>> for AnthropicCompletionRequest {
             max_tokens,
             system,
             temperature: req.temperature,
+            sampling: sampling.try_into()?,
             tool_choice: req.tool_choice.and_then(|x| ToolChoice::try_from(x).ok()),
             tools,
+            thinking,
             additional_params: req.additional_params,
         })
     }
@@ -936,7 +1638,7 @@ where
 
     async fn completion(
         &self,
-        mut completion_request: completion::CompletionRequest,
+        completion_request: completion::CompletionRequest,
     ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
         let span = if tracing::Span::current().is_disabled() {
             info_span!(
@@ -955,22 +1657,7 @@ where
             tracing::Span::current()
         };
 
-        // Check if max_tokens is set, required for Anthropic
-        if completion_request.max_tokens.is_none() {
-            if let Some(tokens) = self.default_max_tokens {
-                completion_request.max_tokens = Some(tokens);
-            } else {
-                return Err(CompletionError::RequestError(
-                    "`max_tokens` must be set for Anthropic".into(),
-                ));
-            }
-        }
-
-        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
-            model: &self.model,
-            request: completion_request,
-            prompt_caching: self.prompt_caching,
-        })?;
+        let request = self.create_completion_request(completion_request)?;
 
         if enabled!(Level::TRACE) {
             tracing::trace!(
@@ -981,11 +1668,10 @@ where
         }
 
         async move {
+            let betas = request.betas();
             let request: Vec<u8> = serde_json::to_vec(&request)?;
 
-            let req = self
-                .client
-                .post("/v1/messages")?
+            let req = with_betas(self.client.post("/v1/messages")?, &betas)
                 .body(request)
                 .map_err(|e| CompletionError::HttpError(e.into()))?;
 
@@ -1065,7 +1751,8 @@ enum ApiResponse<T> {
 
 /// Hardcoded system instruction for Claude Code OAuth tokens.
 /// This matches the behavior expected by Claude Code authentication.
-pub const CLAUDE_CODE_INSTRUCTIONS: &str = "You are Claude Code, Anthropic's official CLI for Claude.";
+pub const CLAUDE_CODE_INSTRUCTIONS: &str =
+    "You are Claude Code, Anthropic's official CLI for Claude.";
 
 /// Completion model for OAuth-authenticated clients (Claude Code tokens).
 ///
@@ -1078,6 +1765,7 @@ pub struct OAuthCompletionModel<T = reqwest::Client> {
     pub model: String,
     pub default_max_tokens: Option<u64>,
     pub prompt_caching: bool,
+    pub citations: bool,
 }
 
 impl<T> OAuthCompletionModel<T>
@@ -1093,6 +1781,7 @@ where
             model,
             default_max_tokens,
             prompt_caching: false,
+            citations: false,
         }
     }
 
@@ -1100,6 +1789,12 @@ where
         self.prompt_caching = true;
         self
     }
+
+    /// Enable citations of the documents of requests, see [CompletionModel::with_citations].
+    pub fn with_citations(mut self) -> Self {
+        self.citations = true;
+        self
+    }
 }
 
 impl<T> completion::CompletionModel for OAuthCompletionModel<T>
@@ -1168,6 +1863,7 @@ where
             model: &self.model,
             request: completion_request,
             prompt_caching: self.prompt_caching,
+            citations: self.citations,
         })?;
 
         if enabled!(Level::TRACE) {
@@ -1179,11 +1875,10 @@ where
         }
 
         async move {
+            let betas = request.betas();
             let request: Vec<u8> = serde_json::to_vec(&request)?;
 
-            let req = self
-                .client
-                .post("/v1/messages")?
+            let req = with_betas(self.client.post("/v1/messages")?, &betas)
                 .body(request)
                 .map_err(|e| CompletionError::HttpError(e.into()))?;
 
@@ -1332,6 +2027,7 @@ mod tests {
             Content::Text {
                 text: "\n\nHello there, how may I assist you today?".to_owned(),
                 cache_control: None,
+                citations: None,
             }
         );
 
@@ -1412,6 +2108,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stop_reason_mapping() {
+        assert_eq!(map_stop_reason("end_turn"), completion::FinishReason::Stop);
+        assert_eq!(
+            map_stop_reason("max_tokens"),
+            completion::FinishReason::Length
+        );
+        assert_eq!(
+            map_stop_reason("tool_use"),
+            completion::FinishReason::ToolCalls
+        );
+        assert_eq!(
+            map_stop_reason("pause_turn"),
+            completion::FinishReason::Other("pause_turn".to_string())
+        );
+    }
+
     #[test]
     fn test_message_to_message_conversion() {
         let user_message: Message = serde_json::from_str(
@@ -1488,7 +2201,7 @@ mod tests {
                 }
 
                 match iter.next().unwrap() {
-                    message::UserContent::Text(message::Text { text }) => {
+                    message::UserContent::Text(message::Text { text, .. }) => {
                         assert_eq!(text, "What is in this image?");
                     }
                     _ => panic!("Expected text content"),
@@ -1520,7 +2233,7 @@ mod tests {
                 };
                 assert_eq!(id, "toolu_01A09q90qw90lq917835lq9");
                 match content.first() {
-                    message::ToolResultContent::Text(message::Text { text }) => {
+                    message::ToolResultContent::Text(message::Text { text, .. }) => {
                         assert_eq!(text, "15 degrees");
                     }
                     _ => panic!("Expected text content"),
@@ -1605,6 +2318,7 @@ mod tests {
         let content = Content::Text {
             text: "Test message".to_string(),
             cache_control: Some(CacheControl::Ephemeral),
+            citations: None,
         };
         let json_content = serde_json::to_string(&content).unwrap();
         assert!(json_content.contains(r#""cache_control":{"type":"ephemeral"}"#));
@@ -1620,6 +2334,7 @@ mod tests {
                 content: OneOrMany::one(Content::Text {
                     text: "First message".to_string(),
                     cache_control: None,
+                    citations: None,
                 }),
             },
             Message {
@@ -1627,6 +2342,7 @@ mod tests {
                 content: OneOrMany::one(Content::Text {
                     text: "Response".to_string(),
                     cache_control: None,
+                    citations: None,
                 }),
             },
         ];
@@ -1655,4 +2371,351 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_sampling_params_mapping() {
+        let sampling = completion::SamplingParams {
+            top_p: Some(0.9),
+            top_k: Some(20),
+            stop: Some(vec!["END".to_string()]),
+            ..Default::default()
+        };
+
+        let params = SamplingParams::try_from(sampling.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&params).unwrap(),
+            json!({
+                "top_p": 0.9,
+                "top_k": 20,
+                "stop_sequences": ["END"]
+            })
+        );
+
+        let strict = completion::SamplingParams {
+            seed: Some(1),
+            strict: true,
+            ..sampling
+        };
+        assert!(SamplingParams::try_from(strict).is_err());
+    }
+
+    #[test]
+    fn test_thinking_config_from_reasoning() {
+        let high = completion::ReasoningConfig::effort(completion::ReasoningEffort::High);
+        assert_eq!(
+            serde_json::to_value(ThinkingConfig::new(&high, 64_000).unwrap()).unwrap(),
+            json!({ "type": "enabled", "budget_tokens": 16_384 })
+        );
+        // Derived budgets leave room for the answer, but never go below the minimum
+        assert_eq!(
+            ThinkingConfig::new(&high, 8_000).unwrap(),
+            ThinkingConfig::Enabled {
+                budget_tokens: 4_000
+            }
+        );
+        assert_eq!(
+            ThinkingConfig::new(&high, 1_500).unwrap(),
+            ThinkingConfig::Enabled {
+                budget_tokens: MIN_THINKING_BUDGET_TOKENS
+            }
+        );
+        // The budget must be lower than `max_tokens`
+        assert!(ThinkingConfig::new(&high, 1_000).is_err());
+        assert!(
+            ThinkingConfig::new(&completion::ReasoningConfig::budget_tokens(2_000), 2_000).is_err()
+        );
+        assert_eq!(
+            serde_json::to_value(
+                ThinkingConfig::new(&completion::ReasoningConfig::disabled(), 1_000).unwrap()
+            )
+            .unwrap(),
+            json!({ "type": "disabled" })
+        );
+    }
+
+    #[test]
+    fn test_thinking_drops_incompatible_sampling() {
+        let request = |strict| {
+            completion::CompletionRequestBuilder::new(
+                crate::testing::MockCompletionModel::new(),
+                "Hello",
+            )
+            .max_tokens(4_096)
+            .temperature(0.5)
+            .top_k(10)
+            .reasoning(completion::ReasoningConfig::effort(
+                completion::ReasoningEffort::Low,
+            ))
+            .strict_sampling(strict)
+            .build()
+        };
+
+        let params = |request| AnthropicRequestParams {
+            model: "claude",
+            request,
+            prompt_caching: false,
+            citations: false,
+        };
+        let body = serde_json::to_value(
+            AnthropicCompletionRequest::try_from(params(request(false))).unwrap(),
+        )
+        .unwrap();
+        assert!(body.get("temperature").is_none());
+        assert!(body.get("top_k").is_none());
+        assert_eq!(body["thinking"]["budget_tokens"], 2_048);
+
+        assert!(AnthropicCompletionRequest::try_from(params(request(true))).is_err());
+    }
+
+    #[test]
+    fn test_redacted_thinking_round_trip() {
+        let content: Content = serde_json::from_value(json!({
+            "type": "redacted_thinking",
+            "data": "EmwKAhgBEgy3va3pzix"
+        }))
+        .unwrap();
+
+        let reasoning = message::AssistantContent::try_from(content.clone()).unwrap();
+        let message::AssistantContent::Reasoning(Reasoning { ref signature, .. }) = reasoning
+        else {
+            panic!("Expected reasoning content");
+        };
+        assert_eq!(signature.as_deref(), Some("EmwKAhgBEgy3va3pzix"));
+
+        assert_eq!(Content::try_from(reasoning).unwrap(), content);
+    }
+
+    #[test]
+    fn test_server_tool_round_trip() {
+        let message: Message = serde_json::from_value(json!({
+            "role": "assistant",
+            "content": [
+                {
+                    "type": "server_tool_use",
+                    "id": "srvtoolu_01",
+                    "name": "web_search",
+                    "input": { "query": "rust async runtimes" }
+                },
+                {
+                    "type": "web_search_tool_result",
+                    "tool_use_id": "srvtoolu_01",
+                    "content": [{
+                        "type": "web_search_result",
+                        "url": "https://tokio.rs",
+                        "title": "Tokio",
+                        "encrypted_content": "EqgfCioIARgBIiQ3"
+                    }]
+                },
+                { "type": "text", "text": "Tokio is the most popular one." }
+            ]
+        }))
+        .unwrap();
+
+        let converted = message::Message::try_from(message.clone()).unwrap();
+        let message::Message::Assistant { ref content, .. } = converted else {
+            panic!("Expected an assistant message");
+        };
+        assert_eq!(content.len(), 2);
+
+        let message::AssistantContent::HostedToolCall(call) = content.first() else {
+            panic!("Expected a hosted tool call");
+        };
+        assert_eq!(call.tool, message::HostedToolKind::WebSearch);
+        assert_eq!(call.input.as_deref(), Some("rust async runtimes"));
+        assert_eq!(
+            call.sources,
+            vec![message::HostedToolSource::url(
+                "https://tokio.rs",
+                Some("Tokio".to_string())
+            )]
+        );
+
+        // The server tool blocks are sent back as they were received
+        assert_eq!(Message::try_from(converted).unwrap(), message);
+    }
+
+    #[test]
+    fn test_code_execution_result() {
+        let call = hosted_tool_call(
+            Content::ServerToolUse {
+                id: "srvtoolu_02".to_string(),
+                name: "code_execution".to_string(),
+                input: json!({ "code": "print(1 / 0)" }),
+            },
+            Some(Content::CodeExecutionToolResult {
+                tool_use_id: "srvtoolu_02".to_string(),
+                content: json!({
+                    "type": "code_execution_result",
+                    "stdout": "",
+                    "stderr": "ZeroDivisionError: division by zero",
+                    "return_code": 1
+                }),
+            }),
+        )
+        .unwrap();
+
+        assert_eq!(call.tool, message::HostedToolKind::CodeInterpreter);
+        assert_eq!(call.input.as_deref(), Some("print(1 / 0)"));
+        assert_eq!(
+            call.output.as_deref(),
+            Some("ZeroDivisionError: division by zero")
+        );
+        assert!(call.error.is_some());
+    }
+
+    #[test]
+    fn test_additional_params_override_sampling() {
+        use crate::testing::MockCompletionModel;
+
+        let request = completion::CompletionRequestBuilder::new(MockCompletionModel::new(), "Hi")
+            .max_tokens(1024)
+            .top_p(0.9)
+            .stop(["END"])
+            .additional_params(json!({ "top_p": 0.5, "stop_sequences": ["STOP"] }))
+            .build();
+
+        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: "claude-sonnet-4-5",
+            request,
+            prompt_caching: false,
+            citations: false,
+        })
+        .unwrap();
+        let body = serde_json::to_string(&request).unwrap();
+
+        assert_eq!(body.matches("\"top_p\"").count(), 1);
+        assert_eq!(body.matches("\"stop_sequences\"").count(), 1);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["top_p"], json!(0.5));
+        assert_eq!(body["stop_sequences"], json!(["STOP"]));
+    }
+
+    #[test]
+    fn test_hosted_tools_request() {
+        use crate::testing::MockCompletionModel;
+
+        let request = completion::CompletionRequestBuilder::new(MockCompletionModel::new(), "Hi")
+            .max_tokens(1024)
+            .hosted_tool(completion::HostedTool::web_search().with_allowed_domains(["docs.rs"]))
+            .hosted_tool(completion::HostedTool::code_interpreter())
+            .build();
+
+        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: "claude-sonnet-4-5",
+            request,
+            prompt_caching: false,
+            citations: false,
+        })
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["tools"],
+            json!([
+                {
+                    "type": "web_search_20250305",
+                    "name": "web_search",
+                    "allowed_domains": ["docs.rs"]
+                },
+                { "type": "code_execution_20250522", "name": "code_execution" }
+            ])
+        );
+        assert_eq!(request.betas(), vec![CODE_EXECUTION_BETA]);
+
+        let request = completion::CompletionRequestBuilder::new(MockCompletionModel::new(), "Hi")
+            .max_tokens(1024)
+            .hosted_tool(completion::HostedTool::file_search(["vs_1"]))
+            .build();
+        assert!(
+            AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+                model: "claude-sonnet-4-5",
+                request,
+                prompt_caching: false,
+                citations: false,
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_document_citations() {
+        use crate::testing::MockCompletionModel;
+
+        let request = completion::CompletionRequestBuilder::new(MockCompletionModel::new(), "Hi")
+            .max_tokens(1024)
+            .document(completion::Document {
+                id: "doc_0".to_string(),
+                text: "The grass is green.".to_string(),
+                additional_props: std::collections::HashMap::from([(
+                    "source".to_string(),
+                    "wiki".to_string(),
+                )]),
+            })
+            .build();
+
+        let plain = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: "claude-sonnet-4-5",
+            request: request.clone(),
+            prompt_caching: false,
+            citations: false,
+        })
+        .unwrap();
+        let plain = serde_json::to_value(&plain).unwrap();
+        assert_eq!(
+            plain["messages"][0]["content"][0],
+            json!({
+                "type": "text",
+                "text": request.documents[0].to_string()
+            })
+        );
+
+        let request = AnthropicCompletionRequest::try_from(AnthropicRequestParams {
+            model: "claude-sonnet-4-5",
+            request,
+            prompt_caching: false,
+            citations: true,
+        })
+        .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["messages"][0]["content"][0],
+            json!({
+                "type": "document",
+                "source": {
+                    "type": "text",
+                    "media_type": "text/plain",
+                    "data": "The grass is green."
+                },
+                "title": "doc_0",
+                "context": "source: wiki",
+                "citations": { "enabled": true }
+            })
+        );
+
+        let content: Content = serde_json::from_value(json!({
+            "type": "text",
+            "text": "the grass is green",
+            "citations": [{
+                "type": "char_location",
+                "cited_text": "The grass is green.",
+                "document_index": 0,
+                "document_title": "doc_0",
+                "start_char_index": 0,
+                "end_char_index": 19
+            }]
+        }))
+        .unwrap();
+
+        let message::AssistantContent::Text(text) =
+            message::AssistantContent::try_from(content).unwrap()
+        else {
+            panic!("Expected text content");
+        };
+        let citation = &text.citations[0];
+        assert_eq!(text.cited_span(citation), Some("the grass is green"));
+        assert_eq!(citation.document_ids().collect::<Vec<_>>(), vec!["doc_0"]);
+        assert_eq!(
+            citation.sources[0].quote.as_deref(),
+            Some("The grass is green.")
+        );
+    }
 }
@@ -1,4 +1,4 @@
-//! JSONL is currently not used, it might be used when Anthropic batches beta feature is used.
+//! JSONL decoder, used to stream the results of batches (see [crate::batch]).
 use crate::providers::anthropic::decoders::line::LineDecoder;
 use futures::{Stream, StreamExt};
 use serde::de::DeserializeOwned;
@@ -0,0 +1,189 @@
+//! Anthropic Files API implementation
+//!
+//! The Files API is in beta: the beta header is added to the requests of the Files API, and to the
+//! message requests that reference uploaded files, in addition to the betas of the client.
+//! See <https://docs.anthropic.com/en/docs/build-with-claude/files>.
+use serde::Deserialize;
+
+use super::{
+    Client,
+    client::with_betas,
+    completion::{Content, Message},
+};
+use crate::{
+    files::{FileObject, FileUpload, FilesClient, FilesError},
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+/// The beta of the Anthropic API enabling the Files API.
+pub const FILES_API_BETA: &str = "files-api-2025-04-14";
+
+/// The maximum number of files returned by a page of the list endpoint.
+const LIST_LIMIT: usize = 1000;
+
+/// The file metadata object of the Anthropic API, only the fields used to build a [FileObject].
+#[derive(Debug, Deserialize)]
+struct AnthropicFile {
+    id: String,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    mime_type: Option<String>,
+    #[serde(default)]
+    size_bytes: Option<u64>,
+}
+
+impl AnthropicFile {
+    fn from_raw(raw: serde_json::Value) -> Result<FileObject, FilesError> {
+        let file: Self = serde_json::from_value(raw.clone())?;
+
+        Ok(FileObject {
+            id: file.id,
+            filename: file.filename,
+            mime_type: file.mime_type,
+            bytes: file.size_bytes,
+            raw,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListFilesResponse {
+    data: Vec<serde_json::Value>,
+    #[serde(default)]
+    has_more: bool,
+    #[serde(default)]
+    last_id: Option<String>,
+}
+
+/// Add the Files API beta to the `anthropic-beta` header of the request, keeping the betas
+/// configured on the client.
+pub(crate) fn with_files_beta(req: http_client::Builder) -> http_client::Builder {
+    with_betas(req, &[FILES_API_BETA])
+}
+
+/// Whether the messages reference files uploaded with the Files API.
+pub(crate) fn references_files(messages: &[Message]) -> bool {
+    messages.iter().any(|message| {
+        message.content.iter().any(|content| {
+            matches!(
+                content,
+                Content::ImageFile { .. } | Content::DocumentFile { .. }
+            )
+        })
+    })
+}
+
+impl<H> FilesClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn upload_file(&self, file: FileUpload) -> Result<FileObject, FilesError> {
+        let req = with_files_beta(self.post("/v1/files")?);
+
+        AnthropicFile::from_raw(self.upload_multipart(req, &[], file).await?)
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileObject>, FilesError> {
+        let mut files = Vec::new();
+        let mut after_id: Option<String> = None;
+
+        loop {
+            let path = match &after_id {
+                Some(after_id) => format!("/v1/files?limit={LIST_LIMIT}&after_id={after_id}"),
+                None => format!("/v1/files?limit={LIST_LIMIT}"),
+            };
+
+            let req = with_files_beta(self.get(path)?)
+                .body(http_client::NoBody)
+                .map_err(http_client::Error::from)?;
+
+            let page: ListFilesResponse = self.files_response(req).await?;
+            for raw in page.data {
+                files.push(AnthropicFile::from_raw(raw)?);
+            }
+
+            match page.last_id {
+                Some(last_id) if page.has_more => after_id = Some(last_id),
+                _ => return Ok(files),
+            }
+        }
+    }
+
+    async fn retrieve_file(&self, id: &str) -> Result<FileObject, FilesError> {
+        let req = with_files_beta(self.get(format!("/v1/files/{id}"))?)
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        AnthropicFile::from_raw(self.files_response(req).await?)
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<(), FilesError> {
+        let req = with_files_beta(self.delete(format!("/v1/files/{id}"))?)
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        let _: serde_json::Value = self.files_response(req).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OneOrMany, message};
+
+    #[test]
+    fn test_with_files_beta() {
+        let req = with_files_beta(http::Request::post("https://api.anthropic.com/v1/files"));
+        assert_eq!(
+            req.headers_ref().unwrap().get("anthropic-beta").unwrap(),
+            FILES_API_BETA
+        );
+
+        let req = with_files_beta(
+            http::Request::post("https://api.anthropic.com/v1/messages")
+                .header("anthropic-beta", "prompt-caching-2024-07-31"),
+        );
+        let req = with_files_beta(req);
+        assert_eq!(
+            req.headers_ref().unwrap().get("anthropic-beta").unwrap(),
+            "prompt-caching-2024-07-31,files-api-2025-04-14"
+        );
+    }
+
+    #[test]
+    fn test_file_content_serialization() {
+        let message: Message = message::Message::User {
+            content: OneOrMany::many(vec![
+                message::UserContent::document_file_id("file_011CNha8iCJcU1wXNR6q4V8w", None),
+                message::UserContent::image_file_id("file_011CPMxVD3fHLUhvTqtsQA5w", None),
+                message::UserContent::text("Describe these files"),
+            ])
+            .unwrap(),
+        }
+        .try_into()
+        .unwrap();
+
+        assert!(references_files(std::slice::from_ref(&message)));
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            serde_json::json!({
+                "role": "user",
+                "content": [
+                    {
+                        "type": "document",
+                        "source": { "type": "file", "file_id": "file_011CNha8iCJcU1wXNR6q4V8w" }
+                    },
+                    {
+                        "type": "image",
+                        "source": { "type": "file", "file_id": "file_011CPMxVD3fHLUhvTqtsQA5w" }
+                    },
+                    { "type": "text", "text": "Describe these files" }
+                ]
+            })
+        );
+    }
+}
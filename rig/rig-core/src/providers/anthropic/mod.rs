@@ -28,9 +28,11 @@
 //! let sonnet = client.completion_model(anthropic::CLAUDE_3_5_SONNET);
 //! ```
 
+pub mod batch;
 pub mod client;
 pub mod completion;
 pub mod decoders;
+pub mod files;
 pub mod streaming;
 
 pub use client::{Client, ClientBuilder, OAuthClient, OAuthClientBuilder};
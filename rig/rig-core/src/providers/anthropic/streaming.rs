@@ -5,15 +5,20 @@ use serde_json::json;
 use tracing::{Level, enabled, info_span};
 use tracing_futures::Instrument;
 
+use super::client::with_betas;
 use super::completion::{
-    CompletionModel, Content, Message, OAuthCompletionModel, SystemContent, ToolChoice,
-    ToolDefinition, Usage, apply_cache_control,
+    CompletionModel, Content, OAuthCompletionModel, SamplingParams, SystemContent, TextCitation,
+    ThinkingConfig, ToolChoice, Usage, apply_cache_control, hosted_tool_call, map_stop_reason,
+    request_betas, request_messages, request_tools,
 };
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::http_client::{self, HttpClientExt};
 use crate::json_utils::merge_inplace;
-use crate::streaming::{self, RawStreamingChoice, RawStreamingToolCall, StreamingResult};
+use crate::message;
+use crate::streaming::{
+    self, RawStreamingChoice, RawStreamingToolCall, ResponseMetadata, StreamingResult,
+};
 use crate::telemetry::SpanCombinator;
 
 #[derive(Debug, Deserialize)]
@@ -39,10 +44,21 @@ pub enum StreamingEvent {
     },
     MessageStop,
     Ping,
+    /// An error sent in the middle of the stream (e.g. when the API is overloaded)
+    Error {
+        error: StreamingApiError,
+    },
     #[serde(other)]
     Unknown,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct StreamingApiError {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub message: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct MessageStart {
     pub id: String,
@@ -61,6 +77,7 @@ pub enum ContentDelta {
     InputJsonDelta { partial_json: String },
     ThinkingDelta { thinking: String },
     SignatureDelta { signature: String },
+    CitationsDelta { citation: TextCitation },
 }
 
 #[derive(Debug, Deserialize)]
@@ -94,6 +111,20 @@ struct ToolCallState {
     input_json: String,
 }
 
+#[derive(Default)]
+struct ServerToolState {
+    current: Option<ToolCallState>,
+    tool_uses: Vec<Content>,
+}
+
+/// The citations of the current text block, yielded once the block is complete.
+#[derive(Default)]
+struct CitationState {
+    /// The offset of the current text block in the text streamed so far
+    start: usize,
+    citations: Vec<TextCitation>,
+}
+
 #[derive(Default)]
 struct ThinkingState {
     thinking: String,
@@ -123,7 +154,7 @@ where
 {
     pub(crate) async fn stream(
         &self,
-        completion_request: CompletionRequest,
+        mut completion_request: CompletionRequest,
     ) -> Result<streaming::StreamingCompletionResponse<StreamingCompletionResponse>, CompletionError>
     {
         let span = if tracing::Span::current().is_disabled() {
@@ -154,16 +185,14 @@ where
             ));
         };
 
-        let mut full_history = vec![];
-        if let Some(docs) = completion_request.normalized_documents() {
-            full_history.push(docs);
-        }
-        full_history.extend(completion_request.chat_history);
+        let thinking = ThinkingConfig::from_request(&mut completion_request, max_tokens)?;
+        completion_request.check_logprobs_unsupported("Anthropic")?;
 
-        let mut messages = full_history
-            .into_iter()
-            .map(Message::try_from)
-            .collect::<Result<Vec<Message>, _>>()?;
+        let mut messages = request_messages(
+            completion_request.documents,
+            completion_request.chat_history,
+            self.citations,
+        )?;
 
         // Convert system prompt to array format for cache_control support
         let mut system: Vec<SystemContent> =
@@ -201,22 +230,21 @@ where
             merge_inplace(&mut body, json!({ "temperature": temperature }));
         }
 
+        let sampling = SamplingParams::try_from(completion_request.sampling.clone())?;
+        merge_inplace(&mut body, serde_json::to_value(sampling)?);
+
+        if let Some(thinking) = thinking {
+            merge_inplace(&mut body, json!({ "thinking": thinking }));
+        }
+
         if !completion_request.tools.is_empty() {
-            merge_inplace(
-                &mut body,
-                json!({
-                    "tools": completion_request
-                        .tools
-                        .into_iter()
-                        .map(|tool| ToolDefinition {
-                            name: tool.name,
-                            description: Some(tool.description),
-                            input_schema: tool.parameters,
-                        })
-                        .collect::<Vec<_>>(),
-                    "tool_choice": ToolChoice::Auto,
-                }),
-            );
+            merge_inplace(&mut body, json!({ "tool_choice": ToolChoice::Auto }));
+        }
+
+        let tools = request_tools(completion_request.tools, &completion_request.hosted_tools)?;
+        let betas = request_betas(&messages, &tools);
+        if !tools.is_empty() {
+            merge_inplace(&mut body, json!({ "tools": tools }));
         }
 
         if let Some(ref params) = completion_request.additional_params {
@@ -233,9 +261,7 @@ where
 
         let body: Vec<u8> = serde_json::to_vec(&body)?;
 
-        let req = self
-            .client
-            .post("/v1/messages")?
+        let req = with_betas(self.client.post("/v1/messages")?, &betas)
             .body(body)
             .map_err(http_client::Error::Protocol)?;
 
@@ -245,6 +271,8 @@ where
         let stream: StreamingResult<StreamingCompletionResponse> = Box::pin(stream! {
             let mut current_tool_call: Option<ToolCallState> = None;
             let mut current_thinking: Option<ThinkingState> = None;
+            let mut server_tool_state = ServerToolState::default();
+            let mut citation_state = CitationState::default();
             let mut sse_stream = Box::pin(stream);
             let mut input_tokens = 0;
             let mut final_usage = None;
@@ -265,9 +293,19 @@ where
                                         let span = tracing::Span::current();
                                         span.record("gen_ai.response.id", &message.id);
                                         span.record("gen_ai.response.model_name", &message.model);
+
+                                        yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::new(
+                                            Some(message.id.clone()),
+                                            Some(message.model.clone()),
+                                            None,
+                                        )));
                                     },
                                     StreamingEvent::MessageDelta { delta, usage } => {
-                                        if delta.stop_reason.is_some() {
+                                        if let Some(stop_reason) = &delta.stop_reason {
+                                            yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::finish_reason(
+                                                map_stop_reason(stop_reason),
+                                            )));
+
                                             let usage = PartialUsage {
                                                  output_tokens: usage.output_tokens,
                                                  input_tokens: Some(input_tokens.try_into().expect("Failed to convert input_tokens to usize")),
@@ -279,9 +317,26 @@ where
                                             break;
                                         }
                                     }
+                                    StreamingEvent::Error { error } => {
+                                        yield Err(CompletionError::ProviderError(format!(
+                                            "{}: {}",
+                                            error.r#type, error.message
+                                        )));
+                                        break;
+                                    }
                                     _ => {}
                                 }
 
+                                if let Some(result) = handle_server_tool_event(&event, &mut server_tool_state) {
+                                    yield result;
+                                    continue;
+                                }
+
+                                if let Some(result) = handle_citation_event(&event, &mut citation_state, text_content.len()) {
+                                    yield result;
+                                    continue;
+                                }
+
                                 if let Some(result) = handle_event(&event, &mut current_tool_call, &mut current_thinking) {
                                     if let Ok(RawStreamingChoice::Message(ref text)) = result {
                                         text_content += text;
@@ -358,6 +413,9 @@ where
             ));
         };
 
+        let thinking = ThinkingConfig::from_request(&mut completion_request, max_tokens)?;
+        completion_request.check_logprobs_unsupported("Anthropic")?;
+
         // Claude Code OAuth: Prepend original system prompt to first user message,
         // then replace system prompt with hardcoded Claude Code instruction
         let original_preamble = completion_request.preamble.take();
@@ -375,16 +433,11 @@ where
             }
         }
 
-        let mut full_history = vec![];
-        if let Some(docs) = completion_request.normalized_documents() {
-            full_history.push(docs);
-        }
-        full_history.extend(completion_request.chat_history);
-
-        let mut messages = full_history
-            .into_iter()
-            .map(Message::try_from)
-            .collect::<Result<Vec<Message>, _>>()?;
+        let mut messages = request_messages(
+            completion_request.documents,
+            completion_request.chat_history,
+            self.citations,
+        )?;
 
         // Use hardcoded Claude Code instruction as the system prompt
         let mut system: Vec<SystemContent> = vec![SystemContent::Text {
@@ -411,22 +464,21 @@ where
             merge_inplace(&mut body, json!({ "temperature": temperature }));
         }
 
+        let sampling = SamplingParams::try_from(completion_request.sampling.clone())?;
+        merge_inplace(&mut body, serde_json::to_value(sampling)?);
+
+        if let Some(thinking) = thinking {
+            merge_inplace(&mut body, json!({ "thinking": thinking }));
+        }
+
         if !completion_request.tools.is_empty() {
-            merge_inplace(
-                &mut body,
-                json!({
-                    "tools": completion_request
-                        .tools
-                        .into_iter()
-                        .map(|tool| ToolDefinition {
-                            name: tool.name,
-                            description: Some(tool.description),
-                            input_schema: tool.parameters,
-                        })
-                        .collect::<Vec<_>>(),
-                    "tool_choice": ToolChoice::Auto,
-                }),
-            );
+            merge_inplace(&mut body, json!({ "tool_choice": ToolChoice::Auto }));
+        }
+
+        let tools = request_tools(completion_request.tools, &completion_request.hosted_tools)?;
+        let betas = request_betas(&messages, &tools);
+        if !tools.is_empty() {
+            merge_inplace(&mut body, json!({ "tools": tools }));
         }
 
         if let Some(ref params) = completion_request.additional_params {
@@ -443,9 +495,7 @@ where
 
         let body: Vec<u8> = serde_json::to_vec(&body)?;
 
-        let req = self
-            .client
-            .post("/v1/messages")?
+        let req = with_betas(self.client.post("/v1/messages")?, &betas)
             .body(body)
             .map_err(http_client::Error::Protocol)?;
 
@@ -454,6 +504,8 @@ where
         let stream: StreamingResult<StreamingCompletionResponse> = Box::pin(stream! {
             let mut current_tool_call: Option<ToolCallState> = None;
             let mut current_thinking: Option<ThinkingState> = None;
+            let mut server_tool_state = ServerToolState::default();
+            let mut citation_state = CitationState::default();
             let mut sse_stream = Box::pin(stream);
             let mut input_tokens = 0;
             let mut final_usage = None;
@@ -473,9 +525,19 @@ where
                                         let span = tracing::Span::current();
                                         span.record("gen_ai.response.id", &message.id);
                                         span.record("gen_ai.response.model_name", &message.model);
+
+                                        yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::new(
+                                            Some(message.id.clone()),
+                                            Some(message.model.clone()),
+                                            None,
+                                        )));
                                     },
                                     StreamingEvent::MessageDelta { delta, usage } => {
-                                        if delta.stop_reason.is_some() {
+                                        if let Some(stop_reason) = &delta.stop_reason {
+                                            yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::finish_reason(
+                                                map_stop_reason(stop_reason),
+                                            )));
+
                                             let usage = PartialUsage {
                                                  output_tokens: usage.output_tokens,
                                                  input_tokens: Some(input_tokens.try_into().expect("Failed to convert input_tokens to usize")),
@@ -487,9 +549,26 @@ where
                                             break;
                                         }
                                     }
+                                    StreamingEvent::Error { error } => {
+                                        yield Err(CompletionError::ProviderError(format!(
+                                            "{}: {}",
+                                            error.r#type, error.message
+                                        )));
+                                        break;
+                                    }
                                     _ => {}
                                 }
 
+                                if let Some(result) = handle_server_tool_event(&event, &mut server_tool_state) {
+                                    yield result;
+                                    continue;
+                                }
+
+                                if let Some(result) = handle_citation_event(&event, &mut citation_state, text_content.len()) {
+                                    yield result;
+                                    continue;
+                                }
+
                                 if let Some(result) = handle_event(&event, &mut current_tool_call, &mut current_thinking) {
                                     if let Ok(RawStreamingChoice::Message(ref text)) = result {
                                         text_content += text;
@@ -562,6 +641,8 @@ fn handle_event(
                     reasoning: thinking.clone(),
                 }))
             }
+            // Citations are handled by [handle_citation_event]
+            ContentDelta::CitationsDelta { .. } => None,
             ContentDelta::SignatureDelta { signature } => {
                 if current_thinking.is_none() {
                     *current_thinking = Some(ThinkingState::default());
@@ -588,6 +669,12 @@ fn handle_event(
                 *current_thinking = Some(ThinkingState::default());
                 None
             }
+            // Redacted thinking is sent in a single block, which has to be sent back as is
+            Content::RedactedThinking { data } => Some(Ok(RawStreamingChoice::Reasoning {
+                id: None,
+                reasoning: String::new(),
+                signature: Some(data.clone()),
+            })),
             // Handle other content types - they don't need special handling
             _ => None,
         },
@@ -629,10 +716,110 @@ fn handle_event(
         | StreamingEvent::MessageDelta { .. }
         | StreamingEvent::MessageStop
         | StreamingEvent::Ping
+        | StreamingEvent::Error { .. }
         | StreamingEvent::Unknown => None,
     }
 }
 
+/// Handles the citations of the text blocks, which are yielded with the span of their block in the
+/// text streamed so far once the block is complete.
+fn handle_citation_event(
+    event: &StreamingEvent,
+    state: &mut CitationState,
+    text_len: usize,
+) -> Option<Result<RawStreamingChoice<StreamingCompletionResponse>, CompletionError>> {
+    match event {
+        StreamingEvent::ContentBlockStart {
+            content_block: Content::Text { citations, .. },
+            ..
+        } => {
+            state.start = text_len;
+            state.citations = citations.clone().unwrap_or_default();
+            None
+        }
+        StreamingEvent::ContentBlockDelta {
+            delta: ContentDelta::CitationsDelta { citation },
+            ..
+        } => {
+            state.citations.push(citation.clone());
+            None
+        }
+        StreamingEvent::ContentBlockStop { .. } if !state.citations.is_empty() => {
+            let sources = std::mem::take(&mut state.citations)
+                .into_iter()
+                .map(Into::into)
+                .collect();
+
+            Some(Ok(RawStreamingChoice::Citation(message::Citation::new(
+                Some(state.start..text_len),
+                sources,
+            ))))
+        }
+        _ => None,
+    }
+}
+
+/// Handles the events of the server tool calls, which are yielded as hosted tool calls once their
+/// result is received.
+fn handle_server_tool_event(
+    event: &StreamingEvent,
+    state: &mut ServerToolState,
+) -> Option<Result<RawStreamingChoice<StreamingCompletionResponse>, CompletionError>> {
+    match event {
+        StreamingEvent::ContentBlockStart {
+            content_block: Content::ServerToolUse { id, name, .. },
+            ..
+        } => {
+            state.current = Some(ToolCallState {
+                name: name.clone(),
+                id: id.clone(),
+                input_json: String::new(),
+            });
+            None
+        }
+        StreamingEvent::ContentBlockDelta {
+            delta: ContentDelta::InputJsonDelta { partial_json },
+            ..
+        } => {
+            state.current.as_mut()?.input_json.push_str(partial_json);
+            None
+        }
+        StreamingEvent::ContentBlockStop { .. } => {
+            let tool_use = state.current.take()?;
+            let json_str = if tool_use.input_json.is_empty() {
+                "{}"
+            } else {
+                &tool_use.input_json
+            };
+            match serde_json::from_str(json_str) {
+                Ok(input) => {
+                    state.tool_uses.push(Content::ServerToolUse {
+                        id: tool_use.id,
+                        name: tool_use.name,
+                        input,
+                    });
+                    None
+                }
+                Err(e) => Some(Err(CompletionError::from(e))),
+            }
+        }
+        StreamingEvent::ContentBlockStart { content_block, .. } => {
+            let id = content_block.server_tool_use_id()?;
+            let index = state.tool_uses.iter().position(
+                |tool_use| matches!(tool_use, Content::ServerToolUse { id: use_id, .. } if use_id == id),
+            )?;
+            let tool_use = state.tool_uses.remove(index);
+
+            Some(
+                hosted_tool_call(tool_use, Some(content_block.clone()))
+                    .map(RawStreamingChoice::HostedToolCall)
+                    .map_err(CompletionError::from),
+            )
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -933,4 +1120,34 @@ mod tests {
         // Tool call state should be taken
         assert!(tool_call_state.is_none());
     }
+
+    #[test]
+    fn test_handle_server_tool_events() {
+        let events: Vec<StreamingEvent> = [
+            r#"{"type": "content_block_start", "index": 0, "content_block": {"type": "server_tool_use", "id": "srvtoolu_01", "name": "web_fetch", "input": {}}}"#,
+            r#"{"type": "content_block_delta", "index": 0, "delta": {"type": "input_json_delta", "partial_json": "{\"url\": \"https://rig.rs\"}"}}"#,
+            r#"{"type": "content_block_stop", "index": 0}"#,
+            r#"{"type": "content_block_start", "index": 1, "content_block": {"type": "web_fetch_tool_result", "tool_use_id": "srvtoolu_01", "content": {"type": "web_fetch_result", "url": "https://rig.rs", "content": {"type": "document", "source": {"type": "text", "media_type": "text/plain", "data": "Rig"}, "title": "Rig"}}}}"#,
+        ]
+        .iter()
+        .map(|event| serde_json::from_str(event).unwrap())
+        .collect();
+
+        let mut state = ServerToolState::default();
+        let mut results: Vec<_> = events
+            .iter()
+            .filter_map(|event| handle_server_tool_event(event, &mut state))
+            .collect();
+
+        assert_eq!(results.len(), 1);
+        let Ok(RawStreamingChoice::HostedToolCall(call)) = results.remove(0) else {
+            panic!("Expected a hosted tool call");
+        };
+        assert_eq!(call.id, "srvtoolu_01");
+        assert_eq!(call.tool, crate::message::HostedToolKind::UrlContext);
+        assert_eq!(call.input.as_deref(), Some("https://rig.rs"));
+        assert_eq!(call.sources[0].title.as_deref(), Some("Rig"));
+        assert_eq!(call.sources[0].text.as_deref(), Some("Rig"));
+        assert!(state.tool_uses.is_empty());
+    }
 }
@@ -9,6 +9,8 @@
 //! let gpt4o = client.completion_model(azure::GPT_4O);
 //! ```
 
+use crate::completion::SamplingParam;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use std::fmt::Debug;
 
 use super::openai::{TranscriptionResponse, send_compatible_streaming_request};
@@ -244,11 +246,16 @@ where
         self.post(&url)
     }
 
-    fn post_transcription(&self, deployment_id: &str) -> http_client::Result<http_client::Builder> {
+    fn post_transcription(
+        &self,
+        deployment_id: &str,
+        path: &str,
+    ) -> http_client::Result<http_client::Builder> {
         let url = format!(
-            "{}/openai/deployments/{}/audio/translations?api-version={}",
+            "{}/openai/deployments/{}{}?api-version={}",
             self.endpoint(),
             deployment_id.trim_start_matches('/'),
+            path,
             self.api_version()
         );
 
@@ -541,12 +548,26 @@ pub const GPT_35_TURBO_INSTRUCT: &str = "gpt-3.5-turbo-instruct";
 /// `gpt-3.5-turbo-16k` completion model
 pub const GPT_35_TURBO_16K: &str = "gpt-3.5-turbo-16k";
 
+/// The sampling parameters supported by the Azure OpenAI API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct AzureOpenAICompletionRequest {
     model: String,
     pub messages: Vec<openai::Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<openai::ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -559,6 +580,7 @@ impl TryFrom<(&str, CompletionRequest)> for AzureOpenAICompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("Azure OpenAI")?;
         //FIXME: Must fix!
         if req.tool_choice.is_some() {
             tracing::warn!(
@@ -598,6 +620,12 @@ impl TryFrom<(&str, CompletionRequest)> for AzureOpenAICompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "Azure OpenAI",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
@@ -804,10 +832,16 @@ where
         transcription::TranscriptionResponse<Self::Response>,
         transcription::TranscriptionError,
     > {
-        let data = request.data;
+        let path = openai::transcription::transcription_path(&request);
 
-        let mut body =
-            MultipartForm::new().part(Part::bytes("file", data).filename(request.filename.clone()));
+        let mut body = openai::transcription::with_output_options(MultipartForm::new(), &request)
+            .part(Part::bytes("file", request.data).filename(request.filename.clone()));
+
+        if let Some(language) = request.language
+            && !request.translate
+        {
+            body = body.text("language", language);
+        }
 
         if let Some(prompt) = request.prompt {
             body = body.text("prompt", prompt.clone());
@@ -828,7 +862,7 @@ where
 
         let req = self
             .client
-            .post_transcription(&self.model)?
+            .post_transcription(&self.model, path)?
             .body(body)
             .map_err(|e| TranscriptionError::HttpError(e.into()))?;
 
@@ -1049,7 +1083,11 @@ mod azure_tests {
                 max_tokens: Some(100),
                 temperature: Some(0.0),
                 tools: vec![],
+                hosted_tools: Vec::new(),
                 tool_choice: None,
+                sampling: Default::default(),
+                logprobs: None,
+                reasoning: None,
                 additional_params: None,
             })
             .await
@@ -1,8 +1,8 @@
 use crate::{
     Embed,
     client::{
-        self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
-        ProviderClient,
+        self, BearerAuth, Capabilities, Capable, DebugExt, Modality, ModelInfo, ModelListingClient,
+        ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
     },
     embeddings::EmbeddingsBuilder,
     http_client::{self, HttpClientExt},
@@ -128,3 +128,112 @@ where
         EmbeddingModel::new(self.clone(), model, input_type, ndims)
     }
 }
+
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    models: Vec<Model>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    name: String,
+    #[serde(default)]
+    endpoints: Vec<String>,
+    #[serde(default)]
+    context_length: Option<u64>,
+    #[serde(default)]
+    features: Vec<String>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        let has = |values: &[String], value: &str| values.iter().any(|v| v == value);
+        let is_embedding = has(&model.endpoints, "embed");
+
+        let mut input_modalities = vec![Modality::Text];
+        if has(&model.features, "vision") {
+            input_modalities.push(Modality::Image);
+        }
+
+        Self {
+            context_window: model.context_length,
+            input_modalities,
+            output_modalities: if has(&model.endpoints, "chat") {
+                vec![Modality::Text]
+            } else {
+                vec![]
+            },
+            supports_tools: Some(has(&model.features, "tools") || has(&model.features, "tool_use")),
+            is_embedding: Some(is_embedding),
+            embedding_dimensions: super::model_dimensions_from_identifier(&model.name),
+            ..Self::new(model.name)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let mut models = vec![];
+        let mut path = "/v1/models?page_size=1000".to_string();
+
+        loop {
+            let page: ModelList = self.get_models(&path).await?;
+            models.extend(page.models.into_iter().map(ModelInfo::from));
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => {
+                    path = format!("/v1/models?page_size=1000&page_token={token}");
+                }
+                _ => break,
+            }
+        }
+
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let page: ModelList = serde_json::from_value(serde_json::json!({
+            "models": [
+                {
+                    "name": "command-a-03-2025",
+                    "endpoints": ["generate", "chat", "summarize"],
+                    "finetuned": false,
+                    "context_length": 256000,
+                    "features": ["json_mode", "json_schema", "safety_modes", "strict_tools", "tools"]
+                },
+                {
+                    "name": "embed-english-v3.0",
+                    "endpoints": ["embed"],
+                    "context_length": 512
+                }
+            ]
+        }))
+        .unwrap();
+
+        let models = page
+            .models
+            .into_iter()
+            .map(ModelInfo::from)
+            .collect::<Vec<_>>();
+        assert_eq!(models[0].context_window, Some(256_000));
+        assert_eq!(models[0].supports_tools, Some(true));
+        assert_eq!(models[0].output_modalities, vec![Modality::Text]);
+        assert_eq!(models[1].is_embedding, Some(true));
+        assert_eq!(models[1].embedding_dimensions, Some(1_024));
+    }
+}
@@ -91,6 +91,18 @@ pub enum FinishReason {
     ToolCall,
 }
 
+impl From<&FinishReason> for completion::FinishReason {
+    fn from(reason: &FinishReason) -> Self {
+        match reason {
+            FinishReason::MaxTokens => completion::FinishReason::Length,
+            FinishReason::StopSequence => completion::FinishReason::StopSequence,
+            FinishReason::Complete => completion::FinishReason::Stop,
+            FinishReason::Error => completion::FinishReason::Other("ERROR".to_string()),
+            FinishReason::ToolCall => completion::FinishReason::ToolCalls,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Usage {
     #[serde(default)]
@@ -137,7 +149,8 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
     type Error = CompletionError;
 
     fn try_from(response: CompletionResponse) -> Result<Self, Self::Error> {
-        let (content, _, tool_calls) = response.message();
+        let (content, citations, tool_calls) = response.message();
+        let mut citations = Some(citations);
 
         let model_response = if !tool_calls.is_empty() {
             OneOrMany::many(
@@ -154,7 +167,13 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             .expect("We have atleast 1 tool call in this if block")
         } else {
             OneOrMany::many(content.into_iter().map(|content| match content {
-                AssistantContent::Text { text } => completion::AssistantContent::text(text),
+                // Cohere reports citations against the first text block of the message
+                AssistantContent::Text { text } => match citations.take() {
+                    Some(citations) => {
+                        completion::AssistantContent::Text(cited_text(text, &citations))
+                    }
+                    None => completion::AssistantContent::text(text),
+                },
                 AssistantContent::Thinking { thinking } => {
                     completion::AssistantContent::Reasoning(Reasoning {
                         id: None,
@@ -189,6 +208,10 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice: OneOrMany::many(model_response).expect("There is atleast one content"),
             usage,
+            finish_reason: Some((&response.finish_reason).into()),
+            id: Some(response.id.clone()),
+            model: None,
+            logprobs: None,
             raw_response: response,
         })
     }
@@ -340,6 +363,69 @@ pub struct Citation {
     pub sources: Vec<Source>,
 }
 
+impl Citation {
+    /// Converts this citation into a [`message::Citation`] over `text`.
+    /// Cohere reports offsets in characters, which are mapped to byte offsets here.
+    /// Plan citations and citations without document sources are skipped.
+    pub(crate) fn to_message_citation(&self, text: &str) -> Option<message::Citation> {
+        if matches!(self.citation_type, Some(CitationType::Plan)) {
+            return None;
+        }
+
+        let sources = self
+            .sources
+            .iter()
+            .filter_map(|source| match source {
+                Source::Document { id, document } => {
+                    let field = |key: &str| {
+                        document
+                            .as_ref()
+                            .and_then(|document| document.get(key))
+                            .and_then(|value| value.as_str())
+                            .map(str::to_string)
+                    };
+
+                    Some(message::CitationSource {
+                        document_id: id.clone().or_else(|| field("id")),
+                        title: field("title"),
+                        url: field("url"),
+                        quote: self.text.clone(),
+                    })
+                }
+                Source::Tool { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        if sources.is_empty() {
+            return None;
+        }
+
+        let span = match (self.start, self.end) {
+            (Some(start), Some(end)) => {
+                Some(char_to_byte_offset(text, start)..char_to_byte_offset(text, end))
+            }
+            _ => None,
+        };
+
+        Some(message::Citation::new(span, sources))
+    }
+}
+
+fn char_to_byte_offset(text: &str, offset: u32) -> usize {
+    text.char_indices()
+        .nth(offset as usize)
+        .map_or(text.len(), |(index, _)| index)
+}
+
+fn cited_text(text: String, citations: &[Citation]) -> message::Text {
+    let citations = citations
+        .iter()
+        .filter_map(|citation| citation.to_message_citation(&text))
+        .collect();
+
+    message::Text::from(text).with_citations(citations)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Source {
@@ -368,7 +454,7 @@ impl TryFrom<message::Message> for Vec<Message> {
             message::Message::User { content } => content
                 .into_iter()
                 .map(|content| match content {
-                    message::UserContent::Text(message::Text { text }) => Ok(Message::User {
+                    message::UserContent::Text(message::Text { text, .. }) => Ok(Message::User {
                         content: OneOrMany::one(UserContent::Text { text }),
                     }),
                     message::UserContent::ToolResult(message::ToolResult {
@@ -395,7 +481,7 @@ impl TryFrom<message::Message> for Vec<Message> {
 
                 for content in content.into_iter() {
                     match content {
-                        message::AssistantContent::Text(message::Text { text }) => {
+                        message::AssistantContent::Text(message::Text { text, .. }) => {
                             text_content.push(AssistantContent::Text { text });
                         }
                         message::AssistantContent::ToolCall(message::ToolCall {
@@ -424,6 +510,8 @@ impl TryFrom<message::Message> for Vec<Message> {
                                 "Cohere currently doesn't support images.".to_owned(),
                             ));
                         }
+                        // Hosted tool calls can only be sent back to the provider that made them
+                        message::AssistantContent::HostedToolCall(_) => {}
                     }
                 }
 
@@ -446,7 +534,7 @@ impl TryFrom<Message> for message::Message {
             Message::User { content } => Ok(message::Message::User {
                 content: content.map(|content| match content {
                     UserContent::Text { text } => {
-                        message::UserContent::Text(message::Text { text })
+                        message::UserContent::Text(message::Text::from(text))
                     }
                     UserContent::ImageUrl { image_url } => {
                         message::UserContent::image_url(image_url.url, None, None)
@@ -531,9 +619,12 @@ pub struct CompletionModel<T = reqwest::Client> {
 pub(super) struct CohereCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
-    documents: Vec<crate::completion::Document>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    documents: Vec<Document>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<Tool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -542,22 +633,73 @@ pub(super) struct CohereCompletionRequest {
     pub additional_params: Option<serde_json::Value>,
 }
 
+/// The sampling parameters supported by the Cohere chat API.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SamplingParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    k: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f64>,
+}
+
+impl TryFrom<completion::SamplingParams> for SamplingParams {
+    type Error = CompletionError;
+
+    fn try_from(sampling: completion::SamplingParams) -> Result<Self, Self::Error> {
+        use completion::SamplingParam;
+
+        sampling.check_supported(
+            "Cohere",
+            &[
+                SamplingParam::TopP,
+                SamplingParam::TopK,
+                SamplingParam::Stop,
+                SamplingParam::Seed,
+                SamplingParam::PresencePenalty,
+                SamplingParam::FrequencyPenalty,
+            ],
+        )?;
+
+        Ok(Self {
+            p: sampling.top_p,
+            k: sampling.top_k,
+            stop_sequences: sampling.stop,
+            seed: sampling.seed,
+            presence_penalty: sampling.presence_penalty,
+            frequency_penalty: sampling.frequency_penalty,
+        })
+    }
+}
+
 impl TryFrom<(&str, CompletionRequest)> for CohereCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
-        let mut partial_history = vec![];
-        if let Some(docs) = req.normalized_documents() {
-            partial_history.push(docs);
-        }
-        partial_history.extend(req.chat_history);
+        let sampling = req.sampling_unless_overridden(|param| match param {
+            completion::SamplingParam::TopP => "p",
+            completion::SamplingParam::TopK => "k",
+            completion::SamplingParam::Stop => "stop_sequences",
+            param => param.name(),
+        });
 
+        req.check_logprobs_unsupported("Cohere")?;
+        req.check_hosted_tools_unsupported("Cohere")?;
         let mut full_history: Vec<Message> = req.preamble.map_or_else(Vec::new, |preamble| {
             vec![Message::System { content: preamble }]
         });
 
+        // Documents are sent through the dedicated `documents` field so that Cohere can cite
+        // them by id, rather than being inlined into the chat history.
         full_history.extend(
-            partial_history
+            req.chat_history
                 .into_iter()
                 .map(message::Message::try_into)
                 .collect::<Result<Vec<Vec<Message>>, _>>()?
@@ -581,8 +723,9 @@ impl TryFrom<(&str, CompletionRequest)> for CohereCompletionRequest {
         Ok(Self {
             model: model.to_string(),
             messages: full_history,
-            documents: req.documents,
+            documents: req.documents.into_iter().map(Document::from).collect(),
             temperature: req.temperature,
+            sampling: sampling.try_into()?,
             tools: req.tools.into_iter().map(Tool::from).collect::<Vec<_>>(),
             tool_choice,
             additional_params: req.additional_params,
@@ -775,13 +918,79 @@ mod tests {
         assert_eq!(arguments, serde_json::json!({"x": 5, "y": 2}));
     }
 
+    #[test]
+    fn test_response_citations_are_attached_to_text() {
+        let json_data = serde_json::json!({
+            "id": "abc123",
+            "finish_reason": "COMPLETE",
+            "message": {
+                "role": "assistant",
+                "content": [{ "type": "text", "text": "Café owls hoot at night." }],
+                "citations": [{
+                    "start": 5,
+                    "end": 23,
+                    "text": "owls hoot at night",
+                    "type": "TEXT_CONTENT",
+                    "sources": [{
+                        "type": "document",
+                        "id": "doc_0",
+                        "document": { "id": "doc_0", "title": "Owls", "text": "..." }
+                    }]
+                }]
+            }
+        });
+
+        let response: CompletionResponse = serde_json::from_value(json_data).unwrap();
+        let response: completion::CompletionResponse<CompletionResponse> =
+            response.try_into().unwrap();
+
+        let completion::AssistantContent::Text(text) = response.choice.first() else {
+            panic!("Expected text content");
+        };
+
+        assert_eq!(text.citations.len(), 1);
+        let citation = &text.citations[0];
+        assert_eq!(text.cited_span(citation), Some("owls hoot at night"));
+        assert_eq!(citation.document_ids().collect::<Vec<_>>(), vec!["doc_0"]);
+        assert_eq!(citation.sources[0].title.as_deref(), Some("Owls"));
+    }
+
+    #[test]
+    fn test_documents_are_sent_with_ids() {
+        let request = completion::CompletionRequest {
+            documents: vec![completion::Document {
+                id: "doc_0".to_string(),
+                text: "Owls hoot at night.".to_string(),
+                additional_props: HashMap::new(),
+            }],
+            preamble: None,
+            chat_history: OneOrMany::one("Do owls hoot?".into()),
+            tools: vec![],
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: None,
+            additional_params: None,
+        };
+
+        let request = CohereCompletionRequest::try_from(("command-r", request)).unwrap();
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            json["documents"],
+            serde_json::json!([{ "id": "doc_0", "data": { "text": "Owls hoot at night." } }])
+        );
+        assert_eq!(json["messages"].as_array().unwrap().len(), 1);
+    }
+
     #[test]
     fn test_convert_completion_message_to_message_and_back() {
         let completion_message = completion::Message::User {
             content: OneOrMany::one(completion::message::UserContent::Text(
-                completion::message::Text {
-                    text: "Hello, world!".to_string(),
-                },
+                completion::message::Text::from("Hello, world!".to_string()),
             )),
         };
 
@@ -3,7 +3,8 @@ use crate::http_client::HttpClientExt;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::providers::cohere::CompletionModel;
 use crate::providers::cohere::completion::{
-    AssistantContent, CohereCompletionRequest, Message, ToolCall, ToolCallFunction, ToolType, Usage,
+    AssistantContent, Citation, CohereCompletionRequest, FinishReason, Message, ToolCall,
+    ToolCallFunction, ToolType, Usage,
 };
 use crate::streaming::{RawStreamingChoice, RawStreamingToolCall};
 use crate::telemetry::SpanCombinator;
@@ -17,7 +18,7 @@ use tracing_futures::Instrument;
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case", tag = "type")]
 enum StreamingEvent {
-    MessageStart,
+    MessageStart { id: Option<String> },
     ContentStart,
     ContentDelta { delta: Option<Delta> },
     ContentEnd,
@@ -25,6 +26,8 @@ enum StreamingEvent {
     ToolCallStart { delta: Option<Delta> },
     ToolCallDelta { delta: Option<Delta> },
     ToolCallEnd,
+    CitationStart { delta: Option<Delta> },
+    CitationEnd,
     MessageEnd { delta: Option<MessageEndDelta> },
 }
 
@@ -49,6 +52,7 @@ struct MessageToolCallDelta {
 struct MessageDelta {
     content: Option<MessageContentDelta>,
     tool_calls: Option<MessageToolCallDelta>,
+    citations: Option<Citation>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,6 +62,7 @@ struct Delta {
 
 #[derive(Debug, Deserialize)]
 struct MessageEndDelta {
+    finish_reason: Option<String>,
     usage: Option<Usage>,
 }
 
@@ -141,6 +146,7 @@ where
             let mut current_tool_call: Option<(String, String, String)> = None;
             let mut text_response = String::new();
             let mut tool_calls = Vec::new();
+            let mut citations = Vec::new();
             let mut final_usage = None;
 
             while let Some(event_result) = event_source.next().await {
@@ -165,6 +171,14 @@ where
                         };
 
                         match event {
+                            StreamingEvent::MessageStart { id: Some(id) } => {
+                                yield Ok(RawStreamingChoice::Metadata(streaming::ResponseMetadata::new(
+                                    Some(id),
+                                    None,
+                                    None,
+                                )));
+                            },
+
                             StreamingEvent::ContentDelta { delta: Some(delta) } => {
                                 let Some(message) = &delta.message else { continue; };
                                 let Some(content) = &message.content else { continue; };
@@ -180,17 +194,39 @@ where
                                     tool_calls: tool_calls.clone(),
                                     content: vec![AssistantContent::Text { text: text_response.clone() }],
                                     tool_plan: None,
-                                    citations: vec![]
+                                    citations: citations.clone()
                                 };
 
                                 let span = tracing::Span::current();
                                 span.record_token_usage(&delta.usage);
                                 span.record_model_output(&vec![message]);
 
+                                if let Some(reason) = &delta.finish_reason {
+                                    // Unknown reasons must not prevent the usage from being read
+                                    let reason = serde_json::from_value::<FinishReason>(reason.as_str().into())
+                                        .map(|reason| (&reason).into())
+                                        .unwrap_or_else(|_| crate::completion::FinishReason::Other(reason.clone()));
+                                    yield Ok(RawStreamingChoice::Metadata(
+                                        streaming::ResponseMetadata::finish_reason(reason),
+                                    ));
+                                }
+
                                 final_usage = Some(delta.usage.clone());
                                 break;
                             },
 
+                            StreamingEvent::CitationStart { delta: Some(delta) } => {
+                                let Some(message) = delta.message else { continue; };
+                                let Some(citation) = message.citations else { continue; };
+
+                                let converted = citation.to_message_citation(&text_response);
+                                citations.push(citation);
+
+                                if let Some(citation) = converted {
+                                    yield Ok(RawStreamingChoice::Citation(citation));
+                                }
+                            },
+
                             StreamingEvent::ToolCallStart { delta: Some(delta) } => {
                                 let Some(message) = &delta.message else { continue; };
                                 let Some(tool_calls) = &message.tool_calls else { continue; };
@@ -370,6 +406,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_citation_start_deserialization() {
+        let json = json!({
+            "type": "citation-start",
+            "index": 0,
+            "delta": {
+                "message": {
+                    "citations": {
+                        "start": 0,
+                        "end": 4,
+                        "text": "Owls",
+                        "type": "TEXT_CONTENT",
+                        "sources": [{ "type": "document", "id": "doc_0" }]
+                    }
+                }
+            }
+        });
+
+        let event: StreamingEvent = serde_json::from_value(json).unwrap();
+        let StreamingEvent::CitationStart { delta: Some(delta) } = event else {
+            panic!("Expected CitationStart");
+        };
+        let citation = delta.message.unwrap().citations.unwrap();
+        let citation = citation.to_message_citation("Owls hoot.").unwrap();
+
+        assert_eq!(citation.span, Some(0..4));
+        assert_eq!(citation.document_ids().collect::<Vec<_>>(), vec!["doc_0"]);
+    }
+
     #[test]
     fn test_message_end_with_usage_deserialization() {
         let json = json!({
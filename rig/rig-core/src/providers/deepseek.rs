@@ -9,7 +9,9 @@
 //! let deepseek_chat = client.completion_model(deepseek::DEEPSEEK_CHAT);
 //! ```
 
+use crate::completion::SamplingParam;
 use crate::json_utils::empty_or_none;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use async_stream::stream;
 use bytes::Bytes;
 use futures::StreamExt;
@@ -25,6 +27,7 @@ use crate::completion::GetTokenUsage;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::http_client::{self, HttpClientExt};
 use crate::message::{Document, DocumentSourceKind};
+use crate::providers::openai::completion::map_finish_reason;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -72,6 +75,8 @@ impl<H> Capabilities<H> for DeepSeekExt {
     type AudioGeneration = Nothing;
 }
 
+crate::client::model_listing::impl_openai_compatible_model_listing!(DeepSeekExt, "/models");
+
 impl DebugExt for DeepSeekExt {}
 
 impl ProviderBuilder for DeepSeekExtBuilder {
@@ -195,6 +200,10 @@ pub enum Message {
         content: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        /// The reasoning of the model in thinking mode. It must be sent back together with the
+        /// tool calls of the same turn.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reasoning_content: Option<String>,
         #[serde(
             default,
             deserialize_with = "json_utils::null_or_vec",
@@ -304,6 +313,7 @@ impl TryFrom<message::Message> for Vec<Message> {
                         message::AssistantContent::Text(text) => Some(Message::Assistant {
                             content: text.text,
                             name: None,
+                            reasoning_content: None,
                             tool_calls: vec![],
                         }),
                         _ => None,
@@ -324,11 +334,23 @@ impl TryFrom<message::Message> for Vec<Message> {
                     })
                     .collect::<Vec<_>>();
 
-                // if we have tool calls, we add a new Assistant message with them
+                // if we have tool calls, we add a new Assistant message with them and the
+                // reasoning that led to them
                 if !tool_calls.is_empty() {
+                    let reasoning = content
+                        .iter()
+                        .filter_map(|content| match content {
+                            message::AssistantContent::Reasoning(reasoning) => {
+                                Some(reasoning.reasoning.concat())
+                            }
+                            _ => None,
+                        })
+                        .collect::<String>();
+
                     messages.push(Message::Assistant {
                         content: "".to_string(),
                         name: None,
+                        reasoning_content: (!reasoning.is_empty()).then_some(reasoning),
                         tool_calls,
                     });
                 }
@@ -387,16 +409,22 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         let content = match &choice.message {
             Message::Assistant {
                 content,
+                reasoning_content,
                 tool_calls,
                 ..
             } => {
-                let mut content = if content.trim().is_empty() {
-                    vec![]
-                } else {
-                    vec![completion::AssistantContent::text(content)]
+                let mut assistant_content = match reasoning_content {
+                    Some(reasoning) if !reasoning.is_empty() => {
+                        vec![completion::AssistantContent::reasoning(reasoning)]
+                    }
+                    _ => vec![],
                 };
 
-                content.extend(
+                if !content.trim().is_empty() {
+                    assistant_content.push(completion::AssistantContent::text(content));
+                }
+
+                assistant_content.extend(
                     tool_calls
                         .iter()
                         .map(|call| {
@@ -408,7 +436,7 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                         })
                         .collect::<Vec<_>>(),
                 );
-                Ok(content)
+                Ok(assistant_content)
             }
             _ => Err(CompletionError::ResponseError(
                 "Response did not contain a valid message or tool call".into(),
@@ -430,21 +458,64 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .map(|choice| map_finish_reason(&choice.finish_reason)),
+            id: None,
+            model: None,
+            logprobs: response
+                .choices
+                .first()
+                .and_then(|choice| choice.logprobs.as_ref())
+                .and_then(crate::providers::openai::completion::token_logprobs),
             raw_response: response,
         })
     }
 }
 
+/// The sampling parameters supported by the DeepSeek API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+];
+
+/// Toggles the thinking mode of the DeepSeek models supporting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Thinking {
+    Enabled,
+    Disabled,
+}
+
+impl From<&completion::ReasoningConfig> for Thinking {
+    fn from(reasoning: &completion::ReasoningConfig) -> Self {
+        if reasoning.is_disabled() {
+            Self::Disabled
+        } else {
+            Self::Enabled
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct DeepseekCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<crate::providers::openrouter::ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<Thinking>,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<serde_json::Value>,
 }
@@ -453,6 +524,7 @@ impl TryFrom<(&str, CompletionRequest)> for DeepseekCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("DeepSeek")?;
         let mut full_history: Vec<Message> = match &req.preamble {
             Some(preamble) => vec![Message::system(preamble)],
             None => vec![],
@@ -481,10 +553,20 @@ impl TryFrom<(&str, CompletionRequest)> for DeepseekCompletionRequest {
             .map(crate::providers::openrouter::ToolChoice::try_from)
             .transpose()?;
 
+        let thinking = req
+            .reasoning_unless_overridden("thinking")
+            .map(Thinking::from);
+
         Ok(Self {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "DeepSeek",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
@@ -492,6 +574,7 @@ impl TryFrom<(&str, CompletionRequest)> for DeepseekCompletionRequest {
                 .map(ToolDefinition::from)
                 .collect::<Vec<_>>(),
             tool_choice,
+            thinking,
             additional_params: req.additional_params,
         })
     }
@@ -667,10 +750,16 @@ pub struct StreamingDelta {
 #[derive(Deserialize, Debug)]
 struct StreamingChoice {
     delta: StreamingDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 struct StreamingCompletionChunk {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
     choices: Vec<StreamingChoice>,
     usage: Option<Usage>,
 }
@@ -707,7 +796,9 @@ where
     let stream = stream! {
         let mut final_usage = Usage::new();
         let mut text_response = String::new();
+        let mut reasoning_response = String::new();
         let mut calls: HashMap<usize, (String, String, String)> = HashMap::new();
+        let mut metadata_yielded = false;
 
         while let Some(event_result) = event_source.next().await {
             match event_result {
@@ -727,6 +818,21 @@ where
                         continue;
                     };
 
+                    if !metadata_yielded && (data.id.is_some() || data.model.is_some()) {
+                        metadata_yielded = true;
+                        yield Ok(crate::streaming::RawStreamingChoice::Metadata(
+                            crate::streaming::ResponseMetadata::new(data.id.clone(), data.model.clone(), None),
+                        ));
+                    }
+
+                    if let Some(reason) = data.choices.first().and_then(|choice| choice.finish_reason.as_deref()) {
+                        yield Ok(crate::streaming::RawStreamingChoice::Metadata(
+                            crate::streaming::ResponseMetadata::finish_reason(
+                                crate::providers::openai::completion::map_finish_reason(reason),
+                            ),
+                        ));
+                    }
+
                     if let Some(choice) = data.choices.first() {
                         let delta = &choice.delta;
 
@@ -774,6 +880,7 @@ where
 
                         // DeepSeek-specific reasoning stream
                         if let Some(content) = &delta.reasoning_content {
+                            reasoning_response += content;
                             yield Ok(crate::streaming::RawStreamingChoice::ReasoningDelta {
                                 id: None,
                                 reasoning: content.to_string()
@@ -827,6 +934,7 @@ where
         let message = Message::Assistant {
             content: text_response,
             name: None,
+            reasoning_content: (!reasoning_response.is_empty()).then_some(reasoning_response),
             tool_calls
         };
 
@@ -985,6 +1093,7 @@ mod tests {
             message: Message::Assistant {
                 content: "".to_string(),
                 name: None,
+                reasoning_content: None,
                 tool_calls: vec![ToolCall {
                     id: "call_0_2b4a85ee-b04a-40ad-a16b-a405caf6e65b".to_string(),
                     function: Function {
@@ -999,4 +1108,54 @@ mod tests {
 
         assert_eq!(choice, expected_choice);
     }
+
+    #[test]
+    fn test_reasoning_content_round_trip() {
+        let message = message::Message::Assistant {
+            id: None,
+            content: OneOrMany::many(vec![
+                message::AssistantContent::reasoning("I should subtract"),
+                message::AssistantContent::tool_call(
+                    "call_0",
+                    "subtract",
+                    serde_json::json!({"x": 2, "y": 5}),
+                ),
+            ])
+            .unwrap(),
+        };
+
+        let messages: Vec<Message> = message.try_into().unwrap();
+        assert_eq!(messages.len(), 1);
+        match &messages[0] {
+            Message::Assistant {
+                reasoning_content,
+                tool_calls,
+                ..
+            } => {
+                assert_eq!(reasoning_content.as_deref(), Some("I should subtract"));
+                assert_eq!(tool_calls.len(), 1);
+            }
+            _ => panic!("Expected assistant message"),
+        }
+
+        let request = CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one(message::Message::user("Hello")),
+            documents: vec![],
+            tools: vec![],
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: Default::default(),
+            logprobs: None,
+            reasoning: Some(completion::ReasoningConfig::disabled()),
+            additional_params: None,
+        };
+        let request = DeepseekCompletionRequest::try_from((DEEPSEEK_CHAT, request)).unwrap();
+        assert_eq!(
+            serde_json::to_value(&request).unwrap()["thinking"],
+            serde_json::json!({ "type": "disabled" })
+        );
+    }
 }
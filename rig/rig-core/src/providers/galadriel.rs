@@ -15,8 +15,11 @@ use crate::client::{
     self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
     ProviderClient,
 };
+use crate::completion::SamplingParam;
 use crate::http_client::{self, HttpClientExt};
 use crate::message::MessageError;
+use crate::providers::openai::completion::map_finish_reason;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
 use crate::{
@@ -268,6 +271,17 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .map(|choice| map_finish_reason(&choice.finish_reason)),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: response
+                .choices
+                .first()
+                .and_then(|choice| choice.logprobs.as_ref())
+                .and_then(crate::providers::openai::completion::token_logprobs),
             raw_response: response,
         })
     }
@@ -388,6 +402,8 @@ impl TryFrom<message::Message> for Message {
                                 "Galadriel currently doesn't support images.".into(),
                             ));
                         }
+                        // Hosted tool calls can only be sent back to the provider that made them
+                        message::AssistantContent::HostedToolCall(_) => {}
                     }
                 }
 
@@ -422,12 +438,26 @@ pub struct Function {
     pub arguments: String,
 }
 
+/// The sampling parameters supported by the Galadriel API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct GaladrielCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -440,6 +470,9 @@ impl TryFrom<(&str, CompletionRequest)> for GaladrielCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("Galadriel")?;
+        let sampling = req.sampling_unless_overridden(SamplingParam::name);
+
         // Build up the order of messages (context, chat_history, prompt)
         let mut partial_history = vec![];
         if let Some(docs) = req.normalized_documents() {
@@ -471,6 +504,8 @@ impl TryFrom<(&str, CompletionRequest)> for GaladrielCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new("Galadriel", sampling, SUPPORTED_SAMPLING_PARAMS)?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
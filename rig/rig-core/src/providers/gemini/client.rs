@@ -1,17 +1,18 @@
-#[cfg(feature = "image")]
+#[cfg(any(feature = "image", feature = "audio"))]
 use crate::client::Nothing;
 use crate::client::{
-    self, ApiKey, Capabilities, Capable, DebugExt, Provider, ProviderBuilder, ProviderClient,
-    Transport,
+    self, ApiKey, Capabilities, Capable, DebugExt, ModelInfo, ModelListingClient,
+    ModelListingError, Provider, ProviderBuilder, ProviderClient, Transport,
 };
-use crate::http_client;
+use crate::http_client::{self, HttpClientExt};
+use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use serde::Deserialize;
 use std::fmt::Debug;
 
 // ================================================================
 // Google Gemini Client
 // ================================================================
-const GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+pub(crate) const GEMINI_API_BASE_URL: &str = "https://generativelanguage.googleapis.com";
 
 #[derive(Debug, Default, Clone)]
 pub struct GeminiExt {
@@ -57,20 +58,25 @@ impl Provider for GeminiExt {
     }
 
     fn build_uri(&self, base_url: &str, path: &str, transport: Transport) -> String {
+        // The path may already have query parameters
+        let separator = if path.contains('?') { '&' } else { '?' };
+
         match transport {
             Transport::Sse => {
                 format!(
-                    "{}/{}?alt=sse&key={}",
+                    "{}/{}{}alt=sse&key={}",
                     base_url,
                     path.trim_start_matches('/'),
+                    separator,
                     self.api_key
                 )
             }
             _ => {
                 format!(
-                    "{}/{}?key={}",
+                    "{}/{}{}key={}",
                     base_url,
                     path.trim_start_matches('/'),
+                    separator,
                     self.api_key
                 )
             }
@@ -122,3 +128,133 @@ pub enum ApiResponse<T> {
     Ok(T),
     Err(ApiErrorResponse),
 }
+
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ModelList {
+    #[serde(default)]
+    models: Vec<Model>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Model {
+    /// The resource name of the model, e.g. `models/gemini-2.5-flash`
+    name: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    input_token_limit: Option<u64>,
+    #[serde(default)]
+    output_token_limit: Option<u64>,
+    #[serde(default)]
+    supported_generation_methods: Vec<String>,
+    #[serde(default)]
+    thinking: Option<bool>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        let supports = |method: &str| {
+            model
+                .supported_generation_methods
+                .iter()
+                .any(|supported| supported == method)
+        };
+        let is_embedding = supports("embedContent");
+
+        Self {
+            name: model.display_name,
+            context_window: model.input_token_limit,
+            max_output_tokens: model.output_token_limit,
+            supports_reasoning: model.thinking,
+            is_embedding: Some(is_embedding),
+            ..Self::new(model.name.trim_start_matches("models/"))
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let mut models = vec![];
+        let mut path = "/v1beta/models?pageSize=1000".to_string();
+
+        loop {
+            let page: ModelList = self.get_models(&path).await?;
+            models.extend(page.models.into_iter().map(ModelInfo::from));
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => {
+                    path = format!("/v1beta/models?pageSize=1000&pageToken={token}");
+                }
+                _ => break,
+            }
+        }
+
+        Ok(models)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_uri_with_query() {
+        let ext = GeminiExt {
+            api_key: "key".to_string(),
+        };
+
+        assert_eq!(
+            ext.build_uri("https://g.co", "/v1beta/models", Transport::Http),
+            "https://g.co/v1beta/models?key=key"
+        );
+        assert_eq!(
+            ext.build_uri(
+                "https://g.co",
+                "/v1beta/models?pageSize=10",
+                Transport::Http
+            ),
+            "https://g.co/v1beta/models?pageSize=10&key=key"
+        );
+    }
+
+    #[test]
+    fn test_model_list() {
+        let page: ModelList = serde_json::from_value(serde_json::json!({
+            "models": [{
+                "name": "models/gemini-2.5-flash",
+                "displayName": "Gemini 2.5 Flash",
+                "inputTokenLimit": 1048576,
+                "outputTokenLimit": 65536,
+                "supportedGenerationMethods": ["generateContent", "countTokens"],
+                "thinking": true
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(page.next_page_token, None);
+        assert_eq!(
+            page.models
+                .into_iter()
+                .map(ModelInfo::from)
+                .collect::<Vec<_>>(),
+            vec![ModelInfo {
+                name: Some("Gemini 2.5 Flash".to_string()),
+                context_window: Some(1_048_576),
+                max_output_tokens: Some(65_536),
+                supports_reasoning: Some(true),
+                is_embedding: Some(false),
+                ..ModelInfo::new("gemini-2.5-flash")
+            }]
+        );
+    }
+}
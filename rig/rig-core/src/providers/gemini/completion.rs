@@ -23,18 +23,19 @@ use self::gemini_api_types::Schema;
 use crate::http_client::HttpClientExt;
 use crate::message::{self, MimeType, Reasoning};
 
+use super::gemini_api_types::CodeExecutionOutcome;
 use crate::providers::gemini::completion::gemini_api_types::{
-    AdditionalParameters, FunctionCallingMode, ToolConfig,
+    AdditionalParameters, FunctionCallingMode, GenerationConfig, ThinkingConfig, ToolConfig,
 };
 use crate::providers::gemini::streaming::StreamingCompletionResponse;
 use crate::telemetry::SpanCombinator;
 use crate::{
     OneOrMany,
-    completion::{self, CompletionError, CompletionRequest},
+    completion::{self, CompletionError, CompletionRequest, SamplingParam},
 };
 use gemini_api_types::{
-    Content, FunctionDeclaration, GenerateContentRequest, GenerateContentResponse, Part, PartKind,
-    Role, Tool,
+    CodeExecution, Content, FileSearch, FunctionDeclaration, GenerateContentRequest,
+    GenerateContentResponse, GoogleSearch, LogprobsResult, Part, PartKind, Role, Tool, UrlContext,
 };
 use serde_json::{Map, Value};
 use std::convert::TryFrom;
@@ -198,6 +199,32 @@ pub(crate) fn create_request_body(
         additional_params,
     } = serde_json::from_value::<AdditionalParameters>(additional_params)?;
 
+    let sampling = completion_request.sampling;
+    sampling.check_supported(
+        "Gemini",
+        &[
+            SamplingParam::TopP,
+            SamplingParam::TopK,
+            SamplingParam::Stop,
+            SamplingParam::Seed,
+            SamplingParam::PresencePenalty,
+            SamplingParam::FrequencyPenalty,
+        ],
+    )?;
+
+    // Sampling parameters need a generation config to be sent
+    if generation_config.is_none()
+        && (completion_request.logprobs.is_some()
+            || completion_request.reasoning.is_some()
+            || SamplingParam::ALL.iter().any(|p| sampling.is_set(*p)))
+    {
+        generation_config = Some(GenerationConfig {
+            temperature: None,
+            max_output_tokens: None,
+            ..Default::default()
+        });
+    }
+
     generation_config = generation_config.map(|mut cfg| {
         if let Some(temp) = completion_request.temperature {
             cfg.temperature = Some(temp);
@@ -207,6 +234,44 @@ pub(crate) fn create_request_body(
             cfg.max_output_tokens = Some(max_tokens);
         };
 
+        if let Some(top_logprobs) = completion_request.logprobs {
+            cfg.response_logprobs = Some(true);
+            if top_logprobs > 0 {
+                cfg.logprobs = Some(top_logprobs as i32);
+            }
+        }
+
+        if let Some(top_p) = sampling.top_p {
+            cfg.top_p = Some(top_p);
+        }
+
+        if let Some(top_k) = sampling.top_k {
+            cfg.top_k = Some(top_k as i32);
+        }
+
+        if let Some(stop) = sampling.stop {
+            cfg.stop_sequences = Some(stop);
+        }
+
+        if let Some(seed) = sampling.seed {
+            cfg.seed = Some(seed);
+        }
+
+        if let Some(presence_penalty) = sampling.presence_penalty {
+            cfg.presence_penalty = Some(presence_penalty);
+        }
+
+        if let Some(frequency_penalty) = sampling.frequency_penalty {
+            cfg.frequency_penalty = Some(frequency_penalty);
+        }
+
+        // A thinking config given in the additional parameters takes precedence
+        if cfg.thinking_config.is_none()
+            && let Some(reasoning) = &completion_request.reasoning
+        {
+            cfg.thinking_config = Some(ThinkingConfig::from(reasoning));
+        }
+
         cfg
     });
 
@@ -215,10 +280,15 @@ pub(crate) fn create_request_body(
         role: Some(Role::Model),
     });
 
-    let tools = if completion_request.tools.is_empty() {
+    let tools = if completion_request.tools.is_empty() && completion_request.hosted_tools.is_empty()
+    {
         None
     } else {
-        Some(Tool::try_from(completion_request.tools)?)
+        let mut tool = Tool::try_from(completion_request.tools)?;
+        for hosted_tool in &completion_request.hosted_tools {
+            tool.add_hosted_tool(hosted_tool)?;
+        }
+        Some(tool)
     };
 
     let tool_config = if let Some(cfg) = completion_request.tool_choice {
@@ -265,7 +335,7 @@ impl TryFrom<completion::ToolDefinition> for Tool {
                 description: tool.description,
                 parameters,
             }],
-            code_execution: None,
+            ..Default::default()
         })
     }
 }
@@ -302,11 +372,71 @@ impl TryFrom<Vec<completion::ToolDefinition>> for Tool {
 
         Ok(Self {
             function_declarations,
-            code_execution: None,
+            ..Default::default()
         })
     }
 }
 
+impl Tool {
+    /// Enables the native Gemini tool of a hosted tool.
+    fn add_hosted_tool(&mut self, tool: &completion::HostedTool) -> Result<(), CompletionError> {
+        match tool {
+            completion::HostedTool::WebSearch {
+                allowed_domains, ..
+            } => {
+                if !allowed_domains.is_empty() {
+                    return Err(CompletionError::RequestError(
+                        "Gemini does not support restricting the domains of the web search".into(),
+                    ));
+                }
+                self.google_search = Some(GoogleSearch {});
+            }
+            completion::HostedTool::CodeInterpreter => self.code_execution = Some(CodeExecution {}),
+            completion::HostedTool::FileSearch { store_ids, .. } => {
+                self.file_search = Some(FileSearch {
+                    file_search_store_names: store_ids.clone(),
+                })
+            }
+            completion::HostedTool::UrlContext => self.url_context = Some(UrlContext {}),
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts the code executed by Gemini and its result into a [message::HostedToolCall]. The parts
+/// are kept as the raw call, to be sent back as-is.
+pub(crate) fn code_execution_call(code: &Part, result: Option<&Part>) -> message::HostedToolCall {
+    let PartKind::ExecutableCode(executable_code) = &code.part else {
+        unreachable!("Code execution calls are created from executable code parts");
+    };
+
+    let mut call =
+        message::HostedToolCall::new("code_execution", message::HostedToolKind::CodeInterpreter)
+            .with_input(Some(executable_code.code.clone()));
+
+    let mut parts = vec![code];
+    if let Some(part) = result {
+        parts.push(part);
+
+        if let PartKind::CodeExecutionResult(result) = &part.part {
+            if result.outcome == CodeExecutionOutcome::Ok {
+                call.output = result.output.clone();
+            } else {
+                call.error =
+                    Some(result.output.clone().unwrap_or_else(|| {
+                        format!("The code execution failed: {:?}", result.outcome)
+                    }));
+            }
+        }
+    }
+
+    match serde_json::to_value(parts) {
+        Ok(raw) => call.with_raw(raw),
+        Err(_) => call,
+    }
+}
+
 impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<GenerateContentResponse> {
     type Error = CompletionError;
 
@@ -315,7 +445,7 @@ impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<Generat
             CompletionError::ResponseError("No response candidates in response".into())
         })?;
 
-        let content = candidate
+        let parts = &candidate
             .content
             .as_ref()
             .ok_or_else(|| {
@@ -332,65 +462,85 @@ impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<Generat
                     "Gemini candidate missing content ({reason}, finish_message={message})"
                 ))
             })?
-            .parts
-            .iter()
-            .map(
-                |Part {
-                     thought,
-                     thought_signature,
-                     part,
-                     ..
-                 }| {
-                    Ok(match part {
-                        PartKind::Text(text) => {
-                            if let Some(thought) = thought
-                                && *thought
-                            {
-                                completion::AssistantContent::Reasoning(Reasoning::new(text))
-                            } else {
-                                completion::AssistantContent::text(text)
-                            }
-                        }
-                        PartKind::InlineData(inline_data) => {
-                            let mime_type =
-                                message::MediaType::from_mime_type(&inline_data.mime_type);
-
-                            match mime_type {
-                                Some(message::MediaType::Image(media_type)) => {
-                                    message::AssistantContent::image_base64(
-                                        &inline_data.data,
-                                        Some(media_type),
-                                        Some(message::ImageDetail::default()),
-                                    )
-                                }
-                                _ => {
-                                    return Err(CompletionError::ResponseError(format!(
-                                        "Unsupported media type {mime_type:?}"
-                                    )));
-                                }
-                            }
-                        }
-                        PartKind::FunctionCall(function_call) => {
-                            completion::AssistantContent::ToolCall(
-                                message::ToolCall::new(
-                                    function_call.name.clone(),
-                                    message::ToolFunction::new(
-                                        function_call.name.clone(),
-                                        function_call.args.clone(),
-                                    ),
-                                )
-                                .with_signature(thought_signature.clone()),
+            .parts;
+
+        let citations = candidate.citations();
+
+        let mut content = Vec::new();
+        let mut parts = parts.iter().enumerate().peekable();
+        while let Some((
+            index,
+            code @ Part {
+                thought,
+                thought_signature,
+                part,
+                ..
+            },
+        )) = parts.next()
+        {
+            content.push(match part {
+                PartKind::Text(text) => {
+                    if let Some(thought) = thought
+                        && *thought
+                    {
+                        completion::AssistantContent::Reasoning(
+                            Reasoning::new(text).with_signature(thought_signature.clone()),
+                        )
+                    } else {
+                        completion::AssistantContent::Text(
+                            message::Text::from(text.clone()).with_citations(
+                                citations
+                                    .iter()
+                                    .filter(|(part_index, _)| *part_index == index)
+                                    .map(|(_, citation)| citation.clone())
+                                    .collect(),
+                            ),
+                        )
+                    }
+                }
+                PartKind::InlineData(inline_data) => {
+                    let mime_type = message::MediaType::from_mime_type(&inline_data.mime_type);
+
+                    match mime_type {
+                        Some(message::MediaType::Image(media_type)) => {
+                            message::AssistantContent::image_base64(
+                                &inline_data.data,
+                                Some(media_type),
+                                Some(message::ImageDetail::default()),
                             )
                         }
                         _ => {
-                            return Err(CompletionError::ResponseError(
-                                "Response did not contain a message or tool call".into(),
-                            ));
+                            return Err(CompletionError::ResponseError(format!(
+                                "Unsupported media type {mime_type:?}"
+                            )));
                         }
-                    })
-                },
-            )
-            .collect::<Result<Vec<_>, _>>()?;
+                    }
+                }
+                PartKind::FunctionCall(function_call) => completion::AssistantContent::ToolCall(
+                    message::ToolCall::new(
+                        function_call.name.clone(),
+                        message::ToolFunction::new(
+                            function_call.name.clone(),
+                            function_call.args.clone(),
+                        ),
+                    )
+                    .with_signature(thought_signature.clone()),
+                ),
+                PartKind::ExecutableCode(_) => {
+                    let result = parts
+                        .next_if(|(_, part)| matches!(part.part, PartKind::CodeExecutionResult(_)));
+                    completion::AssistantContent::HostedToolCall(code_execution_call(
+                        code,
+                        result.map(|(_, part)| part),
+                    ))
+                }
+                _ => {
+                    return Err(CompletionError::ResponseError(
+                        "Response did not contain a message or tool call".into(),
+                    ));
+                }
+            });
+        }
 
         let choice = OneOrMany::many(content).map_err(|_| {
             CompletionError::ResponseError(
@@ -408,9 +558,33 @@ impl TryFrom<GenerateContentResponse> for completion::CompletionResponse<Generat
             })
             .unwrap_or_default();
 
+        // Gemini reports `STOP` when the model calls functions
+        let finish_reason = response
+            .candidates
+            .first()
+            .and_then(|candidate| candidate.finish_reason.as_ref())
+            .map(|reason| match completion::FinishReason::from(reason) {
+                completion::FinishReason::Stop
+                    if choice.iter().any(|content| {
+                        matches!(content, completion::AssistantContent::ToolCall(_))
+                    }) =>
+                {
+                    completion::FinishReason::ToolCalls
+                }
+                reason => reason,
+            });
+
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason,
+            id: Some(response.response_id.clone()),
+            model: response.model_version.clone(),
+            logprobs: response
+                .candidates
+                .first()
+                .and_then(|candidate| candidate.logprobs_result.as_ref())
+                .map(LogprobsResult::token_logprobs),
             raw_response: response,
         })
     }
@@ -428,8 +602,9 @@ pub mod gemini_api_types {
 
     use crate::completion::GetTokenUsage;
     use crate::message::{DocumentSourceKind, ImageMediaType, MessageError, MimeType};
+    use crate::providers::gemini::files::file_uri;
     use crate::{
-        completion::CompletionError,
+        completion::{self, CompletionError},
         message::{self},
         providers::gemini::gemini_api_types::{CodeExecutionResult, ExecutableCode},
     };
@@ -545,6 +720,10 @@ pub mod gemini_api_types {
         /// This field may be populated with recitation information for any text included in the content.
         /// These are passages that are "recited" from copyrighted material in the foundational LLM's training data.
         pub citation_metadata: Option<CitationMetadata>,
+        /// Output only. Grounding metadata for the candidate, populated when the response was
+        /// grounded with Google Search, URL context or file search.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub grounding_metadata: Option<GroundingMetadata>,
         /// Output only. Token count for this candidate.
         pub token_count: Option<i32>,
         /// Output only.
@@ -579,13 +758,34 @@ pub mod gemini_api_types {
                         .collect::<Result<Vec<_>, _>>()?,
                     role: Some(Role::User),
                 },
-                message::Message::Assistant { content, .. } => Content {
-                    role: Some(Role::Model),
-                    parts: content
-                        .into_iter()
-                        .map(|content| content.try_into())
-                        .collect::<Result<Vec<_>, _>>()?,
-                },
+                message::Message::Assistant { content, .. } => {
+                    let mut parts = Vec::new();
+                    for content in content {
+                        match content {
+                            // The hosted tool calls of other providers can't be sent back
+                            message::AssistantContent::HostedToolCall(call) => parts.extend(
+                                call.raw
+                                    .and_then(|raw| serde_json::from_value::<Vec<Part>>(raw).ok())
+                                    .filter(|parts| {
+                                        matches!(
+                                            parts.first(),
+                                            Some(Part {
+                                                part: PartKind::ExecutableCode(_),
+                                                ..
+                                            })
+                                        )
+                                    })
+                                    .unwrap_or_default(),
+                            ),
+                            content => parts.push(content.try_into()?),
+                        }
+                    }
+
+                    Content {
+                        role: Some(Role::Model),
+                        parts,
+                    }
+                }
             })
         }
     }
@@ -671,6 +871,10 @@ pub mod gemini_api_types {
                     mime_type: Some(mime_type),
                     file_uri: url,
                 }),
+                DocumentSourceKind::FileId(file_id) => PartKind::FileData(FileData {
+                    mime_type: Some(mime_type),
+                    file_uri: file_uri(&file_id),
+                }),
                 DocumentSourceKind::Base64(data) | DocumentSourceKind::String(data) => {
                     PartKind::InlineData(Blob { mime_type, data })
                 }
@@ -695,7 +899,7 @@ pub mod gemini_api_types {
 
         fn try_from(content: message::UserContent) -> Result<Self, Self::Error> {
             match content {
-                message::UserContent::Text(message::Text { text }) => Ok(Part {
+                message::UserContent::Text(message::Text { text, .. }) => Ok(Part {
                     thought: Some(false),
                     thought_signature: None,
                     part: PartKind::Text(text),
@@ -771,6 +975,10 @@ pub mod gemini_api_types {
                                 mime_type: Some(mime_type),
                                 file_uri,
                             }),
+                            DocumentSourceKind::FileId(file_id) => PartKind::FileData(FileData {
+                                mime_type: Some(mime_type),
+                                file_uri: file_uri(&file_id),
+                            }),
                             DocumentSourceKind::Base64(data) | DocumentSourceKind::String(data) => {
                                 PartKind::InlineData(Blob { mime_type, data })
                             }
@@ -818,6 +1026,10 @@ pub mod gemini_api_types {
                             mime_type: Some(mime_type),
                             file_uri,
                         }),
+                        DocumentSourceKind::FileId(file_id) => PartKind::FileData(FileData {
+                            mime_type: Some(mime_type),
+                            file_uri: file_uri(&file_id),
+                        }),
                         DocumentSourceKind::String(_) => {
                             return Err(message::MessageError::ConversionError(
                                 "Strings cannot be used as audio files!".into(),
@@ -879,6 +1091,10 @@ pub mod gemini_api_types {
                             };
                             PartKind::InlineData(Blob { mime_type, data })
                         }
+                        DocumentSourceKind::FileId(file_id) => PartKind::FileData(FileData {
+                            mime_type,
+                            file_uri: file_uri(&file_id),
+                        }),
                         DocumentSourceKind::String(_) => {
                             return Err(message::MessageError::ConversionError(
                                 "Strings cannot be used as audio files!".into(),
@@ -912,7 +1128,7 @@ pub mod gemini_api_types {
 
         fn try_from(content: message::AssistantContent) -> Result<Self, Self::Error> {
             match content {
-                message::AssistantContent::Text(message::Text { text }) => Ok(text.into()),
+                message::AssistantContent::Text(message::Text { text, .. }) => Ok(text.into()),
                 message::AssistantContent::Image(message::Image {
                     data, media_type, ..
                 }) => match media_type {
@@ -939,15 +1155,22 @@ pub mod gemini_api_types {
                     )),
                 },
                 message::AssistantContent::ToolCall(tool_call) => Ok(tool_call.into()),
-                message::AssistantContent::Reasoning(message::Reasoning { reasoning, .. }) => {
-                    Ok(Part {
-                        thought: Some(true),
-                        thought_signature: None,
-                        part: PartKind::Text(
-                            reasoning.first().cloned().unwrap_or_else(|| "".to_string()),
-                        ),
-                        additional_params: None,
-                    })
+                message::AssistantContent::Reasoning(message::Reasoning {
+                    reasoning,
+                    signature,
+                    ..
+                }) => Ok(Part {
+                    thought: Some(true),
+                    thought_signature: signature,
+                    part: PartKind::Text(
+                        reasoning.first().cloned().unwrap_or_else(|| "".to_string()),
+                    ),
+                    additional_params: None,
+                }),
+                message::AssistantContent::HostedToolCall(_) => {
+                    Err(message::MessageError::ConversionError(
+                        "Hosted tool calls are converted into several parts".to_string(),
+                    ))
                 }
             }
         }
@@ -1154,6 +1377,28 @@ pub mod gemini_api_types {
         MalformedFunctionCall,
     }
 
+    impl From<&FinishReason> for crate::completion::FinishReason {
+        fn from(reason: &FinishReason) -> Self {
+            use crate::completion::FinishReason as Reason;
+
+            match reason {
+                FinishReason::Stop => Reason::Stop,
+                FinishReason::MaxTokens => Reason::Length,
+                FinishReason::Safety
+                | FinishReason::Recitation
+                | FinishReason::Blocklist
+                | FinishReason::ProhibitedContent
+                | FinishReason::Spii => Reason::ContentFilter,
+                other => Reason::Other(
+                    serde_json::to_value(other)
+                        .ok()
+                        .and_then(|value| value.as_str().map(str::to_string))
+                        .unwrap_or_else(|| format!("{other:?}")),
+                ),
+            }
+        }
+    }
+
     #[derive(Clone, Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct CitationMetadata {
@@ -1173,23 +1418,209 @@ pub mod gemini_api_types {
         pub license: Option<String>,
     }
 
+    impl ContentCandidate {
+        /// Returns the citations of the candidate along with the index of the content part they
+        /// refer to. Grounding supports are attributed to the part of their segment, while
+        /// recitation sources are attributed to the first text part.
+        pub fn citations(&self) -> Vec<(usize, message::Citation)> {
+            let mut citations = Vec::new();
+
+            if let Some(grounding) = &self.grounding_metadata {
+                citations.extend(grounding.grounding_supports.iter().filter_map(|support| {
+                    let sources = support
+                        .grounding_chunk_indices
+                        .iter()
+                        .filter_map(|index| grounding.grounding_chunks.get(*index))
+                        .filter_map(GroundingChunk::citation_source)
+                        .collect::<Vec<_>>();
+
+                    if sources.is_empty() {
+                        return None;
+                    }
+
+                    let Segment {
+                        part_index,
+                        start_index,
+                        end_index,
+                        ..
+                    } = support.segment;
+
+                    Some((
+                        part_index,
+                        message::Citation::new(Some(start_index..end_index), sources),
+                    ))
+                }));
+            }
+
+            if let Some(metadata) = &self.citation_metadata {
+                let part_index = self
+                    .content
+                    .as_ref()
+                    .and_then(|content| {
+                        content.parts.iter().position(|part| {
+                            matches!(part.part, PartKind::Text(_)) && part.thought != Some(true)
+                        })
+                    })
+                    .unwrap_or_default();
+
+                citations.extend(metadata.citation_sources.iter().filter_map(|source| {
+                    let url = source.uri.clone()?;
+                    let span = match (source.start_index, source.end_index) {
+                        (Some(start), Some(end)) => {
+                            Some(start.max(0) as usize..end.max(0) as usize)
+                        }
+                        _ => None,
+                    };
+
+                    Some((
+                        part_index,
+                        message::Citation::new(span, vec![message::CitationSource::url(url, None)]),
+                    ))
+                }));
+            }
+
+            citations
+        }
+    }
+
+    /// Metadata returned when grounding is enabled.
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GroundingMetadata {
+        /// The sources used to ground the response.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub grounding_chunks: Vec<GroundingChunk>,
+        /// The segments of the response supported by the grounding chunks.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub grounding_supports: Vec<GroundingSupport>,
+        /// The queries used for the Google Search grounding.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub web_search_queries: Vec<String>,
+        /// The Google Search suggestions to render along with the response.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub search_entry_point: Option<Value>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GroundingChunk {
+        /// A web page (Google Search, URL context)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub web: Option<GroundingChunkSource>,
+        /// A retrieved document (file search)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub retrieved_context: Option<GroundingChunkSource>,
+    }
+
+    impl GroundingChunk {
+        fn citation_source(&self) -> Option<message::CitationSource> {
+            if let Some(web) = &self.web {
+                return Some(message::CitationSource {
+                    url: web.uri.clone(),
+                    title: web.title.clone(),
+                    ..Default::default()
+                });
+            }
+
+            self.retrieved_context
+                .as_ref()
+                .map(|context| message::CitationSource {
+                    url: context.uri.clone(),
+                    title: context.title.clone(),
+                    quote: context.text.clone(),
+                    ..Default::default()
+                })
+        }
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GroundingChunkSource {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub uri: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub title: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub text: Option<String>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GroundingSupport {
+        pub segment: Segment,
+        /// The indices of the [GroundingChunk]s supporting the segment
+        #[serde(default)]
+        pub grounding_chunk_indices: Vec<usize>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub confidence_scores: Vec<f64>,
+    }
+
+    /// A segment of the response, as byte offsets in a content part.
+    #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct Segment {
+        #[serde(default)]
+        pub part_index: usize,
+        #[serde(default)]
+        pub start_index: usize,
+        #[serde(default)]
+        pub end_index: usize,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub text: Option<String>,
+    }
+
     #[derive(Clone, Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct LogprobsResult {
+        #[serde(default, rename = "topCandidates", alias = "topCandidate")]
         pub top_candidate: Vec<TopCandidate>,
+        #[serde(default, rename = "chosenCandidates", alias = "chosenCandidate")]
         pub chosen_candidate: Vec<LogProbCandidate>,
     }
 
+    impl LogprobsResult {
+        /// Converts the chosen tokens (and their top alternatives) into provider-neutral token logprobs.
+        pub fn token_logprobs(&self) -> Vec<crate::completion::TokenLogprob> {
+            self.chosen_candidate
+                .iter()
+                .enumerate()
+                .map(|(i, chosen)| crate::completion::TokenLogprob {
+                    token: chosen.token.clone(),
+                    logprob: chosen.log_probability,
+                    bytes: None,
+                    top_logprobs: self
+                        .top_candidate
+                        .get(i)
+                        .map(|top| {
+                            top.candidates
+                                .iter()
+                                .map(|candidate| crate::completion::TopLogprob {
+                                    token: candidate.token.clone(),
+                                    logprob: candidate.log_probability,
+                                    bytes: None,
+                                })
+                                .collect()
+                        })
+                        .unwrap_or_default(),
+                })
+                .collect()
+        }
+    }
+
     #[derive(Clone, Debug, Deserialize, Serialize)]
     pub struct TopCandidate {
+        #[serde(default)]
         pub candidates: Vec<LogProbCandidate>,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct LogProbCandidate {
+        #[serde(default)]
         pub token: String,
-        pub token_id: String,
+        #[serde(default)]
+        pub token_id: Option<i64>,
+        #[serde(default)]
         pub log_probability: f64,
     }
 
@@ -1256,6 +1687,9 @@ pub mod gemini_api_types {
         /// that the model doesn't apply top-k sampling and doesn't allow setting topK on requests.
         #[serde(skip_serializing_if = "Option::is_none")]
         pub top_k: Option<i32>,
+        /// Seed used in decoding. If not set, the request uses a randomly generated seed.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub seed: Option<u64>,
         /// Presence penalty applied to the next token's logprobs if the token has already been seen in the response.
         /// This penalty is binary on/off and not dependent on the number of times the token is used (after the first).
         /// Use frequencyPenalty for a penalty that increases with each use. A positive penalty will discourage the use
@@ -1299,6 +1733,7 @@ pub mod gemini_api_types {
                 candidate_count: None,
                 top_p: None,
                 top_k: None,
+                seed: None,
                 presence_penalty: None,
                 frequency_penalty: None,
                 response_logprobs: None,
@@ -1316,6 +1751,20 @@ pub mod gemini_api_types {
         pub include_thoughts: Option<bool>,
     }
 
+    impl From<&completion::ReasoningConfig> for ThinkingConfig {
+        fn from(reasoning: &completion::ReasoningConfig) -> Self {
+            // A budget of 0 disables thinking on the models supporting it
+            let thinking_budget = reasoning
+                .resolved_budget_tokens()
+                .unwrap_or_else(|| completion::ReasoningEffort::Medium.budget_tokens());
+
+            Self {
+                thinking_budget: thinking_budget.try_into().unwrap_or(u32::MAX),
+                include_thoughts: reasoning.summary,
+            }
+        }
+    }
+
     #[derive(Debug, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct ImageConfig {
@@ -1658,11 +2107,18 @@ pub mod gemini_api_types {
         pub additional_params: Option<serde_json::Value>,
     }
 
-    #[derive(Debug, Serialize)]
+    #[derive(Debug, Serialize, Default)]
     #[serde(rename_all = "camelCase")]
     pub struct Tool {
         pub function_declarations: Vec<FunctionDeclaration>,
+        #[serde(skip_serializing_if = "Option::is_none")]
         pub code_execution: Option<CodeExecution>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub google_search: Option<GoogleSearch>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub url_context: Option<UrlContext>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub file_search: Option<FileSearch>,
     }
 
     #[derive(Debug, Serialize, Clone)]
@@ -1713,6 +2169,18 @@ pub mod gemini_api_types {
     #[derive(Debug, Serialize)]
     pub struct CodeExecution {}
 
+    #[derive(Debug, Serialize)]
+    pub struct GoogleSearch {}
+
+    #[derive(Debug, Serialize)]
+    pub struct UrlContext {}
+
+    #[derive(Debug, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct FileSearch {
+        pub file_search_store_names: Vec<String>,
+    }
+
     #[derive(Debug, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct SafetySetting {
@@ -1892,6 +2360,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_file_id_conversion() {
+        let part: Part = message::UserContent::document_file_id(
+            "files/abc-123",
+            Some(message::DocumentMediaType::PDF),
+        )
+        .try_into()
+        .unwrap();
+
+        assert_eq!(
+            part.part,
+            PartKind::FileData(gemini_api_types::FileData {
+                mime_type: Some("application/pdf".to_string()),
+                file_uri: "https://generativelanguage.googleapis.com/v1beta/files/abc-123"
+                    .to_string(),
+            })
+        );
+    }
+
     #[test]
     fn test_message_conversion_model() {
         let msg = message::Message::assistant("Hello, user!");
@@ -2063,4 +2550,103 @@ mod tests {
             assert!(items.properties.is_some());
         }
     }
+
+    #[test]
+    fn test_logprobs_result_conversion() {
+        let response: GenerateContentResponse = serde_json::from_value(json!({
+            "responseId": "resp_1",
+            "modelVersion": "gemini-2.5-flash",
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{"text": "Yes"}]
+                },
+                "finishReason": "STOP",
+                "logprobsResult": {
+                    "topCandidates": [{
+                        "candidates": [
+                            {"token": "Yes", "tokenId": 1, "logProbability": -0.05},
+                            {"token": "No", "tokenId": 2, "logProbability": -3.2}
+                        ]
+                    }],
+                    "chosenCandidates": [
+                        {"token": "Yes", "tokenId": 1, "logProbability": -0.05}
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        let response: completion::CompletionResponse<GenerateContentResponse> =
+            response.try_into().unwrap();
+        let logprobs = response.logprobs.unwrap();
+
+        assert_eq!(logprobs.len(), 1);
+        assert_eq!(logprobs[0].token, "Yes");
+        assert_eq!(logprobs[0].logprob, -0.05);
+        assert_eq!(logprobs[0].top_logprobs.len(), 2);
+        assert_eq!(logprobs[0].top_logprobs[1].token, "No");
+    }
+
+    #[test]
+    fn test_grounding_metadata_conversion() {
+        let response: GenerateContentResponse = serde_json::from_value(json!({
+            "responseId": "resp_1",
+            "modelVersion": "gemini-2.5-flash",
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [{"text": "Spain won Euro 2024."}]
+                },
+                "finishReason": "STOP",
+                "groundingMetadata": {
+                    "webSearchQueries": ["euro 2024 winner"],
+                    "groundingChunks": [
+                        {"web": {"uri": "https://uefa.com", "title": "uefa.com"}},
+                        {"web": {"uri": "https://aljazeera.com", "title": "aljazeera.com"}}
+                    ],
+                    "groundingSupports": [{
+                        "segment": {"endIndex": 20, "text": "Spain won Euro 2024."},
+                        "groundingChunkIndices": [0, 1]
+                    }]
+                }
+            }]
+        }))
+        .unwrap();
+
+        let response: completion::CompletionResponse<GenerateContentResponse> =
+            response.try_into().unwrap();
+        let completion::AssistantContent::Text(text) = response.choice.first() else {
+            panic!("Expected text content");
+        };
+
+        assert_eq!(text.citations.len(), 1);
+        let citation = &text.citations[0];
+        assert_eq!(text.cited_span(citation), Some("Spain won Euro 2024."));
+        assert_eq!(citation.sources.len(), 2);
+        assert_eq!(citation.sources[0].url.as_deref(), Some("https://uefa.com"));
+        assert_eq!(citation.sources[1].title.as_deref(), Some("aljazeera.com"));
+    }
+
+    #[test]
+    fn test_logprobs_request() {
+        let request = CompletionRequest {
+            preamble: None,
+            chat_history: OneOrMany::one("Is the sky blue?".into()),
+            documents: vec![],
+            tools: vec![],
+            hosted_tools: Vec::new(),
+            temperature: None,
+            max_tokens: None,
+            tool_choice: None,
+            sampling: Default::default(),
+            logprobs: Some(3),
+            reasoning: None,
+            additional_params: None,
+        };
+
+        let body = serde_json::to_value(create_request_body(request).unwrap()).unwrap();
+        assert_eq!(body["generationConfig"]["responseLogprobs"], json!(true));
+        assert_eq!(body["generationConfig"]["logprobs"], json!(3));
+    }
 }
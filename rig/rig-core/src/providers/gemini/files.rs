@@ -0,0 +1,225 @@
+//! Google Gemini Files API implementation
+//!
+//! Files are uploaded with the resumable upload protocol, and referenced in messages by their
+//! URI, which is used as the id of the [FileObject]. Uploaded files are deleted after 48 hours.
+//! See <https://ai.google.dev/gemini-api/docs/files>.
+use serde::Deserialize;
+
+use super::{Client, client::GEMINI_API_BASE_URL};
+use crate::{
+    files::{self, FileObject, FileUpload, FilesClient, FilesError},
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+/// The maximum number of files returned by a page of the list endpoint.
+const LIST_PAGE_SIZE: usize = 100;
+
+/// The file resource of the Gemini API, only the fields used to build a [FileObject].
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiFile {
+    name: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    mime_type: Option<String>,
+    /// An int64, serialized as a string
+    #[serde(default)]
+    size_bytes: Option<String>,
+    #[serde(default)]
+    uri: Option<String>,
+}
+
+impl GeminiFile {
+    fn from_raw(raw: serde_json::Value) -> Result<FileObject, FilesError> {
+        let file: Self = serde_json::from_value(raw.clone())?;
+
+        Ok(FileObject {
+            id: file.uri.unwrap_or_else(|| file_uri(&file.name)),
+            filename: file.display_name,
+            mime_type: file.mime_type,
+            bytes: file.size_bytes.and_then(|size| size.parse().ok()),
+            raw,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct UploadFileResponse {
+    file: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListFilesResponse {
+    #[serde(default)]
+    files: Vec<serde_json::Value>,
+    #[serde(default)]
+    next_page_token: Option<String>,
+}
+
+/// The URI referencing a file in messages, from the id of a [FileObject] or the resource name of
+/// the file (`files/{id}`).
+pub(crate) fn file_uri(id: &str) -> String {
+    if id.starts_with("https://") {
+        id.to_string()
+    } else {
+        format!("{GEMINI_API_BASE_URL}/v1beta/{}", file_name(id))
+    }
+}
+
+/// The resource name of a file (`files/{id}`), from its URI, resource name or bare id.
+fn file_name(id: &str) -> String {
+    match id.rfind("files/") {
+        Some(index) => id[index..].to_string(),
+        None => format!("files/{id}"),
+    }
+}
+
+impl<H> FilesClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn upload_file(&self, file: FileUpload) -> Result<FileObject, FilesError> {
+        let mime_type = file
+            .mime_type
+            .clone()
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        // Start a resumable upload, which returns the URL to upload the file to
+        let body = serde_json::to_vec(&serde_json::json!({
+            "file": { "display_name": file.filename }
+        }))?;
+
+        let req = self
+            .post("/upload/v1beta/files")?
+            .header("X-Goog-Upload-Protocol", "resumable")
+            .header("X-Goog-Upload-Command", "start")
+            .header("X-Goog-Upload-Header-Content-Length", file.data.len())
+            .header("X-Goog-Upload-Header-Content-Type", &mime_type)
+            .body(body)
+            .map_err(http_client::Error::from)?;
+
+        let response = self.send::<_, Vec<u8>>(req).await?;
+        let upload_url = response
+            .headers()
+            .get("x-goog-upload-url")
+            .and_then(|url| url.to_str().ok())
+            .map(str::to_string);
+        let status = response.status();
+        let text = http_client::text(response).await?;
+
+        let Some(upload_url) = upload_url else {
+            return Err(files::files_result::<serde_json::Value>(status, &text)
+                .err()
+                .unwrap_or_else(|| {
+                    FilesError::ProviderError("The upload response has no upload URL".into())
+                }));
+        };
+
+        // Upload the content of the file in a single chunk. This request is sent with the HTTP
+        // client directly, since the client would send it as JSON.
+        let req = http_client::Request::post(upload_url)
+            .header("X-Goog-Upload-Offset", 0)
+            .header("X-Goog-Upload-Command", "upload, finalize")
+            .header(http::header::CONTENT_TYPE, &mime_type)
+            .body(file.data)
+            .map_err(http_client::Error::from)?;
+
+        let response = self.http_client().send::<_, Vec<u8>>(req).await?;
+        let status = response.status();
+        let text = http_client::text(response).await?;
+
+        let response: UploadFileResponse = files::files_result(status, &text)?;
+
+        GeminiFile::from_raw(response.file)
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileObject>, FilesError> {
+        let mut files = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        loop {
+            let path = match &page_token {
+                Some(token) => {
+                    format!("/v1beta/files?pageSize={LIST_PAGE_SIZE}&pageToken={token}")
+                }
+                None => format!("/v1beta/files?pageSize={LIST_PAGE_SIZE}"),
+            };
+
+            let req = self
+                .get(path)?
+                .body(http_client::NoBody)
+                .map_err(http_client::Error::from)?;
+
+            let page: ListFilesResponse = self.files_response(req).await?;
+            for raw in page.files {
+                files.push(GeminiFile::from_raw(raw)?);
+            }
+
+            match page.next_page_token {
+                Some(token) if !token.is_empty() => page_token = Some(token),
+                _ => return Ok(files),
+            }
+        }
+    }
+
+    async fn retrieve_file(&self, id: &str) -> Result<FileObject, FilesError> {
+        let req = self
+            .get(format!("/v1beta/{}", file_name(id)))?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        GeminiFile::from_raw(self.files_response(req).await?)
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<(), FilesError> {
+        let req = self
+            .delete(format!("/v1beta/{}", file_name(id)))?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        let _: serde_json::Value = self.files_response(req).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_file_names() {
+        let uri = "https://generativelanguage.googleapis.com/v1beta/files/abc-123";
+
+        assert_eq!(file_name(uri), "files/abc-123");
+        assert_eq!(file_name("files/abc-123"), "files/abc-123");
+        assert_eq!(file_name("abc-123"), "files/abc-123");
+        assert_eq!(file_uri("files/abc-123"), uri);
+        assert_eq!(file_uri(uri), uri);
+    }
+
+    #[test]
+    fn test_file_object() {
+        let file = GeminiFile::from_raw(json!({
+            "name": "files/abc-123",
+            "displayName": "report.pdf",
+            "mimeType": "application/pdf",
+            "sizeBytes": "1024",
+            "createTime": "2024-08-20T18:37:24.100435Z",
+            "uri": "https://generativelanguage.googleapis.com/v1beta/files/abc-123",
+            "state": "ACTIVE"
+        }))
+        .unwrap();
+
+        assert_eq!(
+            file.id,
+            "https://generativelanguage.googleapis.com/v1beta/files/abc-123"
+        );
+        assert_eq!(file.filename.as_deref(), Some("report.pdf"));
+        assert_eq!(file.bytes, Some(1024));
+    }
+}
@@ -12,6 +12,7 @@
 pub mod client;
 pub mod completion;
 pub mod embedding;
+pub mod files;
 pub mod streaming;
 pub mod transcription;
 
@@ -4,8 +4,8 @@ use serde::{Deserialize, Serialize};
 use tracing::{Level, enabled, info_span};
 use tracing_futures::Instrument;
 
-use super::completion::gemini_api_types::{ContentCandidate, Part, PartKind};
-use super::completion::{CompletionModel, create_request_body};
+use super::completion::gemini_api_types::{ContentCandidate, LogprobsResult, Part, PartKind};
+use super::completion::{CompletionModel, code_execution_call, create_request_body};
 use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::HttpClientExt;
 use crate::http_client::sse::{Event, GenericEventSource};
@@ -42,6 +42,8 @@ impl GetTokenUsage for PartialUsage {
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StreamGenerateContentResponse {
+    #[serde(default)]
+    pub response_id: Option<String>,
     /// Candidate responses from the model.
     pub candidates: Vec<ContentCandidate>,
     pub model_version: Option<String>,
@@ -118,6 +120,10 @@ where
 
         let stream = stream! {
             let mut final_usage = None;
+            let mut metadata_yielded = false;
+            let mut called_tools = false;
+            // The executed code, until its result is received
+            let mut executable_code: Option<Part> = None;
             while let Some(event_result) = event_source.next().await {
                 match event_result {
                     Ok(Event::Open) => {
@@ -138,12 +144,38 @@ where
                             }
                         };
 
+                        if !metadata_yielded && (data.response_id.is_some() || data.model_version.is_some()) {
+                            metadata_yielded = true;
+                            yield Ok(streaming::RawStreamingChoice::Metadata(streaming::ResponseMetadata::new(
+                                data.response_id.clone(),
+                                data.model_version.clone(),
+                                None,
+                            )));
+                        }
+
                         // Process the response data
                         let Some(choice) = data.candidates.into_iter().next() else {
                             tracing::debug!("There is no content candidate");
                             continue;
                         };
 
+                        // Gemini reports `STOP` when the model calls functions
+                        if let Some(reason) = &choice.finish_reason {
+                            let has_tool_calls = called_tools || choice.content.as_ref().is_some_and(|content| {
+                                content.parts.iter().any(|part| matches!(part.part, PartKind::FunctionCall(_)))
+                            });
+                            let reason = match crate::completion::FinishReason::from(reason) {
+                                crate::completion::FinishReason::Stop if has_tool_calls => crate::completion::FinishReason::ToolCalls,
+                                reason => reason,
+                            };
+                            yield Ok(streaming::RawStreamingChoice::Metadata(streaming::ResponseMetadata::finish_reason(reason)));
+                        }
+
+                        // Grounding metadata is sent along with the final chunks, over the whole text
+                        for (_, citation) in choice.citations() {
+                            yield Ok(streaming::RawStreamingChoice::Citation(citation));
+                        }
+
                         let Some(content) = choice.content else {
                             tracing::debug!(finish_reason = ?choice.finish_reason, "Streaming candidate missing content");
                             continue;
@@ -176,17 +208,40 @@ where
                                     thought_signature,
                                     ..
                                 } => {
+                                    called_tools = true;
                                     yield Ok(streaming::RawStreamingChoice::ToolCall(
                                         streaming::RawStreamingToolCall::new(function_call.name.clone(), function_call.name.clone(), function_call.args.clone())
                                             .with_signature(thought_signature)
                                     ));
                                 },
+                                part @ Part {
+                                    part: PartKind::ExecutableCode(_),
+                                    ..
+                                } => {
+                                    if let Some(code) = executable_code.replace(part) {
+                                        yield Ok(streaming::RawStreamingChoice::HostedToolCall(code_execution_call(&code, None)));
+                                    }
+                                },
+                                part @ Part {
+                                    part: PartKind::CodeExecutionResult(_),
+                                    ..
+                                } => {
+                                    if let Some(code) = executable_code.take() {
+                                        yield Ok(streaming::RawStreamingChoice::HostedToolCall(code_execution_call(&code, Some(&part))));
+                                    }
+                                },
                                 part => {
                                     tracing::warn!(?part, "Unsupported response type with streaming");
                                 }
                             }
                         }
 
+                        if let Some(logprobs) = choice.logprobs_result.as_ref().map(LogprobsResult::token_logprobs)
+                            && !logprobs.is_empty()
+                        {
+                            yield Ok(streaming::RawStreamingChoice::Logprobs(logprobs));
+                        }
+
                         // Check if this is the final response
                         if choice.finish_reason.is_some() {
                             let span = tracing::Span::current();
@@ -209,6 +264,10 @@ where
             // Ensure event source is closed when stream ends
             event_source.close();
 
+            if let Some(code) = executable_code {
+                yield Ok(streaming::RawStreamingChoice::HostedToolCall(code_execution_call(&code, None)));
+            }
+
             yield Ok(streaming::RawStreamingChoice::FinalResponse(StreamingCompletionResponse {
                 usage_metadata: final_usage.unwrap_or_default()
             }));
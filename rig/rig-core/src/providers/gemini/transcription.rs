@@ -2,14 +2,17 @@ use std::path::Path;
 
 use base64::{Engine, prelude::BASE64_STANDARD};
 use mime_guess;
-use serde_json::{Map, Value};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
 
 use crate::{
     http_client::HttpClientExt,
     providers::gemini::completion::gemini_api_types::{
         Blob, Content, GenerateContentRequest, GenerationConfig, Part, PartKind, Role,
     },
-    transcription::{self, TranscriptionError},
+    transcription::{
+        self, TimestampGranularity, TranscriptionError, TranscriptionFormat, TranscriptionSegment,
+    },
     wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
 
@@ -17,6 +20,66 @@ use super::{Client, completion::gemini_api_types::GenerateContentResponse};
 
 const TRANSCRIPTION_PREAMBLE: &str =
     "Translate the provided audio exactly. Do not add additional information.";
+const TRANSLATION_PREAMBLE: &str =
+    "Translate the provided audio to English exactly. Do not add additional information.";
+const SEGMENTS_PREAMBLE: &str = "Split the transcription into segments at the pauses of the speech, \
+    with the start and end times of each segment in seconds from the start of the audio.";
+const WORDS_PREAMBLE: &str =
+    "List the words of each segment with their start and end times in seconds.";
+const SPEAKERS_PREAMBLE: &str = "Label the speaker of each segment and word, naming the speakers \
+    A, B, C and so on in their order of appearance.";
+
+/// The transcription requested as JSON when timed segments are requested, Gemini having no
+/// timestamps otherwise.
+#[derive(Debug, Deserialize)]
+struct SegmentedTranscription {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+    #[serde(default)]
+    segments: Vec<TranscriptionSegment>,
+}
+
+/// The schema of [SegmentedTranscription], with words and speakers only when requested.
+fn segmented_transcription_schema(words: bool, speakers: bool) -> Value {
+    let mut word = json!({
+        "type": "object",
+        "properties": {
+            "word": { "type": "string" },
+            "start": { "type": "number" },
+            "end": { "type": "number" }
+        },
+        "required": ["word", "start", "end"]
+    });
+    let mut segment = json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string" },
+            "start": { "type": "number" },
+            "end": { "type": "number" }
+        },
+        "required": ["text", "start", "end"]
+    });
+
+    if speakers {
+        word["properties"]["speaker"] = json!({ "type": "string" });
+        segment["properties"]["speaker"] = json!({ "type": "string" });
+    }
+
+    if words {
+        segment["properties"]["words"] = json!({ "type": "array", "items": word });
+    }
+
+    json!({
+        "type": "object",
+        "properties": {
+            "text": { "type": "string" },
+            "language": { "type": "string" },
+            "segments": { "type": "array", "items": segment }
+        },
+        "required": ["text", "segments"]
+    })
+}
 
 #[derive(Clone)]
 pub struct TranscriptionModel<T = reqwest::Client> {
@@ -52,6 +115,8 @@ where
         transcription::TranscriptionResponse<Self::Response>,
         transcription::TranscriptionError,
     > {
+        let format = request.output_format();
+
         // Handle Gemini specific parameters
         let additional_params = request
             .additional_params
@@ -63,8 +128,34 @@ where
             generation_config.temperature = Some(temp);
         }
 
+        let segmented = format.is_some_and(|format| format != TranscriptionFormat::Json);
+        let mut preamble = vec![if request.translate {
+            TRANSLATION_PREAMBLE
+        } else {
+            TRANSCRIPTION_PREAMBLE
+        }];
+
+        if segmented {
+            let words = request
+                .timestamp_granularities
+                .contains(&TimestampGranularity::Word);
+            let speakers = format == Some(TranscriptionFormat::DiarizedJson);
+
+            preamble.push(SEGMENTS_PREAMBLE);
+            if words {
+                preamble.push(WORDS_PREAMBLE);
+            }
+            if speakers {
+                preamble.push(SPEAKERS_PREAMBLE);
+            }
+
+            generation_config.response_mime_type = Some("application/json".to_string());
+            generation_config.response_json_schema =
+                Some(segmented_transcription_schema(words, speakers));
+        }
+
         let system_instruction = Some(Content {
-            parts: vec![TRANSCRIPTION_PREAMBLE.into()],
+            parts: vec![preamble.join(" ").into()],
             role: Some(Role::Model),
         });
 
@@ -127,7 +218,16 @@ where
 
             tracing::debug!("Received response");
 
-            Ok(transcription::TranscriptionResponse::try_from(body)?)
+            let mut response = transcription::TranscriptionResponse::try_from(body)?;
+
+            if segmented {
+                let transcription: SegmentedTranscription = serde_json::from_str(&response.text)?;
+                response.text = transcription.text;
+                response.segments = transcription.segments;
+                response.language = transcription.language;
+            }
+
+            Ok(response)
         } else {
             let text = String::from_utf8_lossy(&response.into_body().await?).into();
             Err(TranscriptionError::ProviderError(text))
@@ -169,7 +269,34 @@ impl TryFrom<GenerateContentResponse>
 
         Ok(transcription::TranscriptionResponse {
             text: text.to_string(),
+            segments: vec![],
+            language: None,
             response,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segmented_transcription() {
+        let schema = segmented_transcription_schema(true, false);
+        assert!(schema["properties"]["segments"]["items"]["properties"]["words"].is_object());
+        assert!(schema["properties"]["segments"]["items"]["properties"]["speaker"].is_null());
+
+        let transcription: SegmentedTranscription = serde_json::from_value(json!({
+            "text": "Hello. Hi!",
+            "segments": [
+                { "text": "Hello.", "start": 0.0, "end": 0.8, "speaker": "A" },
+                { "text": "Hi!", "start": 1.0, "end": 1.3, "speaker": "B" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            transcription.segments[1],
+            TranscriptionSegment::new("Hi!", 1.0, 1.3).with_speaker("B")
+        );
+    }
+}
@@ -8,6 +8,8 @@
 //!
 //! let gpt4o = client.completion_model(groq::GPT_4O);
 //! ```
+use crate::completion::SamplingParam;
+use crate::providers::openai::completion::SamplingParams;
 use bytes::Bytes;
 use http::Request;
 use serde_json::Map;
@@ -19,15 +21,17 @@ use super::openai::{
     CompletionResponse, Message as OpenAIMessage, StreamingToolCall, TranscriptionResponse, Usage,
 };
 use crate::client::{
-    self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
-    ProviderClient,
+    self, BearerAuth, Capabilities, Capable, DebugExt, ModelInfo, ModelListingClient,
+    ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
 };
 use crate::completion::GetTokenUsage;
 use crate::http_client::multipart::Part;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::http_client::{self, HttpClientExt, MultipartForm};
 use crate::json_utils::empty_or_none;
+use crate::providers::openai::transcription::{transcription_path, with_output_options};
 use crate::providers::openai::{AssistantContent, Function, ToolType};
+use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use async_stream::stream;
 use futures::StreamExt;
 
@@ -106,6 +110,44 @@ impl ProviderClient for Client {
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<Model>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+    #[serde(default)]
+    owned_by: Option<String>,
+    #[serde(default)]
+    context_window: Option<u64>,
+    #[serde(default)]
+    max_completion_tokens: Option<u64>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        Self {
+            owned_by: model.owned_by,
+            context_window: model.context_window,
+            max_output_tokens: model.max_completion_tokens,
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let models: ModelList = self.get_models("/models").await?;
+
+        Ok(models.data.into_iter().map(ModelInfo::from).collect())
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct ApiErrorResponse {
     message: String,
@@ -157,12 +199,45 @@ pub enum ReasoningFormat {
     Hidden,
 }
 
+/// The reasoning effort of the Groq models supporting it. The supported values depend on the model.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    None,
+    Default,
+    Low,
+    Medium,
+    High,
+}
+
+impl From<completion::ReasoningEffort> for ReasoningEffort {
+    fn from(effort: completion::ReasoningEffort) -> Self {
+        match effort {
+            completion::ReasoningEffort::None => Self::None,
+            completion::ReasoningEffort::Minimal | completion::ReasoningEffort::Low => Self::Low,
+            completion::ReasoningEffort::Medium => Self::Medium,
+            completion::ReasoningEffort::High => Self::High,
+        }
+    }
+}
+
+/// The sampling parameters supported by the Groq API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct GroqCompletionRequest {
     model: String,
     pub messages: Vec<OpenAIMessage>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -183,6 +258,10 @@ impl TryFrom<(&str, CompletionRequest)> for GroqCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        let sampling = req.sampling_unless_overridden(SamplingParam::name);
+
+        req.check_logprobs_unsupported("Groq")?;
+        req.check_hosted_tools_unsupported("Groq")?;
         // Build up the order of messages (context, chat_history, prompt)
         let mut partial_history = vec![];
         if let Some(docs) = req.normalized_documents() {
@@ -213,17 +292,23 @@ impl TryFrom<(&str, CompletionRequest)> for GroqCompletionRequest {
             .map(crate::providers::openai::ToolChoice::try_from)
             .transpose()?;
 
-        let additional_params: Option<GroqAdditionalParameters> =
+        let mut additional_params: Option<GroqAdditionalParameters> =
             if let Some(params) = req.additional_params {
                 Some(serde_json::from_value(params)?)
             } else {
                 None
             };
 
+        if let Some(effort) = req.reasoning.as_ref().and_then(|r| r.resolved_effort()) {
+            let params = additional_params.get_or_insert_with(Default::default);
+            params.reasoning_effort.get_or_insert(effort.into());
+        }
+
         Ok(Self {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new("Groq", sampling, SUPPORTED_SAMPLING_PARAMS)?,
             tools: req
                 .tools
                 .clone()
@@ -244,6 +329,9 @@ pub struct GroqAdditionalParameters {
     /// The reasoning format. See Groq's API docs for more details.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reasoning_format: Option<ReasoningFormat>,
+    /// The reasoning effort. See Groq's API docs for more details.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning_effort: Option<ReasoningEffort>,
     /// Whether or not to include reasoning. See Groq's API docs for more details.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub include_reasoning: Option<bool>,
@@ -457,13 +545,16 @@ where
         transcription::TranscriptionResponse<Self::Response>,
         transcription::TranscriptionError,
     > {
-        let data = request.data;
+        let path = transcription_path(&request);
 
-        let mut body = MultipartForm::new()
+        let mut body = with_output_options(MultipartForm::new(), &request)
             .text("model", self.model.clone())
-            .part(Part::bytes("file", data).filename(request.filename.clone()));
+            .part(Part::bytes("file", request.data).filename(request.filename.clone()));
 
-        if let Some(language) = request.language {
+        // Translations are always in English
+        if let Some(language) = request.language
+            && !request.translate
+        {
             body = body.text("language", language);
         }
 
@@ -484,11 +575,7 @@ where
             }
         }
 
-        let req = self
-            .client
-            .post("/audio/transcriptions")?
-            .body(body)
-            .unwrap();
+        let req = self.client.post(path)?.body(body).unwrap();
 
         let response = self.client.send_multipart::<Bytes>(req).await.unwrap();
 
@@ -527,10 +614,16 @@ enum StreamingDelta {
 #[derive(Deserialize, Debug)]
 struct StreamingChoice {
     delta: StreamingDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
 }
 
 #[derive(Deserialize, Debug)]
 struct StreamingCompletionChunk {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
     choices: Vec<StreamingChoice>,
     usage: Option<Usage>,
 }
@@ -576,6 +669,7 @@ where
         let mut text_response = String::new();
 
         let mut calls: HashMap<usize, (String, String, String)> = HashMap::new();
+        let mut metadata_yielded = false;
 
         while let Some(event_result) = event_source.next().await {
             match event_result {
@@ -594,6 +688,21 @@ where
                         continue;
                     };
 
+                    if !metadata_yielded && (data.id.is_some() || data.model.is_some()) {
+                        metadata_yielded = true;
+                        yield Ok(crate::streaming::RawStreamingChoice::Metadata(
+                            crate::streaming::ResponseMetadata::new(data.id.clone(), data.model.clone(), None),
+                        ));
+                    }
+
+                    if let Some(reason) = data.choices.first().and_then(|choice| choice.finish_reason.as_deref()) {
+                        yield Ok(crate::streaming::RawStreamingChoice::Metadata(
+                            crate::streaming::ResponseMetadata::finish_reason(
+                                crate::providers::openai::completion::map_finish_reason(reason),
+                            ),
+                        ));
+                    }
+
                     if let Some(choice) = data.choices.first() {
                         match &choice.delta {
                             StreamingDelta::Reasoning { reasoning } => {
@@ -715,6 +824,7 @@ where
 
 #[cfg(test)]
 mod tests {
+    use super::{ModelInfo, ModelList, SUPPORTED_SAMPLING_PARAMS, SamplingParams};
     use crate::{
         OneOrMany,
         providers::{
@@ -744,6 +854,7 @@ mod tests {
                 name: None,
             }],
             stream: false,
+            sampling: Default::default(),
             additional_params: Some(additional_params),
         };
 
@@ -768,4 +879,56 @@ mod tests {
             })
         )
     }
+
+    #[test]
+    fn test_sampling_params_drop_unsupported() {
+        let sampling = crate::completion::SamplingParams {
+            top_p: Some(0.5),
+            top_k: Some(40),
+            seed: Some(7),
+            ..Default::default()
+        };
+
+        let sampling = SamplingParams::new("Groq", sampling, SUPPORTED_SAMPLING_PARAMS).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&sampling).unwrap(),
+            serde_json::json!({
+                "top_p": 0.5,
+                "seed": 7
+            })
+        );
+    }
+
+    #[test]
+    fn test_model_list() {
+        let models: ModelList = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [{
+                "id": "llama-3.3-70b-versatile",
+                "object": "model",
+                "created": 1733447754,
+                "owned_by": "Meta",
+                "active": true,
+                "context_window": 131072,
+                "public_apps": null,
+                "max_completion_tokens": 32768
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            models
+                .data
+                .into_iter()
+                .map(ModelInfo::from)
+                .collect::<Vec<_>>(),
+            vec![ModelInfo {
+                owned_by: Some("Meta".to_string()),
+                context_window: Some(131_072),
+                max_output_tokens: Some(32_768),
+                ..ModelInfo::new("llama-3.3-70b-versatile")
+            }]
+        );
+    }
 }
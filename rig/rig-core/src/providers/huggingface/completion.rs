@@ -1,7 +1,10 @@
 use super::client::Client;
 use crate::completion::GetTokenUsage;
+use crate::completion::SamplingParam;
 use crate::http_client::HttpClientExt;
 use crate::providers::openai::StreamingCompletionResponse;
+use crate::providers::openai::completion::map_finish_reason;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use crate::telemetry::SpanCombinator;
 use crate::{
     OneOrMany,
@@ -310,9 +313,10 @@ impl TryFrom<message::Message> for Vec<Message> {
                                 name: id,
                                 arguments: None,
                                 content: content.try_map(|content| match content {
-                                    message::ToolResultContent::Text(message::Text { text }) => {
-                                        Ok(text)
-                                    }
+                                    message::ToolResultContent::Text(message::Text {
+                                        text,
+                                        ..
+                                    }) => Ok(text),
                                     _ => Err(message::MessageError::ConversionError(
                                         "Tool result content does not support non-text".into(),
                                     )),
@@ -369,6 +373,8 @@ impl TryFrom<message::Message> for Vec<Message> {
                             message::AssistantContent::Image(_) => {
                                 panic!("Image content is not supported on HuggingFace via Rig");
                             }
+                            // Hosted tool calls can only be sent back to the provider that made them
+                            message::AssistantContent::HostedToolCall(_) => {}
                         }
                         (texts, tools)
                     },
@@ -607,17 +613,40 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .map(|choice| map_finish_reason(&choice.finish_reason)),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: response.choices.first().and_then(|choice| {
+                crate::providers::openai::completion::token_logprobs(&choice.logprobs)
+            }),
             raw_response: response,
         })
     }
 }
 
+/// The sampling parameters supported by the Hugging Face API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct HuggingfaceCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -630,6 +659,7 @@ impl TryFrom<(&str, CompletionRequest)> for HuggingfaceCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("Hugging Face")?;
         let mut full_history: Vec<Message> = match &req.preamble {
             Some(preamble) => vec![Message::system(preamble)],
             None => vec![],
@@ -661,6 +691,12 @@ impl TryFrom<(&str, CompletionRequest)> for HuggingfaceCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "Hugging Face",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
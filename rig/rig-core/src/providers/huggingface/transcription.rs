@@ -2,7 +2,9 @@ use crate::http_client::HttpClientExt;
 use crate::providers::huggingface::Client;
 use crate::providers::huggingface::completion::ApiResponse;
 use crate::transcription;
-use crate::transcription::TranscriptionError;
+use crate::transcription::{
+    TimestampGranularity, TranscriptionError, TranscriptionSegment, TranscriptionWord,
+};
 use crate::wasm_compat::WasmCompatSync;
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
@@ -17,6 +19,47 @@ pub const WHISPER_SMALL: &str = "openai/whisper-small";
 #[derive(Debug, Deserialize)]
 pub struct TranscriptionResponse {
     pub text: String,
+    /// The timed chunks of the transcription, returned when `return_timestamps` is set
+    #[serde(default)]
+    pub chunks: Vec<TranscriptionChunk>,
+}
+
+/// A timed chunk of a transcription, a segment or a word depending on `return_timestamps`. The
+/// end of the last chunk is missing when the audio was cut mid-sentence.
+#[derive(Debug, Deserialize)]
+pub struct TranscriptionChunk {
+    pub text: String,
+    pub timestamp: (f64, Option<f64>),
+}
+
+impl TranscriptionChunk {
+    fn end(&self) -> f64 {
+        self.timestamp.1.unwrap_or(self.timestamp.0)
+    }
+}
+
+impl TranscriptionResponse {
+    /// The chunks as segments, or as the words of a single segment when they are word chunks.
+    fn segments(&self, words: bool) -> Vec<TranscriptionSegment> {
+        if words {
+            let words = self
+                .chunks
+                .iter()
+                .map(|chunk| {
+                    TranscriptionWord::new(chunk.text.trim(), chunk.timestamp.0, chunk.end())
+                })
+                .collect();
+
+            transcription::segments_with_words(&self.text, vec![], words)
+        } else {
+            self.chunks
+                .iter()
+                .map(|chunk| {
+                    TranscriptionSegment::new(chunk.text.trim(), chunk.timestamp.0, chunk.end())
+                })
+                .collect()
+        }
+    }
 }
 
 impl TryFrom<TranscriptionResponse>
@@ -27,6 +70,8 @@ impl TryFrom<TranscriptionResponse>
     fn try_from(value: TranscriptionResponse) -> Result<Self, Self::Error> {
         Ok(transcription::TranscriptionResponse {
             text: value.text.clone(),
+            segments: value.segments(false),
+            language: None,
             response: value,
         })
     }
@@ -63,12 +108,34 @@ where
         &self,
         request: transcription::TranscriptionRequest,
     ) -> Result<transcription::TranscriptionResponse<Self::Response>, TranscriptionError> {
-        let data = request.data;
-        let data = BASE64_STANDARD.encode(data);
+        let word_timestamps = request
+            .timestamp_granularities
+            .contains(&TimestampGranularity::Word);
+        let mut parameters = serde_json::Map::new();
+
+        if word_timestamps {
+            parameters.insert("return_timestamps".to_string(), json!("word"));
+        } else if request
+            .output_format()
+            .is_some_and(|format| format != transcription::TranscriptionFormat::Json)
+        {
+            parameters.insert("return_timestamps".to_string(), json!(true));
+        }
+
+        if request.translate {
+            parameters.insert(
+                "generate_kwargs".to_string(),
+                json!({ "task": "translate" }),
+            );
+        }
 
-        let request = json!({
-            "inputs": data
-        });
+        let data = BASE64_STANDARD.encode(request.data);
+
+        let request = if parameters.is_empty() {
+            json!({ "inputs": data })
+        } else {
+            json!({ "inputs": data, "parameters": parameters })
+        };
 
         let route = self
             .client
@@ -90,7 +157,14 @@ where
             let body: Vec<u8> = response.into_body().await?;
             let body: ApiResponse<TranscriptionResponse> = serde_json::from_slice(&body)?;
             match body {
-                ApiResponse::Ok(response) => response.try_into(),
+                ApiResponse::Ok(response) => {
+                    let segments = response.segments(word_timestamps);
+                    let mut response: transcription::TranscriptionResponse<_> =
+                        response.try_into()?;
+                    response.segments = segments;
+
+                    Ok(response)
+                }
                 ApiResponse::Err(err) => Err(TranscriptionError::ProviderError(err.to_string())),
             }
         } else {
@@ -101,3 +175,37 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_chunks() {
+        let response: TranscriptionResponse = serde_json::from_value(json!({
+            "text": " Hello there",
+            "chunks": [
+                { "text": " Hello", "timestamp": [0.0, 0.5] },
+                { "text": " there", "timestamp": [0.5, null] }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            response.segments(false),
+            vec![
+                TranscriptionSegment::new("Hello", 0.0, 0.5),
+                TranscriptionSegment::new("there", 0.5, 0.5),
+            ]
+        );
+        assert_eq!(
+            response.segments(true),
+            vec![
+                TranscriptionSegment::new("Hello there", 0.0, 0.5).with_words(vec![
+                    TranscriptionWord::new("Hello", 0.0, 0.5),
+                    TranscriptionWord::new("there", 0.5, 0.5),
+                ])
+            ]
+        );
+    }
+}
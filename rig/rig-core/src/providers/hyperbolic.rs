@@ -9,6 +9,8 @@
 //! let llama_3_1_8b = client.completion_model(hyperbolic::LLAMA_3_1_8B);
 //! ```
 use super::openai::{AssistantContent, send_compatible_streaming_request};
+use crate::completion::SamplingParam;
+use crate::providers::openai::completion::SamplingParams;
 
 use crate::client::{self, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder};
 use crate::client::{BearerAuth, ProviderClient};
@@ -16,6 +18,7 @@ use crate::http_client::{self, HttpClientExt};
 use crate::streaming::StreamingCompletionResponse;
 
 use crate::providers::openai;
+use crate::providers::openai::completion::map_finish_reason;
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -62,6 +65,8 @@ impl<H> Capabilities<H> for HyperbolicExt {
     type AudioGeneration = Capable<AudioGenerationModel<H>>;
 }
 
+crate::client::model_listing::impl_openai_compatible_model_listing!(HyperbolicExt, "/v1/models");
+
 impl DebugExt for HyperbolicExt {}
 
 impl ProviderBuilder for HyperbolicBuilder {
@@ -234,6 +239,13 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .map(|choice| map_finish_reason(&choice.finish_reason)),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: None,
             raw_response: response,
         })
     }
@@ -246,12 +258,23 @@ pub struct Choice {
     pub finish_reason: String,
 }
 
+/// The sampling parameters supported by the Hyperbolic API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::TopK,
+    SamplingParam::Stop,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct HyperbolicCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<serde_json::Value>,
 }
@@ -263,6 +286,8 @@ impl TryFrom<(&str, CompletionRequest)> for HyperbolicCompletionRequest {
         if req.tool_choice.is_some() {
             tracing::warn!("WARNING: `tool_choice` not supported on Hyperbolic");
         }
+        req.check_logprobs_unsupported("Hyperbolic")?;
+        req.check_hosted_tools_unsupported("Hyperbolic")?;
 
         if !req.tools.is_empty() {
             tracing::warn!("WARNING: `tools` not supported on Hyperbolic");
@@ -294,6 +319,11 @@ impl TryFrom<(&str, CompletionRequest)> for HyperbolicCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "Hyperbolic",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
             additional_params: req.additional_params,
         })
     }
@@ -604,7 +634,9 @@ use tracing::{Instrument, info_span};
 mod audio_generation {
     use super::{ApiResponse, Client};
     use crate::audio_generation;
-    use crate::audio_generation::{AudioGenerationError, AudioGenerationRequest};
+    use crate::audio_generation::{
+        AudioGenerationError, AudioGenerationRequest, StreamingAudioGenerationResponse,
+    };
     use crate::http_client::{self, HttpClientExt};
     use base64::Engine;
     use base64::prelude::BASE64_STANDARD;
@@ -618,6 +650,73 @@ mod audio_generation {
         pub language: String,
     }
 
+    impl<T> AudioGenerationModel<T>
+    where
+        T: HttpClientExt + Clone + Default + std::fmt::Debug + Send + 'static,
+    {
+        async fn generate(
+            &self,
+            text: &str,
+            voice: &str,
+            speed: f32,
+        ) -> Result<
+            audio_generation::AudioGenerationResponse<AudioGenerationResponse>,
+            AudioGenerationError,
+        > {
+            let request = json!({
+                "language": self.language,
+                "speaker": voice,
+                "text": text,
+                "speed": speed
+            });
+
+            let body = serde_json::to_vec(&request)?;
+
+            let req = self
+                .client
+                .post("/v1/audio/generation")?
+                .body(body)
+                .map_err(http_client::Error::from)?;
+
+            let response = self.client.send::<_, Bytes>(req).await?;
+            let status = response.status();
+            let response_body = response.into_body().into_future().await?.to_vec();
+
+            if !status.is_success() {
+                return Err(AudioGenerationError::ProviderError(format!(
+                    "{status}: {}",
+                    String::from_utf8_lossy(&response_body)
+                )));
+            }
+
+            match serde_json::from_slice::<ApiResponse<AudioGenerationResponse>>(&response_body)? {
+                ApiResponse::Ok(response) => response.try_into(),
+                ApiResponse::Err(err) => Err(AudioGenerationError::ProviderError(err.message)),
+            }
+        }
+    }
+
+    /// Splits `text` after each sentence terminator followed by whitespace, keeping the terminator.
+    fn split_sentences(text: &str) -> Vec<&str> {
+        let mut sentences = Vec::new();
+        let mut start = 0;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((_, c)) = chars.next() {
+            if matches!(c, '.' | '!' | '?' | '\n')
+                && let Some(&(next, next_char)) = chars.peek()
+                && next_char.is_whitespace()
+            {
+                sentences.push(text[start..next].trim());
+                start = next;
+            }
+        }
+        sentences.push(text[start..].trim());
+
+        sentences.retain(|sentence| !sentence.is_empty());
+        sentences
+    }
+
     #[derive(Clone, Deserialize)]
     pub struct AudioGenerationResponse {
         audio: String,
@@ -659,36 +758,43 @@ mod audio_generation {
             request: AudioGenerationRequest,
         ) -> Result<audio_generation::AudioGenerationResponse<Self::Response>, AudioGenerationError>
         {
-            let request = json!({
-                "language": self.language,
-                "speaker": request.voice,
-                "text": request.text,
-                "speed": request.speed
-            });
-
-            let body = serde_json::to_vec(&request)?;
-
-            let req = self
-                .client
-                .post("/v1/audio/generation")?
-                .body(body)
-                .map_err(http_client::Error::from)?;
+            self.generate(&request.text, &request.voice, request.speed)
+                .await
+        }
 
-            let response = self.client.send::<_, Bytes>(req).await?;
-            let status = response.status();
-            let response_body = response.into_body().into_future().await?.to_vec();
+        /// Hyperbolic has no streaming endpoint, the text is instead synthesised one sentence at a
+        /// time. Each chunk is a self-contained audio clip of a sentence.
+        async fn stream_audio_generation(
+            &self,
+            request: AudioGenerationRequest,
+        ) -> Result<StreamingAudioGenerationResponse, AudioGenerationError> {
+            let model = self.clone();
+
+            Ok(Box::pin(async_stream::stream! {
+                for sentence in split_sentences(&request.text) {
+                    match model.generate(sentence, &request.voice, request.speed).await {
+                        Ok(response) => yield Ok(Bytes::from(response.audio)),
+                        Err(err) => {
+                            yield Err(err);
+                            break;
+                        }
+                    }
+                }
+            }))
+        }
+    }
 
-            if !status.is_success() {
-                return Err(AudioGenerationError::ProviderError(format!(
-                    "{status}: {}",
-                    String::from_utf8_lossy(&response_body)
-                )));
-            }
+    #[cfg(test)]
+    mod tests {
+        use super::split_sentences;
 
-            match serde_json::from_slice::<ApiResponse<AudioGenerationResponse>>(&response_body)? {
-                ApiResponse::Ok(response) => response.try_into(),
-                ApiResponse::Err(err) => Err(AudioGenerationError::ProviderError(err.message)),
-            }
+        #[test]
+        fn test_split_sentences() {
+            assert_eq!(
+                split_sentences("Hello there! It is 3.5 degrees. Is it cold?\nYes"),
+                vec!["Hello there!", "It is 3.5 degrees.", "Is it cold?", "Yes"]
+            );
+            assert!(split_sentences("  ").is_empty());
         }
     }
 }
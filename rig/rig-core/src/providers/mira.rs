@@ -14,6 +14,7 @@ use crate::client::{
 use crate::http_client::{self, HttpClientExt};
 use crate::message::{Document, DocumentSourceKind};
 use crate::providers::openai;
+use crate::providers::openai::completion::map_finish_reason;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
 use crate::{
@@ -106,13 +107,11 @@ impl TryFrom<RawMessage> for message::Message {
     fn try_from(raw: RawMessage) -> Result<Self, Self::Error> {
         match raw.role.as_str() {
             "user" => Ok(message::Message::User {
-                content: OneOrMany::one(UserContent::Text(message::Text { text: raw.content })),
+                content: OneOrMany::one(UserContent::Text(message::Text::from(raw.content))),
             }),
             "assistant" => Ok(message::Message::Assistant {
                 id: None,
-                content: OneOrMany::one(AssistantContent::Text(message::Text {
-                    text: raw.content,
-                })),
+                content: OneOrMany::one(AssistantContent::Text(message::Text::from(raw.content))),
             }),
             _ => Err(CompletionError::ResponseError(format!(
                 "Unsupported message role: {}",
@@ -219,6 +218,9 @@ impl TryFrom<(&str, CompletionRequest)> for MiraCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_logprobs_unsupported("Mira")?;
+        req.check_hosted_tools_unsupported("Mira")?;
+
         let mut messages = Vec::new();
 
         if let Some(content) = &req.preamble {
@@ -281,6 +283,8 @@ impl TryFrom<(&str, CompletionRequest)> for MiraCompletionRequest {
             });
         }
 
+        req.sampling.check_supported("Mira", &[])?;
+
         Ok(Self {
             model: model.to_string(),
             messages,
@@ -562,9 +566,27 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
             )
         })?;
 
+        let (finish_reason, id, model) = match &response {
+            CompletionResponse::Structured {
+                id, model, choices, ..
+            } => (
+                choices
+                    .first()
+                    .and_then(|choice| choice.finish_reason.as_deref())
+                    .map(map_finish_reason),
+                Some(id.clone()),
+                Some(model.clone()),
+            ),
+            CompletionResponse::Simple(_) => (None, None, None),
+        };
+
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason,
+            id,
+            model,
+            logprobs: None,
             raw_response: response,
         })
     }
@@ -657,11 +679,11 @@ impl TryFrom<serde_json::Value> for Message {
 
         match role {
             "user" => Ok(Message::User {
-                content: OneOrMany::one(UserContent::Text(message::Text { text: content })),
+                content: OneOrMany::one(UserContent::Text(message::Text::from(content))),
             }),
             "assistant" => Ok(Message::Assistant {
                 id: None,
-                content: OneOrMany::one(AssistantContent::Text(message::Text { text: content })),
+                content: OneOrMany::one(AssistantContent::Text(message::Text::from(content))),
             }),
             _ => Err(CompletionError::ResponseError(format!(
                 "Unsupported message role: {role}"
@@ -707,9 +729,9 @@ mod tests {
             Message::Assistant { content, .. } => {
                 assert_eq!(
                     content.first(),
-                    AssistantContent::Text(message::Text {
-                        text: "Hello there, how may I assist you today?".to_string()
-                    })
+                    AssistantContent::Text(message::Text::from(
+                        "Hello there, how may I assist you today?".to_string()
+                    ))
                 );
             }
             _ => panic!("Expected assistant message"),
@@ -719,9 +741,9 @@ mod tests {
             Message::User { content } => {
                 assert_eq!(
                     content.first(),
-                    UserContent::Text(message::Text {
-                        text: "What can you help me with?".to_string()
-                    })
+                    UserContent::Text(message::Text::from(
+                        "What can you help me with?".to_string()
+                    ))
                 );
             }
             _ => panic!("Expected user message"),
@@ -732,9 +754,9 @@ mod tests {
             Message::Assistant { content, .. } => {
                 assert_eq!(
                     content.first(),
-                    AssistantContent::Text(message::Text {
-                        text: "Hello there, how may I assist you today?".to_string()
-                    })
+                    AssistantContent::Text(message::Text::from(
+                        "Hello there, how may I assist you today?".to_string()
+                    ))
                 );
             }
             _ => panic!("Expected assistant message"),
@@ -1,9 +1,10 @@
 use crate::{
     client::{
-        self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
-        ProviderClient,
+        self, BearerAuth, Capabilities, Capable, DebugExt, Modality, ModelInfo, ModelListingClient,
+        ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
     },
-    http_client,
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -99,3 +100,119 @@ pub(crate) enum ApiResponse<T> {
     Ok(T),
     Err(ApiErrorResponse),
 }
+
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<Model>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    owned_by: Option<String>,
+    #[serde(default)]
+    max_context_length: Option<u64>,
+    #[serde(default)]
+    capabilities: ModelCapabilities,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ModelCapabilities {
+    #[serde(default)]
+    completion_chat: bool,
+    #[serde(default)]
+    function_calling: bool,
+    #[serde(default)]
+    vision: bool,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        let capabilities = model.capabilities;
+        let is_embedding = model.id.contains("embed");
+
+        let mut input_modalities = vec![Modality::Text];
+        if capabilities.vision {
+            input_modalities.push(Modality::Image);
+        }
+
+        Self {
+            name: model.name,
+            owned_by: model.owned_by,
+            context_window: model.max_context_length,
+            input_modalities,
+            output_modalities: if capabilities.completion_chat {
+                vec![Modality::Text]
+            } else {
+                vec![]
+            },
+            supports_tools: Some(capabilities.function_calling),
+            is_embedding: Some(is_embedding),
+            embedding_dimensions: (model.id == super::MISTRAL_EMBED).then_some(1_024),
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let models: ModelList = self.get_models("v1/models").await?;
+
+        Ok(models.data.into_iter().map(ModelInfo::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let models: ModelList = serde_json::from_value(serde_json::json!({
+            "object": "list",
+            "data": [{
+                "id": "pixtral-large-latest",
+                "object": "model",
+                "owned_by": "mistralai",
+                "capabilities": {
+                    "completion_chat": true,
+                    "completion_fim": false,
+                    "function_calling": true,
+                    "fine_tuning": false,
+                    "vision": true
+                },
+                "name": "pixtral-large-2411",
+                "max_context_length": 131072,
+                "type": "base"
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            models
+                .data
+                .into_iter()
+                .map(ModelInfo::from)
+                .collect::<Vec<_>>(),
+            vec![ModelInfo {
+                name: Some("pixtral-large-2411".to_string()),
+                owned_by: Some("mistralai".to_string()),
+                context_window: Some(131_072),
+                input_modalities: vec![Modality::Text, Modality::Image],
+                output_modalities: vec![Modality::Text],
+                supports_tools: Some(true),
+                is_embedding: Some(false),
+                ..ModelInfo::new("pixtral-large-latest")
+            }]
+        );
+    }
+}
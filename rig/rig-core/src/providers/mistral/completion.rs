@@ -1,3 +1,4 @@
+use crate::providers::openai::completion::{LogprobsParams, map_finish_reason};
 use async_stream::stream;
 use serde::{Deserialize, Serialize};
 use std::{convert::Infallible, str::FromStr};
@@ -139,7 +140,7 @@ impl TryFrom<message::Message> for Vec<Message> {
                                 tool_call_id: call_id_key,
                             });
                         }
-                        message::UserContent::Text(message::Text { text }) => {
+                        message::UserContent::Text(message::Text { text, .. }) => {
                             other_messages.push(Message::User { content: text });
                         }
                         _ => {}
@@ -162,6 +163,8 @@ impl TryFrom<message::Message> for Vec<Message> {
                             message::AssistantContent::Image(_) => {
                                 panic!("Image content is not currently supported on Mistral via Rig");
                             }
+                            // Hosted tool calls can only be sent back to the provider that made them
+                            message::AssistantContent::HostedToolCall(_) => {}
                         }
                         (texts, tools)
                     },
@@ -328,6 +331,10 @@ pub(super) struct MistralCompletionRequest {
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -336,10 +343,53 @@ pub(super) struct MistralCompletionRequest {
     pub additional_params: Option<serde_json::Value>,
 }
 
+/// The sampling parameters supported by the Mistral chat completions API.
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct SamplingParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    random_seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f64>,
+}
+
+impl TryFrom<completion::SamplingParams> for SamplingParams {
+    type Error = CompletionError;
+
+    fn try_from(sampling: completion::SamplingParams) -> Result<Self, Self::Error> {
+        use completion::SamplingParam;
+
+        sampling.check_supported(
+            "Mistral",
+            &[
+                SamplingParam::TopP,
+                SamplingParam::Stop,
+                SamplingParam::Seed,
+                SamplingParam::PresencePenalty,
+                SamplingParam::FrequencyPenalty,
+            ],
+        )?;
+
+        Ok(Self {
+            top_p: sampling.top_p,
+            stop: sampling.stop,
+            random_seed: sampling.seed,
+            presence_penalty: sampling.presence_penalty,
+            frequency_penalty: sampling.frequency_penalty,
+        })
+    }
+}
+
 impl TryFrom<(&str, CompletionRequest)> for MistralCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("Mistral")?;
         let mut full_history: Vec<Message> = match &req.preamble {
             Some(preamble) => vec![Message::system(preamble.clone())],
             None => vec![],
@@ -371,6 +421,13 @@ impl TryFrom<(&str, CompletionRequest)> for MistralCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: req
+                .sampling_unless_overridden(|param| match param {
+                    completion::SamplingParam::Seed => "random_seed",
+                    param => param.name(),
+                })
+                .try_into()?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
@@ -521,6 +578,17 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .map(|choice| map_finish_reason(&choice.finish_reason)),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: response
+                .choices
+                .first()
+                .and_then(|choice| choice.logprobs.as_ref())
+                .and_then(crate::providers::openai::completion::token_logprobs),
             raw_response: response,
         })
     }
@@ -630,9 +698,18 @@ where
                     message::AssistantContent::Image(_) => {
                         panic!("Image content is not supported on Mistral via Rig")
                     }
+                    message::AssistantContent::HostedToolCall(call) => {
+                        yield Ok(RawStreamingChoice::HostedToolCall(call.clone()))
+                    }
                 }
             }
 
+            yield Ok(RawStreamingChoice::Metadata(crate::streaming::ResponseMetadata::new(
+                resp.id.clone(),
+                resp.model.clone(),
+                resp.finish_reason.clone(),
+            )));
+
             yield Ok(RawStreamingChoice::FinalResponse(resp.raw_response.clone()));
         };
 
@@ -709,4 +786,21 @@ mod tests {
         assert_eq!(created, 1702256327);
         assert_eq!(choices.len(), 1);
     }
+
+    #[test]
+    fn test_request_logprobs() {
+        let request = completion::CompletionRequestBuilder::new(
+            crate::testing::MockCompletionModel::new(),
+            "Hi",
+        )
+        .top_logprobs(3)
+        .build();
+
+        let request =
+            MistralCompletionRequest::try_from(("mistral-small-latest", request)).unwrap();
+        let request = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(request["logprobs"], serde_json::json!(true));
+        assert_eq!(request["top_logprobs"], serde_json::json!(3));
+    }
 }
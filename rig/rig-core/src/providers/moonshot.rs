@@ -12,7 +12,9 @@ use crate::client::{
     self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
     ProviderClient,
 };
+use crate::completion::SamplingParam;
 use crate::http_client::HttpClientExt;
+use crate::providers::openai::completion::SamplingParams;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
 use crate::{
@@ -71,6 +73,8 @@ impl<H> Capabilities<H> for MoonshotExt {
     type AudioGeneration = Nothing;
 }
 
+crate::client::model_listing::impl_openai_compatible_model_listing!(MoonshotExt, "/models");
+
 pub type Client<H = reqwest::Client> = client::Client<MoonshotExt, H>;
 pub type ClientBuilder<H = reqwest::Client> =
     client::ClientBuilder<MoonshotBuilder, MoonshotApiKey, H>;
@@ -113,12 +117,22 @@ enum ApiResponse<T> {
 
 pub const MOONSHOT_CHAT: &str = "moonshot-v1-128k";
 
+/// The sampling parameters supported by the Moonshot API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct MoonshotCompletionRequest {
     model: String,
     pub messages: Vec<openai::Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<openai::ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -133,6 +147,10 @@ impl TryFrom<(&str, CompletionRequest)> for MoonshotCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        let sampling = req.sampling_unless_overridden(SamplingParam::name);
+
+        req.check_logprobs_unsupported("Moonshot")?;
+        req.check_hosted_tools_unsupported("Moonshot")?;
         // Build up the order of messages (context, chat_history, prompt)
         let mut partial_history = vec![];
         if let Some(docs) = req.normalized_documents() {
@@ -167,6 +185,7 @@ impl TryFrom<(&str, CompletionRequest)> for MoonshotCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new("Moonshot", sampling, SUPPORTED_SAMPLING_PARAMS)?,
             max_tokens: req.max_tokens,
             tools: req
                 .tools
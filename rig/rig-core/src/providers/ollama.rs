@@ -39,12 +39,15 @@
 //! let extractor = client.extractor::<serde_json::Value>("llama3.2");
 //! ```
 use crate::client::{
-    self, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder, ProviderClient,
+    self, Capabilities, Capable, DebugExt, Modality, ModelInfo, ModelListingClient,
+    ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
 };
-use crate::completion::{GetTokenUsage, Usage};
+use crate::completion::{GetTokenUsage, SamplingParam, Usage};
 use crate::http_client::{self, HttpClientExt};
 use crate::message::DocumentSourceKind;
+use crate::providers::openai::completion::SamplingParams;
 use crate::streaming::RawStreamingChoice;
+use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use crate::{
     OneOrMany,
     completion::{self, CompletionError, CompletionRequest},
@@ -128,6 +131,105 @@ impl ProviderClient for Client {
     }
 }
 
+// ---------- Model Listing ----------
+
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    #[serde(default)]
+    models: Vec<ListedModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListedModel {
+    name: String,
+}
+
+/// The response of the `api/show` endpoint, with the details of a model.
+#[derive(Debug, Deserialize)]
+struct ModelDetails {
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    model_info: serde_json::Map<String, Value>,
+}
+
+impl ModelDetails {
+    fn into_model_info(self, id: &str) -> ModelInfo {
+        let has = |capability: &str| self.capabilities.iter().any(|c| c == capability);
+        // The model parameters are prefixed by the architecture, e.g. `llama.context_length`
+        let param = |name: &str| {
+            self.model_info
+                .get("general.architecture")
+                .and_then(Value::as_str)
+                .and_then(|arch| self.model_info.get(&format!("{arch}.{name}")))
+                .and_then(Value::as_u64)
+        };
+        let is_embedding = has("embedding");
+
+        let mut input_modalities = vec![Modality::Text];
+        if has("vision") {
+            input_modalities.push(Modality::Image);
+        }
+
+        ModelInfo {
+            context_window: param("context_length"),
+            input_modalities,
+            output_modalities: if has("completion") {
+                vec![Modality::Text]
+            } else {
+                vec![]
+            },
+            supports_tools: Some(has("tools")),
+            supports_reasoning: Some(has("thinking")),
+            is_embedding: Some(is_embedding),
+            embedding_dimensions: param("embedding_length")
+                .filter(|_| is_embedding)
+                .map(|length| length as usize),
+            ..ModelInfo::new(id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    /// List the local models. Only their ids are returned, use `model_info` for their details.
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let models: ModelList = self.get_models("api/tags").await?;
+
+        Ok(models
+            .models
+            .into_iter()
+            .map(|model| ModelInfo::new(model.name))
+            .collect())
+    }
+
+    async fn model_info(&self, model: &str) -> Result<Option<ModelInfo>, ModelListingError> {
+        let body = serde_json::to_vec(&json!({ "model": model }))?;
+        let req = self
+            .post("api/show")?
+            .body(body)
+            .map_err(http_client::Error::from)?;
+
+        let response = HttpClientExt::send::<_, Vec<u8>>(self, req).await?;
+        let status = response.status();
+        if status == http::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let text = http_client::text(response).await?;
+        if !status.is_success() {
+            return Err(ModelListingError::ProviderError(format!(
+                "Failed with '{status}': {text}"
+            )));
+        }
+
+        let details: ModelDetails = serde_json::from_str(&text)?;
+        Ok(Some(details.into_model_info(model)))
+    }
+}
+
 // ---------- API Error and Response Structures ----------
 
 #[derive(Debug, Deserialize)]
@@ -297,6 +399,12 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 ..
             } => {
                 let mut assistant_contents = Vec::new();
+                // Add the model's thinking, if any.
+                if let Some(thinking) = thinking.as_deref()
+                    && !thinking.is_empty()
+                {
+                    assistant_contents.push(completion::AssistantContent::reasoning(thinking));
+                }
                 // Add the assistant's text content if any.
                 if !content.is_empty() {
                     assistant_contents.push(completion::AssistantContent::text(&content));
@@ -315,6 +423,10 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                 })?;
                 let prompt_tokens = resp.prompt_eval_count.unwrap_or(0);
                 let completion_tokens = resp.eval_count.unwrap_or(0);
+                let finish_reason = resp
+                    .done_reason
+                    .as_deref()
+                    .map(|reason| map_done_reason(reason, !tool_calls.is_empty()));
 
                 let raw_response = CompletionResponse {
                     model: resp.model,
@@ -343,6 +455,10 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                         output_tokens: completion_tokens,
                         total_tokens: prompt_tokens + completion_tokens,
                     },
+                    finish_reason,
+                    id: None,
+                    model: Some(raw_response.model.clone()),
+                    logprobs: None,
                     raw_response,
                 })
             }
@@ -353,6 +469,17 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
     }
 }
 
+/// Map an Ollama `done_reason` to a [completion::FinishReason].
+/// Ollama reports `stop` when the model calls tools.
+fn map_done_reason(reason: &str, has_tool_calls: bool) -> completion::FinishReason {
+    match reason {
+        "stop" if has_tool_calls => completion::FinishReason::ToolCalls,
+        "stop" => completion::FinishReason::Stop,
+        "length" => completion::FinishReason::Length,
+        other => completion::FinishReason::Other(other.to_string()),
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct OllamaCompletionRequest {
     model: String,
@@ -372,6 +499,9 @@ impl TryFrom<(&str, CompletionRequest)> for OllamaCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_logprobs_unsupported("Ollama")?;
+        req.check_hosted_tools_unsupported("Ollama")?;
+
         if req.tool_choice.is_some() {
             tracing::warn!("WARNING: `tool_choice` not supported for Ollama");
         }
@@ -399,7 +529,28 @@ impl TryFrom<(&str, CompletionRequest)> for OllamaCompletionRequest {
                 .collect::<Vec<_>>(),
         );
 
-        let mut think = false;
+        let mut think = req
+            .reasoning
+            .as_ref()
+            .is_some_and(|reasoning| !reasoning.is_disabled());
+
+        // Ollama's sampling options share their names with the OpenAI parameters
+        let sampling = SamplingParams::new(
+            "Ollama",
+            req.sampling,
+            &[
+                SamplingParam::TopP,
+                SamplingParam::TopK,
+                SamplingParam::Stop,
+                SamplingParam::Seed,
+                SamplingParam::PresencePenalty,
+                SamplingParam::FrequencyPenalty,
+            ],
+        )?;
+        let base_options = json_utils::merge(
+            json!({ "temperature": req.temperature }),
+            serde_json::to_value(sampling)?,
+        );
 
         // TODO: Fix this up to include the full range of ollama options
         let options = if let Some(mut extra) = req.additional_params {
@@ -408,9 +559,9 @@ impl TryFrom<(&str, CompletionRequest)> for OllamaCompletionRequest {
                     CompletionError::RequestError("`think` must be a bool".into())
                 })?;
             }
-            json_utils::merge(json!({ "temperature": req.temperature }), extra)
+            json_utils::merge(base_options, extra)
         } else {
-            json!({ "temperature": req.temperature })
+            base_options
         };
 
         Ok(Self {
@@ -634,6 +785,14 @@ where
                     let response: CompletionResponse = serde_json::from_slice(line)?;
 
                     if response.done {
+                        yield RawStreamingChoice::Metadata(crate::streaming::ResponseMetadata::new(
+                            None,
+                            Some(response.model.clone()),
+                            response
+                                .done_reason
+                                .as_deref()
+                                .map(|reason| map_done_reason(reason, !tool_calls_final.is_empty())),
+                        ));
                         span.record("gen_ai.usage.input_tokens", response.prompt_eval_count);
                         span.record("gen_ai.usage.output_tokens", response.eval_count);
                         let message = Message::Assistant {
@@ -675,8 +834,9 @@ where
 
                         for tool_call in tool_calls {
                             tool_calls_final.push(tool_call.clone());
+                            // Ollama has no tool call ids, use the name like non-streaming responses
                             yield RawStreamingChoice::ToolCall(
-                                crate::streaming::RawStreamingToolCall::new(String::new(), tool_call.function.name, tool_call.function.arguments)
+                                crate::streaming::RawStreamingToolCall::new(tool_call.function.name.clone(), tool_call.function.name, tool_call.function.arguments)
                             );
                         }
                     }
@@ -820,6 +980,7 @@ impl TryFrom<crate::message::Message> for Vec<Message> {
                             match content {
                                 crate::message::UserContent::Text(crate::message::Text {
                                     text,
+                                    ..
                                 }) => texts.push(text),
                                 crate::message::UserContent::Image(crate::message::Image {
                                     data: DocumentSourceKind::Base64(data),
@@ -878,6 +1039,8 @@ impl TryFrom<crate::message::Message> for Vec<Message> {
                                 "Ollama currently doesn't support images.".into(),
                             ));
                         }
+                        // Hosted tool calls can only be sent back to the provider that made them
+                        crate::message::AssistantContent::HostedToolCall(_) => {}
                     }
                 }
 
@@ -904,9 +1067,9 @@ impl From<Message> for crate::completion::Message {
     fn from(msg: Message) -> Self {
         match msg {
             Message::User { content, .. } => crate::completion::Message::User {
-                content: OneOrMany::one(crate::completion::message::UserContent::Text(Text {
-                    text: content,
-                })),
+                content: OneOrMany::one(crate::completion::message::UserContent::Text(Text::from(
+                    content,
+                ))),
             },
             Message::Assistant {
                 content,
@@ -914,9 +1077,9 @@ impl From<Message> for crate::completion::Message {
                 ..
             } => {
                 let mut assistant_contents =
-                    vec![crate::completion::message::AssistantContent::Text(Text {
-                        text: content,
-                    })];
+                    vec![crate::completion::message::AssistantContent::Text(
+                        Text::from(content),
+                    )];
                 for tc in tool_calls {
                     assistant_contents.push(
                         crate::completion::message::AssistantContent::tool_call(
@@ -933,9 +1096,9 @@ impl From<Message> for crate::completion::Message {
             }
             // System and ToolResult are converted to User message as needed.
             Message::System { content, .. } => crate::completion::Message::User {
-                content: OneOrMany::one(crate::completion::message::UserContent::Text(Text {
-                    text: content,
-                })),
+                content: OneOrMany::one(crate::completion::message::UserContent::Text(Text::from(
+                    content,
+                ))),
             },
             Message::ToolResult { name, content } => crate::completion::Message::User {
                 content: OneOrMany::one(message::UserContent::tool_result(
@@ -1275,9 +1438,9 @@ mod tests {
             id: None,
             content: crate::OneOrMany::many(vec![
                 crate::message::AssistantContent::Reasoning(reasoning_content),
-                crate::message::AssistantContent::Text(crate::message::Text {
-                    text: "The answer is X".to_string(),
-                }),
+                crate::message::AssistantContent::Text(crate::message::Text::from(
+                    "The answer is X".to_string(),
+                )),
             ])
             .unwrap(),
         };
@@ -1387,4 +1550,32 @@ mod tests {
             panic!("Expected Assistant message with thinking and tool calls");
         }
     }
+
+    #[test]
+    fn test_model_details() {
+        let details: ModelDetails = serde_json::from_value(json!({
+            "modelfile": "FROM llama3.2",
+            "details": { "family": "llama", "parameter_size": "3.2B" },
+            "model_info": {
+                "general.architecture": "llama",
+                "llama.context_length": 131072,
+                "llama.embedding_length": 3072
+            },
+            "capabilities": ["completion", "tools"]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            details.into_model_info("llama3.2"),
+            ModelInfo {
+                context_window: Some(131_072),
+                input_modalities: vec![Modality::Text],
+                output_modalities: vec![Modality::Text],
+                supports_tools: Some(true),
+                supports_reasoning: Some(false),
+                is_embedding: Some(false),
+                ..ModelInfo::new("llama3.2")
+            }
+        );
+    }
 }
@@ -1,13 +1,17 @@
 use crate::audio_generation::{
     self, AudioGenerationError, AudioGenerationRequest, AudioGenerationResponse,
+    StreamingAudioGenerationResponse,
 };
 use crate::http_client::{self, HttpClientExt};
+use crate::json_utils::merge;
 use crate::providers::openai::Client;
 use bytes::{Buf, Bytes};
+use futures::StreamExt;
 use serde_json::json;
 
 pub const TTS_1: &str = "tts-1";
 pub const TTS_1_HD: &str = "tts-1-hd";
+pub const GPT_4O_MINI_TTS: &str = "gpt-4o-mini-tts";
 
 #[derive(Clone)]
 pub struct AudioGenerationModel<T = reqwest::Client> {
@@ -22,6 +26,24 @@ impl<T> AudioGenerationModel<T> {
             model: model.into(),
         }
     }
+
+    fn create_request_body(
+        &self,
+        request: AudioGenerationRequest,
+    ) -> Result<Vec<u8>, AudioGenerationError> {
+        let mut body = json!({
+            "model": self.model,
+            "input": request.text,
+            "voice": request.voice,
+            "speed": request.speed,
+        });
+
+        if let Some(additional_params) = request.additional_params {
+            body = merge(body, additional_params);
+        }
+
+        Ok(serde_json::to_vec(&body)?)
+    }
 }
 
 impl<T> audio_generation::AudioGenerationModel for AudioGenerationModel<T>
@@ -40,12 +62,7 @@ where
         &self,
         request: AudioGenerationRequest,
     ) -> Result<AudioGenerationResponse<Self::Response>, AudioGenerationError> {
-        let body = serde_json::to_vec(&json!({
-            "model": self.model,
-            "input": request.text,
-            "voice": request.voice,
-            "speed": request.speed,
-        }))?;
+        let body = self.create_request_body(request)?;
 
         let req = self
             .client
@@ -76,4 +93,39 @@ where
             response: bytes,
         })
     }
+    /// The speech endpoint sends the audio with chunked transfer encoding, so the chunks are
+    /// forwarded as soon as they are received.
+    async fn stream_audio_generation(
+        &self,
+        request: AudioGenerationRequest,
+    ) -> Result<StreamingAudioGenerationResponse, AudioGenerationError> {
+        let body = self.create_request_body(request)?;
+
+        let req = self
+            .client
+            .post("/audio/speech")?
+            .body(body)
+            .map_err(http_client::Error::from)?;
+
+        let response = self.client.send_streaming(req).await?;
+        let status = response.status();
+        let mut byte_stream = response.into_body();
+
+        if !status.is_success() {
+            let mut text = Vec::new();
+            while let Some(chunk) = byte_stream.next().await {
+                text.extend_from_slice(&chunk?);
+            }
+
+            return Err(AudioGenerationError::ProviderError(format!(
+                "{}: {}",
+                status,
+                String::from_utf8_lossy(&text)
+            )));
+        }
+
+        Ok(Box::pin(
+            byte_stream.map(|chunk| chunk.map_err(AudioGenerationError::from)),
+        ))
+    }
 }
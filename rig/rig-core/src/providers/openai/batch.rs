@@ -0,0 +1,521 @@
+//! OpenAI Batch API implementation
+//!
+//! The requests of a batch are uploaded as a JSONL file, processed within 24 hours, and their
+//! results downloaded from the output (and error) files of the batch.
+//! See <https://platform.openai.com/docs/guides/batch>.
+use futures::StreamExt;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use super::{
+    CompletionsClient, client::Client, completion::OpenAIRequestParams, embedding::EmbeddingModel,
+    files, responses_api::ResponsesCompletionModel,
+};
+use crate::{
+    batch::{
+        self, Batch, BatchClient, BatchError, BatchItem, BatchItemError, BatchItemResult,
+        BatchRequestCounts, BatchResults, BatchStatus, CompletionBatchModel, EmbeddingBatchModel,
+    },
+    client::{self, Provider},
+    completion::{self, CompletionError},
+    files::FileUpload,
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+/// The window within which OpenAI processes batches, the only one currently supported.
+const COMPLETION_WINDOW: &str = "24h";
+
+/// A line of the input file of a batch.
+#[derive(Debug, Serialize)]
+struct BatchInputLine<'a, T> {
+    custom_id: &'a str,
+    method: &'static str,
+    url: &'static str,
+    body: T,
+}
+
+/// The batch object of the OpenAI API, only the fields used to build a [Batch].
+#[derive(Debug, Deserialize)]
+struct OpenAIBatch {
+    id: String,
+    status: BatchStatus,
+    #[serde(default)]
+    request_counts: Option<OpenAIRequestCounts>,
+    #[serde(default)]
+    output_file_id: Option<String>,
+    #[serde(default)]
+    error_file_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAIRequestCounts {
+    total: u64,
+    completed: u64,
+    failed: u64,
+}
+
+impl OpenAIBatch {
+    fn from_raw(raw: &serde_json::Value) -> Result<Self, BatchError> {
+        Ok(serde_json::from_value(raw.clone())?)
+    }
+
+    fn into_batch(self, raw: serde_json::Value) -> Batch {
+        Batch {
+            id: self.id,
+            status: self.status,
+            request_counts: self
+                .request_counts
+                .map(|counts| BatchRequestCounts {
+                    total: counts.total,
+                    succeeded: counts.completed,
+                    failed: counts.failed,
+                })
+                .unwrap_or_default(),
+            raw,
+        }
+    }
+}
+
+/// A line of the output or error file of a batch.
+#[derive(Debug, Deserialize)]
+struct BatchOutputLine {
+    custom_id: String,
+    #[serde(default)]
+    response: Option<BatchOutputResponse>,
+    #[serde(default)]
+    error: Option<BatchOutputError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchOutputResponse {
+    status_code: u16,
+    body: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchOutputError {
+    #[serde(default)]
+    code: Option<String>,
+    message: String,
+}
+
+impl BatchOutputLine {
+    /// The response body of the request, deserialized as `T`.
+    fn into_result<T>(self) -> BatchItemResult<T>
+    where
+        T: DeserializeOwned,
+    {
+        let result = match (self.response, self.error) {
+            (_, Some(error)) => Err(match error.code.as_deref() {
+                Some("batch_expired") => BatchItemError::Expired,
+                Some("batch_cancelled") => BatchItemError::Cancelled,
+                _ => BatchItemError::Failed(error.message),
+            }),
+            (Some(response), None) if response.status_code == 200 => {
+                serde_json::from_value(response.body)
+                    .map_err(|err| BatchItemError::Failed(err.to_string()))
+            }
+            (Some(response), None) => Err(BatchItemError::Failed(
+                response
+                    .body
+                    .pointer("/error/message")
+                    .and_then(|message| message.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("Failed with status {}", response.status_code)),
+            )),
+            (None, None) => Err(BatchItemError::Failed(
+                "The result contains neither a response nor an error".into(),
+            )),
+        };
+
+        BatchItemResult {
+            custom_id: self.custom_id,
+            result,
+        }
+    }
+}
+
+/// Upload the requests as a JSONL file and create a batch sending them to `endpoint`.
+async fn submit_batch<Ext, H, T>(
+    client: &client::Client<Ext, H>,
+    endpoint: &'static str,
+    requests: Vec<BatchItem<T>>,
+) -> Result<Batch, BatchError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+    T: Serialize,
+{
+    let lines = requests
+        .iter()
+        .map(|item| BatchInputLine {
+            custom_id: &item.custom_id,
+            method: "POST",
+            url: endpoint,
+            body: &item.request,
+        })
+        .collect::<Vec<_>>();
+
+    let file = files::upload_file(
+        client,
+        FileUpload::new("batch.jsonl", batch::encode_jsonl(&lines)?).purpose("batch"),
+    )
+    .await?;
+
+    let body = serde_json::to_vec(&serde_json::json!({
+        "input_file_id": file.id,
+        "endpoint": endpoint,
+        "completion_window": COMPLETION_WINDOW,
+    }))?;
+
+    let req = client
+        .post("/batches")?
+        .body(body)
+        .map_err(http_client::Error::from)?;
+
+    let raw: serde_json::Value = client.batch_response(req).await?;
+
+    Ok(OpenAIBatch::from_raw(&raw)?.into_batch(raw))
+}
+
+async fn retrieve_batch<Ext, H>(
+    client: &client::Client<Ext, H>,
+    id: &str,
+) -> Result<Batch, BatchError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let req = client
+        .get(format!("/batches/{id}"))?
+        .body(http_client::NoBody)
+        .map_err(http_client::Error::from)?;
+
+    let raw: serde_json::Value = client.batch_response(req).await?;
+
+    Ok(OpenAIBatch::from_raw(&raw)?.into_batch(raw))
+}
+
+async fn cancel_batch<Ext, H>(
+    client: &client::Client<Ext, H>,
+    id: &str,
+) -> Result<Batch, BatchError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let req = client
+        .post(format!("/batches/{id}/cancel"))?
+        .body(http_client::NoBody)
+        .map_err(http_client::Error::from)?;
+
+    let raw: serde_json::Value = client.batch_response(req).await?;
+
+    Ok(OpenAIBatch::from_raw(&raw)?.into_batch(raw))
+}
+
+/// Stream the lines of the output file and then of the error file of the batch.
+async fn batch_results<Ext, H, T, R>(
+    client: &client::Client<Ext, H>,
+    batch: &Batch,
+    convert: fn(T) -> Result<R, BatchItemError>,
+) -> Result<BatchResults<R>, BatchError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+    T: DeserializeOwned + 'static,
+    R: WasmCompatSend + 'static,
+{
+    let OpenAIBatch {
+        output_file_id,
+        error_file_id,
+        ..
+    } = OpenAIBatch::from_raw(&batch.raw)?;
+
+    if output_file_id.is_none() && error_file_id.is_none() {
+        return Err(BatchError::ResultsUnavailable(format!(
+            "Batch {} is {:?} and has no output file",
+            batch.id, batch.status
+        )));
+    }
+
+    let mut files = Vec::new();
+    for file_id in output_file_id.into_iter().chain(error_file_id) {
+        files.push(
+            client
+                .batch_results_jsonl::<BatchOutputLine>(&format!("/files/{file_id}/content"))
+                .await?,
+        );
+    }
+
+    Ok(Box::pin(futures::stream::iter(files).flatten().map(
+        move |line| {
+            let BatchItemResult { custom_id, result } = line?.into_result::<T>();
+
+            Ok(BatchItemResult {
+                custom_id,
+                result: result.and_then(convert),
+            })
+        },
+    )))
+}
+
+fn completion_response<T>(response: T) -> Result<completion::CompletionResponse<T>, BatchItemError>
+where
+    T: TryInto<completion::CompletionResponse<T>, Error = CompletionError>,
+{
+    response
+        .try_into()
+        .map_err(|err: CompletionError| BatchItemError::Failed(err.to_string()))
+}
+
+impl<H> BatchClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn retrieve_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        retrieve_batch(self, id).await
+    }
+
+    async fn cancel_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        cancel_batch(self, id).await
+    }
+}
+
+impl<H> BatchClient for CompletionsClient<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn retrieve_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        retrieve_batch(self, id).await
+    }
+
+    async fn cancel_batch(&self, id: &str) -> Result<Batch, BatchError> {
+        cancel_batch(self, id).await
+    }
+}
+
+impl<T> CompletionBatchModel for super::completion::CompletionModel<T>
+where
+    T: HttpClientExt
+        + Default
+        + std::fmt::Debug
+        + Clone
+        + WasmCompatSend
+        + WasmCompatSync
+        + 'static,
+{
+    async fn submit_batch(
+        &self,
+        requests: Vec<BatchItem<completion::CompletionRequest>>,
+    ) -> Result<Batch, BatchError> {
+        let requests = requests
+            .into_iter()
+            .map(|item| {
+                let request =
+                    super::completion::CompletionRequest::try_from(OpenAIRequestParams {
+                        model: self.model.clone(),
+                        request: item.request,
+                        strict_tools: self.strict_tools,
+                        tool_result_array_content: self.tool_result_array_content,
+                    })?;
+
+                Ok(BatchItem::new(item.custom_id, request))
+            })
+            .collect::<Result<Vec<_>, CompletionError>>()?;
+
+        submit_batch(&self.client, "/v1/chat/completions", requests).await
+    }
+
+    async fn batch_results(
+        &self,
+        batch: &Batch,
+    ) -> Result<BatchResults<completion::CompletionResponse<Self::Response>>, BatchError> {
+        batch_results(&self.client, batch, completion_response).await
+    }
+}
+
+impl<T> CompletionBatchModel for ResponsesCompletionModel<T>
+where
+    T: HttpClientExt
+        + Default
+        + std::fmt::Debug
+        + Clone
+        + WasmCompatSend
+        + WasmCompatSync
+        + 'static,
+{
+    async fn submit_batch(
+        &self,
+        requests: Vec<BatchItem<completion::CompletionRequest>>,
+    ) -> Result<Batch, BatchError> {
+        let requests = requests
+            .into_iter()
+            .map(|item| {
+                let request = self.create_completion_request(item.request)?;
+                Ok(BatchItem::new(item.custom_id, request))
+            })
+            .collect::<Result<Vec<_>, CompletionError>>()?;
+
+        submit_batch(&self.client, "/v1/responses", requests).await
+    }
+
+    async fn batch_results(
+        &self,
+        batch: &Batch,
+    ) -> Result<BatchResults<completion::CompletionResponse<Self::Response>>, BatchError> {
+        batch_results(&self.client, batch, completion_response).await
+    }
+}
+
+impl<T> EmbeddingBatchModel for EmbeddingModel<T>
+where
+    T: HttpClientExt
+        + Default
+        + std::fmt::Debug
+        + Clone
+        + WasmCompatSend
+        + WasmCompatSync
+        + 'static,
+{
+    async fn submit_embedding_batch(
+        &self,
+        requests: Vec<BatchItem<Vec<String>>>,
+    ) -> Result<Batch, BatchError> {
+        let requests = requests
+            .into_iter()
+            .map(|item| BatchItem::new(item.custom_id, self.request_body(&item.request, None)))
+            .collect();
+
+        submit_batch(&self.client, "/v1/embeddings", requests).await
+    }
+
+    async fn embedding_batch_results(
+        &self,
+        batch: &Batch,
+    ) -> Result<BatchResults<Vec<Vec<f64>>>, BatchError> {
+        batch_results(&self.client, batch, |response: super::EmbeddingResponse| {
+            let mut data = response.data;
+            data.sort_by_key(|embedding| embedding.index);
+
+            Ok(data
+                .into_iter()
+                .map(|embedding| embedding.embedding)
+                .collect())
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_batch_from_raw() {
+        let raw = json!({
+            "id": "batch_abc123",
+            "object": "batch",
+            "endpoint": "/v1/chat/completions",
+            "input_file_id": "file-abc123",
+            "completion_window": "24h",
+            "status": "completed",
+            "output_file_id": "file-cvaTdG",
+            "error_file_id": "file-HOWS94",
+            "created_at": 1711471533,
+            "request_counts": { "total": 100, "completed": 95, "failed": 5 }
+        });
+
+        let batch = OpenAIBatch::from_raw(&raw).unwrap().into_batch(raw.clone());
+
+        assert_eq!(batch.id, "batch_abc123");
+        assert_eq!(batch.status, BatchStatus::Completed);
+        assert_eq!(
+            batch.request_counts,
+            BatchRequestCounts {
+                total: 100,
+                succeeded: 95,
+                failed: 5,
+            }
+        );
+        assert_eq!(batch.raw, raw);
+    }
+
+    #[test]
+    fn test_batch_output_lines() {
+        let line: BatchOutputLine = serde_json::from_value(json!({
+            "id": "batch_req_123",
+            "custom_id": "request-1",
+            "response": {
+                "status_code": 200,
+                "request_id": "req_123",
+                "body": {
+                    "id": "chatcmpl-123",
+                    "object": "chat.completion",
+                    "created": 1711652795,
+                    "model": "gpt-4o-mini",
+                    "choices": [{
+                        "index": 0,
+                        "message": { "role": "assistant", "content": "Hello." },
+                        "logprobs": null,
+                        "finish_reason": "stop"
+                    }],
+                    "usage": { "prompt_tokens": 22, "completion_tokens": 2, "total_tokens": 24 }
+                }
+            },
+            "error": null
+        }))
+        .unwrap();
+
+        let result = line.into_result::<super::super::completion::CompletionResponse>();
+        assert_eq!(result.custom_id, "request-1");
+        let response = completion_response(result.result.unwrap()).unwrap();
+        assert_eq!(response.usage.total_tokens, 24);
+
+        let line: BatchOutputLine = serde_json::from_value(json!({
+            "custom_id": "request-2",
+            "response": {
+                "status_code": 400,
+                "body": { "error": { "message": "Invalid model", "type": "invalid_request_error" } }
+            },
+            "error": null
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_result::<serde_json::Value>().result,
+            Err(BatchItemError::Failed("Invalid model".into()))
+        );
+
+        let line: BatchOutputLine = serde_json::from_value(json!({
+            "custom_id": "request-3",
+            "response": null,
+            "error": { "code": "batch_expired", "message": "This request could not be executed before the completion window expired." }
+        }))
+        .unwrap();
+        assert_eq!(
+            line.into_result::<serde_json::Value>().result,
+            Err(BatchItemError::Expired)
+        );
+    }
+
+    #[test]
+    fn test_batch_input_line() {
+        let line = BatchInputLine {
+            custom_id: "request-1",
+            method: "POST",
+            url: "/v1/embeddings",
+            body: json!({ "model": "text-embedding-3-small", "input": ["hello"] }),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&line).unwrap(),
+            json!({
+                "custom_id": "request-1",
+                "method": "POST",
+                "url": "/v1/embeddings",
+                "body": { "model": "text-embedding-3-small", "input": ["hello"] }
+            })
+        );
+    }
+}
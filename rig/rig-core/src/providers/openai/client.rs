@@ -1,7 +1,8 @@
 use crate::{
     client::{
-        self, BearerAuth, Capabilities, Capable, DebugExt, Provider, ProviderBuilder,
-        ProviderClient,
+        self, BearerAuth, Capabilities, Capable, DebugExt, ModelInfo, ModelListingClient,
+        ModelListingError, Provider, ProviderBuilder, ProviderClient,
+        model_listing::OpenAIModelList,
     },
     extractor::ExtractorBuilder,
     http_client::{self, HttpClientExt},
@@ -91,6 +92,49 @@ impl<H> Capabilities<H> for OpenAICompletionsExt {
     type AudioGeneration = Capable<super::audio_generation::AudioGenerationModel<H>>;
 }
 
+/// Lists the models of the OpenAI API, which only returns their ids: the dimensions of the
+/// known embedding models are filled in.
+async fn list_models<Ext, H>(
+    client: &client::Client<Ext, H>,
+) -> Result<Vec<ModelInfo>, ModelListingError>
+where
+    Ext: Provider,
+    H: HttpClientExt,
+{
+    let models: OpenAIModelList = client.get_models("/models").await?;
+
+    Ok(models
+        .into_models()
+        .into_iter()
+        .map(|mut info| {
+            info.embedding_dimensions =
+                super::embedding::model_dimensions_from_identifier(&info.id);
+            if info.embedding_dimensions.is_some() {
+                info.is_embedding = Some(true);
+            }
+            info
+        })
+        .collect())
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        list_models(self).await
+    }
+}
+
+impl<H> ModelListingClient for CompletionsClient<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        list_models(self).await
+    }
+}
+
 impl DebugExt for OpenAIResponsesExt {}
 
 impl DebugExt for OpenAICompletionsExt {}
@@ -5,10 +5,11 @@
 use super::{
     CompletionsClient as Client,
     client::{ApiErrorResponse, ApiResponse},
+    responses_api::ReasoningEffort,
     streaming::StreamingCompletionResponse,
 };
 use crate::completion::{
-    CompletionError, CompletionRequest as CoreCompletionRequest, GetTokenUsage,
+    CompletionError, CompletionRequest as CoreCompletionRequest, GetTokenUsage, SamplingParam,
 };
 use crate::http_client::{self, HttpClientExt};
 use crate::message::{AudioMediaType, DocumentSourceKind, ImageDetail, MimeType};
@@ -206,6 +207,9 @@ pub enum UserContent {
     Audio {
         input_audio: InputAudio,
     },
+    File {
+        file: InputFile,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
@@ -221,6 +225,17 @@ pub struct InputAudio {
     pub format: AudioMediaType,
 }
 
+/// A file input, either uploaded with the Files API (see [crate::files]) or inlined.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct InputFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_data: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct ToolResultContent {
     #[serde(default)]
@@ -386,7 +401,7 @@ impl TryFrom<message::ToolResult> for Message {
             .content
             .into_iter()
             .map(|content| match content {
-                message::ToolResultContent::Text(message::Text { text }) => Ok(text),
+                message::ToolResultContent::Text(message::Text { text, .. }) => Ok(text),
                 _ => Err(message::MessageError::ConversionError(
                     "Tool result content does not support non-text".into(),
                 )),
@@ -406,7 +421,9 @@ impl TryFrom<message::UserContent> for UserContent {
 
     fn try_from(value: message::UserContent) -> Result<Self, Self::Error> {
         match value {
-            message::UserContent::Text(message::Text { text }) => Ok(UserContent::Text { text }),
+            message::UserContent::Text(message::Text { text, .. }) => {
+                Ok(UserContent::Text { text })
+            }
             message::UserContent::Image(message::Image {
                 data,
                 detail,
@@ -448,6 +465,16 @@ impl TryFrom<message::UserContent> for UserContent {
                     "Unsupported document type: {doc:?}"
                 ))),
             },
+            message::UserContent::Document(message::Document {
+                data: DocumentSourceKind::FileId(file_id),
+                ..
+            }) => Ok(UserContent::File {
+                file: InputFile {
+                    file_id: Some(file_id),
+                    file_data: None,
+                    filename: None,
+                },
+            }),
             message::UserContent::Document(message::Document { data, .. }) => {
                 if let DocumentSourceKind::Base64(text) | DocumentSourceKind::String(text) = data {
                     Ok(UserContent::Text { text })
@@ -545,6 +572,8 @@ impl TryFrom<OneOrMany<message::AssistantContent>> for Vec<Message> {
                             "The OpenAI Completions API doesn't support image content in assistant messages!"
                         );
                     }
+                    // Hosted tool calls can only be sent back to the provider that made them
+                    message::AssistantContent::HostedToolCall(_) => {}
                 }
                 (texts, tools)
             },
@@ -680,6 +709,16 @@ impl From<UserContent> for message::UserContent {
             UserContent::Audio { input_audio } => {
                 message::UserContent::audio(input_audio.data, Some(input_audio.format))
             }
+            UserContent::File { file } => match (file.file_id, file.file_data) {
+                (Some(file_id), _) => message::UserContent::document_file_id(file_id, None),
+                (None, file_data) => message::UserContent::Document(message::Document {
+                    data: file_data
+                        .map(DocumentSourceKind::Base64)
+                        .unwrap_or_default(),
+                    media_type: None,
+                    additional_params: None,
+                }),
+            },
         }
     }
 }
@@ -753,6 +792,8 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         let choice = response.choices.first().ok_or_else(|| {
             CompletionError::ResponseError("Response contained no choices".to_owned())
         })?;
+        let finish_reason = map_finish_reason(&choice.finish_reason);
+        let logprobs = choice.logprobs.as_ref().and_then(token_logprobs);
 
         let content = match &choice.message {
             Message::Assistant {
@@ -813,11 +854,28 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: Some(finish_reason),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs,
             raw_response: response,
         })
     }
 }
 
+/// Map an OpenAI `finish_reason` to a [completion::FinishReason].
+/// Also used by the providers exposing an OpenAI compatible API.
+pub(crate) fn map_finish_reason(reason: &str) -> completion::FinishReason {
+    match reason {
+        "stop" => completion::FinishReason::Stop,
+        // Mistral reports `model_length` when the context window of the model is exhausted
+        "length" | "model_length" => completion::FinishReason::Length,
+        "tool_calls" | "function_call" => completion::FinishReason::ToolCalls,
+        "content_filter" => completion::FinishReason::ContentFilter,
+        other => completion::FinishReason::Other(other.to_string()),
+    }
+}
+
 impl ProviderResponseExt for CompletionResponse {
     type OutputMessage = Choice;
     type Usage = Usage;
@@ -964,9 +1022,99 @@ pub struct CompletionRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
     #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<ReasoningEffort>,
+    #[serde(flatten)]
     additional_params: Option<serde_json::Value>,
 }
 
+/// The log-probabilities parameters of the OpenAI chat completions API.
+/// Also used by the providers exposing an OpenAI compatible API.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct LogprobsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logprobs: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_logprobs: Option<u32>,
+}
+
+impl From<Option<u32>> for LogprobsParams {
+    fn from(top_logprobs: Option<u32>) -> Self {
+        Self {
+            logprobs: top_logprobs.map(|_| true),
+            top_logprobs: top_logprobs.filter(|top_logprobs| *top_logprobs > 0),
+        }
+    }
+}
+
+/// Converts the `logprobs` object of a chat completion choice (or chunk) into provider-neutral token logprobs.
+pub(crate) fn token_logprobs(
+    logprobs: &serde_json::Value,
+) -> Option<Vec<completion::TokenLogprob>> {
+    serde_json::from_value(logprobs.get("content")?.clone()).ok()
+}
+
+/// The sampling parameters of the OpenAI chat completions API.
+/// Also used by the providers exposing an OpenAI compatible API, which support a subset of them.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct SamplingParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_k: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    presence_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    frequency_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logit_bias: Option<std::collections::HashMap<u32, f64>>,
+}
+
+/// The sampling parameters supported by the OpenAI chat completions API.
+pub(crate) const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
+impl SamplingParams {
+    /// Keeps the `supported` parameters, warning about (or rejecting in strict mode) the others.
+    pub(crate) fn new(
+        provider: &str,
+        sampling: completion::SamplingParams,
+        supported: &[SamplingParam],
+    ) -> Result<Self, CompletionError> {
+        sampling.check_supported(provider, supported)?;
+
+        let keep = |param| supported.contains(&param);
+        Ok(Self {
+            top_p: sampling.top_p.filter(|_| keep(SamplingParam::TopP)),
+            top_k: sampling.top_k.filter(|_| keep(SamplingParam::TopK)),
+            stop: sampling.stop.filter(|_| keep(SamplingParam::Stop)),
+            seed: sampling.seed.filter(|_| keep(SamplingParam::Seed)),
+            presence_penalty: sampling
+                .presence_penalty
+                .filter(|_| keep(SamplingParam::PresencePenalty)),
+            frequency_penalty: sampling
+                .frequency_penalty
+                .filter(|_| keep(SamplingParam::FrequencyPenalty)),
+            logit_bias: sampling
+                .logit_bias
+                .filter(|_| keep(SamplingParam::LogitBias)),
+        })
+    }
+}
+
 pub struct OpenAIRequestParams {
     pub model: String,
     pub request: CoreCompletionRequest,
@@ -984,11 +1132,21 @@ impl TryFrom<OpenAIRequestParams> for CompletionRequest {
             strict_tools,
             tool_result_array_content,
         } = params;
+        req.check_hosted_tools_unsupported("OpenAI")?;
 
         let mut partial_history = vec![];
         if let Some(docs) = req.normalized_documents() {
             partial_history.push(docs);
         }
+        let reasoning_effort = req
+            .reasoning_unless_overridden("reasoning_effort")
+            .and_then(|reasoning| reasoning.resolved_effort())
+            .map(ReasoningEffort::from);
+        let sampling = SamplingParams::new(
+            "OpenAI",
+            req.sampling_unless_overridden(SamplingParam::name),
+            SUPPORTED_SAMPLING_PARAMS,
+        )?;
         let CoreCompletionRequest {
             preamble,
             chat_history,
@@ -996,6 +1154,7 @@ impl TryFrom<OpenAIRequestParams> for CompletionRequest {
             temperature,
             additional_params,
             tool_choice,
+            logprobs,
             ..
         } = req;
 
@@ -1038,6 +1197,9 @@ impl TryFrom<OpenAIRequestParams> for CompletionRequest {
             tools,
             tool_choice,
             temperature,
+            sampling,
+            logprobs: logprobs.into(),
+            reasoning_effort,
             additional_params,
         };
 
@@ -1208,3 +1370,28 @@ where
         Self::stream(self, request).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::CompletionRequest;
+    use crate::{completion::CompletionRequestBuilder, testing::MockCompletionModel};
+
+    #[test]
+    fn test_additional_params_override_sampling() {
+        let request = CompletionRequestBuilder::new(MockCompletionModel::new(), "Hi")
+            .top_p(0.9)
+            .seed(42)
+            .additional_params(json!({ "top_p": 0.5 }))
+            .build();
+
+        let request = CompletionRequest::try_from(("gpt-4o".to_string(), request)).unwrap();
+        let body = serde_json::to_string(&request).unwrap();
+
+        assert_eq!(body.matches("\"top_p\"").count(), 1);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["top_p"], json!(0.5));
+        assert_eq!(body["seed"], json!(42));
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use async_stream::stream;
 use futures::StreamExt;
@@ -51,14 +51,32 @@ pub enum FinishReason {
     Other(String), // This will handle the deprecated function_call
 }
 
+impl From<&FinishReason> for crate::completion::FinishReason {
+    fn from(reason: &FinishReason) -> Self {
+        match reason {
+            FinishReason::ToolCalls => crate::completion::FinishReason::ToolCalls,
+            FinishReason::Stop => crate::completion::FinishReason::Stop,
+            FinishReason::ContentFilter => crate::completion::FinishReason::ContentFilter,
+            FinishReason::Length => crate::completion::FinishReason::Length,
+            FinishReason::Other(reason) => completion::map_finish_reason(reason),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 struct StreamingChoice {
     delta: StreamingDelta,
     finish_reason: Option<FinishReason>,
+    #[serde(default)]
+    logprobs: Option<serde_json::Value>,
 }
 
 #[derive(Deserialize, Debug)]
 struct StreamingCompletionChunk {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    model: Option<String>,
     choices: Vec<StreamingChoice>,
     usage: Option<Usage>,
 }
@@ -157,10 +175,11 @@ where
         let span = tracing::Span::current();
 
         // Accumulate tool calls by index while streaming
-        let mut tool_calls: HashMap<usize, ToolCall> = HashMap::new();
+        let mut tool_calls: BTreeMap<usize, ToolCall> = BTreeMap::new();
         let mut text_content = String::new();
         let mut final_tool_calls: Vec<completion::ToolCall> = Vec::new();
         let mut final_usage = None;
+        let mut metadata_yielded = false;
 
         while let Some(event_result) = event_source.next().await {
             match event_result {
@@ -182,6 +201,21 @@ where
                         }
                     };
 
+                    // The id and model are repeated on every chunk, only forward them once
+                    if !metadata_yielded && (data.id.is_some() || data.model.is_some()) {
+                        metadata_yielded = true;
+                        yield Ok(RawStreamingChoice::Metadata(streaming::ResponseMetadata::new(
+                            data.id.clone(),
+                            data.model.clone(),
+                            None,
+                        )));
+                    }
+
+                    // Usage updates, the last chunk only contains the usage and no choice
+                    if let Some(usage) = data.usage {
+                        final_usage = Some(usage);
+                    }
+
                     // Expect at least one choice
                      let Some(choice) = data.choices.first() else {
                         tracing::debug!("There is no choice");
@@ -250,9 +284,17 @@ where
                         yield Ok(streaming::RawStreamingChoice::Message(content.clone()));
                     }
 
-                    // Usage updates
-                    if let Some(usage) = data.usage {
-                        final_usage = Some(usage);
+                    // Log-probabilities of the streamed tokens
+                    if let Some(logprobs) = choice.logprobs.as_ref().and_then(completion::token_logprobs)
+                        && !logprobs.is_empty()
+                    {
+                        yield Ok(RawStreamingChoice::Logprobs(logprobs));
+                    }
+
+                    if let Some(finish_reason) = &choice.finish_reason {
+                        yield Ok(RawStreamingChoice::Metadata(
+                            streaming::ResponseMetadata::finish_reason(finish_reason.into()),
+                        ));
                     }
 
                     // Finish reason
@@ -274,7 +316,7 @@ where
                                 )
                             ));
                         }
-                        tool_calls = HashMap::new();
+                        tool_calls = BTreeMap::new();
                     }
                 }
                 Err(crate::http_client::Error::StreamEnded) => {
@@ -408,6 +450,66 @@ mod tests {
         assert!(chunk.usage.is_some());
     }
 
+    #[test]
+    fn test_streaming_chunk_metadata_deserialization() {
+        let json = r#"{
+            "id": "chatcmpl-123",
+            "model": "gpt-4o-2024-08-06",
+            "choices": [{
+                "delta": {
+                    "content": null
+                },
+                "finish_reason": "length"
+            }]
+        }"#;
+        let chunk: StreamingCompletionChunk = serde_json::from_str(json).unwrap();
+        assert_eq!(chunk.id.as_deref(), Some("chatcmpl-123"));
+        assert_eq!(chunk.model.as_deref(), Some("gpt-4o-2024-08-06"));
+
+        let finish_reason = chunk.choices[0].finish_reason.as_ref().unwrap();
+        assert_eq!(
+            crate::completion::FinishReason::from(finish_reason),
+            crate::completion::FinishReason::Length
+        );
+    }
+
+    #[test]
+    fn test_streaming_chunk_logprobs_deserialization() {
+        let json = r#"{
+            "choices": [{
+                "delta": {
+                    "content": "Yes"
+                },
+                "finish_reason": null,
+                "logprobs": {
+                    "content": [{
+                        "token": "Yes",
+                        "logprob": -0.01,
+                        "bytes": [89, 101, 115],
+                        "top_logprobs": [
+                            {"token": "Yes", "logprob": -0.01, "bytes": [89, 101, 115]},
+                            {"token": "No", "logprob": -4.6, "bytes": [78, 111]}
+                        ]
+                    }],
+                    "refusal": null
+                }
+            }]
+        }"#;
+        let chunk: StreamingCompletionChunk = serde_json::from_str(json).unwrap();
+
+        let logprobs = chunk.choices[0]
+            .logprobs
+            .as_ref()
+            .and_then(completion::token_logprobs)
+            .unwrap();
+        assert_eq!(logprobs.len(), 1);
+        assert_eq!(logprobs[0].token, "Yes");
+        assert_eq!(logprobs[0].bytes.as_deref(), Some(&b"Yes"[..]));
+        assert_eq!(logprobs[0].top_logprobs.len(), 2);
+        assert_eq!(logprobs[0].top_logprobs[1].token, "No");
+        assert!(logprobs[0].probability() > 0.98);
+    }
+
     #[test]
     fn test_streaming_chunk_with_multiple_tool_call_deltas() {
         // Simulates multiple partial tool call chunks arriving
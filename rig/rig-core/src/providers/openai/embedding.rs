@@ -6,7 +6,8 @@ use super::{
 use crate::embeddings::EmbeddingError;
 use crate::http_client::HttpClientExt;
 use crate::{embeddings, http_client};
-use serde::Deserialize;
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde::{Deserialize, de::DeserializeOwned};
 use serde_json::json;
 
 // ================================================================
@@ -51,12 +52,12 @@ pub struct EmbeddingData {
 
 #[derive(Clone)]
 pub struct EmbeddingModel<T = reqwest::Client> {
-    client: Client<T>,
+    pub(crate) client: Client<T>,
     pub model: String,
     ndims: usize,
 }
 
-fn model_dimensions_from_identifier(identifier: &str) -> Option<usize> {
+pub(crate) fn model_dimensions_from_identifier(identifier: &str) -> Option<usize> {
     match identifier {
         TEXT_EMBEDDING_3_LARGE => Some(3_072),
         TEXT_EMBEDDING_3_SMALL | TEXT_EMBEDDING_ADA_002 => Some(1_536),
@@ -91,6 +92,48 @@ where
     ) -> Result<Vec<embeddings::Embedding>, EmbeddingError> {
         let documents = documents.into_iter().collect::<Vec<_>>();
 
+        let response: EmbeddingResponse = self.send_embeddings_request(&documents, None).await?;
+
+        Ok(response
+            .data
+            .into_iter()
+            .zip(documents.into_iter())
+            .map(|(embedding, document)| embeddings::Embedding {
+                document,
+                vec: embedding.embedding,
+            })
+            .collect())
+    }
+
+    /// Requests `base64` encoded embeddings, which OpenAI returns as little-endian `f32` bytes.
+    async fn embed_texts_f32(
+        &self,
+        documents: impl IntoIterator<Item = String>,
+    ) -> Result<Vec<embeddings::F32Embedding>, EmbeddingError> {
+        let documents = documents.into_iter().collect::<Vec<_>>();
+
+        let response: Base64EmbeddingResponse = self
+            .send_embeddings_request(&documents, Some("base64"))
+            .await?;
+
+        response
+            .data
+            .into_iter()
+            .map(|embedding| decode_base64_embedding(&embedding.embedding))
+            .collect()
+    }
+}
+
+impl<T> EmbeddingModel<T>
+where
+    T: HttpClientExt + Clone + std::fmt::Debug + Default + Send + 'static,
+{
+    /// The body of an embeddings request for the given documents.
+    pub(crate) fn request_body(
+        &self,
+        documents: &[String],
+        encoding_format: Option<&str>,
+    ) -> serde_json::Value {
         let mut body = json!({
             "model": self.model,
             "input": documents,
@@ -100,7 +143,22 @@ where
             body["dimensions"] = json!(self.ndims);
         }
 
-        let body = serde_json::to_vec(&body)?;
+        if let Some(encoding_format) = encoding_format {
+            body["encoding_format"] = json!(encoding_format);
+        }
+
+        body
+    }
+
+    async fn send_embeddings_request<R>(
+        &self,
+        documents: &[String],
+        encoding_format: Option<&str>,
+    ) -> Result<R, EmbeddingError>
+    where
+        R: DeserializeOwned + HasEmbeddingData,
+    {
+        let body = serde_json::to_vec(&self.request_body(documents, encoding_format))?;
 
         let req = self
             .client
@@ -112,30 +170,22 @@ where
 
         if response.status().is_success() {
             let body: Vec<u8> = response.into_body().await?;
-            let body: ApiResponse<EmbeddingResponse> = serde_json::from_slice(&body)?;
+            let body: ApiResponse<R> = serde_json::from_slice(&body)?;
 
             match body {
                 ApiResponse::Ok(response) => {
                     tracing::info!(target: "rig",
                         "OpenAI embedding token usage: {:?}",
-                        response.usage
+                        response.usage()
                     );
 
-                    if response.data.len() != documents.len() {
+                    if response.len() != documents.len() {
                         return Err(EmbeddingError::ResponseError(
                             "Response data length does not match input length".into(),
                         ));
                     }
 
-                    Ok(response
-                        .data
-                        .into_iter()
-                        .zip(documents.into_iter())
-                        .map(|(embedding, document)| embeddings::Embedding {
-                            document,
-                            vec: embedding.embedding,
-                        })
-                        .collect())
+                    Ok(response)
                 }
                 ApiResponse::Err(err) => Err(EmbeddingError::ProviderError(err.message)),
             }
@@ -146,6 +196,61 @@ where
     }
 }
 
+/// Common accessors over the float and base64 embedding responses.
+trait HasEmbeddingData {
+    fn len(&self) -> usize;
+    fn usage(&self) -> &Usage;
+}
+
+impl HasEmbeddingData for EmbeddingResponse {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn usage(&self) -> &Usage {
+        &self.usage
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Base64EmbeddingResponse {
+    data: Vec<Base64EmbeddingData>,
+    usage: Usage,
+}
+
+#[derive(Debug, Deserialize)]
+struct Base64EmbeddingData {
+    embedding: String,
+}
+
+impl HasEmbeddingData for Base64EmbeddingResponse {
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn usage(&self) -> &Usage {
+        &self.usage
+    }
+}
+
+fn decode_base64_embedding(data: &str) -> Result<embeddings::F32Embedding, EmbeddingError> {
+    let bytes = BASE64_STANDARD
+        .decode(data)
+        .map_err(|e| EmbeddingError::ResponseError(format!("Invalid base64 embedding: {e}")))?;
+
+    if bytes.len() % 4 != 0 {
+        return Err(EmbeddingError::ResponseError(
+            "Base64 embedding length is not a multiple of 4 bytes".into(),
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect::<Vec<_>>()
+        .into())
+}
+
 impl<T> EmbeddingModel<T> {
     pub fn new(client: Client<T>, model: impl Into<String>, ndims: usize) -> Self {
         Self {
@@ -163,3 +268,27 @@ impl<T> EmbeddingModel<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, prelude::BASE64_STANDARD};
+
+    use super::decode_base64_embedding;
+
+    #[test]
+    fn test_decode_base64_embedding() {
+        let bytes = [0.5f32, -1.25, 3.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let embedding = decode_base64_embedding(&BASE64_STANDARD.encode(bytes)).unwrap();
+
+        assert_eq!(embedding.vec, vec![0.5, -1.25, 3.0]);
+    }
+
+    #[test]
+    fn test_decode_base64_embedding_invalid_length() {
+        assert!(decode_base64_embedding(&BASE64_STANDARD.encode([0u8; 3])).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! OpenAI Files API implementation
+//!
+//! Uploaded files are referenced by id in user messages: documents as `file` content parts of the
+//! Chat Completions API, and documents and images as `input_file` and `input_image` content parts
+//! of the Responses API.
+//! See <https://platform.openai.com/docs/api-reference/files>.
+use serde::Deserialize;
+
+use super::{CompletionsClient, client::Client};
+use crate::{
+    client::{self, Provider},
+    files::{FileObject, FileUpload, FilesClient, FilesError},
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+/// The purpose of uploaded files when none is given, for files used as model inputs.
+const DEFAULT_PURPOSE: &str = "user_data";
+
+/// The maximum number of files returned by a page of the list endpoint.
+const LIST_LIMIT: usize = 10000;
+
+/// The file object of the OpenAI API, only the fields used to build a [FileObject].
+#[derive(Debug, Deserialize)]
+struct OpenAIFile {
+    id: String,
+    #[serde(default)]
+    filename: Option<String>,
+    #[serde(default)]
+    bytes: Option<u64>,
+}
+
+impl OpenAIFile {
+    fn from_raw(raw: serde_json::Value) -> Result<FileObject, FilesError> {
+        let file: Self = serde_json::from_value(raw.clone())?;
+        let mime_type = file
+            .filename
+            .as_deref()
+            .and_then(|filename| mime_guess::from_path(filename).first())
+            .map(|mime| mime.to_string());
+
+        Ok(FileObject {
+            id: file.id,
+            filename: file.filename,
+            mime_type,
+            bytes: file.bytes,
+            raw,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ListFilesResponse {
+    data: Vec<serde_json::Value>,
+    #[serde(default)]
+    has_more: bool,
+}
+
+/// Upload a file with the given purpose, `user_data` by default.
+pub(crate) async fn upload_file<Ext, H>(
+    client: &client::Client<Ext, H>,
+    file: FileUpload,
+) -> Result<FileObject, FilesError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let purpose = file
+        .purpose
+        .clone()
+        .unwrap_or_else(|| DEFAULT_PURPOSE.to_string());
+
+    let raw = client
+        .upload_multipart(client.post("/files")?, &[("purpose", &purpose)], file)
+        .await?;
+
+    OpenAIFile::from_raw(raw)
+}
+
+async fn list_files<Ext, H>(client: &client::Client<Ext, H>) -> Result<Vec<FileObject>, FilesError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let mut files = Vec::new();
+    let mut after: Option<String> = None;
+
+    loop {
+        let path = match &after {
+            Some(after) => format!("/files?limit={LIST_LIMIT}&after={after}"),
+            None => format!("/files?limit={LIST_LIMIT}"),
+        };
+
+        let req = client
+            .get(path)?
+            .body(http_client::NoBody)
+            .map_err(http_client::Error::from)?;
+
+        let page: ListFilesResponse = client.files_response(req).await?;
+        for raw in page.data {
+            files.push(OpenAIFile::from_raw(raw)?);
+        }
+
+        match files.last() {
+            Some(last) if page.has_more => after = Some(last.id.clone()),
+            _ => return Ok(files),
+        }
+    }
+}
+
+async fn retrieve_file<Ext, H>(
+    client: &client::Client<Ext, H>,
+    id: &str,
+) -> Result<FileObject, FilesError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let req = client
+        .get(format!("/files/{id}"))?
+        .body(http_client::NoBody)
+        .map_err(http_client::Error::from)?;
+
+    OpenAIFile::from_raw(client.files_response(req).await?)
+}
+
+async fn delete_file<Ext, H>(client: &client::Client<Ext, H>, id: &str) -> Result<(), FilesError>
+where
+    Ext: Provider + WasmCompatSend + WasmCompatSync + 'static,
+    H: HttpClientExt + 'static,
+{
+    let req = client
+        .delete(format!("/files/{id}"))?
+        .body(http_client::NoBody)
+        .map_err(http_client::Error::from)?;
+
+    let _: serde_json::Value = client.files_response(req).await?;
+
+    Ok(())
+}
+
+impl<H> FilesClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn upload_file(&self, file: FileUpload) -> Result<FileObject, FilesError> {
+        upload_file(self, file).await
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileObject>, FilesError> {
+        list_files(self).await
+    }
+
+    async fn retrieve_file(&self, id: &str) -> Result<FileObject, FilesError> {
+        retrieve_file(self, id).await
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<(), FilesError> {
+        delete_file(self, id).await
+    }
+}
+
+impl<H> FilesClient for CompletionsClient<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn upload_file(&self, file: FileUpload) -> Result<FileObject, FilesError> {
+        upload_file(self, file).await
+    }
+
+    async fn list_files(&self) -> Result<Vec<FileObject>, FilesError> {
+        list_files(self).await
+    }
+
+    async fn retrieve_file(&self, id: &str) -> Result<FileObject, FilesError> {
+        retrieve_file(self, id).await
+    }
+
+    async fn delete_file(&self, id: &str) -> Result<(), FilesError> {
+        delete_file(self, id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_file_object() {
+        let file = OpenAIFile::from_raw(json!({
+            "id": "file-abc123",
+            "object": "file",
+            "bytes": 120000,
+            "created_at": 1677610602,
+            "filename": "mydata.pdf",
+            "purpose": "user_data"
+        }))
+        .unwrap();
+
+        assert_eq!(file.id, "file-abc123");
+        assert_eq!(file.filename.as_deref(), Some("mydata.pdf"));
+        assert_eq!(file.mime_type.as_deref(), Some("application/pdf"));
+        assert_eq!(file.bytes, Some(120000));
+    }
+}
@@ -8,9 +8,14 @@
 //!
 //! let gpt4o = client.completion_model(openai::GPT_4O);
 //! ```
+pub mod batch;
 pub mod client;
 pub mod completion;
 pub mod embedding;
+pub mod files;
+#[cfg(feature = "realtime")]
+#[cfg_attr(docsrs, doc(cfg(feature = "realtime")))]
+pub mod realtime;
 pub mod responses_api;
 
 #[cfg(feature = "audio")]
@@ -100,7 +105,7 @@ pub(crate) fn sanitize_schema(schema: &mut serde_json::Value) {
 }
 
 #[cfg(feature = "audio")]
-pub use audio_generation::{TTS_1, TTS_1_HD};
+pub use audio_generation::{GPT_4O_MINI_TTS, TTS_1, TTS_1_HD};
 
 pub use streaming::*;
 pub use transcription::*;
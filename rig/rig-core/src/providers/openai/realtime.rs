@@ -0,0 +1,513 @@
+//! OpenAI Realtime API implementation
+//!
+//! The session is configured with `session.update` events, and the provider-neutral
+//! [ClientEvent]s and [ServerEvent]s are mapped to the client and server events of the protocol.
+//! See <https://platform.openai.com/docs/guides/realtime> and
+//! <https://platform.openai.com/docs/api-reference/realtime>.
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use super::client::Client;
+use crate::{
+    completion::{ToolDefinition, Usage},
+    http_client::{
+        self, HttpClientExt,
+        websocket::{self, WebSocketMessage},
+    },
+    json_utils,
+    message::{ToolCall, ToolFunction},
+    realtime::{
+        self, ClientEvent, RealtimeError, RealtimeProtocol, RealtimeSession, ServerEvent,
+        SessionConfig, TurnDetection,
+    },
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+// ================================================================
+// OpenAI Realtime Models
+// ================================================================
+/// `gpt-realtime` realtime model
+pub const GPT_REALTIME: &str = "gpt-realtime";
+/// `gpt-4o-realtime-preview` realtime model
+pub const GPT_4O_REALTIME_PREVIEW: &str = "gpt-4o-realtime-preview";
+/// `gpt-4o-mini-realtime-preview` realtime model
+pub const GPT_4O_MINI_REALTIME_PREVIEW: &str = "gpt-4o-mini-realtime-preview";
+
+#[derive(Clone)]
+pub struct RealtimeModel<T = reqwest::Client> {
+    client: Client<T>,
+    pub model: String,
+}
+
+impl<T> RealtimeModel<T> {
+    pub fn new(client: Client<T>, model: impl Into<String>) -> Self {
+        Self {
+            client,
+            model: model.into(),
+        }
+    }
+}
+
+impl<T> realtime::RealtimeModel for RealtimeModel<T>
+where
+    T: HttpClientExt + Clone + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn connect(&self, config: SessionConfig) -> Result<RealtimeSession, RealtimeError> {
+        let req = self
+            .client
+            .websocket(format!("/realtime?model={}", self.model))?
+            .header("OpenAI-Beta", "realtime=v1")
+            .body(())
+            .map_err(http_client::Error::from)?;
+
+        let socket = websocket::connect(req).await?;
+        let session = RealtimeSession::new(socket, OpenAIRealtimeProtocol);
+        session.send(ClientEvent::UpdateSession(config)).await?;
+
+        Ok(session)
+    }
+}
+
+impl<T> Client<T> {
+    /// Create a realtime model with the given name, see [crate::realtime].
+    pub fn realtime_model(&self, model: impl Into<String>) -> RealtimeModel<T>
+    where
+        T: Clone,
+    {
+        RealtimeModel::new(self.clone(), model)
+    }
+}
+
+/// The `session` object of `session.update` events.
+#[derive(Debug, Default, Serialize)]
+struct Session {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    instructions: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    modalities: Option<Vec<crate::client::Modality>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    voice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_audio_format: Option<realtime::AudioFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_audio_format: Option<realtime::AudioFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_audio_transcription: Option<Value>,
+    /// `null` disables the turn detection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    turn_detection: Option<Value>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tools: Vec<Tool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_response_output_tokens: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Tool {
+    r#type: &'static str,
+    name: String,
+    description: String,
+    parameters: Value,
+}
+
+impl From<ToolDefinition> for Tool {
+    fn from(tool: ToolDefinition) -> Self {
+        Self {
+            r#type: "function",
+            name: tool.name,
+            description: tool.description,
+            parameters: tool.parameters,
+        }
+    }
+}
+
+impl TryFrom<SessionConfig> for Session {
+    type Error = RealtimeError;
+
+    fn try_from(config: SessionConfig) -> Result<Self, Self::Error> {
+        let turn_detection = config
+            .turn_detection
+            .map(|turn_detection| match turn_detection {
+                TurnDetection::Disabled => Ok(Value::Null),
+                turn_detection => {
+                    let mut value = serde_json::to_value(turn_detection)?;
+                    // Unset settings keep the defaults of the provider
+                    if let Value::Object(settings) = &mut value {
+                        settings.retain(|_, setting| !setting.is_null());
+                    }
+                    Ok::<_, RealtimeError>(value)
+                }
+            })
+            .transpose()?;
+
+        Ok(Self {
+            instructions: config.instructions,
+            modalities: config.modalities,
+            voice: config.voice,
+            input_audio_format: config.input_audio_format,
+            output_audio_format: config.output_audio_format,
+            input_audio_transcription: config
+                .input_audio_transcription
+                .map(|model| json!({ "model": model })),
+            turn_detection,
+            tools: config.tools.into_iter().map(Tool::from).collect(),
+            temperature: config.temperature,
+            max_response_output_tokens: config.max_output_tokens,
+        })
+    }
+}
+
+/// The server events of the protocol mapped to [ServerEvent]s. The aliases are the names of the
+/// events in the GA version of the protocol.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum OpenAIServerEvent {
+    #[serde(rename = "session.created", alias = "session.updated")]
+    Session { session: Value },
+    #[serde(rename = "input_audio_buffer.speech_started")]
+    SpeechStarted { audio_start_ms: Option<u64> },
+    #[serde(rename = "input_audio_buffer.speech_stopped")]
+    SpeechStopped { audio_end_ms: Option<u64> },
+    #[serde(rename = "conversation.item.input_audio_transcription.completed")]
+    InputTranscription { item_id: String, transcript: String },
+    #[serde(rename = "response.audio.delta", alias = "response.output_audio.delta")]
+    AudioDelta { response_id: String, delta: String },
+    #[serde(
+        rename = "response.audio_transcript.delta",
+        alias = "response.output_audio_transcript.delta"
+    )]
+    AudioTranscriptDelta { response_id: String, delta: String },
+    #[serde(rename = "response.text.delta", alias = "response.output_text.delta")]
+    TextDelta { response_id: String, delta: String },
+    #[serde(rename = "response.output_item.done")]
+    OutputItemDone { item: OutputItem },
+    #[serde(rename = "response.done")]
+    ResponseDone { response: Response },
+    #[serde(rename = "error")]
+    Error { error: ErrorDetails },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutputItem {
+    FunctionCall {
+        id: String,
+        call_id: String,
+        name: String,
+        arguments: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    id: String,
+    usage: Option<ResponseUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseUsage {
+    input_tokens: u64,
+    output_tokens: u64,
+    total_tokens: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorDetails {
+    message: String,
+    code: Option<String>,
+}
+
+/// The [RealtimeProtocol] of the OpenAI Realtime API.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenAIRealtimeProtocol;
+
+impl RealtimeProtocol for OpenAIRealtimeProtocol {
+    fn encode(&self, event: ClientEvent) -> Result<Vec<WebSocketMessage>, RealtimeError> {
+        let event = match event {
+            ClientEvent::UpdateSession(mut config) => {
+                let additional_params = config.additional_params.take();
+                let mut session = serde_json::to_value(Session::try_from(config)?)?;
+                if let Some(params) = additional_params {
+                    json_utils::merge_inplace(&mut session, params);
+                }
+
+                json!({ "type": "session.update", "session": session })
+            }
+            ClientEvent::AppendAudio(audio) => json!({
+                "type": "input_audio_buffer.append",
+                "audio": BASE64_STANDARD.encode(audio),
+            }),
+            ClientEvent::CommitAudio => json!({ "type": "input_audio_buffer.commit" }),
+            ClientEvent::ClearAudio => json!({ "type": "input_audio_buffer.clear" }),
+            ClientEvent::UserMessage(text) => json!({
+                "type": "conversation.item.create",
+                "item": {
+                    "type": "message",
+                    "role": "user",
+                    "content": [{ "type": "input_text", "text": text }],
+                },
+            }),
+            ClientEvent::CreateResponse => json!({ "type": "response.create" }),
+            ClientEvent::CancelResponse => json!({ "type": "response.cancel" }),
+            ClientEvent::ToolResult { call_id, output } => json!({
+                "type": "conversation.item.create",
+                "item": {
+                    "type": "function_call_output",
+                    "call_id": call_id,
+                    "output": output,
+                },
+            }),
+        };
+
+        Ok(vec![WebSocketMessage::Text(event.to_string())])
+    }
+
+    fn decode(&self, message: WebSocketMessage) -> Result<Vec<ServerEvent>, RealtimeError> {
+        let WebSocketMessage::Text(text) = message else {
+            return Err(RealtimeError::ProtocolError(
+                "OpenAI realtime events are sent as text messages".into(),
+            ));
+        };
+
+        let raw: Value = serde_json::from_str(&text)?;
+
+        let event = match serde_json::from_value(raw.clone())? {
+            OpenAIServerEvent::Session { session } => ServerEvent::SessionUpdated(session),
+            OpenAIServerEvent::SpeechStarted { audio_start_ms } => {
+                ServerEvent::SpeechStarted { audio_start_ms }
+            }
+            OpenAIServerEvent::SpeechStopped { audio_end_ms } => {
+                ServerEvent::SpeechStopped { audio_end_ms }
+            }
+            OpenAIServerEvent::InputTranscription {
+                item_id,
+                transcript,
+            } => ServerEvent::InputTranscription {
+                item_id,
+                transcript,
+            },
+            OpenAIServerEvent::AudioDelta { response_id, delta } => ServerEvent::AudioDelta {
+                response_id,
+                data: BASE64_STANDARD.decode(delta).map_err(|error| {
+                    RealtimeError::ProtocolError(format!("Invalid audio delta: {error}"))
+                })?,
+            },
+            OpenAIServerEvent::AudioTranscriptDelta { response_id, delta } => {
+                ServerEvent::AudioTranscriptDelta { response_id, delta }
+            }
+            OpenAIServerEvent::TextDelta { response_id, delta } => {
+                ServerEvent::TextDelta { response_id, delta }
+            }
+            OpenAIServerEvent::OutputItemDone {
+                item:
+                    OutputItem::FunctionCall {
+                        id,
+                        call_id,
+                        name,
+                        arguments,
+                    },
+            } => {
+                let arguments = if arguments.is_empty() {
+                    json!({})
+                } else {
+                    serde_json::from_str(&arguments)?
+                };
+
+                ServerEvent::ToolCall(
+                    ToolCall::new(id, ToolFunction::new(name, arguments)).with_call_id(call_id),
+                )
+            }
+            OpenAIServerEvent::ResponseDone { response } => ServerEvent::ResponseDone {
+                response_id: response.id,
+                usage: response.usage.map(|usage| Usage {
+                    input_tokens: usage.input_tokens,
+                    output_tokens: usage.output_tokens,
+                    total_tokens: usage.total_tokens,
+                }),
+            },
+            OpenAIServerEvent::Error { error } => ServerEvent::Error {
+                message: error.message,
+                code: error.code,
+            },
+            OpenAIServerEvent::OutputItemDone {
+                item: OutputItem::Other,
+            }
+            | OpenAIServerEvent::Unknown => ServerEvent::Other(raw),
+        };
+
+        Ok(vec![event])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::realtime::AudioFormat;
+
+    fn encode(event: ClientEvent) -> Value {
+        let mut messages = OpenAIRealtimeProtocol.encode(event).unwrap();
+        let Some(WebSocketMessage::Text(text)) = messages.pop() else {
+            panic!("Expected a text message");
+        };
+        serde_json::from_str(&text).unwrap()
+    }
+
+    fn decode(event: Value) -> ServerEvent {
+        OpenAIRealtimeProtocol
+            .decode(WebSocketMessage::Text(event.to_string()))
+            .unwrap()
+            .remove(0)
+    }
+
+    #[test]
+    fn test_session_update() {
+        let config = SessionConfig::new()
+            .instructions("Be brief")
+            .voice("alloy")
+            .input_audio_format(AudioFormat::Pcm16)
+            .input_audio_transcription("whisper-1")
+            .turn_detection(TurnDetection::ServerVad {
+                threshold: Some(0.6),
+                prefix_padding_ms: None,
+                silence_duration_ms: Some(300),
+                create_response: None,
+            })
+            .tool(ToolDefinition {
+                name: "add".to_string(),
+                description: "Add two numbers".to_string(),
+                parameters: json!({ "type": "object" }),
+            })
+            .additional_params(json!({ "tool_choice": "auto" }));
+
+        assert_eq!(
+            encode(ClientEvent::UpdateSession(config)),
+            json!({
+                "type": "session.update",
+                "session": {
+                    "instructions": "Be brief",
+                    "voice": "alloy",
+                    "input_audio_format": "pcm16",
+                    "input_audio_transcription": { "model": "whisper-1" },
+                    "turn_detection": {
+                        "type": "server_vad",
+                        "threshold": 0.6,
+                        "silence_duration_ms": 300
+                    },
+                    "tools": [{
+                        "type": "function",
+                        "name": "add",
+                        "description": "Add two numbers",
+                        "parameters": { "type": "object" }
+                    }],
+                    "tool_choice": "auto"
+                }
+            })
+        );
+
+        let disabled = SessionConfig::new().turn_detection(TurnDetection::Disabled);
+        assert_eq!(
+            encode(ClientEvent::UpdateSession(disabled)),
+            json!({ "type": "session.update", "session": { "turn_detection": null } })
+        );
+    }
+
+    #[test]
+    fn test_client_events() {
+        assert_eq!(
+            encode(ClientEvent::AppendAudio(vec![1, 2, 3])),
+            json!({ "type": "input_audio_buffer.append", "audio": "AQID" })
+        );
+        assert_eq!(
+            encode(ClientEvent::ToolResult {
+                call_id: "call_1".to_string(),
+                output: "3".to_string(),
+            }),
+            json!({
+                "type": "conversation.item.create",
+                "item": { "type": "function_call_output", "call_id": "call_1", "output": "3" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_server_events() {
+        assert_eq!(
+            decode(json!({
+                "type": "response.audio.delta",
+                "event_id": "event_1",
+                "response_id": "resp_1",
+                "item_id": "item_1",
+                "output_index": 0,
+                "content_index": 0,
+                "delta": "AQID"
+            })),
+            ServerEvent::AudioDelta {
+                response_id: "resp_1".to_string(),
+                data: vec![1, 2, 3],
+            }
+        );
+
+        assert_eq!(
+            decode(json!({
+                "type": "response.output_audio_transcript.delta",
+                "response_id": "resp_1",
+                "delta": "Hello"
+            })),
+            ServerEvent::AudioTranscriptDelta {
+                response_id: "resp_1".to_string(),
+                delta: "Hello".to_string(),
+            }
+        );
+
+        assert_eq!(
+            decode(json!({
+                "type": "response.output_item.done",
+                "response_id": "resp_1",
+                "item": {
+                    "id": "item_1",
+                    "type": "function_call",
+                    "status": "completed",
+                    "name": "add",
+                    "call_id": "call_1",
+                    "arguments": "{\"x\":1,\"y\":2}"
+                }
+            })),
+            ServerEvent::ToolCall(
+                ToolCall::new(
+                    "item_1".to_string(),
+                    ToolFunction::new("add".to_string(), json!({ "x": 1, "y": 2 }))
+                )
+                .with_call_id("call_1".to_string())
+            )
+        );
+
+        assert_eq!(
+            decode(json!({
+                "type": "response.done",
+                "response": {
+                    "id": "resp_1",
+                    "status": "completed",
+                    "usage": { "input_tokens": 10, "output_tokens": 5, "total_tokens": 15 }
+                }
+            })),
+            ServerEvent::ResponseDone {
+                response_id: "resp_1".to_string(),
+                usage: Some(Usage {
+                    input_tokens: 10,
+                    output_tokens: 5,
+                    total_tokens: 15,
+                }),
+            }
+        );
+
+        let rate_limits = json!({ "type": "rate_limits.updated", "rate_limits": [] });
+        assert_eq!(decode(rate_limits.clone()), ServerEvent::Other(rate_limits));
+    }
+}
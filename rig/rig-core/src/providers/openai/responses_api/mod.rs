@@ -10,7 +10,7 @@
 use super::completion::ToolChoice;
 use super::{Client, responses_api::streaming::StreamingCompletionResponse};
 use super::{InputAudio, SystemContent};
-use crate::completion::CompletionError;
+use crate::completion::{CompletionError, SamplingParam};
 use crate::http_client;
 use crate::http_client::HttpClientExt;
 use crate::json_utils;
@@ -56,9 +56,9 @@ pub struct CompletionRequest {
     /// If none provided, the default option is "auto".
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<ToolChoice>,
-    /// The tools you want to use. Currently this is limited to functions, but will be expanded on in future.
+    /// The tools you want to use: functions, and the tools built into OpenAI.
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub tools: Vec<ResponsesToolDefinition>,
+    pub tools: Vec<ResponsesTool>,
     /// Additional parameters
     #[serde(flatten)]
     pub additional_parameters: AdditionalParameters,
@@ -91,59 +91,72 @@ impl CompletionRequest {
         use serde_json::json;
 
         // Convert input items to simple format
-        let simple_input: Vec<serde_json::Value> = self.input.iter().map(|item| {
-            match &item.input {
-                InputContent::Message(msg) => {
-                    let role = match &item.role {
-                        Some(Role::User) => "user",
-                        Some(Role::Assistant) => "assistant",
-                        Some(Role::System) => "system",
-                        None => "assistant",
-                    };
-                    // Extract plain text content
-                    let content = match msg {
-                        Message::User { content, .. } => {
-                            content.iter().filter_map(|c| {
-                                match c {
+        let simple_input: Vec<serde_json::Value> = self
+            .input
+            .iter()
+            .map(|item| {
+                match &item.input {
+                    InputContent::Message(msg) => {
+                        let role = match &item.role {
+                            Some(Role::User) => "user",
+                            Some(Role::Assistant) => "assistant",
+                            Some(Role::System) => "system",
+                            None => "assistant",
+                        };
+                        // Extract plain text content
+                        let content = match msg {
+                            Message::User { content, .. } => content
+                                .iter()
+                                .filter_map(|c| match c {
                                     UserContent::InputText { text } => Some(text.clone()),
                                     _ => None,
-                                }
-                            }).collect::<Vec<_>>().join("\n")
-                        },
-                        Message::Assistant { content, .. } => {
-                            content.iter().filter_map(|c| {
-                                match c {
-                                    AssistantContentType::Text(AssistantContent::OutputText(Text { text })) => Some(text.clone()),
-                                    AssistantContentType::Text(AssistantContent::Refusal { refusal }) => Some(refusal.clone()),
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                            Message::Assistant { content, .. } => content
+                                .iter()
+                                .filter_map(|c| match c {
+                                    AssistantContentType::Text(AssistantContent::OutputText(
+                                        Text { text, .. },
+                                    )) => Some(text.clone()),
+                                    AssistantContentType::Text(AssistantContent::Refusal {
+                                        refusal,
+                                    }) => Some(refusal.clone()),
                                     _ => None,
-                                }
-                            }).collect::<Vec<_>>().join("\n")
-                        },
-                        Message::System { content, .. } => {
-                            content.iter().map(|c| c.text.clone()).collect::<Vec<_>>().join("\n")
-                        },
-                        _ => String::new(),
-                    };
-                    json!({ "role": role, "content": content })
-                },
-                InputContent::FunctionCall(fc) => {
-                    json!({
-                        "type": "function_call",
-                        "call_id": fc.call_id,
-                        "name": fc.name,
-                        "arguments": fc.arguments.to_string()
-                    })
-                },
-                InputContent::FunctionCallOutput(tr) => {
-                    json!({
-                        "type": "function_call_output",
-                        "call_id": tr.call_id,
-                        "output": tr.output
-                    })
-                },
-                InputContent::Reasoning(_) => json!({}),
-            }
-        }).collect();
+                                })
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                            Message::System { content, .. } => content
+                                .iter()
+                                .map(|c| c.text.clone())
+                                .collect::<Vec<_>>()
+                                .join("\n"),
+                            _ => String::new(),
+                        };
+                        json!({ "role": role, "content": content })
+                    }
+                    InputContent::FunctionCall(fc) => {
+                        json!({
+                            "type": "function_call",
+                            "call_id": fc.call_id,
+                            "name": fc.name,
+                            "arguments": fc.arguments.to_string()
+                        })
+                    }
+                    InputContent::FunctionCallOutput(tr) => {
+                        json!({
+                            "type": "function_call_output",
+                            "call_id": tr.call_id,
+                            "output": tr.output
+                        })
+                    }
+                    InputContent::Reasoning(_)
+                    | InputContent::WebSearchCall(_)
+                    | InputContent::CodeInterpreterCall(_)
+                    | InputContent::FileSearchCall(_) => json!({}),
+                }
+            })
+            .collect();
 
         // Build codex-compatible request (no max_output_tokens)
         let mut request = json!({
@@ -206,6 +219,9 @@ pub enum InputContent {
     Reasoning(OpenAIReasoning),
     FunctionCall(OutputFunctionCall),
     FunctionCallOutput(ToolResult),
+    WebSearchCall(OutputWebSearchCall),
+    CodeInterpreterCall(OutputCodeInterpreterCall),
+    FileSearchCall(OutputFileSearchCall),
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -298,7 +314,7 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
 
                 for user_content in content {
                     match user_content {
-                        crate::message::UserContent::Text(Text { text }) => {
+                        crate::message::UserContent::Text(Text { text, .. }) => {
                             items.push(InputItem {
                                 role: Some(Role::User),
                                 input: InputContent::Message(Message::User {
@@ -317,6 +333,7 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                             for tool_result_content in tool_content {
                                 let crate::completion::message::ToolResultContent::Text(Text {
                                     text,
+                                    ..
                                 }) = tool_result_content
                                 else {
                                     return Err(CompletionError::ProviderError(
@@ -336,6 +353,21 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                                 });
                             }
                         }
+                        crate::message::UserContent::Document(Document {
+                            data: DocumentSourceKind::FileId(file_id),
+                            ..
+                        }) => items.push(InputItem {
+                            role: Some(Role::User),
+                            input: InputContent::Message(Message::User {
+                                content: OneOrMany::one(UserContent::InputFile {
+                                    file_id: Some(file_id),
+                                    file_url: None,
+                                    file_data: None,
+                                    filename: None,
+                                }),
+                                name: None,
+                            }),
+                        }),
                         crate::message::UserContent::Document(Document {
                             data,
                             media_type: Some(DocumentMediaType::PDF),
@@ -363,6 +395,7 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                                 role: Some(Role::User),
                                 input: InputContent::Message(Message::User {
                                     content: OneOrMany::one(UserContent::InputFile {
+                                        file_id: None,
                                         file_data,
                                         file_url,
                                         filename: Some("document.pdf".to_string()),
@@ -392,6 +425,21 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                                 name: None,
                             }),
                         }),
+                        crate::message::UserContent::Image(crate::message::Image {
+                            data: DocumentSourceKind::FileId(file_id),
+                            detail,
+                            ..
+                        }) => items.push(InputItem {
+                            role: Some(Role::User),
+                            input: InputContent::Message(Message::User {
+                                content: OneOrMany::one(UserContent::InputImage {
+                                    image_url: String::new(),
+                                    file_id: Some(file_id),
+                                    detail: detail.unwrap_or_default(),
+                                }),
+                                name: None,
+                            }),
+                        }),
                         crate::message::UserContent::Image(crate::message::Image {
                             data,
                             media_type,
@@ -425,6 +473,7 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                                 input: InputContent::Message(Message::User {
                                     content: OneOrMany::one(UserContent::InputImage {
                                         image_url: url,
+                                        file_id: None,
                                         detail: detail.unwrap_or_default(),
                                     }),
                                     name: None,
@@ -446,13 +495,13 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
 
                 for assistant_content in content {
                     match assistant_content {
-                        crate::message::AssistantContent::Text(Text { text }) => {
+                        crate::message::AssistantContent::Text(Text { text, .. }) => {
                             let id = id.as_ref().unwrap_or(&String::default()).clone();
                             items.push(InputItem {
                                 role: Some(Role::Assistant),
                                 input: InputContent::Message(Message::Assistant {
                                     content: OneOrMany::one(AssistantContentType::Text(
-                                        AssistantContent::OutputText(Text { text }),
+                                        AssistantContent::OutputText(Text::from(text)),
                                     )),
                                     id,
                                     name: None,
@@ -478,15 +527,25 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                             });
                         }
                         crate::message::AssistantContent::Reasoning(
-                            crate::message::Reasoning { id, reasoning, .. },
+                            crate::message::Reasoning {
+                                id,
+                                reasoning,
+                                signature,
+                            },
                         ) => {
+                            // Reasoning items without an OpenAI-generated ID (e.g. produced by
+                            // another provider) cannot be sent back
+                            let Some(id) = id else { continue };
+
                             items.push(InputItem {
                                 role: None,
                                 input: InputContent::Reasoning(OpenAIReasoning {
-                                    id: id
-                                        .expect("An OpenAI-generated ID is required when using OpenAI reasoning items"),
-                                    summary: reasoning.into_iter().map(|x| ReasoningSummary::new(&x)).collect(),
-                                    encrypted_content: None,
+                                    id,
+                                    summary: reasoning
+                                        .into_iter()
+                                        .map(|x| ReasoningSummary::new(&x))
+                                        .collect(),
+                                    encrypted_content: signature,
                                     status: None,
                                 }),
                             });
@@ -497,6 +556,18 @@ impl TryFrom<crate::completion::Message> for Vec<InputItem> {
                                     .to_string(),
                             ));
                         }
+                        crate::message::AssistantContent::HostedToolCall(call) => {
+                            // Hosted tool calls of other providers cannot be sent back
+                            let Some(input) = call
+                                .raw
+                                .and_then(|raw| serde_json::from_value::<Output>(raw).ok())
+                                .and_then(|output| output.into_hosted_tool_input())
+                            else {
+                                continue;
+                            };
+
+                            items.push(InputItem { role: None, input });
+                        }
                     }
                 }
 
@@ -528,6 +599,77 @@ pub struct ResponsesToolDefinition {
     pub description: String,
 }
 
+/// A tool of a request: either a function, or a tool built into OpenAI.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum ResponsesTool {
+    Function(ResponsesToolDefinition),
+    BuiltIn(BuiltInTool),
+}
+
+/// A tool built into OpenAI, see [completion::HostedTool].
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BuiltInTool {
+    #[serde(alias = "web_search_preview")]
+    WebSearch {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        filters: Option<WebSearchFilters>,
+    },
+    CodeInterpreter {
+        /// The container to run the code in, either a container ID or `{"type": "auto"}`.
+        container: serde_json::Value,
+    },
+    FileSearch {
+        vector_store_ids: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max_num_results: Option<u32>,
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct WebSearchFilters {
+    pub allowed_domains: Vec<String>,
+}
+
+impl BuiltInTool {
+    /// The results to include in the response for the calls of the tool.
+    fn include(&self) -> Option<Include> {
+        match self {
+            BuiltInTool::WebSearch { .. } => Some(Include::WebSearchCallActionSources),
+            BuiltInTool::CodeInterpreter { .. } => Some(Include::CodeInterpreterCallOutputs),
+            BuiltInTool::FileSearch { .. } => Some(Include::FileSearchCallResults),
+        }
+    }
+}
+
+impl TryFrom<&completion::HostedTool> for BuiltInTool {
+    type Error = CompletionError;
+
+    fn try_from(tool: &completion::HostedTool) -> Result<Self, Self::Error> {
+        match tool {
+            completion::HostedTool::WebSearch {
+                allowed_domains, ..
+            } => Ok(BuiltInTool::WebSearch {
+                filters: (!allowed_domains.is_empty()).then(|| WebSearchFilters {
+                    allowed_domains: allowed_domains.clone(),
+                }),
+            }),
+            completion::HostedTool::CodeInterpreter => Ok(BuiltInTool::CodeInterpreter {
+                container: serde_json::json!({ "type": "auto" }),
+            }),
+            completion::HostedTool::FileSearch {
+                store_ids,
+                max_num_results,
+            } => Ok(BuiltInTool::FileSearch {
+                vector_store_ids: store_ids.clone(),
+                max_num_results: *max_num_results,
+            }),
+            tool => Err(tool.unsupported("OpenAI Responses")),
+        }
+    }
+}
+
 impl From<completion::ToolDefinition> for ResponsesToolDefinition {
     fn from(value: completion::ToolDefinition) -> Self {
         let completion::ToolDefinition {
@@ -684,12 +826,49 @@ pub enum ResponseStatus {
     Incomplete,
 }
 
+impl CompletionResponse {
+    /// The reason why the model stopped generating, derived from the status of the response.
+    /// Returns `None` while the response is still queued or in progress.
+    pub fn finish_reason(&self) -> Option<completion::FinishReason> {
+        match self.status {
+            ResponseStatus::Completed
+                if self
+                    .output
+                    .iter()
+                    .any(|output| matches!(output, Output::FunctionCall(_))) =>
+            {
+                Some(completion::FinishReason::ToolCalls)
+            }
+            ResponseStatus::Completed => Some(completion::FinishReason::Stop),
+            ResponseStatus::Incomplete => Some(
+                match self
+                    .incomplete_details
+                    .as_ref()
+                    .map(|details| details.reason.as_str())
+                {
+                    Some("max_output_tokens") => completion::FinishReason::Length,
+                    Some("content_filter") => completion::FinishReason::ContentFilter,
+                    Some(reason) => completion::FinishReason::Other(reason.to_string()),
+                    None => completion::FinishReason::Other("incomplete".to_string()),
+                },
+            ),
+            ResponseStatus::Failed => Some(completion::FinishReason::Other("failed".to_string())),
+            ResponseStatus::Cancelled => {
+                Some(completion::FinishReason::Other("cancelled".to_string()))
+            }
+            ResponseStatus::InProgress | ResponseStatus::Queued => None,
+        }
+    }
+}
+
 /// Attempt to try and create a `NewCompletionRequest` from a model name and [`crate::completion::CompletionRequest`]
 impl TryFrom<(String, crate::completion::CompletionRequest)> for CompletionRequest {
     type Error = CompletionError;
     fn try_from(
         (model, req): (String, crate::completion::CompletionRequest),
     ) -> Result<Self, Self::Error> {
+        req.check_logprobs_unsupported("OpenAI Responses")?;
+
         let input = {
             let mut partial_history = vec![];
             if let Some(docs) = req.normalized_documents() {
@@ -723,7 +902,7 @@ impl TryFrom<(String, crate::completion::CompletionRequest)> for CompletionReque
             .unwrap_or(Value::Null)
             .as_bool();
 
-        let additional_parameters = if let Some(ref map) = req.additional_params {
+        let mut additional_parameters = if let Some(ref map) = req.additional_params {
             tracing::debug!("additional_params JSON: {:?}", map);
             let mut params = serde_json::from_value::<AdditionalParameters>(map.clone()).expect("Converting additional parameters to AdditionalParameters should never fail as every field is an Option");
             // Manually check for codex_mode since it has skip_serializing
@@ -738,8 +917,48 @@ impl TryFrom<(String, crate::completion::CompletionRequest)> for CompletionReque
             AdditionalParameters::default()
         };
 
+        req.sampling
+            .check_supported("OpenAI Responses", &[SamplingParam::TopP])?;
+        // A `top_p` given in the additional parameters takes precedence
+        if additional_parameters.top_p.is_none() {
+            additional_parameters.top_p = req.sampling.top_p;
+        }
+
+        // A reasoning configuration given in the additional parameters takes precedence
+        if additional_parameters.reasoning.is_none()
+            && let Some(reasoning) = &req.reasoning
+        {
+            additional_parameters.reasoning = Some(Reasoning {
+                effort: reasoning.resolved_effort().map(ReasoningEffort::from),
+                summary: reasoning
+                    .summary
+                    .and_then(|summary| summary.then_some(ReasoningSummaryLevel::Auto)),
+            });
+        }
+
         let tool_choice = req.tool_choice.map(ToolChoice::try_from).transpose()?;
 
+        let built_in_tools = req
+            .hosted_tools
+            .iter()
+            .map(BuiltInTool::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The results of the built-in tool calls are only returned when included
+        for include in built_in_tools.iter().filter_map(BuiltInTool::include) {
+            let includes = additional_parameters.include.get_or_insert_with(Vec::new);
+            if !includes.contains(&include) {
+                includes.push(include);
+            }
+        }
+
+        let mut tools: Vec<ResponsesTool> = req
+            .tools
+            .into_iter()
+            .map(|tool| ResponsesTool::Function(tool.into()))
+            .collect();
+        tools.extend(built_in_tools.into_iter().map(ResponsesTool::BuiltIn));
+
         Ok(Self {
             input,
             model,
@@ -747,11 +966,7 @@ impl TryFrom<(String, crate::completion::CompletionRequest)> for CompletionReque
             max_output_tokens: req.max_tokens,
             stream,
             tool_choice,
-            tools: req
-                .tools
-                .into_iter()
-                .map(ResponsesToolDefinition::from)
-                .collect(),
+            tools,
             temperature: req.temperature,
             additional_parameters,
         })
@@ -829,7 +1044,7 @@ pub struct CompletionResponse {
     pub output: Vec<Output>,
     /// Tools
     #[serde(default)]
-    pub tools: Vec<ResponsesToolDefinition>,
+    pub tools: Vec<ResponsesTool>,
     /// Additional parameters
     #[serde(flatten)]
     pub additional_parameters: AdditionalParameters,
@@ -998,6 +1213,18 @@ pub enum ReasoningEffort {
     High,
 }
 
+impl From<completion::ReasoningEffort> for ReasoningEffort {
+    fn from(effort: completion::ReasoningEffort) -> Self {
+        match effort {
+            completion::ReasoningEffort::None => ReasoningEffort::None,
+            completion::ReasoningEffort::Minimal => ReasoningEffort::Minimal,
+            completion::ReasoningEffort::Low => ReasoningEffort::Low,
+            completion::ReasoningEffort::Medium => ReasoningEffort::Medium,
+            completion::ReasoningEffort::High => ReasoningEffort::High,
+        }
+    }
+}
+
 /// The amount of effort that will go into a reasoning summary by a given model.
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -1010,7 +1237,7 @@ pub enum ReasoningSummaryLevel {
 
 /// Results to additionally include in the OpenAI Responses API.
 /// Note that most of these are currently unsupported, but have been added for completeness.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
 pub enum Include {
     #[serde(rename = "file_search_call.results")]
     FileSearchCallResults,
@@ -1022,6 +1249,8 @@ pub enum Include {
     ReasoningEncryptedContent,
     #[serde(rename = "code_interpreter_call.outputs")]
     CodeInterpreterCallOutputs,
+    #[serde(rename = "web_search_call.action.sources")]
+    WebSearchCallActionSources,
 }
 
 /// A currently non-exhaustive list of output types.
@@ -1035,7 +1264,179 @@ pub enum Output {
     Reasoning {
         id: String,
         summary: Vec<ReasoningSummary>,
+        /// Only returned when `reasoning.encrypted_content` is included, required to send the
+        /// reasoning back when responses are not stored
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        encrypted_content: Option<String>,
     },
+    WebSearchCall(OutputWebSearchCall),
+    CodeInterpreterCall(OutputCodeInterpreterCall),
+    FileSearchCall(OutputFileSearchCall),
+}
+
+impl Output {
+    /// Converts the call of a built-in tool into a hosted tool call, keeping the output item as
+    /// the raw call to send it back.
+    pub fn hosted_tool_call(&self) -> Option<message::HostedToolCall> {
+        let call = match self {
+            Output::WebSearchCall(call) => {
+                let mut hosted =
+                    message::HostedToolCall::new(&call.id, message::HostedToolKind::WebSearch);
+                match &call.action {
+                    Some(WebSearchAction::Search { query, sources }) => {
+                        hosted.input = query.clone();
+                        hosted.sources = sources
+                            .iter()
+                            .flatten()
+                            .map(|WebSearchSource::Url { url }| {
+                                message::HostedToolSource::url(url, None)
+                            })
+                            .collect();
+                    }
+                    Some(WebSearchAction::OpenPage { url } | WebSearchAction::Find { url, .. }) => {
+                        hosted.input = url.clone();
+                    }
+                    None => {}
+                }
+                hosted.with_error(failed(&call.status))
+            }
+            Output::CodeInterpreterCall(call) => {
+                let logs = call
+                    .outputs
+                    .iter()
+                    .flatten()
+                    .filter_map(|output| match output {
+                        CodeInterpreterOutput::Logs { logs } => Some(logs.as_str()),
+                        CodeInterpreterOutput::Image { .. } => None,
+                    })
+                    .collect::<Vec<_>>();
+
+                message::HostedToolCall::new(&call.id, message::HostedToolKind::CodeInterpreter)
+                    .with_input(call.code.clone())
+                    .with_output((!logs.is_empty()).then(|| logs.join("\n")))
+                    .with_error(failed(&call.status))
+            }
+            Output::FileSearchCall(call) => {
+                let sources = call
+                    .results
+                    .iter()
+                    .flatten()
+                    .map(|result| message::HostedToolSource {
+                        file_id: result.file_id.clone(),
+                        title: result.filename.clone(),
+                        text: result.text.clone(),
+                        ..Default::default()
+                    })
+                    .collect();
+
+                message::HostedToolCall::new(&call.id, message::HostedToolKind::FileSearch)
+                    .with_input((!call.queries.is_empty()).then(|| call.queries.join("\n")))
+                    .with_sources(sources)
+                    .with_error(failed(&call.status))
+            }
+            _ => return None,
+        };
+
+        Some(call.with_raw(serde_json::to_value(self).ok()?))
+    }
+
+    /// The input item sending back the call of a built-in tool.
+    fn into_hosted_tool_input(self) -> Option<InputContent> {
+        match self {
+            Output::WebSearchCall(call) => Some(InputContent::WebSearchCall(call)),
+            Output::CodeInterpreterCall(call) => Some(InputContent::CodeInterpreterCall(call)),
+            Output::FileSearchCall(call) => Some(InputContent::FileSearchCall(call)),
+            _ => None,
+        }
+    }
+}
+
+fn failed(status: &str) -> Option<String> {
+    (status == "failed").then(|| "The hosted tool call failed".to_string())
+}
+
+/// A call of the built-in web search tool.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OutputWebSearchCall {
+    pub id: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<WebSearchAction>,
+}
+
+/// The action taken by a web search call.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSearchAction {
+    Search {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        query: Option<String>,
+        /// Only returned when `web_search_call.action.sources` is included
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sources: Option<Vec<WebSearchSource>>,
+    },
+    OpenPage {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    Find {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pattern: Option<String>,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebSearchSource {
+    Url { url: String },
+}
+
+/// A call of the built-in code interpreter tool.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OutputCodeInterpreterCall {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub code: Option<String>,
+    pub container_id: String,
+    /// Only returned when `code_interpreter_call.outputs` is included
+    #[serde(default)]
+    pub outputs: Option<Vec<CodeInterpreterOutput>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CodeInterpreterOutput {
+    Logs { logs: String },
+    Image { url: String },
+}
+
+/// A call of the built-in file search tool.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct OutputFileSearchCall {
+    pub id: String,
+    pub status: String,
+    #[serde(default)]
+    pub queries: Vec<String>,
+    /// Only returned when `file_search_call.results` is included
+    #[serde(default)]
+    pub results: Option<Vec<FileSearchResult>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct FileSearchResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attributes: Option<serde_json::Value>,
 }
 
 impl From<Output> for Vec<completion::AssistantContent> {
@@ -1054,13 +1455,24 @@ impl From<Output> for Vec<completion::AssistantContent> {
             }) => vec![completion::AssistantContent::tool_call_with_call_id(
                 id, call_id, name, arguments,
             )],
-            Output::Reasoning { id, summary } => {
+            Output::Reasoning {
+                id,
+                summary,
+                encrypted_content,
+            } => {
                 let summary: Vec<String> = summary.into_iter().map(|x| x.text()).collect();
 
                 vec![completion::AssistantContent::Reasoning(
-                    message::Reasoning::multi(summary).with_id(id),
+                    message::Reasoning::multi(summary)
+                        .with_id(id)
+                        .with_signature(encrypted_content),
                 )]
             }
+            output => output
+                .hosted_tool_call()
+                .map(completion::AssistantContent::HostedToolCall)
+                .into_iter()
+                .collect(),
         };
 
         res
@@ -1251,6 +1663,10 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response.finish_reason(),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: None,
             raw_response: response,
         })
     }
@@ -1318,10 +1734,10 @@ impl From<AssistantContent> for completion::AssistantContent {
     fn from(value: AssistantContent) -> Self {
         match value {
             AssistantContent::Refusal { refusal } => {
-                completion::AssistantContent::Text(Text { text: refusal })
+                completion::AssistantContent::Text(Text::from(refusal))
             }
-            AssistantContent::OutputText(Text { text }) => {
-                completion::AssistantContent::Text(Text { text })
+            AssistantContent::OutputText(Text { text, .. }) => {
+                completion::AssistantContent::Text(Text::from(text))
             }
         }
     }
@@ -1344,11 +1760,18 @@ pub enum UserContent {
         text: String,
     },
     InputImage {
+        #[serde(default, skip_serializing_if = "String::is_empty")]
         image_url: String,
+        /// The id of an image uploaded with the Files API, used instead of the image URL
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
         #[serde(default)]
         detail: ImageDetail,
     },
     InputFile {
+        /// The id of a file uploaded with the Files API (see [crate::files])
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         file_url: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -1392,7 +1815,7 @@ impl TryFrom<message::Message> for Vec<Message> {
                                     let res = content.first();
                                     match res {
                                         completion::message::ToolResultContent::Text(Text {
-                                            text,
+                                            text, ..
                                         }) => text,
                                         _ => return  Err(MessageError::ConversionError("This API only currently supports text tool results".into()))
                                     }
@@ -1405,9 +1828,18 @@ impl TryFrom<message::Message> for Vec<Message> {
                     let other_content = other_content
                         .into_iter()
                         .map(|content| match content {
-                            message::UserContent::Text(message::Text { text }) => {
+                            message::UserContent::Text(message::Text { text, .. }) => {
                                 Ok(UserContent::InputText { text })
                             }
+                            message::UserContent::Image(message::Image {
+                                data: DocumentSourceKind::FileId(file_id),
+                                detail,
+                                ..
+                            }) => Ok(UserContent::InputImage {
+                                image_url: String::new(),
+                                file_id: Some(file_id),
+                                detail: detail.unwrap_or_default(),
+                            }),
                             message::UserContent::Image(message::Image {
                                 data,
                                 detail,
@@ -1439,9 +1871,19 @@ impl TryFrom<message::Message> for Vec<Message> {
 
                                 Ok(UserContent::InputImage {
                                     image_url: url,
+                                    file_id: None,
                                     detail: detail.unwrap_or_default(),
                                 })
                             }
+                            message::UserContent::Document(message::Document {
+                                data: DocumentSourceKind::FileId(file_id),
+                                ..
+                            }) => Ok(UserContent::InputFile {
+                                file_id: Some(file_id),
+                                file_url: None,
+                                file_data: None,
+                                filename: None,
+                            }),
                             message::UserContent::Document(message::Document {
                                 media_type: Some(DocumentMediaType::PDF),
                                 data,
@@ -1466,6 +1908,7 @@ impl TryFrom<message::Message> for Vec<Message> {
                                 };
 
                                 Ok(UserContent::InputFile {
+                                    file_id: None,
                                     file_url,
                                     file_data,
                                     filename: Some("document.pdf".into()),
@@ -1509,13 +1952,13 @@ impl TryFrom<message::Message> for Vec<Message> {
                 let assistant_message_id = id;
 
                 match content.first() {
-                    crate::message::AssistantContent::Text(Text { text }) => {
+                    crate::message::AssistantContent::Text(Text { text, .. }) => {
                         Ok(vec![Message::Assistant {
                             id: assistant_message_id
                                 .expect("The assistant message ID should exist"),
                             status: ToolStatus::Completed,
                             content: OneOrMany::one(AssistantContentType::Text(
-                                AssistantContent::OutputText(Text { text }),
+                                AssistantContent::OutputText(Text::from(text)),
                             )),
                             name: None,
                         }])
@@ -1542,12 +1985,12 @@ impl TryFrom<message::Message> for Vec<Message> {
                     crate::message::AssistantContent::Reasoning(crate::message::Reasoning {
                         id,
                         reasoning,
-                        ..
+                        signature,
                     }) => Ok(vec![Message::Assistant {
                         content: OneOrMany::one(AssistantContentType::Reasoning(OpenAIReasoning {
                             id: id.expect("An OpenAI-generated ID is required when using OpenAI reasoning items"),
                             summary: reasoning.into_iter().map(|x| ReasoningSummary::SummaryText { text: x }).collect(),
-                            encrypted_content: None,
+                            encrypted_content: signature,
                             status: Some(ToolStatus::Completed),
                         })),
                         id: assistant_message_id.expect("The assistant message ID should exist!"),
@@ -1559,6 +2002,11 @@ impl TryFrom<message::Message> for Vec<Message> {
                             "Assistant image content is not supported in OpenAI Responses API".into(),
                         ))
                     }
+                    crate::message::AssistantContent::HostedToolCall(_) => {
+                        Err(MessageError::ConversionError(
+                            "Hosted tool calls are sent back as input items in OpenAI Responses API".into(),
+                        ))
+                    }
                 }
             }
         }
@@ -1574,3 +2022,104 @@ impl FromStr for UserContent {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_hosted_tools_request() {
+        let request = completion::CompletionRequestBuilder::new(
+            crate::testing::MockCompletionModel::new(),
+            "What's new in Rust?",
+        )
+        .hosted_tool(
+            completion::HostedTool::web_search().with_allowed_domains(["blog.rust-lang.org"]),
+        )
+        .hosted_tool(completion::HostedTool::file_search(["vs_1"]))
+        .build();
+
+        let request = CompletionRequest::try_from(("gpt-5".to_string(), request)).unwrap();
+        let request = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            request["tools"],
+            json!([
+                {
+                    "type": "web_search",
+                    "filters": { "allowed_domains": ["blog.rust-lang.org"] }
+                },
+                { "type": "file_search", "vector_store_ids": ["vs_1"] }
+            ])
+        );
+        assert_eq!(
+            request["include"],
+            json!(["web_search_call.action.sources", "file_search_call.results"])
+        );
+
+        let request = completion::CompletionRequestBuilder::new(
+            crate::testing::MockCompletionModel::new(),
+            "Summarize https://rig.rs",
+        )
+        .hosted_tool(completion::HostedTool::url_context())
+        .build();
+        assert!(CompletionRequest::try_from(("gpt-5".to_string(), request)).is_err());
+    }
+
+    #[test]
+    fn test_web_search_call_round_trip() {
+        let output: Output = serde_json::from_value(json!({
+            "type": "web_search_call",
+            "id": "ws_123",
+            "status": "completed",
+            "action": {
+                "type": "search",
+                "query": "rust 1.90 release",
+                "sources": [{ "type": "url", "url": "https://blog.rust-lang.org" }]
+            }
+        }))
+        .unwrap();
+
+        let content = <Vec<completion::AssistantContent>>::from(output);
+        let [completion::AssistantContent::HostedToolCall(call)] = content.as_slice() else {
+            panic!("Expected a hosted tool call");
+        };
+        assert_eq!(call.tool, message::HostedToolKind::WebSearch);
+        assert_eq!(call.input.as_deref(), Some("rust 1.90 release"));
+        assert_eq!(
+            call.sources[0].url.as_deref(),
+            Some("https://blog.rust-lang.org")
+        );
+
+        // The call is sent back as an input item
+        let items = <Vec<InputItem>>::try_from(completion::Message::Assistant {
+            id: None,
+            content: OneOrMany::one(completion::AssistantContent::HostedToolCall(call.clone())),
+        })
+        .unwrap();
+        assert_eq!(
+            serde_json::to_value(&items).unwrap()[0],
+            call.raw.clone().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_code_interpreter_call() {
+        let output: Output = serde_json::from_value(json!({
+            "type": "code_interpreter_call",
+            "id": "ci_123",
+            "status": "completed",
+            "code": "print(2 ** 10)",
+            "container_id": "cntr_123",
+            "outputs": [{ "type": "logs", "logs": "1024" }]
+        }))
+        .unwrap();
+
+        let call = output.hosted_tool_call().unwrap();
+        assert_eq!(call.tool, message::HostedToolKind::CodeInterpreter);
+        assert_eq!(call.input.as_deref(), Some("print(2 ** 10)"));
+        assert_eq!(call.output.as_deref(), Some("1024"));
+        assert_eq!(call.error, None);
+    }
+}
@@ -218,7 +218,10 @@ where
         let body = if is_codex {
             tracing::debug!("streaming: using codex JSON format");
             let codex_json = request.to_codex_json();
-            tracing::debug!("streaming: codex JSON: {}", serde_json::to_string_pretty(&codex_json).unwrap_or_default());
+            tracing::debug!(
+                "streaming: codex JSON: {}",
+                serde_json::to_string_pretty(&codex_json).unwrap_or_default()
+            );
             if enabled!(Level::TRACE) {
                 tracing::trace!(
                     target: "rig::completions",
@@ -312,7 +315,7 @@ where
                                             ));
                                         }
 
-                                        StreamingItemDoneOutput {  item: Output::Reasoning {  summary, id }, .. } => {
+                                        StreamingItemDoneOutput {  item: Output::Reasoning {  summary, id, encrypted_content }, .. } => {
                                             let reasoning = summary
                                                 .iter()
                                                 .map(|x| {
@@ -324,10 +327,14 @@ where
                                             yield Ok(streaming::RawStreamingChoice::Reasoning {
                                                 id: Some(id.to_string()),
                                                 reasoning,
-                                                signature: None,
+                                                signature: encrypted_content.clone(),
                                             })
                                         }
-                                        _ => continue
+                                        StreamingItemDoneOutput { item, .. } => {
+                                            if let Some(call) = item.hosted_tool_call() {
+                                                yield Ok(streaming::RawStreamingChoice::HostedToolCall(call));
+                                            }
+                                        }
                                     }
                                 }
                                 ItemChunkKind::OutputTextDelta(delta) => {
@@ -350,9 +357,14 @@ where
                         }
 
                         if let StreamingCompletionChunk::Response(chunk) = data {
-                            if let ResponseChunk { kind: ResponseChunkKind::ResponseCompleted, response, .. } = *chunk {
-                                span.record("gen_ai.response.id", response.id);
-                                span.record("gen_ai.response.model", response.model);
+                            if let ResponseChunk { kind: ResponseChunkKind::ResponseCompleted | ResponseChunkKind::ResponseIncomplete | ResponseChunkKind::ResponseFailed, response, .. } = *chunk {
+                                span.record("gen_ai.response.id", &response.id);
+                                span.record("gen_ai.response.model", &response.model);
+                                yield Ok(RawStreamingChoice::Metadata(streaming::ResponseMetadata::new(
+                                    Some(response.id.clone()),
+                                    Some(response.model.clone()),
+                                    response.finish_reason(),
+                                )));
                                 if let Some(usage) = response.usage {
                                     final_usage = usage;
                                 }
@@ -1,10 +1,16 @@
+use async_stream::stream;
 use bytes::Bytes;
+use futures::StreamExt;
 
 use crate::http_client::multipart::Part;
-use crate::http_client::{HttpClientExt, MultipartForm};
+use crate::http_client::sse::{Event, GenericEventSource};
+use crate::http_client::{self, HttpClientExt, MultipartForm};
 use crate::providers::openai::{Client, client::ApiResponse};
 use crate::transcription;
-use crate::transcription::TranscriptionError;
+use crate::transcription::{
+    StreamedTranscription, StreamingTranscriptionRequest, StreamingTranscriptionResponse,
+    TranscriptionError, TranscriptionSegment, TranscriptionWord,
+};
 use serde::Deserialize;
 
 // ================================================================
@@ -12,10 +18,23 @@ use serde::Deserialize;
 // ================================================================
 
 pub const WHISPER_1: &str = "whisper-1";
+pub const GPT_4O_TRANSCRIBE: &str = "gpt-4o-transcribe";
+pub const GPT_4O_MINI_TRANSCRIBE: &str = "gpt-4o-mini-transcribe";
+pub const GPT_4O_TRANSCRIBE_DIARIZE: &str = "gpt-4o-transcribe-diarize";
 
+/// A transcription of the OpenAI compatible APIs. The `verbose_json` and `diarized_json` formats
+/// add the language, duration and timed segments, and the words with the `word` timestamps.
 #[derive(Debug, Deserialize)]
 pub struct TranscriptionResponse {
     pub text: String,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub segments: Vec<TranscriptionSegment>,
+    #[serde(default)]
+    pub words: Vec<TranscriptionWord>,
 }
 
 impl TryFrom<TranscriptionResponse>
@@ -26,6 +45,12 @@ impl TryFrom<TranscriptionResponse>
     fn try_from(value: TranscriptionResponse) -> Result<Self, Self::Error> {
         Ok(transcription::TranscriptionResponse {
             text: value.text.clone(),
+            segments: transcription::segments_with_words(
+                &value.text,
+                value.segments.clone(),
+                value.words.clone(),
+            ),
+            language: value.language.clone(),
             response: value,
         })
     }
@@ -37,6 +62,45 @@ pub struct TranscriptionModel<T = reqwest::Client> {
     pub model: String,
 }
 
+/// An event of a streamed transcription, sent by the `gpt-4o` transcription models when `stream`
+/// is set.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+enum StreamingTranscriptionEvent {
+    #[serde(rename = "transcript.text.delta")]
+    Delta { delta: String },
+    #[serde(rename = "transcript.text.done")]
+    Done { text: String },
+}
+
+/// The path of the OpenAI compatible endpoint of `request`.
+pub(crate) fn transcription_path(request: &transcription::TranscriptionRequest) -> &'static str {
+    if request.translate {
+        "/audio/translations"
+    } else {
+        "/audio/transcriptions"
+    }
+}
+
+/// Adds the response format and the timestamp granularities of `request` to an OpenAI compatible
+/// transcription form. Translations have no timestamp granularities.
+pub(crate) fn with_output_options(
+    mut body: MultipartForm,
+    request: &transcription::TranscriptionRequest,
+) -> MultipartForm {
+    if let Some(format) = request.output_format() {
+        body = body.text("response_format", format.as_str());
+    }
+
+    if !request.translate {
+        for granularity in &request.timestamp_granularities {
+            body = body.text("timestamp_granularities[]", granularity.as_str());
+        }
+    }
+
+    body
+}
+
 impl<T> TranscriptionModel<T> {
     pub fn new(client: Client<T>, model: impl Into<String>) -> Self {
         Self {
@@ -44,39 +108,21 @@ impl<T> TranscriptionModel<T> {
             model: model.into(),
         }
     }
-}
-
-impl<T> transcription::TranscriptionModel for TranscriptionModel<T>
-where
-    T: HttpClientExt + Clone + std::fmt::Debug + Default + Send + 'static,
-{
-    type Response = TranscriptionResponse;
-
-    type Client = Client<T>;
-
-    fn make(client: &Self::Client, model: impl Into<String>) -> Self {
-        Self::new(client.clone(), model)
-    }
-
-    async fn transcription(
-        &self,
-        request: transcription::TranscriptionRequest,
-    ) -> Result<
-        transcription::TranscriptionResponse<Self::Response>,
-        transcription::TranscriptionError,
-    > {
-        let data = request.data;
 
-        let mut body = MultipartForm::new()
+    fn create_form(&self, request: transcription::TranscriptionRequest) -> MultipartForm {
+        let mut body = with_output_options(MultipartForm::new(), &request)
             .text("model", self.model.clone())
-            .part(Part::bytes("file", data).filename(request.filename.clone()));
+            .part(Part::bytes("file", request.data).filename(request.filename));
 
-        if let Some(language) = request.language {
+        // Translations are always in English
+        if let Some(language) = request.language
+            && !request.translate
+        {
             body = body.text("language", language);
         }
 
         if let Some(prompt) = request.prompt {
-            body = body.text("prompt", prompt.clone());
+            body = body.text("prompt", prompt);
         }
 
         if let Some(ref temperature) = request.temperature {
@@ -92,11 +138,33 @@ where
             }
         }
 
-        let req = self
-            .client
-            .post("/audio/transcriptions")?
-            .body(body)
-            .unwrap();
+        body
+    }
+}
+
+impl<T> transcription::TranscriptionModel for TranscriptionModel<T>
+where
+    T: HttpClientExt + Clone + std::fmt::Debug + Default + Send + 'static,
+{
+    type Response = TranscriptionResponse;
+
+    type Client = Client<T>;
+
+    fn make(client: &Self::Client, model: impl Into<String>) -> Self {
+        Self::new(client.clone(), model)
+    }
+
+    async fn transcription(
+        &self,
+        request: transcription::TranscriptionRequest,
+    ) -> Result<
+        transcription::TranscriptionResponse<Self::Response>,
+        transcription::TranscriptionError,
+    > {
+        let path = transcription_path(&request);
+        let body = self.create_form(request);
+
+        let req = self.client.post(path)?.body(body).unwrap();
 
         let response = self.client.send_multipart::<Bytes>(req).await.unwrap();
 
@@ -114,4 +182,99 @@ where
             Err(TranscriptionError::ProviderError(str))
         }
     }
+    /// The `gpt-4o` transcription models stream the transcription of each window of audio as it is
+    /// generated, `whisper-1` transcribes each window at once.
+    async fn stream_transcription(
+        &self,
+        request: StreamingTranscriptionRequest,
+    ) -> Result<StreamingTranscriptionResponse, TranscriptionError> {
+        if self.model == WHISPER_1 {
+            return Ok(transcription::transcribe_windows(self.clone(), request));
+        }
+
+        let model = self.clone();
+        let StreamingTranscriptionRequest {
+            audio,
+            sample_rate,
+            channels,
+            window,
+            language,
+            prompt,
+            temperature,
+            additional_params,
+        } = request;
+        let mut windows = transcription::audio_windows(audio, sample_rate, channels, window);
+
+        Ok(Box::pin(stream! {
+            'windows: while let Some(window) = windows.next().await {
+                let (boundary, body) = model
+                    .create_form(transcription::TranscriptionRequest {
+                        data: window.wav,
+                        filename: "audio.wav".to_string(),
+                        language: language.clone(),
+                        prompt: prompt.clone(),
+                        temperature,
+                        response_format: None,
+                        timestamp_granularities: vec![],
+                        translate: false,
+                        additional_params: additional_params.clone(),
+                    })
+                    .text("stream", "true")
+                    .encode();
+
+                let req = match model.client.post("/audio/transcriptions").and_then(|req| {
+                    req.header(
+                        "Content-Type",
+                        format!("multipart/form-data; boundary={boundary}"),
+                    )
+                    .body(body)
+                    .map_err(http_client::Error::from)
+                }) {
+                    Ok(req) => req,
+                    Err(err) => {
+                        yield Err(err.into());
+                        break;
+                    }
+                };
+
+                let mut event_source = GenericEventSource::new(model.client.clone(), req);
+
+                while let Some(event) = event_source.next().await {
+                    match event {
+                        Ok(Event::Open) => continue,
+                        Ok(Event::Message(message)) => {
+                            match serde_json::from_str::<StreamingTranscriptionEvent>(&message.data) {
+                                Ok(StreamingTranscriptionEvent::Delta { delta }) => {
+                                    yield Ok(StreamedTranscription::Partial {
+                                        text: delta,
+                                        start: window.start,
+                                    });
+                                }
+                                Ok(StreamingTranscriptionEvent::Done { text }) => {
+                                    if !text.trim().is_empty() {
+                                        yield Ok(StreamedTranscription::Segment(
+                                            TranscriptionSegment::new(text, window.start, window.end),
+                                        ));
+                                    }
+                                }
+                                Err(err) => {
+                                    tracing::debug!(
+                                        "Couldn't parse OpenAI transcription event: {err}"
+                                    );
+                                }
+                            }
+                        }
+                        Err(http_client::Error::StreamEnded) => break,
+                        Err(err) => {
+                            yield Err(err.into());
+                            event_source.close();
+                            break 'windows;
+                        }
+                    }
+                }
+
+                event_source.close();
+            }
+        }))
+    }
 }
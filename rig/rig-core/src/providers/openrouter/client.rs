@@ -1,10 +1,11 @@
 use crate::{
     client::{
-        self, BearerAuth, Capabilities, Capable, DebugExt, Nothing, Provider, ProviderBuilder,
-        ProviderClient,
+        self, BearerAuth, Capabilities, Capable, DebugExt, Modality, ModelInfo, ModelListingClient,
+        ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
     },
     completion::GetTokenUsage,
-    http_client,
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -113,3 +114,133 @@ impl GetTokenUsage for Usage {
         Some(usage)
     }
 }
+
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+struct ModelList {
+    data: Vec<Model>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    context_length: Option<u64>,
+    #[serde(default)]
+    architecture: Option<ModelArchitecture>,
+    #[serde(default)]
+    top_provider: Option<ModelTopProvider>,
+    #[serde(default)]
+    supported_parameters: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelArchitecture {
+    #[serde(default)]
+    input_modalities: Vec<String>,
+    #[serde(default)]
+    output_modalities: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelTopProvider {
+    #[serde(default)]
+    max_completion_tokens: Option<u64>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        let modalities = |names: &[String]| {
+            names
+                .iter()
+                .filter_map(|name| Modality::from_name(name))
+                .collect::<Vec<_>>()
+        };
+        let supports = |param: &str| model.supported_parameters.iter().any(|p| p == param);
+        let (supports_tools, supports_reasoning) = (supports("tools"), supports("reasoning"));
+        let (input_modalities, output_modalities) = model
+            .architecture
+            .map(|arch| {
+                (
+                    modalities(&arch.input_modalities),
+                    modalities(&arch.output_modalities),
+                )
+            })
+            .unwrap_or_default();
+
+        Self {
+            name: model.name,
+            context_window: model.context_length,
+            max_output_tokens: model
+                .top_provider
+                .and_then(|provider| provider.max_completion_tokens),
+            input_modalities,
+            output_modalities,
+            supports_tools: Some(supports_tools),
+            supports_reasoning: Some(supports_reasoning),
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let models: ModelList = self.get_models("/models").await?;
+
+        Ok(models.data.into_iter().map(ModelInfo::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let models: ModelList = serde_json::from_value(serde_json::json!({
+            "data": [{
+                "id": "anthropic/claude-sonnet-4",
+                "name": "Anthropic: Claude Sonnet 4",
+                "context_length": 200000,
+                "architecture": {
+                    "modality": "text+image->text",
+                    "input_modalities": ["image", "text", "file"],
+                    "output_modalities": ["text"],
+                    "tokenizer": "Claude"
+                },
+                "top_provider": {
+                    "context_length": 200000,
+                    "max_completion_tokens": 64000,
+                    "is_moderated": true
+                },
+                "supported_parameters": ["max_tokens", "reasoning", "tools", "tool_choice"]
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(
+            models
+                .data
+                .into_iter()
+                .map(ModelInfo::from)
+                .collect::<Vec<_>>(),
+            vec![ModelInfo {
+                name: Some("Anthropic: Claude Sonnet 4".to_string()),
+                context_window: Some(200_000),
+                max_output_tokens: Some(64_000),
+                input_modalities: vec![Modality::Image, Modality::Text, Modality::File],
+                output_modalities: vec![Modality::Text],
+                supports_tools: Some(true),
+                supports_reasoning: Some(true),
+                ..ModelInfo::new("anthropic/claude-sonnet-4")
+            }]
+        );
+    }
+}
@@ -2,7 +2,10 @@ use super::{
     client::{ApiErrorResponse, ApiResponse, Client, Usage},
     streaming::StreamingCompletionResponse,
 };
+use crate::completion::SamplingParam;
 use crate::message;
+use crate::providers::openai::completion::map_finish_reason;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use crate::telemetry::SpanCombinator;
 use crate::{
     OneOrMany,
@@ -119,6 +122,18 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
         Ok(completion::CompletionResponse {
             choice,
             usage,
+            finish_reason: response
+                .choices
+                .first()
+                .and_then(|choice| choice.finish_reason.as_deref())
+                .map(map_finish_reason),
+            id: Some(response.id.clone()),
+            model: Some(response.model.clone()),
+            logprobs: response
+                .choices
+                .first()
+                .and_then(|choice| choice.logprobs.as_ref())
+                .and_then(crate::providers::openai::completion::token_logprobs),
             raw_response: response,
         })
     }
@@ -130,6 +145,8 @@ pub struct Choice {
     pub native_finish_reason: Option<String>,
     pub message: Message,
     pub finish_reason: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logprobs: Option<serde_json::Value>,
 }
 
 /// OpenRouter message.
@@ -316,6 +333,8 @@ impl TryFrom<OneOrMany<message::AssistantContent>> for Vec<Message> {
                         "OpenRouter currently doesn't support images.".into(),
                     ));
                 }
+                // Hosted tool calls can only be sent back to the provider that made them
+                message::AssistantContent::HostedToolCall(_) => {}
             }
         }
 
@@ -389,16 +408,64 @@ pub enum ToolChoiceFunctionKind {
     Function { name: String },
 }
 
+/// The sampling parameters supported by the OpenRouter API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::TopK,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
+/// The reasoning parameters of an OpenRouter request.
+/// See the [OpenRouter docs](https://openrouter.ai/docs/use-cases/reasoning-tokens).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReasoningParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effort: Option<completion::ReasoningEffort>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    /// Whether the reasoning is used internally by the model but not returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclude: Option<bool>,
+}
+
+impl From<&completion::ReasoningConfig> for ReasoningParams {
+    fn from(reasoning: &completion::ReasoningConfig) -> Self {
+        // OpenRouter accepts either an effort or a token budget, the explicit one wins
+        let (effort, max_tokens) = match reasoning.budget_tokens {
+            Some(budget_tokens) if !reasoning.is_disabled() && reasoning.effort.is_none() => {
+                (None, Some(budget_tokens))
+            }
+            _ => (reasoning.resolved_effort(), None),
+        };
+
+        Self {
+            effort,
+            max_tokens,
+            exclude: reasoning.summary.map(|summary| !summary),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct OpenrouterCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<crate::providers::openai::completion::ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<crate::providers::openai::completion::ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning: Option<ReasoningParams>,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<serde_json::Value>,
 }
@@ -419,6 +486,7 @@ impl TryFrom<OpenRouterRequestParams<'_>> for OpenrouterCompletionRequest {
             request: req,
             strict_tools,
         } = params;
+        req.check_hosted_tools_unsupported("OpenRouter")?;
 
         let mut full_history: Vec<Message> = match &req.preamble {
             Some(preamble) => vec![Message::system(preamble)],
@@ -457,12 +525,23 @@ impl TryFrom<OpenRouterRequestParams<'_>> for OpenrouterCompletionRequest {
             })
             .collect();
 
+        let reasoning = req
+            .reasoning_unless_overridden("reasoning")
+            .map(ReasoningParams::from);
+
         Ok(Self {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "OpenRouter",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
+            logprobs: req.logprobs.into(),
             tools,
             tool_choice,
+            reasoning,
             additional_params: req.additional_params,
         })
     }
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
 use async_stream::stream;
 use futures::StreamExt;
@@ -12,6 +12,7 @@ use crate::completion::{CompletionError, CompletionRequest, GetTokenUsage};
 use crate::http_client::HttpClientExt;
 use crate::http_client::sse::{Event, GenericEventSource};
 use crate::json_utils;
+use crate::providers::openai::completion::token_logprobs;
 use crate::providers::openrouter::{
     OpenRouterRequestParams, OpenrouterCompletionRequest, ReasoningDetails,
 };
@@ -46,6 +47,19 @@ pub enum FinishReason {
     Other(String),
 }
 
+impl From<&FinishReason> for crate::completion::FinishReason {
+    fn from(reason: &FinishReason) -> Self {
+        match reason {
+            FinishReason::ToolCalls => Self::ToolCalls,
+            FinishReason::Stop => Self::Stop,
+            FinishReason::Error => Self::Other("error".to_string()),
+            FinishReason::ContentFilter => Self::ContentFilter,
+            FinishReason::Length => Self::Length,
+            FinishReason::Other(reason) => Self::Other(reason.clone()),
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 #[allow(dead_code)]
 struct StreamingChoice {
@@ -176,8 +190,9 @@ where
 
     let stream = stream! {
         // Accumulate tool calls by index while streaming
-        let mut tool_calls: HashMap<usize, streaming::RawStreamingToolCall> = HashMap::new();
+        let mut tool_calls: BTreeMap<usize, streaming::RawStreamingToolCall> = BTreeMap::new();
         let mut final_usage = None;
+        let mut metadata_yielded = false;
 
         while let Some(event_result) = event_source.next().await {
             match event_result {
@@ -199,6 +214,15 @@ where
                         }
                     };
 
+                    if !metadata_yielded {
+                        metadata_yielded = true;
+                        yield Ok(streaming::RawStreamingChoice::Metadata(streaming::ResponseMetadata::new(
+                            Some(data.id.clone()),
+                            Some(data.model.clone()),
+                            None,
+                        )));
+                    }
+
                     // Expect at least one choice
                      let Some(choice) = data.choices.first() else {
                         tracing::debug!("There is no choice");
@@ -276,6 +300,13 @@ where
                         yield Ok(streaming::RawStreamingChoice::Message(content.clone()));
                     }
 
+                    // Log-probabilities of the streamed tokens
+                    if let Some(logprobs) = choice.logprobs.as_ref().and_then(token_logprobs)
+                        && !logprobs.is_empty()
+                    {
+                        yield Ok(streaming::RawStreamingChoice::Logprobs(logprobs));
+                    }
+
                     // Usage updates
                     if let Some(usage) = data.usage {
                         final_usage = Some(usage);
@@ -286,7 +317,13 @@ where
                         for (_idx, tool_call) in tool_calls.into_iter() {
                             yield Ok(streaming::RawStreamingChoice::ToolCall(tool_call));
                         }
-                        tool_calls = HashMap::new();
+                        tool_calls = BTreeMap::new();
+                    }
+
+                    if let Some(finish_reason) = &choice.finish_reason {
+                        yield Ok(streaming::RawStreamingChoice::Metadata(
+                            streaming::ResponseMetadata::finish_reason(finish_reason.into()),
+                        ));
                     }
                 }
                 Err(crate::http_client::Error::StreamEnded) => {
@@ -10,7 +10,10 @@
 //! ```
 use crate::client::BearerAuth;
 use crate::completion::CompletionRequest;
+use crate::completion::SamplingParam;
 use crate::providers::openai;
+use crate::providers::openai::completion::SamplingParams;
+use crate::providers::openai::completion::map_finish_reason;
 use crate::providers::openai::send_compatible_streaming_request;
 use crate::streaming::StreamingCompletionResponse;
 use crate::{
@@ -188,6 +191,10 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
                     output_tokens: response.usage.completion_tokens as u64,
                     total_tokens: response.usage.total_tokens as u64,
                 },
+                finish_reason: Some(map_finish_reason(&choice.finish_reason)),
+                id: Some(response.id.clone()),
+                model: Some(response.model.clone()),
+                logprobs: None,
                 raw_response: response,
             }),
             _ => Err(CompletionError::ResponseError(
@@ -197,12 +204,22 @@ impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionRe
     }
 }
 
+/// The sampling parameters supported by the Perplexity API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::TopK,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct PerplexityCompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub max_tokens: Option<u64>,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
@@ -214,6 +231,10 @@ impl TryFrom<(&str, CompletionRequest)> for PerplexityCompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        let sampling = req.sampling_unless_overridden(SamplingParam::name);
+        req.check_logprobs_unsupported("Perplexity")?;
+        req.check_hosted_tools_unsupported("Perplexity")?;
+
         let mut partial_history = vec![];
         if let Some(docs) = req.normalized_documents() {
             partial_history.push(docs);
@@ -240,6 +261,7 @@ impl TryFrom<(&str, CompletionRequest)> for PerplexityCompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new("Perplexity", sampling, SUPPORTED_SAMPLING_PARAMS)?,
             max_tokens: req.max_tokens,
             additional_params: req.additional_params,
             stream: false,
@@ -271,7 +293,7 @@ impl TryFrom<message::Message> for Message {
                 let collapsed_content = content
                     .into_iter()
                     .map(|content| match content {
-                        message::UserContent::Text(message::Text { text }) => Ok(text),
+                        message::UserContent::Text(message::Text { text, .. }) => Ok(text),
                         _ => Err(MessageError::ConversionError(
                             "Only text content is supported by Perplexity".to_owned(),
                         )),
@@ -290,7 +312,7 @@ impl TryFrom<message::Message> for Message {
                     .into_iter()
                     .map(|content| {
                         Ok(match content {
-                            message::AssistantContent::Text(message::Text { text }) => text,
+                            message::AssistantContent::Text(message::Text { text, .. }) => text,
                             _ => return Err(MessageError::ConversionError(
                                 "Only text assistant message content is supported by Perplexity"
                                     .to_owned(),
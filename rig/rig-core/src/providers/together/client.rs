@@ -1,9 +1,12 @@
 use crate::{
     client::{
-        self, BearerAuth, Capabilities, Capable, Nothing, Provider, ProviderBuilder, ProviderClient,
+        self, BearerAuth, Capabilities, Capable, Modality, ModelInfo, ModelListingClient,
+        ModelListingError, Nothing, Provider, ProviderBuilder, ProviderClient,
     },
-    http_client,
+    http_client::{self, HttpClientExt},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
 };
+use serde::Deserialize;
 
 // ================================================================
 // Together AI Client
@@ -66,6 +69,56 @@ impl ProviderClient for Client {
     }
 }
 
+// ================================================================
+// Model listing
+// ================================================================
+#[derive(Debug, Deserialize)]
+struct Model {
+    id: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    #[serde(default)]
+    organization: Option<String>,
+    #[serde(default)]
+    r#type: Option<String>,
+    #[serde(default)]
+    context_length: Option<u64>,
+}
+
+impl From<Model> for ModelInfo {
+    fn from(model: Model) -> Self {
+        let (input_modalities, output_modalities) = match model.r#type.as_deref() {
+            Some("chat" | "language" | "code") => (vec![Modality::Text], vec![Modality::Text]),
+            Some("image") => (vec![Modality::Text], vec![Modality::Image]),
+            Some("audio") => (vec![Modality::Text], vec![Modality::Audio]),
+            Some("transcribe") => (vec![Modality::Audio], vec![Modality::Text]),
+            Some("embedding" | "rerank" | "moderation") => (vec![Modality::Text], vec![]),
+            _ => (vec![], vec![]),
+        };
+
+        Self {
+            name: model.display_name,
+            owned_by: model.organization,
+            context_window: model.context_length.filter(|length| *length > 0),
+            input_modalities,
+            output_modalities,
+            is_embedding: model.r#type.map(|r#type| r#type == "embedding"),
+            ..Self::new(model.id)
+        }
+    }
+}
+
+impl<H> ModelListingClient for Client<H>
+where
+    H: HttpClientExt + WasmCompatSend + WasmCompatSync + 'static,
+{
+    async fn list_models(&self) -> Result<Vec<ModelInfo>, ModelListingError> {
+        let models: Vec<Model> = self.get_models("/v1/models").await?;
+
+        Ok(models.into_iter().map(ModelInfo::from).collect())
+    }
+}
+
 pub mod together_ai_api_types {
     use serde::Deserialize;
 
@@ -88,3 +141,49 @@ pub mod together_ai_api_types {
         Error(ApiErrorResponse),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_list() {
+        let models: Vec<Model> = serde_json::from_value(serde_json::json!([
+            {
+                "id": "meta-llama/Llama-3.3-70B-Instruct-Turbo",
+                "object": "model",
+                "type": "chat",
+                "display_name": "Meta Llama 3.3 70B Instruct Turbo",
+                "organization": "Meta",
+                "context_length": 131072
+            },
+            {
+                "id": "BAAI/bge-large-en-v1.5",
+                "object": "model",
+                "type": "embedding",
+                "context_length": 0
+            }
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            models.into_iter().map(ModelInfo::from).collect::<Vec<_>>(),
+            vec![
+                ModelInfo {
+                    name: Some("Meta Llama 3.3 70B Instruct Turbo".to_string()),
+                    owned_by: Some("Meta".to_string()),
+                    context_window: Some(131_072),
+                    input_modalities: vec![Modality::Text],
+                    output_modalities: vec![Modality::Text],
+                    is_embedding: Some(false),
+                    ..ModelInfo::new("meta-llama/Llama-3.3-70B-Instruct-Turbo")
+                },
+                ModelInfo {
+                    input_modalities: vec![Modality::Text],
+                    is_embedding: Some(true),
+                    ..ModelInfo::new("BAAI/bge-large-en-v1.5")
+                },
+            ]
+        );
+    }
+}
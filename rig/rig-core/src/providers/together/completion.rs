@@ -3,6 +3,8 @@
 //! From [Together AI Reference](https://docs.together.ai/docs/chat-overview)
 // ================================================================
 
+use crate::completion::SamplingParam;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use crate::{
     completion::{self, CompletionError},
     http_client::HttpClientExt,
@@ -129,12 +131,27 @@ pub const WIZARDLM_13B_V1_2: &str = "WizardLM/WizardLM-13B-V1.2";
 // Rig Implementation Types
 // =================================================================
 
+/// The sampling parameters supported by the Together AI API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::TopK,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct TogetherAICompletionRequest {
     model: String,
     pub messages: Vec<openai::Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<crate::providers::openai::completion::ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -147,6 +164,9 @@ impl TryFrom<(&str, CompletionRequest)> for TogetherAICompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("Together AI")?;
+        let sampling = req.sampling_unless_overridden(SamplingParam::name);
+
         let mut full_history: Vec<openai::Message> = match &req.preamble {
             Some(preamble) => vec![openai::Message::system(preamble)],
             None => vec![],
@@ -177,6 +197,8 @@ impl TryFrom<(&str, CompletionRequest)> for TogetherAICompletionRequest {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new("Together AI", sampling, SUPPORTED_SAMPLING_PARAMS)?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
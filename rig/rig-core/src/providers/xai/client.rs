@@ -45,6 +45,8 @@ impl<H> Capabilities<H> for XAiExt {
     type AudioGeneration = Nothing;
 }
 
+crate::client::model_listing::impl_openai_compatible_model_listing!(XAiExt, "/v1/models");
+
 impl DebugExt for XAiExt {}
 
 impl ProviderBuilder for XAiExtBuilder {
@@ -3,6 +3,8 @@
 //! From [xAI Reference](https://docs.x.ai/docs/api-reference#chat-completions)
 // ================================================================
 
+use crate::completion::SamplingParam;
+use crate::providers::openai::completion::{LogprobsParams, SamplingParams};
 use crate::{
     completion::{self, CompletionError},
     http_client::HttpClientExt,
@@ -28,16 +30,56 @@ pub const GROK_3_MINI_FAST: &str = "grok-3-mini-fast";
 pub const GROK_2_IMAGE_1212: &str = "grok-2-image-1212";
 pub const GROK_4: &str = "grok-4-0709";
 
+/// The sampling parameters supported by the xAI API.
+const SUPPORTED_SAMPLING_PARAMS: &[SamplingParam] = &[
+    SamplingParam::TopP,
+    SamplingParam::Stop,
+    SamplingParam::Seed,
+    SamplingParam::PresencePenalty,
+    SamplingParam::FrequencyPenalty,
+    SamplingParam::LogitBias,
+];
+
+/// The reasoning effort of the xAI models supporting it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    High,
+}
+
+impl ReasoningEffort {
+    /// Maps a provider-neutral effort to the closest effort supported by xAI.
+    /// `None` if reasoning is disabled, which xAI does not support.
+    fn from_effort(effort: completion::ReasoningEffort) -> Option<Self> {
+        match effort {
+            completion::ReasoningEffort::None => None,
+            completion::ReasoningEffort::Minimal | completion::ReasoningEffort::Low => {
+                Some(Self::Low)
+            }
+            completion::ReasoningEffort::Medium | completion::ReasoningEffort::High => {
+                Some(Self::High)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub(super) struct XAICompletionRequest {
     model: String,
     pub messages: Vec<Message>,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f64>,
+    #[serde(flatten)]
+    sampling: SamplingParams,
+    #[serde(flatten)]
+    logprobs: LogprobsParams,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     tools: Vec<ToolDefinition>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<crate::providers::openrouter::ToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<ReasoningEffort>,
     #[serde(flatten, skip_serializing_if = "Option::is_none")]
     pub additional_params: Option<serde_json::Value>,
 }
@@ -46,6 +88,7 @@ impl TryFrom<(&str, CompletionRequest)> for XAICompletionRequest {
     type Error = CompletionError;
 
     fn try_from((model, req): (&str, CompletionRequest)) -> Result<Self, Self::Error> {
+        req.check_hosted_tools_unsupported("xAI")?;
         let mut full_history: Vec<Message> = match &req.preamble {
             Some(preamble) => vec![Message::system(preamble)],
             None => vec![],
@@ -73,10 +116,21 @@ impl TryFrom<(&str, CompletionRequest)> for XAICompletionRequest {
             .map(crate::providers::openrouter::ToolChoice::try_from)
             .transpose()?;
 
+        let reasoning_effort = req
+            .reasoning_unless_overridden("reasoning_effort")
+            .and_then(|reasoning| reasoning.resolved_effort())
+            .and_then(ReasoningEffort::from_effort);
+
         Ok(Self {
             model: model.to_string(),
             messages: full_history,
             temperature: req.temperature,
+            sampling: SamplingParams::new(
+                "xAI",
+                req.sampling_unless_overridden(SamplingParam::name),
+                SUPPORTED_SAMPLING_PARAMS,
+            )?,
+            logprobs: req.logprobs.into(),
             tools: req
                 .tools
                 .clone()
@@ -84,6 +138,7 @@ impl TryFrom<(&str, CompletionRequest)> for XAICompletionRequest {
                 .map(ToolDefinition::from)
                 .collect::<Vec<_>>(),
             tool_choice,
+            reasoning_effort,
             additional_params: req.additional_params,
         })
     }
@@ -262,6 +317,12 @@ pub mod xai_api_types {
             Ok(completion::CompletionResponse {
                 choice,
                 usage,
+                finish_reason: response.choices.first().map(|choice| {
+                    crate::providers::openai::completion::map_finish_reason(&choice.finish_reason)
+                }),
+                id: Some(response.id.clone()),
+                model: Some(response.model.clone()),
+                logprobs: None,
                 raw_response: response,
             })
         }
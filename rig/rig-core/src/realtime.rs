@@ -0,0 +1,488 @@
+//! This module provides functionality for working with provider realtime APIs.
+//!
+//! Realtime APIs keep a bidirectional WebSocket connection open (see
+//! [crate::http_client::websocket]) over which audio and text are streamed in both directions,
+//! which makes them a good fit for low-latency voice agents. A [RealtimeSession] sends
+//! provider-neutral [ClientEvent]s and receives provider-neutral [ServerEvent]s, each provider
+//! translating them to and from its own protocol with a [RealtimeProtocol].
+//!
+//! With server VAD (voice activity detection, see [TurnDetection::ServerVad]), the provider
+//! detects the end of each user turn in the input audio and answers on its own. The function calls
+//! of the model can be dispatched to a [ToolServerHandle] (e.g. the tools of an agent, see
+//! [RealtimeModel::connect_agent]), in which case the results are sent back to the model and a new
+//! response is requested once the current one is done.
+//!
+//! # Example
+//! ```no_run
+//! use rig::prelude::*;
+//! use rig::providers::openai;
+//! use rig::realtime::{RealtimeModel, ServerEvent, SessionConfig, TurnDetection};
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = openai::Client::from_env();
+//! let model = client.realtime_model(openai::realtime::GPT_REALTIME);
+//!
+//! let config = SessionConfig::new()
+//!     .instructions("You are a helpful voice assistant")
+//!     .voice("alloy")
+//!     .turn_detection(TurnDetection::server_vad());
+//! let mut session = model.connect(config).await?;
+//!
+//! // Audio can be sent from another task while the events are received
+//! let sender = session.sender();
+//! tokio::spawn(async move {
+//!     let chunk = vec![0u8; 4800]; // 100ms of 24kHz PCM16 audio from the microphone
+//!     sender.append_audio(chunk).await
+//! });
+//!
+//! while let Some(event) = session.next_event().await {
+//!     match event? {
+//!         ServerEvent::AudioDelta { data, .. } => { /* play `data` */ }
+//!         ServerEvent::AudioTranscriptDelta { delta, .. } => print!("{delta}"),
+//!         _ => {}
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::{collections::VecDeque, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    agent::Agent,
+    client::Modality,
+    completion::{CompletionModel, ToolDefinition, Usage},
+    http_client::{
+        self,
+        websocket::{WebSocket, WebSocketMessage, WebSocketReceiver, WebSocketSender},
+    },
+    message::ToolCall,
+    tool::server::{ToolServerError, ToolServerHandle},
+    wasm_compat::{WasmCompatSend, WasmCompatSync},
+};
+
+// Errors
+#[derive(Debug, Error)]
+pub enum RealtimeError {
+    /// Http error (e.g.: connection error, WebSocket handshake error, etc.)
+    #[error("HttpError: {0}")]
+    HttpError(#[from] http_client::Error),
+
+    /// Json error (e.g.: serialization, deserialization)
+    #[error("JsonError: {0}")]
+    JsonError(#[from] serde_json::Error),
+
+    /// Error retrieving the tool definitions of the session
+    #[error("ToolServerError: {0}")]
+    ToolServerError(#[from] ToolServerError),
+
+    /// Error converting an event to or from the provider's protocol
+    #[error("ProtocolError: {0}")]
+    ProtocolError(String),
+}
+
+/// The configuration of a realtime session, sent when connecting and updated with
+/// [ClientEvent::UpdateSession]. Unset fields keep the provider (or current) values.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct SessionConfig {
+    /// System instructions of the session
+    pub instructions: Option<String>,
+    /// The output modalities of the responses, e.g. text and audio
+    pub modalities: Option<Vec<Modality>>,
+    /// The voice of the audio output, see the voices of [crate::audio_generation]
+    pub voice: Option<String>,
+    pub input_audio_format: Option<AudioFormat>,
+    pub output_audio_format: Option<AudioFormat>,
+    /// The transcription model used to transcribe the input audio (see [crate::transcription]),
+    /// the transcripts being received as [ServerEvent::InputTranscription]
+    pub input_audio_transcription: Option<String>,
+    pub turn_detection: Option<TurnDetection>,
+    pub tools: Vec<ToolDefinition>,
+    pub temperature: Option<f64>,
+    pub max_output_tokens: Option<u64>,
+    /// Additional provider-specific parameters, merged into the session configuration
+    pub additional_params: Option<serde_json::Value>,
+}
+
+impl SessionConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn instructions(mut self, instructions: impl Into<String>) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    pub fn modalities(mut self, modalities: impl IntoIterator<Item = Modality>) -> Self {
+        self.modalities = Some(modalities.into_iter().collect());
+        self
+    }
+
+    pub fn voice(mut self, voice: impl Into<String>) -> Self {
+        self.voice = Some(voice.into());
+        self
+    }
+
+    pub fn input_audio_format(mut self, format: AudioFormat) -> Self {
+        self.input_audio_format = Some(format);
+        self
+    }
+
+    pub fn output_audio_format(mut self, format: AudioFormat) -> Self {
+        self.output_audio_format = Some(format);
+        self
+    }
+
+    pub fn input_audio_transcription(mut self, model: impl Into<String>) -> Self {
+        self.input_audio_transcription = Some(model.into());
+        self
+    }
+
+    pub fn turn_detection(mut self, turn_detection: TurnDetection) -> Self {
+        self.turn_detection = Some(turn_detection);
+        self
+    }
+
+    pub fn tool(mut self, tool: ToolDefinition) -> Self {
+        self.tools.push(tool);
+        self
+    }
+
+    pub fn tools(mut self, tools: impl IntoIterator<Item = ToolDefinition>) -> Self {
+        self.tools.extend(tools);
+        self
+    }
+
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.temperature = Some(temperature);
+        self
+    }
+
+    pub fn max_output_tokens(mut self, max_output_tokens: u64) -> Self {
+        self.max_output_tokens = Some(max_output_tokens);
+        self
+    }
+
+    pub fn additional_params(mut self, params: serde_json::Value) -> Self {
+        self.additional_params = Some(params);
+        self
+    }
+}
+
+/// The format of the audio sent and received in a realtime session.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AudioFormat {
+    /// 16-bit PCM, 24kHz, mono, little-endian
+    Pcm16,
+    /// G.711 μ-law, 8kHz
+    G711Ulaw,
+    /// G.711 A-law, 8kHz
+    G711Alaw,
+}
+
+/// How the end of the user turns is detected in the input audio.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TurnDetection {
+    /// The provider detects speech in the input audio, commits the input audio buffer at the end of
+    /// each turn and (by default) creates a response
+    ServerVad {
+        /// Activation threshold of the detection, between 0 and 1
+        threshold: Option<f64>,
+        /// Audio included before the detected speech, in milliseconds
+        prefix_padding_ms: Option<u64>,
+        /// Silence duration ending a turn, in milliseconds
+        silence_duration_ms: Option<u64>,
+        /// Whether a response is created at the end of each turn
+        create_response: Option<bool>,
+    },
+    /// Turns are ended manually, with [ClientEvent::CommitAudio] and [ClientEvent::CreateResponse]
+    Disabled,
+}
+
+impl TurnDetection {
+    /// Server VAD with the default settings of the provider.
+    pub fn server_vad() -> Self {
+        Self::ServerVad {
+            threshold: None,
+            prefix_padding_ms: None,
+            silence_duration_ms: None,
+            create_response: None,
+        }
+    }
+}
+
+/// An event sent by the client of a realtime session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClientEvent {
+    /// Update the configuration of the session
+    UpdateSession(SessionConfig),
+    /// Append audio, in the input audio format of the session, to the input audio buffer
+    AppendAudio(Vec<u8>),
+    /// Commit the input audio buffer as a user message (not needed with server VAD)
+    CommitAudio,
+    /// Clear the input audio buffer
+    ClearAudio,
+    /// Add a user text message to the conversation
+    UserMessage(String),
+    /// Request a response of the model
+    CreateResponse,
+    /// Cancel the response in progress
+    CancelResponse,
+    /// Send the result of a function call of the model
+    ToolResult { call_id: String, output: String },
+}
+
+/// An event received from the provider in a realtime session.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ServerEvent {
+    /// The configuration of the session was created or updated
+    SessionUpdated(serde_json::Value),
+    /// Speech was detected in the input audio (server VAD)
+    SpeechStarted { audio_start_ms: Option<u64> },
+    /// The end of speech was detected in the input audio (server VAD)
+    SpeechStopped { audio_end_ms: Option<u64> },
+    /// The transcript of a user audio message
+    InputTranscription { item_id: String, transcript: String },
+    /// A chunk of audio of a response, in the output audio format of the session
+    AudioDelta { response_id: String, data: Vec<u8> },
+    /// A chunk of the transcript of the audio of a response
+    AudioTranscriptDelta { response_id: String, delta: String },
+    /// A chunk of text of a response
+    TextDelta { response_id: String, delta: String },
+    /// A function call of the model, complete with its arguments
+    ToolCall(ToolCall),
+    /// The result of a function call dispatched to the tool server of the session
+    ToolResult { call_id: String, output: String },
+    /// A response is done, either completed, cancelled or failed
+    ResponseDone {
+        response_id: String,
+        usage: Option<Usage>,
+    },
+    /// An error reported by the provider, the session stays open
+    Error {
+        message: String,
+        code: Option<String>,
+    },
+    /// An event not mapped to the events above, as sent by the provider
+    Other(serde_json::Value),
+}
+
+/// Translates [ClientEvent]s and [ServerEvent]s to and from a provider's realtime protocol.
+pub trait RealtimeProtocol: WasmCompatSend + WasmCompatSync {
+    fn encode(&self, event: ClientEvent) -> Result<Vec<WebSocketMessage>, RealtimeError>;
+
+    fn decode(&self, message: WebSocketMessage) -> Result<Vec<ServerEvent>, RealtimeError>;
+}
+
+/// A model of a provider realtime API.
+pub trait RealtimeModel: Clone + WasmCompatSend + WasmCompatSync {
+    /// Open a realtime session with the given configuration.
+    fn connect(
+        &self,
+        config: SessionConfig,
+    ) -> impl std::future::Future<Output = Result<RealtimeSession, RealtimeError>> + WasmCompatSend;
+
+    /// Open a realtime session configured from an agent: its preamble as instructions, its
+    /// temperature and its tools, the function calls of the model being dispatched to the tool
+    /// server of the agent.
+    fn connect_agent<M>(
+        &self,
+        agent: &Agent<M>,
+        config: SessionConfig,
+    ) -> impl std::future::Future<Output = Result<RealtimeSession, RealtimeError>> + WasmCompatSend
+    where
+        M: CompletionModel,
+    {
+        let handle = agent.tool_server_handle.clone();
+        let preamble = agent.preamble.clone();
+        let temperature = agent.temperature;
+
+        async move {
+            let tools = handle.get_tool_defs(None).await?;
+            let config = SessionConfig {
+                instructions: config.instructions.or(preamble),
+                temperature: config.temperature.or(temperature),
+                ..config
+            }
+            .tools(tools);
+
+            Ok(self.connect(config).await?.with_tool_server(handle))
+        }
+    }
+}
+
+/// A handle sending [ClientEvent]s to a [RealtimeSession], which can be cloned and used from other
+/// tasks (e.g. the task capturing the microphone).
+#[derive(Clone)]
+pub struct RealtimeSender {
+    sender: Arc<tokio::sync::Mutex<WebSocketSender>>,
+    protocol: Arc<dyn RealtimeProtocol>,
+}
+
+impl RealtimeSender {
+    pub async fn send(&self, event: ClientEvent) -> Result<(), RealtimeError> {
+        let messages = self.protocol.encode(event)?;
+
+        let mut sender = self.sender.lock().await;
+        for message in messages {
+            sender.send(message).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn update_session(&self, config: SessionConfig) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::UpdateSession(config)).await
+    }
+
+    pub async fn append_audio(&self, audio: impl Into<Vec<u8>>) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::AppendAudio(audio.into())).await
+    }
+
+    pub async fn commit_audio(&self) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::CommitAudio).await
+    }
+
+    /// Add a user text message to the conversation and request a response.
+    pub async fn send_text(&self, text: impl Into<String>) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::UserMessage(text.into())).await?;
+        self.send(ClientEvent::CreateResponse).await
+    }
+
+    pub async fn create_response(&self) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::CreateResponse).await
+    }
+
+    pub async fn cancel_response(&self) -> Result<(), RealtimeError> {
+        self.send(ClientEvent::CancelResponse).await
+    }
+
+    /// Close the connection.
+    pub async fn close(&self) -> Result<(), RealtimeError> {
+        Ok(self.sender.lock().await.close().await?)
+    }
+}
+
+/// An open realtime session, see the [module documentation](self).
+pub struct RealtimeSession {
+    sender: RealtimeSender,
+    receiver: WebSocketReceiver,
+    tool_server: Option<ToolServerHandle>,
+    /// The events decoded but not returned yet
+    pending: VecDeque<ServerEvent>,
+    /// Whether tool results were sent during the current response, in which case a new response is
+    /// requested once it is done
+    tool_results_sent: bool,
+}
+
+impl RealtimeSession {
+    pub fn new(socket: WebSocket, protocol: impl RealtimeProtocol + 'static) -> Self {
+        let (sender, receiver) = socket.split();
+
+        Self {
+            sender: RealtimeSender {
+                sender: Arc::new(tokio::sync::Mutex::new(sender)),
+                protocol: Arc::new(protocol),
+            },
+            receiver,
+            tool_server: None,
+            pending: VecDeque::new(),
+            tool_results_sent: false,
+        }
+    }
+
+    /// Dispatch the function calls of the model to a tool server. The tools are not added to the
+    /// configuration of the session.
+    pub fn with_tool_server(mut self, handle: ToolServerHandle) -> Self {
+        self.tool_server = Some(handle);
+        self
+    }
+
+    /// A handle to send events to the session, see [RealtimeSender].
+    pub fn sender(&self) -> RealtimeSender {
+        self.sender.clone()
+    }
+
+    pub async fn send(&self, event: ClientEvent) -> Result<(), RealtimeError> {
+        self.sender.send(event).await
+    }
+
+    /// Receive the next event of the session, `None` once the connection is closed.
+    ///
+    /// When the session has a tool server, [ServerEvent::ToolCall]s are dispatched to it before
+    /// being returned, and followed by their [ServerEvent::ToolResult].
+    pub async fn next_event(&mut self) -> Option<Result<ServerEvent, RealtimeError>> {
+        while self.pending.is_empty() {
+            let message = match self.receiver.next().await? {
+                Ok(WebSocketMessage::Close) => return None,
+                Ok(message) => message,
+                Err(error) => return Some(Err(error.into())),
+            };
+
+            match self.sender.protocol.decode(message) {
+                Ok(events) => self.pending.extend(events),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+
+        let event = self.pending.pop_front()?;
+
+        if let Err(error) = self.handle_event(&event).await {
+            return Some(Err(error));
+        }
+
+        Some(Ok(event))
+    }
+
+    async fn handle_event(&mut self, event: &ServerEvent) -> Result<(), RealtimeError> {
+        let Some(tool_server) = &self.tool_server else {
+            return Ok(());
+        };
+
+        match event {
+            ServerEvent::ToolCall(tool_call) => {
+                let call_id = tool_call
+                    .call_id
+                    .clone()
+                    .unwrap_or_else(|| tool_call.id.clone());
+                let output = match tool_server
+                    .call_tool(
+                        &tool_call.function.name,
+                        &tool_call.function.arguments.to_string(),
+                    )
+                    .await
+                {
+                    Ok(output) => output,
+                    Err(error) => error.to_string(),
+                };
+
+                self.sender
+                    .send(ClientEvent::ToolResult {
+                        call_id: call_id.clone(),
+                        output: output.clone(),
+                    })
+                    .await?;
+                self.tool_results_sent = true;
+                self.pending
+                    .push_front(ServerEvent::ToolResult { call_id, output });
+            }
+            ServerEvent::ResponseDone { .. } if self.tool_results_sent => {
+                self.tool_results_sent = false;
+                self.sender.send(ClientEvent::CreateResponse).await?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Close the connection.
+    pub async fn close(self) -> Result<(), RealtimeError> {
+        self.sender.close().await
+    }
+}
@@ -13,10 +13,12 @@ use crate::agent::Agent;
 use crate::agent::prompt_request::streaming::StreamingPromptRequest;
 use crate::client::FinalCompletionResponse;
 use crate::completion::{
-    CompletionError, CompletionModel, CompletionRequestBuilder, CompletionResponse, GetTokenUsage,
-    Message, Usage,
+    CompletionError, CompletionModel, CompletionRequestBuilder, CompletionResponse, FinishReason,
+    GetTokenUsage, Message, TokenLogprob, Usage,
+};
+use crate::message::{
+    AssistantContent, Citation, HostedToolCall, Reasoning, Text, ToolCall, ToolFunction, ToolResult,
 };
-use crate::message::{AssistantContent, Reasoning, Text, ToolCall, ToolFunction, ToolResult};
 use crate::wasm_compat::{WasmCompatSend, WasmCompatSync};
 use futures::stream::{AbortHandle, Abortable};
 use futures::{Stream, StreamExt};
@@ -89,6 +91,50 @@ where
     /// The final response object, must be yielded if you want the
     /// `response` field to be populated on the `StreamingCompletionResponse`
     FinalResponse(R),
+
+    /// Metadata of the response (id, served model, finish reason).
+    /// Can be yielded several times, the fields that are set override the previous values.
+    Metadata(ResponseMetadata),
+
+    /// Log-probabilities of the tokens generated since the previous chunk
+    Logprobs(Vec<TokenLogprob>),
+
+    /// A call of a tool hosted by the provider, along with its result
+    HostedToolCall(HostedToolCall),
+
+    /// A citation of the text streamed so far. Its span is a byte range of the whole text of
+    /// the response, i.e. all the [RawStreamingChoice::Message] chunks
+    Citation(Citation),
+}
+
+/// Provider-neutral metadata of a streamed response
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResponseMetadata {
+    pub id: Option<String>,
+    pub model: Option<String>,
+    pub finish_reason: Option<FinishReason>,
+}
+
+impl ResponseMetadata {
+    pub fn new(
+        id: Option<String>,
+        model: Option<String>,
+        finish_reason: Option<FinishReason>,
+    ) -> Self {
+        Self {
+            id,
+            model,
+            finish_reason,
+        }
+    }
+
+    /// Metadata only containing a finish reason
+    pub fn finish_reason(finish_reason: FinishReason) -> Self {
+        Self {
+            finish_reason: Some(finish_reason),
+            ..Default::default()
+        }
+    }
 }
 
 /// Describes a streaming tool call response (in its entirety)
@@ -176,13 +222,25 @@ where
     pub(crate) pause_control: PauseControl,
     text: String,
     reasoning: String,
+    reasoning_blocks: Vec<Reasoning>,
+    hosted_tool_calls: Vec<HostedToolCall>,
+    citations: Vec<Citation>,
     tool_calls: Vec<ToolCall>,
     /// The final aggregated message from the stream
-    /// contains all text and tool calls generated
+    /// contains all reasoning, hosted tool calls, text and tool calls generated
     pub choice: OneOrMany<AssistantContent>,
     /// The final response from the stream, may be `None`
     /// if the provider didn't yield it during the stream
     pub response: Option<R>,
+    /// The reason why the model stopped generating, if reported by the provider
+    pub finish_reason: Option<FinishReason>,
+    /// The id of the response, if returned by the provider
+    pub id: Option<String>,
+    /// The name of the model that served the request, if returned by the provider
+    pub model: Option<String>,
+    /// The log-probabilities of the generated tokens, empty if they were not requested
+    /// or not returned by the provider
+    pub logprobs: Vec<TokenLogprob>,
     pub final_response_yielded: AtomicBool,
 }
 
@@ -199,10 +257,17 @@ where
             abort_handle,
             pause_control,
             reasoning: String::new(),
+            reasoning_blocks: vec![],
+            hosted_tool_calls: vec![],
+            citations: vec![],
             text: "".to_string(),
             tool_calls: vec![],
             choice: OneOrMany::one(AssistantContent::text("")),
             response: None,
+            finish_reason: None,
+            id: None,
+            model: None,
+            logprobs: Vec::new(),
             final_response_yielded: AtomicBool::new(false),
         }
     }
@@ -232,6 +297,10 @@ where
         CompletionResponse {
             choice: value.choice,
             usage: Usage::new(), // Usage is not tracked in streaming responses
+            finish_reason: value.finish_reason,
+            id: value.id,
+            model: value.model,
+            logprobs: (!value.logprobs.is_empty()).then_some(value.logprobs),
             raw_response: value.response,
         }
     }
@@ -258,15 +327,41 @@ where
                 // a single unified `Message`.
                 let mut choice = vec![];
 
+                // Complete reasoning blocks carry the signatures some providers need back,
+                // the deltas are only used if the provider streamed nothing else
+                if !stream.reasoning_blocks.is_empty() {
+                    choice.extend(
+                        stream
+                            .reasoning_blocks
+                            .iter()
+                            .cloned()
+                            .map(AssistantContent::Reasoning),
+                    );
+                } else if !stream.reasoning.is_empty() {
+                    choice.push(AssistantContent::Reasoning(Reasoning::new(
+                        &stream.reasoning,
+                    )));
+                }
+
+                choice.extend(
+                    stream
+                        .hosted_tool_calls
+                        .iter()
+                        .cloned()
+                        .map(AssistantContent::HostedToolCall),
+                );
+
+                // This is required to ensure there's always at least one text or tool call
+                if stream.tool_calls.is_empty() || !stream.text.is_empty() {
+                    choice.push(AssistantContent::Text(
+                        Text::from(stream.text.clone()).with_citations(stream.citations.clone()),
+                    ));
+                }
+
                 stream.tool_calls.iter().for_each(|tc| {
                     choice.push(AssistantContent::ToolCall(tc.clone()));
                 });
 
-                // This is required to ensure there's always at least one item in the content
-                if choice.is_empty() || !stream.text.is_empty() {
-                    choice.insert(0, AssistantContent::text(stream.text.clone()));
-                }
-
                 stream.choice = OneOrMany::many(choice)
                     .expect("There should be at least one assistant message");
 
@@ -296,11 +391,16 @@ where
                     id,
                     reasoning,
                     signature,
-                } => Poll::Ready(Some(Ok(StreamedAssistantContent::Reasoning(Reasoning {
-                    id,
-                    reasoning: vec![reasoning],
-                    signature,
-                })))),
+                } => {
+                    // Keep track of each reasoning block to aggregate the final message later
+                    let reasoning = Reasoning {
+                        id,
+                        reasoning: vec![reasoning],
+                        signature,
+                    };
+                    stream.reasoning_blocks.push(reasoning.clone());
+                    Poll::Ready(Some(Ok(StreamedAssistantContent::Reasoning(reasoning))))
+                }
                 RawStreamingChoice::ReasoningDelta { id, reasoning } => {
                     // Forward the streaming tokens to the outer stream
                     // and concat the text together
@@ -317,6 +417,38 @@ where
                     stream.tool_calls.push(tool_call.clone());
                     Poll::Ready(Some(Ok(StreamedAssistantContent::ToolCall(tool_call))))
                 }
+                RawStreamingChoice::Metadata(ResponseMetadata {
+                    id,
+                    model,
+                    finish_reason,
+                }) => {
+                    // Keep track of the metadata and return the next item in the stream
+                    if id.is_some() {
+                        stream.id = id;
+                    }
+                    if model.is_some() {
+                        stream.model = model;
+                    }
+                    if finish_reason.is_some() {
+                        stream.finish_reason = finish_reason;
+                    }
+                    stream.poll_next_unpin(cx)
+                }
+                RawStreamingChoice::Logprobs(logprobs) => {
+                    // Keep track of the logprobs to aggregate them later and pass them to the outer stream
+                    stream.logprobs.extend(logprobs.iter().cloned());
+                    Poll::Ready(Some(Ok(StreamedAssistantContent::Logprobs(logprobs))))
+                }
+                RawStreamingChoice::HostedToolCall(call) => {
+                    // Keep track of each hosted tool call to aggregate the final message later
+                    stream.hosted_tool_calls.push(call.clone());
+                    Poll::Ready(Some(Ok(StreamedAssistantContent::HostedToolCall(call))))
+                }
+                RawStreamingChoice::Citation(citation) => {
+                    // Keep track of the citations to attach them to the final text
+                    stream.citations.push(citation.clone());
+                    Poll::Ready(Some(Ok(StreamedAssistantContent::Citation(citation))))
+                }
                 RawStreamingChoice::FinalResponse(response) => {
                     if stream
                         .final_response_yielded
@@ -421,6 +553,18 @@ impl<R: Clone + Unpin + GetTokenUsage> Stream for StreamingResultDyn<R> {
                 RawStreamingChoice::ToolCall(tool_call) => {
                     Poll::Ready(Some(Ok(RawStreamingChoice::ToolCall(tool_call))))
                 }
+                RawStreamingChoice::Metadata(metadata) => {
+                    Poll::Ready(Some(Ok(RawStreamingChoice::Metadata(metadata))))
+                }
+                RawStreamingChoice::Logprobs(logprobs) => {
+                    Poll::Ready(Some(Ok(RawStreamingChoice::Logprobs(logprobs))))
+                }
+                RawStreamingChoice::HostedToolCall(call) => {
+                    Poll::Ready(Some(Ok(RawStreamingChoice::HostedToolCall(call))))
+                }
+                RawStreamingChoice::Citation(citation) => {
+                    Poll::Ready(Some(Ok(RawStreamingChoice::Citation(citation))))
+                }
             },
         }
     }
@@ -497,6 +641,7 @@ mod tests {
     use std::time::Duration;
 
     use super::*;
+    use crate::message::CitationSource;
     use async_stream::stream;
     use tokio::time::sleep;
 
@@ -566,6 +711,15 @@ mod tests {
                     println!("Reasoning delta: {reasoning}");
                     chunk_count += 1;
                 }
+                Ok(StreamedAssistantContent::Logprobs(logprobs)) => {
+                    println!("\nLogprobs: {logprobs:?}");
+                }
+                Ok(StreamedAssistantContent::HostedToolCall(call)) => {
+                    println!("\nHosted tool call: {call:?}");
+                }
+                Ok(StreamedAssistantContent::Citation(citation)) => {
+                    println!("\nCitation: {citation:?}");
+                }
                 Err(e) => {
                     eprintln!("Error: {e:?}");
                     break;
@@ -599,6 +753,156 @@ mod tests {
         stream.resume();
         assert!(!stream.is_paused());
     }
+
+    #[tokio::test]
+    async fn test_stream_metadata_is_merged() {
+        let stream = stream! {
+            yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::new(
+                Some("resp_123".to_string()),
+                Some("mock-model".to_string()),
+                None,
+            )));
+            yield Ok(RawStreamingChoice::Message("hello".to_string()));
+            yield Ok(RawStreamingChoice::Metadata(ResponseMetadata::finish_reason(
+                FinishReason::Length,
+            )));
+            yield Ok(RawStreamingChoice::FinalResponse(MockResponse { token_count: 15 }));
+        };
+
+        let pinned_stream: StreamingResult<MockResponse> = Box::pin(stream);
+        let mut stream = StreamingCompletionResponse::stream(pinned_stream);
+
+        while let Some(chunk) = stream.next().await {
+            chunk.unwrap();
+        }
+
+        assert_eq!(stream.id.as_deref(), Some("resp_123"));
+        assert_eq!(stream.model.as_deref(), Some("mock-model"));
+        assert_eq!(stream.finish_reason, Some(FinishReason::Length));
+
+        let response: CompletionResponse<Option<MockResponse>> = stream.into();
+        assert_eq!(response.finish_reason, Some(FinishReason::Length));
+        assert_eq!(response.id.as_deref(), Some("resp_123"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_logprobs_are_forwarded_and_aggregated() {
+        let logprob = |token: &str, logprob: f64| TokenLogprob {
+            token: token.to_string(),
+            logprob,
+            bytes: None,
+            top_logprobs: vec![],
+        };
+
+        let chunks = vec![vec![logprob("Hello", -0.1)], vec![logprob(" world", -0.5)]];
+        let raw_chunks = chunks.clone();
+        let stream = stream! {
+            for chunk in raw_chunks {
+                yield Ok(RawStreamingChoice::Message(chunk[0].token.clone()));
+                yield Ok(RawStreamingChoice::Logprobs(chunk));
+            }
+            yield Ok(RawStreamingChoice::FinalResponse(MockResponse { token_count: 2 }));
+        };
+
+        let pinned_stream: StreamingResult<MockResponse> = Box::pin(stream);
+        let mut stream = StreamingCompletionResponse::stream(pinned_stream);
+
+        let mut streamed = vec![];
+        while let Some(chunk) = stream.next().await {
+            if let StreamedAssistantContent::Logprobs(logprobs) = chunk.unwrap() {
+                streamed.push(logprobs);
+            }
+        }
+
+        assert_eq!(streamed, chunks);
+        assert_eq!(stream.logprobs, chunks.concat());
+
+        let response: CompletionResponse<Option<MockResponse>> = stream.into();
+        assert_eq!(response.logprobs, Some(chunks.concat()));
+    }
+
+    #[tokio::test]
+    async fn test_stream_citations_are_attached_to_text() {
+        let citation = Citation::new(Some(6..11), vec![CitationSource::document("doc_0")]);
+        let raw_citation = citation.clone();
+        let stream = stream! {
+            yield Ok(RawStreamingChoice::Message("Hello ".to_string()));
+            yield Ok(RawStreamingChoice::Message("world".to_string()));
+            yield Ok(RawStreamingChoice::Citation(raw_citation));
+            yield Ok(RawStreamingChoice::FinalResponse(MockResponse { token_count: 2 }));
+        };
+
+        let pinned_stream: StreamingResult<MockResponse> = Box::pin(stream);
+        let mut stream = StreamingCompletionResponse::stream(pinned_stream);
+
+        let mut streamed = vec![];
+        while let Some(chunk) = stream.next().await {
+            if let StreamedAssistantContent::Citation(citation) = chunk.unwrap() {
+                streamed.push(citation);
+            }
+        }
+
+        assert_eq!(streamed, vec![citation.clone()]);
+
+        let AssistantContent::Text(text) = stream.choice.first() else {
+            panic!("Expected text content");
+        };
+        assert_eq!(text.citations, vec![citation]);
+        assert_eq!(text.cited_span(&text.citations[0]), Some("world"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_choice_aggregates_reasoning() {
+        let stream = stream! {
+            yield Ok(RawStreamingChoice::ReasoningDelta { id: None, reasoning: "Let me ".to_string() });
+            yield Ok(RawStreamingChoice::ReasoningDelta { id: None, reasoning: "think".to_string() });
+            yield Ok(RawStreamingChoice::ToolCall(RawStreamingToolCall::new(
+                "call_1".to_string(),
+                "add".to_string(),
+                serde_json::json!({"x": 1, "y": 2}),
+            )));
+            yield Ok(RawStreamingChoice::FinalResponse(MockResponse { token_count: 2 }));
+        };
+
+        let pinned_stream: StreamingResult<MockResponse> = Box::pin(stream);
+        let mut stream = StreamingCompletionResponse::stream(pinned_stream);
+        while stream.next().await.is_some() {}
+
+        let choice = stream.choice.into_iter().collect::<Vec<_>>();
+        assert_eq!(choice.len(), 2);
+        assert_eq!(
+            choice[0],
+            AssistantContent::Reasoning(Reasoning::new("Let me think"))
+        );
+        assert!(matches!(choice[1], AssistantContent::ToolCall(_)));
+
+        // Complete reasoning blocks keep their signature and take precedence over the deltas
+        let stream = stream! {
+            yield Ok(RawStreamingChoice::ReasoningDelta { id: None, reasoning: "Hmm".to_string() });
+            yield Ok(RawStreamingChoice::Reasoning {
+                id: None,
+                reasoning: "Hmm".to_string(),
+                signature: Some("sig".to_string()),
+            });
+            yield Ok(RawStreamingChoice::Message("Hello".to_string()));
+            yield Ok(RawStreamingChoice::FinalResponse(MockResponse { token_count: 2 }));
+        };
+
+        let pinned_stream: StreamingResult<MockResponse> = Box::pin(stream);
+        let mut stream = StreamingCompletionResponse::stream(pinned_stream);
+        while stream.next().await.is_some() {}
+
+        let choice = stream.choice.into_iter().collect::<Vec<_>>();
+        assert_eq!(
+            choice,
+            vec![
+                AssistantContent::Reasoning(
+                    Reasoning::new("Hmm").with_signature(Some("sig".to_string()))
+                ),
+                AssistantContent::text("Hello"),
+            ]
+        );
+    }
 }
 
 /// Describes responses from a streamed provider response which is either text, a tool call or a final usage response.
@@ -616,6 +920,12 @@ pub enum StreamedAssistantContent<R> {
         id: Option<String>,
         reasoning: String,
     },
+    /// A call of a tool hosted by the provider, along with its result
+    HostedToolCall(HostedToolCall),
+    /// A citation of the text streamed so far, see [RawStreamingChoice::Citation]
+    Citation(Citation),
+    /// Log-probabilities of the tokens generated since the previous item
+    Logprobs(Vec<TokenLogprob>),
     Final(R),
 }
 
@@ -624,9 +934,7 @@ where
     R: Clone + Unpin,
 {
     pub fn text(text: &str) -> Self {
-        Self::Text(Text {
-            text: text.to_string(),
-        })
+        Self::Text(Text::from(text.to_string()))
     }
 
     pub fn final_response(res: R) -> Self {
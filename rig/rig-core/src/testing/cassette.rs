@@ -0,0 +1,736 @@
+//! Record the interactions with a completion model into a [Cassette] and replay them later.
+//! See [RecordingModel] and [ReplayModel].
+//!
+//! Cassettes are JSON files containing the list of recorded interactions:
+//! ```text
+//! {
+//!   "interactions": [
+//!     {
+//!       "request": { "preamble": "...", "chat_history": [...], ... },
+//!       "response": { "type": "completion", "choice": [...], "usage": {...}, "raw_response": {...} }
+//!     },
+//!     {
+//!       "request": { ... },
+//!       "response": { "type": "stream", "chunks": [{ "type": "message", "text": "Hel" }, ...] }
+//!     }
+//!   ]
+//! }
+//! ```
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    OneOrMany,
+    completion::{
+        CompletionError, CompletionModel, CompletionRequest, CompletionResponse, FinishReason,
+        GetTokenUsage, TokenLogprob, Usage,
+    },
+    message::{AssistantContent, Citation, HostedToolCall},
+    streaming::{
+        RawStreamingChoice, RawStreamingToolCall, ResponseMetadata, StreamingCompletionResponse,
+        StreamingResult,
+    },
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum CassetteError {
+    /// Error reading or writing the cassette file
+    #[error("IoError: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// Error (de)serializing the cassette
+    #[error("JsonError: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// A list of recorded interactions with a completion model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Save the cassette to a JSON file, creating the parent directories if needed.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CassetteError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// A recorded request (see [normalize_request]) and the response of the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: serde_json::Value,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedResponse {
+    /// The response of a regular completion request
+    Completion {
+        choice: Box<OneOrMany<AssistantContent>>,
+        usage: Usage,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<FinishReason>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        logprobs: Option<Vec<TokenLogprob>>,
+        raw_response: serde_json::Value,
+    },
+    /// The chunks of a streaming completion request
+    Stream { chunks: Vec<RecordedChunk> },
+    /// The error returned by the model
+    Error { message: String },
+}
+
+/// A recorded streaming chunk, mirroring [RawStreamingChoice].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedChunk {
+    Message {
+        text: String,
+    },
+    ToolCall {
+        id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        call_id: Option<String>,
+        name: String,
+        arguments: serde_json::Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        additional_params: Option<serde_json::Value>,
+    },
+    ToolCallDelta {
+        id: String,
+        delta: String,
+    },
+    Reasoning {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        reasoning: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        signature: Option<String>,
+    },
+    ReasoningDelta {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        reasoning: String,
+    },
+    FinalResponse(ReplayedResponse),
+    Metadata {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        model: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        finish_reason: Option<FinishReason>,
+    },
+    Logprobs {
+        logprobs: Vec<TokenLogprob>,
+    },
+    HostedToolCall {
+        call: HostedToolCall,
+    },
+    Citation {
+        citation: Citation,
+    },
+    /// An error yielded in the middle of the stream
+    Error {
+        message: String,
+    },
+}
+
+impl RecordedChunk {
+    fn record<R>(chunk: &Result<RawStreamingChoice<R>, CompletionError>) -> Self
+    where
+        R: Clone + Serialize + GetTokenUsage,
+    {
+        let chunk = match chunk {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                return Self::Error {
+                    message: err.to_string(),
+                };
+            }
+        };
+
+        match chunk.clone() {
+            RawStreamingChoice::Message(text) => Self::Message { text },
+            RawStreamingChoice::ToolCall(tool_call) => Self::ToolCall {
+                id: tool_call.id,
+                call_id: tool_call.call_id,
+                name: tool_call.name,
+                arguments: tool_call.arguments,
+                signature: tool_call.signature,
+                additional_params: tool_call.additional_params,
+            },
+            RawStreamingChoice::ToolCallDelta { id, delta } => Self::ToolCallDelta { id, delta },
+            RawStreamingChoice::Reasoning {
+                id,
+                reasoning,
+                signature,
+            } => Self::Reasoning {
+                id,
+                reasoning,
+                signature,
+            },
+            RawStreamingChoice::ReasoningDelta { id, reasoning } => {
+                Self::ReasoningDelta { id, reasoning }
+            }
+            RawStreamingChoice::FinalResponse(response) => Self::FinalResponse(ReplayedResponse {
+                usage: response.token_usage(),
+                raw_response: serde_json::to_value(&response).unwrap_or_default(),
+            }),
+            RawStreamingChoice::Metadata(metadata) => Self::Metadata {
+                id: metadata.id,
+                model: metadata.model,
+                finish_reason: metadata.finish_reason,
+            },
+            RawStreamingChoice::Logprobs(logprobs) => Self::Logprobs { logprobs },
+            RawStreamingChoice::HostedToolCall(call) => Self::HostedToolCall { call },
+            RawStreamingChoice::Citation(citation) => Self::Citation { citation },
+        }
+    }
+
+    fn replay(self) -> Result<RawStreamingChoice<ReplayedResponse>, CompletionError> {
+        let chunk = match self {
+            Self::Message { text } => RawStreamingChoice::Message(text),
+            Self::ToolCall {
+                id,
+                call_id,
+                name,
+                arguments,
+                signature,
+                additional_params,
+            } => RawStreamingChoice::ToolCall(RawStreamingToolCall {
+                id,
+                call_id,
+                name,
+                arguments,
+                signature,
+                additional_params,
+            }),
+            Self::ToolCallDelta { id, delta } => RawStreamingChoice::ToolCallDelta { id, delta },
+            Self::Reasoning {
+                id,
+                reasoning,
+                signature,
+            } => RawStreamingChoice::Reasoning {
+                id,
+                reasoning,
+                signature,
+            },
+            Self::ReasoningDelta { id, reasoning } => {
+                RawStreamingChoice::ReasoningDelta { id, reasoning }
+            }
+            Self::FinalResponse(response) => RawStreamingChoice::FinalResponse(response),
+            Self::Metadata {
+                id,
+                model,
+                finish_reason,
+            } => RawStreamingChoice::Metadata(ResponseMetadata::new(id, model, finish_reason)),
+            Self::Logprobs { logprobs } => RawStreamingChoice::Logprobs(logprobs),
+            Self::HostedToolCall { call } => RawStreamingChoice::HostedToolCall(call),
+            Self::Citation { citation } => RawStreamingChoice::Citation(citation),
+            Self::Error { message } => return Err(CompletionError::ProviderError(message)),
+        };
+
+        Ok(chunk)
+    }
+}
+
+/// The final response of a replayed stream: the token usage and the raw response
+/// of the recorded model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayedResponse {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Usage>,
+    pub raw_response: serde_json::Value,
+}
+
+impl GetTokenUsage for ReplayedResponse {
+    fn token_usage(&self) -> Option<Usage> {
+        self.usage
+    }
+}
+
+/// Normalize a request into the JSON value used to match recorded interactions:
+/// fields that are not set (`null`, and empty lists at the top level) are removed so that
+/// cassettes keep matching when new optional fields are added to [CompletionRequest].
+pub fn normalize_request(request: &CompletionRequest) -> serde_json::Value {
+    let mut value = serde_json::to_value(request).unwrap_or_default();
+
+    if let serde_json::Value::Object(map) = &mut value {
+        map.retain(|_, value| !value.as_array().is_some_and(Vec::is_empty));
+    }
+    remove_nulls(&mut value);
+    value
+}
+
+fn remove_nulls(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            map.retain(|_, value| !value.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        serde_json::Value::Array(values) => values.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+/// A [CompletionModel] wrapper recording the requests sent to the inner model and
+/// its responses (including streamed chunks) into a [Cassette].
+///
+/// Clones of the model share the same cassette. Streamed responses are recorded once the stream
+/// is fully consumed, so [RecordingModel::save] should be called at the end of the test.
+#[derive(Clone)]
+pub struct RecordingModel<M> {
+    model: M,
+    cassette: Arc<Mutex<Cassette>>,
+}
+
+impl<M> RecordingModel<M>
+where
+    M: CompletionModel,
+{
+    pub fn new(model: M) -> Self {
+        Self {
+            model,
+            cassette: Arc::new(Mutex::new(Cassette::default())),
+        }
+    }
+
+    /// The interactions recorded so far.
+    pub fn cassette(&self) -> Cassette {
+        self.cassette
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Save the interactions recorded so far to a JSON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CassetteError> {
+        self.cassette().save(path)
+    }
+
+    fn record(cassette: &Mutex<Cassette>, request: serde_json::Value, response: RecordedResponse) {
+        cassette
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .interactions
+            .push(Interaction { request, response });
+    }
+}
+
+impl<M> CompletionModel for RecordingModel<M>
+where
+    M: CompletionModel + 'static,
+{
+    type Response = M::Response;
+    type StreamingResponse = M::StreamingResponse;
+    type Client = M::Client;
+
+    fn make(client: &Self::Client, model: impl Into<String>) -> Self {
+        Self::new(M::make(client, model))
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        let normalized = normalize_request(&request);
+
+        match self.model.completion(request).await {
+            Ok(response) => {
+                let recorded = RecordedResponse::Completion {
+                    choice: Box::new(response.choice.clone()),
+                    usage: response.usage,
+                    finish_reason: response.finish_reason.clone(),
+                    id: response.id.clone(),
+                    model: response.model.clone(),
+                    logprobs: response.logprobs.clone(),
+                    raw_response: serde_json::to_value(&response.raw_response)?,
+                };
+                Self::record(&self.cassette, normalized, recorded);
+                Ok(response)
+            }
+            Err(err) => {
+                let recorded = RecordedResponse::Error {
+                    message: err.to_string(),
+                };
+                Self::record(&self.cassette, normalized, recorded);
+                Err(err)
+            }
+        }
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let normalized = normalize_request(&request);
+
+        let response = match self.model.stream(request).await {
+            Ok(response) => response,
+            Err(err) => {
+                Self::record(
+                    &self.cassette,
+                    normalized,
+                    RecordedResponse::Error {
+                        message: err.to_string(),
+                    },
+                );
+                return Err(err);
+            }
+        };
+
+        let mut inner = response.inner;
+        let cassette = self.cassette.clone();
+        let stream = async_stream::stream! {
+            let mut chunks = Vec::new();
+            while let Some(chunk) = inner.next().await {
+                chunks.push(RecordedChunk::record(&chunk));
+                yield chunk;
+            }
+            Self::record(&cassette, normalized, RecordedResponse::Stream { chunks });
+        };
+        let stream: StreamingResult<M::StreamingResponse> = Box::pin(stream);
+
+        Ok(StreamingCompletionResponse::stream(stream))
+    }
+}
+
+#[derive(Debug, Default)]
+struct ReplayState {
+    cassette: Cassette,
+    replayed: Vec<bool>,
+}
+
+/// A [CompletionModel] serving the interactions of a [Cassette] recorded with a [RecordingModel].
+///
+/// Requests are matched against the recorded ones after normalization (see [normalize_request]).
+/// When the same request was recorded several times, the interactions are replayed in order
+/// (the last one is then served again). Requests without a recorded interaction fail with a
+/// [CompletionError::ProviderError].
+///
+/// Regular and streaming completions can be replayed from both kinds of recorded responses.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayModel {
+    state: Arc<Mutex<ReplayState>>,
+}
+
+impl ReplayModel {
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ReplayState {
+                replayed: vec![false; cassette.interactions.len()],
+                cassette,
+            })),
+        }
+    }
+
+    /// Load the cassette from a JSON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CassetteError> {
+        Ok(Self::new(Cassette::load(path)?))
+    }
+
+    fn replay(&self, request: &CompletionRequest) -> Result<RecordedResponse, CompletionError> {
+        let normalized = normalize_request(request);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        let matching = state
+            .cassette
+            .interactions
+            .iter()
+            .enumerate()
+            .filter(|(_, interaction)| interaction.request == normalized)
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+
+        let index = matching
+            .iter()
+            .find(|&&i| !state.replayed[i])
+            .or(matching.last())
+            .copied()
+            .ok_or_else(|| {
+                CompletionError::ProviderError(format!(
+                    "No recorded interaction matches the request: {normalized}"
+                ))
+            })?;
+
+        state.replayed[index] = true;
+        Ok(state.cassette.interactions[index].response.clone())
+    }
+}
+
+impl CompletionModel for ReplayModel {
+    type Response = serde_json::Value;
+    type StreamingResponse = ReplayedResponse;
+    type Client = Cassette;
+
+    fn make(client: &Self::Client, _: impl Into<String>) -> Self {
+        Self::new(client.clone())
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        match self.replay(&request)? {
+            RecordedResponse::Completion {
+                choice,
+                usage,
+                finish_reason,
+                id,
+                model,
+                logprobs,
+                raw_response,
+            } => Ok(CompletionResponse {
+                choice: *choice,
+                usage,
+                finish_reason,
+                id,
+                model,
+                logprobs,
+                raw_response,
+            }),
+            RecordedResponse::Stream { chunks } => {
+                let stream: StreamingResult<ReplayedResponse> =
+                    Box::pin(futures::stream::iter(chunks).map(RecordedChunk::replay));
+                let mut response = StreamingCompletionResponse::stream(stream);
+                while let Some(chunk) = response.next().await {
+                    chunk?;
+                }
+
+                Ok(CompletionResponse {
+                    choice: response.choice.clone(),
+                    usage: response
+                        .response
+                        .as_ref()
+                        .and_then(|response| response.usage)
+                        .unwrap_or_default(),
+                    finish_reason: response.finish_reason.clone(),
+                    id: response.id.clone(),
+                    model: response.model.clone(),
+                    logprobs: (!response.logprobs.is_empty()).then(|| response.logprobs.clone()),
+                    raw_response: response
+                        .response
+                        .map(|response| response.raw_response)
+                        .unwrap_or_default(),
+                })
+            }
+            RecordedResponse::Error { message } => Err(CompletionError::ProviderError(message)),
+        }
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let chunks = match self.replay(&request)? {
+            RecordedResponse::Completion {
+                choice,
+                usage,
+                finish_reason,
+                id,
+                model,
+                logprobs,
+                raw_response,
+            } => {
+                // The spans of streamed citations are relative to the whole streamed text
+                let mut text_len = 0;
+                let mut chunks = (*choice)
+                    .into_iter()
+                    .flat_map(|content| match content {
+                        AssistantContent::Text(text) => {
+                            let offset = text_len;
+                            text_len += text.text.len();
+                            std::iter::once(RecordedChunk::Message { text: text.text })
+                                .chain(text.citations.into_iter().map(|citation| {
+                                    RecordedChunk::Citation {
+                                        citation: citation.offset(offset),
+                                    }
+                                }))
+                                .collect()
+                        }
+                        AssistantContent::ToolCall(tool_call) => vec![RecordedChunk::ToolCall {
+                            id: tool_call.id,
+                            call_id: tool_call.call_id,
+                            name: tool_call.function.name,
+                            arguments: tool_call.function.arguments,
+                            signature: tool_call.signature,
+                            additional_params: tool_call.additional_params,
+                        }],
+                        AssistantContent::Reasoning(reasoning) => vec![RecordedChunk::Reasoning {
+                            id: reasoning.id,
+                            reasoning: reasoning.reasoning.join("\n"),
+                            signature: reasoning.signature,
+                        }],
+                        AssistantContent::HostedToolCall(call) => {
+                            vec![RecordedChunk::HostedToolCall { call }]
+                        }
+                        AssistantContent::Image(_) => vec![],
+                    })
+                    .collect::<Vec<_>>();
+                if let Some(logprobs) = logprobs {
+                    chunks.push(RecordedChunk::Logprobs { logprobs });
+                }
+                chunks.push(RecordedChunk::Metadata {
+                    id,
+                    model,
+                    finish_reason,
+                });
+                chunks.push(RecordedChunk::FinalResponse(ReplayedResponse {
+                    usage: Some(usage),
+                    raw_response,
+                }));
+                chunks
+            }
+            RecordedResponse::Stream { chunks } => chunks,
+            RecordedResponse::Error { message } => {
+                return Err(CompletionError::ProviderError(message));
+            }
+        };
+
+        let stream: StreamingResult<ReplayedResponse> =
+            Box::pin(futures::stream::iter(chunks).map(RecordedChunk::replay));
+
+        Ok(StreamingCompletionResponse::stream(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{agent::AgentBuilder, completion::Prompt, testing::MockCompletionModel};
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let mock = MockCompletionModel::new()
+            .text("Hello!")
+            .text("Goodbye!")
+            .error("overloaded");
+        let model = RecordingModel::new(mock);
+        let agent = AgentBuilder::new(model.clone())
+            .preamble("Be polite")
+            .build();
+
+        assert_eq!(agent.prompt("Hi").await.unwrap(), "Hello!");
+        assert_eq!(agent.prompt("Bye").await.unwrap(), "Goodbye!");
+        assert!(agent.prompt("Hi again").await.is_err());
+
+        let path = std::env::temp_dir().join(format!("rig-cassette-{}.json", std::process::id()));
+        model.save(&path).unwrap();
+        let replay = ReplayModel::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replay.state.lock().unwrap().cassette, model.cassette());
+
+        // Requests are matched regardless of their order
+        let agent = AgentBuilder::new(replay).preamble("Be polite").build();
+        assert_eq!(agent.prompt("Bye").await.unwrap(), "Goodbye!");
+        assert_eq!(agent.prompt("Hi").await.unwrap(), "Hello!");
+        assert!(
+            agent
+                .prompt("Hi again")
+                .await
+                .unwrap_err()
+                .to_string()
+                .contains("overloaded")
+        );
+
+        let err = agent.prompt("Unknown").await.unwrap_err();
+        assert!(err.to_string().contains("No recorded interaction"));
+    }
+
+    #[test]
+    fn test_normalize_request() {
+        use crate::completion::{HostedTool, ReasoningConfig, ReasoningEffort};
+
+        let model = MockCompletionModel::new();
+        let normalized = normalize_request(&model.completion_request("Hi").build());
+        // Unset fields are left out
+        assert_eq!(
+            normalized.as_object().unwrap().keys().collect::<Vec<_>>(),
+            vec!["chat_history", "sampling"]
+        );
+
+        // Every field of the request is taken into account
+        let request = model
+            .completion_request("Hi")
+            .reasoning(ReasoningConfig::effort(ReasoningEffort::Low))
+            .hosted_tool(HostedTool::code_interpreter())
+            .build();
+        let normalized = normalize_request(&request);
+        assert!(normalized.get("reasoning").is_some());
+        assert!(normalized.get("hosted_tools").is_some());
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_stream() {
+        let mock = MockCompletionModel::new()
+            .tool_call("search", json!({"query": "rig"}))
+            .text("Found it");
+        let model = RecordingModel::new(mock);
+
+        for _ in 0..2 {
+            let request = model.completion_request("Search rig").build();
+            let mut response = model.stream(request).await.unwrap();
+            while response.next().await.is_some() {}
+        }
+
+        let cassette = model.cassette();
+        assert_eq!(cassette.interactions.len(), 2);
+        let RecordedResponse::Stream { chunks } = &cassette.interactions[0].response else {
+            panic!("Expected a recorded stream");
+        };
+        assert!(matches!(&chunks[0], RecordedChunk::ToolCall { name, .. } if name == "search"));
+
+        // Identical requests are replayed in the order they were recorded
+        let replay = ReplayModel::new(cassette);
+        let mut response = replay
+            .stream(replay.completion_request("Search rig").build())
+            .await
+            .unwrap();
+        while response.next().await.is_some() {}
+        assert_eq!(
+            response.choice,
+            OneOrMany::one(AssistantContent::tool_call(
+                "call_0",
+                "search",
+                json!({"query": "rig"})
+            ))
+        );
+
+        let response = replay
+            .completion(replay.completion_request("Search rig").build())
+            .await
+            .unwrap();
+        assert_eq!(
+            response.choice,
+            OneOrMany::one(AssistantContent::text("Found it"))
+        );
+    }
+}
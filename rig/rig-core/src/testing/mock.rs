@@ -0,0 +1,425 @@
+//! A scriptable [CompletionModel] returning queued responses. See [MockCompletionModel].
+
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    OneOrMany,
+    completion::{
+        CompletionError, CompletionModel, CompletionRequest, CompletionResponse, FinishReason,
+        GetTokenUsage, Usage,
+    },
+    message::AssistantContent,
+    streaming::{
+        RawStreamingChoice, RawStreamingToolCall, ResponseMetadata, StreamingCompletionResponse,
+        StreamingResult,
+    },
+};
+
+/// A response returned by a [MockCompletionModel].
+/// Also used as the raw (streaming) response of the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MockResponse {
+    pub content: OneOrMany<AssistantContent>,
+    pub usage: Usage,
+    pub finish_reason: Option<FinishReason>,
+}
+
+impl MockResponse {
+    pub fn new(content: OneOrMany<AssistantContent>) -> Self {
+        Self {
+            content,
+            usage: Usage::new(),
+            finish_reason: None,
+        }
+    }
+
+    /// A response containing a single text message.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::new(OneOrMany::one(AssistantContent::text(text))).finish_reason(FinishReason::Stop)
+    }
+
+    /// A response containing a single tool call.
+    pub fn tool_call(name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        Self::new(OneOrMany::one(AssistantContent::tool_call(
+            "call_0", name, arguments,
+        )))
+        .finish_reason(FinishReason::ToolCalls)
+    }
+
+    /// Add a tool call to the response, e.g. to return parallel tool calls.
+    /// Tool calls are given the ids `call_0`, `call_1`... in order of appearance in the response.
+    pub fn with_tool_call(mut self, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        let id = format!("call_{}", self.tool_calls_count());
+        self.content
+            .push(AssistantContent::tool_call(id, name, arguments));
+        self
+    }
+
+    /// Add some content (text, reasoning...) to the response.
+    pub fn with_content(mut self, content: AssistantContent) -> Self {
+        self.content.push(content);
+        self
+    }
+
+    pub fn usage(mut self, usage: Usage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    pub fn finish_reason(mut self, finish_reason: FinishReason) -> Self {
+        self.finish_reason = Some(finish_reason);
+        self
+    }
+
+    fn tool_calls_count(&self) -> usize {
+        self.content
+            .iter()
+            .filter(|content| matches!(content, AssistantContent::ToolCall(_)))
+            .count()
+    }
+
+    /// Split the response into the chunks a streaming provider would yield.
+    /// Images cannot be streamed and are skipped.
+    fn into_chunks(self) -> Vec<RawStreamingChoice<MockResponse>> {
+        // The spans of streamed citations are relative to the whole streamed text
+        let mut text_len = 0;
+        let mut chunks = self
+            .content
+            .iter()
+            .flat_map(|content| match content {
+                AssistantContent::Text(text) => {
+                    let offset = text_len;
+                    text_len += text.text.len();
+                    std::iter::once(RawStreamingChoice::Message(text.text.clone()))
+                        .chain(text.citations.iter().map(|citation| {
+                            RawStreamingChoice::Citation(citation.clone().offset(offset))
+                        }))
+                        .collect()
+                }
+                AssistantContent::ToolCall(tool_call) => {
+                    vec![RawStreamingChoice::ToolCall(RawStreamingToolCall {
+                        id: tool_call.id.clone(),
+                        call_id: tool_call.call_id.clone(),
+                        name: tool_call.function.name.clone(),
+                        arguments: tool_call.function.arguments.clone(),
+                        signature: tool_call.signature.clone(),
+                        additional_params: tool_call.additional_params.clone(),
+                    })]
+                }
+                AssistantContent::Reasoning(reasoning) => vec![RawStreamingChoice::Reasoning {
+                    id: reasoning.id.clone(),
+                    reasoning: reasoning.reasoning.join("\n"),
+                    signature: reasoning.signature.clone(),
+                }],
+                AssistantContent::HostedToolCall(call) => {
+                    vec![RawStreamingChoice::HostedToolCall(call.clone())]
+                }
+                AssistantContent::Image(_) => vec![],
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(finish_reason) = self.finish_reason.clone() {
+            chunks.push(RawStreamingChoice::Metadata(ResponseMetadata::new(
+                None,
+                None,
+                Some(finish_reason),
+            )));
+        }
+        chunks.push(RawStreamingChoice::FinalResponse(self));
+
+        chunks
+    }
+}
+
+impl GetTokenUsage for MockResponse {
+    fn token_usage(&self) -> Option<Usage> {
+        Some(self.usage)
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    responses: VecDeque<Result<MockResponse, String>>,
+    requests: Vec<CompletionRequest>,
+}
+
+/// A [CompletionModel] returning queued responses, in order, to the requests it receives
+/// (both for regular and streaming completions).
+///
+/// Clones of the model share the same queue and request log, so a clone can be given to an agent
+/// while the original is used to queue more responses or inspect the requests.
+/// Once the queue is empty, requests fail with a [CompletionError::ProviderError].
+///
+/// # Example
+/// ```rust
+/// use rig::testing::{MockCompletionModel, MockResponse};
+/// use serde_json::json;
+///
+/// let model = MockCompletionModel::new()
+///     .tool_call("search", json!({"query": "rig"}))
+///     .response(
+///         MockResponse::tool_call("add", json!({"x": 1, "y": 2}))
+///             .with_tool_call("subtract", json!({"x": 5, "y": 3}))
+///     )
+///     .error("rate limited")
+///     .text("Done!");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockCompletionModel {
+    state: Arc<Mutex<MockState>>,
+}
+
+impl MockCompletionModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a response.
+    pub fn response(self, response: MockResponse) -> Self {
+        self.push(response);
+        self
+    }
+
+    /// Queue a text response.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.response(MockResponse::text(text))
+    }
+
+    /// Queue a response containing a single tool call.
+    pub fn tool_call(self, name: impl Into<String>, arguments: serde_json::Value) -> Self {
+        self.response(MockResponse::tool_call(name, arguments))
+    }
+
+    /// Queue an error, returned as a [CompletionError::ProviderError].
+    pub fn error(self, message: impl Into<String>) -> Self {
+        self.lock().responses.push_back(Err(message.into()));
+        self
+    }
+
+    /// Queue a response without consuming the model (e.g. after it was given to an agent).
+    pub fn push(&self, response: MockResponse) {
+        self.lock().responses.push_back(Ok(response));
+    }
+
+    /// The requests received so far, in order.
+    pub fn requests(&self) -> Vec<CompletionRequest> {
+        self.lock().requests.clone()
+    }
+
+    /// The number of queued responses that were not returned yet.
+    pub fn remaining(&self) -> usize {
+        self.lock().responses.len()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn next_response(&self, request: CompletionRequest) -> Result<MockResponse, CompletionError> {
+        let mut state = self.lock();
+        state.requests.push(request);
+
+        match state.responses.pop_front() {
+            Some(Ok(response)) => Ok(response),
+            Some(Err(message)) => Err(CompletionError::ProviderError(message)),
+            None => Err(CompletionError::ProviderError(
+                "MockCompletionModel has no queued response left".into(),
+            )),
+        }
+    }
+}
+
+impl CompletionModel for MockCompletionModel {
+    type Response = MockResponse;
+    type StreamingResponse = MockResponse;
+    type Client = ();
+
+    fn make(_: &Self::Client, _: impl Into<String>) -> Self {
+        Self::new()
+    }
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        let response = self.next_response(request)?;
+
+        Ok(CompletionResponse {
+            choice: response.content.clone(),
+            usage: response.usage,
+            finish_reason: response.finish_reason.clone(),
+            id: None,
+            model: None,
+            logprobs: None,
+            raw_response: response,
+        })
+    }
+
+    async fn stream(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<StreamingCompletionResponse<Self::StreamingResponse>, CompletionError> {
+        let chunks = self.next_response(request)?.into_chunks();
+        let stream: StreamingResult<MockResponse> =
+            Box::pin(futures::stream::iter(chunks.into_iter().map(Ok)));
+
+        Ok(StreamingCompletionResponse::stream(stream))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::StreamExt;
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        agent::{AgentBuilder, MultiTurnStreamItem},
+        completion::{Prompt, ToolDefinition},
+        extractor::ExtractorBuilder,
+        message::{Message, ToolResultContent, UserContent},
+        streaming::StreamingPrompt,
+        tool::Tool,
+    };
+
+    #[derive(Deserialize)]
+    struct AddArgs {
+        x: i32,
+        y: i32,
+    }
+
+    struct Adder;
+
+    impl Tool for Adder {
+        const NAME: &'static str = "add";
+        type Error = std::convert::Infallible;
+        type Args = AddArgs;
+        type Output = i32;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Add x and y".to_string(),
+                parameters: json!({
+                    "type": "object",
+                    "properties": {
+                        "x": { "type": "number" },
+                        "y": { "type": "number" }
+                    }
+                }),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            Ok(args.x + args.y)
+        }
+    }
+
+    fn tool_results(request: &CompletionRequest) -> Vec<String> {
+        let mut results = request
+            .chat_history
+            .iter()
+            .filter_map(|message| match message {
+                Message::User { content } => match content.first() {
+                    UserContent::ToolResult(result) => match result.content.first() {
+                        ToolResultContent::Text(text) => Some(text.text),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        results.sort();
+        results
+    }
+
+    #[tokio::test]
+    async fn test_mock_model_multi_turn_tool_loop() {
+        let model = MockCompletionModel::new()
+            .tool_call("add", json!({"x": 1, "y": 2}))
+            .text("1 + 2 = 3");
+        let agent = AgentBuilder::new(model.clone()).tool(Adder).build();
+
+        let answer = agent.prompt("What is 1 + 2?").multi_turn(2).await.unwrap();
+        assert_eq!(answer, "1 + 2 = 3");
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].tools.len(), 1);
+        assert_eq!(tool_results(&requests[1]), vec!["3"]);
+        assert_eq!(model.remaining(), 0);
+
+        let err = agent.prompt("Again").await.unwrap_err();
+        assert!(err.to_string().contains("no queued response"));
+    }
+
+    #[tokio::test]
+    async fn test_mock_model_stream() {
+        let model = MockCompletionModel::new()
+            .response(
+                MockResponse::tool_call("add", json!({"x": 1, "y": 2}))
+                    .with_tool_call("add", json!({"x": 3, "y": 4})),
+            )
+            .text("3 and 7");
+        let agent = AgentBuilder::new(model.clone()).tool(Adder).build();
+
+        let mut stream = agent
+            .stream_prompt("Add 1 + 2 and 3 + 4")
+            .multi_turn(2)
+            .await;
+        let mut final_response = None;
+        while let Some(item) = stream.next().await {
+            if let MultiTurnStreamItem::FinalResponse(response) = item.unwrap() {
+                final_response = Some(response);
+            }
+        }
+        assert_eq!(final_response.unwrap().response(), "3 and 7");
+
+        let requests = model.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(tool_results(&requests[1]), vec!["3", "7"]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_model_error() {
+        let model = MockCompletionModel::new()
+            .error("rate limited")
+            .text("Hello");
+        let agent = AgentBuilder::new(model).build();
+
+        assert!(agent.prompt("Hi").await.is_err());
+        assert_eq!(agent.prompt("Hi").await.unwrap(), "Hello");
+    }
+
+    #[derive(Debug, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+    struct Person {
+        name: String,
+        age: u8,
+    }
+
+    #[tokio::test]
+    async fn test_mock_model_extractor_retry() {
+        let model = MockCompletionModel::new()
+            .text("John is 42")
+            .tool_call("submit", json!({"name": "John", "age": 42}));
+        let extractor = ExtractorBuilder::<_, Person>::new(model.clone())
+            .retries(1)
+            .build();
+
+        let person = extractor.extract("John is 42 years old").await.unwrap();
+        assert_eq!(
+            person,
+            Person {
+                name: "John".into(),
+                age: 42
+            }
+        );
+        assert_eq!(model.requests().len(), 2);
+    }
+}
@@ -0,0 +1,52 @@
+//! Completion models to test agents, extractors and pipelines offline and deterministically.
+//!
+//! - [MockCompletionModel]: a scriptable model returning queued responses (text, tool calls,
+//!   reasoning or errors) and keeping the requests it received for later assertions.
+//! - [RecordingModel]: wraps a real model and records every request/response pair (including
+//!   streamed chunks) into a [Cassette] that can be saved to a JSON file.
+//! - [ReplayModel]: serves the interactions of a cassette back, matching on the normalized request.
+//!
+//! # Example
+//! ```rust
+//! use rig::{agent::AgentBuilder, completion::Prompt, testing::MockCompletionModel};
+//! use serde_json::json;
+//!
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let model = MockCompletionModel::new()
+//!     .tool_call("add", json!({"x": 1, "y": 2}))
+//!     .text("1 + 2 = 3");
+//!
+//! let agent = AgentBuilder::new(model.clone())
+//!     .preamble("You are a calculator")
+//!     // `Adder` implements the `Tool` trait
+//!     .tool(Adder)
+//!     .build();
+//!
+//! let answer = agent.prompt("What is 1 + 2?").multi_turn(2).await?;
+//! assert_eq!(answer, "1 + 2 = 3");
+//! assert_eq!(model.requests().len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Recording a cassette once with a real provider, then replaying it in CI:
+//! ```rust,ignore
+//! use rig::testing::{RecordingModel, ReplayModel};
+//!
+//! // Record (needs network access and an API key)
+//! let model = RecordingModel::new(openai_client.completion_model(openai::GPT_4O));
+//! let agent = AgentBuilder::new(model.clone()).build();
+//! agent.prompt("Hello!").await?;
+//! model.save("tests/cassettes/hello.json")?;
+//!
+//! // Replay (offline)
+//! let model = ReplayModel::load("tests/cassettes/hello.json")?;
+//! let agent = AgentBuilder::new(model).build();
+//! agent.prompt("Hello!").await?;
+//! ```
+
+pub mod cassette;
+pub mod mock;
+
+pub use cassette::{Cassette, CassetteError, RecordingModel, ReplayModel, normalize_request};
+pub use mock::{MockCompletionModel, MockResponse};
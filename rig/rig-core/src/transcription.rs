@@ -5,7 +5,12 @@
 use crate::client::transcription::TranscriptionModelHandle;
 use crate::wasm_compat::{WasmBoxedFuture, WasmCompatSend, WasmCompatSync};
 use crate::{http_client, json_utils};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::time::Duration;
 use std::{fs, path::Path};
 use thiserror::Error;
 
@@ -66,9 +71,251 @@ where
 /// and the raw response.
 pub struct TranscriptionResponse<T> {
     pub text: String,
+    /// The timed segments of the transcription, empty unless requested with
+    /// [TranscriptionRequestBuilder::response_format] or
+    /// [TranscriptionRequestBuilder::timestamp_granularities] (or returned by default by the
+    /// provider)
+    pub segments: Vec<TranscriptionSegment>,
+    /// The language of the audio, when detected by the provider
+    pub language: Option<String>,
     pub response: T,
 }
 
+impl<T> TranscriptionResponse<T> {
+    /// Renders the segments of the transcription as SubRip (SRT) subtitles.
+    pub fn to_srt(&self) -> String {
+        to_srt(&self.segments)
+    }
+
+    /// Renders the segments of the transcription as WebVTT subtitles.
+    pub fn to_vtt(&self) -> String {
+        to_vtt(&self.segments)
+    }
+}
+
+/// A transcribed segment of audio. The times are in seconds from the start of the audio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionSegment {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+    /// The timed words of the segment, when word timestamps were requested
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<TranscriptionWord>,
+    /// The label of the speaker of the segment, when the transcription is diarized
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+impl TranscriptionSegment {
+    pub fn new(text: impl Into<String>, start: f64, end: f64) -> Self {
+        Self {
+            text: text.into(),
+            start,
+            end,
+            words: vec![],
+            speaker: None,
+        }
+    }
+
+    pub fn with_words(mut self, words: Vec<TranscriptionWord>) -> Self {
+        self.words = words;
+        self
+    }
+
+    pub fn with_speaker(mut self, speaker: impl Into<String>) -> Self {
+        self.speaker = Some(speaker.into());
+        self
+    }
+
+    /// Shifts the times of the segment and of its words by `offset` seconds.
+    pub(crate) fn offset(mut self, offset: f64) -> Self {
+        self.start += offset;
+        self.end += offset;
+        for word in &mut self.words {
+            word.start += offset;
+            word.end += offset;
+        }
+        self
+    }
+}
+
+/// A transcribed word. The times are in seconds from the start of the audio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptionWord {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+}
+
+impl TranscriptionWord {
+    pub fn new(word: impl Into<String>, start: f64, end: f64) -> Self {
+        Self {
+            word: word.into(),
+            start,
+            end,
+            speaker: None,
+        }
+    }
+}
+
+/// Assigns each of `words` to the segment during which it starts. Providers returning words
+/// without segments get a single segment spanning all the words.
+pub(crate) fn segments_with_words(
+    text: &str,
+    mut segments: Vec<TranscriptionSegment>,
+    words: Vec<TranscriptionWord>,
+) -> Vec<TranscriptionSegment> {
+    if words.is_empty() {
+        return segments;
+    }
+
+    if segments.is_empty() {
+        let start = words.first().map(|word| word.start).unwrap_or_default();
+        let end = words.last().map(|word| word.end).unwrap_or_default();
+        return vec![TranscriptionSegment::new(text.trim(), start, end).with_words(words)];
+    }
+
+    for word in words {
+        let index = segments
+            .iter()
+            .rposition(|segment| segment.start <= word.start)
+            .unwrap_or_default();
+        segments[index].words.push(word);
+    }
+
+    segments
+}
+
+/// The output of a transcription request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscriptionFormat {
+    /// Only the text of the transcription
+    Json,
+    /// The text along with the timed segments and the detected language
+    VerboseJson,
+    /// The timed segments labelled with their speaker, for the models supporting diarization
+    DiarizedJson,
+}
+
+impl TranscriptionFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptionFormat::Json => "json",
+            TranscriptionFormat::VerboseJson => "verbose_json",
+            TranscriptionFormat::DiarizedJson => "diarized_json",
+        }
+    }
+}
+
+/// The level of detail of the timestamps of a transcription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampGranularity {
+    Segment,
+    Word,
+}
+
+impl TimestampGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimestampGranularity::Segment => "segment",
+            TimestampGranularity::Word => "word",
+        }
+    }
+}
+
+/// Renders `segments` as SubRip (SRT) subtitles, prefixing the text of the segments with their
+/// speaker when diarized.
+pub fn to_srt(segments: &[TranscriptionSegment]) -> String {
+    let mut srt = String::new();
+
+    for (index, segment) in segments.iter().enumerate() {
+        let _ = writeln!(
+            srt,
+            "{}\n{} --> {}",
+            index + 1,
+            subtitle_timestamp(segment.start, ','),
+            subtitle_timestamp(segment.end, ',')
+        );
+        match &segment.speaker {
+            Some(speaker) => {
+                let _ = writeln!(srt, "{speaker}: {}\n", segment.text.trim());
+            }
+            None => {
+                let _ = writeln!(srt, "{}\n", segment.text.trim());
+            }
+        }
+    }
+
+    srt
+}
+
+/// Renders `segments` as WebVTT subtitles, with a voice span for the speaker of the diarized
+/// segments.
+pub fn to_vtt(segments: &[TranscriptionSegment]) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+
+    for segment in segments {
+        let _ = writeln!(
+            vtt,
+            "{} --> {}",
+            subtitle_timestamp(segment.start, '.'),
+            subtitle_timestamp(segment.end, '.')
+        );
+        match &segment.speaker {
+            Some(speaker) => {
+                let _ = writeln!(vtt, "<v {speaker}>{}\n", segment.text.trim());
+            }
+            None => {
+                let _ = writeln!(vtt, "{}\n", segment.text.trim());
+            }
+        }
+    }
+
+    vtt
+}
+
+/// Formats `seconds` as `HH:MM:SS<separator>mmm`.
+fn subtitle_timestamp(seconds: f64, separator: char) -> String {
+    let millis = (seconds.max(0.0) * 1000.0).round() as u64;
+
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// An item of a streamed transcription.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamedTranscription {
+    /// Text transcribed so far for the segment starting at `start`, to be appended to the previous
+    /// partials of the segment.
+    Partial { text: String, start: f64 },
+    /// The final transcription of a segment, superseding its partials.
+    Segment(TranscriptionSegment),
+}
+
+/// The chunks of raw audio of a streaming transcription: 16-bit little-endian PCM, interleaved
+/// when there are several channels.
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub type AudioInputStream = Pin<Box<dyn Stream<Item = Vec<u8>> + Send>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub type AudioInputStream = Pin<Box<dyn Stream<Item = Vec<u8>>>>;
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+pub type StreamingTranscriptionResponse =
+    Pin<Box<dyn Stream<Item = Result<StreamedTranscription, TranscriptionError>> + Send>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub type StreamingTranscriptionResponse =
+    Pin<Box<dyn Stream<Item = Result<StreamedTranscription, TranscriptionError>>>>;
+
 /// Trait defining a transcription model that can be used to generate transcription requests.
 /// This trait is meant to be implemented by the user to define a custom transcription model,
 /// either from a third-party provider (e.g: OpenAI) or a local model.
@@ -87,10 +334,179 @@ pub trait TranscriptionModel: Clone + WasmCompatSend + WasmCompatSync {
         Output = Result<TranscriptionResponse<Self::Response>, TranscriptionError>,
     > + WasmCompatSend;
 
+    /// Transcribes a stream of audio as it is received, yielding the partial and final segments of
+    /// the transcription.
+    /// Models without streaming support transcribe the audio one window at a time (see
+    /// [StreamingTranscriptionRequest::window]), yielding a segment per window.
+    fn stream_transcription(
+        &self,
+        request: StreamingTranscriptionRequest,
+    ) -> impl std::future::Future<
+        Output = Result<StreamingTranscriptionResponse, TranscriptionError>,
+    > + WasmCompatSend
+    where
+        Self: 'static,
+    {
+        let response = transcribe_windows(self.clone(), request);
+
+        async move { Ok(response) }
+    }
+
     /// Generates a transcription request builder for the given `file`
     fn transcription_request(&self) -> TranscriptionRequestBuilder<Self> {
         TranscriptionRequestBuilder::new(self.clone())
     }
+
+    /// Generates a streaming transcription request builder for the given `audio`, sampled at
+    /// `sample_rate` Hz
+    fn streaming_transcription_request(
+        &self,
+        audio: AudioInputStream,
+        sample_rate: u32,
+    ) -> StreamingTranscriptionRequestBuilder<Self>
+    where
+        Self: 'static,
+    {
+        StreamingTranscriptionRequestBuilder::new(self.clone(), audio, sample_rate)
+    }
+}
+
+/// Transcribes each window of `request` with the one-shot [TranscriptionModel::transcription],
+/// skipping the silent windows.
+pub(crate) fn transcribe_windows<M>(
+    model: M,
+    request: StreamingTranscriptionRequest,
+) -> StreamingTranscriptionResponse
+where
+    M: TranscriptionModel + 'static,
+{
+    let StreamingTranscriptionRequest {
+        audio,
+        sample_rate,
+        channels,
+        window,
+        language,
+        prompt,
+        temperature,
+        additional_params,
+    } = request;
+
+    let mut windows = audio_windows(audio, sample_rate, channels, window);
+
+    Box::pin(async_stream::stream! {
+        while let Some(window) = windows.next().await {
+            let request = TranscriptionRequest {
+                data: window.wav,
+                filename: "audio.wav".to_string(),
+                language: language.clone(),
+                prompt: prompt.clone(),
+                temperature,
+                response_format: None,
+                timestamp_granularities: vec![],
+                translate: false,
+                additional_params: additional_params.clone(),
+            };
+
+            match model.transcription(request).await {
+                Ok(response) if response.text.trim().is_empty() => continue,
+                // Models returning segments by default get them shifted to the window
+                Ok(response) if !response.segments.is_empty() => {
+                    for segment in response.segments {
+                        yield Ok(StreamedTranscription::Segment(segment.offset(window.start)));
+                    }
+                }
+                Ok(response) => {
+                    yield Ok(StreamedTranscription::Segment(TranscriptionSegment::new(
+                        response.text,
+                        window.start,
+                        window.end,
+                    )));
+                }
+                Err(err) => {
+                    yield Err(err);
+                    break;
+                }
+            }
+        }
+    })
+}
+
+/// A window of a streamed audio input, encoded as a WAV file.
+pub(crate) struct AudioWindow {
+    pub wav: Vec<u8>,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32")))]
+type AudioWindowStream = Pin<Box<dyn Stream<Item = AudioWindow> + Send>>;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+type AudioWindowStream = Pin<Box<dyn Stream<Item = AudioWindow>>>;
+
+/// Splits a PCM16 audio stream into windows of `window`, the last window holding the remainder of
+/// the audio.
+pub(crate) fn audio_windows(
+    mut audio: AudioInputStream,
+    sample_rate: u32,
+    channels: u16,
+    window: Duration,
+) -> AudioWindowStream {
+    let frame_size = 2 * channels.max(1) as usize;
+    let bytes_per_second = (sample_rate as usize * frame_size) as f64;
+    let window_size =
+        ((bytes_per_second * window.as_secs_f64()) as usize / frame_size).max(1) * frame_size;
+
+    Box::pin(async_stream::stream! {
+        let mut buffer = Vec::new();
+        let mut offset = 0;
+
+        loop {
+            let chunk = audio.next().await;
+            let ended = chunk.is_none();
+            buffer.extend(chunk.unwrap_or_default());
+
+            while buffer.len() >= window_size || (ended && !buffer.is_empty()) {
+                let data = buffer
+                    .drain(..window_size.min(buffer.len()))
+                    .collect::<Vec<_>>();
+                let start = offset as f64 / bytes_per_second;
+                offset += data.len();
+
+                yield AudioWindow {
+                    wav: pcm16_to_wav(&data, sample_rate, channels),
+                    start,
+                    end: offset as f64 / bytes_per_second,
+                };
+            }
+
+            if ended {
+                break;
+            }
+        }
+    })
+}
+
+/// Wraps 16-bit little-endian PCM samples in a WAV container.
+pub(crate) fn pcm16_to_wav(data: &[u8], sample_rate: u32, channels: u16) -> Vec<u8> {
+    let block_align = channels * 2;
+    let byte_rate = sample_rate * block_align as u32;
+
+    let mut wav = Vec::with_capacity(44 + data.len());
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&channels.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&byte_rate.to_le_bytes());
+    wav.extend_from_slice(&block_align.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(data);
+    wav
 }
 
 #[allow(deprecated)]
@@ -121,6 +537,8 @@ where
 
             Ok(TranscriptionResponse {
                 text: resp.text,
+                segments: resp.segments,
+                language: resp.language,
                 response: (),
             })
         })
@@ -145,10 +563,27 @@ pub struct TranscriptionRequest {
     pub prompt: Option<String>,
     /// The temperature sent to the transcription model provider
     pub temperature: Option<f64>,
+    /// The output of the transcription, the default of the provider when unset
+    pub response_format: Option<TranscriptionFormat>,
+    /// The timestamps to include in the transcription, implying [TranscriptionFormat::VerboseJson]
+    /// when no response format is set
+    pub timestamp_granularities: Vec<TimestampGranularity>,
+    /// Whether to translate the audio to English instead of transcribing it
+    pub translate: bool,
     /// Additional parameters to be sent to the transcription model provider
     pub additional_params: Option<serde_json::Value>,
 }
 
+impl TranscriptionRequest {
+    /// The output requested, [TranscriptionFormat::VerboseJson] when only timestamps were
+    /// requested.
+    pub(crate) fn output_format(&self) -> Option<TranscriptionFormat> {
+        self.response_format.or_else(|| {
+            (!self.timestamp_granularities.is_empty()).then_some(TranscriptionFormat::VerboseJson)
+        })
+    }
+}
+
 /// Builder struct for a transcription request
 ///
 /// Example usage:
@@ -201,6 +636,9 @@ where
     language: Option<String>,
     prompt: Option<String>,
     temperature: Option<f64>,
+    response_format: Option<TranscriptionFormat>,
+    timestamp_granularities: Vec<TimestampGranularity>,
+    translate: bool,
     additional_params: Option<serde_json::Value>,
 }
 
@@ -216,6 +654,9 @@ where
             language: None,
             prompt: None,
             temperature: None,
+            response_format: None,
+            timestamp_granularities: vec![],
+            translate: false,
             additional_params: None,
         }
     }
@@ -267,6 +708,28 @@ where
         self
     }
 
+    /// Sets the output of the transcription (e.g. [TranscriptionFormat::VerboseJson] for timed
+    /// segments)
+    pub fn response_format(mut self, response_format: TranscriptionFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
+
+    /// Sets the timestamps to include in the transcription
+    pub fn timestamp_granularities(
+        mut self,
+        timestamp_granularities: impl IntoIterator<Item = TimestampGranularity>,
+    ) -> Self {
+        self.timestamp_granularities = timestamp_granularities.into_iter().collect();
+        self
+    }
+
+    /// Translates the audio to English instead of transcribing it
+    pub fn translate(mut self, translate: bool) -> Self {
+        self.translate = translate;
+        self
+    }
+
     /// Adds additional parameters to the transcription request.
     pub fn additional_params(mut self, additional_params: serde_json::Value) -> Self {
         match self.additional_params {
@@ -299,6 +762,9 @@ where
             language: self.language,
             prompt: self.prompt,
             temperature: self.temperature,
+            response_format: self.response_format,
+            timestamp_granularities: self.timestamp_granularities,
+            translate: self.translate,
             additional_params: self.additional_params,
         }
     }
@@ -310,3 +776,214 @@ where
         model.transcription(self.build()).await
     }
 }
+
+/// Struct representing a streaming transcription request, see
+/// [TranscriptionModel::stream_transcription].
+pub struct StreamingTranscriptionRequest {
+    /// The audio to transcribe, as 16-bit little-endian PCM
+    pub audio: AudioInputStream,
+    /// The sample rate of the audio, in Hz
+    pub sample_rate: u32,
+    /// The number of interleaved channels of the audio
+    pub channels: u16,
+    /// The length of audio transcribed at once by models without streaming support
+    pub window: Duration,
+    /// The language used in the response from the transcription model provider
+    pub language: Option<String>,
+    /// The prompt to be sent to the transcription model provider
+    pub prompt: Option<String>,
+    /// The temperature sent to the transcription model provider
+    pub temperature: Option<f64>,
+    /// Additional parameters to be sent to the transcription model provider
+    pub additional_params: Option<serde_json::Value>,
+}
+
+/// Builder struct for a streaming transcription request
+///
+/// Example usage:
+/// ```rust,ignore
+/// use futures::StreamExt;
+/// use rig::transcription::{StreamedTranscription, TranscriptionModel};
+///
+/// // `microphone` yields chunks of 16kHz mono PCM16 audio
+/// let mut stream = model
+///     .streaming_transcription_request(Box::pin(microphone), 16_000)
+///     .language("en".to_string())
+///     .send()
+///     .await?;
+///
+/// while let Some(item) = stream.next().await {
+///     match item? {
+///         StreamedTranscription::Partial { text, .. } => print!("{text}"),
+///         StreamedTranscription::Segment(segment) => println!("\n[{}s] {}", segment.start, segment.text),
+///     }
+/// }
+/// ```
+pub struct StreamingTranscriptionRequestBuilder<M>
+where
+    M: TranscriptionModel,
+{
+    model: M,
+    request: StreamingTranscriptionRequest,
+}
+
+impl<M> StreamingTranscriptionRequestBuilder<M>
+where
+    M: TranscriptionModel + 'static,
+{
+    pub fn new(model: M, audio: AudioInputStream, sample_rate: u32) -> Self {
+        Self {
+            model,
+            request: StreamingTranscriptionRequest {
+                audio,
+                sample_rate,
+                channels: 1,
+                window: Duration::from_secs(5),
+                language: None,
+                prompt: None,
+                temperature: None,
+                additional_params: None,
+            },
+        }
+    }
+
+    /// Sets the number of interleaved channels of the audio (defaults to 1)
+    pub fn channels(mut self, channels: u16) -> Self {
+        self.request.channels = channels;
+        self
+    }
+
+    /// Sets the length of audio transcribed at once by models without streaming support
+    /// (defaults to 5 seconds)
+    pub fn window(mut self, window: Duration) -> Self {
+        self.request.window = window;
+        self
+    }
+
+    /// Sets the output language for the transcription request
+    pub fn language(mut self, language: String) -> Self {
+        self.request.language = Some(language);
+        self
+    }
+
+    /// Sets the prompt to be sent in the transcription request
+    pub fn prompt(mut self, prompt: String) -> Self {
+        self.request.prompt = Some(prompt);
+        self
+    }
+
+    /// Set the temperature to be sent in the transcription request
+    pub fn temperature(mut self, temperature: f64) -> Self {
+        self.request.temperature = Some(temperature);
+        self
+    }
+
+    /// Adds additional parameters to the transcription request.
+    pub fn additional_params(mut self, additional_params: serde_json::Value) -> Self {
+        self.request.additional_params = Some(match self.request.additional_params {
+            Some(params) => json_utils::merge(params, additional_params),
+            None => additional_params,
+        });
+        self
+    }
+
+    /// Builds the streaming transcription request
+    pub fn build(self) -> StreamingTranscriptionRequest {
+        self.request
+    }
+
+    /// Sends the streaming transcription request to the transcription model provider and returns
+    /// the stream of transcribed segments
+    pub async fn send(self) -> Result<StreamingTranscriptionResponse, TranscriptionError> {
+        self.model.stream_transcription(self.request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pcm16_to_wav_header() {
+        let wav = pcm16_to_wav(&[1, 0, 2, 0], 16_000, 1);
+
+        assert_eq!(wav.len(), 48);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 40);
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 16_000);
+        assert_eq!(u32::from_le_bytes(wav[28..32].try_into().unwrap()), 32_000);
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 4);
+        assert_eq!(&wav[44..], &[1, 0, 2, 0]);
+    }
+
+    #[test]
+    fn test_segments_with_words() {
+        let segments = segments_with_words(
+            "Hello there. Bye.",
+            vec![
+                TranscriptionSegment::new("Hello there.", 0.0, 1.0),
+                TranscriptionSegment::new("Bye.", 1.0, 1.5),
+            ],
+            vec![
+                TranscriptionWord::new("Hello", 0.0, 0.4),
+                TranscriptionWord::new("there", 0.5, 0.9),
+                TranscriptionWord::new("Bye", 1.1, 1.4),
+            ],
+        );
+        assert_eq!(segments[0].words.len(), 2);
+        assert_eq!(segments[1].words[0].word, "Bye");
+
+        let segments = segments_with_words(
+            " Hello there",
+            vec![],
+            vec![
+                TranscriptionWord::new("Hello", 0.2, 0.4),
+                TranscriptionWord::new("there", 0.5, 0.9),
+            ],
+        );
+        assert_eq!(
+            segments,
+            vec![
+                TranscriptionSegment::new("Hello there", 0.2, 0.9).with_words(vec![
+                    TranscriptionWord::new("Hello", 0.2, 0.4),
+                    TranscriptionWord::new("there", 0.5, 0.9),
+                ])
+            ]
+        );
+    }
+
+    #[test]
+    fn test_subtitles() {
+        let segments = vec![
+            TranscriptionSegment::new(" Hello there.", 0.0, 1.25),
+            TranscriptionSegment::new("Hi!", 3661.5, 3662.0).with_speaker("B"),
+        ];
+
+        assert_eq!(
+            to_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:01,250\nHello there.\n\n\
+             2\n01:01:01,500 --> 01:01:02,000\nB: Hi!\n\n"
+        );
+        assert_eq!(
+            to_vtt(&segments),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.250\nHello there.\n\n\
+             01:01:01.500 --> 01:01:02.000\n<v B>Hi!\n\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_audio_windows() {
+        // 2.5 seconds of 4Hz mono audio, received in uneven chunks
+        let audio = futures::stream::iter(vec![vec![0; 6], vec![0; 10], vec![0; 4]]);
+        let windows = audio_windows(Box::pin(audio), 4, 1, Duration::from_secs(1))
+            .collect::<Vec<_>>()
+            .await;
+
+        let times = windows
+            .iter()
+            .map(|window| (window.start, window.end, window.wav.len() - 44))
+            .collect::<Vec<_>>();
+        assert_eq!(times, vec![(0.0, 1.0, 8), (1.0, 2.0, 8), (2.0, 2.5, 4)]);
+    }
+}
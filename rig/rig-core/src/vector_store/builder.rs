@@ -1,7 +1,10 @@
 use serde::Serialize;
 use std::collections::HashMap;
 
-use crate::{OneOrMany, embeddings::Embedding};
+use crate::{
+    OneOrMany,
+    embeddings::{Embedding, Quantization},
+};
 
 use super::{IndexStrategy, in_memory_store::InMemoryVectorStore};
 
@@ -15,6 +18,12 @@ where
 
     /// Index strategy for the vector store.
     index_strategy: IndexStrategy,
+
+    /// Quantization applied to the stored embeddings.
+    quantization: Quantization,
+
+    /// Oversampling factor used when rescoring quantized candidates.
+    rescore: Option<usize>,
 }
 
 impl<D> Default for InMemoryVectorStoreBuilder<D>
@@ -36,6 +45,8 @@ where
         Self {
             embeddings: HashMap::new(),
             index_strategy: IndexStrategy::default(),
+            quantization: Quantization::default(),
+            rescore: None,
         }
     }
 
@@ -58,6 +69,33 @@ where
         self
     }
 
+    /// Set the quantization applied to the stored embeddings.
+    /// Unless [InMemoryVectorStoreBuilder::rescore] is also set, the full precision vectors
+    /// are dropped once quantized and searches are scored on the quantized vectors only.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use rig::{embeddings::Quantization, vector_store::InMemoryVectorStoreBuilder};
+    ///
+    /// let store = InMemoryVectorStoreBuilder::<String>::new()
+    ///     .quantization(Quantization::Int8)
+    ///     .documents(documents)
+    ///     .build();
+    /// ```
+    pub fn quantization(mut self, quantization: Quantization) -> Self {
+        self.quantization = quantization;
+        self
+    }
+
+    /// Keep the full precision vectors alongside the quantized ones and use them to rescore
+    /// the top `n * oversampling` quantized candidates of every search.
+    /// Has no effect unless a [Quantization] is set.
+    pub fn rescore(mut self, oversampling: usize) -> Self {
+        self.rescore = Some(oversampling);
+        self
+    }
+
     /// Add documents with auto-generated IDs.
     /// IDs will have the form `"doc{n}"` where `n` is the index.
     pub fn documents(
@@ -101,6 +139,11 @@ where
 
     /// Build the [InMemoryVectorStore] with the configured settings.
     pub fn build(self) -> InMemoryVectorStore<D> {
-        InMemoryVectorStore::from_builder(self.embeddings, self.index_strategy)
+        InMemoryVectorStore::from_builder(
+            self.embeddings,
+            self.index_strategy,
+            self.quantization,
+            self.rescore,
+        )
     }
 }
@@ -0,0 +1,565 @@
+use fastrand::Rng;
+use ordered_float::OrderedFloat;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+};
+
+/// Hierarchical Navigable Small World (HNSW) graph for approximate nearest neighbour search.
+/// Vectors are inserted into a hierarchy of proximity graphs, where upper layers hold
+/// exponentially fewer nodes and act as express lanes towards the query's neighbourhood.
+/// See <https://arxiv.org/abs/1603.09320> for details on how HNSW works.
+///
+/// Each node is the embedding of a document: node `(id, n)` is the `n`-th embedding of the
+/// document `id`, and a document may own several nodes. The index only stores the graph, the
+/// vectors are scored through [HNSWVectors] so that they are not duplicated (e.g. the quantized
+/// embeddings of an [InMemoryVectorStore](super::in_memory_store::InMemoryVectorStore)).
+#[derive(Clone, Default)]
+pub struct HNSWIndex {
+    /// Maximum number of neighbours per node on the upper layers.
+    m: usize,
+    /// Maximum number of neighbours per node on the bottom layer.
+    m_max0: usize,
+    /// Size of the dynamic candidate list used while inserting.
+    ef_construction: usize,
+    /// Size of the dynamic candidate list used while searching.
+    ef_search: usize,
+    /// Normalization factor for the random level generation.
+    level_mult: f64,
+    /// Node arena. Deleted nodes leave a `None` slot that is reused by later inserts.
+    nodes: Vec<Option<Node>>,
+    free_slots: Vec<usize>,
+    entry_point: Option<usize>,
+    max_level: usize,
+    /// Top level -> node slots, to promote a new entry point when the current one is removed
+    levels: BTreeMap<usize, HashSet<usize>>,
+    /// Document ID -> node slots
+    ids: HashMap<String, Vec<usize>>,
+    rng: Rng,
+}
+
+#[derive(Clone)]
+struct Node {
+    id: String,
+    /// Index of the embedding among the embeddings of the document.
+    embedding: usize,
+    /// Neighbour slots for each layer the node belongs to.
+    neighbours: Vec<Vec<usize>>,
+    /// Slots of the nodes linking to this node, for each layer the node belongs to.
+    incoming: Vec<HashSet<usize>>,
+}
+
+/// The vectors of the nodes of an [HNSWIndex], owned by the caller.
+pub trait HNSWVectors {
+    /// Cosine distance between the `a.1`-th embedding of document `a.0` and the `b.1`-th
+    /// embedding of document `b.0`.
+    fn distance(&self, a: (&str, usize), b: (&str, usize)) -> f32;
+}
+
+impl HNSWIndex {
+    /// Create a new HNSWIndex.
+    pub fn new(m: usize, ef_construction: usize, ef_search: usize) -> Self {
+        let m = m.max(2);
+
+        Self {
+            m,
+            m_max0: m * 2,
+            ef_construction: ef_construction.max(m),
+            ef_search: ef_search.max(1),
+            level_mult: 1.0 / (m as f64).ln(),
+            nodes: Vec::new(),
+            free_slots: Vec::new(),
+            entry_point: None,
+            max_level: 0,
+            levels: BTreeMap::new(),
+            ids: HashMap::new(),
+            rng: Rng::new(),
+        }
+    }
+
+    /// Number of vectors in the index.
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free_slots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Insert the `embedding`-th embedding of a document ID, scored with `vectors`.
+    pub fn insert(&mut self, id: String, embedding: usize, vectors: &impl HNSWVectors) {
+        let level = self.random_level();
+
+        let slot = match self.free_slots.pop() {
+            Some(slot) => slot,
+            None => {
+                self.nodes.push(None);
+                self.nodes.len() - 1
+            }
+        };
+
+        self.nodes[slot] = Some(Node {
+            id: id.clone(),
+            embedding,
+            neighbours: vec![Vec::new(); level + 1],
+            incoming: vec![HashSet::new(); level + 1],
+        });
+        self.ids.entry(id.clone()).or_default().push(slot);
+        self.levels.entry(level).or_default().insert(slot);
+
+        let Some(mut entry_point) = self.entry_point else {
+            self.entry_point = Some(slot);
+            self.max_level = level;
+            return;
+        };
+
+        let distance = |index: &Self, other: usize| {
+            OrderedFloat(vectors.distance((&id, embedding), index.key(other)))
+        };
+
+        // Greedily descend the layers above the new node's level
+        for layer in (level + 1..=self.max_level).rev() {
+            entry_point = self.greedy_closest(&distance, entry_point, layer);
+        }
+
+        let mut entry_points = vec![entry_point];
+        for layer in (0..=level.min(self.max_level)).rev() {
+            let candidates =
+                self.search_layer(&distance, &entry_points, self.ef_construction, layer);
+            let neighbours = self.select_neighbours(&candidates, self.m, vectors);
+
+            self.set_neighbours(slot, layer, neighbours.clone());
+
+            // Add the reverse links, shrinking neighbour lists that grew too large
+            let max_neighbours = self.max_neighbours(layer);
+            for neighbour in neighbours {
+                let mut links = self.node(neighbour).neighbours[layer].clone();
+                links.push(slot);
+                self.set_neighbours(neighbour, layer, links);
+
+                if self.node(neighbour).neighbours[layer].len() > max_neighbours {
+                    self.prune(neighbour, layer, max_neighbours, vectors);
+                }
+            }
+
+            entry_points = candidates.into_iter().map(|(_, slot)| slot).collect();
+        }
+
+        if level > self.max_level {
+            self.entry_point = Some(slot);
+            self.max_level = level;
+        }
+    }
+
+    /// Remove all embeddings of a document ID from the index. Only the nodes linked to the
+    /// removed ones are visited.
+    pub fn remove(&mut self, id: &str, vectors: &impl HNSWVectors) {
+        let Some(slots) = self.ids.remove(id) else {
+            return;
+        };
+
+        for slot in slots {
+            self.remove_node(slot, vectors);
+        }
+    }
+
+    /// Query for candidate document IDs, ordered from most to least similar. `distance` is the
+    /// cosine distance between the query and the `n`-th embedding of a document.
+    /// At least `n` nodes are explored, even if `ef_search` is smaller.
+    pub fn query(&self, distance: impl Fn(&str, usize) -> f32, n: usize) -> Vec<String> {
+        let Some(mut entry_point) = self.entry_point else {
+            return Vec::new();
+        };
+
+        let distance = |index: &Self, slot: usize| {
+            let (id, embedding) = index.key(slot);
+            OrderedFloat(distance(id, embedding))
+        };
+
+        for layer in (1..=self.max_level).rev() {
+            entry_point = self.greedy_closest(&distance, entry_point, layer);
+        }
+
+        let mut candidates = self.search_layer(&distance, &[entry_point], self.ef_search.max(n), 0);
+        candidates.sort();
+
+        let mut seen = HashSet::new();
+        candidates
+            .into_iter()
+            .map(|(_, slot)| &self.node(slot).id)
+            .filter(|id| seen.insert(*id))
+            .cloned()
+            .collect()
+    }
+
+    /// Clear the index
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free_slots.clear();
+        self.levels.clear();
+        self.ids.clear();
+        self.entry_point = None;
+        self.max_level = 0;
+    }
+
+    fn node(&self, slot: usize) -> &Node {
+        self.nodes[slot]
+            .as_ref()
+            .expect("HNSW graph should only link live nodes")
+    }
+
+    fn node_mut(&mut self, slot: usize) -> &mut Node {
+        self.nodes[slot]
+            .as_mut()
+            .expect("HNSW graph should only link live nodes")
+    }
+
+    fn key(&self, slot: usize) -> (&str, usize) {
+        let node = self.node(slot);
+        (&node.id, node.embedding)
+    }
+
+    fn max_neighbours(&self, layer: usize) -> usize {
+        if layer == 0 { self.m_max0 } else { self.m }
+    }
+
+    fn random_level(&mut self) -> usize {
+        // Sample from (0, 1] so the logarithm stays finite
+        let uniform = 1.0 - self.rng.f64();
+        (-uniform.ln() * self.level_mult).floor() as usize
+    }
+
+    /// Replace the neighbours of a node on a layer, keeping the back-links of the old and new
+    /// neighbours in sync.
+    fn set_neighbours(&mut self, slot: usize, layer: usize, neighbours: Vec<usize>) {
+        let old = std::mem::replace(
+            &mut self.node_mut(slot).neighbours[layer],
+            neighbours.clone(),
+        );
+
+        for neighbour in old.iter().filter(|n| !neighbours.contains(n)) {
+            if let Some(node) = self.nodes[*neighbour].as_mut() {
+                node.incoming[layer].remove(&slot);
+            }
+        }
+
+        for neighbour in neighbours.iter().filter(|n| !old.contains(n)) {
+            self.node_mut(*neighbour).incoming[layer].insert(slot);
+        }
+    }
+
+    /// Walk a single layer towards the node closest to the query.
+    fn greedy_closest(
+        &self,
+        distance: &impl Fn(&Self, usize) -> OrderedFloat<f32>,
+        mut current: usize,
+        layer: usize,
+    ) -> usize {
+        let mut current_distance = distance(self, current);
+
+        loop {
+            let mut changed = false;
+
+            for &neighbour in &self.node(current).neighbours[layer] {
+                let distance = distance(self, neighbour);
+                if distance < current_distance {
+                    current = neighbour;
+                    current_distance = distance;
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return current;
+            }
+        }
+    }
+
+    /// Beam search over a single layer, returning up to `ef` (distance, slot) pairs.
+    fn search_layer(
+        &self,
+        distance: &impl Fn(&Self, usize) -> OrderedFloat<f32>,
+        entry_points: &[usize],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<(OrderedFloat<f32>, usize)> {
+        let mut visited = entry_points.iter().copied().collect::<HashSet<_>>();
+        // Min-heap of candidates to expand
+        let mut candidates = BinaryHeap::new();
+        // Max-heap of the best results found so far
+        let mut results = BinaryHeap::new();
+
+        for &slot in entry_points {
+            let distance = distance(self, slot);
+            candidates.push(Reverse((distance, slot)));
+            results.push((distance, slot));
+        }
+
+        while let Some(Reverse((current, slot))) = candidates.pop() {
+            if let Some(&(furthest, _)) = results.peek()
+                && current > furthest
+                && results.len() >= ef
+            {
+                break;
+            }
+
+            let Some(neighbours) = self.node(slot).neighbours.get(layer) else {
+                continue;
+            };
+
+            for &neighbour in neighbours {
+                if !visited.insert(neighbour) {
+                    continue;
+                }
+
+                let distance = distance(self, neighbour);
+                let furthest = results.peek().map(|(distance, _)| *distance);
+
+                if results.len() < ef || furthest.is_some_and(|furthest| distance < furthest) {
+                    candidates.push(Reverse((distance, neighbour)));
+                    results.push((distance, neighbour));
+
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_vec()
+    }
+
+    /// Select up to `m` neighbours among candidates scored against a base vector, using the
+    /// heuristic from the HNSW paper: a candidate is preferred if it is closer to the base
+    /// vector than to any already selected neighbour.
+    /// Remaining slots are filled with the closest discarded candidates.
+    fn select_neighbours(
+        &self,
+        candidates: &[(OrderedFloat<f32>, usize)],
+        m: usize,
+        vectors: &impl HNSWVectors,
+    ) -> Vec<usize> {
+        let mut candidates = candidates.to_vec();
+        candidates.sort();
+
+        let mut selected: Vec<usize> = Vec::with_capacity(m);
+        let mut discarded = Vec::new();
+
+        for (distance, slot) in candidates {
+            if selected.len() >= m {
+                break;
+            }
+
+            let dominated = selected.iter().any(|&other| {
+                OrderedFloat(vectors.distance(self.key(slot), self.key(other))) < distance
+            });
+
+            if dominated {
+                discarded.push(slot);
+            } else {
+                selected.push(slot);
+            }
+        }
+
+        let missing = m.saturating_sub(selected.len());
+        selected.extend(discarded.into_iter().take(missing));
+
+        selected
+    }
+
+    /// Shrink the neighbour list of a node on a layer to `max_neighbours`.
+    fn prune(
+        &mut self,
+        slot: usize,
+        layer: usize,
+        max_neighbours: usize,
+        vectors: &impl HNSWVectors,
+    ) {
+        let base = self.key(slot);
+        let candidates = self.node(slot).neighbours[layer]
+            .iter()
+            .filter(|neighbour| **neighbour != slot)
+            .map(|&neighbour| {
+                (
+                    OrderedFloat(vectors.distance(base, self.key(neighbour))),
+                    neighbour,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let neighbours = self.select_neighbours(&candidates, max_neighbours, vectors);
+        self.set_neighbours(slot, layer, neighbours);
+    }
+
+    fn remove_node(&mut self, slot: usize, vectors: &impl HNSWVectors) {
+        let Some(removed) = self.nodes[slot].take() else {
+            return;
+        };
+        self.free_slots.push(slot);
+
+        let level = removed.neighbours.len() - 1;
+        if let Some(slots) = self.levels.get_mut(&level) {
+            slots.remove(&slot);
+            if slots.is_empty() {
+                self.levels.remove(&level);
+            }
+        }
+
+        for (layer, neighbours) in removed.neighbours.iter().enumerate() {
+            for &neighbour in neighbours {
+                if let Some(node) = self.nodes[neighbour].as_mut() {
+                    node.incoming[layer].remove(&slot);
+                }
+            }
+
+            // Reconnect the nodes that pointed to the removed node through its own neighbours
+            for &other in &removed.incoming[layer] {
+                if self.nodes[other].is_none() {
+                    continue;
+                }
+
+                let mut links = self.node(other).neighbours[layer].clone();
+                links.retain(|n| *n != slot);
+                for &replacement in neighbours {
+                    if replacement != other
+                        && self.nodes[replacement].is_some()
+                        && !links.contains(&replacement)
+                    {
+                        links.push(replacement);
+                    }
+                }
+                self.set_neighbours(other, layer, links);
+
+                let max_neighbours = self.max_neighbours(layer);
+                if self.node(other).neighbours[layer].len() > max_neighbours {
+                    self.prune(other, layer, max_neighbours, vectors);
+                }
+            }
+        }
+
+        if self.entry_point == Some(slot) {
+            // Promote a remaining node with the highest level
+            match self.levels.iter().next_back() {
+                Some((level, slots)) => {
+                    self.entry_point = slots.iter().next().copied();
+                    self.max_level = *level;
+                }
+                None => {
+                    self.entry_point = None;
+                    self.max_level = 0;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use fastrand::Rng;
+    use std::collections::HashMap;
+
+    use super::{HNSWIndex, HNSWVectors};
+
+    struct Vectors(HashMap<String, Vec<f64>>);
+
+    impl Vectors {
+        fn query(&self, query: &[f64]) -> impl Fn(&str, usize) -> f32 {
+            move |id, _| cosine_distance(query, &self.0[id])
+        }
+    }
+
+    impl HNSWVectors for Vectors {
+        fn distance(&self, a: (&str, usize), b: (&str, usize)) -> f32 {
+            cosine_distance(&self.0[a.0], &self.0[b.0])
+        }
+    }
+
+    fn cosine_distance(a: &[f64], b: &[f64]) -> f32 {
+        let dot = a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+        let norm = |v: &[f64]| v.iter().map(|x| x * x).sum::<f64>().sqrt();
+        (1.0 - dot / (norm(a) * norm(b))) as f32
+    }
+
+    fn random_vectors(count: usize, dims: usize) -> Vectors {
+        let mut rng = Rng::with_seed(42);
+        Vectors(
+            (0..count)
+                .map(|i| {
+                    (
+                        format!("doc{i}"),
+                        (0..dims).map(|_| rng.f64() * 2.0 - 1.0).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_query_finds_exact_match() {
+        let vectors = random_vectors(500, 16);
+        let mut index = HNSWIndex::new(16, 100, 50);
+
+        for i in 0..500 {
+            index.insert(format!("doc{i}"), 0, &vectors);
+        }
+
+        assert_eq!(index.len(), 500);
+
+        for i in [0, 123, 499] {
+            let query = &vectors.0[&format!("doc{i}")];
+            assert_eq!(index.query(vectors.query(query), 1)[0], format!("doc{i}"));
+        }
+    }
+
+    #[test]
+    fn test_remove() {
+        let vectors = random_vectors(200, 8);
+        let mut index = HNSWIndex::new(8, 64, 32);
+
+        for i in 0..200 {
+            index.insert(format!("doc{i}"), 0, &vectors);
+        }
+
+        for i in (0..200).step_by(2) {
+            index.remove(&format!("doc{i}"), &vectors);
+        }
+
+        assert_eq!(index.len(), 100);
+
+        for i in (1..200).step_by(2) {
+            let query = &vectors.0[&format!("doc{i}")];
+            let results = index.query(vectors.query(query), 10);
+            assert!(results.iter().all(|id| id != "doc0"));
+            assert_eq!(results[0], format!("doc{i}"));
+        }
+
+        // No live node links to a removed one
+        for node in index.nodes.iter().flatten() {
+            for neighbours in &node.neighbours {
+                assert!(neighbours.iter().all(|n| index.nodes[*n].is_some()));
+            }
+        }
+
+        // Slots of removed nodes are reused
+        index.insert("doc0".to_string(), 0, &vectors);
+        assert_eq!(index.len(), 101);
+        assert_eq!(index.query(vectors.query(&vectors.0["doc0"]), 1)[0], "doc0");
+    }
+
+    #[test]
+    fn test_remove_all() {
+        let vectors = Vectors(HashMap::from([
+            ("doc0".to_string(), vec![1.0, 0.0]),
+            ("doc1".to_string(), vec![0.0, 1.0]),
+        ]));
+        let mut index = HNSWIndex::new(4, 16, 16);
+        index.insert("doc0".to_string(), 0, &vectors);
+        index.insert("doc1".to_string(), 0, &vectors);
+
+        index.remove("doc0", &vectors);
+        index.remove("doc1", &vectors);
+
+        assert!(index.is_empty());
+        assert!(index.query(vectors.query(&[1.0, 0.0]), 1).is_empty());
+    }
+}
@@ -10,11 +10,16 @@ use serde::{Deserialize, Serialize};
 use super::{IndexStrategy, VectorStoreError, VectorStoreIndex, request::VectorSearchRequest};
 use crate::{
     OneOrMany,
-    embeddings::{Embedding, EmbeddingModel, distance::VectorDistance},
+    embeddings::{
+        Embedding, EmbeddingModel, Quantization, QuantizedVector, distance::VectorDistance,
+    },
     vector_store::request::Filter,
 };
 
-use super::lsh::LSHIndex;
+use super::{
+    hnsw::{HNSWIndex, HNSWVectors},
+    lsh::LSHIndex,
+};
 
 pub use super::builder::InMemoryVectorStoreBuilder;
 
@@ -30,6 +35,54 @@ pub struct InMemoryVectorStore<D: Serialize> {
     index_strategy: IndexStrategy,
 
     lsh_index: Option<LSHIndex>,
+
+    hnsw_index: Option<HNSWIndex>,
+
+    /// Quantization applied to the stored embeddings.
+    quantization: Quantization,
+
+    /// Oversampling factor used when rescoring quantized candidates with full precision vectors.
+    /// When `None`, the full precision vectors are dropped once quantized.
+    rescore: Option<usize>,
+
+    /// Quantized embeddings keyed by document id, in the same order as the stored embeddings.
+    quantized: HashMap<String, Vec<QuantizedVector>>,
+
+    /// Counter for the automatically generated `"doc{n}"` ids. Only ever increases, so ids of
+    /// removed documents are not handed out again.
+    next_id: usize,
+}
+
+/// The stored vectors, as scored by the HNSW index: the quantized embeddings of a document when
+/// the store is quantized, its full precision embeddings otherwise.
+struct StoreVectors<'a, D> {
+    embeddings: &'a HashMap<String, (D, OneOrMany<Embedding>)>,
+    quantized: &'a HashMap<String, Vec<QuantizedVector>>,
+}
+
+impl<D> HNSWVectors for StoreVectors<'_, D> {
+    fn distance(&self, a: (&str, usize), b: (&str, usize)) -> f32 {
+        let quantized = |(id, index): (&str, usize)| {
+            self.quantized
+                .get(id)
+                .and_then(|vectors| vectors.get(index))
+        };
+        let embedding = |(id, index): (&str, usize)| {
+            self.embeddings
+                .get(id)
+                .and_then(|(_, embeddings)| embeddings.iter().nth(index))
+        };
+
+        let similarity = match (quantized(a), quantized(b)) {
+            (Some(a), Some(b)) => a.cosine_similarity(b, false),
+            _ => match (embedding(a), embedding(b)) {
+                (Some(a), Some(b)) => a.cosine_similarity(b, false),
+                _ => -1.0,
+            },
+        };
+
+        (1.0 - similarity) as f32
+    }
 }
 
 impl<D: Serialize + Eq> InMemoryVectorStore<D> {
@@ -53,14 +106,22 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     pub(super) fn from_builder(
         embeddings: HashMap<String, (D, OneOrMany<Embedding>)>,
         index_strategy: IndexStrategy,
+        quantization: Quantization,
+        rescore: Option<usize>,
     ) -> Self {
+        let next_id = embeddings.len();
         let mut vector_store = Self {
             embeddings,
             index_strategy: index_strategy.clone(),
             lsh_index: None,
+            hnsw_index: None,
+            quantization,
+            rescore,
+            quantized: HashMap::new(),
+            next_id,
         };
 
-        // Initialize LSH index if needed
+        // The LSH index hashes the full precision vectors, so build it before quantizing
         if let IndexStrategy::LSH {
             num_tables,
             num_hyperplanes,
@@ -69,6 +130,21 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
             vector_store.initialize_lsh_index(num_tables, num_hyperplanes);
         }
 
+        let ids = vector_store.embeddings.keys().cloned().collect::<Vec<_>>();
+        for id in ids {
+            vector_store.quantize_document(&id);
+        }
+
+        // The HNSW index scores the stored vectors, quantized ones included
+        if let IndexStrategy::HNSW {
+            m,
+            ef_construction,
+            ef_search,
+        } = index_strategy
+        {
+            vector_store.initialize_hnsw_index(m, ef_construction, ef_search);
+        }
+
         vector_store
     }
 
@@ -85,11 +161,17 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
             .for_each(|(i, (doc, embeddings))| {
                 store.insert(format!("doc{i}"), (doc, embeddings));
             });
+        let next_id = store.len();
 
         Self {
             embeddings: store,
             index_strategy: IndexStrategy::default(),
             lsh_index: None,
+            hnsw_index: None,
+            quantization: Quantization::default(),
+            rescore: None,
+            quantized: HashMap::new(),
+            next_id,
         }
     }
 
@@ -103,11 +185,17 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         documents.into_iter().for_each(|(i, doc, embeddings)| {
             store.insert(i.to_string(), (doc, embeddings));
         });
+        let next_id = store.len();
 
         Self {
             embeddings: store,
             index_strategy: IndexStrategy::default(),
             lsh_index: None,
+            hnsw_index: None,
+            quantization: Quantization::default(),
+            rescore: None,
+            quantized: HashMap::new(),
+            next_id,
         }
     }
 
@@ -123,23 +211,87 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         documents.into_iter().for_each(|(doc, embeddings)| {
             store.insert(f(&doc), (doc, embeddings));
         });
+        let next_id = store.len();
 
         Self {
             embeddings: store,
             index_strategy: IndexStrategy::default(),
             lsh_index: None,
+            hnsw_index: None,
+            quantization: Quantization::default(),
+            rescore: None,
+            quantized: HashMap::new(),
+            next_id,
         }
     }
 
     /// Implement vector search on [InMemoryVectorStore].
     /// To be used by implementations of [VectorStoreIndex::top_n] and [VectorStoreIndex::top_n_ids] methods.
     fn vector_search(&self, prompt_embedding: &Embedding, n: usize) -> EmbeddingRanking<'_, D> {
-        match &self.index_strategy {
-            IndexStrategy::BruteForce => self.vector_search_brute_force(prompt_embedding, n),
+        let query = self.quantization.quantize(&prompt_embedding.vec);
+
+        // Oversample quantized candidates so rescoring can recover from quantization error
+        let rescore = query.is_some() && self.rescore.is_some();
+        let candidates = match self.rescore {
+            Some(oversampling) if rescore => n.saturating_mul(oversampling.max(1)),
+            _ => n,
+        };
+
+        let docs = match &self.index_strategy {
+            IndexStrategy::BruteForce => {
+                self.vector_search_brute_force(prompt_embedding, query.as_ref(), candidates)
+            }
             IndexStrategy::LSH {
                 num_tables,
                 num_hyperplanes,
-            } => self.vector_search_lsh(prompt_embedding, n, *num_tables, *num_hyperplanes),
+            } => self.vector_search_lsh(
+                prompt_embedding,
+                query.as_ref(),
+                candidates,
+                *num_tables,
+                *num_hyperplanes,
+            ),
+            IndexStrategy::HNSW { .. } => {
+                self.vector_search_hnsw(prompt_embedding, query.as_ref(), candidates)
+            }
+        };
+
+        if rescore {
+            self.rescore_candidates(docs, prompt_embedding, n)
+        } else {
+            docs
+        }
+    }
+
+    /// Get the best scoring embedding of a document given the prompt.
+    /// Scores the quantized embeddings of the document when a quantized query is given.
+    fn best_match<'a>(
+        &'a self,
+        id: &str,
+        embeddings: &'a OneOrMany<Embedding>,
+        prompt_embedding: &Embedding,
+        query: Option<&QuantizedVector>,
+    ) -> Option<(OrderedFloat<f64>, &'a String)> {
+        match (query, self.quantized.get(id)) {
+            (Some(query), Some(quantized)) => quantized
+                .iter()
+                .zip(embeddings.iter())
+                .map(|(vector, embedding)| {
+                    (
+                        OrderedFloat(vector.cosine_similarity(query, false)),
+                        &embedding.document,
+                    )
+                })
+                .max_by(|a, b| a.0.cmp(&b.0)),
+            _ => embeddings
+                .iter()
+                .map(|embedding| {
+                    (
+                        OrderedFloat(embedding.cosine_similarity(prompt_embedding, false)),
+                        &embedding.document,
+                    )
+                })
+                .max_by(|a, b| a.0.cmp(&b.0)),
         }
     }
 
@@ -147,6 +299,7 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     fn vector_search_brute_force(
         &self,
         prompt_embedding: &Embedding,
+        query: Option<&QuantizedVector>,
         n: usize,
     ) -> EmbeddingRanking<'_, D> {
         // Sort documents by best embedding distance
@@ -154,15 +307,8 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
 
         for (id, (doc, embeddings)) in self.embeddings.iter() {
             // Get the best context for the document given the prompt
-            if let Some((distance, embed_doc)) = embeddings
-                .iter()
-                .map(|embedding| {
-                    (
-                        OrderedFloat(embedding.cosine_similarity(prompt_embedding, false)),
-                        &embedding.document,
-                    )
-                })
-                .max_by(|a, b| a.0.cmp(&b.0))
+            if let Some((distance, embed_doc)) =
+                self.best_match(id, embeddings, prompt_embedding, query)
             {
                 docs.push(Reverse(RankingItem(distance, id, doc, embed_doc)));
             };
@@ -189,6 +335,7 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     fn vector_search_lsh(
         &self,
         prompt_embedding: &Embedding,
+        query: Option<&QuantizedVector>,
         n: usize,
         _num_tables: usize,
         _num_hyperplanes: usize,
@@ -196,7 +343,7 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         // If we don't have an LSH index yet, fall back to brute force
         if self.lsh_index.is_none() {
             tracing::warn!("LSH index not initialized, falling back to brute force search");
-            return self.vector_search_brute_force(prompt_embedding, n);
+            return self.vector_search_brute_force(prompt_embedding, query, n);
         }
 
         let lsh_index = self.lsh_index.as_ref().unwrap();
@@ -211,15 +358,8 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         for candidate_id in candidates {
             if let Some((doc, embeddings)) = self.embeddings.get(&candidate_id) {
                 // Get the best context for the document given the prompt
-                if let Some((distance, embed_doc)) = embeddings
-                    .iter()
-                    .map(|embedding| {
-                        (
-                            OrderedFloat(embedding.cosine_similarity(prompt_embedding, false)),
-                            &embedding.document,
-                        )
-                    })
-                    .max_by(|a, b| a.0.cmp(&b.0))
+                if let Some((distance, embed_doc)) =
+                    self.best_match(&candidate_id, embeddings, prompt_embedding, query)
                 {
                     scored_docs.push((distance, candidate_id, doc, embed_doc));
                 }
@@ -232,7 +372,7 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
 
         // Convert to BinaryHeap format using the original HashMap keys
         for (distance, candidate_id, doc, embed_doc) in scored_docs {
-            if let Some((id_ref, _)) = self.embeddings.iter().find(|(k, _)| **k == candidate_id) {
+            if let Some((id_ref, _)) = self.embeddings.get_key_value(&candidate_id) {
                 docs.push(Reverse(RankingItem(distance, id_ref, doc, embed_doc)));
             }
         }
@@ -249,6 +389,136 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         docs
     }
 
+    /// HNSW-based vector search - uses the HNSW graph to find candidates then computes exact distances
+    fn vector_search_hnsw(
+        &self,
+        prompt_embedding: &Embedding,
+        query: Option<&QuantizedVector>,
+        n: usize,
+    ) -> EmbeddingRanking<'_, D> {
+        // If we don't have an HNSW index yet, fall back to brute force
+        let Some(hnsw_index) = self.hnsw_index.as_ref() else {
+            tracing::warn!("HNSW index not initialized, falling back to brute force search");
+            return self.vector_search_brute_force(prompt_embedding, query, n);
+        };
+
+        let mut docs = BinaryHeap::new();
+
+        // Score the nodes with the same vectors as the ranking: quantized ones when available
+        let distance = |id: &str, index: usize| {
+            let quantized = query.zip(
+                self.quantized
+                    .get(id)
+                    .and_then(|vectors| vectors.get(index)),
+            );
+            let similarity = match quantized {
+                Some((query, vector)) => vector.cosine_similarity(query, false),
+                None => self
+                    .embeddings
+                    .get(id)
+                    .and_then(|(_, embeddings)| embeddings.iter().nth(index))
+                    .map_or(-1.0, |embedding| {
+                        embedding.cosine_similarity(prompt_embedding, false)
+                    }),
+            };
+            (1.0 - similarity) as f32
+        };
+
+        for candidate_id in hnsw_index.query(distance, n) {
+            if let Some((id, (doc, embeddings))) = self.embeddings.get_key_value(&candidate_id)
+                && let Some((distance, embed_doc)) =
+                    self.best_match(id, embeddings, prompt_embedding, query)
+            {
+                docs.push(Reverse(RankingItem(distance, id, doc, embed_doc)));
+            }
+
+            if docs.len() > n {
+                docs.pop();
+            }
+        }
+
+        // Log selected tools with their distances
+        tracing::info!(target: "rig",
+            "Selected documents (HNSW): {}",
+            docs.iter()
+                .map(|Reverse(RankingItem(distance, id, _, _))| format!("{id} ({distance})"))
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+
+        docs
+    }
+
+    /// Rescore quantized candidates with the full precision embeddings and keep the top n.
+    fn rescore_candidates<'a>(
+        &'a self,
+        candidates: EmbeddingRanking<'a, D>,
+        prompt_embedding: &Embedding,
+        n: usize,
+    ) -> EmbeddingRanking<'a, D> {
+        let mut docs = BinaryHeap::new();
+
+        for Reverse(RankingItem(_, id, doc, _)) in candidates {
+            if let Some((distance, embed_doc)) = self
+                .embeddings
+                .get(id)
+                .and_then(|(_, embeddings)| self.best_match(id, embeddings, prompt_embedding, None))
+            {
+                docs.push(Reverse(RankingItem(distance, id, doc, embed_doc)));
+            }
+
+            if docs.len() > n {
+                docs.pop();
+            }
+        }
+
+        docs
+    }
+
+    /// Quantize the embeddings of a document if a quantization is configured.
+    /// The full precision vectors are dropped unless they are kept for rescoring.
+    fn quantize_document(&mut self, id: &str) {
+        if self.quantization == Quantization::None {
+            return;
+        }
+
+        let Some((_, embeddings)) = self.embeddings.get_mut(id) else {
+            return;
+        };
+
+        let quantized = embeddings
+            .iter()
+            .filter_map(|embedding| self.quantization.quantize(&embedding.vec))
+            .collect();
+
+        if self.rescore.is_none() {
+            for embedding in embeddings.iter_mut() {
+                embedding.vec = Vec::new();
+            }
+        }
+
+        self.quantized.insert(id.to_string(), quantized);
+    }
+
+    /// Initialize HNSW index from existing embeddings
+    fn initialize_hnsw_index(&mut self, m: usize, ef_construction: usize, ef_search: usize) {
+        let mut hnsw_index = HNSWIndex::new(m, ef_construction, ef_search);
+
+        let vectors = StoreVectors {
+            embeddings: &self.embeddings,
+            quantized: &self.quantized,
+        };
+
+        // Insert all existing embeddings into the HNSW index
+        for (id, (_, embeddings)) in self.embeddings.iter() {
+            for index in 0..embeddings.len() {
+                hnsw_index.insert(id.clone(), index, &vectors);
+            }
+        }
+
+        self.hnsw_index = Some(hnsw_index);
+    }
+
     /// Initialize LSH index from existing embeddings
     fn initialize_lsh_index(&mut self, num_tables: usize, num_hyperplanes: usize) {
         if self.embeddings.is_empty() {
@@ -280,29 +550,55 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         self.lsh_index = Some(lsh_index);
     }
 
+    /// Insert a document into the store and keep the indexes and quantized embeddings in sync.
+    /// Replaces any document previously stored under the same id.
+    fn insert_document(&mut self, id: String, doc: D, embeddings: OneOrMany<Embedding>) {
+        if self.embeddings.contains_key(&id) {
+            self.remove_document(&id);
+        }
+
+        // Update LSH index if it exists
+        if let Some(ref mut lsh_index) = self.lsh_index {
+            for embedding in embeddings.iter() {
+                lsh_index.insert(id.clone(), &embedding.vec);
+            }
+        }
+
+        let count = embeddings.len();
+        self.embeddings.insert(id.clone(), (doc, embeddings));
+        self.quantize_document(&id);
+
+        // Update HNSW index if it exists, once the vectors it scores are stored
+        if let Some(ref mut hnsw_index) = self.hnsw_index {
+            let vectors = StoreVectors {
+                embeddings: &self.embeddings,
+                quantized: &self.quantized,
+            };
+
+            for index in 0..count {
+                hnsw_index.insert(id.clone(), index, &vectors);
+            }
+        }
+    }
+
     /// Add documents and their corresponding embeddings to the store.
-    /// Ids are automatically generated have will have the form `"doc{n}"` where `n`
-    /// is the index of the document.
+    /// Ids are automatically generated have will have the form `"doc{n}"`, where `n` is a
+    /// counter that skips ids already in the store.
     pub fn add_documents(
         &mut self,
         documents: impl IntoIterator<Item = (D, OneOrMany<Embedding>)>,
     ) {
-        let current_index = self.embeddings.len();
-        documents
-            .into_iter()
-            .enumerate()
-            .for_each(|(index, (doc, embeddings))| {
-                let id = format!("doc{}", index + current_index);
-                self.embeddings
-                    .insert(id.clone(), (doc, embeddings.clone()));
-
-                // Update LSH index if it exists
-                if let Some(ref mut lsh_index) = self.lsh_index {
-                    for embedding in embeddings.iter() {
-                        lsh_index.insert(id.clone(), &embedding.vec);
-                    }
+        for (doc, embeddings) in documents {
+            let id = loop {
+                let id = format!("doc{}", self.next_id);
+                self.next_id += 1;
+
+                if !self.embeddings.contains_key(&id) {
+                    break id;
                 }
-            });
+            };
+            self.insert_document(id, doc, embeddings);
+        }
     }
 
     /// Add documents and their corresponding embeddings to the store with ids.
@@ -311,16 +607,7 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
         documents: impl IntoIterator<Item = (impl ToString, D, OneOrMany<Embedding>)>,
     ) {
         documents.into_iter().for_each(|(id, doc, embeddings)| {
-            let id_str = id.to_string();
-            self.embeddings
-                .insert(id_str.clone(), (doc, embeddings.clone()));
-
-            // Update LSH index if it exists
-            if let Some(ref mut lsh_index) = self.lsh_index {
-                for embedding in embeddings.iter() {
-                    lsh_index.insert(id_str.clone(), &embedding.vec);
-                }
-            }
+            self.insert_document(id.to_string(), doc, embeddings);
         });
     }
 
@@ -333,16 +620,43 @@ impl<D: Serialize + Eq> InMemoryVectorStore<D> {
     ) {
         for (doc, embeddings) in documents {
             let id = f(&doc);
-            self.embeddings
-                .insert(id.clone(), (doc, embeddings.clone()));
+            self.insert_document(id, doc, embeddings);
+        }
+    }
 
-            // Update LSH index if it exists
-            if let Some(ref mut lsh_index) = self.lsh_index {
-                for embedding in embeddings.iter() {
-                    lsh_index.insert(id.clone(), &embedding.vec);
-                }
-            }
+    /// Remove a document and its embeddings from the store and its indexes.
+    /// Returns the removed document, if any.
+    pub fn remove_document(&mut self, id: &str) -> Option<(D, OneOrMany<Embedding>)> {
+        if !self.embeddings.contains_key(id) {
+            return None;
+        }
+
+        // Unlink from the HNSW graph first, since relinking scores the document's other nodes
+        if let Some(ref mut hnsw_index) = self.hnsw_index {
+            let vectors = StoreVectors {
+                embeddings: &self.embeddings,
+                quantized: &self.quantized,
+            };
+            hnsw_index.remove(id, &vectors);
         }
+
+        if let Some(ref mut lsh_index) = self.lsh_index {
+            lsh_index.remove(id);
+        }
+
+        self.quantized.remove(id);
+
+        self.embeddings.remove(id)
+    }
+
+    /// Get the ids of the `n` documents closest to an already computed embedding, using the
+    /// configured index strategy. Results are ordered from most to least similar.
+    pub fn top_n_ids_by_embedding(&self, embedding: &Embedding, n: usize) -> Vec<(f64, String)> {
+        self.vector_search(embedding, n)
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(RankingItem(distance, id, _, _))| (distance.0, id.clone()))
+            .collect()
     }
 
     /// Get the document by its id and deserialize it into the given type.
@@ -468,7 +782,11 @@ impl<M: EmbeddingModel + Sync, D: Serialize + Sync + Send + Eq> VectorStoreIndex
 mod tests {
     use std::cmp::Reverse;
 
-    use crate::{OneOrMany, embeddings::embedding::Embedding, vector_store::IndexStrategy};
+    use crate::{
+        OneOrMany,
+        embeddings::{Quantization, embedding::Embedding},
+        vector_store::IndexStrategy,
+    };
 
     use super::{InMemoryVectorStore, RankingItem};
 
@@ -725,4 +1043,236 @@ mod tests {
             )]
         )
     }
+
+    fn quantized_store(
+        quantization: Quantization,
+        rescore: Option<usize>,
+    ) -> InMemoryVectorStore<String> {
+        let builder = InMemoryVectorStore::builder()
+            .quantization(quantization)
+            .documents_with_ids(vec![
+                (
+                    "doc1",
+                    "glarb-garb".to_string(),
+                    OneOrMany::one(Embedding {
+                        document: "glarb-garb".to_string(),
+                        vec: vec![0.1, 0.1, 0.5],
+                    }),
+                ),
+                (
+                    "doc2",
+                    "marble-marble".to_string(),
+                    OneOrMany::one(Embedding {
+                        document: "marble-marble".to_string(),
+                        vec: vec![0.7, -0.3, 0.0],
+                    }),
+                ),
+                (
+                    "doc3",
+                    "flumb-flumb".to_string(),
+                    OneOrMany::one(Embedding {
+                        document: "flumb-flumb".to_string(),
+                        vec: vec![0.3, 0.7, 0.1],
+                    }),
+                ),
+            ]);
+
+        match rescore {
+            Some(oversampling) => builder.rescore(oversampling),
+            None => builder,
+        }
+        .build()
+    }
+
+    #[test]
+    fn test_quantized_search() {
+        for quantization in [Quantization::F32, Quantization::Int8] {
+            let vector_store = quantized_store(quantization, None);
+
+            // Full precision vectors are dropped when not rescoring
+            assert!(
+                vector_store
+                    .embeddings
+                    .values()
+                    .all(|(_, embeddings)| embeddings.iter().all(|e| e.vec.is_empty()))
+            );
+
+            let ranking = vector_store.vector_search(
+                &Embedding {
+                    document: "glarby-glarble".to_string(),
+                    vec: vec![0.0, 0.1, 0.6],
+                },
+                1,
+            );
+
+            let results = ranking
+                .into_iter()
+                .map(|Reverse(RankingItem(distance, id, _, _))| (distance.0, id.clone()))
+                .collect::<Vec<_>>();
+
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].1, "doc1");
+            assert!((results[0].0 - 0.9807965956109156).abs() < 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_binary_search_with_rescore() {
+        let vector_store = quantized_store(Quantization::Binary, Some(3));
+
+        let ranking = vector_store.vector_search(
+            &Embedding {
+                document: "glarby-glarble".to_string(),
+                vec: vec![0.0, 0.1, 0.6],
+            },
+            1,
+        );
+
+        // Rescoring returns the exact full precision similarity
+        assert_eq!(
+            ranking
+                .into_iter()
+                .map(|Reverse(RankingItem(distance, id, _, _))| (distance.0, id.clone()))
+                .collect::<Vec<_>>(),
+            vec![(0.9807965956109156, "doc1".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_hnsw_search_and_remove() {
+        let mut vector_store = InMemoryVectorStore::builder()
+            .index_strategy(IndexStrategy::hnsw())
+            .documents_with_ids(vec![
+                (
+                    "doc1",
+                    "glarb-garb",
+                    OneOrMany::one(Embedding {
+                        document: "glarb-garb".to_string(),
+                        vec: vec![0.1, 0.1, 0.5],
+                    }),
+                ),
+                (
+                    "doc2",
+                    "marble-marble",
+                    OneOrMany::one(Embedding {
+                        document: "marble-marble".to_string(),
+                        vec: vec![0.7, -0.3, 0.0],
+                    }),
+                ),
+                (
+                    "doc3",
+                    "flumb-flumb",
+                    OneOrMany::one(Embedding {
+                        document: "flumb-flumb".to_string(),
+                        vec: vec![0.3, 0.7, 0.1],
+                    }),
+                ),
+            ])
+            .build();
+
+        let prompt = Embedding {
+            document: "glarby-glarble".to_string(),
+            vec: vec![0.0, 0.1, 0.6],
+        };
+
+        assert_eq!(
+            vector_store.top_n_ids_by_embedding(&prompt, 1),
+            vec![(0.9807965956109156, "doc1".to_string())]
+        );
+
+        assert!(vector_store.remove_document("doc1").is_some());
+        assert!(vector_store.remove_document("doc1").is_none());
+
+        let results = vector_store.top_n_ids_by_embedding(&prompt, 3);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].1, "doc3");
+
+        // Documents added after the store is built are indexed incrementally
+        vector_store.add_documents_with_ids(vec![(
+            "doc4",
+            "glarby",
+            OneOrMany::one(Embedding {
+                document: "glarby".to_string(),
+                vec: vec![0.0, 0.1, 0.6],
+            }),
+        )]);
+
+        assert_eq!(
+            vector_store.top_n_ids_by_embedding(&prompt, 1)[0].1,
+            "doc4".to_string()
+        );
+    }
+
+    #[test]
+    fn test_auto_ids_after_remove() {
+        let embedding = |document: &str, vec: Vec<f64>| {
+            OneOrMany::one(Embedding {
+                document: document.to_string(),
+                vec,
+            })
+        };
+
+        let mut vector_store = InMemoryVectorStore::from_documents(vec![
+            ("glarb-garb", embedding("glarb-garb", vec![0.1, 0.1, 0.5])),
+            (
+                "marble-marble",
+                embedding("marble-marble", vec![0.7, -0.3, 0.0]),
+            ),
+            ("flumb-flumb", embedding("flumb-flumb", vec![0.3, 0.7, 0.1])),
+        ]);
+
+        assert!(vector_store.remove_document("doc0").is_some());
+
+        vector_store.add_documents(vec![("glarby", embedding("glarby", vec![0.0, 0.1, 0.6]))]);
+
+        // The new document does not replace doc2, nor reuse the removed doc0
+        let mut documents = vector_store
+            .iter()
+            .map(|(id, (doc, _))| (id.clone(), *doc))
+            .collect::<Vec<_>>();
+        documents.sort();
+
+        assert_eq!(
+            documents,
+            vec![
+                ("doc1".to_string(), "marble-marble"),
+                ("doc2".to_string(), "flumb-flumb"),
+                ("doc3".to_string(), "glarby"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_quantized_hnsw_search() {
+        let mut vector_store = InMemoryVectorStore::builder()
+            .index_strategy(IndexStrategy::hnsw())
+            .quantization(Quantization::Int8)
+            .documents((0..50).map(|i| {
+                let angle = i as f64 / 10.0;
+                (
+                    i,
+                    OneOrMany::one(Embedding {
+                        document: i.to_string(),
+                        vec: vec![angle.cos(), angle.sin(), 0.5],
+                    }),
+                )
+            }))
+            .build();
+
+        let prompt = Embedding {
+            document: "prompt".to_string(),
+            vec: vec![2.5f64.cos(), 2.5f64.sin(), 0.5],
+        };
+
+        assert_eq!(
+            vector_store.top_n_ids_by_embedding(&prompt, 1)[0].1,
+            "doc25"
+        );
+
+        assert!(vector_store.remove_document("doc25").is_some());
+        let results = vector_store.top_n_ids_by_embedding(&prompt, 2);
+        let mut ids = results.into_iter().map(|(_, id)| id).collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["doc24".to_string(), "doc26".to_string()]);
+    }
 }
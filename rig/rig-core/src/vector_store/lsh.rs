@@ -121,6 +121,16 @@ impl LSHIndex {
         candidates.into_iter().collect()
     }
 
+    /// Remove a document ID from all tables
+    pub fn remove(&mut self, id: &str) {
+        for table in self.tables.iter_mut() {
+            for ids in table.values_mut() {
+                ids.retain(|existing| existing != id);
+            }
+            table.retain(|_, ids| !ids.is_empty());
+        }
+    }
+
     /// Clear all tables
     pub fn clear(&mut self) {
         for table in self.tables.iter_mut() {
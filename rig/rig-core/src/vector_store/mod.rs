@@ -13,6 +13,7 @@ use crate::{
 };
 
 pub mod builder;
+pub mod hnsw;
 pub mod in_memory_store;
 pub mod lsh;
 pub mod request;
@@ -229,6 +230,27 @@ pub enum IndexStrategy {
         /// Number of hyperplanes to use for LSH.
         num_hyperplanes: usize,
     },
+
+    /// Uses an HNSW graph to find candidates then computes exact distances.
+    HNSW {
+        /// Maximum number of neighbours per node (doubled on the bottom layer).
+        m: usize,
+        /// Size of the candidate list used while inserting. Higher values improve recall at the cost of insert speed.
+        ef_construction: usize,
+        /// Size of the candidate list used while searching. Higher values improve recall at the cost of query speed.
+        ef_search: usize,
+    },
+}
+
+impl IndexStrategy {
+    /// HNSW with commonly used defaults: `m = 16`, `ef_construction = 200`, `ef_search = 64`.
+    pub fn hnsw() -> Self {
+        Self::HNSW {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+        }
+    }
 }
 
 impl Default for IndexStrategy {
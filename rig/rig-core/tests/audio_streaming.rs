@@ -0,0 +1,139 @@
+//! Runs the streaming audio generation and transcription of OpenAI against a local mock server.
+
+use futures::StreamExt;
+use httpmock::{Method, MockServer};
+use rig::audio_generation::AudioGenerationModel;
+use rig::prelude::*;
+use rig::providers::openai;
+use rig::transcription::{StreamedTranscription, TranscriptionModel, TranscriptionSegment};
+use serde_json::json;
+use std::time::Duration;
+
+fn client(server: &MockServer) -> openai::Client<reqwest::Client> {
+    openai::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.url("/v1"))
+        .build()
+        .unwrap()
+}
+
+/// 1.5 seconds of 16kHz mono PCM16 silence, in chunks of 0.1 seconds.
+fn audio() -> rig::transcription::AudioInputStream {
+    Box::pin(futures::stream::iter(vec![vec![0; 3_200]; 15]))
+}
+
+#[tokio::test]
+async fn openai_streaming_speech() {
+    let server = MockServer::start_async().await;
+    let speech = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/audio/speech")
+                .json_body(json!({
+                    "model": openai::GPT_4O_MINI_TTS,
+                    "input": "Hello!",
+                    "voice": "alloy",
+                    "speed": 1.0,
+                    "response_format": "pcm"
+                }));
+            then.status(200).body([1u8, 2, 3, 4]);
+        })
+        .await;
+
+    let model = client(&server).audio_generation_model(openai::GPT_4O_MINI_TTS);
+    let chunks = model
+        .audio_generation_request()
+        .text("Hello!")
+        .voice("alloy")
+        .additional_params(json!({ "response_format": "pcm" }))
+        .stream()
+        .await
+        .unwrap()
+        .collect::<Vec<_>>()
+        .await;
+    speech.assert_async().await;
+
+    let audio = chunks
+        .into_iter()
+        .flat_map(|chunk| chunk.unwrap().to_vec())
+        .collect::<Vec<_>>();
+    assert_eq!(audio, vec![1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn openai_streaming_transcription() {
+    let server = MockServer::start_async().await;
+    let transcription = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/audio/transcriptions")
+                .body_contains("name=\"stream\"")
+                .body_contains(openai::GPT_4O_TRANSCRIBE);
+            then.status(200)
+                .header("Content-Type", "text/event-stream")
+                .body(concat!(
+                    "data: {\"type\":\"transcript.text.delta\",\"delta\":\"Hello\"}\n\n",
+                    "data: {\"type\":\"transcript.text.delta\",\"delta\":\" world\"}\n\n",
+                    "data: {\"type\":\"transcript.text.done\",\"text\":\"Hello world\"}\n\n",
+                ));
+        })
+        .await;
+
+    let model = client(&server).transcription_model(openai::GPT_4O_TRANSCRIBE);
+    let items = model
+        .streaming_transcription_request(audio(), 16_000)
+        .window(Duration::from_secs(1))
+        .send()
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+    transcription.assert_hits_async(2).await;
+
+    assert_eq!(items.len(), 6);
+    assert_eq!(
+        items[0],
+        StreamedTranscription::Partial {
+            text: "Hello".to_string(),
+            start: 0.0,
+        }
+    );
+    assert_eq!(
+        items[5],
+        StreamedTranscription::Segment(TranscriptionSegment::new("Hello world", 1.0, 1.5))
+    );
+}
+
+#[tokio::test]
+async fn openai_whisper_transcribes_each_window() {
+    let server = MockServer::start_async().await;
+    let transcription = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/audio/transcriptions")
+                .body_contains(openai::WHISPER_1);
+            then.status(200).json_body(json!({ "text": "Hello" }));
+        })
+        .await;
+
+    let model = client(&server).transcription_model(openai::WHISPER_1);
+    let items = model
+        .streaming_transcription_request(audio(), 16_000)
+        .window(Duration::from_secs(1))
+        .send()
+        .await
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+        .await;
+    transcription.assert_hits_async(2).await;
+
+    assert_eq!(
+        items,
+        vec![
+            StreamedTranscription::Segment(TranscriptionSegment::new("Hello", 0.0, 1.0)),
+            StreamedTranscription::Segment(TranscriptionSegment::new("Hello", 1.0, 1.5)),
+        ]
+    );
+}
@@ -0,0 +1,241 @@
+//! Runs the batch APIs of the providers end to end against a local mock server.
+
+use futures::StreamExt;
+use httpmock::{Method, MockServer};
+use rig::batch::{
+    BatchClient, BatchItem, BatchItemError, BatchStatus, CompletionBatchModel, EmbeddingBatchModel,
+};
+use rig::completion::{CompletionModel, CompletionRequest};
+use rig::prelude::*;
+use rig::providers::{anthropic, openai};
+use serde_json::json;
+
+fn requests<M: CompletionModel>(model: &M) -> Vec<BatchItem<CompletionRequest>> {
+    ["first", "second"]
+        .into_iter()
+        .map(|id| {
+            let request = model
+                .completion_request(format!("Say {id}"))
+                .max_tokens(100)
+                .build();
+            BatchItem::new(id, request)
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn anthropic_message_batch() {
+    let server = MockServer::start_async().await;
+
+    let batch = |status: &str| {
+        json!({
+            "id": "msgbatch_01",
+            "type": "message_batch",
+            "processing_status": status,
+            "request_counts": { "processing": 0, "succeeded": 1, "errored": 1, "canceled": 0, "expired": 0 },
+            "cancel_initiated_at": null
+        })
+    };
+
+    let create = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/messages/batches")
+                .json_body_partial(
+                    json!({
+                        "requests": [
+                            { "custom_id": "first", "params": { "model": "claude-3-5-haiku-latest", "max_tokens": 100 } },
+                            { "custom_id": "second" }
+                        ]
+                    })
+                    .to_string(),
+                );
+            then.status(200).json_body(batch("in_progress"));
+        })
+        .await;
+    server
+        .mock_async(|when, then| {
+            when.method(Method::GET)
+                .path("/v1/messages/batches/msgbatch_01");
+            then.status(200).json_body(batch("ended"));
+        })
+        .await;
+    server
+        .mock_async(|when, then| {
+            when.method(Method::GET)
+                .path("/v1/messages/batches/msgbatch_01/results");
+            then.status(200).body(
+                [
+                    json!({
+                        "custom_id": "second",
+                        "result": {
+                            "type": "errored",
+                            "error": { "type": "error", "error": { "type": "overloaded_error", "message": "Overloaded" } }
+                        }
+                    }),
+                    json!({
+                        "custom_id": "first",
+                        "result": {
+                            "type": "succeeded",
+                            "message": {
+                                "id": "msg_01",
+                                "type": "message",
+                                "role": "assistant",
+                                "model": "claude-3-5-haiku-latest",
+                                "content": [{ "type": "text", "text": "first" }],
+                                "stop_reason": "end_turn",
+                                "stop_sequence": null,
+                                "usage": { "input_tokens": 10, "output_tokens": 1 }
+                            }
+                        }
+                    }),
+                ]
+                .map(|line| line.to_string())
+                .join("\n"),
+            );
+        })
+        .await;
+
+    let client = anthropic::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.base_url())
+        .build()
+        .unwrap();
+    let model = client.completion_model(anthropic::completion::CLAUDE_3_5_HAIKU);
+
+    let batch = model.submit_batch(requests(&model)).await.unwrap();
+    create.assert_async().await;
+    assert_eq!(batch.status, BatchStatus::InProgress);
+
+    let batch = client
+        .wait_for_batch(&batch.id, std::time::Duration::from_millis(10))
+        .await
+        .unwrap();
+    assert_eq!(batch.status, BatchStatus::Completed);
+    assert_eq!(batch.request_counts.total, 2);
+
+    let results: Vec<_> = model
+        .batch_results(&batch)
+        .await
+        .unwrap()
+        .map(|result| result.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0].custom_id, "second");
+    assert_eq!(
+        results[0].result.as_ref().unwrap_err(),
+        &BatchItemError::Failed("Overloaded".into())
+    );
+    assert_eq!(results[1].custom_id, "first");
+    assert_eq!(results[1].result.as_ref().unwrap().usage.input_tokens, 10);
+}
+
+#[tokio::test]
+async fn openai_embeddings_batch() {
+    let server = MockServer::start_async().await;
+
+    let batch = |status: &str, output_file_id: Option<&str>| {
+        json!({
+            "id": "batch_01",
+            "object": "batch",
+            "endpoint": "/v1/embeddings",
+            "input_file_id": "file-input",
+            "completion_window": "24h",
+            "status": status,
+            "output_file_id": output_file_id,
+            "error_file_id": null,
+            "request_counts": { "total": 1, "completed": 0, "failed": 0 }
+        })
+    };
+
+    let upload = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/files")
+                .body_contains("\"url\":\"/v1/embeddings\"")
+                .body_contains("\"custom_id\":\"docs\"");
+            then.status(200)
+                .json_body(json!({ "id": "file-input", "object": "file", "purpose": "batch" }));
+        })
+        .await;
+    let create = server
+        .mock_async(|when, then| {
+            when.method(Method::POST).path("/batches").json_body(json!({
+                "input_file_id": "file-input",
+                "endpoint": "/v1/embeddings",
+                "completion_window": "24h"
+            }));
+            then.status(200).json_body(batch("validating", None));
+        })
+        .await;
+    server
+        .mock_async(|when, then| {
+            when.method(Method::POST).path("/batches/batch_01/cancel");
+            then.status(200)
+                .json_body(batch("cancelled", Some("file-output")));
+        })
+        .await;
+    server
+        .mock_async(|when, then| {
+            when.method(Method::GET).path("/files/file-output/content");
+            then.status(200).body(
+                json!({
+                    "id": "batch_req_01",
+                    "custom_id": "docs",
+                    "response": {
+                        "status_code": 200,
+                        "body": {
+                            "object": "list",
+                            "model": "text-embedding-3-small",
+                            "data": [
+                                { "object": "embedding", "index": 1, "embedding": [0.3, 0.4] },
+                                { "object": "embedding", "index": 0, "embedding": [0.1, 0.2] }
+                            ],
+                            "usage": { "prompt_tokens": 4, "total_tokens": 4 }
+                        }
+                    },
+                    "error": null
+                })
+                .to_string(),
+            );
+        })
+        .await;
+
+    let client = openai::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.base_url())
+        .build()
+        .unwrap();
+    let model = client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+    let batch = model
+        .submit_embedding_batch(vec![BatchItem::new(
+            "docs",
+            vec!["hello".to_string(), "world".to_string()],
+        )])
+        .await
+        .unwrap();
+    upload.assert_async().await;
+    create.assert_async().await;
+    assert_eq!(batch.status, BatchStatus::Validating);
+
+    // Results are not available before the batch has an output file
+    assert!(model.embedding_batch_results(&batch).await.is_err());
+
+    let batch = client.cancel_batch(&batch.id).await.unwrap();
+    assert_eq!(batch.status, BatchStatus::Cancelled);
+
+    let results: Vec<_> = model
+        .embedding_batch_results(&batch)
+        .await
+        .unwrap()
+        .map(|result| result.unwrap())
+        .collect()
+        .await;
+
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].custom_id, "docs");
+    assert_eq!(results[0].result, Ok(vec![vec![0.1, 0.2], vec![0.3, 0.4]]));
+}
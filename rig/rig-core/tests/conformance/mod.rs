@@ -0,0 +1,430 @@
+//! Provider conformance kit.
+//!
+//! Runs a provider [CompletionModel] against a local `httpmock` server serving canned responses,
+//! and checks the provider-neutral output (text, tool calls, reasoning, usage, errors...).
+//!
+//! Fixtures are JSON files stored in `tests/conformance/fixtures/<provider>/<case>.json`:
+//! ```text
+//! {
+//!   "description": "A single tool call",
+//!   "request": {
+//!     "preamble": "You are a calculator",
+//!     "prompt": "What is 1 + 2?",
+//!     "tools": [{ "name": "add", "description": "Add x and y", "parameters": { ... } }],
+//!     "stream": false
+//!   },
+//!   "expected_request": { "tools": [{ "function": { "name": "add" } }] },
+//!   "response": { "status": 200, "body": { ... } },
+//!   "expected": {
+//!     "tool_calls": [{ "name": "add", "arguments": { "x": 1, "y": 2 } }],
+//!     "usage": { "input_tokens": 10, "output_tokens": 5 }
+//!   }
+//! }
+//! ```
+//!
+//! - `request.prompt` is a text prompt. Alternatively, `request.messages` is a list of rig
+//!   [Message]s, the last one being the prompt (e.g. to send images or tool results).
+//! - `expected_request` (optional) is matched against the JSON body sent to the provider.
+//!   Objects only need to contain the expected fields.
+//! - `response.body` is the JSON body returned by the server. For streaming fixtures,
+//!   `response.events` is the list of events: JSON values are sent in the provider's stream
+//!   format (`data: <json>` for SSE, one JSON value per line for NDJSON), strings are sent as-is
+//!   to exercise edge cases (comments, `event:` lines, `[DONE]`...). A streaming fixture may give
+//!   a `body` instead, for providers emulating streaming with a regular completion request.
+//! - `expected` lists the expected output. Only the given fields are checked, with the same
+//!   partial matching as `expected_request`. `error` is a substring of the expected error.
+//!
+//! Adding a provider only requires a [ProviderSpec] and a fixtures directory, see
+//! `tests/provider_conformance.rs`.
+
+use std::path::{Path, PathBuf};
+
+use futures::StreamExt;
+use httpmock::{Method, MockServer};
+use rig::{
+    completion::{CompletionModel, GetTokenUsage, ToolDefinition},
+    message::{AssistantContent, Message},
+    streaming::StreamedAssistantContent,
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+/// Wire format of the streaming responses of a provider.
+#[derive(Debug, Clone, Copy)]
+pub enum StreamFormat {
+    /// Server-sent events: `data: <json>\n\n`
+    Sse,
+    /// Newline-delimited JSON: `<json>\n`
+    NdJson,
+}
+
+/// Describes how to reach the completion endpoints of a provider.
+pub struct ProviderSpec {
+    /// Name of the fixtures directory
+    pub name: &'static str,
+    /// Path of the completion endpoint
+    pub completion_path: String,
+    /// Path of the streaming completion endpoint
+    pub stream_path: String,
+    pub stream_format: StreamFormat,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Fixture {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub request: FixtureRequest,
+    #[serde(default)]
+    pub expected_request: Option<Value>,
+    pub response: FixtureResponse,
+    pub expected: Expected,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureRequest {
+    #[serde(default)]
+    pub preamble: Option<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub messages: Vec<Message>,
+    #[serde(default)]
+    pub tools: Vec<ToolDefinition>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    #[serde(default)]
+    pub stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FixtureResponse {
+    #[serde(default = "default_status")]
+    pub status: u16,
+    #[serde(default)]
+    pub body: Option<Value>,
+    #[serde(default)]
+    pub events: Vec<Value>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub tool_calls: Option<Value>,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    #[serde(default)]
+    pub usage: Option<Value>,
+    #[serde(default)]
+    pub finish_reason: Option<Value>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// The provider-neutral output of a completion, compared with [Expected].
+#[derive(Debug, Default)]
+struct Output {
+    text: String,
+    tool_calls: Vec<Value>,
+    reasoning: String,
+    usage: Option<Value>,
+    finish_reason: Option<Value>,
+}
+
+impl Output {
+    fn push(&mut self, content: &AssistantContent) {
+        match content {
+            AssistantContent::Text(text) => self.text.push_str(&text.text),
+            AssistantContent::ToolCall(tool_call) => self.tool_calls.push(json!({
+                "id": tool_call.id,
+                "call_id": tool_call.call_id,
+                "name": tool_call.function.name,
+                "arguments": tool_call.function.arguments,
+            })),
+            AssistantContent::Reasoning(reasoning) => {
+                self.reasoning.push_str(&reasoning.reasoning.join(""))
+            }
+            AssistantContent::Image(_) | AssistantContent::HostedToolCall(_) => {}
+        }
+    }
+}
+
+/// Run all the fixtures of a provider, panicking with the list of failing fixtures.
+pub async fn run<M, F>(spec: ProviderSpec, make_model: F)
+where
+    M: CompletionModel + 'static,
+    F: Fn(&MockServer) -> M,
+{
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/conformance/fixtures")
+        .join(spec.name);
+    let fixtures = fixture_paths(&dir);
+    assert!(!fixtures.is_empty(), "No fixtures in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in fixtures {
+        let name = path.file_stem().unwrap().to_string_lossy().to_string();
+        let fixture: Fixture = serde_json::from_str(&std::fs::read_to_string(&path).unwrap())
+            .unwrap_or_else(|e| panic!("Invalid fixture {}: {e}", path.display()));
+
+        if let Err(error) = run_fixture(&spec, &fixture, &make_model).await {
+            let description = fixture.description.as_deref().unwrap_or_default();
+            failures.push(format!("- {}/{name} ({description}): {error}", spec.name));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} conformance fixture(s) failed:\n{}",
+        failures.len(),
+        failures.join("\n")
+    );
+}
+
+fn fixture_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+async fn run_fixture<M, F>(
+    spec: &ProviderSpec,
+    fixture: &Fixture,
+    make_model: &F,
+) -> Result<(), String>
+where
+    M: CompletionModel + 'static,
+    F: Fn(&MockServer) -> M,
+{
+    let server = MockServer::start_async().await;
+    let path = if fixture.request.stream {
+        &spec.stream_path
+    } else {
+        &spec.completion_path
+    };
+
+    let mock = server
+        .mock_async(|when, then| {
+            let when = when.method(Method::POST).path(path.as_str());
+            if let Some(expected_request) = &fixture.expected_request {
+                when.json_body_partial(expected_request.to_string());
+            }
+
+            let then = then.status(fixture.response.status);
+            if fixture.request.stream
+                && fixture.response.status == 200
+                && fixture.response.body.is_none()
+            {
+                let content_type = match spec.stream_format {
+                    StreamFormat::Sse => "text/event-stream",
+                    StreamFormat::NdJson => "application/x-ndjson",
+                };
+                then.header("content-type", content_type)
+                    .body(stream_body(spec.stream_format, &fixture.response.events));
+            } else {
+                then.header("content-type", "application/json").body(
+                    fixture
+                        .response
+                        .body
+                        .as_ref()
+                        .map(Value::to_string)
+                        .unwrap_or_default(),
+                );
+            }
+        })
+        .await;
+
+    let model = make_model(&server);
+    let result = if fixture.request.stream {
+        stream(&model, &fixture.request).await
+    } else {
+        complete(&model, &fixture.request).await
+    };
+
+    if mock.hits_async().await == 0 {
+        return Err(format!(
+            "The request did not reach `{path}` or did not match `expected_request`"
+        ));
+    }
+
+    check(&fixture.expected, result)
+}
+
+fn stream_body(format: StreamFormat, events: &[Value]) -> String {
+    events
+        .iter()
+        .map(|event| match (event, format) {
+            (Value::String(raw), _) => format!("{raw}\n"),
+            (event, StreamFormat::Sse) => format!("data: {event}\n\n"),
+            (event, StreamFormat::NdJson) => format!("{event}\n"),
+        })
+        .collect()
+}
+
+fn build_request<M: CompletionModel>(
+    model: &M,
+    request: &FixtureRequest,
+) -> rig::completion::CompletionRequest {
+    let mut messages = request.messages.clone();
+    let prompt = match (&request.prompt, messages.pop()) {
+        (Some(prompt), None) => Message::user(prompt),
+        (None, Some(prompt)) => prompt,
+        _ => panic!("A fixture request needs either a `prompt` or `messages`"),
+    };
+
+    let mut builder = model
+        .completion_request(prompt)
+        .messages(messages)
+        .tools(request.tools.clone());
+    if let Some(preamble) = &request.preamble {
+        builder = builder.preamble(preamble.clone());
+    }
+    if let Some(max_tokens) = request.max_tokens {
+        builder = builder.max_tokens(max_tokens);
+    }
+    builder.build()
+}
+
+async fn complete<M: CompletionModel>(
+    model: &M,
+    request: &FixtureRequest,
+) -> Result<Output, String> {
+    let response = model
+        .completion(build_request(model, request))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut output = Output {
+        usage: Some(json!(response.usage)),
+        finish_reason: response.finish_reason.map(|reason| json!(reason)),
+        ..Default::default()
+    };
+    response
+        .choice
+        .iter()
+        .for_each(|content| output.push(content));
+
+    Ok(output)
+}
+
+async fn stream<M: CompletionModel>(model: &M, request: &FixtureRequest) -> Result<Output, String> {
+    let mut response = model
+        .stream(build_request(model, request))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mut output = Output::default();
+    // Providers may send reasoning deltas, whole reasoning blocks or both
+    let mut reasoning_deltas = String::new();
+    while let Some(chunk) = response.next().await {
+        match chunk.map_err(|e| e.to_string())? {
+            StreamedAssistantContent::Text(text) => output.text.push_str(&text.text),
+            StreamedAssistantContent::ToolCall(tool_call) => {
+                output.push(&AssistantContent::ToolCall(tool_call))
+            }
+            StreamedAssistantContent::Reasoning(reasoning) => {
+                output.push(&AssistantContent::Reasoning(reasoning))
+            }
+            StreamedAssistantContent::ReasoningDelta { reasoning, .. } => {
+                reasoning_deltas.push_str(&reasoning)
+            }
+            _ => {}
+        }
+    }
+
+    if output.reasoning.is_empty() {
+        output.reasoning = reasoning_deltas;
+    }
+    output.usage = response
+        .response
+        .as_ref()
+        .and_then(|response| response.token_usage())
+        .map(|usage| json!(usage));
+    output.finish_reason = response.finish_reason.map(|reason| json!(reason));
+
+    Ok(output)
+}
+
+fn check(expected: &Expected, result: Result<Output, String>) -> Result<(), String> {
+    let output = match (result, &expected.error) {
+        (Err(error), Some(expected)) if error.contains(expected.as_str()) => return Ok(()),
+        (Err(error), _) => return Err(format!("Unexpected error: {error}")),
+        (Ok(output), Some(error)) => {
+            return Err(format!(
+                "Expected an error containing `{error}`, got {output:?}"
+            ));
+        }
+        (Ok(output), None) => output,
+    };
+
+    if let Some(text) = &expected.text
+        && *text != output.text
+    {
+        return Err(format!("Expected text {text:?}, got {:?}", output.text));
+    }
+    if let Some(reasoning) = &expected.reasoning
+        && *reasoning != output.reasoning
+    {
+        return Err(format!(
+            "Expected reasoning {reasoning:?}, got {:?}",
+            output.reasoning
+        ));
+    }
+    if let Some(tool_calls) = &expected.tool_calls {
+        contains(tool_calls, &Value::Array(output.tool_calls.clone()))
+            .map_err(|e| format!("Tool calls mismatch: {e}"))?;
+    }
+    if let Some(usage) = &expected.usage {
+        contains(usage, output.usage.as_ref().unwrap_or(&Value::Null))
+            .map_err(|e| format!("Usage mismatch: {e}"))?;
+    }
+    if let Some(finish_reason) = &expected.finish_reason {
+        contains(
+            finish_reason,
+            output.finish_reason.as_ref().unwrap_or(&Value::Null),
+        )
+        .map_err(|e| format!("Finish reason mismatch: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Check that `actual` contains `expected`: objects only need the expected fields,
+/// arrays must have the same length and other values must be equal.
+fn contains(expected: &Value, actual: &Value) -> Result<(), String> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.iter().try_for_each(|(key, expected)| {
+                let actual = actual.get(key).unwrap_or(&Value::Null);
+                contains(expected, actual).map_err(|e| format!("{key}: {e}"))
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) if expected.len() == actual.len() => {
+            expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .try_for_each(|(i, (expected, actual))| {
+                    contains(expected, actual).map_err(|e| format!("[{i}]: {e}"))
+                })
+        }
+        (expected, actual) if expected == actual => Ok(()),
+        (expected, actual) => Err(format!("expected {expected}, got {actual}")),
+    }
+}
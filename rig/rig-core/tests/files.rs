@@ -0,0 +1,153 @@
+//! Runs the files APIs of the providers end to end against a local mock server.
+
+use httpmock::{Method, MockServer};
+use rig::OneOrMany;
+use rig::completion::Prompt;
+use rig::files::{FileUpload, FilesClient};
+use rig::message::{Message, UserContent};
+use rig::prelude::*;
+use rig::providers::{anthropic, gemini};
+use serde_json::json;
+
+#[tokio::test]
+async fn anthropic_file_reference() {
+    let server = MockServer::start_async().await;
+
+    let upload = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/files")
+                .header("anthropic-beta", anthropic::files::FILES_API_BETA)
+                .body_contains("filename=\"report.pdf\"");
+            then.status(200).json_body(json!({
+                "id": "file_011CNha8iCJcU1wXNR6q4V8w",
+                "type": "file",
+                "filename": "report.pdf",
+                "mime_type": "application/pdf",
+                "size_bytes": 8,
+                "created_at": "2025-04-14T12:00:00Z",
+                "downloadable": false
+            }));
+        })
+        .await;
+    let message = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/messages")
+                .header("anthropic-beta", anthropic::files::FILES_API_BETA)
+                .json_body_partial(
+                    json!({
+                        "messages": [{
+                            "role": "user",
+                            "content": [{
+                                "type": "document",
+                                "source": { "type": "file", "file_id": "file_011CNha8iCJcU1wXNR6q4V8w" }
+                            }]
+                        }]
+                    })
+                    .to_string(),
+                );
+            then.status(200).json_body(json!({
+                "id": "msg_01",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-3-5-haiku-latest",
+                "content": [{ "type": "text", "text": "A report." }],
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": { "input_tokens": 10, "output_tokens": 3 }
+            }));
+        })
+        .await;
+
+    let client = anthropic::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.base_url())
+        .build()
+        .unwrap();
+
+    let file = client
+        .upload_file(FileUpload::new("report.pdf", b"%PDF-1.4".to_vec()))
+        .await
+        .unwrap();
+    upload.assert_async().await;
+    assert_eq!(file.bytes, Some(8));
+
+    let agent = client
+        .agent(anthropic::completion::CLAUDE_3_5_HAIKU)
+        .max_tokens(100)
+        .build();
+    let response = agent
+        .prompt(Message::User {
+            content: OneOrMany::many(vec![file.document(), UserContent::text("What is this?")])
+                .unwrap(),
+        })
+        .await
+        .unwrap();
+
+    message.assert_async().await;
+    assert_eq!(response, "A report.");
+}
+
+#[tokio::test]
+async fn gemini_resumable_upload() {
+    let server = MockServer::start_async().await;
+    let uri = "https://generativelanguage.googleapis.com/v1beta/files/abc-123";
+
+    let start = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/upload/v1beta/files")
+                .query_param("key", "TEST")
+                .header("X-Goog-Upload-Command", "start")
+                .header("X-Goog-Upload-Header-Content-Length", "5")
+                .header("X-Goog-Upload-Header-Content-Type", "text/plain")
+                .json_body(json!({ "file": { "display_name": "notes.txt" } }));
+            then.status(200)
+                .header("x-goog-upload-url", server.url("/upload/session/1"));
+        })
+        .await;
+    let finalize = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/upload/session/1")
+                .header("X-Goog-Upload-Command", "upload, finalize")
+                .header("content-type", "text/plain")
+                .body("hello");
+            then.status(200).json_body(json!({
+                "file": {
+                    "name": "files/abc-123",
+                    "displayName": "notes.txt",
+                    "mimeType": "text/plain",
+                    "sizeBytes": "5",
+                    "uri": uri,
+                    "state": "ACTIVE"
+                }
+            }));
+        })
+        .await;
+    let delete = server
+        .mock_async(|when, then| {
+            when.method(Method::DELETE).path("/v1beta/files/abc-123");
+            then.status(200).json_body(json!({}));
+        })
+        .await;
+
+    let client = gemini::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.base_url())
+        .build()
+        .unwrap();
+
+    let file = client
+        .upload_file(FileUpload::new("notes.txt", b"hello".to_vec()))
+        .await
+        .unwrap();
+    start.assert_async().await;
+    finalize.assert_async().await;
+    assert_eq!(file.id, uri);
+    assert_eq!(file.bytes, Some(5));
+
+    client.delete_file(&file.id).await.unwrap();
+    delete.assert_async().await;
+}
@@ -0,0 +1,177 @@
+//! Runs an OpenAI realtime session end to end against a local WebSocket stand-in.
+
+use futures::{SinkExt, StreamExt};
+use rig::agent::AgentBuilder;
+use rig::completion::ToolDefinition;
+use rig::providers::openai;
+use rig::realtime::{RealtimeModel, ServerEvent, SessionConfig, TurnDetection};
+use rig::testing::MockCompletionModel;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::{
+    Message,
+    handshake::server::{Request, Response},
+};
+
+#[derive(Deserialize)]
+struct OperationArgs {
+    x: i32,
+    y: i32,
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Math error")]
+struct MathError;
+
+struct Adder;
+
+impl Tool for Adder {
+    const NAME: &'static str = "add";
+    type Error = MathError;
+    type Args = OperationArgs;
+    type Output = i32;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "add".to_string(),
+            description: "Add x and y together".to_string(),
+            parameters: json!({ "type": "object" }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        Ok(args.x + args.y)
+    }
+}
+
+/// Receives the next client event of the stand-in connection.
+async fn next_event<S>(socket: &mut S) -> Value
+where
+    S: futures::Stream<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+{
+    match socket.next().await.unwrap().unwrap() {
+        Message::Text(text) => serde_json::from_str(&text).unwrap(),
+        message => panic!("Unexpected message: {message:?}"),
+    }
+}
+
+#[tokio::test]
+async fn openai_realtime_session_dispatches_tool_calls() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket =
+            tokio_tungstenite::accept_hdr_async(stream, |request: &Request, response: Response| {
+                assert_eq!(request.uri().to_string(), "/v1/realtime?model=gpt-realtime");
+                assert_eq!(request.headers()["authorization"], "Bearer TEST");
+                assert_eq!(request.headers()["openai-beta"], "realtime=v1");
+                Ok(response)
+            })
+            .await
+            .unwrap();
+
+        let update = next_event(&mut socket).await;
+        assert_eq!(update["type"], "session.update");
+        assert_eq!(update["session"]["instructions"], "You are a calculator");
+        assert_eq!(update["session"]["turn_detection"]["type"], "server_vad");
+        assert_eq!(update["session"]["tools"][0]["name"], "add");
+
+        let message = next_event(&mut socket).await;
+        assert_eq!(message["item"]["content"][0]["text"], "What is 1 + 2?");
+        assert_eq!(next_event(&mut socket).await["type"], "response.create");
+
+        for event in [
+            json!({
+                "type": "response.output_item.done",
+                "response_id": "resp_1",
+                "item": {
+                    "id": "item_1",
+                    "type": "function_call",
+                    "name": "add",
+                    "call_id": "call_1",
+                    "arguments": "{\"x\":1,\"y\":2}"
+                }
+            }),
+            json!({ "type": "response.done", "response": { "id": "resp_1" } }),
+        ] {
+            socket.send(Message::Text(event.to_string())).await.unwrap();
+        }
+
+        assert_eq!(
+            next_event(&mut socket).await["item"],
+            json!({ "type": "function_call_output", "call_id": "call_1", "output": "3" })
+        );
+        assert_eq!(next_event(&mut socket).await["type"], "response.create");
+
+        for event in [
+            json!({ "type": "response.audio.delta", "response_id": "resp_2", "delta": "AQID" }),
+            json!({
+                "type": "response.audio_transcript.delta",
+                "response_id": "resp_2",
+                "delta": "It is 3."
+            }),
+            json!({
+                "type": "response.done",
+                "response": {
+                    "id": "resp_2",
+                    "usage": { "input_tokens": 20, "output_tokens": 4, "total_tokens": 24 }
+                }
+            }),
+        ] {
+            socket.send(Message::Text(event.to_string())).await.unwrap();
+        }
+
+        socket.close(None).await.unwrap();
+    });
+
+    let client = openai::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(format!("http://{address}/v1"))
+        .build()
+        .unwrap();
+    let agent = AgentBuilder::new(MockCompletionModel::new())
+        .preamble("You are a calculator")
+        .tool(Adder)
+        .build();
+
+    let model = client.realtime_model(openai::realtime::GPT_REALTIME);
+    let config = SessionConfig::new().turn_detection(TurnDetection::server_vad());
+    let mut session = model.connect_agent(&agent, config).await.unwrap();
+    session.sender().send_text("What is 1 + 2?").await.unwrap();
+
+    let mut events = Vec::new();
+    while let Some(event) = session.next_event().await {
+        events.push(event.unwrap());
+    }
+    server.await.unwrap();
+
+    assert!(matches!(&events[0], ServerEvent::ToolCall(call) if call.function.name == "add"));
+    assert_eq!(
+        events[1],
+        ServerEvent::ToolResult {
+            call_id: "call_1".to_string(),
+            output: "3".to_string(),
+        }
+    );
+    assert!(
+        matches!(&events[2], ServerEvent::ResponseDone { response_id, .. } if response_id == "resp_1")
+    );
+    assert_eq!(
+        events[3],
+        ServerEvent::AudioDelta {
+            response_id: "resp_2".to_string(),
+            data: vec![1, 2, 3],
+        }
+    );
+    assert!(
+        matches!(&events[4], ServerEvent::AudioTranscriptDelta { delta, .. } if delta == "It is 3.")
+    );
+    assert!(
+        matches!(&events[5], ServerEvent::ResponseDone { usage: Some(usage), .. } if usage.total_tokens == 24)
+    );
+    assert_eq!(events.len(), 6);
+}
@@ -0,0 +1,138 @@
+//! Runs the timed and translated transcriptions of the providers against a local mock server.
+
+use httpmock::{Method, MockServer};
+use rig::prelude::*;
+use rig::providers::{groq, openai};
+use rig::transcription::{
+    TimestampGranularity, TranscriptionFormat, TranscriptionModel, TranscriptionSegment,
+    TranscriptionWord,
+};
+use serde_json::json;
+
+#[tokio::test]
+async fn openai_verbose_json_with_word_timestamps() {
+    let server = MockServer::start_async().await;
+    let transcription = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/audio/transcriptions")
+                .body_contains("verbose_json")
+                .body_contains("name=\"timestamp_granularities[]\"\r\n\r\nword");
+            then.status(200).json_body(json!({
+                "task": "transcribe",
+                "language": "english",
+                "duration": 2.1,
+                "text": "Hello there. Bye.",
+                "segments": [
+                    { "id": 0, "seek": 0, "start": 0.0, "end": 1.2, "text": " Hello there.", "tokens": [] },
+                    { "id": 1, "seek": 0, "start": 1.2, "end": 2.1, "text": " Bye.", "tokens": [] }
+                ],
+                "words": [
+                    { "word": "Hello", "start": 0.0, "end": 0.5 },
+                    { "word": "there", "start": 0.6, "end": 1.1 },
+                    { "word": "Bye", "start": 1.4, "end": 1.9 }
+                ]
+            }));
+        })
+        .await;
+
+    let client = openai::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.url("/v1"))
+        .build()
+        .unwrap();
+    let response = client
+        .transcription_model(openai::WHISPER_1)
+        .transcription_request()
+        .data(vec![0; 16])
+        .timestamp_granularities([TimestampGranularity::Segment, TimestampGranularity::Word])
+        .send()
+        .await
+        .unwrap();
+    transcription.assert_async().await;
+
+    assert_eq!(response.language.as_deref(), Some("english"));
+    assert_eq!(
+        response.segments[1],
+        TranscriptionSegment::new(" Bye.", 1.2, 2.1)
+            .with_words(vec![TranscriptionWord::new("Bye", 1.4, 1.9)])
+    );
+    assert_eq!(response.segments[0].words.len(), 2);
+    assert_eq!(
+        response.to_srt(),
+        "1\n00:00:00,000 --> 00:00:01,200\nHello there.\n\n\
+         2\n00:00:01,200 --> 00:00:02,100\nBye.\n\n"
+    );
+}
+
+#[tokio::test]
+async fn openai_diarized_json() {
+    let server = MockServer::start_async().await;
+    server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/v1/audio/transcriptions")
+                .body_contains("diarized_json");
+            then.status(200).json_body(json!({
+                "text": "Hello. Hi!",
+                "segments": [
+                    { "type": "transcript.text.segment", "id": "seg_0", "start": 0.0, "end": 0.8, "text": "Hello.", "speaker": "A" },
+                    { "type": "transcript.text.segment", "id": "seg_1", "start": 1.0, "end": 1.3, "text": "Hi!", "speaker": "B" }
+                ]
+            }));
+        })
+        .await;
+
+    let client = openai::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.url("/v1"))
+        .build()
+        .unwrap();
+    let response = client
+        .transcription_model(openai::GPT_4O_TRANSCRIBE_DIARIZE)
+        .transcription_request()
+        .data(vec![0; 16])
+        .response_format(TranscriptionFormat::DiarizedJson)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        response.to_vtt(),
+        "WEBVTT\n\n\
+         00:00:00.000 --> 00:00:00.800\n<v A>Hello.\n\n\
+         00:00:01.000 --> 00:00:01.300\n<v B>Hi!\n\n"
+    );
+}
+
+#[tokio::test]
+async fn groq_translation() {
+    let server = MockServer::start_async().await;
+    let translation = server
+        .mock_async(|when, then| {
+            when.method(Method::POST)
+                .path("/openai/v1/audio/translations")
+                .body_contains(groq::WHISPER_LARGE_V3);
+            then.status(200).json_body(json!({ "text": "Hello" }));
+        })
+        .await;
+
+    let client = groq::Client::<reqwest::Client>::builder()
+        .api_key("TEST")
+        .base_url(server.url("/openai/v1"))
+        .build()
+        .unwrap();
+    let response = client
+        .transcription_model(groq::WHISPER_LARGE_V3)
+        .transcription_request()
+        .data(vec![0; 16])
+        .language("fr".to_string())
+        .translate(true)
+        .send()
+        .await
+        .unwrap();
+    translation.assert_async().await;
+
+    assert_eq!(response.text, "Hello");
+    assert!(response.segments.is_empty());
+}